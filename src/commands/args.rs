@@ -0,0 +1,29 @@
+//! Argument helpers shared by the command handlers
+
+/// Value following the option at `args[i]`
+pub fn option_value(args: &[String], i: usize) -> Result<String, String> {
+    args.get(i + 1)
+        .cloned()
+        .ok_or_else(|| format!("{} requires a value", args[i]))
+}
+
+/// Value following `flag` anywhere in `args`, if present
+pub fn find_option<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+/// Leading positional argument such as a record ID; `name` describes it in the error
+pub fn positional_arg(args: &[String], name: &str, usage: &str) -> Result<String, String> {
+    match args.first() {
+        Some(value) if !value.starts_with("--") => Ok(value.clone()),
+        _ => Err(format!("{name} is required. Usage: {usage}")),
+    }
+}
+
+/// Whether the arguments ask for help
+pub fn is_help(args: &[String]) -> bool {
+    args.first().is_some_and(|arg| arg == "--help" || arg == "-h")
+}
@@ -0,0 +1,246 @@
+/*
+ * QMS (Quality Management System)
+ * Attachment Command Handler
+ *
+ * CLI command handlers for evidence files attached to documents, risks,
+ * requirements, test executions and CAPAs
+ */
+
+use crate::commands::args::{is_help, option_value};
+use crate::modules::attachments::{Attachment, AttachmentManager, AttachmentTarget, NewAttachment};
+use crate::utils::get_current_project_path;
+use crate::utils::user_context::get_current_user_id;
+use std::path::Path;
+
+pub fn handle_attach_command(args: &[String]) -> Result<(), String> {
+    if args.len() < 3 {
+        print_attach_help();
+        return Ok(());
+    }
+
+    match args[2].as_str() {
+        "add" => handle_attach_add(&args[3..]),
+        "list" => handle_attach_list(&args[3..]),
+        "show" => handle_attach_show(&args[3..]),
+        "get" => handle_attach_get(&args[3..]),
+        "verify" => handle_attach_verify(),
+        "--help" | "-h" | "help" => {
+            print_attach_help();
+            Ok(())
+        }
+        _ => {
+            eprintln!("Error: Unknown attach command '{}'", args[2]);
+            print_attach_help();
+            Err(format!("Unknown attach command '{}'", args[2]))
+        }
+    }
+}
+
+fn attachment_manager() -> Result<AttachmentManager, String> {
+    let project_path = get_current_project_path()
+        .map_err(|e| format!("Failed to get project path: {e}"))?;
+    AttachmentManager::new(&project_path).map_err(|e| format!("Failed to initialize attachments: {e}"))
+}
+
+fn handle_attach_add(args: &[String]) -> Result<(), String> {
+    if is_help(args) {
+        print_attach_add_help();
+        return Ok(());
+    }
+    let usage = "qms attach add <TYPE> <RECORD-ID> <FILE> [--name <NAME>] [--comment <TEXT>]";
+    let positional: Vec<&String> = args.iter().take_while(|arg| !arg.starts_with("--")).collect();
+    let [target, record_id, file] = positional[..] else {
+        return Err(format!("Record type, record ID and file are required. Usage: {usage}"));
+    };
+
+    let mut name = None;
+    let mut comment = None;
+    let mut i = 3;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--name" => name = Some(option_value(args, i)?),
+            "--comment" => comment = Some(option_value(args, i)?),
+            _ => return Err(format!("Unknown option: {}", args[i])),
+        }
+        i += 2;
+    }
+
+    let target = AttachmentTarget::parse(target).map_err(|e| e.to_string())?;
+    let data = std::fs::read(file).map_err(|e| format!("Failed to read {file}: {e}"))?;
+    let attachment = attachment_manager()?
+        .add(
+            NewAttachment {
+                target,
+                record_id: record_id.clone(),
+                file_name: file.clone(),
+                name,
+                comment,
+            },
+            &data,
+            &get_current_user_id(),
+        )
+        .map_err(|e| format!("Failed to add attachment: {e}"))?;
+
+    let latest = attachment.latest().expect("an added attachment has a version");
+    if latest.version == 1 {
+        println!("✅ Attachment added");
+    } else {
+        println!("✅ Version {} added", latest.version);
+    }
+    println!("🆔 ID: {}", attachment.id);
+    println!("📎 {} on {} {}", attachment.name, attachment.target.name(), attachment.record_id);
+    println!("📄 Type: {} ({} bytes)", latest.mime_type, latest.size);
+    println!("🔒 SHA-256: {}", latest.sha256);
+    Ok(())
+}
+
+fn handle_attach_list(args: &[String]) -> Result<(), String> {
+    let mut target = None;
+    let mut record_id = None;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--type" => target = Some(AttachmentTarget::parse(&option_value(args, i)?).map_err(|e| e.to_string())?),
+            "--record" => record_id = Some(option_value(args, i)?),
+            "--help" | "-h" => {
+                println!("USAGE:\n    qms attach list [--type <TYPE>] [--record <RECORD-ID>]");
+                return Ok(());
+            }
+            _ => return Err(format!("Unknown option: {}", args[i])),
+        }
+        i += 2;
+    }
+
+    let attachments = attachment_manager()?
+        .list(target, record_id.as_deref())
+        .map_err(|e| format!("Failed to list attachments: {e}"))?;
+
+    if attachments.is_empty() {
+        println!("No attachments found");
+        return Ok(());
+    }
+
+    println!("{:<9} {:<26} {:<4} {:>10} {:<16} Name", "ID", "Record", "Ver", "Size", "SHA-256");
+    println!("{:-<90}", "");
+    for attachment in &attachments {
+        let Some(latest) = attachment.latest() else { continue };
+        println!(
+            "{:<9} {:<26} {:<4} {:>10} {:<16} {}",
+            attachment.id,
+            format!("{} {}", attachment.target.name(), attachment.record_id),
+            latest.version,
+            latest.size,
+            &latest.sha256[..16],
+            attachment.name
+        );
+    }
+    println!("\nTotal: {} attachment(s)", attachments.len());
+    Ok(())
+}
+
+fn handle_attach_show(args: &[String]) -> Result<(), String> {
+    let attachment_id = match args.first() {
+        Some(id) if !id.starts_with("--") => id,
+        _ => return Err("Attachment ID is required. Usage: qms attach show <ATT-ID>".to_string()),
+    };
+    let attachment = attachment_manager()?.get(attachment_id).map_err(|e| e.to_string())?;
+    print_attachment_details(&attachment);
+    Ok(())
+}
+
+fn print_attachment_details(attachment: &Attachment) {
+    println!("📎 {} - {}", attachment.id, attachment.name);
+    println!("{:-<60}", "");
+    println!("Record:      {} {}", attachment.target.name(), attachment.record_id);
+    println!("Versions:    {}", attachment.versions.len());
+    for version in attachment.versions.iter().rev() {
+        println!();
+        println!("  v{} uploaded {} by {}", version.version, version.uploaded_at, version.uploaded_by);
+        println!("    File:    {} ({} bytes, {})", version.original_filename, version.size, version.mime_type);
+        println!("    SHA-256: {}", version.sha256);
+        if let Some(comment) = &version.comment {
+            println!("    Comment: {comment}");
+        }
+    }
+}
+
+fn handle_attach_get(args: &[String]) -> Result<(), String> {
+    let usage = "qms attach get <ATT-ID> [--version <N>] --output <FILE>";
+    let attachment_id = match args.first() {
+        Some(id) if !id.starts_with("--") => id,
+        _ => return Err(format!("Attachment ID is required. Usage: {usage}")),
+    };
+
+    let mut version = None;
+    let mut output = None;
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--version" => {
+                let value = option_value(args, i)?;
+                version = Some(value.trim_start_matches('v').parse::<u32>().map_err(|_| format!("Invalid version '{value}'"))?);
+            }
+            "--output" | "-o" => output = Some(option_value(args, i)?),
+            _ => return Err(format!("Unknown option: {}", args[i])),
+        }
+        i += 2;
+    }
+    let output = output.ok_or_else(|| format!("--output is required. Usage: {usage}"))?;
+
+    let (attachment, selected, data) = attachment_manager()?
+        .read_content(attachment_id, version)
+        .map_err(|e| format!("Failed to read attachment: {e}"))?;
+    std::fs::write(Path::new(&output), &data).map_err(|e| format!("Failed to write {output}: {e}"))?;
+
+    println!("✅ {} v{} ({}) written to {output}", attachment.id, selected.version, attachment.name);
+    println!("🔒 SHA-256 verified: {}", selected.sha256);
+    Ok(())
+}
+
+fn handle_attach_verify() -> Result<(), String> {
+    let report = attachment_manager()?
+        .verify()
+        .map_err(|e| format!("Failed to verify attachments: {e}"))?;
+
+    if report.is_valid() {
+        println!("✅ All {} attachment version(s) match their SHA-256 fingerprints", report.versions_checked);
+        return Ok(());
+    }
+    println!("❌ {} problem(s) in {} attachment version(s):", report.problems.len(), report.versions_checked);
+    for problem in &report.problems {
+        println!("   {problem}");
+    }
+    Err("Attachment verification failed".to_string())
+}
+
+fn print_attach_help() {
+    println!("Manage evidence files attached to QMS records\n");
+    println!("USAGE:");
+    println!("    qms attach <COMMAND>\n");
+    println!("COMMANDS:");
+    println!("    add       Attach a file to a record (same name adds a version)");
+    println!("    list      List attachments");
+    println!("    show      Show an attachment and its versions");
+    println!("    get       Write a version's contents to a file");
+    println!("    verify    Check every version against its SHA-256 fingerprint");
+    println!("    help      Show this help message\n");
+    println!("RECORD TYPES:");
+    println!("    document, risk, requirement, test-execution, capa\n");
+    println!("For more information on a specific command, use:");
+    println!("    qms attach <COMMAND> --help");
+}
+
+fn print_attach_add_help() {
+    println!("Attach a file to a record\n");
+    println!("USAGE:");
+    println!("    qms attach add <TYPE> <RECORD-ID> <FILE> [OPTIONS]\n");
+    println!("OPTIONS:");
+    println!("    --name <NAME>       Attachment name on the record (default: file name)");
+    println!("    --comment <TEXT>    Note stored with this version");
+    println!("    --help              Show this help message\n");
+    println!("Uploading a file under a name the record already has adds a new version.");
+    println!("The size limit is set by the project setting 'attachment_max_size_mb' (default 50).\n");
+    println!("EXAMPLES:");
+    println!("    qms attach add test-execution EXEC-20250101-001 run.log");
+    println!("    qms attach add capa CAPA-001 signed_report.pdf --comment \"Signed effectiveness review\"");
+}
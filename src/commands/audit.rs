@@ -4,13 +4,15 @@ use crate::modules::audit_logger::{
     initialize_audit_system, get_audit_statistics,
     rotate_audit_logs, set_current_session, clear_current_session,
     verify_audit_file, export_chain_verification_report,
+    create_checkpoint, verify_from_checkpoint, format_checkpoint_verification,
+    server_key_fingerprint, checkpoint_key_store_dir, CHECKPOINT_KEY_DIR_ENV,
     check_and_rotate_daily_logs, cleanup_old_logs_comprehensive, get_rotation_statistics,
     ElectronicSignatureManager, format_signature_verification,
     ExportFormat, ExportOptions, AuditExportEngine, format_export_stats,
     AuditBackupManager, format_backup_stats, format_backup_info,
     PerformanceAuditLogger, PerformanceConfig, format_performance_metrics
 };
-use crate::utils::{get_current_project_path, generate_uuid};
+use crate::utils::{current_date_string, get_current_project_path, generate_uuid};
 
 pub fn handle_audit_command(args: &[String]) -> Result<(), String> {
     if args.len() < 3 {
@@ -30,6 +32,7 @@ pub fn handle_audit_command(args: &[String]) -> Result<(), String> {
         "login" => handle_audit_login(&args[3..]),
         "logout" => handle_audit_logout(&args[3..]),
         "verify" => handle_audit_verify(&args[3..]),
+        "checkpoint" => handle_audit_checkpoint(&args[3..]),
         "dashboard" => handle_audit_dashboard(&args[3..]),
         "signature" => handle_audit_signature(&args[3..]),
         "backup" => handle_audit_backup(&args[3..]),
@@ -570,6 +573,16 @@ fn handle_audit_verify(args: &[String]) -> Result<(), String> {
     
     let audit_dir = project_path.join("audit");
     
+    // Checkpoint mode: verify against signed Merkle checkpoints instead of the bare hash chain
+    if let Some(pos) = args.iter().position(|a| a == "--from-checkpoint") {
+        let start = args.get(pos + 1).filter(|a| !a.starts_with("--")).map(String::as_str);
+        let fingerprint = args.iter()
+            .position(|a| a == "--key-fingerprint")
+            .and_then(|pos| args.get(pos + 1))
+            .map(String::as_str);
+        return handle_checkpoint_verify(&project_path, start, fingerprint);
+    }
+    
     // Check for --report flag to export verification report
     let export_report = args.contains(&"--report".to_string());
     let report_path = if export_report {
@@ -663,6 +676,70 @@ fn handle_audit_verify(args: &[String]) -> Result<(), String> {
     Ok(())
 }
 
+fn handle_checkpoint_verify(
+    project_path: &std::path::Path,
+    start: Option<&str>,
+    fingerprint: Option<&str>,
+) -> Result<(), String> {
+    match start {
+        Some(selector) => println!("\n🔍 Verifying audit trail from checkpoint {selector}..."),
+        None => println!("\n🔍 Verifying audit trail against all signed checkpoints..."),
+    }
+
+    let key_dir = checkpoint_key_store_dir()
+        .map_err(|e| format!("Failed to locate checkpoint key store: {e}"))?;
+    let result = verify_from_checkpoint(project_path, &key_dir, start, fingerprint)
+        .map_err(|e| format!("Checkpoint verification failed: {e}"))?;
+    print!("{}", format_checkpoint_verification(&result));
+
+    if !result.is_valid {
+        return Err("Audit trail does not match its signed checkpoints".to_string());
+    }
+
+    println!("\n✅ No audit entries have been altered or removed since they were checkpointed.");
+    Ok(())
+}
+
+fn handle_audit_checkpoint(args: &[String]) -> Result<(), String> {
+    if args.iter().any(|a| a == "--help" || a == "-h") {
+        println!("Create a signed checkpoint of audit entries recorded since the last checkpoint\n");
+        println!("USAGE:");
+        println!("    qms audit checkpoint [--date <YYYY-MM-DD>]");
+        println!("    qms audit checkpoint --show-key\n");
+        println!("The signing key is kept in the checkpoint key store outside the project");
+        println!("(~/.qms/keys/checkpoints, or ${CHECKPOINT_KEY_DIR_ENV}). Record its fingerprint out of band;");
+        println!("'qms audit verify --from-checkpoint --key-fingerprint <fp>' trusts only that key.");
+        return Ok(());
+    }
+
+    let project_path = get_current_project_path()
+        .map_err(|e| format!("Failed to get project path: {e}"))?;
+    let key_dir = checkpoint_key_store_dir()
+        .map_err(|e| format!("Failed to locate checkpoint key store: {e}"))?;
+
+    if args.iter().any(|a| a == "--show-key") {
+        let fingerprint = server_key_fingerprint(&project_path, &key_dir)
+            .map_err(|e| format!("Failed to load checkpoint key: {e}"))?;
+        println!("Checkpoint signing key: {fingerprint}");
+        return Ok(());
+    }
+
+    let date = args.iter()
+        .position(|a| a == "--date")
+        .and_then(|pos| args.get(pos + 1))
+        .cloned()
+        .unwrap_or_else(current_date_string);
+
+    let checkpoint = create_checkpoint(&project_path, &key_dir, &date)
+        .map_err(|e| format!("Failed to create checkpoint: {e}"))?;
+
+    println!("✅ Checkpoint CP-{:06} created", checkpoint.sequence);
+    println!("   Entries: {}..{}", checkpoint.first_entry, checkpoint.end_entry());
+    println!("   Merkle root: {}", checkpoint.merkle_root);
+    println!("   Signing key: {}", checkpoint.key_fingerprint);
+    Ok(())
+}
+
 fn print_audit_help() {
     println!("Manage QMS audit trail\n");
     println!("USAGE:");
@@ -696,6 +773,11 @@ fn print_audit_help() {
     println!("    logout     End current audit session");
     println!("    verify     Verify audit trail integrity and hash chain");
     println!("               --report                Export verification report");
+    println!("               --from-checkpoint [seq|date]  Verify against signed checkpoints");
+    println!("               --key-fingerprint <fp>  Trust only this checkpoint key");
+    println!("    checkpoint Sign a Merkle checkpoint of entries since the last one");
+    println!("               --date <YYYY-MM-DD>     Day the checkpoint covers (default: today)");
+    println!("               --show-key              Print the signing key fingerprint");
     println!("    dashboard  Generate comprehensive audit dashboard");
     println!("               --period <days>         Analysis period (default: 30)");
     println!("               --output <file>         Save to file");
@@ -1074,6 +1156,9 @@ fn handle_signature_create(manager: &ElectronicSignatureManager, args: &[String]
     let mut entity_type = String::new();
     let mut entity_id = String::new();
     let mut reason: Option<String> = None;
+    let mut password: Option<String> = None;
+    let mut code: Option<String> = None;
+    let mut record_file: Option<String> = None;
 
     let mut i = 0;
     while i < args.len() {
@@ -1118,6 +1203,30 @@ fn handle_signature_create(manager: &ElectronicSignatureManager, args: &[String]
                     return Err("--reason requires a value".to_string());
                 }
             }
+            "--password" => {
+                if i + 1 < args.len() {
+                    password = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    return Err("--password requires a value".to_string());
+                }
+            }
+            "--code" => {
+                if i + 1 < args.len() {
+                    code = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    return Err("--code requires a value".to_string());
+                }
+            }
+            "--record" => {
+                if i + 1 < args.len() {
+                    record_file = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    return Err("--record requires a value".to_string());
+                }
+            }
             _ => {
                 return Err(format!("Unknown create option: {}", args[i]));
             }
@@ -1125,10 +1234,21 @@ fn handle_signature_create(manager: &ElectronicSignatureManager, args: &[String]
     }
 
     if user_id.is_empty() || action.is_empty() || entity_type.is_empty() || entity_id.is_empty() {
-        return Err("Missing required parameters. Use: --user <user> --action <action> --entity-type <type> --entity-id <id>".to_string());
+        return Err("Missing required parameters. Use: --user <user> --action <action> --entity-type <type> --entity-id <id> --record <file>".to_string());
     }
 
-    match manager.create_signature(user_id, &action, entity_type, entity_id, reason) {
+    let record_file = record_file
+        .ok_or_else(|| "--record <file> is required: the signature is computed over the record content".to_string())?;
+    let record_content = std::fs::read(&record_file)
+        .map_err(|e| format!("Failed to read record file '{record_file}': {e}"))?;
+
+    let password = match password {
+        Some(password) => password,
+        None => prompt_signing_password(&user_id)?,
+    };
+    let code = signing_code(&user_id, code)?;
+
+    match manager.create_signature_with_code(user_id, &password, code.as_deref(), &action, entity_type, entity_id, &record_content, reason) {
         Ok(signature) => {
             println!("✅ Electronic signature created successfully!");
             println!("Signature ID: {}", signature.id);
@@ -1136,6 +1256,12 @@ fn handle_signature_create(manager: &ElectronicSignatureManager, args: &[String]
             println!("Meaning: {}", signature.meaning);
             println!("Timestamp: {}", signature.timestamp);
             println!("Hash: {}", signature.signature_hash);
+            if let Some(ref content_hash) = signature.content_hash {
+                println!("Record SHA-256: {content_hash}");
+            }
+            if let Some(ref fingerprint) = signature.key_fingerprint {
+                println!("Key: Ed25519 {fingerprint}");
+            }
         }
         Err(e) => {
             return Err(format!("Failed to create signature: {e}"));
@@ -1147,12 +1273,26 @@ fn handle_signature_create(manager: &ElectronicSignatureManager, args: &[String]
 
 fn handle_signature_verify(manager: &ElectronicSignatureManager, args: &[String]) -> Result<(), String> {
     if args.is_empty() {
-        return Err("Usage: qms audit signature verify <signature_id>".to_string());
+        return Err("Usage: qms audit signature verify <signature_id> [--record <file>]".to_string());
     }
 
     let signature_id = &args[0];
+    let record_content = match args.get(1).map(String::as_str) {
+        Some("--record") => {
+            let record_file = args.get(2).ok_or("--record requires a value")?;
+            Some(std::fs::read(record_file)
+                .map_err(|e| format!("Failed to read record file '{record_file}': {e}"))?)
+        }
+        Some(other) => return Err(format!("Unknown verify option: {other}")),
+        None => None,
+    };
+
+    let result = match record_content {
+        Some(content) => manager.verify_signature_for_record(signature_id, &content),
+        None => manager.verify_signature(signature_id),
+    };
 
-    match manager.verify_signature(signature_id) {
+    match result {
         Ok(verification) => {
             println!("{}", format_signature_verification(&verification));
         }
@@ -1213,7 +1353,10 @@ fn handle_signature_list(manager: &ElectronicSignatureManager, args: &[String])
                     if let Some(ref reason) = signature.reason {
                         println!("  Reason: {reason}");
                     }
-                    println!("  Valid: {}", if signature.verify_hash() { "✅ Yes" } else { "❌ No" });
+                    let valid = manager.verify_signature(&signature.id)
+                        .map(|verification| verification.is_valid)
+                        .unwrap_or(false);
+                    println!("  Valid: {}", if valid { "✅ Yes" } else { "❌ No" });
                     println!();
                 }
             }
@@ -1226,6 +1369,37 @@ fn handle_signature_list(manager: &ElectronicSignatureManager, args: &[String])
     Ok(())
 }
 
+/// Prompt for the password that unlocks the signer's private key
+pub(crate) fn prompt_signing_password(user_id: &str) -> Result<String, String> {
+    use std::io::{self, Write};
+
+    print!("Password for {user_id} (signature): ");
+    io::stdout().flush().map_err(|e| e.to_string())?;
+    let mut password = String::new();
+    io::stdin().read_line(&mut password).map_err(|e| e.to_string())?;
+    Ok(password.trim().to_string())
+}
+
+/// Second-factor code for a signing: the `--code` value if given, otherwise a
+/// prompt when the signer has enrolled in MFA for the current project
+pub(crate) fn signing_code(user_id: &str, code: Option<String>) -> Result<Option<String>, String> {
+    use std::io::{self, Write};
+
+    if code.is_some() {
+        return Ok(code);
+    }
+    let project_path = get_current_project_path().map_err(|e| e.to_string())?;
+    if !crate::modules::user_manager::MfaStore::new(&project_path).is_enrolled(user_id) {
+        return Ok(None);
+    }
+
+    print!("Authentication code for {user_id} (signature): ");
+    io::stdout().flush().map_err(|e| e.to_string())?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).map_err(|e| e.to_string())?;
+    Ok(Some(input.trim().to_string()))
+}
+
 fn handle_signature_requirements(manager: &ElectronicSignatureManager, _args: &[String]) -> Result<(), String> {
     let report = manager.generate_requirements_report();
     println!("{report}");
@@ -1773,16 +1947,20 @@ fn print_backup_cleanup_help() {
     println!("                --entity-type <type>    Type of entity");
     println!("                --entity-id <id>        Entity ID");
     println!("                --reason <reason>       Reason for signature (if required)");
+    println!("                --record <file>         Record content being signed");
+    println!("                --password <password>   Signer password (prompted if omitted)");
+    println!("                --code <code>           Authentication code (prompted if enrolled in MFA)");
     println!("    verify      Verify electronic signature");
     println!("                <signature_id>          Signature to verify");
+    println!("                --record <file>         Also check the record is unchanged");
     println!("    list        List signatures for entity");
     println!("                --entity-type <type>    Entity type");
     println!("                --entity-id <id>        Entity ID");
     println!("    requirements Show signature requirements");
     println!("    help        Show this help message\n");
     println!("EXAMPLES:");
-    println!("    qms audit signature create --user john --action document_approve --entity-type Document --entity-id DOC-001");
-    println!("    qms audit signature verify 12345678-abcd-ef12-3456-789012345678");
+    println!("    qms audit signature create --user john --action document_approve --entity-type Document --entity-id DOC-001 --record documents/DOC-001/metadata.json");
+    println!("    qms audit signature verify 12345678-abcd-ef12-3456-789012345678 --record documents/DOC-001/metadata.json");
     println!("    qms audit signature list --entity-type Document --entity-id DOC-001");
     println!("    qms audit signature requirements");
 }/// Handle audit performance optimization commands
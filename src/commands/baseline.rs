@@ -0,0 +1,222 @@
+/*
+ * QMS (Quality Management System)
+ * Configuration Baseline Command Handler
+ *
+ * CLI command handlers for named release baselines of the design record
+ * (ISO 13485:2016 7.5.9, FDA 21 CFR 820.30(j))
+ */
+
+use crate::commands::args::{is_help, option_value, positional_arg};
+use crate::modules::baseline::{BaselineDiff, BaselineKind, BaselineManager, ChangeKind, WORKING_STATE};
+use crate::utils::get_current_project_path;
+use crate::utils::user_context::get_current_user_id;
+
+pub fn handle_baseline_command(args: &[String]) -> Result<(), String> {
+    if args.len() < 3 {
+        print_baseline_help();
+        return Ok(());
+    }
+
+    match args[2].as_str() {
+        "create" => handle_baseline_create(&args[3..]),
+        "list" => handle_baseline_list(&args[3..]),
+        "show" => handle_baseline_show(&args[3..]),
+        "compare" => handle_baseline_compare(&args[3..]),
+        "verify" => handle_baseline_verify(&args[3..]),
+        "--help" | "-h" | "help" => {
+            print_baseline_help();
+            Ok(())
+        }
+        _ => {
+            eprintln!("Error: Unknown baseline command '{}'", args[2]);
+            print_baseline_help();
+            Err(format!("Unknown baseline command '{}'", args[2]))
+        }
+    }
+}
+
+fn baseline_manager() -> Result<BaselineManager, String> {
+    let project_path = get_current_project_path()
+        .map_err(|e| format!("Failed to get project path: {e}"))?;
+    BaselineManager::new(&project_path).map_err(|e| format!("Failed to initialize baselines: {e}"))
+}
+
+fn handle_baseline_create(args: &[String]) -> Result<(), String> {
+    let usage = "qms baseline create <NAME> [--desc <TEXT>]";
+    if is_help(args) {
+        println!("USAGE:\n    {usage}");
+        println!("\nFreezes every document, requirement, risk, trace link and test case.");
+        return Ok(());
+    }
+    let name = positional_arg(args, "Baseline name", usage)?;
+    let mut description = String::new();
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--desc" | "--description" => description = option_value(args, i)?,
+            _ => return Err(format!("Unknown option: {}. Usage: {usage}", args[i])),
+        }
+        i += 2;
+    }
+
+    let baseline = baseline_manager()?
+        .create(&name, &description, &get_current_user_id())
+        .map_err(|e| format!("Failed to create baseline: {e}"))?;
+    println!("✅ Baseline {} created", baseline.name);
+    for kind in BaselineKind::ALL {
+        println!("   {:<14} {}", kind.plural_name(), baseline.items_of(kind).count());
+    }
+    println!("\nNext: qms baseline compare {} {WORKING_STATE}", baseline.name);
+    Ok(())
+}
+
+fn handle_baseline_list(args: &[String]) -> Result<(), String> {
+    if let Some(arg) = args.first() {
+        if arg == "--help" || arg == "-h" {
+            println!("USAGE:\n    qms baseline list");
+            return Ok(());
+        }
+        return Err(format!("Unknown option: {arg}"));
+    }
+
+    let baselines = baseline_manager()?
+        .list()
+        .map_err(|e| format!("Failed to list baselines: {e}"))?;
+
+    if baselines.is_empty() {
+        println!("No baselines found");
+        return Ok(());
+    }
+
+    println!("{:<16} {:<22} {:<12} {:<6} Description", "Name", "Created", "By", "Items");
+    println!("{:-<80}", "");
+    for baseline in &baselines {
+        println!(
+            "{:<16} {:<22} {:<12} {:<6} {}",
+            baseline.name, baseline.created_at, baseline.created_by, baseline.items.len(), baseline.description
+        );
+    }
+    println!("\nTotal: {} baseline(s)", baselines.len());
+    Ok(())
+}
+
+fn handle_baseline_show(args: &[String]) -> Result<(), String> {
+    let usage = "qms baseline show <NAME> [--type <TYPE>]";
+    let name = positional_arg(args, "Baseline name", usage)?;
+    let mut kinds = BaselineKind::ALL.to_vec();
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--type" => kinds = vec![BaselineKind::parse(&option_value(args, i)?).map_err(|e| e.to_string())?],
+            _ => return Err(format!("Unknown option: {}. Usage: {usage}", args[i])),
+        }
+        i += 2;
+    }
+
+    let baseline = baseline_manager()?.load(&name).map_err(|e| e.to_string())?;
+    println!("📌 Baseline {}", baseline.name);
+    println!("{:-<60}", "");
+    println!("Created:     {} by {}", baseline.created_at, baseline.created_by);
+    if !baseline.description.is_empty() {
+        println!("Description: {}", baseline.description);
+    }
+
+    for kind in kinds {
+        let items: Vec<_> = baseline.items_of(kind).collect();
+        println!("\n{} ({}):", kind.plural_name(), items.len());
+        for item in items {
+            println!("   {:<16} {:<22} {:.12}  {}", item.id, item.revision, item.sha256, item.title);
+        }
+    }
+    Ok(())
+}
+
+fn handle_baseline_compare(args: &[String]) -> Result<(), String> {
+    let usage = "qms baseline compare <FROM> [<TO>]";
+    let from = positional_arg(args, "Baseline name", usage)?;
+    let to = match args.get(1) {
+        Some(to) if !to.starts_with("--") => to.clone(),
+        Some(option) => return Err(format!("Unknown option: {option}. Usage: {usage}")),
+        None => WORKING_STATE.to_string(),
+    };
+    if args.len() > 2 {
+        return Err(format!("Unexpected argument: {}. Usage: {usage}", args[2]));
+    }
+
+    let diff = baseline_manager()?
+        .compare(&from, &to)
+        .map_err(|e| format!("Failed to compare baselines: {e}"))?;
+    print_diff(&diff);
+    Ok(())
+}
+
+fn print_diff(diff: &BaselineDiff) {
+    println!("🔀 {} → {}", diff.from, diff.to);
+    println!("{:-<60}", "");
+    println!("{:<14} {:>7} {:>8} {:>8}", "Type", "Added", "Removed", "Changed");
+    for kind in BaselineKind::ALL {
+        let (added, removed, changed) = diff.counts(kind);
+        println!("{:<14} {added:>7} {removed:>8} {changed:>8}", kind.plural_name());
+    }
+
+    if diff.is_empty() {
+        println!("\nNo differences");
+        return;
+    }
+
+    println!();
+    for change in &diff.changes {
+        let symbol = match change.change {
+            ChangeKind::Added => "+",
+            ChangeKind::Removed => "-",
+            ChangeKind::Changed => "~",
+        };
+        let revisions = match (&change.from_revision, &change.to_revision) {
+            (Some(from), Some(to)) if from != to => format!(" ({from} → {to})"),
+            _ => String::new(),
+        };
+        println!("{symbol} {:<12} {:<16} {}{revisions}", change.kind.name(), change.id, change.title);
+    }
+    println!("\nTotal: {} difference(s)", diff.changes.len());
+}
+
+fn handle_baseline_verify(args: &[String]) -> Result<(), String> {
+    let name = positional_arg(args, "Baseline name", "qms baseline verify <NAME>")?;
+    let problems = baseline_manager()?.verify(&name).map_err(|e| e.to_string())?;
+
+    if problems.is_empty() {
+        println!("✅ Baseline {name}: every snapshot matches its fingerprint");
+        return Ok(());
+    }
+    println!("❌ Baseline {name}: {} problem(s)", problems.len());
+    for problem in &problems {
+        println!("   {problem}");
+    }
+    Err(format!("Baseline {name} failed verification"))
+}
+
+fn print_baseline_help() {
+    println!("Manage configuration baselines of the design record\n");
+    println!("USAGE:");
+    println!("    qms baseline <COMMAND>\n");
+    println!("COMMANDS:");
+    println!("    create    Freeze documents, requirements, risks, trace links and test cases");
+    println!("    list      List baselines");
+    println!("    show      Show the entities frozen in a baseline");
+    println!("    compare   Compare two baselines, or a baseline with the working state");
+    println!("    verify    Check every snapshot against its SHA-256 fingerprint");
+    println!("    help      Show this help message\n");
+    println!("TYPES:");
+    println!("    document, requirement, risk, trace-link, test-case\n");
+    println!("AS-OF-BASELINE REPORTS:");
+    println!("    qms report dhf --baseline <NAME>");
+    println!("    qms trace matrix --baseline <NAME>");
+    println!("    qms risk generate-rmf --baseline <NAME>\n");
+    println!("EXAMPLES:");
+    println!("    qms baseline create R2.1 --desc \"Release 2.1 design freeze\"");
+    println!("    qms baseline compare R2.0 R2.1");
+    println!("    qms baseline compare R2.1            # against the working state");
+    println!("    qms baseline show R2.1 --type requirement");
+}
@@ -0,0 +1,472 @@
+/*
+ * QMS (Quality Management System)
+ * CAPA Command Handler
+ *
+ * CLI command handlers for Corrective and Preventive Action records
+ * (FDA 21 CFR 820.100, ISO 13485:2016 8.5.2 / 8.5.3)
+ */
+
+use crate::commands::args::{option_value, positional_arg};
+use crate::modules::capa::{
+    CapaActionStatus, CapaManager, CapaPriority, CapaRecord, CapaSource, CapaStatus, CapaType,
+    FishboneCategory, FishboneCause, NewCapa,
+};
+use crate::modules::traceability::links::TraceLinkType;
+use crate::utils::get_current_project_path;
+use crate::utils::user_context::get_current_user_id;
+use std::str::FromStr;
+
+pub fn handle_capa_command(args: &[String]) -> Result<(), String> {
+    if args.len() < 3 {
+        print_capa_help();
+        return Ok(());
+    }
+
+    match args[2].as_str() {
+        "create" => handle_capa_create(&args[3..]),
+        "list" => handle_capa_list(&args[3..]),
+        "show" => handle_capa_show(&args[3..]),
+        "root-cause" => handle_capa_root_cause(&args[3..]),
+        "add-action" => handle_capa_add_action(&args[3..]),
+        "action-status" => handle_capa_action_status(&args[3..]),
+        "effectiveness" => handle_capa_effectiveness(&args[3..]),
+        "link-risk" => handle_capa_link_risk(&args[3..]),
+        "link" => handle_capa_link(&args[3..]),
+        "close" => handle_capa_close(&args[3..]),
+        "cancel" => handle_capa_cancel(&args[3..]),
+        "--help" | "-h" | "help" => {
+            print_capa_help();
+            Ok(())
+        }
+        _ => {
+            eprintln!("Error: Unknown CAPA command '{}'", args[2]);
+            print_capa_help();
+            Err(format!("Unknown CAPA command '{}'", args[2]))
+        }
+    }
+}
+
+fn capa_manager() -> Result<CapaManager, String> {
+    let project_path = get_current_project_path()
+        .map_err(|e| format!("Failed to get project path: {e}"))?;
+    CapaManager::new(&project_path).map_err(|e| format!("Failed to initialize CAPA system: {e}"))
+}
+
+fn handle_capa_create(args: &[String]) -> Result<(), String> {
+    let mut title = String::new();
+    let mut description = String::new();
+    let mut source = None;
+    let mut source_reference = None;
+    let mut capa_type = CapaType::Corrective;
+    let mut priority = CapaPriority::Medium;
+    let mut owner = None;
+    let mut due_date = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--title" => title = option_value(args, i)?,
+            "--desc" | "--description" => description = option_value(args, i)?,
+            "--source" => source = Some(CapaSource::from_str(&option_value(args, i)?).map_err(|e| e.to_string())?),
+            "--ref" | "--source-ref" => source_reference = Some(option_value(args, i)?),
+            "--type" => capa_type = CapaType::from_str(&option_value(args, i)?).map_err(|e| e.to_string())?,
+            "--priority" => priority = CapaPriority::from_str(&option_value(args, i)?).map_err(|e| e.to_string())?,
+            "--owner" => owner = Some(option_value(args, i)?),
+            "--due" => due_date = Some(option_value(args, i)?),
+            "--help" | "-h" => {
+                print_capa_create_help();
+                return Ok(());
+            }
+            _ => return Err(format!("Unknown option: {}", args[i])),
+        }
+        i += 2;
+    }
+
+    if title.is_empty() {
+        return Err("Title is required (--title)".to_string());
+    }
+    if description.is_empty() {
+        return Err("Description is required (--desc)".to_string());
+    }
+    let source = source.ok_or("Source is required (--source complaint|audit|nonconformance|surveillance)")?;
+
+    let user = get_current_user_id();
+    let record = capa_manager()?
+        .create_capa(
+            NewCapa {
+                title,
+                description,
+                source,
+                source_reference,
+                capa_type,
+                priority,
+                owner: owner.unwrap_or_else(|| user.clone()),
+                due_date,
+            },
+            &user,
+        )
+        .map_err(|e| format!("Failed to create CAPA: {e}"))?;
+
+    println!("✅ CAPA created successfully");
+    println!("🆔 ID: {}", record.id);
+    println!("📄 Title: {}", record.title);
+    println!("📥 Source: {}", record.source.display_name());
+    println!("⭐ Priority: {}", record.priority.display_name());
+    Ok(())
+}
+
+fn handle_capa_list(args: &[String]) -> Result<(), String> {
+    let mut status = None;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--status" => status = Some(CapaStatus::from_str(&option_value(args, i)?).map_err(|e| e.to_string())?),
+            "--help" | "-h" => {
+                println!("USAGE:\n    qms capa list [--status <STATUS>]");
+                return Ok(());
+            }
+            _ => return Err(format!("Unknown option: {}", args[i])),
+        }
+        i += 2;
+    }
+
+    let records = capa_manager()?
+        .list_capas(status.as_ref())
+        .map_err(|e| format!("Failed to list CAPAs: {e}"))?;
+
+    if records.is_empty() {
+        println!("No CAPA records found");
+        return Ok(());
+    }
+
+    println!("{:<10} {:<20} {:<15} {:<9} Title", "ID", "Status", "Source", "Priority");
+    println!("{:-<80}", "");
+    for record in &records {
+        println!(
+            "{:<10} {:<20} {:<15} {:<9} {}",
+            record.id,
+            record.status.display_name(),
+            record.source.display_name(),
+            record.priority.display_name(),
+            record.title
+        );
+    }
+    println!("\nTotal: {} CAPA record(s)", records.len());
+    Ok(())
+}
+
+fn handle_capa_show(args: &[String]) -> Result<(), String> {
+    let capa_id = positional_arg(args, "CAPA ID", "qms capa show <CAPA-ID>")?;
+    let manager = capa_manager()?;
+    let record = manager.load_capa(&capa_id).map_err(|e| e.to_string())?;
+    print_capa_details(&record);
+
+    if let Ok(links) = manager.trace_links(&capa_id) {
+        if !links.is_empty() {
+            println!("\n🔗 Traceability:");
+            for link in links {
+                println!("   {} --{}--> {}", link.source_id, link.link_type, link.target_id);
+            }
+        }
+    }
+
+    if record.closure_signature_id.is_some() {
+        let valid = manager.verify_closure(&capa_id).map(|v| v.is_valid).unwrap_or(false);
+        println!("\n✍️  Closure signature: {}", if valid { "✅ Valid" } else { "❌ Invalid" });
+    }
+    Ok(())
+}
+
+fn print_capa_details(record: &CapaRecord) {
+    println!("📋 {} - {}", record.id, record.title);
+    println!("{:-<60}", "");
+    println!("Type:        {}", record.capa_type.display_name());
+    println!("Status:      {}", record.status.display_name());
+    println!("Priority:    {}", record.priority.display_name());
+    println!("Source:      {}{}", record.source.display_name(),
+        record.source_reference.as_ref().map(|r| format!(" ({r})")).unwrap_or_default());
+    println!("Owner:       {}", record.owner);
+    if let Some(ref due) = record.due_date {
+        println!("Due:         {due}");
+    }
+    println!("Created:     {} by {}", record.created_at, record.created_by);
+    println!("\nDescription:\n   {}", record.description);
+
+    let rca = &record.root_cause;
+    if rca.is_complete() {
+        println!("\n🔍 Root Cause: {}", rca.root_cause_statement);
+        for (n, why) in rca.five_whys.iter().enumerate() {
+            println!("   Why {}: {why}", n + 1);
+        }
+        for cause in &rca.fishbone {
+            println!("   [{}] {}", cause.category.display_name(), cause.cause);
+        }
+    }
+
+    if !record.actions.is_empty() {
+        println!("\n🛠️  Actions:");
+        for action in &record.actions {
+            println!("   {} [{}] {} (owner: {}{})", action.id, action.status.display_name(), action.description,
+                action.owner, action.due_date.as_ref().map(|d| format!(", due {d}")).unwrap_or_default());
+        }
+    }
+
+    if let Some(ref check) = record.effectiveness {
+        println!("\n📈 Effectiveness: {}", if check.effective { "Effective" } else { "Not effective" });
+        println!("   Method: {}", check.method);
+        println!("   Criteria: {}", check.criteria);
+        if !check.evidence.is_empty() {
+            println!("   Evidence: {}", check.evidence);
+        }
+        println!("   Checked by {} at {}", check.checked_by, check.checked_at);
+    }
+
+    if !record.linked_risks.is_empty() {
+        println!("\n⚠️  Linked risks: {}", record.linked_risks.join(", "));
+    }
+    if let (Some(by), Some(at)) = (&record.closed_by, &record.closed_at) {
+        println!("\n🔒 Closed by {by} at {at}");
+    }
+    if let Some(ref reason) = record.cancellation_reason {
+        println!("\n🚫 Cancelled: {reason}");
+    }
+}
+
+fn handle_capa_root_cause(args: &[String]) -> Result<(), String> {
+    let usage = "qms capa root-cause <CAPA-ID> --statement <TEXT> [--why <TEXT>]... [--fishbone <CATEGORY>:<CAUSE>]...";
+    let capa_id = positional_arg(args, "CAPA ID", usage)?;
+    let mut statement = String::new();
+    let mut whys = Vec::new();
+    let mut fishbone = Vec::new();
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--statement" => statement = option_value(args, i)?,
+            "--why" => whys.push(option_value(args, i)?),
+            "--fishbone" => {
+                let value = option_value(args, i)?;
+                let (category, cause) = value.split_once(':')
+                    .ok_or_else(|| format!("--fishbone expects <CATEGORY>:<CAUSE>, got '{value}'"))?;
+                fishbone.push(FishboneCause {
+                    category: FishboneCategory::from_str(category.trim()).map_err(|e| e.to_string())?,
+                    cause: cause.trim().to_string(),
+                });
+            }
+            _ => return Err(format!("Unknown option: {}. Usage: {usage}", args[i])),
+        }
+        i += 2;
+    }
+
+    if whys.len() > 5 {
+        return Err("At most five --why entries are allowed".to_string());
+    }
+
+    let record = capa_manager()?
+        .record_root_cause(&capa_id, whys, fishbone, &statement, &get_current_user_id())
+        .map_err(|e| format!("Failed to record root cause: {e}"))?;
+    println!("✅ Root cause recorded for {} ({})", record.id, record.status.display_name());
+    Ok(())
+}
+
+fn handle_capa_add_action(args: &[String]) -> Result<(), String> {
+    let usage = "qms capa add-action <CAPA-ID> --desc <TEXT> [--owner <USER>] [--due <DATE>]";
+    let capa_id = positional_arg(args, "CAPA ID", usage)?;
+    let mut description = String::new();
+    let mut owner = get_current_user_id();
+    let mut due_date = None;
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--desc" | "--description" => description = option_value(args, i)?,
+            "--owner" => owner = option_value(args, i)?,
+            "--due" => due_date = Some(option_value(args, i)?),
+            _ => return Err(format!("Unknown option: {}. Usage: {usage}", args[i])),
+        }
+        i += 2;
+    }
+
+    let action = capa_manager()?
+        .add_action(&capa_id, &description, &owner, due_date)
+        .map_err(|e| format!("Failed to add action: {e}"))?;
+    println!("✅ Action {} added to {capa_id}", action.id);
+    Ok(())
+}
+
+fn handle_capa_action_status(args: &[String]) -> Result<(), String> {
+    let usage = "qms capa action-status <CAPA-ID> <ACTION-ID> <planned|in-progress|completed|cancelled>";
+    if args.len() != 3 {
+        return Err(format!("Usage: {usage}"));
+    }
+    let status = CapaActionStatus::from_str(&args[2]).map_err(|e| e.to_string())?;
+    let record = capa_manager()?
+        .update_action_status(&args[0], &args[1], status)
+        .map_err(|e| format!("Failed to update action: {e}"))?;
+    println!("✅ {} {} updated ({})", record.id, args[1], record.status.display_name());
+    Ok(())
+}
+
+fn handle_capa_effectiveness(args: &[String]) -> Result<(), String> {
+    let usage = "qms capa effectiveness <CAPA-ID> --method <TEXT> --criteria <TEXT> (--effective | --not-effective) [--evidence <REF>]";
+    let capa_id = positional_arg(args, "CAPA ID", usage)?;
+    let mut method = String::new();
+    let mut criteria = String::new();
+    let mut evidence = String::new();
+    let mut effective = None;
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--method" => method = option_value(args, i)?,
+            "--criteria" => criteria = option_value(args, i)?,
+            "--evidence" => evidence = option_value(args, i)?,
+            "--effective" => {
+                effective = Some(true);
+                i += 1;
+                continue;
+            }
+            "--not-effective" => {
+                effective = Some(false);
+                i += 1;
+                continue;
+            }
+            _ => return Err(format!("Unknown option: {}. Usage: {usage}", args[i])),
+        }
+        i += 2;
+    }
+
+    let effective = effective.ok_or_else(|| format!("--effective or --not-effective is required. Usage: {usage}"))?;
+    let record = capa_manager()?
+        .record_effectiveness(&capa_id, &method, &criteria, effective, &evidence, &get_current_user_id())
+        .map_err(|e| format!("Failed to record effectiveness check: {e}"))?;
+
+    if effective {
+        println!("✅ {} verified effective - ready for closure (qms capa close {})", record.id, record.id);
+    } else {
+        println!("⚠️  {} not effective - returned to {} for additional actions", record.id, record.status.display_name());
+    }
+    Ok(())
+}
+
+fn handle_capa_link_risk(args: &[String]) -> Result<(), String> {
+    if args.len() != 2 {
+        return Err("Usage: qms capa link-risk <CAPA-ID> <RISK-ID>".to_string());
+    }
+    capa_manager()?
+        .link_risk(&args[0], &args[1])
+        .map_err(|e| format!("Failed to link risk: {e}"))?;
+    println!("✅ {} linked to risk {} (risk flagged for review)", args[0], args[1]);
+    Ok(())
+}
+
+fn handle_capa_link(args: &[String]) -> Result<(), String> {
+    let usage = "qms capa link <CAPA-ID> <TARGET-ID> [--type <LINK-TYPE>]";
+    if args.len() < 2 {
+        return Err(format!("Usage: {usage}"));
+    }
+    let mut link_type = TraceLinkType::Related;
+    let mut i = 2;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--type" => link_type = TraceLinkType::from_str(&option_value(args, i)?).map_err(|e| e.to_string())?,
+            _ => return Err(format!("Unknown option: {}. Usage: {usage}", args[i])),
+        }
+        i += 2;
+    }
+
+    let link = capa_manager()?
+        .link_trace(&args[0], &args[1], link_type)
+        .map_err(|e| format!("Failed to create trace link: {e}"))?;
+    println!("✅ Trace link {} created: {} --{}--> {}", link.id, link.source_id, link.link_type, link.target_id);
+    Ok(())
+}
+
+fn handle_capa_close(args: &[String]) -> Result<(), String> {
+    let usage = "qms capa close <CAPA-ID> [--user <USER>] [--password <PASSWORD>] [--code <CODE>]";
+    let capa_id = positional_arg(args, "CAPA ID", usage)?;
+    let mut user = get_current_user_id();
+    let mut password = None;
+    let mut code = None;
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--user" => user = option_value(args, i)?,
+            "--password" => password = Some(option_value(args, i)?),
+            "--code" => code = Some(option_value(args, i)?),
+            _ => return Err(format!("Unknown option: {}. Usage: {usage}", args[i])),
+        }
+        i += 2;
+    }
+
+    let password = match password {
+        Some(password) => password,
+        None => crate::commands::audit::prompt_signing_password(&user)?,
+    };
+    let code = crate::commands::audit::signing_code(&user, code)?;
+
+    let record = capa_manager()?
+        .close_capa(&capa_id, &user, &password, code.as_deref())
+        .map_err(|e| format!("Failed to close CAPA: {e}"))?;
+    println!("🔒 {} closed by {}", record.id, user);
+    if let Some(ref signature_id) = record.closure_signature_id {
+        println!("✍️  Signature: {signature_id}");
+    }
+    Ok(())
+}
+
+fn handle_capa_cancel(args: &[String]) -> Result<(), String> {
+    let usage = "qms capa cancel <CAPA-ID> --reason <TEXT>";
+    let capa_id = positional_arg(args, "CAPA ID", usage)?;
+    let reason = match args.get(1).map(String::as_str) {
+        Some("--reason") => option_value(args, 1)?,
+        _ => return Err(format!("--reason is required. Usage: {usage}")),
+    };
+    capa_manager()?
+        .cancel_capa(&capa_id, &reason)
+        .map_err(|e| format!("Failed to cancel CAPA: {e}"))?;
+    println!("🚫 {capa_id} cancelled");
+    Ok(())
+}
+
+fn print_capa_help() {
+    println!("Manage Corrective and Preventive Actions (CAPA)\n");
+    println!("USAGE:");
+    println!("    qms capa <COMMAND>\n");
+    println!("COMMANDS:");
+    println!("    create         Open a new CAPA record");
+    println!("    list           List CAPA records");
+    println!("    show           Show CAPA details");
+    println!("    root-cause     Record 5-why / fishbone root-cause analysis");
+    println!("    add-action     Add an action to the action plan");
+    println!("    action-status  Update an action's status");
+    println!("    effectiveness  Record the effectiveness check");
+    println!("    link-risk      Link to a risk and flag it for review");
+    println!("    link           Create a traceability link");
+    println!("    close          Close with electronic signature");
+    println!("    cancel         Cancel with justification");
+    println!("    help           Show this help message\n");
+    println!("WORKFLOW:");
+    println!("    Open → Investigation → Action Planned → Implementation → Effectiveness Check → Closed\n");
+    println!("For more information on a specific command, use:");
+    println!("    qms capa <COMMAND> --help");
+}
+
+fn print_capa_create_help() {
+    println!("Open a new CAPA record\n");
+    println!("USAGE:");
+    println!("    qms capa create --title <TITLE> --desc <DESCRIPTION> --source <SOURCE> [OPTIONS]\n");
+    println!("OPTIONS:");
+    println!("    --title <TITLE>          CAPA title (required)");
+    println!("    --desc <DESCRIPTION>     Problem description (required)");
+    println!("    --source <SOURCE>        complaint, audit, nonconformance, surveillance (required)");
+    println!("    --ref <ID>               Reference to the originating record");
+    println!("    --type <TYPE>            corrective (default) or preventive");
+    println!("    --priority <PRIORITY>    low, medium (default), high, critical");
+    println!("    --owner <USER>           Responsible owner (default: current user)");
+    println!("    --due <DATE>             Target completion date");
+    println!("    --help                   Show this help message\n");
+    println!("EXAMPLES:");
+    println!("    qms capa create --title \"Infusion rate drift\" --desc \"Customer reports drift\" --source complaint --ref CMP-042");
+}
@@ -0,0 +1,337 @@
+/*
+ * QMS (Quality Management System)
+ * Change Control Command Handler
+ *
+ * CLI command handlers for engineering change requests
+ * (FDA 21 CFR 820.30(i), ISO 13485:2016 7.3.9)
+ */
+
+use crate::commands::args::{option_value, positional_arg};
+use crate::modules::change_control::{ChangeControlManager, ChangeRequest, ChangeState, ChangeTarget, ProposedChange};
+use crate::utils::get_current_project_path;
+use crate::utils::user_context::get_current_user_id;
+use std::str::FromStr;
+
+pub fn handle_change_command(args: &[String]) -> Result<(), String> {
+    if args.len() < 3 {
+        print_change_help();
+        return Ok(());
+    }
+
+    match args[2].as_str() {
+        "create" => handle_change_create(&args[3..]),
+        "list" => handle_change_list(&args[3..]),
+        "show" => handle_change_show(&args[3..]),
+        "add" => handle_change_add(&args[3..]),
+        "submit" => handle_change_submit(&args[3..]),
+        "approve" => handle_change_approve(&args[3..]),
+        "reject" => handle_change_reject(&args[3..]),
+        "withdraw" => handle_change_withdraw(&args[3..]),
+        "cancel" => handle_change_cancel(&args[3..]),
+        "implement" => handle_change_implement(&args[3..]),
+        "--help" | "-h" | "help" => {
+            print_change_help();
+            Ok(())
+        }
+        _ => {
+            eprintln!("Error: Unknown change command '{}'", args[2]);
+            print_change_help();
+            Err(format!("Unknown change command '{}'", args[2]))
+        }
+    }
+}
+
+fn change_manager() -> Result<ChangeControlManager, String> {
+    let project_path = get_current_project_path()
+        .map_err(|e| format!("Failed to get project path: {e}"))?;
+    ChangeControlManager::new(&project_path).map_err(|e| format!("Failed to initialize change control: {e}"))
+}
+
+fn handle_change_create(args: &[String]) -> Result<(), String> {
+    let usage = "qms change create --title <TITLE> --reason <REASON>";
+    let mut title = String::new();
+    let mut reason = String::new();
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--title" => title = option_value(args, i)?,
+            "--reason" => reason = option_value(args, i)?,
+            "--help" | "-h" => {
+                println!("USAGE:\n    {usage}");
+                return Ok(());
+            }
+            _ => return Err(format!("Unknown option: {}. Usage: {usage}", args[i])),
+        }
+        i += 2;
+    }
+
+    let record = change_manager()?
+        .create_change(&title, &reason, &get_current_user_id())
+        .map_err(|e| format!("Failed to create change request: {e}"))?;
+    println!("✅ Change request created");
+    println!("🆔 ID: {}", record.id);
+    println!("\nNext: qms change add {} --target <doc|req|risk> --id <ID> --set <FIELD>=<VALUE>", record.id);
+    Ok(())
+}
+
+fn handle_change_list(args: &[String]) -> Result<(), String> {
+    let mut state = None;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--state" | "--status" => state = Some(ChangeState::from_str(&option_value(args, i)?).map_err(|e| e.to_string())?),
+            "--help" | "-h" => {
+                println!("USAGE:\n    qms change list [--state <STATE>]");
+                return Ok(());
+            }
+            _ => return Err(format!("Unknown option: {}", args[i])),
+        }
+        i += 2;
+    }
+
+    let records = change_manager()?
+        .list_changes(state.as_ref())
+        .map_err(|e| format!("Failed to list change requests: {e}"))?;
+
+    if records.is_empty() {
+        println!("No change requests found");
+        return Ok(());
+    }
+
+    println!("{:<9} {:<13} {:<8} Title", "ID", "State", "Changes");
+    println!("{:-<70}", "");
+    for record in &records {
+        println!("{:<9} {:<13} {:<8} {}", record.id, record.state.display_name(), record.changes.len(), record.title);
+    }
+    println!("\nTotal: {} change request(s)", records.len());
+    Ok(())
+}
+
+fn handle_change_show(args: &[String]) -> Result<(), String> {
+    let change_id = positional_arg(args, "Change request ID", "qms change show <ECR-ID>")?;
+    let manager = change_manager()?;
+    let record = manager.load_change(&change_id).map_err(|e| e.to_string())?;
+    print_change_details(&record);
+
+    if !record.approvals.is_empty() {
+        let verifications = manager.verify_approvals(&change_id).map_err(|e| e.to_string())?;
+        println!("\n✍️  CCB signatures:");
+        for approval in &record.approvals {
+            let valid = verifications.iter().any(|(user, v)| user == &approval.user_id && v.is_valid);
+            println!("   {:<16} {} ({}){}", approval.user_id, approval.signed_at,
+                if valid { "✅ Valid" } else { "❌ Invalid" },
+                approval.comments.as_ref().map(|c| format!(" - {c}")).unwrap_or_default());
+        }
+    }
+    Ok(())
+}
+
+fn print_change_details(record: &ChangeRequest) {
+    println!("📋 {} - {}", record.id, record.title);
+    println!("{:-<60}", "");
+    println!("State:       {}", record.state.display_name());
+    println!("Requested:   {} by {}", record.created_at, record.requested_by);
+    println!("Reason:      {}", record.reason);
+
+    if !record.changes.is_empty() {
+        println!("\n📝 Proposed changes:");
+        for change in &record.changes {
+            println!("   {} {}{}", change.target.display_name(), change.entity_id,
+                if change.description.is_empty() { String::new() } else { format!(" - {}", change.description) });
+            for (field, value) in &change.fields {
+                let preview: String = value.chars().take(60).collect();
+                println!("      {field} = {preview}{}", if value.chars().count() > 60 { "…" } else { "" });
+            }
+        }
+    }
+
+    if !record.impact.is_empty() {
+        println!("\n🔍 Impact analysis:");
+        for impact in &record.impact {
+            println!("   {}: {} direct, {} indirect, ~{}h", impact.entity_id, impact.direct_impacts.len(),
+                impact.indirect_impacts.len(), impact.effort_hours);
+            if !impact.direct_impacts.is_empty() {
+                println!("      Direct: {}", impact.direct_impacts.join(", "));
+            }
+            if !impact.risk_assessment.is_empty() {
+                println!("      {}", impact.risk_assessment);
+            }
+        }
+    }
+
+    if !record.ccb_members.is_empty() {
+        println!("\n👥 CCB: {}", record.ccb_members.join(", "));
+        let outstanding = record.outstanding_approvers();
+        if record.state == ChangeState::UnderReview && !outstanding.is_empty() {
+            println!("   Awaiting: {}", outstanding.join(", "));
+        }
+    }
+    if let Some(ref reason) = record.rejection_reason {
+        println!("\n❌ Rejected: {reason}");
+    }
+    if let (Some(by), Some(at)) = (&record.implemented_by, &record.implemented_at) {
+        println!("\n✅ Implemented by {by} at {at}");
+    }
+}
+
+fn handle_change_add(args: &[String]) -> Result<(), String> {
+    let usage = "qms change add <ECR-ID> --target <doc|req|risk> --id <ENTITY-ID> --set <FIELD>=<VALUE>... [--desc <TEXT>]";
+    let change_id = positional_arg(args, "Change request ID", usage)?;
+    let mut target = None;
+    let mut entity_id = None;
+    let mut fields = Vec::new();
+    let mut description = String::new();
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--target" => target = Some(ChangeTarget::from_str(&option_value(args, i)?).map_err(|e| e.to_string())?),
+            "--id" => entity_id = Some(option_value(args, i)?),
+            "--set" => {
+                let value = option_value(args, i)?;
+                let (field, new_value) = value.split_once('=')
+                    .ok_or_else(|| format!("--set expects <FIELD>=<VALUE>, got '{value}'"))?;
+                fields.push((field.trim().to_string(), new_value.to_string()));
+            }
+            "--desc" | "--description" => description = option_value(args, i)?,
+            _ => return Err(format!("Unknown option: {}. Usage: {usage}", args[i])),
+        }
+        i += 2;
+    }
+
+    let change = ProposedChange {
+        target: target.ok_or_else(|| format!("--target is required. Usage: {usage}"))?,
+        entity_id: entity_id.ok_or_else(|| format!("--id is required. Usage: {usage}"))?,
+        fields,
+        description,
+    };
+    let record = change_manager()?
+        .add_change(&change_id, change)
+        .map_err(|e| format!("Failed to add change: {e}"))?;
+    println!("✅ Change added to {} ({} change(s))", record.id, record.changes.len());
+    Ok(())
+}
+
+fn handle_change_submit(args: &[String]) -> Result<(), String> {
+    let usage = "qms change submit <ECR-ID> --ccb <USER>[,<USER>...]";
+    let change_id = positional_arg(args, "Change request ID", usage)?;
+    let members = match args.get(1).map(String::as_str) {
+        Some("--ccb") => option_value(args, 1)?,
+        _ => return Err(format!("--ccb is required. Usage: {usage}")),
+    };
+
+    let record = change_manager()?
+        .submit(&change_id, members.split(',').map(str::to_string).collect(), &get_current_user_id())
+        .map_err(|e| format!("Failed to submit change request: {e}"))?;
+    println!("📤 {} submitted to the CCB ({})", record.id, record.ccb_members.join(", "));
+    for impact in &record.impact {
+        println!("   🔍 {}: {} direct / {} indirect impacts, ~{}h",
+            impact.entity_id, impact.direct_impacts.len(), impact.indirect_impacts.len(), impact.effort_hours);
+    }
+    Ok(())
+}
+
+fn handle_change_approve(args: &[String]) -> Result<(), String> {
+    let usage = "qms change approve <ECR-ID> [--user <USER>] [--password <PASSWORD>] [--code <CODE>] [--comments <TEXT>]";
+    let change_id = positional_arg(args, "Change request ID", usage)?;
+    let mut user = get_current_user_id();
+    let mut password = None;
+    let mut code = None;
+    let mut comments = None;
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--user" => user = option_value(args, i)?,
+            "--password" => password = Some(option_value(args, i)?),
+            "--code" => code = Some(option_value(args, i)?),
+            "--comments" => comments = Some(option_value(args, i)?),
+            _ => return Err(format!("Unknown option: {}. Usage: {usage}", args[i])),
+        }
+        i += 2;
+    }
+
+    let password = match password {
+        Some(password) => password,
+        None => crate::commands::audit::prompt_signing_password(&user)?,
+    };
+    let code = crate::commands::audit::signing_code(&user, code)?;
+
+    let record = change_manager()?
+        .approve(&change_id, &user, &password, code.as_deref(), comments.as_deref())
+        .map_err(|e| format!("Failed to approve change request: {e}"))?;
+    println!("✍️  {} signed by {user}", record.id);
+    if record.state == ChangeState::Approved {
+        println!("✅ Approved by all CCB members - apply with: qms change implement {}", record.id);
+    } else {
+        println!("⏳ Awaiting: {}", record.outstanding_approvers().join(", "));
+    }
+    Ok(())
+}
+
+fn handle_change_reject(args: &[String]) -> Result<(), String> {
+    let usage = "qms change reject <ECR-ID> --reason <TEXT>";
+    let change_id = positional_arg(args, "Change request ID", usage)?;
+    let reason = match args.get(1).map(String::as_str) {
+        Some("--reason") => option_value(args, 1)?,
+        _ => return Err(format!("--reason is required. Usage: {usage}")),
+    };
+    change_manager()?
+        .reject(&change_id, &get_current_user_id(), &reason)
+        .map_err(|e| format!("Failed to reject change request: {e}"))?;
+    println!("❌ {change_id} rejected");
+    Ok(())
+}
+
+fn handle_change_withdraw(args: &[String]) -> Result<(), String> {
+    let change_id = positional_arg(args, "Change request ID", "qms change withdraw <ECR-ID>")?;
+    change_manager()?
+        .withdraw(&change_id, &get_current_user_id())
+        .map_err(|e| format!("Failed to withdraw change request: {e}"))?;
+    println!("↩️  {change_id} withdrawn to Draft - CCB signatures discarded");
+    Ok(())
+}
+
+fn handle_change_cancel(args: &[String]) -> Result<(), String> {
+    let change_id = positional_arg(args, "Change request ID", "qms change cancel <ECR-ID>")?;
+    change_manager()?
+        .cancel(&change_id, &get_current_user_id())
+        .map_err(|e| format!("Failed to cancel change request: {e}"))?;
+    println!("🚫 {change_id} cancelled");
+    Ok(())
+}
+
+fn handle_change_implement(args: &[String]) -> Result<(), String> {
+    let change_id = positional_arg(args, "Change request ID", "qms change implement <ECR-ID>")?;
+    let record = change_manager()?
+        .implement(&change_id, &get_current_user_id())
+        .map_err(|e| format!("Failed to implement change request (no changes were applied): {e}"))?;
+    println!("✅ {} implemented - {} change(s) applied", record.id, record.changes.len());
+    Ok(())
+}
+
+fn print_change_help() {
+    println!("Manage engineering change requests (ECR/ECO)\n");
+    println!("USAGE:");
+    println!("    qms change <COMMAND>\n");
+    println!("COMMANDS:");
+    println!("    create     Open a change request");
+    println!("    list       List change requests");
+    println!("    show       Show changes, impact analysis and CCB signatures");
+    println!("    add        Add a document, requirement or risk change");
+    println!("    submit     Submit to the CCB (runs impact analysis)");
+    println!("    approve    Sign as a CCB member");
+    println!("    reject     Reject as a CCB member");
+    println!("    withdraw   Return a submitted request to draft");
+    println!("    cancel     Cancel an unapproved request");
+    println!("    implement  Apply all approved changes atomically");
+    println!("    help       Show this help message\n");
+    println!("WORKFLOW:");
+    println!("    Draft → Under Review → Approved → Implemented\n");
+    println!("CHANGEABLE FIELDS:");
+    println!("    doc   title, content, change_type (major|minor|patch)");
+    println!("    req   title, description, category, priority, status, source, rationale,");
+    println!("          acceptance_criteria, verification_method");
+    println!("    risk  hazard_description, hazardous_situation, harm, severity, occurrence, detectability");
+}
@@ -4,6 +4,7 @@
 
 use crate::prelude::*;
 use crate::modules::user_manager::{FileBasedAuthService, UserSession, SessionType};
+use crate::modules::user_manager::mfa::is_second_factor_required;
 use crate::web::unified_auth_context::UnifiedAuthContext;
 use std::path::{Path, PathBuf};
 use std::fs;
@@ -30,7 +31,7 @@ impl CliAuthHelper {
     }
     
     /// Get the global QMS directory
-    fn get_qms_directory() -> QmsResult<PathBuf> {
+    pub(crate) fn get_qms_directory() -> QmsResult<PathBuf> {
         let home = std::env::var("HOME")
             .or_else(|_| std::env::var("USERPROFILE"))
             .map_err(|_| QmsError::io_error("Cannot determine home directory"))?;
@@ -56,12 +57,32 @@ impl CliAuthHelper {
         self.auth_service.validate_session(&session_id)
     }
     
-    /// Login user and save session
+    /// Login user and save session, prompting for a code if the user has MFA enrolled
     pub fn login(&self, username: &str, password: &str) -> QmsResult<UserSession> {
-        let session = self.auth_service.cli_login(username, password)?;
+        match self.login_with_code(username, password, None) {
+            Err(e) if is_second_factor_required(&e) => {
+                let code = Self::prompt_code()?;
+                self.login_with_code(username, password, Some(&code))
+            }
+            result => result,
+        }
+    }
+
+    /// Login user with an optional TOTP or recovery code and save session
+    pub fn login_with_code(&self, username: &str, password: &str, code: Option<&str>) -> QmsResult<UserSession> {
+        let session = self.auth_service.cli_login_with_code(username, password, code)?;
         self.save_session_id(&session.session_id)?;
         Ok(session)
     }
+
+    /// Prompt for a second-factor code
+    fn prompt_code() -> QmsResult<String> {
+        print!("Authentication code: ");
+        io::stdout().flush()?;
+        let mut code = String::new();
+        io::stdin().read_line(&mut code)?;
+        Ok(code.trim().to_string())
+    }
     
     /// Interactive login with prompts
     pub fn interactive_login(&self) -> QmsResult<UserSession> {
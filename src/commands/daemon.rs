@@ -0,0 +1,299 @@
+/*
+ * QMS (Quality Management System)
+ * Daemon Command Handler
+ *
+ * CLI command handlers for the background job scheduler: running scheduled
+ * jobs in the foreground, managing schedules and querying run history
+ */
+
+use crate::modules::audit_logger::functions::audit_log_action;
+use crate::modules::scheduler::{
+    load_schedules, query_history, save_schedules, CronSchedule, HistoryQuery, JobKind, JobRun,
+    ScheduledJob, Scheduler, SchedulerHandle, UtcTime,
+};
+use crate::utils::get_current_project_path;
+use crate::utils::user_context::get_current_username;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+
+pub fn handle_daemon_command(args: &[String]) -> Result<(), String> {
+    if args.len() < 3 {
+        print_daemon_help();
+        return Ok(());
+    }
+
+    match args[2].as_str() {
+        "run" => handle_daemon_run(&args[3..]),
+        "schedule" => handle_daemon_schedule(&args[3..]),
+        "run-job" => handle_daemon_run_job(&args[3..]),
+        "history" => handle_daemon_history(&args[3..]),
+        "--help" | "-h" | "help" => {
+            print_daemon_help();
+            Ok(())
+        }
+        _ => {
+            eprintln!("Error: Unknown daemon command '{}'", args[2]);
+            print_daemon_help();
+            Err(format!("Unknown daemon command '{}'", args[2]))
+        }
+    }
+}
+
+/// Start the scheduler thread for `qms serve` if the project has enabled schedules
+pub fn start_background_scheduler() -> Option<SchedulerHandle> {
+    let project_path = get_current_project_path().ok()?;
+    let enabled = load_schedules(&project_path)
+        .map(|schedules| schedules.iter().filter(|job| job.enabled).count())
+        .unwrap_or(0);
+    if enabled == 0 {
+        return None;
+    }
+    println!("⏰ Scheduler running {enabled} scheduled job(s) (disable with --no-scheduler)");
+    Some(Scheduler::spawn(&project_path))
+}
+
+/// Add a schedule to the project, rejecting duplicate names
+pub fn add_schedule(project_path: &Path, job: ScheduledJob) -> Result<(), String> {
+    let mut schedules = load_schedules(project_path).map_err(|e| format!("Failed to load schedules: {e}"))?;
+    if schedules.iter().any(|existing| existing.name == job.name) {
+        return Err(format!("Schedule '{}' already exists", job.name));
+    }
+
+    let details = format!("{} '{}' {}", job.name, job.schedule, job.job.name());
+    println!("✅ Schedule '{}' added: {} ({})", job.name, job.job.name(), job.schedule);
+    print_next_run(&job);
+    schedules.push(job);
+    save_schedules(project_path, &schedules).map_err(|e| format!("Failed to save schedules: {e}"))?;
+    let _ = audit_log_action("SCHEDULE_CREATED", "ScheduledJob", &details);
+    Ok(())
+}
+
+/// Print a table of schedules with their next run time
+pub fn print_schedules(schedules: &[ScheduledJob]) {
+    if schedules.is_empty() {
+        println!("No schedules configured. Add one with 'qms daemon schedule add'.");
+        return;
+    }
+    let now = crate::utils::current_timestamp();
+    println!("{:<24} {:<18} {:<24} {:<8} {:<22} Options", "Name", "Schedule", "Job", "Enabled", "Next run");
+    println!("{}", "─".repeat(110));
+    for job in schedules {
+        let next = job
+            .schedule
+            .next_after(now)
+            .map(|ts| UtcTime::from_timestamp(ts).to_string())
+            .unwrap_or_else(|| "never".to_string());
+        let options: Vec<String> = job.job.options().iter().map(|(k, v)| format!("{k}={v}")).collect();
+        println!(
+            "{:<24} {:<18} {:<24} {:<8} {:<22} {}",
+            job.name,
+            job.schedule.expression(),
+            job.job.name(),
+            if job.enabled { "yes" } else { "no" },
+            if job.enabled { next } else { "-".to_string() },
+            options.join(" ")
+        );
+    }
+}
+
+fn handle_daemon_run(args: &[String]) -> Result<(), String> {
+    let project_path = current_project()?;
+    let scheduler = Scheduler::new(&project_path);
+
+    if args.iter().any(|arg| arg == "--once") {
+        let runs = scheduler
+            .run_due(crate::utils::current_timestamp())
+            .map_err(|e| format!("Failed to load schedules: {e}"))?;
+        if runs.is_empty() {
+            println!("No scheduled jobs are due this minute");
+        }
+        runs.iter().for_each(print_run);
+        return Ok(());
+    }
+
+    let schedules = load_schedules(&project_path).map_err(|e| format!("Failed to load schedules: {e}"))?;
+    println!("⏰ QMS scheduler started for {}", project_path.display());
+    print_schedules(&schedules);
+    println!("🛑 Press Ctrl+C to stop the scheduler");
+    let _ = audit_log_action("SCHEDULER_STARTED", "Scheduler", &project_path.display().to_string());
+
+    // Runs until the process is interrupted
+    let stop = AtomicBool::new(false);
+    scheduler.run_until(&stop);
+    Ok(())
+}
+
+fn handle_daemon_schedule(args: &[String]) -> Result<(), String> {
+    let project_path = current_project()?;
+    match args.first().map(String::as_str) {
+        None | Some("list") => {
+            let schedules = load_schedules(&project_path).map_err(|e| format!("Failed to load schedules: {e}"))?;
+            print_schedules(&schedules);
+            Ok(())
+        }
+        Some("add") => {
+            if args.len() < 4 {
+                return Err("Usage: qms daemon schedule add <name> \"<cron>\" <job> [--option key=value]...".to_string());
+            }
+            let schedule = CronSchedule::parse(&args[2]).map_err(|e| e.to_string())?;
+            let options = parse_options(&args[4..])?;
+            let job = JobKind::from_parts(&args[3], &options).map_err(|e| e.to_string())?;
+            let job = ScheduledJob::new(&args[1], schedule, job, &get_current_username()).map_err(|e| e.to_string())?;
+            add_schedule(&project_path, job)
+        }
+        Some(action @ ("remove" | "enable" | "disable")) => {
+            let name = args.get(1).ok_or_else(|| format!("Usage: qms daemon schedule {action} <name>"))?;
+            update_schedule(&project_path, name, action)
+        }
+        Some("--help" | "-h" | "help") => {
+            print_daemon_help();
+            Ok(())
+        }
+        Some(other) => Err(format!("Unknown schedule command '{other}'")),
+    }
+}
+
+fn update_schedule(project_path: &Path, name: &str, action: &str) -> Result<(), String> {
+    let mut schedules = load_schedules(project_path).map_err(|e| format!("Failed to load schedules: {e}"))?;
+    let index = schedules
+        .iter()
+        .position(|job| job.name == name)
+        .ok_or_else(|| format!("Schedule '{name}' not found"))?;
+
+    let audit_action = match action {
+        "remove" => {
+            schedules.remove(index);
+            "SCHEDULE_DELETED"
+        }
+        _ => {
+            schedules[index].enabled = action == "enable";
+            "SCHEDULE_UPDATED"
+        }
+    };
+    save_schedules(project_path, &schedules).map_err(|e| format!("Failed to save schedules: {e}"))?;
+    let _ = audit_log_action(audit_action, "ScheduledJob", &format!("{name} {action}"));
+    println!("✅ Schedule '{name}' {}", if action == "remove" { "removed".to_string() } else { format!("{action}d") });
+    Ok(())
+}
+
+fn handle_daemon_run_job(args: &[String]) -> Result<(), String> {
+    let name = args.first().ok_or("Usage: qms daemon run-job <name>")?;
+    let project_path = current_project()?;
+    let run = Scheduler::new(&project_path).run_named(name).map_err(|e| e.to_string())?;
+    print_run(&run);
+    if run.success {
+        Ok(())
+    } else {
+        Err(format!("Job '{name}' failed"))
+    }
+}
+
+fn handle_daemon_history(args: &[String]) -> Result<(), String> {
+    let mut query = HistoryQuery { limit: Some(20), ..Default::default() };
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--job" => {
+                query.job = Some(args.get(i + 1).ok_or("--job requires a value")?.clone());
+                i += 2;
+            }
+            "--limit" => {
+                let limit = args.get(i + 1).ok_or("--limit requires a value")?;
+                query.limit = Some(limit.parse().map_err(|_| "Invalid limit value")?);
+                i += 2;
+            }
+            "--failed" => {
+                query.failed_only = true;
+                i += 1;
+            }
+            other => return Err(format!("Unknown option: {other}")),
+        }
+    }
+
+    let project_path = current_project()?;
+    let runs = query_history(&project_path, &query).map_err(|e| format!("Failed to read job history: {e}"))?;
+    if runs.is_empty() {
+        println!("No job runs recorded");
+        return Ok(());
+    }
+    println!("{:<22} {:<24} {:<24} {:<7} {:>6}  Summary", "Started", "Schedule", "Job", "Result", "Secs");
+    println!("{}", "─".repeat(110));
+    for run in &runs {
+        println!(
+            "{:<22} {:<24} {:<24} {:<7} {:>6}  {}",
+            UtcTime::from_timestamp(run.started_at).to_string(),
+            run.job,
+            run.kind,
+            if run.success { "ok" } else { "FAILED" },
+            run.finished_at.saturating_sub(run.started_at),
+            run.summary
+        );
+    }
+    Ok(())
+}
+
+fn current_project() -> Result<PathBuf, String> {
+    get_current_project_path().map_err(|e| format!("Failed to get project path: {e}"))
+}
+
+/// Collect `--option key=value` pairs
+fn parse_options(args: &[String]) -> Result<HashMap<String, String>, String> {
+    let mut options = HashMap::new();
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--option" | "-o" => {
+                let pair = args.get(i + 1).ok_or("--option requires key=value")?;
+                let (key, value) = pair
+                    .split_once('=')
+                    .ok_or_else(|| format!("Option '{pair}' must be key=value"))?;
+                options.insert(key.to_string(), value.to_string());
+                i += 2;
+            }
+            other => return Err(format!("Unknown option: {other}")),
+        }
+    }
+    Ok(options)
+}
+
+fn print_run(run: &JobRun) {
+    let icon = if run.success { "✅" } else { "❌" };
+    println!("{icon} {} ({}): {}", run.job, run.kind, run.summary);
+}
+
+fn print_next_run(job: &ScheduledJob) {
+    if let Some(next) = job.schedule.next_after(crate::utils::current_timestamp()) {
+        println!("   Next run: {}", UtcTime::from_timestamp(next));
+    }
+}
+
+fn print_daemon_help() {
+    println!("Run recurring QMS jobs on a schedule\n");
+    println!("USAGE:");
+    println!("    qms daemon <COMMAND>\n");
+    println!("COMMANDS:");
+    println!("    run [--once]                              Run due jobs every minute until stopped");
+    println!("                                              (--once: run this minute's jobs and exit)");
+    println!("    schedule list                             List schedules and their next run");
+    println!("    schedule add <name> \"<cron>\" <job> [--option key=value]...");
+    println!("                                              Add a schedule");
+    println!("    schedule remove|enable|disable <name>     Remove, enable or disable a schedule");
+    println!("    run-job <name>                            Run a scheduled job now");
+    println!("    history [--job <name>] [--limit <n>] [--failed]");
+    println!("                                              Show recent job runs");
+    println!("    help                                      Show this help message\n");
+    println!("JOBS:");
+    println!("    report                   Generate a report (options: report=dhf|risks|audit, format=md|csv|json|pdf|html)");
+    println!("    rotate-audit-logs        Rotate audit logs and apply retention");
+    println!("    audit-backup             Back up the audit trail");
+    println!("    document-backup-cleanup  Remove old document backups (option: retention_days, default 2555)");
+    println!("    risk-escalation          Flag risks needing escalation (option: rpn_threshold, default 100)\n");
+    println!("Cron expressions use five UTC fields (minute hour day-of-month month day-of-week)");
+    println!("or @hourly, @daily, @weekly, @monthly, @yearly. Schedules are stored in the");
+    println!("project config; 'qms serve' also runs them unless started with --no-scheduler.\n");
+    println!("EXAMPLES:");
+    println!("    qms daemon schedule add nightly-audit-backup \"0 2 * * *\" audit-backup");
+    println!("    qms daemon schedule add weekly-risks @weekly risk-escalation --option rpn_threshold=80");
+    println!("    qms daemon history --failed");
+}
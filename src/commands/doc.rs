@@ -3,6 +3,8 @@ use std::collections::HashMap;
 use crate::modules::document_control::service::DocumentService;
 use crate::modules::document_control::document::DocumentType;
 use crate::modules::document_control::version::VersionChangeType;
+use crate::modules::document_control::diff::{self, DiffFormat, DocumentDiff};
+use std::io::IsTerminal;
 
 #[derive(Default)]
 pub struct SearchFilters {
@@ -67,6 +69,7 @@ pub fn handle_doc_command(args: &[String]) -> Result<(), String> {
         "regulatory" => handle_doc_regulatory(&args[3..]),
         "compliance" => handle_doc_compliance(&args[3..]),
         "backup" => handle_doc_backup(&args[3..]),
+        "store" => handle_doc_store(&args[3..]),
         "--help" | "-h" => {
             print_doc_help();
             Ok(())
@@ -220,6 +223,7 @@ fn handle_doc_template(args: &[String]) -> Result<(), String> {
         "list" => handle_template_list(&args[1..]),
         "create" => handle_template_create(&args[1..]),
         "init" => handle_template_init(&args[1..]),
+        "render" => handle_template_render(&args[1..]),
         _ => {
             eprintln!("Error: Unknown template command '{}'", args[0]);
             print_doc_template_help();
@@ -366,6 +370,71 @@ fn handle_template_create(args: &[String]) -> Result<(), String> {
     }
 }
 
+fn handle_template_render(args: &[String]) -> Result<(), String> {
+    if args.is_empty() || args.contains(&"--help".to_string()) || args.contains(&"-h".to_string()) {
+        print_template_render_help();
+        return Ok(());
+    }
+
+    let mut template_name = None;
+    let mut project_name = None;
+    let mut user = "CLI User".to_string();
+    let mut output = None;
+    let mut custom_variables: HashMap<String, String> = HashMap::new();
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--template" | "-t" => {
+                template_name = Some(args.get(i + 1).ok_or("Error: --template requires a value")?.clone());
+                i += 2;
+            }
+            "--project" | "-p" => {
+                project_name = Some(args.get(i + 1).ok_or("Error: --project requires a value")?.clone());
+                i += 2;
+            }
+            "--author" | "-a" => {
+                user = args.get(i + 1).ok_or("Error: --author requires a value")?.clone();
+                i += 2;
+            }
+            "--output" | "-o" => {
+                output = Some(args.get(i + 1).ok_or("Error: --output requires a value")?.clone());
+                i += 2;
+            }
+            "--var" => {
+                if i + 2 < args.len() {
+                    custom_variables.insert(args[i + 1].clone(), args[i + 2].clone());
+                    i += 3;
+                } else {
+                    return Err("Error: --var requires KEY VALUE".to_string());
+                }
+            }
+            name if template_name.is_none() && !name.starts_with('-') => {
+                template_name = Some(name.to_string());
+                i += 1;
+            }
+            other => return Err(format!("Error: Unknown argument '{other}'")),
+        }
+    }
+
+    let template_name = template_name.ok_or("Error: template name is required")?;
+    let project_name = project_name.unwrap_or_else(|| "My QMS Project".to_string());
+    let service = DocumentService::new(".".to_string().into());
+
+    let content = service
+        .render_template(&template_name, project_name, user, custom_variables)
+        .map_err(|e| format!("Failed to render template: {e}"))?;
+
+    match output {
+        Some(path) => {
+            std::fs::write(&path, &content).map_err(|e| format!("Failed to write {path}: {e}"))?;
+            println!("✅ Template '{template_name}' rendered to {path}");
+        }
+        None => print!("{content}"),
+    }
+    Ok(())
+}
+
 fn handle_template_init(_args: &[String]) -> Result<(), String> {
     let service = DocumentService::new(".".to_string().into());
     
@@ -585,6 +654,80 @@ fn handle_doc_backup(args: &[String]) -> Result<(), String> {
     }
 }
 
+fn handle_doc_store(args: &[String]) -> Result<(), String> {
+    use crate::modules::document_control::content_store::ContentStore;
+    use crate::modules::document_control::version::DocumentVersionControl;
+
+    if args.is_empty() || args.contains(&"--help".to_string()) || args.contains(&"-h".to_string()) {
+        print_doc_store_help();
+        return Ok(());
+    }
+    let project_path =
+        crate::utils::get_current_project_path().map_err(|e| format!("Failed to get project path: {e}"))?;
+
+    match args[0].as_str() {
+        "migrate" => {
+            let dry_run = args[1..].iter().any(|arg| arg == "--dry-run");
+            let report = DocumentVersionControl::migrate_to_content_store(&project_path, dry_run)
+                .map_err(|e| format!("Migration failed: {e}"))?;
+            for failure in &report.failures {
+                eprintln!("❌ {failure}");
+            }
+            if dry_run {
+                println!("{} version file(s) would be migrated, {} already use the content store",
+                         report.migrated, report.already_migrated);
+                return Ok(());
+            }
+            println!("✅ Migrated {} version file(s) ({} already used the content store)",
+                     report.migrated, report.already_migrated);
+            if report.migrated > 0 {
+                println!("   Size: {} → {} bytes", report.bytes_before, report.bytes_after);
+            }
+            if report.failures.is_empty() {
+                Ok(())
+            } else {
+                Err(format!("{} version file(s) could not be migrated", report.failures.len()))
+            }
+        }
+        "verify" => {
+            let verification = DocumentVersionControl::verify_content_store(&project_path)
+                .map_err(|e| format!("Verification failed: {e}"))?;
+            for problem in &verification.problems {
+                eprintln!("❌ {problem}");
+            }
+            println!("Checked {} version file(s) and {} object(s)",
+                     verification.versions_checked, verification.objects_checked);
+            if !verification.unreferenced.is_empty() {
+                println!("{} unreferenced object(s); run 'qms doc store gc' to remove them",
+                         verification.unreferenced.len());
+            }
+            if verification.is_valid() {
+                println!("✅ Content store is intact");
+                Ok(())
+            } else {
+                Err(format!("{} integrity problem(s) found", verification.problems.len()))
+            }
+        }
+        "gc" => {
+            let removed = DocumentVersionControl::collect_unreferenced_objects(&project_path)
+                .map_err(|e| format!("Garbage collection failed: {e}"))?;
+            println!("✅ Removed {removed} unreferenced object(s)");
+            Ok(())
+        }
+        "stats" => {
+            let stats = ContentStore::new(&project_path).stats().map_err(|e| e.to_string())?;
+            println!("Objects: {}", stats.objects);
+            println!("Size:    {} bytes", stats.bytes);
+            Ok(())
+        }
+        other => {
+            eprintln!("Error: Unknown store command '{other}'");
+            print_doc_store_help();
+            Err("Unknown store command".to_string())
+        }
+    }
+}
+
 fn handle_backup_list(args: &[String]) -> Result<(), String> {
     let mut document_id: Option<String> = None;
 
@@ -973,6 +1116,7 @@ fn print_doc_help() {
     println!("    compliance Show compliance status and generate reports");
     println!("\nBACKUP & RECOVERY:");
     println!("    backup    Manage document backups and recovery operations");
+    println!("    store     Migrate, verify and clean the version content store");
     println!("\n    help      Show this help message\n");
     println!("For more information on a specific command, use:");
     println!("    qms doc <COMMAND> --help");
@@ -1226,55 +1370,84 @@ fn handle_doc_compare(args: &[String]) -> Result<(), String> {
     let version_a = &args[1];
     let version_b = &args[2];
 
+    // Parse output options
+    let mut format = DiffFormat::Unified;
+    let mut context = diff::DEFAULT_CONTEXT;
+    let mut width = 160;
+    let mut output_path: Option<&str> = None;
+    let mut color = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+    let mut i = 3;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--format" => {
+                let value = args.get(i + 1).ok_or("--format requires a value")?;
+                format = DiffFormat::parse(value)
+                    .ok_or_else(|| format!("Unknown format '{value}'. Use unified, side-by-side or html"))?;
+                i += 2;
+            }
+            "--context" => {
+                context = args.get(i + 1).and_then(|v| v.parse().ok()).ok_or("--context requires a number")?;
+                i += 2;
+            }
+            "--width" => {
+                width = args.get(i + 1).and_then(|v| v.parse().ok()).ok_or("--width requires a number")?;
+                i += 2;
+            }
+            "--output" => {
+                output_path = Some(args.get(i + 1).ok_or("--output requires a file path")?);
+                i += 2;
+            }
+            "--no-color" => {
+                color = false;
+                i += 1;
+            }
+            other => return Err(format!("Unknown option: {other}")),
+        }
+    }
+
     // Load project
     let project_path = std::env::current_dir()
         .map_err(|e| format!("Failed to get current directory: {e}"))?;
 
     let service = DocumentService::new(project_path);
 
-    match service.compare_document_versions(document_id, version_a, version_b) {
-        Ok((doc_a, doc_b)) => {
+    let (doc_a, doc_b) = service
+        .compare_document_versions(document_id, version_a, version_b)
+        .map_err(|e| format!("Failed to compare document versions: {e}"))?;
+    let comparison = DocumentDiff::between(&doc_a, &doc_b);
+
+    if output_path.is_some() {
+        color = false;
+    }
+    let rendered = match format {
+        DiffFormat::Unified => comparison.to_unified(context, color),
+        DiffFormat::SideBySide => comparison.to_side_by_side(width, context, color),
+        DiffFormat::Html => comparison.to_html(),
+    };
+
+    let (added, removed, modified) = comparison.stats();
+    let summary = format!("{added} lines added, {removed} removed, {modified} modified, {} metadata fields changed", comparison.metadata.len());
+    match output_path {
+        Some(path) => {
+            std::fs::write(path, &rendered).map_err(|e| format!("Failed to write {path}: {e}"))?;
+            println!("✅ Comparison of {document_id} v{version_a} and v{version_b} written to {path}");
+            println!("   {summary}");
+        }
+        None if format == DiffFormat::Html => print!("{rendered}"),
+        None => {
             println!("Comparing Document {document_id} Versions: {version_a} vs {version_b}");
             println!("{}", "=".repeat(60));
-            
-            // Compare metadata
-            println!("METADATA COMPARISON:");
-            println!("Title:      {} | {}", doc_a.title, doc_b.title);
-            println!("Type:       {} | {}", doc_a.doc_type.to_string(), doc_b.doc_type.to_string());
-            println!("Status:     {:?} | {:?}", doc_a.status, doc_b.status);
-            println!("Updated:    {} | {}", 
-                crate::utils::format_timestamp_from_string(&doc_a.updated_at),
-                crate::utils::format_timestamp_from_string(&doc_b.updated_at)
-            );
-            
-            // Basic content comparison
-            println!("\nCONTENT COMPARISON:");
-            if doc_a.content == doc_b.content {
-                println!("✓ Content is identical");
+            if comparison.has_changes() {
+                print!("{rendered}");
+                println!("{}", "=".repeat(60));
+                println!("{summary}");
             } else {
-                println!("✗ Content differs");
-                println!("  Version {} length: {} characters", version_a, doc_a.content.len());
-                println!("  Version {} length: {} characters", version_b, doc_b.content.len());
-                
-                // Show first few lines if different
-                let lines_a: Vec<&str> = doc_a.content.lines().collect();
-                let lines_b: Vec<&str> = doc_b.content.lines().collect();
-                
-                println!("\nFirst few lines comparison:");
-                for i in 0..std::cmp::min(5, std::cmp::max(lines_a.len(), lines_b.len())) {
-                    let line_a = lines_a.get(i).unwrap_or(&"<END>");
-                    let line_b = lines_b.get(i).unwrap_or(&"<END>");
-                    
-                    if line_a != line_b {
-                        println!("  Line {}: {} | {}", i + 1, line_a, line_b);
-                    }
-                }
+                println!("✓ Versions are identical");
             }
-            
-            Ok(())
         }
-        Err(e) => Err(format!("Failed to compare document versions: {e}")),
     }
+
+    Ok(())
 }
 
 fn handle_doc_rollback(args: &[String]) -> Result<(), String> {
@@ -2200,12 +2373,20 @@ fn print_doc_compare_help() {
     println!("    <DOCUMENT_ID>    The UUID of the document");
     println!("    <VERSION_A>      First version to compare (e.g., 1.0.0)");
     println!("    <VERSION_B>      Second version to compare (e.g., 1.1.0)\n");
+    println!("OPTIONS:");
+    println!("    --format <FORMAT>    unified, side-by-side or html [default: unified]");
+    println!("    --context <LINES>    Unchanged lines shown around each change [default: 3]");
+    println!("    --width <COLUMNS>    Total width of the side-by-side view [default: 160]");
+    println!("    --output <FILE>      Write the comparison to a file instead of stdout");
+    println!("    --no-color           Disable coloured terminal output\n");
     println!("DESCRIPTION:");
-    println!("    Shows differences between two document versions including");
-    println!("    metadata changes and basic content comparison.\n");
+    println!("    Shows a redline of two document versions: line- and word-level content");
+    println!("    changes plus changes to status, tags and regulatory mappings. The HTML");
+    println!("    format marks deletions and insertions for review before approval.\n");
     println!("EXAMPLES:");
     println!("    qms doc compare 123e4567-e89b-12d3-a456-426614174000 1.0.0 1.1.0");
-    println!("    qms doc compare 123e4567-e89b-12d3-a456-426614174000 2.0.0 1.5.3");
+    println!("    qms doc compare 123e4567-e89b-12d3-a456-426614174000 1.0.0 1.1.0 --format side-by-side");
+    println!("    qms doc compare 123e4567-e89b-12d3-a456-426614174000 2.0.0 1.5.3 --format html --output redline.html");
 }
 
 fn print_doc_rollback_help() {
@@ -2528,7 +2709,9 @@ fn print_doc_search_help() {
     println!("    --status, -s <STATUS>  Filter by status (draft, inreview, approved, archived)");
     println!("    --author, -a <AUTHOR>  Filter by document author\n");
     println!("DESCRIPTION:");
-    println!("    Searches through all documents by title and content text.");
+    println!("    Searches document titles and content through the project search index,");
+    println!("    best matches first. Words are matched on their stems, and the query");
+    println!("    accepts the 'qms search' syntax (\"phrases\", title:, tag:, -word).");
     println!("    Additional filters can be applied to narrow results by document");
     println!("    metadata like type, status, and author.\n");
     println!("EXAMPLES:");
@@ -2547,10 +2730,13 @@ fn print_doc_template_help() {
     println!("COMMANDS:");
     println!("    init      Initialize default templates in the project");
     println!("    list      List all available templates");
-    println!("    create    Create a new document from a template\n");
+    println!("    create    Create a new document from a template");
+    println!("    render    Preview a template rendered against current project data\n");
     println!("DESCRIPTION:");
     println!("    Templates provide standardized starting points for different document types.");
-    println!("    They include variable substitution for project-specific information.");
+    println!("    They include variable substitution for project-specific information, and can");
+    println!("    pull live requirements, risks, tests and documents with {{{{#each}}}} loops,");
+    println!("    {{{{#if}}}} conditionals, filters and {{{{> partial}}}} includes.");
     println!("    Default templates are provided for medical device documentation requirements.\n");
     println!("For more information on a specific template command, use:");
     println!("    qms doc template <COMMAND> --help");
@@ -2705,7 +2891,7 @@ fn print_doc_export_help() {
     println!("    json                     Full JSON export with metadata");
     println!("    md, markdown             Clean Markdown with metadata header");
     println!("    html                     HTML format with CSS styling");
-    println!("    pdf                      Text-based PDF-like format\n");
+    println!("    pdf                      Native PDF with document ID/revision headers and signatures\n");
     println!("EXAMPLES:");
     println!("    # Export to JSON with full metadata");
     println!("    qms doc export DOC-20240115-001 --format json --output report.json");
@@ -2716,10 +2902,37 @@ fn print_doc_export_help() {
     println!("    # Export to Markdown for documentation");
     println!("    qms doc export DOC-20240115-001 --format md --output document.md");
     println!();
-    println!("    # Export to PDF-like format with audit trail");
+    println!("    # Export to PDF with audit trail");
     println!("    qms doc export DOC-20240115-001 --format pdf --output report.pdf --include-audit");
 }
 
+fn print_template_render_help() {
+    println!("Render a template against current project data and print the result\n");
+    println!("USAGE:");
+    println!("    qms doc template render <NAME> [OPTIONS]\n");
+    println!("OPTIONS:");
+    println!("    --project, -p <NAME>     Project name (default: \"My QMS Project\")");
+    println!("    --author, -a <AUTHOR>    Value of {{{{USER}}}} (default: \"CLI User\")");
+    println!("    --var <KEY> <VALUE>      Custom variable (can be used multiple times)");
+    println!("    --output, -o <FILE>      Write to FILE instead of printing\n");
+    println!("TEMPLATE LANGUAGE:");
+    println!("    {{{{NAME}}}}, {{{{risk.harm}}}}            Output a value");
+    println!("    {{{{title | upper}}}}                 Filters: upper, lower, title, trim, default:\"x\",");
+    println!("                                     truncate:N, join:\", \", count, first, last,");
+    println!("                                     fixed:N, cell");
+    println!("    {{{{#if rpn>=50}}}}...{{{{else}}}}...{{{{/if}}}}  Conditionals (and, or, not, = != < <= > >=)");
+    println!("    {{{{#each requirements where category=safety sort by id limit 10}}}}...{{{{/each}}}}");
+    println!("                                     Loops; {{{{else}}}} renders when nothing matches,");
+    println!("                                     @index, @number, @first and @last are available");
+    println!("    {{{{> name}}}}                        Include templates/partials/name.md");
+    println!("    {{{{! comment }}}}                    Ignored\n");
+    println!("DATA SOURCES:");
+    println!("    requirements, risks, tests, documents, project\n");
+    println!("EXAMPLES:");
+    println!("    qms doc template render rmf_template --project \"Infusion Pump\"");
+    println!("    qms doc template render srs_template --output srs-preview.md");
+}
+
 fn print_template_create_help() {
     println!("Create a new document from a template\n");
     println!("USAGE:");
@@ -2737,7 +2950,8 @@ fn print_template_create_help() {
     println!("    {{{{DATE}}}}             - Current date");
     println!("    {{{{USER}}}}             - Document author");
     println!("    {{{{VERSION}}}}          - Document version (default: 1.0.0)");
-    println!("    Custom variables can be added with --var KEY VALUE\n");
+    println!("    Custom variables can be added with --var KEY VALUE");
+    println!("    Templates can also loop over project data; see 'qms doc template render --help'\n");
     println!("AVAILABLE TEMPLATES:");
     println!("    srs_template             Software Requirements Specification");
     println!("    sdd_template             Software Design Description");
@@ -3531,6 +3745,21 @@ fn print_doc_backup_help() {
     println!("    qms doc backup <COMMAND> --help");
 }
 
+fn print_doc_store_help() {
+    println!("Manage the content store that holds document version text\n");
+    println!("USAGE:");
+    println!("    qms doc store <COMMAND>\n");
+    println!("COMMANDS:");
+    println!("    migrate [--dry-run]  Move version files that embed the full document into the store");
+    println!("    verify               Check every version and stored object against its SHA-256 hash");
+    println!("    gc                   Remove objects no version refers to");
+    println!("    stats                Show the number and size of stored objects\n");
+    println!("DESCRIPTION:");
+    println!("    Versions keep their text in documents/objects/ as hash-named chunks shared by all");
+    println!("    versions and documents, so a minor revision only stores the chunks it changes.");
+    println!("    Reads verify each chunk, so corruption is reported rather than returned.");
+}
+
 fn print_backup_list_help() {
     println!("List document backups\n");
     println!("USAGE:");
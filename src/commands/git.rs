@@ -0,0 +1,229 @@
+/*
+ * QMS (Quality Management System)
+ * Git History Command Handler
+ *
+ * CLI command handlers for importing a local Git repository's history and
+ * relating its commits, tags and files to QMS records (IEC 62304 Section 8)
+ */
+
+use crate::commands::args::{is_help, option_value};
+use crate::modules::git_history::{GitCommit, GitHistory, GitHistoryManager, RecordKind};
+use crate::utils::get_current_project_path;
+use std::path::Path;
+
+pub fn handle_git_command(args: &[String]) -> Result<(), String> {
+    if args.len() < 3 {
+        print_git_help();
+        return Ok(());
+    }
+
+    match args[2].as_str() {
+        "import" => handle_git_import(&args[3..]),
+        "log" => handle_git_log(&args[3..]),
+        "show" => handle_git_show(&args[3..]),
+        "releases" => handle_git_releases(&args[3..]),
+        "--help" | "-h" | "help" => {
+            print_git_help();
+            Ok(())
+        }
+        _ => {
+            eprintln!("Error: Unknown git command '{}'", args[2]);
+            print_git_help();
+            Err(format!("Unknown git command '{}'", args[2]))
+        }
+    }
+}
+
+fn git_manager() -> Result<GitHistoryManager, String> {
+    let project_path = get_current_project_path()
+        .map_err(|e| format!("Failed to get project path: {e}"))?;
+    GitHistoryManager::new(&project_path).map_err(|e| format!("Failed to initialize Git history: {e}"))
+}
+
+fn load_history() -> Result<GitHistory, String> {
+    git_manager()?.load().map_err(|e| e.to_string())
+}
+
+fn print_commit(commit: &GitCommit) {
+    let references = if commit.references.is_empty() {
+        String::new()
+    } else {
+        format!("  [{}]", commit.references.join(", "))
+    };
+    println!("{}  {}  {:<16} {}{references}", commit.short_hash(), commit.date(), commit.author, commit.subject);
+}
+
+fn handle_git_import(args: &[String]) -> Result<(), String> {
+    let usage = "qms git import [<REPOSITORY>]";
+    if is_help(args) {
+        println!("USAGE:\n    {usage}");
+        println!("\nReads commits reachable from HEAD and every tag of a local repository");
+        println!("(default: the current directory). Re-run to refresh the imported history.");
+        return Ok(());
+    }
+    if args.len() > 1 {
+        return Err(format!("Unexpected argument: {}. Usage: {usage}", args[1]));
+    }
+    let repository = args.first().map_or(".", String::as_str);
+
+    let history = git_manager()?
+        .import(Path::new(repository))
+        .map_err(|e| format!("Failed to import Git history: {e}"))?;
+    let referencing = history.commits.iter().filter(|commit| !commit.references.is_empty()).count();
+    println!("✅ Imported Git history from {}", history.repository);
+    println!("   Commits:              {}", history.commits.len());
+    println!("   Tags:                 {}", history.tags.len());
+    println!("   Referencing records:  {referencing}");
+    println!("\nNext: qms git releases");
+    Ok(())
+}
+
+fn handle_git_log(args: &[String]) -> Result<(), String> {
+    let usage = "qms git log [--from <REV>] [--to <REV>] [--ref <ID>]";
+    let mut from = None;
+    let mut to = "HEAD".to_string();
+    let mut reference = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--from" => from = Some(option_value(args, i)?),
+            "--to" => to = option_value(args, i)?,
+            "--ref" => reference = Some(option_value(args, i)?),
+            "--help" | "-h" => {
+                println!("USAGE:\n    {usage}");
+                println!("\nLists imported commits in <FROM>..<TO>, optionally only those referencing <ID>.");
+                return Ok(());
+            }
+            _ => return Err(format!("Unknown option: {}. Usage: {usage}", args[i])),
+        }
+        i += 2;
+    }
+
+    let history = load_history()?;
+    let commits: Vec<&GitCommit> = history
+        .commits_between(from.as_deref(), &to)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|commit| match &reference {
+            Some(id) => commit.references.contains(id),
+            None => true,
+        })
+        .collect();
+
+    if commits.is_empty() {
+        println!("No commits found");
+        return Ok(());
+    }
+    for commit in &commits {
+        print_commit(commit);
+    }
+    println!("\nTotal: {} commit(s)", commits.len());
+    Ok(())
+}
+
+fn handle_git_show(args: &[String]) -> Result<(), String> {
+    let usage = "qms git show <ID>";
+    let id = match args.first() {
+        Some(arg) if arg == "--help" || arg == "-h" => {
+            println!("USAGE:\n    {usage}");
+            println!("\nShows the commits referencing a requirement, risk, CAPA or change request,");
+            println!("the files they changed and the release that first contained each commit.");
+            return Ok(());
+        }
+        Some(id) if args.len() == 1 => id,
+        _ => return Err(format!("Record ID is required. Usage: {usage}")),
+    };
+    let kind = RecordKind::of(id)
+        .ok_or_else(|| format!("'{id}' is not a requirement, risk, CAPA or change request ID"))?;
+
+    let history = load_history()?;
+    let commits = history.commits_referencing(id);
+    println!("🔗 {} {id}", kind.display_name());
+    println!("{:-<60}", "");
+    if commits.is_empty() {
+        println!("No commits reference {id}");
+        return Ok(());
+    }
+
+    let mut files: Vec<&str> = Vec::new();
+    for commit in &commits {
+        print_commit(commit);
+        println!("          Release: {}", history.release_of(&commit.hash).unwrap_or("unreleased"));
+        for file in &commit.files {
+            if !files.contains(&file.as_str()) {
+                files.push(file);
+            }
+        }
+    }
+    files.sort_unstable();
+    println!("\nFiles changed ({}):", files.len());
+    for file in files {
+        println!("   {file}");
+    }
+    Ok(())
+}
+
+fn handle_git_releases(args: &[String]) -> Result<(), String> {
+    let usage = "qms git releases [--commits]";
+    let mut show_commits = false;
+    for arg in args {
+        match arg.as_str() {
+            "--commits" => show_commits = true,
+            "--help" | "-h" => {
+                println!("USAGE:\n    {usage}");
+                println!("\nLists the changes of each tagged release, newest first.");
+                return Ok(());
+            }
+            _ => return Err(format!("Unknown option: {arg}. Usage: {usage}")),
+        }
+    }
+
+    let history = load_history()?;
+    let releases = history.releases();
+    if releases.is_empty() {
+        println!("No commits imported");
+        return Ok(());
+    }
+
+    for release in &releases {
+        let since = release.previous.as_deref().map(|previous| format!(" (since {previous})")).unwrap_or_default();
+        println!("📦 {}{since}: {} commit(s)", release.name, release.commits.len());
+        let references = release.references();
+        if !references.is_empty() {
+            println!("   References: {}", references.join(", "));
+        }
+        if show_commits {
+            for commit in &release.commits {
+                print!("   ");
+                print_commit(commit);
+            }
+        }
+        println!();
+    }
+    Ok(())
+}
+
+fn print_git_help() {
+    println!("Relate a local Git repository's history to QMS records\n");
+    println!("USAGE:");
+    println!("    qms git <COMMAND>\n");
+    println!("COMMANDS:");
+    println!("    import     Import commits, tags and changed files from a local repository");
+    println!("    log        List imported commits between two revisions");
+    println!("    show       Show the commits, files and releases for a record ID");
+    println!("    releases   List the changes of each tagged release");
+    println!("    help       Show this help message\n");
+    println!("REFERENCES:");
+    println!("    Commit messages are searched for REQ-, RISK-, HAZ-, CAPA- and ECR- IDs,");
+    println!("    e.g. \"Tune flow limit (REQ-014, ECR-003)\". The repository is read with the");
+    println!("    local git client; no remote is contacted.\n");
+    println!("RELATED:");
+    println!("    qms trace impact --between <FROM> <TO>   Requirements touched between releases");
+    println!("    qms report dhf                           DHF lists software changes per release\n");
+    println!("EXAMPLES:");
+    println!("    qms git import ../firmware");
+    println!("    qms git log --from v2.0 --to v2.1");
+    println!("    qms git show REQ-014");
+    println!("    qms git releases --commits");
+}
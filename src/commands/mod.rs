@@ -1,14 +1,27 @@
 // Command handlers for CLI interface
 // Each command module handles parsing and execution for its respective command
 
+pub mod args;
+pub mod attach;
 pub mod audit;
+pub mod baseline;
+pub mod capa;
+pub mod change;
 pub mod cli_auth_helper;
 pub mod command_execution_context;
+pub mod daemon;
 pub mod doc;
+pub mod git;
 pub mod init;
+pub mod ncr;
+pub mod project;
 pub mod report;
 pub mod req;
+pub mod review;
 pub mod risk;
+pub mod search;
+pub mod soup;
+pub mod storage;
 pub mod test;
 pub mod trace;
 pub mod unified_doc_handler;
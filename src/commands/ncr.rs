@@ -0,0 +1,373 @@
+/*
+ * QMS (Quality Management System)
+ * Nonconformance Command Handler
+ *
+ * CLI command handlers for nonconformance reports (NCRs)
+ * (FDA 21 CFR 820.90, ISO 13485:2016 8.3)
+ */
+
+use crate::commands::args::{option_value, positional_arg};
+use crate::modules::nonconformance::{Disposition, NcrManager, NcrRecord, NcrSeverity, NcrState, NcrType, NewNcr};
+use crate::utils::get_current_project_path;
+use crate::utils::user_context::get_current_user_id;
+use std::str::FromStr;
+
+pub fn handle_ncr_command(args: &[String]) -> Result<(), String> {
+    if args.len() < 3 {
+        print_ncr_help();
+        return Ok(());
+    }
+
+    match args[2].as_str() {
+        "create" => handle_ncr_create(&args[3..]),
+        "list" => handle_ncr_list(&args[3..]),
+        "show" => handle_ncr_show(&args[3..]),
+        "contain" => handle_ncr_contain(&args[3..]),
+        "propose" => handle_ncr_propose(&args[3..]),
+        "approve" => handle_ncr_approve(&args[3..]),
+        "reject" => handle_ncr_reject(&args[3..]),
+        "close" => handle_ncr_close(&args[3..]),
+        "escalate" => handle_ncr_escalate(&args[3..]),
+        "cancel" => handle_ncr_cancel(&args[3..]),
+        "--help" | "-h" | "help" => {
+            print_ncr_help();
+            Ok(())
+        }
+        _ => {
+            eprintln!("Error: Unknown NCR command '{}'", args[2]);
+            print_ncr_help();
+            Err(format!("Unknown NCR command '{}'", args[2]))
+        }
+    }
+}
+
+fn ncr_manager() -> Result<NcrManager, String> {
+    let project_path = get_current_project_path()
+        .map_err(|e| format!("Failed to get project path: {e}"))?;
+    NcrManager::new(&project_path).map_err(|e| format!("Failed to initialize NCR system: {e}"))
+}
+
+/// Value of a single required `--<name>` option following the NCR ID
+fn required_option(args: &[String], name: &str, usage: &str) -> Result<String, String> {
+    match args.get(1).map(String::as_str) {
+        Some(option) if option == name => option_value(args, 1),
+        _ => Err(format!("{name} is required. Usage: {usage}")),
+    }
+}
+
+fn handle_ncr_create(args: &[String]) -> Result<(), String> {
+    let mut title = String::new();
+    let mut description = String::new();
+    let mut nc_type = NcrType::Product;
+    let mut severity = NcrSeverity::Minor;
+    let mut product = None;
+    let mut lot_number = None;
+    let mut quantity = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--title" => title = option_value(args, i)?,
+            "--desc" | "--description" => description = option_value(args, i)?,
+            "--type" => nc_type = NcrType::from_str(&option_value(args, i)?).map_err(|e| e.to_string())?,
+            "--severity" => severity = NcrSeverity::from_str(&option_value(args, i)?).map_err(|e| e.to_string())?,
+            "--product" => product = Some(option_value(args, i)?),
+            "--lot" => lot_number = Some(option_value(args, i)?),
+            "--qty" | "--quantity" => {
+                let value = option_value(args, i)?;
+                quantity = Some(value.parse::<u32>().map_err(|_| format!("Invalid quantity: {value}"))?);
+            }
+            "--help" | "-h" => {
+                print_ncr_create_help();
+                return Ok(());
+            }
+            _ => return Err(format!("Unknown option: {}", args[i])),
+        }
+        i += 2;
+    }
+
+    if title.is_empty() {
+        return Err("Title is required (--title)".to_string());
+    }
+    if description.is_empty() {
+        return Err("Description is required (--desc)".to_string());
+    }
+
+    let record = ncr_manager()?
+        .create_ncr(
+            NewNcr { title, description, nc_type, severity, product, lot_number, quantity },
+            &get_current_user_id(),
+        )
+        .map_err(|e| format!("Failed to create NCR: {e}"))?;
+
+    println!("✅ NCR created successfully");
+    println!("🆔 ID: {}", record.id);
+    println!("📄 Title: {}", record.title);
+    println!("⚠️  Severity: {}", record.severity.display_name());
+    println!("\nNext: qms ncr contain {} --actions <TEXT>", record.id);
+    Ok(())
+}
+
+fn handle_ncr_list(args: &[String]) -> Result<(), String> {
+    let mut state = None;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--state" | "--status" => state = Some(NcrState::from_str(&option_value(args, i)?).map_err(|e| e.to_string())?),
+            "--help" | "-h" => {
+                println!("USAGE:\n    qms ncr list [--state <STATE>]");
+                return Ok(());
+            }
+            _ => return Err(format!("Unknown option: {}", args[i])),
+        }
+        i += 2;
+    }
+
+    let records = ncr_manager()?
+        .list_ncrs(state.as_ref())
+        .map_err(|e| format!("Failed to list NCRs: {e}"))?;
+
+    if records.is_empty() {
+        println!("No NCRs found");
+        return Ok(());
+    }
+
+    println!("{:<9} {:<22} {:<8} {:<9} Title", "ID", "State", "Type", "Severity");
+    println!("{:-<80}", "");
+    for record in &records {
+        println!(
+            "{:<9} {:<22} {:<8} {:<9} {}",
+            record.id,
+            record.state.display_name(),
+            record.nc_type.display_name(),
+            record.severity.display_name(),
+            record.title
+        );
+    }
+    println!("\nTotal: {} NCR(s)", records.len());
+    Ok(())
+}
+
+fn handle_ncr_show(args: &[String]) -> Result<(), String> {
+    let ncr_id = positional_arg(args, "NCR ID", "qms ncr show <NCR-ID>")?;
+    let manager = ncr_manager()?;
+    let record = manager.load_ncr(&ncr_id).map_err(|e| e.to_string())?;
+    print_ncr_details(&record);
+
+    if !record.approvals.is_empty() {
+        let verifications = manager.verify_disposition(&ncr_id).map_err(|e| e.to_string())?;
+        println!("\n✍️  Disposition signatures:");
+        for approval in &record.approvals {
+            let valid = verifications.iter().any(|(role, v)| role == &approval.role && v.is_valid);
+            println!("   {:<16} {} at {} ({})", approval.role, approval.user_id, approval.signed_at,
+                if valid { "✅ Valid" } else { "❌ Invalid" });
+        }
+    }
+    Ok(())
+}
+
+fn print_ncr_details(record: &NcrRecord) {
+    println!("📋 {} - {}", record.id, record.title);
+    println!("{:-<60}", "");
+    println!("Type:        {}", record.nc_type.display_name());
+    println!("Severity:    {}", record.severity.display_name());
+    println!("State:       {}", record.state.display_name());
+    if let Some(ref product) = record.product {
+        println!("Product:     {product}");
+    }
+    if let Some(ref lot) = record.lot_number {
+        println!("Lot:         {lot}");
+    }
+    if let Some(quantity) = record.quantity {
+        println!("Quantity:    {quantity}");
+    }
+    println!("Detected:    {} by {}", record.detected_at, record.detected_by);
+    println!("\nDescription:\n   {}", record.description);
+
+    if let Some(ref containment) = record.containment {
+        println!("\n🧱 Containment: {containment}");
+    }
+    if let Some(ref disposition) = record.disposition {
+        println!("\n⚖️  Disposition: {}", disposition.display_name());
+        if let Some(ref justification) = record.justification {
+            println!("   Justification: {justification}");
+        }
+        let outstanding = record.outstanding_roles();
+        if !outstanding.is_empty() && record.state == NcrState::DispositionProposed {
+            println!("   Awaiting signatures: {}", outstanding.join(", "));
+        }
+    }
+    if let Some(ref capa_id) = record.capa_id {
+        println!("\n🛠️  Escalated to {capa_id}");
+    }
+
+    if !record.history.is_empty() {
+        println!("\n📜 History:");
+        for entry in &record.history {
+            println!("   {} {} → {} by {}{}", entry.action_timestamp, entry.from_state.display_name(),
+                entry.to_state.display_name(), entry.actor_id,
+                entry.comments.as_ref().map(|c| format!(" ({c})")).unwrap_or_default());
+        }
+    }
+}
+
+fn handle_ncr_contain(args: &[String]) -> Result<(), String> {
+    let usage = "qms ncr contain <NCR-ID> --actions <TEXT>";
+    let ncr_id = positional_arg(args, "NCR ID", usage)?;
+    let actions = required_option(args, "--actions", usage)?;
+    let record = ncr_manager()?
+        .contain(&ncr_id, &actions, &get_current_user_id())
+        .map_err(|e| format!("Failed to record containment: {e}"))?;
+    println!("✅ {} contained", record.id);
+    Ok(())
+}
+
+fn handle_ncr_propose(args: &[String]) -> Result<(), String> {
+    let usage = "qms ncr propose <NCR-ID> --disposition <use-as-is|rework|scrap|return-to-vendor> [--justification <TEXT>]";
+    let ncr_id = positional_arg(args, "NCR ID", usage)?;
+    let mut disposition = None;
+    let mut justification = None;
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--disposition" => disposition = Some(Disposition::from_str(&option_value(args, i)?).map_err(|e| e.to_string())?),
+            "--justification" => justification = Some(option_value(args, i)?),
+            _ => return Err(format!("Unknown option: {}. Usage: {usage}", args[i])),
+        }
+        i += 2;
+    }
+
+    let disposition = disposition.ok_or_else(|| format!("--disposition is required. Usage: {usage}"))?;
+    let record = ncr_manager()?
+        .propose_disposition(&ncr_id, disposition, justification.as_deref(), &get_current_user_id())
+        .map_err(|e| format!("Failed to propose disposition: {e}"))?;
+    println!("✅ Disposition proposed for {}", record.id);
+    println!("✍️  Required signatures: {}", record.outstanding_roles().join(", "));
+    Ok(())
+}
+
+fn handle_ncr_approve(args: &[String]) -> Result<(), String> {
+    let usage = "qms ncr approve <NCR-ID> --role <ROLE> [--user <USER>] [--password <PASSWORD>] [--code <CODE>]";
+    let ncr_id = positional_arg(args, "NCR ID", usage)?;
+    let mut role = None;
+    let mut user = get_current_user_id();
+    let mut password = None;
+    let mut code = None;
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--role" => role = Some(option_value(args, i)?),
+            "--user" => user = option_value(args, i)?,
+            "--password" => password = Some(option_value(args, i)?),
+            "--code" => code = Some(option_value(args, i)?),
+            _ => return Err(format!("Unknown option: {}. Usage: {usage}", args[i])),
+        }
+        i += 2;
+    }
+
+    let role = role.ok_or_else(|| format!("--role is required. Usage: {usage}"))?;
+    let password = match password {
+        Some(password) => password,
+        None => crate::commands::audit::prompt_signing_password(&user)?,
+    };
+    let code = crate::commands::audit::signing_code(&user, code)?;
+
+    let record = ncr_manager()?
+        .approve_disposition(&ncr_id, &user, &password, code.as_deref(), &role)
+        .map_err(|e| format!("Failed to approve disposition: {e}"))?;
+    println!("✍️  {} signed by {} as {}", record.id, user, role);
+    if record.state == NcrState::DispositionApproved {
+        println!("✅ Disposition approved - all required roles have signed");
+    } else {
+        println!("⏳ Awaiting: {}", record.outstanding_roles().join(", "));
+    }
+    Ok(())
+}
+
+fn handle_ncr_reject(args: &[String]) -> Result<(), String> {
+    let usage = "qms ncr reject <NCR-ID> --reason <TEXT>";
+    let ncr_id = positional_arg(args, "NCR ID", usage)?;
+    let reason = required_option(args, "--reason", usage)?;
+    ncr_manager()?
+        .reject_disposition(&ncr_id, &reason, &get_current_user_id())
+        .map_err(|e| format!("Failed to reject disposition: {e}"))?;
+    println!("↩️  Disposition for {ncr_id} rejected - returned to Contained");
+    Ok(())
+}
+
+fn handle_ncr_close(args: &[String]) -> Result<(), String> {
+    let usage = "qms ncr close <NCR-ID> [--comments <TEXT>]";
+    let ncr_id = positional_arg(args, "NCR ID", usage)?;
+    let comments = match args.get(1).map(String::as_str) {
+        Some("--comments") => Some(option_value(args, 1)?),
+        Some(other) => return Err(format!("Unknown option: {other}. Usage: {usage}")),
+        None => None,
+    };
+    ncr_manager()?
+        .close_ncr(&ncr_id, comments.as_deref(), &get_current_user_id())
+        .map_err(|e| format!("Failed to close NCR: {e}"))?;
+    println!("🔒 {ncr_id} closed");
+    Ok(())
+}
+
+fn handle_ncr_escalate(args: &[String]) -> Result<(), String> {
+    let ncr_id = positional_arg(args, "NCR ID", "qms ncr escalate <NCR-ID>")?;
+    let record = ncr_manager()?
+        .escalate_to_capa(&ncr_id, &get_current_user_id())
+        .map_err(|e| format!("Failed to escalate NCR: {e}"))?;
+    if let Some(ref capa_id) = record.capa_id {
+        println!("🛠️  {} escalated to {capa_id}", record.id);
+        println!("Next: qms capa root-cause {capa_id} --statement <TEXT>");
+    }
+    Ok(())
+}
+
+fn handle_ncr_cancel(args: &[String]) -> Result<(), String> {
+    let usage = "qms ncr cancel <NCR-ID> --reason <TEXT>";
+    let ncr_id = positional_arg(args, "NCR ID", usage)?;
+    let reason = required_option(args, "--reason", usage)?;
+    ncr_manager()?
+        .cancel_ncr(&ncr_id, &reason, &get_current_user_id())
+        .map_err(|e| format!("Failed to cancel NCR: {e}"))?;
+    println!("🚫 {ncr_id} cancelled");
+    Ok(())
+}
+
+fn print_ncr_help() {
+    println!("Manage nonconformance reports (NCR)\n");
+    println!("USAGE:");
+    println!("    qms ncr <COMMAND>\n");
+    println!("COMMANDS:");
+    println!("    create    Identify a new nonconformance");
+    println!("    list      List NCRs");
+    println!("    show      Show NCR details, history and signatures");
+    println!("    contain   Record containment actions");
+    println!("    propose   Propose a disposition");
+    println!("    approve   Sign the proposed disposition for a role");
+    println!("    reject    Reject the proposed disposition");
+    println!("    close     Close after the disposition is carried out");
+    println!("    escalate  Raise a CAPA from this NCR");
+    println!("    cancel    Cancel with justification");
+    println!("    help      Show this help message\n");
+    println!("WORKFLOW:");
+    println!("    Identified → Contained → Disposition Proposed → Disposition Approved → Closed\n");
+    println!("DISPOSITION SIGNATURES:");
+    println!("    use-as-is                      QualityEngineer + Administrator (justification required)");
+    println!("    rework, scrap, return-to-vendor QualityEngineer\n");
+    println!("For more information on a specific command, use:");
+    println!("    qms ncr <COMMAND> --help");
+}
+
+fn print_ncr_create_help() {
+    println!("Identify a new nonconformance\n");
+    println!("USAGE:");
+    println!("    qms ncr create --title <TITLE> --desc <DESCRIPTION> [OPTIONS]\n");
+    println!("OPTIONS:");
+    println!("    --type <TYPE>          product (default) or process");
+    println!("    --severity <LEVEL>     minor (default), major or critical");
+    println!("    --product <PART>       Affected part number or process");
+    println!("    --lot <LOT>            Affected lot / batch");
+    println!("    --qty <N>              Affected quantity");
+}
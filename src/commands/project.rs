@@ -0,0 +1,253 @@
+/*
+ * QMS (Quality Management System)
+ * Project Command Handler
+ *
+ * CLI command handlers for project-wide data protection: encrypting project
+ * data at rest, rotating the project key and recovering a decrypted copy
+ */
+
+use crate::commands::args::find_option;
+use crate::config::Config;
+use crate::modules::audit_logger::functions::audit_log_action;
+use crate::modules::storage::encryption::{self, KeySource, Keyring, KEY_FILE_ENV, PASSPHRASE_ENV};
+use crate::utils::get_current_project_path;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+pub fn handle_project_command(args: &[String]) -> Result<(), String> {
+    if args.len() < 3 {
+        print_project_help();
+        return Ok(());
+    }
+
+    match args[2].as_str() {
+        "status" => handle_project_status(),
+        "encrypt" => handle_project_encrypt(&args[3..]),
+        "rekey" => handle_project_rekey(&args[3..]),
+        "export" => handle_project_export(&args[3..]),
+        "decrypt" => handle_project_decrypt(&args[3..]),
+        "--help" | "-h" | "help" => {
+            print_project_help();
+            Ok(())
+        }
+        _ => {
+            eprintln!("Error: Unknown project command '{}'", args[2]);
+            print_project_help();
+            Err(format!("Unknown project command '{}'", args[2]))
+        }
+    }
+}
+
+/// Unlock an encrypted project before running a command against it
+///
+/// Uses `QMS_PROJECT_PASSPHRASE` / `QMS_PROJECT_KEY_FILE` when set and
+/// otherwise asks for the passphrase on an interactive terminal. A project
+/// that stays locked fails on its first read with an explanatory error.
+pub fn unlock_project_for_command(project_path: &Path) {
+    if !encryption::is_encrypted(project_path) || encryption::is_unlocked(project_path) {
+        return;
+    }
+    let source = match KeySource::from_env() {
+        Some(source) => source,
+        None if io::stdin().is_terminal() => match prompt("Project passphrase: ") {
+            Ok(passphrase) if !passphrase.is_empty() => KeySource::Passphrase(passphrase),
+            _ => return,
+        },
+        None => return,
+    };
+    if let Err(e) = encryption::unlock(project_path, &source) {
+        eprintln!("Warning: Failed to unlock project data: {e}");
+    }
+}
+
+fn handle_project_status() -> Result<(), String> {
+    let project_path = current_project()?;
+    let config = Config::load(&Config::default().config_file_path()).unwrap_or_default();
+
+    println!("Project: {}", project_path.display());
+    println!("Encryption policy (encryption_enabled): {}", if config.encryption_enabled { "on" } else { "off" });
+    match Keyring::load(&project_path) {
+        Ok(keyring) => {
+            println!("Data encryption: ChaCha20-Poly1305");
+            println!("  Key id:     {}", keyring.key_id);
+            println!("  Unlock by:  {}", keyring.methods().join(", "));
+            if let Some(rekeyed_at) = keyring.rekeyed_at {
+                println!("  Rekeyed at: {rekeyed_at}");
+            }
+            println!("  In clear:   {}", encryption::CLEAR_PATHS.join(", "));
+        }
+        Err(_) => {
+            println!("Data encryption: off");
+            if config.encryption_enabled {
+                println!("  Run 'qms project encrypt' to encrypt project data at rest.");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn handle_project_encrypt(args: &[String]) -> Result<(), String> {
+    let project_path = current_project()?;
+    let config = Config::load(&Config::default().config_file_path()).unwrap_or_default();
+    if !config.encryption_enabled {
+        return Err("Encryption is disabled in the QMS configuration (encryption_enabled = false)".to_string());
+    }
+    if encryption::is_encrypted(&project_path) {
+        return Err("Project data is already encrypted. Use 'qms project rekey' to change its key".to_string());
+    }
+
+    let sources = new_key_sources(args, "--passphrase", "--key-file")?;
+    let sealed = encryption::enable(&project_path, &sources).map_err(|e| format!("Encryption failed: {e}"))?;
+
+    let _ = audit_log_action("PROJECT_ENCRYPTED", "Project", &format!("{sealed} records sealed"));
+    println!("✅ Project data encrypted ({sealed} files and records sealed)");
+    print_key_reminder(&sources);
+    Ok(())
+}
+
+fn handle_project_rekey(args: &[String]) -> Result<(), String> {
+    let project_path = current_project()?;
+    if !encryption::is_encrypted(&project_path) {
+        return Err("Project data is not encrypted. Use 'qms project encrypt' first".to_string());
+    }
+
+    let current = current_key_source(args)?;
+    let sources = new_key_sources(args, "--new-passphrase", "--new-key-file")?;
+    let resealed = encryption::rekey(&project_path, &current, &sources)
+        .map_err(|e| format!("Rekey failed: {e}"))?;
+
+    let _ = audit_log_action("PROJECT_REKEYED", "Project", &format!("{resealed} records re-encrypted"));
+    println!("✅ Project key replaced ({resealed} files and records re-encrypted)");
+    println!("   The previous passphrase or key file no longer unlocks this project.");
+    print_key_reminder(&sources);
+    Ok(())
+}
+
+fn handle_project_export(args: &[String]) -> Result<(), String> {
+    let destination = match args.first() {
+        Some(path) if !path.starts_with("--") => PathBuf::from(path),
+        _ => return Err("Destination is required. Usage: qms project export <destination> [--project <path>]".to_string()),
+    };
+    let project_path = match find_option(args, "--project") {
+        Some(path) => PathBuf::from(path),
+        None => current_project()?,
+    };
+    if !encryption::is_encrypted(&project_path) {
+        return Err(format!("{} is not an encrypted project", project_path.display()));
+    }
+
+    let source = current_key_source(args)?;
+    let decrypted = encryption::export_plaintext(&project_path, &source, &destination)
+        .map_err(|e| format!("Export failed: {e}"))?;
+
+    let _ = audit_log_action(
+        "PROJECT_EXPORTED",
+        "Project",
+        &format!("Decrypted copy written to {}", destination.display()),
+    );
+    println!("✅ Decrypted copy of {} written to {}", project_path.display(), destination.display());
+    println!("   {decrypted} files and records decrypted. The copy is NOT encrypted; store it securely.");
+    Ok(())
+}
+
+fn handle_project_decrypt(args: &[String]) -> Result<(), String> {
+    let project_path = current_project()?;
+    if !encryption::is_encrypted(&project_path) {
+        println!("Project data is not encrypted");
+        return Ok(());
+    }
+
+    let source = current_key_source(args)?;
+    let opened = encryption::disable(&project_path, &source).map_err(|e| format!("Decryption failed: {e}"))?;
+
+    let _ = audit_log_action("PROJECT_DECRYPTED", "Project", &format!("{opened} records decrypted"));
+    println!("✅ Project data decrypted ({opened} files and records) and key ring removed");
+    Ok(())
+}
+
+fn current_project() -> Result<PathBuf, String> {
+    get_current_project_path().map_err(|e| format!("Failed to get project path: {e}"))
+}
+
+/// Secret unlocking the current key: `--passphrase`, `--key-file`, the environment or a prompt
+fn current_key_source(args: &[String]) -> Result<KeySource, String> {
+    if let Some(path) = find_option(args, "--key-file") {
+        return Ok(KeySource::KeyFile(PathBuf::from(path)));
+    }
+    if let Some(passphrase) = find_option(args, "--passphrase") {
+        return Ok(KeySource::Passphrase(passphrase.to_string()));
+    }
+    if let Some(source) = KeySource::from_env() {
+        return Ok(source);
+    }
+    let passphrase = prompt("Current project passphrase: ").map_err(|e| e.to_string())?;
+    Ok(KeySource::Passphrase(passphrase))
+}
+
+/// Secrets to wrap a new key under; prompts for a passphrase if none is given
+///
+/// A key file that does not exist yet is generated.
+fn new_key_sources(args: &[String], passphrase_flag: &str, key_file_flag: &str) -> Result<Vec<KeySource>, String> {
+    let mut sources = Vec::new();
+    if let Some(passphrase) = find_option(args, passphrase_flag) {
+        sources.push(KeySource::Passphrase(passphrase.to_string()));
+    }
+    if let Some(path) = find_option(args, key_file_flag) {
+        let path = Path::new(path);
+        let source = if path.exists() {
+            KeySource::KeyFile(path.to_path_buf())
+        } else {
+            KeySource::generate_key_file(path).map_err(|e| e.to_string())?
+        };
+        sources.push(source);
+    }
+    if sources.is_empty() {
+        let passphrase = prompt("New project passphrase: ").map_err(|e| e.to_string())?;
+        let confirmation = prompt("Repeat passphrase: ").map_err(|e| e.to_string())?;
+        if passphrase != confirmation {
+            return Err("Passphrases do not match".to_string());
+        }
+        sources.push(KeySource::Passphrase(passphrase));
+    }
+    Ok(sources)
+}
+
+fn prompt(label: &str) -> io::Result<String> {
+    print!("{label}");
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn print_key_reminder(sources: &[KeySource]) {
+    for source in sources {
+        if let KeySource::KeyFile(path) = source {
+            println!("   Key file: {} (keep a copy off this machine)", path.display());
+        }
+    }
+    println!("   Without the passphrase or key file the project data cannot be recovered.");
+    println!("   Set {PASSPHRASE_ENV} or {KEY_FILE_ENV} to unlock non-interactive runs.");
+}
+
+fn print_project_help() {
+    println!("Protect project data at rest\n");
+    println!("USAGE:");
+    println!("    qms project <COMMAND>\n");
+    println!("COMMANDS:");
+    println!("    status                                  Show whether project data is encrypted");
+    println!("    encrypt [--passphrase <p>] [--key-file <path>]");
+    println!("                                            Encrypt all project data except the project");
+    println!("                                            file, config, templates, exports and reports");
+    println!("    rekey [--passphrase <p> | --key-file <path>]");
+    println!("          [--new-passphrase <p>] [--new-key-file <path>]");
+    println!("                                            Re-encrypt all data under a new project key");
+    println!("    export <destination> [--project <path>] [--passphrase <p> | --key-file <path>]");
+    println!("                                            Write a decrypted copy for disaster recovery");
+    println!("    decrypt [--passphrase <p> | --key-file <path>]");
+    println!("                                            Decrypt project data in place");
+    println!("    help                                    Show this help message\n");
+    println!("The project key is wrapped by a passphrase and/or key file and kept in");
+    println!("config/encryption.json. A key file that does not exist is generated.");
+    println!("Encryption requires encryption_enabled in the QMS configuration.");
+}
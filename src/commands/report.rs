@@ -1,5 +1,11 @@
+use crate::modules::attachments::AttachmentManager;
+use crate::modules::baseline::BaselineManager;
 use crate::modules::report_generator::{DHFReportGenerator, RiskReportGenerator, AuditReportGenerator, ReportConfig, TimeoutExecutor};
+use crate::commands::daemon::{add_schedule, print_schedules};
+use crate::modules::scheduler::{load_schedules, CronSchedule, JobKind, ScheduledJob, REPORT_KINDS};
 use crate::utils::get_current_project_path;
+use crate::utils::user_context::get_current_username;
+use std::collections::HashMap;
 use std::process;
 use std::time::Duration;
 
@@ -34,6 +40,8 @@ pub fn handle_report_command(args: &[String]) -> Result<(), String> {
 fn handle_dhf_report(args: &[String]) -> Result<(), String> {
     let mut format = "md";
     let mut output_path = None;
+    let mut attachments_dir = None;
+    let mut baseline_name = None;
     let mut timeout_secs = 30u64; // Default 30 seconds
     let mut i = 0;
     
@@ -55,6 +63,22 @@ fn handle_dhf_report(args: &[String]) -> Result<(), String> {
                     return Err("--output requires a value".to_string());
                 }
             }
+            "--baseline" => {
+                if i + 1 < args.len() {
+                    baseline_name = Some(args[i + 1].as_str());
+                    i += 2;
+                } else {
+                    return Err("--baseline requires a baseline name".to_string());
+                }
+            }
+            "--attachments" => {
+                if i + 1 < args.len() {
+                    attachments_dir = Some(args[i + 1].as_str());
+                    i += 2;
+                } else {
+                    return Err("--attachments requires a directory".to_string());
+                }
+            }
             "--timeout" => {
                 if i + 1 < args.len() {
                     timeout_secs = args[i + 1].parse()
@@ -83,7 +107,13 @@ fn handle_dhf_report(args: &[String]) -> Result<(), String> {
     
     println!("⏳ Generating DHF report (timeout: {timeout_secs}s)...");
     
-    let generator = DHFReportGenerator::new(&project_path);
+    let generator = match baseline_name {
+        Some(name) => BaselineManager::new(&project_path)
+            .and_then(|manager| manager.load(name))
+            .and_then(|baseline| DHFReportGenerator::as_of_baseline(&project_path, baseline))
+            .map_err(|e| format!("Failed to load baseline: {e}"))?,
+        None => DHFReportGenerator::new(&project_path),
+    };
     
     let result = executor.execute_with_timeout(|| {
         generator.generate_report(format, output_path)
@@ -99,10 +129,20 @@ fn handle_dhf_report(args: &[String]) -> Result<(), String> {
             } else {
                 println!("{report}");
             }
-            Ok(())
         }
-        Err(e) => Err(format!("Failed to generate DHF report: {e}")),
+        Err(e) => return Err(format!("Failed to generate DHF report: {e}")),
     }
+
+    // Evidence files referenced by the report, with a checksum manifest
+    if let Some(dir) = attachments_dir {
+        let exported = AttachmentManager::new(&project_path)
+            .and_then(|manager| manager.export(std::path::Path::new(dir)))
+            .map_err(|e| format!("Failed to export attachments: {e}"))?;
+        if output_path.is_some() {
+            println!("📎 Exported {exported} attachment(s) to: {dir}");
+        }
+    }
+    Ok(())
 }
 
 /// Handle Risk report generation
@@ -289,8 +329,55 @@ fn handle_report_export(args: &[String]) -> Result<(), String> {
 }
 
 fn handle_report_schedule(args: &[String]) -> Result<(), String> {
-    println!("Report schedule command - implementation pending");
-    println!("Args: {args:?}");
+    let project_path = get_current_project_path()
+        .map_err(|e| format!("Failed to get project path: {e}"))?;
+
+    let report = match args.first().map(String::as_str) {
+        Some("--help" | "-h") | None => {
+            print_schedule_help();
+            return Ok(());
+        }
+        Some("list") => {
+            let schedules = load_schedules(&project_path)
+                .map_err(|e| format!("Failed to load schedules: {e}"))?;
+            let reports: Vec<ScheduledJob> = schedules
+                .into_iter()
+                .filter(|job| matches!(job.job, JobKind::Report { .. }))
+                .collect();
+            print_schedules(&reports);
+            return Ok(());
+        }
+        Some(report) if REPORT_KINDS.contains(&report) => report,
+        Some(other) => return Err(format!("Unknown report '{other}'. Expected one of: {}", REPORT_KINDS.join(", "))),
+    };
+    let expression = args.get(1).ok_or("Cron expression is required, e.g. \"0 6 * * 1\"")?;
+
+    let mut format = "md";
+    let mut name = None;
+    let mut i = 2;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--format" => {
+                format = args.get(i + 1).ok_or("--format requires a value")?;
+                i += 2;
+            }
+            "--name" => {
+                name = Some(args.get(i + 1).ok_or("--name requires a value")?.clone());
+                i += 2;
+            }
+            other => return Err(format!("Unknown option: {other}")),
+        }
+    }
+
+    let schedule = CronSchedule::parse(expression).map_err(|e| e.to_string())?;
+    let mut options = HashMap::new();
+    options.insert("report".to_string(), report.to_string());
+    options.insert("format".to_string(), format.to_string());
+    let job = JobKind::from_parts("report", &options).map_err(|e| e.to_string())?;
+    let name = name.unwrap_or_else(|| format!("{report}-report"));
+    let job = ScheduledJob::new(&name, schedule, job, &get_current_username()).map_err(|e| e.to_string())?;
+    add_schedule(&project_path, job)?;
+    println!("📄 Reports are written to reports/scheduled/ by 'qms daemon run' or 'qms serve'");
     Ok(())
 }
 
@@ -306,12 +393,28 @@ fn print_report_help() {
     println!("    list       List available reports");
     println!("    view       View generated report");
     println!("    export     Export report to file");
-    println!("    schedule   Schedule automatic reports");
+    println!("    schedule   Schedule recurring report generation");
     println!("    help       Show this help message\n");
     println!("For more information on a specific command, use:");
     println!("    qms report <COMMAND> --help");
 }
 
+fn print_schedule_help() {
+    println!("Schedule recurring report generation\n");
+    println!("USAGE:");
+    println!("    qms report schedule <dhf|risks|audit> \"<cron>\" [OPTIONS]");
+    println!("    qms report schedule list\n");
+    println!("OPTIONS:");
+    println!("    --format <FORMAT>    Report format: md, csv, json, pdf, html [default: md]");
+    println!("    --name <NAME>        Schedule name [default: <report>-report]");
+    println!("    --help, -h           Show this help message\n");
+    println!("Cron expressions use five UTC fields or @daily, @weekly, @monthly.");
+    println!("Manage schedules and view run history with 'qms daemon'.\n");
+    println!("EXAMPLES:");
+    println!("    qms report schedule dhf \"0 6 * * 1\" --format pdf");
+    println!("    qms report schedule audit @monthly --name monthly-audit");
+}
+
 fn print_dhf_help() {
     println!("Generate Design History File (DHF) report\n");
     println!("USAGE:");
@@ -319,10 +422,14 @@ fn print_dhf_help() {
     println!("OPTIONS:");
     println!("    --format <FORMAT>    Report format: md, csv, json [default: md]");
     println!("    --output <FILE>      Output file path [default: stdout]");
+    println!("    --attachments <DIR>  Also export attached evidence files with SHA256SUMS");
+    println!("    --baseline <NAME>    Report the design record as frozen in a baseline");
     println!("    --timeout <SECS>     Timeout in seconds [default: 30]");
     println!("    --help, -h           Show this help message\n");
     println!("EXAMPLES:");
     println!("    qms report dhf --format md --output dhf_report.md");
+    println!("    qms report dhf --baseline R2.1 --output dhf_r2.1.md");
+    println!("    qms report dhf --output dhf_report.md --attachments dhf_evidence");
     println!("    qms report dhf --format csv --output dhf_report.csv --timeout 60");
     println!("    qms report dhf --format json");
 }
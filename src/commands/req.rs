@@ -10,6 +10,7 @@
  */
 
 use crate::modules::traceability::requirement::{RequirementManager, RequirementCategory, RequirementPriority, RequirementStatus, VerificationMethod, RequirementUpdate};
+use crate::modules::traceability::reqif::{self, ReqIfMapping};
 
 pub fn handle_req_command(args: &[String]) -> Result<(), String> {
     if args.len() < 3 {
@@ -26,6 +27,8 @@ pub fn handle_req_command(args: &[String]) -> Result<(), String> {
         "delete" => handle_req_delete(&args[3..]),
         "verify" => handle_req_verify(&args[3..]),
         "verification-report" => handle_req_verification_report(&args[3..]),
+        "import" => handle_req_import(&args[3..]),
+        "export" => handle_req_export(&args[3..]),
         "--help" | "-h" => {
             print_req_help();
             Ok(())
@@ -569,6 +572,8 @@ fn print_req_help() {
     println!("    show       Show requirement details");
     println!("    update     Update a requirement");
     println!("    delete     Delete a requirement");
+    println!("    import     Import requirements and relations from ReqIF");
+    println!("    export     Export requirements and relations as ReqIF");
     println!("    help       Show this help message\n");
     println!("For more information on a specific command, use:");
     println!("    qms req <COMMAND> --help");
@@ -830,3 +835,128 @@ fn print_req_delete_help() {
     println!("    qms req delete REQ-001 --confirm");
     println!("    qms req delete REQ-999 --confirm");
 }
+
+/// Parse `<FILE> [--mapping <FILE>]` shared by import and export
+fn parse_reqif_args(args: &[String]) -> Result<(String, ReqIfMapping), String> {
+    let mut file = String::new();
+    let mut mapping = ReqIfMapping::default();
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--mapping" => {
+                if i + 1 < args.len() {
+                    mapping = ReqIfMapping::load(std::path::Path::new(&args[i + 1]))
+                        .map_err(|e| format!("Failed to load mapping file '{}': {e}", args[i + 1]))?;
+                    i += 2;
+                } else {
+                    return Err("--mapping requires a value".to_string());
+                }
+            }
+            arg if arg.starts_with("--") => {
+                return Err(format!("Unknown option: {arg}"));
+            }
+            _ => {
+                if file.is_empty() {
+                    file = args[i].clone();
+                }
+                i += 1;
+            }
+        }
+    }
+
+    if file.is_empty() {
+        return Err("ReqIF file is required".to_string());
+    }
+    Ok((file, mapping))
+}
+
+fn handle_req_import(args: &[String]) -> Result<(), String> {
+    if args.is_empty() || args[0] == "--help" || args[0] == "-h" {
+        print_req_import_help();
+        return Ok(());
+    }
+    let (file, mapping) = parse_reqif_args(args)?;
+
+    let content = std::fs::read_to_string(&file)
+        .map_err(|e| format!("Failed to read '{file}': {e}"))?;
+    let project_path = std::env::current_dir()
+        .map_err(|e| format!("Failed to get current directory: {e}"))?;
+
+    println!("📥 Importing ReqIF from '{file}'...");
+    let stats = reqif::import_reqif(&project_path, &content, &mapping)
+        .map_err(|e| format!("ReqIF import failed: {e}"))?;
+
+    println!("✅ Import completed");
+    println!("   📄 Spec objects: {}", stats.spec_objects);
+    println!("   ➕ Requirements created: {}", stats.created.len());
+    println!("   🔄 Requirements updated: {}", stats.updated.len());
+    println!("   🔗 Trace links created: {} ({} already present)", stats.links_created, stats.links_existing);
+
+    if !stats.unmapped_attributes.is_empty() {
+        let unmapped: Vec<&str> = stats.unmapped_attributes.iter().map(String::as_str).collect();
+        println!("   ⚠️  Unmapped attributes (add them to a --mapping file): {}", unmapped.join(", "));
+    }
+    if !stats.errors.is_empty() {
+        println!("   ❌ Errors: {}", stats.errors.len());
+        for error in &stats.errors {
+            println!("      {error}");
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_req_export(args: &[String]) -> Result<(), String> {
+    if args.is_empty() || args[0] == "--help" || args[0] == "-h" {
+        print_req_export_help();
+        return Ok(());
+    }
+    let (file, mapping) = parse_reqif_args(args)?;
+
+    let project_path = std::env::current_dir()
+        .map_err(|e| format!("Failed to get current directory: {e}"))?;
+    let export = reqif::export_reqif(&project_path, &mapping)
+        .map_err(|e| format!("ReqIF export failed: {e}"))?;
+    std::fs::write(&file, &export.document)
+        .map_err(|e| format!("Failed to write '{file}': {e}"))?;
+
+    println!("✅ Exported {} requirement(s) and {} relation(s) to '{file}'", export.requirements, export.relations);
+    Ok(())
+}
+
+fn print_req_import_help() {
+    println!("Import requirements from a ReqIF 1.2 file\n");
+    println!("USAGE:");
+    println!("    qms req import <FILE> [--mapping <FILE>]\n");
+    println!("Spec objects become requirements and spec relations between them become trace links.");
+    println!("Re-importing a file updates the requirements created from it.\n");
+    println!("OPTIONS:");
+    println!("    --mapping <FILE>    JSON mapping of custom attributes, enumeration values and relation types");
+    println!("    --help              Show this help message\n");
+    println!("MAPPING FILE:");
+    println!("    {{");
+    println!("      \"attributes\": {{ \"Safety Class\": \"priority\" }},");
+    println!("      \"values\": {{ \"priority\": {{ \"Class C\": \"critical\" }} }},");
+    println!("      \"relations\": {{ \"satisfies\": \"derived_from\" }}");
+    println!("    }}\n");
+    println!("FIELDS:");
+    println!("    req_id, title, description, category, priority, verification_method,");
+    println!("    status, source, rationale, acceptance_criteria\n");
+    println!("EXAMPLES:");
+    println!("    qms req import supplier.reqif");
+    println!("    qms req import doors_export.reqif --mapping doors_mapping.json");
+}
+
+fn print_req_export_help() {
+    println!("Export requirements as a ReqIF 1.2 file\n");
+    println!("USAGE:");
+    println!("    qms req export <FILE> [--mapping <FILE>]\n");
+    println!("Requirements imported from ReqIF keep their original identifiers.\n");
+    println!("OPTIONS:");
+    println!("    --mapping <FILE>    Attribute names and value labels to export with (see qms req import --help)");
+    println!("    --help              Show this help message\n");
+    println!("EXAMPLES:");
+    println!("    qms req export requirements.reqif");
+    println!("    qms req export to_doors.reqif --mapping doors_mapping.json");
+}
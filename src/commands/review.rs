@@ -0,0 +1,416 @@
+/*
+ * QMS (Quality Management System)
+ * Design Review Command Handler
+ *
+ * CLI command handlers for formal design reviews
+ * (FDA 21 CFR 820.30(e), ISO 13485:2016 7.3.5)
+ */
+
+use crate::commands::args::{option_value, positional_arg};
+use crate::modules::design_review::{
+    DesignReview, DesignReviewManager, NewDesignReview, ReviewOutcome, ReviewPhase, ReviewStatus, ScopeKind,
+};
+use crate::utils::get_current_project_path;
+use crate::utils::user_context::get_current_user_id;
+
+pub fn handle_review_command(args: &[String]) -> Result<(), String> {
+    if args.len() < 3 {
+        print_review_help();
+        return Ok(());
+    }
+
+    match args[2].as_str() {
+        "create" => handle_review_create(&args[3..]),
+        "list" => handle_review_list(&args[3..]),
+        "show" => handle_review_show(&args[3..]),
+        "scope" => handle_review_scope(&args[3..]),
+        "attendee" => handle_review_attendee(&args[3..]),
+        "minutes" => handle_review_minutes(&args[3..]),
+        "action" => handle_review_action(&args[3..]),
+        "close-action" => handle_review_close_action(&args[3..]),
+        "sign" => handle_review_sign(&args[3..]),
+        "cancel" => handle_review_cancel(&args[3..]),
+        "--help" | "-h" | "help" => {
+            print_review_help();
+            Ok(())
+        }
+        _ => {
+            eprintln!("Error: Unknown review command '{}'", args[2]);
+            print_review_help();
+            Err(format!("Unknown review command '{}'", args[2]))
+        }
+    }
+}
+
+fn review_manager() -> Result<DesignReviewManager, String> {
+    let project_path = get_current_project_path()
+        .map_err(|e| format!("Failed to get project path: {e}"))?;
+    DesignReviewManager::new(&project_path).map_err(|e| format!("Failed to initialize design reviews: {e}"))
+}
+
+/// Comma-separated list option
+fn id_list(value: &str) -> Vec<String> {
+    value.split(',').map(str::trim).filter(|id| !id.is_empty()).map(str::to_string).collect()
+}
+
+fn handle_review_create(args: &[String]) -> Result<(), String> {
+    let usage = "qms review create --title <TITLE> --phase <PHASE> [--date <YYYY-MM-DD>]";
+    let mut title = String::new();
+    let mut phase = None;
+    let mut scheduled_date = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--title" => title = option_value(args, i)?,
+            "--phase" => phase = Some(ReviewPhase::parse(&option_value(args, i)?).map_err(|e| e.to_string())?),
+            "--date" => scheduled_date = Some(option_value(args, i)?),
+            "--help" | "-h" => {
+                println!("USAGE:\n    {usage}");
+                println!("\nPHASES:\n    design-input, design-output, verification, validation, transfer, or any other name");
+                return Ok(());
+            }
+            _ => return Err(format!("Unknown option: {}. Usage: {usage}", args[i])),
+        }
+        i += 2;
+    }
+
+    let review = NewDesignReview {
+        title,
+        phase: phase.ok_or_else(|| format!("--phase is required. Usage: {usage}"))?,
+        scheduled_date,
+    };
+    let record = review_manager()?
+        .create_review(review, &get_current_user_id())
+        .map_err(|e| format!("Failed to create design review: {e}"))?;
+    println!("✅ Design review created");
+    println!("🆔 ID: {}", record.id);
+    println!("\nNext: qms review scope {}", record.id);
+    Ok(())
+}
+
+fn handle_review_list(args: &[String]) -> Result<(), String> {
+    let mut status = None;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--status" => status = Some(ReviewStatus::parse(&option_value(args, i)?).map_err(|e| e.to_string())?),
+            "--help" | "-h" => {
+                println!("USAGE:\n    qms review list [--status <STATUS>]");
+                return Ok(());
+            }
+            _ => return Err(format!("Unknown option: {}", args[i])),
+        }
+        i += 2;
+    }
+
+    let records = review_manager()?
+        .list_reviews(status.as_ref())
+        .map_err(|e| format!("Failed to list design reviews: {e}"))?;
+
+    if records.is_empty() {
+        println!("No design reviews found");
+        return Ok(());
+    }
+
+    println!("{:<8} {:<20} {:<16} {:<8} Title", "ID", "Status", "Phase", "Actions");
+    println!("{:-<80}", "");
+    for record in &records {
+        println!(
+            "{:<8} {:<20} {:<16} {:<8} {}",
+            record.id,
+            record.status.display_name(),
+            record.phase.display_name(),
+            format!("{}/{}", record.open_action_items().len(), record.action_items.len()),
+            record.title
+        );
+    }
+    println!("\nTotal: {} design review(s)", records.len());
+    Ok(())
+}
+
+fn handle_review_show(args: &[String]) -> Result<(), String> {
+    let review_id = positional_arg(args, "Design review ID", "qms review show <DR-ID>")?;
+    let manager = review_manager()?;
+    let record = manager.load_review(&review_id).map_err(|e| e.to_string())?;
+    print_review_details(&record);
+
+    if !record.signatures.is_empty() {
+        let verifications = manager.verify_signatures(&review_id).map_err(|e| e.to_string())?;
+        println!("\n✍️  Signatures:");
+        for signature in &record.signatures {
+            let valid = verifications.iter().any(|(user, v)| user == &signature.user_id && v.is_valid);
+            println!("   {:<16} {} ({})", signature.user_id, signature.signed_at,
+                if valid { "✅ Valid" } else { "❌ Invalid" });
+        }
+    }
+    let outstanding = record.outstanding_signers();
+    if record.status == ReviewStatus::Signing && !outstanding.is_empty() {
+        println!("   Awaiting: {}", outstanding.join(", "));
+    }
+    Ok(())
+}
+
+fn print_review_details(record: &DesignReview) {
+    println!("🧭 {} - {}", record.id, record.title);
+    println!("{:-<60}", "");
+    println!("Status:      {}", record.status.display_name());
+    println!("Phase:       {}", record.phase.display_name());
+    if let Some(ref date) = record.scheduled_date {
+        println!("Scheduled:   {date}");
+    }
+    println!("Created:     {} by {}", record.created_at, record.created_by);
+    if let Some(ref at) = record.finalized_at {
+        println!("Finalized:   {at}");
+    }
+    if let Some(ref reason) = record.cancellation_reason {
+        println!("Cancelled:   {reason}");
+    }
+
+    match record.scope {
+        Some(ref scope) => {
+            println!("\n📦 Scope (captured {} by {}):", scope.captured_at, scope.captured_by);
+            for kind in [ScopeKind::Document, ScopeKind::Requirement, ScopeKind::Risk] {
+                for item in scope.items_of(kind) {
+                    println!("   {:<12} {:<14} {:<22} {}", kind.name(), item.id, item.revision, item.title);
+                }
+            }
+        }
+        None => println!("\n📦 Scope: not captured"),
+    }
+
+    if !record.attendees.is_empty() {
+        println!("\n👥 Attendees:");
+        for attendee in &record.attendees {
+            println!("   {:<16} {}{}", attendee.user_id, attendee.role,
+                if attendee.independent { " (independent)" } else { "" });
+        }
+        if !record.has_independent_reviewer() {
+            println!("   ⚠️  No independent reviewer yet");
+        }
+    }
+
+    if let Some(ref outcome) = record.outcome {
+        println!("\n📝 Outcome: {}", outcome.display_name());
+        for line in record.minutes.lines() {
+            println!("   {line}");
+        }
+    }
+
+    if !record.action_items.is_empty() {
+        println!("\n📌 Action items:");
+        for item in &record.action_items {
+            let status = match (&item.closed_at, &item.resolution) {
+                (Some(at), Some(resolution)) => format!("Closed {at}: {resolution}"),
+                _ => format!("Open{}", item.due_date.as_ref().map(|d| format!(", due {d}")).unwrap_or_default()),
+            };
+            println!("   {} [{}] {} - {status}", item.id, item.owner, item.description);
+        }
+    }
+}
+
+fn handle_review_scope(args: &[String]) -> Result<(), String> {
+    let usage = "qms review scope <DR-ID> [--docs <ID>[,<ID>...]] [--reqs <REQ-ID>[,<REQ-ID>...]]";
+    let review_id = positional_arg(args, "Design review ID", usage)?;
+    let mut documents = Vec::new();
+    let mut requirements = Vec::new();
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--docs" => documents = id_list(&option_value(args, i)?),
+            "--reqs" => requirements = id_list(&option_value(args, i)?),
+            _ => return Err(format!("Unknown option: {}. Usage: {usage}", args[i])),
+        }
+        i += 2;
+    }
+
+    let record = review_manager()?
+        .capture_scope(&review_id, &documents, &requirements, &get_current_user_id())
+        .map_err(|e| format!("Failed to capture review scope: {e}"))?;
+    let scope = record.scope.as_ref().expect("scope was just captured");
+    println!("📦 Scope captured for {}", record.id);
+    println!("   Documents:    {}", scope.items_of(ScopeKind::Document).count());
+    println!("   Requirements: {}", scope.items_of(ScopeKind::Requirement).count());
+    println!("   Risks:        {}", scope.items_of(ScopeKind::Risk).count());
+    Ok(())
+}
+
+fn handle_review_attendee(args: &[String]) -> Result<(), String> {
+    let usage = "qms review attendee <DR-ID> --user <USER> --role <ROLE> [--independent]";
+    let review_id = positional_arg(args, "Design review ID", usage)?;
+    let mut user = None;
+    let mut role = String::new();
+    let mut independent = false;
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--user" => user = Some(option_value(args, i)?),
+            "--role" => role = option_value(args, i)?,
+            "--independent" => {
+                independent = true;
+                i += 1;
+                continue;
+            }
+            _ => return Err(format!("Unknown option: {}. Usage: {usage}", args[i])),
+        }
+        i += 2;
+    }
+
+    let user = user.ok_or_else(|| format!("--user is required. Usage: {usage}"))?;
+    let record = review_manager()?
+        .add_attendee(&review_id, &user, &role, independent)
+        .map_err(|e| format!("Failed to add attendee: {e}"))?;
+    println!("👥 {user} added to {} ({} attendee(s))", record.id, record.attendees.len());
+    Ok(())
+}
+
+fn handle_review_minutes(args: &[String]) -> Result<(), String> {
+    let usage = "qms review minutes <DR-ID> --outcome <OUTCOME> (--text <MINUTES> | --file <PATH>)";
+    let review_id = positional_arg(args, "Design review ID", usage)?;
+    let mut outcome = None;
+    let mut minutes = None;
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--outcome" => outcome = Some(ReviewOutcome::parse(&option_value(args, i)?).map_err(|e| e.to_string())?),
+            "--text" => minutes = Some(option_value(args, i)?),
+            "--file" => {
+                let path = option_value(args, i)?;
+                minutes = Some(std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {path}: {e}"))?);
+            }
+            _ => return Err(format!("Unknown option: {}. Usage: {usage}", args[i])),
+        }
+        i += 2;
+    }
+
+    let outcome = outcome.ok_or_else(|| format!("--outcome is required. Usage: {usage}"))?;
+    let minutes = minutes.ok_or_else(|| format!("--text or --file is required. Usage: {usage}"))?;
+    let record = review_manager()?
+        .record_minutes(&review_id, &minutes, outcome, &get_current_user_id())
+        .map_err(|e| format!("Failed to record minutes: {e}"))?;
+    println!("📝 Minutes recorded for {} ({})", record.id,
+        record.outcome.as_ref().map_or("", ReviewOutcome::display_name));
+    Ok(())
+}
+
+fn handle_review_action(args: &[String]) -> Result<(), String> {
+    let usage = "qms review action <DR-ID> --desc <TEXT> --owner <USER> [--due <YYYY-MM-DD>]";
+    let review_id = positional_arg(args, "Design review ID", usage)?;
+    let mut description = String::new();
+    let mut owner = String::new();
+    let mut due_date = None;
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--desc" | "--description" => description = option_value(args, i)?,
+            "--owner" => owner = option_value(args, i)?,
+            "--due" => due_date = Some(option_value(args, i)?),
+            _ => return Err(format!("Unknown option: {}. Usage: {usage}", args[i])),
+        }
+        i += 2;
+    }
+
+    let record = review_manager()?
+        .add_action_item(&review_id, &description, &owner, due_date.as_deref())
+        .map_err(|e| format!("Failed to add action item: {e}"))?;
+    let item = record.action_items.last().expect("action item was just added");
+    println!("📌 {} raised on {} for {}", item.id, record.id, item.owner);
+    Ok(())
+}
+
+fn handle_review_close_action(args: &[String]) -> Result<(), String> {
+    let usage = "qms review close-action <DR-ID> <AI-ID> --resolution <TEXT>";
+    let review_id = positional_arg(args, "Design review ID", usage)?;
+    let action_id = match args.get(1) {
+        Some(id) if !id.starts_with("--") => id.clone(),
+        _ => return Err(format!("Action item ID is required. Usage: {usage}")),
+    };
+    let resolution = match args.get(2).map(String::as_str) {
+        Some("--resolution") => option_value(args, 2)?,
+        _ => return Err(format!("--resolution is required. Usage: {usage}")),
+    };
+
+    let record = review_manager()?
+        .close_action_item(&review_id, &action_id, &resolution, &get_current_user_id())
+        .map_err(|e| format!("Failed to close action item: {e}"))?;
+    println!("✅ {action_id} closed ({} open action item(s) left on {})", record.open_action_items().len(), record.id);
+    Ok(())
+}
+
+fn handle_review_sign(args: &[String]) -> Result<(), String> {
+    let usage = "qms review sign <DR-ID> [--user <USER>] [--password <PASSWORD>] [--code <CODE>]";
+    let review_id = positional_arg(args, "Design review ID", usage)?;
+    let mut user = get_current_user_id();
+    let mut password = None;
+    let mut code = None;
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--user" => user = option_value(args, i)?,
+            "--password" => password = Some(option_value(args, i)?),
+            "--code" => code = Some(option_value(args, i)?),
+            _ => return Err(format!("Unknown option: {}. Usage: {usage}", args[i])),
+        }
+        i += 2;
+    }
+
+    let password = match password {
+        Some(password) => password,
+        None => crate::commands::audit::prompt_signing_password(&user)?,
+    };
+    let code = crate::commands::audit::signing_code(&user, code)?;
+
+    let record = review_manager()?
+        .sign(&review_id, &user, &password, code.as_deref())
+        .map_err(|e| format!("Failed to sign design review: {e}"))?;
+    println!("✍️  {} signed by {user}", record.id);
+    if record.status == ReviewStatus::Finalized {
+        println!("✅ Minutes finalized - signed by all attendees");
+    } else {
+        println!("⏳ Awaiting: {}", record.outstanding_signers().join(", "));
+    }
+    Ok(())
+}
+
+fn handle_review_cancel(args: &[String]) -> Result<(), String> {
+    let usage = "qms review cancel <DR-ID> --reason <TEXT>";
+    let review_id = positional_arg(args, "Design review ID", usage)?;
+    let reason = match args.get(1).map(String::as_str) {
+        Some("--reason") => option_value(args, 1)?,
+        _ => return Err(format!("--reason is required. Usage: {usage}")),
+    };
+    review_manager()?
+        .cancel(&review_id, &reason, &get_current_user_id())
+        .map_err(|e| format!("Failed to cancel design review: {e}"))?;
+    println!("🚫 {review_id} cancelled");
+    Ok(())
+}
+
+fn print_review_help() {
+    println!("Manage formal design reviews\n");
+    println!("USAGE:");
+    println!("    qms review <COMMAND>\n");
+    println!("COMMANDS:");
+    println!("    create        Plan a design review");
+    println!("    list          List design reviews");
+    println!("    show          Show scope, attendees, minutes, action items and signatures");
+    println!("    scope         Baseline the documents, requirements and risks under review");
+    println!("    attendee      Add an attendee (mark at least one --independent)");
+    println!("    minutes       Record the minutes and outcome");
+    println!("    action        Raise an action item");
+    println!("    close-action  Close an action item with its resolution");
+    println!("    sign          Sign the minutes as an attendee");
+    println!("    cancel        Cancel a review that is not finalized");
+    println!("    help          Show this help message\n");
+    println!("WORKFLOW:");
+    println!("    Draft → Awaiting Signatures → Finalized\n");
+    println!("The first signature freezes scope, attendees, minutes and action items.");
+    println!("Action items can still be closed after the minutes are finalized.\n");
+    println!("OUTCOMES:");
+    println!("    approved, approved-with-actions, not-approved");
+}
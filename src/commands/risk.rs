@@ -15,7 +15,7 @@ use crate::modules::risk_manager::{
 
 // Risk assessment and scoring
 use crate::modules::risk_manager::{
-    RiskMatrix, RiskScoring, FMEAManager
+    RiskMatrix, RiskScoring, FMEAManager, RiskPolicy
 };
 
 // Reporting and analytics
@@ -26,7 +26,7 @@ use crate::modules::risk_manager::{
 
 // Compliance and validation
 use crate::modules::risk_manager::{
-    ISO14971Validator, RMFOptions, RMFFormat, ComplianceStatus
+    ISO14971Validator, RMFGenerator, RMFOptions, RMFFormat, ComplianceStatus
 };
 
 // Import/Export functionality
@@ -44,6 +44,7 @@ use crate::modules::risk_manager::{
 
 // Approval workflow
 use crate::modules::risk_manager::approval::ApprovalDecision;
+use crate::modules::baseline::BaselineManager;
 use std::process;
 use std::path::Path;
 
@@ -62,6 +63,7 @@ pub fn handle_risk_command(args: &[String]) -> Result<(), String> {
         "assess" => handle_risk_assess(&args[3..]),
         "matrix" => handle_risk_matrix(&args[3..]),
         "position" => handle_risk_position(&args[3..]),
+        "policy" => handle_risk_policy(&args[3..]),
         "mitigate" => handle_risk_mitigate(&args[3..]),
         "list-mitigations" => handle_list_mitigations(&args[3..]),
         "verify-mitigation" => handle_verify_mitigation(&args[3..]),
@@ -323,6 +325,9 @@ fn handle_risk_view(args: &[String]) -> Result<(), String> {
     println!("Detectability: {:?} ({})", risk.detectability, risk.detectability.clone() as u8);
     println!("RPN: {}", risk.risk_priority_number);
     println!("Risk Level: {:?}", risk.initial_risk_level);
+    if !risk.policy_version.is_empty() {
+        println!("Risk Policy: v{}", risk.policy_version);
+    }
     println!("\n📉 Residual Risk (Post-Mitigation):");
     println!("Severity: {:?} ({})", risk.residual_severity, risk.residual_severity.clone() as u8);
     println!("Occurrence: {:?} ({})", risk.residual_occurrence, risk.residual_occurrence.clone() as u8);
@@ -454,7 +459,7 @@ fn handle_risk_assess(args: &[String]) -> Result<(), String> {
     
     println!("\n📈 Risk Calculation Results:");
     println!("RPN (Risk Priority Number): {}", updated_risk.risk_priority_number);
-    println!("Risk Level: {:?}", updated_risk.initial_risk_level);
+    println!("Risk Level: {:?} (risk policy v{})", updated_risk.initial_risk_level, updated_risk.policy_version);
     
     // Show risk level interpretation
     match updated_risk.initial_risk_level {
@@ -1369,6 +1374,69 @@ fn handle_risk_position(args: &[String]) -> Result<(), String> {
     Ok(())
 }
 
+fn handle_risk_policy(args: &[String]) -> Result<(), String> {
+    let project_path = get_current_project_path().map_err(|e| format!("Failed to get project path: {e}"))?;
+    let policy_path = RiskPolicy::policy_path(&project_path);
+
+    match args.first().map(String::as_str) {
+        None | Some("show") => {
+            let policy = RiskPolicy::load(&project_path).map_err(|e| e.to_string())?;
+            println!("📐 Risk Acceptability Policy");
+            println!("Name: {}", policy.name);
+            println!("Version: {}", policy.version);
+            if policy_path.exists() {
+                println!("File: {}", policy_path.display());
+            } else {
+                println!("File: none (built-in policy; run 'qms risk policy init' to customize)");
+            }
+            println!("Criteria: {}", policy.criteria_summary());
+            println!("Severity scale: {}", policy.severity_scale.join(", "));
+            println!("Probability scale: {}", policy.probability_scale.join(", "));
+            println!("Detectability scale: {}", policy.detectability_scale.join(", "));
+            println!("\n{}", RiskMatrix::with_policy(policy).generate_ascii_matrix());
+            Ok(())
+        }
+        Some("init") => {
+            if policy_path.exists() && !args.iter().any(|a| a == "--force") {
+                return Err(format!("{} already exists (use --force to overwrite)", policy_path.display()));
+            }
+            RiskPolicy::default().save(&project_path).map_err(|e| e.to_string())?;
+            let _ = crate::modules::audit_logger::functions::audit_log_action(
+                "RISK_POLICY_INITIALIZED",
+                "RiskPolicy",
+                crate::modules::risk_manager::policy::DEFAULT_POLICY_VERSION,
+            );
+            println!("✅ Risk policy written to {}", policy_path.display());
+            println!("   Edit the file and raise \"version\" when the acceptability criteria change.");
+            Ok(())
+        }
+        Some("--help") | Some("-h") | Some("help") => {
+            print_policy_help();
+            Ok(())
+        }
+        Some(other) => {
+            print_policy_help();
+            Err(format!("Unknown policy command '{other}'"))
+        }
+    }
+}
+
+fn print_policy_help() {
+    println!("📐 Risk Acceptability Policy (ISO 14971 Section 4.4)\n");
+    println!("USAGE:");
+    println!("    qms risk policy [show]        Show the active policy and its matrix");
+    println!("    qms risk policy init [--force] Write the built-in policy to config/risk_policy.json\n");
+    println!("POLICY FILE FIELDS:");
+    println!("    version                 Recorded on every risk assessment");
+    println!("    severity_scale          2-5 severity labels, lowest first");
+    println!("    probability_scale       2-5 probability labels, lowest first");
+    println!("    detectability_scale     5 detectability labels, best detection first");
+    println!("    matrix                  Acceptable/ALARP/Unacceptable per severity × probability cell");
+    println!("    use_detectability       Classify by RPN thresholds instead of the matrix");
+    println!("    alarp_threshold         Lowest ALARP RPN when detectability is used");
+    println!("    unacceptable_threshold  Lowest Unacceptable RPN when detectability is used");
+}
+
 fn print_matrix_help() {
     println!("📊 Risk Matrix Commands - ISO 14971 Compliant\n");
     println!("USAGE:");
@@ -1386,9 +1454,9 @@ fn print_matrix_help() {
     println!("    qms risk matrix --stats");
     println!("    qms risk matrix --export csv --output risk_matrix.csv\n");
     println!("MATRIX FEATURES:");
-    println!("• 5×5 Severity × Occurrence grid");
+    println!("• Severity × Occurrence grid from the project risk policy (5×5 by default)");
     println!("• Color-coded risk levels (🟢🟡🔴)");
-    println!("• ISO 14971 compliant thresholds");
+    println!("• Acceptability criteria from config/risk_policy.json");
     println!("• RPN calculations with detectability factor");
     println!("• Medical device context explanations");
 }
@@ -1984,6 +2052,7 @@ fn print_risk_help() {
     println!("    assess            Perform risk assessment");
     println!("    matrix            Display ISO 14971 risk matrix");
     println!("    position          Show risk position on matrix");
+    println!("    policy            Show or initialize the risk acceptability policy");
     println!("    mitigate          Add risk mitigation measures");
     println!("    list-mitigations  List mitigation measures for a risk");
     println!("    verify-mitigation Verify mitigation effectiveness");
@@ -2761,6 +2830,7 @@ fn handle_generate_rmf(args: &[String]) -> Result<(), String> {
     let mut include_fmea = true;
     let mut include_verification = true;
     let mut jurisdiction = "FDA".to_string();
+    let mut baseline_name: Option<String> = None;
     
     // Parse arguments
     let mut i = 0;
@@ -2805,6 +2875,14 @@ fn handle_generate_rmf(args: &[String]) -> Result<(), String> {
                     return Err("Error: --jurisdiction requires a jurisdiction".to_string());
                 }
             }
+            "--baseline" | "-b" => {
+                if i + 1 < args.len() {
+                    baseline_name = Some(args[i + 1].clone());
+                    i += 1;
+                } else {
+                    return Err("Error: --baseline requires a baseline name".to_string());
+                }
+            }
             "--help" | "-h" => {
                 print_generate_rmf_help();
                 return Ok(());
@@ -2832,8 +2910,22 @@ fn handle_generate_rmf(args: &[String]) -> Result<(), String> {
         let _ = std::fs::create_dir_all(parent);
     }
     
-    validator.generate_rmf(&output_path, &options)
-        .map_err(|e| format!("RMF generation failed: {e}"))?;
+    match baseline_name {
+        Some(name) => {
+            let baseline = BaselineManager::new(&project_path)
+                .and_then(|manager| manager.load(&name))
+                .map_err(|e| format!("Failed to load baseline: {e}"))?;
+            println!("📌 Generating RMF as of baseline {}", baseline.name);
+            RMFGenerator::new(&project_path)
+                .map(|generator| generator.with_baseline(baseline))
+                .and_then(|generator| generator.generate_rmf(&output_path, &options))
+                .map_err(|e| format!("RMF generation failed: {e}"))?;
+        }
+        None => {
+            validator.generate_rmf(&output_path, &options)
+                .map_err(|e| format!("RMF generation failed: {e}"))?;
+        }
+    }
     
     println!("✅ Risk Management File generated successfully");
     println!("📄 Output: {}", output_path.display());
@@ -3187,6 +3279,7 @@ fn print_generate_rmf_help() {
     println!("    --no-detailed           Exclude detailed risk analysis");
     println!("    --no-fmea              Exclude FMEA data");
     println!("    --no-verification      Exclude verification evidence");
+    println!("    -b, --baseline <NAME>  Render the risks as frozen in a baseline");
     println!("    -h, --help             Show this help message");
     println!();
     println!("RMF CONTENTS:");
@@ -3209,6 +3302,7 @@ fn print_generate_rmf_help() {
     println!("    qms risk generate-rmf --format pdf --output medical_device_rmf.pdf");
     println!("    qms risk generate-rmf --jurisdiction CE --no-fmea");
     println!("    qms risk generate-rmf --format html --output rmf_review.html");
+    println!("    qms risk generate-rmf --baseline R2.1 --format pdf --output rmf_r2.1.pdf");
 }
 
 fn print_compliance_gaps_help() {
@@ -0,0 +1,154 @@
+/*
+ * QMS (Quality Management System)
+ * Search Command Handler
+ *
+ * CLI command handlers for full-text search across documents, requirements,
+ * risks and test cases, and for rebuilding the search index
+ */
+
+use crate::modules::search::{reindex, search_project, SearchEntity, SearchQuery};
+use crate::utils::get_current_project_path;
+use std::path::PathBuf;
+
+const DEFAULT_LIMIT: usize = 20;
+
+pub fn handle_search_command(args: &[String]) -> Result<(), String> {
+    if args.len() < 3 {
+        print_search_help();
+        return Ok(());
+    }
+
+    match args[2].as_str() {
+        "reindex" => handle_search_reindex(),
+        "--help" | "-h" | "help" => {
+            print_search_help();
+            Ok(())
+        }
+        _ => handle_search_query(&args[2..]),
+    }
+}
+
+fn handle_search_query(args: &[String]) -> Result<(), String> {
+    let mut terms = Vec::new();
+    let mut entities = Vec::new();
+    let mut limit = DEFAULT_LIMIT;
+    let mut json = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--type" | "-t" => {
+                let value = args.get(i + 1).ok_or("--type requires a value")?;
+                for name in value.split(',') {
+                    entities.push(SearchEntity::parse(name).ok_or_else(|| {
+                        format!("Unknown record type '{name}': use document, requirement, risk or test")
+                    })?);
+                }
+                i += 2;
+            }
+            "--limit" | "-n" => {
+                let value = args.get(i + 1).ok_or("--limit requires a value")?;
+                limit = value.parse().map_err(|_| format!("Invalid limit '{value}'"))?;
+                i += 2;
+            }
+            "--format" => {
+                match args.get(i + 1).map(String::as_str) {
+                    Some("json") => json = true,
+                    Some("table") => json = false,
+                    other => return Err(format!("Unknown format '{}': use table or json", other.unwrap_or(""))),
+                }
+                i += 2;
+            }
+            "--json" => {
+                json = true;
+                i += 1;
+            }
+            other => {
+                terms.push(other.to_string());
+                i += 1;
+            }
+        }
+    }
+
+    let mut query = SearchQuery::parse(&terms.join(" ")).map_err(|e| e.to_string())?;
+    if query.is_empty() && entities.is_empty() {
+        return Err("Search query is empty".to_string());
+    }
+    query.restrict_to(&entities);
+
+    let project_path = current_project()?;
+    let results = search_project(&project_path, &query, limit).map_err(|e| format!("Search failed: {e}"))?;
+
+    if json {
+        println!("{}", results.to_json().json_to_string());
+        return Ok(());
+    }
+    if results.hits.is_empty() {
+        println!("No matches");
+        return Ok(());
+    }
+
+    println!("{:<12} {:<16} {:<50} {:>7}", "Type", "ID", "Title", "Score");
+    println!("{}", "─".repeat(88));
+    for hit in &results.hits {
+        println!(
+            "{:<12} {:<16} {:<50} {:>7.2}",
+            hit.entity.name(),
+            truncate(&hit.id, 16),
+            truncate(&hit.title, 50),
+            hit.score
+        );
+        if !hit.snippet.is_empty() {
+            println!("             {}", hit.snippet);
+        }
+    }
+    println!("\nShowing {} of {} match(es)", results.hits.len(), results.total);
+    Ok(())
+}
+
+fn handle_search_reindex() -> Result<(), String> {
+    let project_path = current_project()?;
+    let count = reindex(&project_path).map_err(|e| format!("Failed to rebuild search index: {e}"))?;
+    println!("✅ Search index rebuilt: {count} record(s) indexed");
+    Ok(())
+}
+
+fn current_project() -> Result<PathBuf, String> {
+    get_current_project_path().map_err(|e| format!("Failed to get project path: {e}"))
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else {
+        let kept: String = text.chars().take(width - 1).collect();
+        format!("{kept}…")
+    }
+}
+
+fn print_search_help() {
+    println!("Search documents, requirements, risks and test cases\n");
+    println!("USAGE:");
+    println!("    qms search <query> [--type <types>] [--limit <n>] [--format table|json]");
+    println!("    qms search reindex\n");
+    println!("OPTIONS:");
+    println!("    --type <types>       Only search these record types (document, requirement, risk, test;");
+    println!("                         comma-separated)");
+    println!("    --limit <n>          Show at most n hits (default {DEFAULT_LIMIT})");
+    println!("    --format json        Print hits as JSON\n");
+    println!("QUERY SYNTAX:");
+    println!("    alarm latency        Records containing every word (alarms, alarming... also match)");
+    println!("    \"alarm latency\"      Words as a phrase");
+    println!("    title:alarm          Word in the title only (body: for the body only)");
+    println!("    type:SRS             Attribute filter; repeat a field to match any of its values");
+    println!("    entity:risk          Record type filter");
+    println!("    -status:draft        Exclude matches\n");
+    println!("    Filterable fields include id, type, status, version, author, tag, standard, category,");
+    println!("    priority, severity, level, source and assignee.\n");
+    println!("The index is updated as records are saved and built on first use; 'reindex'");
+    println!("rebuilds it from scratch.\n");
+    println!("EXAMPLES:");
+    println!("    qms search type:SRS status:approved \"alarm latency\"");
+    println!("    qms search occlusion --type risk,requirement");
+    println!("    qms search tag:safety -status:closed --format json");
+}
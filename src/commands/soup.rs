@@ -0,0 +1,393 @@
+/*
+ * QMS (Quality Management System)
+ * SOUP Command Handler
+ *
+ * CLI command handlers for the SOUP register populated from CycloneDX and
+ * SPDX SBOMs (IEC 62304 Sections 5.3 and 8)
+ */
+
+use crate::commands::args::{is_help, option_value};
+use crate::modules::soup::{AnomalyEvaluation, SbomDiff, SbomFormat, SoupItem, SoupManager};
+use crate::utils::get_current_project_path;
+use crate::utils::user_context::get_current_user_id;
+use std::path::Path;
+
+pub fn handle_soup_command(args: &[String]) -> Result<(), String> {
+    if args.len() < 3 {
+        print_soup_help();
+        return Ok(());
+    }
+
+    match args[2].as_str() {
+        "import" => handle_soup_import(&args[3..]),
+        "list" => handle_soup_list(&args[3..]),
+        "show" => handle_soup_show(&args[3..]),
+        "require" => handle_soup_require(&args[3..]),
+        "link-risk" => handle_soup_link_risk(&args[3..]),
+        "anomaly" => handle_soup_anomaly(&args[3..]),
+        "imports" => handle_soup_imports(&args[3..]),
+        "diff" => handle_soup_diff(&args[3..]),
+        "--help" | "-h" | "help" => {
+            print_soup_help();
+            Ok(())
+        }
+        _ => {
+            eprintln!("Error: Unknown soup command '{}'", args[2]);
+            print_soup_help();
+            Err(format!("Unknown soup command '{}'", args[2]))
+        }
+    }
+}
+
+fn soup_manager() -> Result<SoupManager, String> {
+    let project_path = get_current_project_path()
+        .map_err(|e| format!("Failed to get project path: {e}"))?;
+    SoupManager::new(&project_path).map_err(|e| format!("Failed to initialize SOUP register: {e}"))
+}
+
+fn print_diff(diff: &SbomDiff) {
+    if diff.is_empty() {
+        println!("No SOUP changes between {} and {}", diff.from, diff.to);
+        return;
+    }
+    println!("🔀 SOUP changes {} → {}", diff.from, diff.to);
+    for component in &diff.added {
+        println!("   + {} {}", component.name, component.version);
+    }
+    for component in &diff.removed {
+        println!("   - {} {}", component.name, component.version);
+    }
+    for (old, new) in &diff.changed {
+        println!("   ~ {} {} → {}", old.name, old.version, new.version);
+    }
+    println!(
+        "\n{} added, {} removed, {} version change(s)",
+        diff.added.len(),
+        diff.removed.len(),
+        diff.changed.len()
+    );
+}
+
+fn handle_soup_import(args: &[String]) -> Result<(), String> {
+    let usage = "qms soup import <FILE> [--format cyclonedx|spdx]";
+    if is_help(args) {
+        println!("USAGE:\n    {usage}");
+        println!("\nImports a CycloneDX or SPDX JSON SBOM into the SOUP register. The format is");
+        println!("detected from the document unless --format is given.");
+        return Ok(());
+    }
+
+    let mut file = None;
+    let mut format = None;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--format" => {
+                format = Some(SbomFormat::parse(&option_value(args, i)?).map_err(|e| e.to_string())?);
+                i += 2;
+            }
+            arg if arg.starts_with("--") => return Err(format!("Unknown option: {arg}. Usage: {usage}")),
+            arg if file.is_none() => {
+                file = Some(arg.to_string());
+                i += 1;
+            }
+            arg => return Err(format!("Unexpected argument: {arg}. Usage: {usage}")),
+        }
+    }
+    let file = file.ok_or_else(|| format!("SBOM file is required. Usage: {usage}"))?;
+    let content = std::fs::read_to_string(&file).map_err(|e| format!("Failed to read {file}: {e}"))?;
+    let name = Path::new(&file).file_name().map_or(file.clone(), |name| name.to_string_lossy().to_string());
+
+    let result = soup_manager()?
+        .import_sbom(&name, &content, format, &get_current_user_id())
+        .map_err(|e| format!("Failed to import SBOM: {e}"))?;
+    println!("✅ Imported {} as {} ({})", name, result.import.id, result.import.format.name());
+    println!("   Components:        {}", result.import.components.len());
+    println!("   New SOUP items:    {}", result.created.len());
+    println!("   Version changes:   {}", result.updated.len());
+    println!("   No longer used:    {}", result.retired.len());
+    println!("   New anomalies:     {}", result.anomalies_added);
+    if let Some(diff) = &result.diff {
+        println!();
+        print_diff(diff);
+        if !diff.is_empty() {
+            println!("\nNext: qms soup diff {} {} --ecr", diff.from, diff.to);
+        }
+    }
+    Ok(())
+}
+
+fn handle_soup_list(args: &[String]) -> Result<(), String> {
+    let usage = "qms soup list [--all]";
+    let mut all = false;
+    for arg in args {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--help" | "-h" => {
+                println!("USAGE:\n    {usage}");
+                println!("\nLists SOUP items in use; --all includes items no longer in the latest SBOM.");
+                return Ok(());
+            }
+            _ => return Err(format!("Unknown option: {arg}. Usage: {usage}")),
+        }
+    }
+
+    let register = soup_manager()?.load_register().map_err(|e| e.to_string())?;
+    let items: Vec<&SoupItem> = register.items.iter().filter(|item| all || item.in_use).collect();
+    if items.is_empty() {
+        println!("No SOUP items found");
+        return Ok(());
+    }
+
+    println!("{:<9} {:<28} {:<14} {:<22} {:>5} {:>7} {:>8}", "ID", "Name", "Version", "License", "Reqs", "Risks", "Pending");
+    println!("{:-<99}", "");
+    for item in &items {
+        let requirements = item.functional_requirements.len() + item.performance_requirements.len();
+        let name = if item.in_use { item.name.clone() } else { format!("{} (removed)", item.name) };
+        println!(
+            "{:<9} {:<28} {:<14} {:<22} {:>5} {:>7} {:>8}",
+            item.id,
+            name,
+            item.version,
+            item.license,
+            requirements,
+            item.risk_ids.len(),
+            item.pending_anomalies()
+        );
+    }
+
+    let in_use: Vec<&&SoupItem> = items.iter().filter(|item| item.in_use).collect();
+    let without_requirements = in_use.iter().filter(|item| !item.has_requirements()).count();
+    let pending: usize = in_use.iter().map(|item| item.pending_anomalies()).sum();
+    println!("\nTotal: {} item(s)", items.len());
+    if without_requirements > 0 {
+        println!("⚠️  {without_requirements} item(s) in use without functional or performance requirements");
+    }
+    if pending > 0 {
+        println!("⚠️  {pending} known anomaly(ies) pending evaluation");
+    }
+    Ok(())
+}
+
+fn handle_soup_show(args: &[String]) -> Result<(), String> {
+    let usage = "qms soup show <SOUP-ID|NAME>";
+    if is_help(args) {
+        println!("USAGE:\n    {usage}");
+        return Ok(());
+    }
+    let [id] = args else {
+        return Err(format!("SOUP ID is required. Usage: {usage}"));
+    };
+    let item = soup_manager()?.get(id).map_err(|e| e.to_string())?;
+
+    println!("📦 {}: {} {}", item.id, item.name, item.version);
+    println!("{:-<60}", "");
+    if let Some(previous) = &item.previous_version {
+        println!("Previous:    {previous}");
+    }
+    println!("Status:      {}", if item.in_use { "In use" } else { "No longer in SBOM" });
+    println!("Supplier:    {}", item.supplier);
+    println!("License:     {}", item.license);
+    if let Some(purl) = &item.purl {
+        println!("Package URL: {purl}");
+    }
+    if !item.description.is_empty() {
+        println!("Description: {}", item.description);
+    }
+    println!("Last import: {}", item.last_import);
+
+    println!("\nFunctional requirements ({}):", item.functional_requirements.len());
+    for requirement in &item.functional_requirements {
+        println!("   • {requirement}");
+    }
+    println!("Performance requirements ({}):", item.performance_requirements.len());
+    for requirement in &item.performance_requirements {
+        println!("   • {requirement}");
+    }
+    println!("Risks: {}", if item.risk_ids.is_empty() { "none".to_string() } else { item.risk_ids.join(", ") });
+
+    println!("\nKnown anomalies ({}):", item.anomalies.len());
+    for anomaly in &item.anomalies {
+        let severity = if anomaly.severity.is_empty() { String::new() } else { format!(" [{}]", anomaly.severity) };
+        println!("   {}{severity}: {} ({})", anomaly.id, anomaly.evaluation.display_name(), anomaly.reported_version);
+        if !anomaly.description.is_empty() {
+            println!("      {}", anomaly.description);
+        }
+        if let (Some(by), Some(at)) = (&anomaly.evaluated_by, &anomaly.evaluated_at) {
+            println!("      Evaluated by {by} on {at}: {}", anomaly.rationale);
+        }
+    }
+    Ok(())
+}
+
+fn handle_soup_require(args: &[String]) -> Result<(), String> {
+    let usage = "qms soup require <SOUP-ID> (--functional <TEXT> | --performance <TEXT>)";
+    if is_help(args) {
+        println!("USAGE:\n    {usage}");
+        println!("\nRecords a functional or performance requirement the SOUP item must meet.");
+        return Ok(());
+    }
+    let id = args.first().ok_or_else(|| format!("SOUP ID is required. Usage: {usage}"))?;
+    let (performance, text) = match args.get(1).map(String::as_str) {
+        Some("--functional") if args.len() == 3 => (false, &args[2]),
+        Some("--performance") if args.len() == 3 => (true, &args[2]),
+        _ => return Err(format!("Usage: {usage}")),
+    };
+
+    let item = soup_manager()?
+        .add_requirement(id, performance, text)
+        .map_err(|e| e.to_string())?;
+    let kind = if performance { "Performance" } else { "Functional" };
+    println!("✅ {kind} requirement added to {} ({})", item.id, item.name);
+    Ok(())
+}
+
+fn handle_soup_link_risk(args: &[String]) -> Result<(), String> {
+    let usage = "qms soup link-risk <SOUP-ID> <HAZARD-ID>";
+    if is_help(args) {
+        println!("USAGE:\n    {usage}");
+        println!("\nLinks the SOUP item to a hazard in the risk register.");
+        return Ok(());
+    }
+    let [id, risk_id] = args else {
+        return Err(format!("SOUP ID and hazard ID are required. Usage: {usage}"));
+    };
+    let item = soup_manager()?.link_risk(id, risk_id).map_err(|e| e.to_string())?;
+    println!("✅ {} ({}) linked to {}", item.id, item.name, item.risk_ids.join(", "));
+    Ok(())
+}
+
+fn handle_soup_anomaly(args: &[String]) -> Result<(), String> {
+    let usage = "qms soup anomaly add <SOUP-ID> <ANOMALY-ID> --description <TEXT> [--severity <LEVEL>]\n    \
+                 qms soup anomaly evaluate <SOUP-ID> <ANOMALY-ID> --result <not-applicable|acceptable|requires-action> --rationale <TEXT>";
+    let (action, id, anomaly_id, options) = match args {
+        [action, id, anomaly_id, options @ ..] if !action.starts_with('-') => (action.as_str(), id, anomaly_id, options),
+        _ => {
+            println!("USAGE:\n    {usage}");
+            return if is_help(args) { Ok(()) } else { Err("Missing anomaly arguments".to_string()) };
+        }
+    };
+
+    let mut description = String::new();
+    let mut severity = String::new();
+    let mut result = None;
+    let mut rationale = String::new();
+    let mut i = 0;
+    while i < options.len() {
+        match options[i].as_str() {
+            "--description" => description = option_value(options, i)?,
+            "--severity" => severity = option_value(options, i)?,
+            "--result" => result = Some(AnomalyEvaluation::parse(&option_value(options, i)?).map_err(|e| e.to_string())?),
+            "--rationale" => rationale = option_value(options, i)?,
+            _ => return Err(format!("Unknown option: {}. Usage: {usage}", options[i])),
+        }
+        i += 2;
+    }
+
+    let manager = soup_manager()?;
+    match action {
+        "add" => {
+            let item = manager
+                .add_anomaly(id, anomaly_id, &description, &severity)
+                .map_err(|e| e.to_string())?;
+            println!("✅ Anomaly {anomaly_id} recorded on {} ({}), pending evaluation", item.id, item.name);
+        }
+        "evaluate" => {
+            let result = result.ok_or_else(|| format!("--result is required. Usage: {usage}"))?;
+            let item = manager
+                .evaluate_anomaly(id, anomaly_id, result, &rationale, &get_current_user_id())
+                .map_err(|e| e.to_string())?;
+            println!("✅ Anomaly {anomaly_id} on {} evaluated: {}", item.id, result.display_name());
+        }
+        _ => return Err(format!("Unknown anomaly command '{action}'. Usage: {usage}")),
+    }
+    Ok(())
+}
+
+fn handle_soup_imports(args: &[String]) -> Result<(), String> {
+    if is_help(args) {
+        println!("USAGE:\n    qms soup imports");
+        return Ok(());
+    }
+    let imports = soup_manager()?.list_imports().map_err(|e| e.to_string())?;
+    if imports.is_empty() {
+        println!("No SBOM imported");
+        return Ok(());
+    }
+    println!("{:<10} {:<10} {:<22} {:<12} {:>10}  File", "ID", "Format", "Imported", "By", "Components");
+    println!("{:-<90}", "");
+    for import in &imports {
+        println!(
+            "{:<10} {:<10} {:<22} {:<12} {:>10}  {}",
+            import.id,
+            import.format.name(),
+            import.imported_at,
+            import.imported_by,
+            import.components.len(),
+            import.file
+        );
+    }
+    Ok(())
+}
+
+fn handle_soup_diff(args: &[String]) -> Result<(), String> {
+    let usage = "qms soup diff [<FROM> [<TO>]] [--ecr]";
+    let mut imports = Vec::new();
+    let mut raise_change = false;
+    for arg in args {
+        match arg.as_str() {
+            "--ecr" => raise_change = true,
+            "--help" | "-h" => {
+                println!("USAGE:\n    {usage}");
+                println!("\nCompares two SBOM imports (default: the last two). --ecr raises a change");
+                println!("request listing the differences.");
+                return Ok(());
+            }
+            arg if arg.starts_with("--") => return Err(format!("Unknown option: {arg}. Usage: {usage}")),
+            arg if imports.len() < 2 => imports.push(arg),
+            arg => return Err(format!("Unexpected argument: {arg}. Usage: {usage}")),
+        }
+    }
+
+    let manager = soup_manager()?;
+    let diff = manager
+        .diff(imports.first().copied(), imports.get(1).copied())
+        .map_err(|e| e.to_string())?;
+    print_diff(&diff);
+
+    if raise_change {
+        let change = manager
+            .raise_change_request(&diff, &get_current_user_id())
+            .map_err(|e| format!("Failed to raise change request: {e}"))?;
+        println!("\n✅ Change request {} raised: {}", change.id, change.title);
+        println!("Next: qms change show {}", change.id);
+    }
+    Ok(())
+}
+
+fn print_soup_help() {
+    println!("Manage SOUP items and their SBOMs\n");
+    println!("USAGE:");
+    println!("    qms soup <COMMAND>\n");
+    println!("COMMANDS:");
+    println!("    import      Import a CycloneDX or SPDX JSON SBOM into the register");
+    println!("    list        List SOUP items with requirement, risk and anomaly counts");
+    println!("    show        Show a SOUP item");
+    println!("    require     Add a functional or performance requirement to an item");
+    println!("    link-risk   Link an item to a hazard in the risk register");
+    println!("    anomaly     Record (add) or evaluate a known anomaly");
+    println!("    imports     List imported SBOMs");
+    println!("    diff        Compare two SBOM imports, optionally raising a change request");
+    println!("    help        Show this help message\n");
+    println!("EVALUATIONS:");
+    println!("    not-applicable    The anomaly cannot occur in the device's use of the item");
+    println!("    acceptable        It can occur and the resulting risk is acceptable");
+    println!("    requires-action   A risk control, workaround or version change is needed\n");
+    println!("EXAMPLES:");
+    println!("    cargo cyclonedx --format json && qms soup import firmware.cdx.json");
+    println!("    qms soup require SOUP-003 --functional \"Parses pump configuration files\"");
+    println!("    qms soup link-risk SOUP-003 HAZ-002");
+    println!("    qms soup anomaly evaluate SOUP-003 RUSTSEC-2023-0001 --result not-applicable \\");
+    println!("        --rationale \"Input size is limited to 4 KiB\"");
+    println!("    qms soup diff SBOM-001 SBOM-002 --ecr");
+}
@@ -0,0 +1,115 @@
+/*
+ * QMS (Quality Management System)
+ * Storage Command Handler
+ *
+ * CLI command handlers for inspecting and switching the project storage
+ * backend (JSON files or embedded SQLite database)
+ */
+
+use crate::modules::attachments::Attachment;
+use crate::modules::audit_logger::functions::audit_log_action;
+use crate::modules::risk_manager::risk::{RiskItem, RiskManager};
+use crate::modules::storage::{copy_collection, EntityStore, StorageConfig, StorageType};
+use crate::modules::traceability::links::TraceabilityLink;
+use crate::modules::traceability::requirement::Requirement;
+use crate::utils::get_current_project_path;
+use std::str::FromStr;
+
+pub fn handle_storage_command(args: &[String]) -> Result<(), String> {
+    if args.len() < 3 {
+        print_storage_help();
+        return Ok(());
+    }
+
+    match args[2].as_str() {
+        "status" => handle_storage_status(),
+        "migrate" => handle_storage_migrate(&args[3..]),
+        "--help" | "-h" | "help" => {
+            print_storage_help();
+            Ok(())
+        }
+        _ => {
+            eprintln!("Error: Unknown storage command '{}'", args[2]);
+            print_storage_help();
+            Err(format!("Unknown storage command '{}'", args[2]))
+        }
+    }
+}
+
+fn handle_storage_status() -> Result<(), String> {
+    let project_path = get_current_project_path()
+        .map_err(|e| format!("Failed to get project path: {e}"))?;
+    let config = StorageConfig::for_project(&project_path).map_err(|e| e.to_string())?;
+
+    println!("Storage backend: {}", config.storage_type.as_str());
+    if config.storage_type == StorageType::Database {
+        println!("Database: {}", config.database_path(&project_path).display());
+    }
+
+    let risks = EntityStore::<RiskItem>::open(&project_path).map_err(|e| e.to_string())?;
+    let requirements = EntityStore::<Requirement>::open(&project_path).map_err(|e| e.to_string())?;
+    let links = EntityStore::<TraceabilityLink>::open(&project_path).map_err(|e| e.to_string())?;
+    let attachments = EntityStore::<Attachment>::open(&project_path).map_err(|e| e.to_string())?;
+    println!("  Risks:        {}", risks.reader().count().map_err(|e| e.to_string())?);
+    println!("  Requirements: {}", requirements.reader().count().map_err(|e| e.to_string())?);
+    println!("  Trace links:  {}", links.reader().count().map_err(|e| e.to_string())?);
+    println!("  Attachments:  {}", attachments.reader().count().map_err(|e| e.to_string())?);
+    Ok(())
+}
+
+fn handle_storage_migrate(args: &[String]) -> Result<(), String> {
+    let target = match args.first() {
+        Some(backend) if !backend.starts_with("--") => {
+            StorageType::from_str(backend).map_err(|e| e.to_string())?
+        }
+        _ => return Err("Target backend is required. Usage: qms storage migrate <filesystem|sqlite>".to_string()),
+    };
+
+    let project_path = get_current_project_path()
+        .map_err(|e| format!("Failed to get project path: {e}"))?;
+    let from = StorageConfig::for_project(&project_path).map_err(|e| e.to_string())?;
+    if from.storage_type == target {
+        println!("Project already uses {} storage", target.as_str());
+        return Ok(());
+    }
+    let to = StorageConfig { storage_type: target.clone(), ..from.clone() };
+
+    let migrate = || -> crate::prelude::QmsResult<(usize, usize, usize, usize)> {
+        let risks = copy_collection::<RiskItem>(&project_path, &from, &to)?;
+        let requirements = copy_collection::<Requirement>(&project_path, &from, &to)?;
+        let links = copy_collection::<TraceabilityLink>(&project_path, &from, &to)?;
+        let attachments = copy_collection::<Attachment>(&project_path, &from, &to)?;
+        StorageConfig::set_project_backend(&project_path, &target)?;
+        if target == StorageType::FileSystem {
+            RiskManager::new(&project_path)?.rebuild_index()?;
+        }
+        Ok((risks, requirements, links, attachments))
+    };
+    let (risks, requirements, links, attachments) = migrate().map_err(|e| format!("Migration failed: {e}"))?;
+
+    let _ = audit_log_action(
+        "STORAGE_MIGRATED",
+        "Project",
+        &format!("{} -> {} ({risks} risks, {requirements} requirements, {links} links, {attachments} attachments)",
+            from.storage_type.as_str(), target.as_str()),
+    );
+
+    println!("✅ Migrated project storage from {} to {}", from.storage_type.as_str(), target.as_str());
+    println!("   Risks: {risks}, Requirements: {requirements}, Trace links: {links}, Attachments: {attachments}");
+    println!("   The previous copy is left in place and is no longer read.");
+    Ok(())
+}
+
+fn print_storage_help() {
+    println!("Manage the project storage backend\n");
+    println!("USAGE:");
+    println!("    qms storage <COMMAND>\n");
+    println!("COMMANDS:");
+    println!("    status                        Show the active backend and record counts");
+    println!("    migrate <filesystem|sqlite>   Copy risks, requirements, trace links and attachment");
+    println!("                                  records to another backend and switch the project to it");
+    println!("    help                          Show this help message\n");
+    println!("The backend is recorded as \"storage_backend\" in config/config.json.");
+    println!("SQLite storage keeps data in qms.db and makes multi-record operations atomic.");
+    println!("Attachment file contents stay in attachments/objects with either backend.");
+}
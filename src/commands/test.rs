@@ -1,4 +1,5 @@
 use crate::modules::traceability::test_case::{TestCaseManager, TestCategory, TestPriority, TestStepStatus, TestExecutionStatus};
+use crate::modules::traceability::test_results::{self, IngestOptions, TestResultFormat};
 use crate::error::QmsResult;
 use std::path::Path;
 
@@ -16,6 +17,7 @@ pub fn handle_test_command(args: Vec<String>) -> QmsResult<()> {
         "execute" => handle_test_execute(args, &mut manager),
         "record-result" => handle_test_record_result(args, &mut manager),
         "finalize" => handle_test_finalize(args, &mut manager),
+        "ingest" => handle_test_ingest(args, project_path, &mut manager),
         "list" => handle_test_list(args, &manager),
         "show" => handle_test_show(args, &manager),
         "summary" => handle_test_summary(args, &manager),
@@ -29,7 +31,7 @@ pub fn handle_test_command(args: Vec<String>) -> QmsResult<()> {
 
 fn handle_test_create(args: Vec<String>, manager: &mut TestCaseManager) -> QmsResult<()> {
     if args.len() < 7 {
-        eprintln!("Usage: qms test create --id <test_id> --title <title> --desc <description> [--category <category>] [--priority <priority>]");
+        eprintln!("Usage: qms test create --id <test_id> --title <title> --desc <description> [--category <category>] [--priority <priority>] [--tags <tag,...>]");
         return Ok(());
     }
     
@@ -38,6 +40,7 @@ fn handle_test_create(args: Vec<String>, manager: &mut TestCaseManager) -> QmsRe
     let mut description = String::new();
     let mut category = TestCategory::Functional;
     let mut priority = TestPriority::Medium;
+    let mut tags = Vec::new();
     let created_by = crate::utils::user_context::get_current_user_id(); // Get from user context
     
     let mut i = 1;
@@ -110,6 +113,18 @@ fn handle_test_create(args: Vec<String>, manager: &mut TestCaseManager) -> QmsRe
                     return Ok(());
                 }
             }
+            "--tags" => {
+                if i + 1 < args.len() {
+                    tags = args[i + 1].split(',')
+                        .map(|tag| tag.trim().to_string())
+                        .filter(|tag| !tag.is_empty())
+                        .collect();
+                    i += 2;
+                } else {
+                    eprintln!("Error: --tags requires a value");
+                    return Ok(());
+                }
+            }
             _ => {
                 eprintln!("Unknown option: {}", args[i]);
                 return Ok(());
@@ -125,6 +140,11 @@ fn handle_test_create(args: Vec<String>, manager: &mut TestCaseManager) -> QmsRe
     match manager.create_test_case(test_id.clone(), title, description, category, priority, created_by) {
         Ok(_) => {
             println!("✅ Test case '{test_id}' created successfully");
+            if !tags.is_empty() {
+                if let Err(e) = manager.set_tags(&test_id, tags) {
+                    eprintln!("❌ Error setting tags: {e}");
+                }
+            }
         }
         Err(e) => {
             eprintln!("❌ Error creating test case: {e}");
@@ -419,6 +439,114 @@ fn handle_test_finalize(args: Vec<String>, manager: &mut TestCaseManager) -> Qms
     Ok(())
 }
 
+fn handle_test_ingest(args: Vec<String>, project_path: &Path, manager: &mut TestCaseManager) -> QmsResult<()> {
+    if args.len() < 2 {
+        eprintln!("Usage: qms test ingest <file> [--format <junit|tap|libtest>] [--environment <env>] [--build <build>]");
+        return Ok(());
+    }
+    
+    let file = &args[1];
+    let mut format = None;
+    let mut environment = None;
+    let mut build = None;
+    
+    let mut i = 2;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--format" | "--environment" | "--build" if i + 1 >= args.len() => {
+                eprintln!("Error: {} requires a value", args[i]);
+                return Ok(());
+            }
+            "--format" => {
+                match TestResultFormat::parse(&args[i + 1]) {
+                    Ok(parsed) => format = Some(parsed),
+                    Err(e) => {
+                        eprintln!("❌ {e}");
+                        return Ok(());
+                    }
+                }
+                i += 2;
+            }
+            "--environment" => {
+                environment = Some(args[i + 1].clone());
+                i += 2;
+            }
+            "--build" => {
+                build = Some(args[i + 1].clone());
+                i += 2;
+            }
+            _ => {
+                eprintln!("Unknown option: {}", args[i]);
+                return Ok(());
+            }
+        }
+    }
+    
+    let content = match std::fs::read_to_string(file) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("❌ Error reading {file}: {e}");
+            return Ok(());
+        }
+    };
+    let Some(format) = format.or_else(|| TestResultFormat::detect(&content)) else {
+        eprintln!("❌ Could not detect the result format of {file}; pass --format <junit|tap|libtest>");
+        return Ok(());
+    };
+    
+    let results = match test_results::parse_results(&content, format) {
+        Ok(results) => results,
+        Err(e) => {
+            eprintln!("❌ Error parsing {file}: {e}");
+            return Ok(());
+        }
+    };
+    
+    let options = IngestOptions {
+        executed_by: crate::utils::user_context::get_current_user_id(),
+        environment,
+        build,
+        source_name: Path::new(file).file_name().map_or_else(|| file.clone(), |name| name.to_string_lossy().to_string()),
+    };
+    
+    match test_results::ingest_results(project_path, manager, &results, format, &options) {
+        Ok(summary) => {
+            println!("✅ Ingested {} result(s) from {file} ({})", summary.total, summary.format.name());
+            println!("   Passed: {}, Failed: {}, Skipped: {}", summary.passed, summary.failed, summary.skipped);
+            
+            if !summary.executions.is_empty() {
+                println!("\n🔄 Executions Recorded:");
+                for execution in &summary.executions {
+                    println!("  📋 {} - {} ({}, {} result(s))",
+                        execution.test_id, execution.execution_id, execution.status, execution.result_count);
+                }
+            }
+            
+            if !summary.requirements.is_empty() {
+                println!("\n🔗 Requirement Verification Updated:");
+                for (requirement_id, status) in &summary.requirements {
+                    println!("  {requirement_id}: {}", status.to_string());
+                }
+            }
+            
+            if !summary.unmatched.is_empty() {
+                println!("\n⚠️  {} result(s) matched no test case by ID or tag:", summary.unmatched.len());
+                for name in summary.unmatched.iter().take(10) {
+                    println!("  {name}");
+                }
+                if summary.unmatched.len() > 10 {
+                    println!("  ... and {} more", summary.unmatched.len() - 10);
+                }
+            }
+        }
+        Err(e) => {
+            eprintln!("❌ Error ingesting test results: {e}");
+        }
+    }
+    
+    Ok(())
+}
+
 fn handle_test_list(args: Vec<String>, manager: &TestCaseManager) -> QmsResult<()> {
     let mut category_filter = None;
     
@@ -527,6 +655,9 @@ fn handle_test_show(args: Vec<String>, manager: &TestCaseManager) -> QmsResult<(
                     if let Some(env) = &execution.environment {
                         println!("     Environment: {env}");
                     }
+                    if let Some(build) = &execution.build {
+                        println!("     Build: {build}");
+                    }
                     if let Some(notes) = &execution.notes {
                         println!("     Notes: {notes}");
                     }
@@ -566,7 +697,7 @@ fn handle_test_help() -> QmsResult<()> {
     println!("{:-<50}", "");
     println!();
     println!("📋 Basic Commands:");
-    println!("  qms test create --id <id> --title <title> --desc <description>");
+    println!("  qms test create --id <id> --title <title> --desc <description> [--tags <tag,...>]");
     println!("    Create a new test case");
     println!();
     println!("  qms test add-step <test_id> --action <action> --expected <expected>");
@@ -581,6 +712,9 @@ fn handle_test_help() -> QmsResult<()> {
     println!("  qms test finalize <test_id> --execution <exec_id> --status <status> [--duration <seconds>]");
     println!("    Finalize test execution");
     println!();
+    println!("  qms test ingest <file> [--format <format>] [--environment <env>] [--build <build>]");
+    println!("    Record automated results as executions and refresh requirement verification");
+    println!();
     println!("📊 Query Commands:");
     println!("  qms test list [--category <category>]");
     println!("    List all test cases or filter by category");
@@ -596,12 +730,15 @@ fn handle_test_help() -> QmsResult<()> {
     println!("  Priorities: critical, high, medium, low");
     println!("  Step Status: passed, failed, blocked, skipped");
     println!("  Execution Status: passed, failed, blocked, incomplete");
+    println!("  Result Formats: junit, tap, libtest (detected from the file when omitted)");
+    println!("  Results match a test case when its ID or one of its tags appears in the test name");
     println!();
     println!("💡 Examples:");
     println!("  qms test create --id TC-001 --title \"Login Test\" --desc \"Test user login functionality\"");
     println!("  qms test add-step TC-001 --action \"Enter username\" --expected \"Username field accepts input\"");
     println!("  qms test execute TC-001 --environment \"QA Environment\"");
     println!("  qms test record-result TC-001 --execution EXEC-TC-001-123 --step 1 --status passed --actual \"Username accepted\"");
+    println!("  qms test ingest target/junit.xml --environment CI --build 1.4.0+abc123");
     println!();
     Ok(())
 }
@@ -1,10 +1,12 @@
 use std::process;
 use std::path::Path;
-use crate::modules::traceability::links::{TraceabilityManager, TraceLinkType, TraceabilityPathNode};
+use crate::modules::baseline::BaselineManager;
+use crate::modules::traceability::links::{LinkProvenance, TraceabilityManager, TraceLinkType, TraceabilityPathNode};
 use crate::modules::traceability::rtm::{RTMGenerator, RTMConfig, RTMFormat, RTMSortBy};
 use crate::modules::traceability::visualization::{GraphVisualizer, GraphFormat};
 use crate::modules::traceability::coverage::{CoverageAnalyzer, CoverageType};
-use crate::modules::traceability::impact::{ImpactAnalyzer, format_impact_analysis};
+use crate::modules::traceability::impact::{ImpactAnalyzer, format_impact_analysis, format_touched_requirements};
+use crate::modules::traceability::source_scan::{scan_and_reconcile, ScanConfig};
 
 pub fn handle_trace_command(args: &[String]) -> Result<(), String> {
     if args.len() < 3 {
@@ -15,6 +17,7 @@ pub fn handle_trace_command(args: &[String]) -> Result<(), String> {
     match args[2].as_str() {
         "create" => handle_trace_create(&args[3..]),
         "link" => handle_trace_link(&args[3..]),
+        "link-risk" => handle_trace_link_risk(&args[3..]),
         "view" => handle_trace_view(&args[3..]),
         "list" => handle_trace_list(&args[3..]),
         "delete" => handle_trace_delete(&args[3..]),
@@ -23,6 +26,7 @@ pub fn handle_trace_command(args: &[String]) -> Result<(), String> {
         "orphans" => handle_trace_orphans(&args[3..]),
         "matrix" => handle_trace_matrix(&args[3..]),
         "import" => handle_trace_import(&args[3..]),
+        "scan" => handle_trace_scan(&args[3..]),
         "export" => handle_trace_export(&args[3..]),
         "stats" => handle_trace_stats(&args[3..]),
         "graph" => handle_trace_graph(&args[3..]),
@@ -115,6 +119,53 @@ fn handle_trace_link(args: &[String]) -> Result<(), String> {
     }
 }
 
+fn handle_trace_link_risk(args: &[String]) -> Result<(), String> {
+    if args.is_empty() || args[0] == "--help" || args[0] == "-h" {
+        print_trace_link_risk_help();
+        return Ok(());
+    }
+
+    let mut req_id = String::new();
+    let mut risk_id = String::new();
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--req" | "--risk" if i + 1 >= args.len() => {
+                return Err(format!("{} requires a value", args[i]));
+            }
+            "--req" => {
+                req_id = args[i + 1].clone();
+                i += 2;
+            }
+            "--risk" => {
+                risk_id = args[i + 1].clone();
+                i += 2;
+            }
+            _ => {
+                return Err(format!("Unknown option: {}", args[i]));
+            }
+        }
+    }
+
+    if req_id.is_empty() || risk_id.is_empty() {
+        return Err("Missing required arguments. Use --req and --risk".to_string());
+    }
+
+    let project_root = std::env::current_dir().map_err(|e| e.to_string())?;
+    let manager = TraceabilityManager::new(&project_root)
+        .map_err(|e| format!("Failed to initialize traceability manager: {e}"))?;
+
+    match manager.link_risk_to_requirement(&req_id, &risk_id) {
+        Ok(link) => {
+            println!("✓ Linked risk {} to requirement {}", link.target_id, link.source_id);
+            println!("  Link ID: {}", link.id);
+            Ok(())
+        }
+        Err(e) => Err(format!("Failed to link risk: {e}")),
+    }
+}
+
 fn handle_trace_view(args: &[String]) -> Result<(), String> {
     if args.is_empty() || args[0] == "--help" || args[0] == "-h" {
         print_trace_view_help();
@@ -152,6 +203,9 @@ fn handle_trace_view(args: &[String]) -> Result<(), String> {
                 if link.verified {
                     println!("    ✓ Verified");
                 }
+                if let Some(provenance) = &link.provenance {
+                    print_link_provenance(provenance);
+                }
             }
             
             Ok(())
@@ -191,6 +245,9 @@ fn handle_trace_list(args: &[String]) -> Result<(), String> {
                 if link.verified {
                     println!("    ✓ Verified");
                 }
+                if let Some(provenance) = &link.provenance {
+                    print_link_provenance(provenance);
+                }
                 println!();
             }
             
@@ -200,6 +257,83 @@ fn handle_trace_list(args: &[String]) -> Result<(), String> {
     }
 }
 
+fn print_link_provenance(provenance: &LinkProvenance) {
+    let stale = if provenance.stale { " (stale)" } else { "" };
+    println!("    Source: {}:{}{}", provenance.file, provenance.line, stale);
+}
+
+fn handle_trace_scan(args: &[String]) -> Result<(), String> {
+    if args.is_empty() || args[0] == "--help" || args[0] == "-h" {
+        print_trace_scan_help();
+        return Ok(());
+    }
+
+    let mut scan_path = None;
+    let mut config = ScanConfig::default();
+    let mut dry_run = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--annotation" | "--ext" | "--exclude" => {
+                let value = args.get(i + 1).ok_or_else(|| format!("{} requires a value", args[i]))?;
+                match args[i].as_str() {
+                    "--annotation" => config.add_annotation(value).map_err(|e| e.to_string())?,
+                    "--ext" => config.extensions.push(value.trim_start_matches('.').to_string()),
+                    _ => config.exclude_dirs.push(value.clone()),
+                }
+                i += 2;
+            }
+            "--dry-run" => {
+                dry_run = true;
+                i += 1;
+            }
+            arg if arg.starts_with("--") => return Err(format!("Unknown option: {arg}")),
+            arg => {
+                if scan_path.is_some() {
+                    return Err(format!("Unexpected argument: {arg}"));
+                }
+                scan_path = Some(arg.to_string());
+                i += 1;
+            }
+        }
+    }
+
+    let scan_path = scan_path.ok_or("Missing source path. Usage: qms trace scan <PATH>")?;
+    let project_root = std::env::current_dir().map_err(|e| e.to_string())?;
+
+    let report = scan_and_reconcile(&project_root, Path::new(&scan_path), &config, dry_run)
+        .map_err(|e| format!("Scan failed: {e}"))?;
+
+    if dry_run {
+        println!("Dry run - no links were changed");
+    }
+    println!(
+        "Scanned {} files under {}: {} annotations",
+        report.files_scanned,
+        if report.scope.is_empty() { scan_path.as_str() } else { report.scope.as_str() },
+        report.annotations
+    );
+    println!("  Created:    {}", report.created.len());
+    println!("  Updated:    {}", report.updated.len());
+    println!("  Unchanged:  {}", report.unchanged);
+    println!("  Stale:      {}", report.stale.len());
+    println!("  Unresolved: {}", report.unresolved.len());
+
+    for (label, links) in [("+", &report.created), ("~", &report.updated), ("!", &report.stale)] {
+        for link in links {
+            if let Some(provenance) = &link.provenance {
+                println!("  {label} {} [{}] {}:{}", link.source_id, link.link_type, provenance.file, provenance.line);
+            }
+        }
+    }
+    for annotation in &report.unresolved {
+        println!("  ? {} not found ({}:{})", annotation.entity_id, annotation.file, annotation.line);
+    }
+
+    Ok(())
+}
+
 fn handle_trace_delete(args: &[String]) -> Result<(), String> {
     if args.is_empty() || args[0] == "--help" || args[0] == "-h" {
         print_trace_delete_help();
@@ -395,10 +529,19 @@ fn handle_trace_matrix(args: &[String]) -> Result<(), String> {
     let mut format = RTMFormat::CSV;
     let mut output_path: Option<String> = None;
     let mut show_stats = false;
+    let mut baseline_name: Option<String> = None;
     let mut i = 0;
 
     while i < args.len() {
         match args[i].as_str() {
+            "--baseline" => {
+                if i + 1 < args.len() {
+                    baseline_name = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    return Err("--baseline requires a value".to_string());
+                }
+            }
             "--format" => {
                 if i + 1 < args.len() {
                     format = RTMFormat::from_str(&args[i + 1]).map_err(|e| e.to_string())?;
@@ -484,6 +627,13 @@ fn handle_trace_matrix(args: &[String]) -> Result<(), String> {
     // Get project root
     let project_root = std::env::current_dir().map_err(|e| e.to_string())?;
     let mut rtm_generator = RTMGenerator::new(&project_root).map_err(|e| e.to_string())?;
+    if let Some(name) = baseline_name {
+        let baseline = BaselineManager::new(&project_root)
+            .and_then(|manager| manager.load(&name))
+            .map_err(|e| e.to_string())?;
+        println!("📌 As of baseline {} ({})", baseline.name, baseline.created_at);
+        rtm_generator = rtm_generator.with_baseline(baseline);
+    }
 
     if show_stats {
         // Generate and display RTM statistics
@@ -862,6 +1012,7 @@ fn print_trace_help() {
     println!("    qms trace <COMMAND>\n");
     println!("COMMANDS:");
     println!("    link       Create traceability links between entities");
+    println!("    link-risk  Link a risk to a requirement (atomic)");
     println!("    view       View traceability links for an entity");
     println!("    list       List all traceability links");
     println!("    delete     Delete a traceability link");
@@ -870,6 +1021,7 @@ fn print_trace_help() {
     println!("    orphans    Find orphaned items with no traceability links");
     println!("    matrix     Generate Requirements Traceability Matrix (RTM)");
     println!("    import     Import traceability links from CSV/JSON files");
+    println!("    scan       Create links from @implements/@verifies annotations in source code");
     println!("    export     Export traceability data to various formats");
     println!("    stats      Show traceability statistics and coverage analysis");
     println!("    graph      Generate visual dependency graphs");
@@ -881,6 +1033,17 @@ fn print_trace_help() {
     println!("    qms trace <COMMAND> --help");
 }
 
+fn print_trace_link_risk_help() {
+    println!("Link a risk to the requirement it affects\n");
+    println!("USAGE:");
+    println!("    qms trace link-risk --req <REQ_ID> --risk <HAZARD_ID>\n");
+    println!("ARGUMENTS:");
+    println!("    --req <REQ_ID>       Requirement ID (e.g., REQ-001)");
+    println!("    --risk <HAZARD_ID>   Risk hazard ID (e.g., HAZ-001)\n");
+    println!("The requirement's linked risks and the trace link are updated together;");
+    println!("with SQLite storage a failure leaves neither change in place.");
+}
+
 fn print_trace_link_help() {
     println!("Create traceability links between entities\n");
     println!("USAGE:");
@@ -903,6 +1066,34 @@ fn print_trace_link_help() {
     println!("    qms trace link --from RISK-001 --to REQ-001 --type related");
 }
 
+fn print_trace_scan_help() {
+    println!("Create traceability links from annotations in source code\n");
+    println!("USAGE:");
+    println!("    qms trace scan <PATH> [OPTIONS]\n");
+    println!("ARGUMENTS:");
+    println!("    <PATH>   Source file or directory to scan\n");
+    println!("OPTIONS:");
+    println!("    --annotation <KEYWORD>=<TYPE>   Recognise another annotation keyword (repeatable)");
+    println!("    --ext <EXT>                     Also scan files with this extension (repeatable)");
+    println!("    --exclude <DIR>                 Skip directories with this name (repeatable)");
+    println!("    --dry-run                       Report changes without storing them\n");
+    println!("ANNOTATIONS:");
+    println!("    // @implements REQ-014            Code implements REQ-014");
+    println!("    #[doc = \"verifies REQ-014\"]     Test verifies REQ-014");
+    println!("    /// Implements: REQ-014, RISK-002  Several IDs after one keyword\n");
+    println!("Keywords are matched after '@' or inside a comment or doc attribute. Each");
+    println!("entity, file and link type gives one link from the entity to the file, with");
+    println!("the file and line of its first annotation. Files are recorded relative to the");
+    println!("project root, or to <PATH> when it lies outside the project. Rescanning moves");
+    println!("links to their new lines and flags links whose annotation is gone as stale.");
+    println!("Hidden directories and target, node_modules, build, dist and __pycache__ are");
+    println!("skipped.\n");
+    println!("EXAMPLES:");
+    println!("    qms trace scan src");
+    println!("    qms trace scan tests --dry-run");
+    println!("    qms trace scan src --annotation satisfies=implements");
+}
+
 fn print_trace_view_help() {
     println!("View traceability links for an entity\n");
     println!("USAGE:");
@@ -950,17 +1141,19 @@ fn print_trace_matrix_help() {
     println!("    --show-verification-details    Include verification details in output");
     println!("    --hide-coverage                Hide coverage metrics from output");
     println!("    --stats                        Show RTM statistics instead of matrix");
+    println!("    --baseline <NAME>              Render the matrix as frozen in a baseline");
     println!("    --help                         Show this help message\n");
     println!("FORMATS:");
     println!("    csv        Comma-separated values (default)");
     println!("    json       JSON format");
     println!("    html       HTML table format");
-    println!("    pdf        PDF format (requires external tools)");
+    println!("    pdf        Native PDF document");
     println!("    markdown   Markdown table format\n");
     println!("EXAMPLES:");
     println!("    qms trace matrix --format csv --output rtm.csv");
     println!("    qms trace matrix --category Functional,Safety --priority High,Critical");
     println!("    qms trace matrix --stats");
+    println!("    qms trace matrix --baseline R2.1 --format pdf --output rtm_r2.1.pdf");
     println!("    qms trace matrix --format html --output rtm.html --show-descriptions");
     println!("    qms trace matrix --verification-status \"Not Verified\" --sort-by priority");
 }
@@ -1242,6 +1435,9 @@ fn handle_trace_impact(args: &[String]) -> Result<(), String> {
         print_trace_impact_help();
         return Ok(());
     }
+    if args[0] == "--between" {
+        return handle_trace_impact_between(&args[1..]);
+    }
 
     let requirement_id = &args[0];
     let mut change_description = String::new();
@@ -1585,11 +1781,28 @@ fn print_trace_verify_help() {
 }
 
 /// Print impact help
+fn handle_trace_impact_between(args: &[String]) -> Result<(), String> {
+    let [from, to] = args else {
+        return Err("Usage: qms trace impact --between <FROM> <TO>".to_string());
+    };
+
+    let project_root = std::env::current_dir()
+        .map_err(|e| format!("Failed to get current directory: {e}"))?;
+    let analyzer = ImpactAnalyzer::new(&project_root)
+        .map_err(|e| format!("Failed to create impact analyzer: {e}"))?;
+    let touched = analyzer.requirements_touched_between(from, to)
+        .map_err(|e| format!("Impact analysis failed: {e}"))?;
+
+    print!("{}", format_touched_requirements(from, to, &touched));
+    Ok(())
+}
+
 fn print_trace_impact_help() {
     println!("Analyze impact of requirement changes
 ");
     println!("USAGE:");
-    println!("    qms trace impact <REQUIREMENT_ID> --analyze-change <DESCRIPTION> [OPTIONS]
+    println!("    qms trace impact <REQUIREMENT_ID> --analyze-change <DESCRIPTION> [OPTIONS]");
+    println!("    qms trace impact --between <FROM> <TO>
 ");
     println!("ARGUMENTS:");
     println!("    <REQUIREMENT_ID>      ID of the requirement being changed (e.g., REQ-001)");
@@ -1613,12 +1826,19 @@ fn print_trace_impact_help() {
     println!("    - Stakeholder analysis: Affected team members and roles");
     println!("    - Critical path: High-priority items requiring immediate attention");
     println!("    - Recommendations: Suggested actions for managing the change
+");
+    println!("CHANGES BETWEEN RELEASES:");
+    println!("    --between lists the requirements touched by the commits in <FROM>..<TO>");
+    println!("    of the history imported with 'qms git import'. <FROM> and <TO> are tags,");
+    println!("    commit hashes or HEAD. A commit touches a requirement when its message");
+    println!("    references the requirement ID or it changes a file linked by 'qms trace scan'.
 ");
     println!("EXAMPLES:");
     println!("    qms trace impact REQ-001 --analyze-change \"Updated authentication method\"");
     println!("    qms trace impact REQ-002 --analyze-change \"Changed data validation rules\" --output impact.txt");
     println!("    qms trace impact REQ-003 --analyze-change \"Modified API interface\" --format json --output impact.json");
     println!("    qms trace impact REQ-004 --analyze-change \"Enhanced security requirements\" --format csv --output impact.csv");
+    println!("    qms trace impact --between v2.0 v2.1");
 }
 
 fn print_trace_forward_help() {
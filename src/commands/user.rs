@@ -1,5 +1,5 @@
-use crate::commands::cli_auth_helper::{get_cli_auth_helper, require_cli_authentication, get_authenticated_project_path};
-use crate::modules::user_manager::{FileAuthManager, RoleManager, Permission};
+use crate::commands::cli_auth_helper::{get_cli_auth_helper, require_cli_authentication, get_authenticated_project_path, CliAuthHelper};
+use crate::modules::user_manager::{FileAuthManager, FileBasedAuthService, MfaStore, RoleManager, Permission, SigningKeyStore};
 use crate::utils::get_current_project_path;
 use std::io::{self, Write};
 use std::process;
@@ -22,6 +22,8 @@ pub fn handle_user_command(args: &[String]) -> Result<(), String> {
         "roles" => handle_user_roles(&args[3..]),
         "permissions" => handle_user_permissions(&args[3..]),
         "session" => handle_user_session(&args[3..]),
+        "keygen" => handle_user_keygen(&args[3..]),
+        "mfa" => handle_user_mfa(&args[3..]),
         "--help" | "-h" => {
             print_user_help();
             Ok(())
@@ -101,6 +103,9 @@ fn handle_user_add(args: &[String]) -> Result<(), String> {
         // Initial setup - create user directly
         auth_helper.create_user_if_not_exists(&username, &password)
             .map_err(|e| format!("Failed to create initial user: {e}"))?;
+        if let Ok(project_path) = get_current_project_path() {
+            enroll_signing_key(&project_path, &username, &password)?;
+        }
         return Ok(());
     }
 
@@ -132,10 +137,222 @@ fn handle_user_add(args: &[String]) -> Result<(), String> {
     println!("✅ User '{}' created successfully", user.username);
     println!("   Created: {}", format_timestamp(user.created_at));
     println!("   Roles: {}", user.roles.iter().map(|r| r.name.as_str()).collect::<Vec<_>>().join(", "));
+
+    enroll_signing_key(&project_path, &user.username, &password)?;
     
     Ok(())
 }
 
+/// Generate the user's electronic signature key pair, protected by their password
+fn enroll_signing_key(project_path: &std::path::Path, username: &str, password: &str) -> Result<(), String> {
+    let key = SigningKeyStore::new(project_path)
+        .generate_key_pair(username, password)
+        .map_err(|e| format!("Failed to generate signing key: {e}"))?;
+    println!("   Signing key: Ed25519 {}", key.fingerprint);
+    Ok(())
+}
+
+/// Handle user keygen command - enroll or rotate a user's signing key
+fn handle_user_keygen(args: &[String]) -> Result<(), String> {
+    let mut username = String::new();
+    let mut password = String::new();
+    let mut rotate = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--username" | "-u" => {
+                if i + 1 >= args.len() {
+                    return Err("Missing username value".to_string());
+                }
+                username = args[i + 1].clone();
+                i += 2;
+            }
+            "--password" | "-p" => {
+                if i + 1 >= args.len() {
+                    return Err("Missing password value".to_string());
+                }
+                password = args[i + 1].clone();
+                i += 2;
+            }
+            "--rotate" => {
+                rotate = true;
+                i += 1;
+            }
+            _ => {
+                return Err(format!("Unknown argument: {}", args[i]));
+            }
+        }
+    }
+
+    let session = require_cli_authentication()
+        .map_err(|e| format!("Authentication required: {e}"))?;
+    if username.is_empty() {
+        username = session.username.clone();
+    }
+    if username != session.username {
+        return Err("Signing keys can only be generated by their owner".to_string());
+    }
+
+    if password.is_empty() {
+        print!("Enter password: ");
+        io::stdout().flush().unwrap();
+        io::stdin().read_line(&mut password).unwrap();
+        password = password.trim().to_string();
+    }
+
+    let project_path = get_authenticated_project_path()
+        .map_err(|e| format!("Failed to get project path: {e}"))?;
+
+    // Confirm the password before it is used to protect the key
+    let auth_manager = FileAuthManager::from_project_path(&project_path)
+        .map_err(|e| format!("Failed to initialize auth manager: {e}"))?;
+    let user = auth_manager.load_user(&username)
+        .map_err(|e| format!("Failed to load user: {e}"))?;
+    if !FileAuthManager::verify_password(&password, &user.password_hash) {
+        return Err("Invalid password".to_string());
+    }
+
+    let key_store = SigningKeyStore::new(&project_path);
+    let key = if rotate {
+        key_store.rotate_key_pair(&username, &password)
+    } else {
+        key_store.generate_key_pair(&username, &password)
+    }.map_err(|e| format!("Failed to generate signing key: {e}"))?;
+
+    println!("✅ Signing key {} for '{}'", if rotate { "rotated" } else { "generated" }, username);
+    println!("   Fingerprint: {}", key.fingerprint);
+    println!("   Public key: {}", key.public_key);
+
+    Ok(())
+}
+
+/// Handle user mfa command
+fn handle_user_mfa(args: &[String]) -> Result<(), String> {
+    let Some(subcommand) = args.first() else {
+        return Err("Usage: qms user mfa <enroll|disable|status> [--password <pass>] [--code <code>]".to_string());
+    };
+
+    let mut password = String::new();
+    let mut code = String::new();
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--password" | "-p" => {
+                if i + 1 >= args.len() {
+                    return Err("Missing password value".to_string());
+                }
+                password = args[i + 1].clone();
+                i += 2;
+            }
+            "--code" | "-c" => {
+                if i + 1 >= args.len() {
+                    return Err("Missing code value".to_string());
+                }
+                code = args[i + 1].clone();
+                i += 2;
+            }
+            _ => {
+                return Err(format!("Unknown argument: {}", args[i]));
+            }
+        }
+    }
+
+    let session = require_cli_authentication()
+        .map_err(|e| format!("Authentication required: {e}"))?;
+    let username = session.username.clone();
+
+    let qms_dir = CliAuthHelper::get_qms_directory()
+        .map_err(|e| format!("Failed to locate QMS directory: {e}"))?;
+    let login_store = MfaStore::new(&qms_dir);
+    // Signing re-challenges go through the project's link to the login enrollment
+    let project_store = get_authenticated_project_path().ok().map(|path| MfaStore::new(&path));
+
+    if subcommand == "status" {
+        let enrolled = login_store.is_enrolled(&username);
+        println!("Two-factor authentication for '{username}': {}", if enrolled { "enrolled" } else { "not enrolled" });
+        if let Ok(enrollment) = login_store.load(&username) {
+            println!("   Recovery codes remaining: {}", enrollment.recovery_codes.len());
+        }
+        if let Some(ref store) = project_store {
+            println!("   Required for signing in current project: {}", if store.is_enrolled(&username) { "yes" } else { "no" });
+        }
+        return Ok(());
+    }
+
+    if password.is_empty() {
+        print!("Enter password: ");
+        io::stdout().flush().unwrap();
+        io::stdin().read_line(&mut password).unwrap();
+        password = password.trim().to_string();
+    }
+
+    // Confirm the password before changing the second factor
+    let auth_service = FileBasedAuthService::create_global()
+        .map_err(|e| format!("Failed to initialize authentication: {e}"))?;
+    let user = auth_service.get_user(&username)
+        .map_err(|e| format!("Failed to load user: {e}"))?;
+    if !FileBasedAuthService::verify_password(&password, &user.password_hash) {
+        return Err("Invalid password".to_string());
+    }
+
+    match subcommand.as_str() {
+        "enroll" => {
+            if login_store.is_enrolled(&username) {
+                // Already enrolled: prove possession, then link the enrollment to this project
+                let code = prompt_if_empty(code, "Authentication code: ");
+                login_store.verify(&username, &code)
+                    .map_err(|e| format!("Verification failed: {e}"))?;
+                let store = project_store.ok_or_else(|| "Already enrolled; no project to link".to_string())?;
+                store.link(&username, &login_store).map_err(|e| format!("Failed to link enrollment: {e}"))?;
+                println!("✅ Two-factor authentication linked to the current project for '{username}'");
+                return Ok(());
+            }
+
+            let setup = login_store.enroll(&username, "QMS")
+                .map_err(|e| format!("Failed to enroll: {e}"))?;
+            if let Some(store) = project_store {
+                store.link(&username, &login_store).map_err(|e| format!("Failed to link enrollment: {e}"))?;
+            }
+
+            println!("✅ Two-factor authentication enrolled for '{username}'");
+            println!("   Add this account to your authenticator app (shown only once):");
+            println!("   {}", setup.otpauth_uri);
+            println!("   Secret: {}", setup.secret);
+            println!();
+            println!("   Recovery codes (each works once; store them offline):");
+            for recovery_code in &setup.recovery_codes {
+                println!("     {recovery_code}");
+            }
+            Ok(())
+        }
+        "disable" => {
+            let code = prompt_if_empty(code, "Authentication code: ");
+            login_store.verify(&username, &code)
+                .map_err(|e| format!("Verification failed: {e}"))?;
+            login_store.disable(&username).map_err(|e| format!("Failed to disable: {e}"))?;
+            if let Some(store) = project_store.filter(|store| store.is_enrolled(&username)) {
+                store.disable(&username).map_err(|e| format!("Failed to disable: {e}"))?;
+            }
+            println!("✅ Two-factor authentication disabled for '{username}'");
+            Ok(())
+        }
+        other => Err(format!("Unknown mfa command: {other}")),
+    }
+}
+
+fn prompt_if_empty(value: String, prompt: &str) -> String {
+    if !value.is_empty() {
+        return value;
+    }
+    print!("{prompt}");
+    io::stdout().flush().unwrap();
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap();
+    input.trim().to_string()
+}
+
 /// Handle user list command
 fn handle_user_list(args: &[String]) -> Result<(), String> {
     let mut show_details = false;
@@ -204,6 +421,7 @@ fn handle_user_list(args: &[String]) -> Result<(), String> {
 fn handle_user_login(args: &[String]) -> Result<(), String> {
     let mut username = String::new();
     let mut password = String::new();
+    let mut code = None;
     
     let mut i = 0;
     while i < args.len() {
@@ -222,6 +440,13 @@ fn handle_user_login(args: &[String]) -> Result<(), String> {
                 password = args[i + 1].clone();
                 i += 2;
             }
+            "--code" | "-c" => {
+                if i + 1 >= args.len() {
+                    return Err("Missing code value".to_string());
+                }
+                code = Some(args[i + 1].clone());
+                i += 2;
+            }
             _ => {
                 return Err(format!("Unknown argument: {}", args[i]));
             }
@@ -249,6 +474,10 @@ fn handle_user_login(args: &[String]) -> Result<(), String> {
         // Interactive login
         auth_helper.interactive_login()
             .map_err(|e| format!("Login failed: {e}"))?
+    } else if let Some(code) = code {
+        // Direct login with second factor
+        auth_helper.login_with_code(&username, &password, Some(&code))
+            .map_err(|e| format!("Login failed: {e}"))?
     } else {
         // Direct login
         auth_helper.login(&username, &password)
@@ -658,12 +887,16 @@ fn print_user_help() {
     println!("  roles                   Show roles");
     println!("  permissions             Show permissions");
     println!("  session                 Show session information");
+    println!("  keygen                  Generate or rotate electronic signature key");
+    println!("  mfa                     Enroll, disable or show two-factor authentication");
     println!();
     println!("USER MANAGEMENT:");
     println!("  qms user add --username <name> --password <pass> [--role <role>]");
     println!("  qms user list [--details] [--permissions]");
-    println!("  qms user login --username <name> --password <pass>");
+    println!("  qms user login --username <name> --password <pass> [--code <code>]");
     println!("  qms user logout --session <session-id>");
+    println!("  qms user keygen [--username <name>] [--password <pass>] [--rotate]");
+    println!("  qms user mfa <enroll|disable|status> [--password <pass>] [--code <code>]");
     println!();
     println!("ROLE MANAGEMENT:");
     println!("  qms user assign-role --username <name> --role <role>");
@@ -62,6 +62,12 @@ impl From<crate::json_utils::JsonError> for QmsError {
     }
 }
 
+impl From<crate::xml_utils::XmlError> for QmsError {
+    fn from(error: crate::xml_utils::XmlError) -> Self {
+        QmsError::Parse(error.to_string())
+    }
+}
+
 impl From<std::time::SystemTimeError> for QmsError {
     fn from(error: std::time::SystemTimeError) -> Self {
         QmsError::Domain(format!("System time error: {error}"))
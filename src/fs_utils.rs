@@ -120,7 +120,7 @@ pub fn backup_file(path: &Path) -> QmsResult<PathBuf> {
 
     let backup_path = path.with_file_name(backup_name);
 
-    fs::copy(path, &backup_path)
+    crate::modules::storage::encryption::copy(path, &backup_path)
         .map_err(|e| QmsError::io_error(&format!("Failed to create backup: {e}")))?;
 
     log_audit(&format!(
@@ -280,6 +280,27 @@ impl JsonValue {
         self.to_string_with_indent(0)
     }
 
+    /// Compact JSON with object keys sorted, so equal values always serialize
+    /// to the same bytes regardless of field order
+    pub fn to_canonical_string(&self) -> String {
+        match self {
+            JsonValue::Object(obj) => {
+                let mut keys: Vec<&String> = obj.keys().collect();
+                keys.sort();
+                let fields: Vec<String> = keys
+                    .into_iter()
+                    .map(|key| format!("\"{}\":{}", JsonValue::escape_string(key), obj[key].to_canonical_string()))
+                    .collect();
+                format!("{{{}}}", fields.join(","))
+            }
+            JsonValue::Array(arr) => {
+                let values: Vec<String> = arr.iter().map(JsonValue::to_canonical_string).collect();
+                format!("[{}]", values.join(","))
+            }
+            other => other.to_string_with_indent(0),
+        }
+    }
+
     fn to_string_with_indent(&self, indent: usize) -> String {
         let indent_str = "  ".repeat(indent);
         let next_indent_str = "  ".repeat(indent + 1);
@@ -564,6 +585,14 @@ mod tests {
         assert!(!validate_range(11, 1, 10));
     }
 
+    #[test]
+    fn test_canonical_string_ignores_field_order() {
+        let a = JsonValue::parse(r#"{"b": [1, {"y": null, "x": "q\""}], "a": true}"#).unwrap();
+        let b = JsonValue::parse(r#"{"a":true,"b":[1,{"x":"q\"","y":null}]}"#).unwrap();
+        assert_eq!(a.to_canonical_string(), r#"{"a":true,"b":[1,{"x":"q\"","y":null}]}"#);
+        assert_eq!(a.to_canonical_string(), b.to_canonical_string());
+    }
+
     #[test]
     fn test_calculate_checksum() {
         let data1 = "test data";
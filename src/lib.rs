@@ -11,6 +11,7 @@ pub mod error;
 pub mod fs_utils;
 pub mod interfaces;
 pub mod json_utils;
+pub mod xml_utils;
 pub mod tui;
 pub mod lock;
 pub mod models;
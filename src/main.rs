@@ -11,6 +11,7 @@ mod constants; // SSOT: Centralized regulatory compliance constants
 mod error;
 mod fs_utils;
 mod json_utils;
+mod xml_utils;
 mod lock;
 mod models;
 mod modules;
@@ -24,7 +25,7 @@ mod web;
 // mod test_audit_integration;
 
 use audit::{init_tracing, log_command_execution, log_error};
-use commands::{audit as audit_cmd, doc, init, report, req, risk, test, trace, user};
+use commands::{attach, audit as audit_cmd, baseline, capa, change, daemon, doc, git, init, ncr, project, report, req, review, risk, search, soup, storage, test, trace, user};
 use config::{Config, LoggingConfig};
 use web::server::QMSWebServer;
 use tui::app::run_tui;
@@ -79,6 +80,13 @@ fn main() {
         }
     }
 
+    // Unlock encrypted project data for commands that read it
+    if let (Ok(project_path), Some(cmd)) = (utils::get_current_project_path(), args.get(1)) {
+        if !matches!(cmd.as_str(), "project" | "init" | "--help" | "-h" | "--version" | "-v") {
+            project::unlock_project_for_command(&project_path);
+        }
+    }
+
     // Parse command line arguments
     match args.get(1) {
         None => {
@@ -114,6 +122,78 @@ fn main() {
                     handle_error(format!("Risk command failed: {e}"));
                 }
             }
+            "attach" => {
+                log_command_execution("attach");
+                if let Err(e) = attach::handle_attach_command(&args) {
+                    handle_error(format!("Attach command failed: {e}"));
+                }
+            }
+            "capa" => {
+                log_command_execution("capa");
+                if let Err(e) = capa::handle_capa_command(&args) {
+                    handle_error(format!("CAPA command failed: {e}"));
+                }
+            }
+            "change" => {
+                log_command_execution("change");
+                if let Err(e) = change::handle_change_command(&args) {
+                    handle_error(format!("Change control command failed: {e}"));
+                }
+            }
+            "review" => {
+                log_command_execution("review");
+                if let Err(e) = review::handle_review_command(&args) {
+                    handle_error(format!("Design review command failed: {e}"));
+                }
+            }
+            "baseline" => {
+                log_command_execution("baseline");
+                if let Err(e) = baseline::handle_baseline_command(&args) {
+                    handle_error(format!("Baseline command failed: {e}"));
+                }
+            }
+            "git" => {
+                log_command_execution("git");
+                if let Err(e) = git::handle_git_command(&args) {
+                    handle_error(format!("Git command failed: {e}"));
+                }
+            }
+            "soup" => {
+                log_command_execution("soup");
+                if let Err(e) = soup::handle_soup_command(&args) {
+                    handle_error(format!("SOUP command failed: {e}"));
+                }
+            }
+            "storage" => {
+                log_command_execution("storage");
+                if let Err(e) = storage::handle_storage_command(&args) {
+                    handle_error(format!("Storage command failed: {e}"));
+                }
+            }
+            "project" => {
+                log_command_execution("project");
+                if let Err(e) = project::handle_project_command(&args) {
+                    handle_error(format!("Project command failed: {e}"));
+                }
+            }
+            "search" => {
+                log_command_execution("search");
+                if let Err(e) = search::handle_search_command(&args) {
+                    handle_error(format!("Search command failed: {e}"));
+                }
+            }
+            "daemon" => {
+                log_command_execution("daemon");
+                if let Err(e) = daemon::handle_daemon_command(&args) {
+                    handle_error(format!("Daemon command failed: {e}"));
+                }
+            }
+            "ncr" => {
+                log_command_execution("ncr");
+                if let Err(e) = ncr::handle_ncr_command(&args) {
+                    handle_error(format!("NCR command failed: {e}"));
+                }
+            }
             "req" => {
                 log_command_execution("req");
                 if let Err(e) = req::handle_req_command(&args) {
@@ -184,6 +264,8 @@ fn handle_serve_command(args: &[String]) -> Result<(), Box<dyn std::error::Error
     let mut tls_cert: Option<String> = None;
     let mut tls_key: Option<String> = None;
     let mut enable_https = false;
+    let mut redirect_port: Option<u16> = None;
+    let mut run_scheduler = true;
 
     // Parse optional arguments
     let mut i = 2; // Skip "qms" and "serve"
@@ -225,6 +307,18 @@ fn handle_serve_command(args: &[String]) -> Result<(), Box<dyn std::error::Error
                 enable_https = true;
                 i += 1;
             }
+            "--redirect-port" => {
+                if i + 1 < args.len() {
+                    redirect_port = Some(args[i + 1].parse().map_err(|_| "Invalid redirect port")?);
+                    i += 2;
+                } else {
+                    return Err("Redirect port value missing".into());
+                }
+            }
+            "--no-scheduler" => {
+                run_scheduler = false;
+                i += 1;
+            }
             "--help" => {
                 print_serve_help();
                 return Ok(());
@@ -248,6 +342,10 @@ fn handle_serve_command(args: &[String]) -> Result<(), Box<dyn std::error::Error
         }
     }
 
+    if let Some(port) = redirect_port {
+        server.set_redirect_port(port)?;
+    }
+
     // Enable HTTPS enforcement if requested and configured
     if enable_https {
         if let Err(e) = server.enable_https_enforcement() {
@@ -286,6 +384,9 @@ fn handle_serve_command(args: &[String]) -> Result<(), Box<dyn std::error::Error
     println!("🛑 Press Ctrl+C to stop the server");
     println!("═══════════════════════════════════════════════════════════════════════");
 
+    // Run scheduled jobs alongside the server while it is up
+    let _scheduler = if run_scheduler { daemon::start_background_scheduler() } else { None };
+
     server.start()?;
     
     Ok(())
@@ -308,7 +409,9 @@ fn print_serve_help() {
     println!("    -h, --host <HOST>         Set the server host (default: 127.0.0.1)");
     println!("    --tls-cert <CERT_FILE>    Path to TLS certificate file (PEM format)");
     println!("    --tls-key <KEY_FILE>      Path to TLS private key file (PEM format)");
-    println!("    --https                   Enable HTTPS enforcement");
+    println!("    --https                   Enable HTTPS enforcement (requires a certificate)");
+    println!("    --redirect-port <PORT>    Plain HTTP port redirected to HTTPS (default: 80)");
+    println!("    --no-scheduler            Do not run scheduled jobs (see 'qms daemon')");
     println!("    --help                    Show this help message");
     println!();
     println!("EXAMPLES:");
@@ -327,6 +430,9 @@ fn print_serve_help() {
     println!("    # Start server with HTTPS on port 443");
     println!("    qms serve --port 443 --tls-cert server.crt --tls-key server.key --https");
     println!();
+    println!("    # HTTPS on 8443 with plain HTTP on 8080 redirected to it");
+    println!("    qms serve --port 8443 --tls-cert chain.pem --tls-key server.key --https --redirect-port 8080");
+    println!();
     println!("FEATURES:");
     println!("    🔒 Security:");
     println!("       - Optional HTTPS/TLS support");
@@ -385,7 +491,7 @@ fn handle_tui_command(args: &[String]) -> Result<(), String> {
 
 fn print_usage() {
     println!("Usage: qms <command> [options]");
-    println!("Commands: init, doc, risk, req, trace, test, audit, user, report, serve, tui");
+    println!("Commands: init, doc, attach, risk, capa, ncr, change, review, baseline, git, soup, req, trace, test, audit, user, report, search, storage, project, daemon, serve, tui");
     println!("Use 'qms --help' for detailed help");
 }
 
@@ -406,10 +512,32 @@ fn print_help() {
     println!("    📋 Document Control (FDA 21 CFR Part 820.40):");
     println!("        init      Initialize a new QMS project with document structure");
     println!("        doc       Document lifecycle management (create, approve, version)");
+    println!("        attach    Versioned evidence files on documents, risks, requirements, tests, CAPAs");
     println!();
     println!("    ⚠️  Risk Management (ISO 14971):");
     println!("        risk      Risk analysis, FMEA, and mitigation tracking");
     println!();
+    println!("    🛠️  CAPA (FDA 21 CFR Part 820.100):");
+    println!("        capa      Corrective and preventive actions with signed closure");
+    println!();
+    println!("    🚧 Nonconformance (FDA 21 CFR Part 820.90):");
+    println!("        ncr       Nonconforming product/process with signed disposition");
+    println!();
+    println!("    🔀 Design Change Control (FDA 21 CFR Part 820.30(i)):");
+    println!("        change    Engineering change requests with CCB approval");
+    println!();
+    println!("    🧭 Design Reviews (FDA 21 CFR Part 820.30(e)):");
+    println!("        review    Design reviews with scope baseline and attendee sign-off");
+    println!();
+    println!("    📌 Configuration Baselines (ISO 13485 Section 7.5.9):");
+    println!("        baseline  Named release snapshots of the design record, compared per type");
+    println!();
+    println!("    🌿 Software Change History (IEC 62304 Section 8):");
+    println!("        git       Local Git commits, tags and files linked to QMS records");
+    println!();
+    println!("    🧩 SOUP Management (IEC 62304 Sections 5.3 and 8):");
+    println!("        soup      SOUP register from CycloneDX/SPDX SBOMs with anomaly evaluation");
+    println!();
     println!("    🔗 Requirements Traceability (ISO 13485 Section 7.3):");
     println!("        req       Requirements management and validation");
     println!("        trace     Bi-directional traceability matrices");
@@ -421,6 +549,10 @@ fn print_help() {
     println!("        audit     Audit trail management and integrity verification");
     println!("        user      User management with role-based access control");
     println!("        report    Regulatory compliance reports (DHF, CFR compliance)");
+    println!("        search    Full-text search across documents, requirements, risks and tests");
+    println!("        storage   Storage backend status and file/SQLite migration");
+    println!("        project   Encryption of project data at rest, rekey and recovery export");
+    println!("        daemon    Scheduled reports, log rotation, backups and risk escalation");
     println!();
     println!("    🌐 User Interfaces:");
     println!("        serve     Start web-based GUI for QMS operations");
@@ -0,0 +1,206 @@
+//! Attachment Record Model
+//!
+//! An attachment is a named evidence file on one QMS record. Uploading a file
+//! with the same name to the same record adds a version instead of replacing
+//! it, so every file that was ever submitted as evidence stays retrievable by
+//! its SHA-256 fingerprint.
+
+use crate::json_utils::{JsonError, JsonSerializable, JsonValue};
+use crate::modules::storage::{FileLayout, ProjectEntity, StorageEntity};
+use crate::prelude::*;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Kind of record an attachment belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AttachmentTarget {
+    Document,
+    Risk,
+    Requirement,
+    TestExecution,
+    Capa,
+}
+
+impl AttachmentTarget {
+    pub const ALL: [Self; 5] = [Self::Document, Self::Risk, Self::Requirement, Self::TestExecution, Self::Capa];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Document => "document",
+            Self::Risk => "risk",
+            Self::Requirement => "requirement",
+            Self::TestExecution => "test-execution",
+            Self::Capa => "capa",
+        }
+    }
+
+    /// Parse a record kind, accepting plurals and common abbreviations
+    pub fn parse(value: &str) -> QmsResult<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "document" | "documents" | "doc" | "docs" => Ok(Self::Document),
+            "risk" | "risks" => Ok(Self::Risk),
+            "requirement" | "requirements" | "req" | "reqs" => Ok(Self::Requirement),
+            "test-execution" | "test_execution" | "execution" | "executions" | "test" | "tests" => {
+                Ok(Self::TestExecution)
+            }
+            "capa" | "capas" => Ok(Self::Capa),
+            other => Err(QmsError::validation_error(&format!(
+                "Unknown record type '{other}'. Expected one of: document, risk, requirement, test-execution, capa"
+            ))),
+        }
+    }
+}
+
+/// One uploaded revision of an attachment
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttachmentVersion {
+    pub version: u32,
+    pub sha256: String,
+    pub size: u64,
+    pub mime_type: String,
+    pub original_filename: String,
+    pub uploaded_by: String,
+    pub uploaded_at: String,
+    pub comment: Option<String>,
+}
+
+/// Evidence file attached to a QMS record
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attachment {
+    pub id: String,                      // ATT-001, ATT-002...
+    pub target: AttachmentTarget,
+    pub record_id: String,
+    pub name: String,                    // Unique per record; new uploads under it add versions
+    pub versions: Vec<AttachmentVersion>,
+}
+
+impl Attachment {
+    /// Most recent version
+    pub fn latest(&self) -> Option<&AttachmentVersion> {
+        self.versions.last()
+    }
+
+    /// A specific version, or the latest when `version` is `None`
+    pub fn version(&self, version: Option<u32>) -> QmsResult<&AttachmentVersion> {
+        let found = match version {
+            Some(number) => self.versions.iter().find(|v| v.version == number),
+            None => self.latest(),
+        };
+        found.ok_or_else(|| {
+            QmsError::not_found(&format!(
+                "Attachment {} has no version {}",
+                self.id,
+                version.map_or_else(|| "on record".to_string(), |n| n.to_string())
+            ))
+        })
+    }
+}
+
+fn optional_string(value: &Option<String>) -> JsonValue {
+    value.as_ref().map_or(JsonValue::Null, |s| JsonValue::String(s.clone()))
+}
+
+fn get_string(obj: &HashMap<String, JsonValue>, field: &str) -> Result<String, JsonError> {
+    match obj.get(field) {
+        Some(JsonValue::String(s)) => Ok(s.clone()),
+        Some(_) => Err(JsonError::InvalidFormat(format!("Field '{field}' is not a string"))),
+        None => Err(JsonError::InvalidFormat(format!("Missing required field '{field}'"))),
+    }
+}
+
+fn get_number(obj: &HashMap<String, JsonValue>, field: &str) -> Result<f64, JsonError> {
+    match obj.get(field) {
+        Some(JsonValue::Number(n)) => Ok(*n),
+        _ => Err(JsonError::InvalidFormat(format!("Missing or invalid number '{field}'"))),
+    }
+}
+
+impl JsonSerializable for Attachment {
+    fn to_json(&self) -> String {
+        let versions = self
+            .versions
+            .iter()
+            .map(|v| {
+                let mut obj = HashMap::new();
+                obj.insert("version".to_string(), JsonValue::Number(f64::from(v.version)));
+                obj.insert("sha256".to_string(), JsonValue::String(v.sha256.clone()));
+                obj.insert("size".to_string(), JsonValue::Number(v.size as f64));
+                obj.insert("mime_type".to_string(), JsonValue::String(v.mime_type.clone()));
+                obj.insert("original_filename".to_string(), JsonValue::String(v.original_filename.clone()));
+                obj.insert("uploaded_by".to_string(), JsonValue::String(v.uploaded_by.clone()));
+                obj.insert("uploaded_at".to_string(), JsonValue::String(v.uploaded_at.clone()));
+                obj.insert("comment".to_string(), optional_string(&v.comment));
+                JsonValue::Object(obj)
+            })
+            .collect();
+
+        let mut obj = HashMap::new();
+        obj.insert("version".to_string(), JsonValue::String("1.0".to_string()));
+        obj.insert("id".to_string(), JsonValue::String(self.id.clone()));
+        obj.insert("target".to_string(), JsonValue::String(self.target.name().to_string()));
+        obj.insert("record_id".to_string(), JsonValue::String(self.record_id.clone()));
+        obj.insert("name".to_string(), JsonValue::String(self.name.clone()));
+        obj.insert("versions".to_string(), JsonValue::Array(versions));
+        JsonValue::Object(obj).json_to_string()
+    }
+
+    fn from_json(json: &str) -> Result<Self, JsonError> {
+        let obj = match JsonValue::parse(json)? {
+            JsonValue::Object(obj) => obj,
+            _ => return Err(JsonError::InvalidFormat("Expected JSON object".to_string())),
+        };
+
+        let mut versions = Vec::new();
+        if let Some(JsonValue::Array(items)) = obj.get("versions") {
+            for item in items {
+                let JsonValue::Object(v) = item else {
+                    return Err(JsonError::InvalidFormat("Attachment versions must be objects".to_string()));
+                };
+                versions.push(AttachmentVersion {
+                    version: get_number(v, "version")? as u32,
+                    sha256: get_string(v, "sha256")?,
+                    size: get_number(v, "size")? as u64,
+                    mime_type: get_string(v, "mime_type")?,
+                    original_filename: get_string(v, "original_filename")?,
+                    uploaded_by: get_string(v, "uploaded_by")?,
+                    uploaded_at: get_string(v, "uploaded_at")?,
+                    comment: v.get("comment").and_then(|c| c.as_string().cloned()),
+                });
+            }
+        }
+
+        Ok(Self {
+            id: get_string(&obj, "id")?,
+            target: AttachmentTarget::parse(&get_string(&obj, "target")?)
+                .map_err(|e| JsonError::InvalidFormat(e.to_string()))?,
+            record_id: get_string(&obj, "record_id")?,
+            name: get_string(&obj, "name")?,
+            versions,
+        })
+    }
+}
+
+impl StorageEntity for Attachment {
+    fn storage_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn to_storage_json(&self) -> String {
+        self.to_json()
+    }
+
+    fn from_storage_json(json: &str) -> QmsResult<Self> {
+        Self::from_json(json).map_err(|e| QmsError::parse_error(&format!("Invalid attachment record: {e}")))
+    }
+}
+
+impl ProjectEntity for Attachment {
+    const COLLECTION: &'static str = "attachments";
+
+    fn file_layout(project_path: &Path) -> FileLayout {
+        FileLayout::Directory {
+            dir: project_path.join("attachments").join("records"),
+            excluded: Vec::new(),
+        }
+    }
+}
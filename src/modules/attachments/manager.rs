@@ -0,0 +1,454 @@
+//! Attachment Manager
+//!
+//! Adds evidence files to documents, risks, requirements, test executions and
+//! CAPAs, keeps their versions and checks them against their fingerprints.
+//! Attachments are never deleted: a record's evidence can only be superseded
+//! by a newer version.
+
+use crate::modules::audit_logger::functions::audit_log_action;
+use crate::modules::capa::CapaManager;
+use crate::modules::document_control::service::DocumentService;
+use crate::modules::risk_manager::risk::RiskManager;
+use crate::modules::storage::{project_setting, EntityStore};
+use crate::modules::traceability::requirement::RequirementManager;
+use crate::modules::traceability::test_case::TestCaseManager;
+use crate::json_utils::JsonValue;
+use crate::prelude::*;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::attachment::{Attachment, AttachmentTarget, AttachmentVersion};
+use super::mime::detect_mime_type;
+use super::store::{blob_hash, BlobStore};
+
+/// Project setting holding the largest accepted file, in megabytes
+pub const MAX_SIZE_SETTING: &str = "attachment_max_size_mb";
+
+/// Size limit used when the project does not set one
+pub const DEFAULT_MAX_SIZE_MB: u64 = 50;
+
+/// Input for an upload
+#[derive(Debug, Clone)]
+pub struct NewAttachment {
+    pub target: AttachmentTarget,
+    pub record_id: String,
+    /// Name of the uploaded file; only its final component is kept
+    pub file_name: String,
+    /// Attachment name on the record, defaulting to the file name
+    pub name: Option<String>,
+    pub comment: Option<String>,
+}
+
+/// Result of checking every attachment version against the object store
+#[derive(Debug, Clone, Default)]
+pub struct AttachmentVerification {
+    pub versions_checked: usize,
+    pub problems: Vec<String>,
+}
+
+impl AttachmentVerification {
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Attachment version kept with a document backup
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackedUpAttachment {
+    pub id: String,
+    pub name: String,
+    pub version: u32,
+    pub sha256: String,
+}
+
+/// Attachment manager
+pub struct AttachmentManager {
+    project_path: PathBuf,
+    store: EntityStore<Attachment>,
+    objects: BlobStore,
+}
+
+impl AttachmentManager {
+    /// Create new attachment manager for a project
+    pub fn new(project_path: &Path) -> QmsResult<Self> {
+        Ok(Self {
+            project_path: project_path.to_path_buf(),
+            store: EntityStore::open(project_path)?,
+            objects: BlobStore::new(project_path),
+        })
+    }
+
+    /// Largest accepted file in bytes
+    pub fn max_size(&self) -> QmsResult<u64> {
+        let megabytes = match project_setting(&self.project_path, MAX_SIZE_SETTING)? {
+            Some(JsonValue::Number(mb)) if mb > 0.0 => mb as u64,
+            Some(_) => {
+                return Err(QmsError::validation_error(&format!(
+                    "Project setting '{MAX_SIZE_SETTING}' must be a positive number"
+                )))
+            }
+            None => DEFAULT_MAX_SIZE_MB,
+        };
+        Ok(megabytes * 1024 * 1024)
+    }
+
+    /// Attach a file to a record, or add a version when the record already has
+    /// an attachment of that name
+    pub fn add(&self, upload: NewAttachment, data: &[u8], uploaded_by: &str) -> QmsResult<Attachment> {
+        let file_name = base_name(&upload.file_name)?;
+        let name = match upload.name {
+            Some(ref name) => base_name(name)?,
+            None => file_name.clone(),
+        };
+        let max_size = self.max_size()?;
+        if data.len() as u64 > max_size {
+            return Err(QmsError::validation_error(&format!(
+                "{file_name} is {} bytes; the project limit is {max_size} bytes (setting '{MAX_SIZE_SETTING}')",
+                data.len()
+            )));
+        }
+        self.ensure_record_exists(upload.target, &upload.record_id)?;
+
+        let sha256 = blob_hash(data);
+        let existing = self
+            .list(Some(upload.target), Some(&upload.record_id))?
+            .into_iter()
+            .find(|attachment| attachment.name == name);
+        let mut attachment = match existing {
+            Some(attachment) => {
+                if let Some(latest) = attachment.latest().filter(|latest| latest.sha256 == sha256) {
+                    return Err(QmsError::validation_error(&format!(
+                        "{name} is identical to version {} of {}", latest.version, attachment.id
+                    )));
+                }
+                attachment
+            }
+            None => Attachment {
+                id: self.next_attachment_id()?,
+                target: upload.target,
+                record_id: upload.record_id.clone(),
+                name: name.clone(),
+                versions: Vec::new(),
+            },
+        };
+
+        self.objects.put(data)?;
+        let version = attachment.latest().map_or(1, |latest| latest.version + 1);
+        attachment.versions.push(AttachmentVersion {
+            version,
+            sha256: sha256.clone(),
+            size: data.len() as u64,
+            mime_type: detect_mime_type(data, &file_name),
+            original_filename: file_name,
+            uploaded_by: uploaded_by.to_string(),
+            uploaded_at: crate::utils::current_iso8601_timestamp(),
+            comment: upload.comment.filter(|comment| !comment.trim().is_empty()),
+        });
+        self.store.writer().save(&attachment)?;
+
+        audit_log_action(
+            if version == 1 { "ATTACHMENT_ADDED" } else { "ATTACHMENT_VERSION_ADDED" },
+            "Attachment",
+            &format!(
+                "{}|{}|{}|{}|v{version}|{sha256}",
+                attachment.id,
+                attachment.target.name(),
+                attachment.record_id,
+                attachment.name
+            ),
+        )?;
+
+        Ok(attachment)
+    }
+
+    /// Load an attachment record
+    pub fn get(&self, attachment_id: &str) -> QmsResult<Attachment> {
+        if !self.store.reader().exists(attachment_id)? {
+            return Err(QmsError::not_found(&format!("Attachment {attachment_id} not found")));
+        }
+        self.store.reader().read(attachment_id)
+    }
+
+    /// List attachments, optionally for one kind of record or one record, ordered by ID
+    pub fn list(&self, target: Option<AttachmentTarget>, record_id: Option<&str>) -> QmsResult<Vec<Attachment>> {
+        let mut attachments: Vec<Attachment> = self
+            .store
+            .reader()
+            .read_all()?
+            .into_iter()
+            .filter(|attachment| target.is_none() || target == Some(attachment.target))
+            .filter(|attachment| record_id.is_none() || record_id == Some(attachment.record_id.as_str()))
+            .collect();
+        attachments.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(attachments)
+    }
+
+    /// Contents of a version (the latest by default), verified against its fingerprint
+    pub fn read_content(&self, attachment_id: &str, version: Option<u32>) -> QmsResult<(Attachment, AttachmentVersion, Vec<u8>)> {
+        let attachment = self.get(attachment_id)?;
+        let selected = attachment.version(version)?.clone();
+        let data = self.objects.get(&selected.sha256)?;
+        Ok((attachment, selected, data))
+    }
+
+    /// Check that every version's file is present and matches its fingerprint and size
+    pub fn verify(&self) -> QmsResult<AttachmentVerification> {
+        let mut report = AttachmentVerification::default();
+        for attachment in self.list(None, None)? {
+            for version in &attachment.versions {
+                report.versions_checked += 1;
+                match self.objects.get(&version.sha256) {
+                    Ok(data) if data.len() as u64 != version.size => report.problems.push(format!(
+                        "{} v{}: size is {} bytes, recorded {}",
+                        attachment.id,
+                        version.version,
+                        data.len(),
+                        version.size
+                    )),
+                    Ok(_) => {}
+                    Err(e) => report.problems.push(format!("{} v{}: {e}", attachment.id, version.version)),
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// Copy the files attached to a record into the object store at `backup_root`
+    pub fn back_up(&self, target: AttachmentTarget, record_id: &str, backup_root: &Path) -> QmsResult<Vec<BackedUpAttachment>> {
+        let backup_store = BlobStore::at(backup_root);
+        let mut backed_up = Vec::new();
+        for attachment in self.list(Some(target), Some(record_id))? {
+            for version in &attachment.versions {
+                self.objects.copy_to(&version.sha256, &backup_store)?;
+                backed_up.push(BackedUpAttachment {
+                    id: attachment.id.clone(),
+                    name: attachment.name.clone(),
+                    version: version.version,
+                    sha256: version.sha256.clone(),
+                });
+            }
+        }
+        Ok(backed_up)
+    }
+
+    /// Put back files missing from the project or failing their check from the store at `backup_root`
+    ///
+    /// Returns the number of files restored.
+    pub fn restore(&self, hashes: &[String], backup_root: &Path) -> QmsResult<usize> {
+        let backup_store = BlobStore::at(backup_root);
+        let mut restored = 0;
+        for hash in hashes {
+            if self.objects.get(hash).is_ok() {
+                continue;
+            }
+            if self.objects.contains(hash) {
+                self.objects.remove(hash)?;
+            }
+            if backup_store.copy_to(hash, &self.objects)? {
+                restored += 1;
+            }
+        }
+        Ok(restored)
+    }
+
+    /// Write the latest version of every attachment to `<dir>/<type>/<record>/<name>`
+    /// with a `SHA256SUMS` file covering them
+    ///
+    /// Returns the number of files written.
+    pub fn export(&self, dir: &Path) -> QmsResult<usize> {
+        let mut sums = String::new();
+        let mut written = BTreeSet::new();
+        for attachment in self.list(None, None)? {
+            let Some(latest) = attachment.latest() else { continue };
+            let data = self.objects.get(&latest.sha256)?;
+            let relative = format!(
+                "{}/{}/{}",
+                attachment.target.name(),
+                path_component(&attachment.record_id),
+                path_component(&attachment.name)
+            );
+            if !written.insert(relative.clone()) {
+                continue;
+            }
+            let path = dir.join(&relative);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, &data)?;
+            sums.push_str(&format!("{}  {relative}\n", latest.sha256));
+        }
+        if !written.is_empty() {
+            fs::write(dir.join("SHA256SUMS"), sums)?;
+        }
+        Ok(written.len())
+    }
+
+    fn ensure_record_exists(&self, target: AttachmentTarget, record_id: &str) -> QmsResult<()> {
+        let path = self.project_path.as_path();
+        let exists = match target {
+            AttachmentTarget::Document => DocumentService::new(self.project_path.clone()).read_document(record_id).is_ok(),
+            AttachmentTarget::Risk => RiskManager::new(path)?.load_risk(record_id).is_ok(),
+            AttachmentTarget::Requirement => {
+                RequirementManager::new(path)?.get_requirement_by_req_id(record_id).is_some()
+            }
+            AttachmentTarget::TestExecution => {
+                path.join("tests").is_dir()
+                    && TestCaseManager::new(path)?.list_test_cases().iter().any(|test_case| {
+                        test_case.execution_results.iter().any(|execution| execution.execution_id == record_id)
+                    })
+            }
+            AttachmentTarget::Capa => CapaManager::new(path)?.capa_exists(record_id),
+        };
+        if exists {
+            Ok(())
+        } else {
+            Err(QmsError::not_found(&format!("{} {record_id} not found", target.name())))
+        }
+    }
+
+    fn next_attachment_id(&self) -> QmsResult<String> {
+        let highest = self
+            .store
+            .reader()
+            .read_all()?
+            .iter()
+            .filter_map(|attachment| attachment.id.strip_prefix("ATT-").and_then(|n| n.parse::<u32>().ok()))
+            .max()
+            .unwrap_or(0);
+        Ok(format!("ATT-{:03}", highest + 1))
+    }
+}
+
+/// Final path component of an uploaded file name
+fn base_name(file_name: &str) -> QmsResult<String> {
+    let name = file_name.rsplit(['/', '\\']).next().unwrap_or_default().trim();
+    if name.is_empty() || name == "." || name == ".." || name.chars().any(char::is_control) {
+        return Err(QmsError::validation_error(&format!("Invalid attachment name '{file_name}'")));
+    }
+    Ok(name.to_string())
+}
+
+/// A record ID or name made safe to use as a single path component
+fn path_component(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '.' | '-' | '_' | ' ') { c } else { '_' })
+        .collect::<String>()
+        .trim_start_matches('.')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::audit_logger::{initialize_audit_system, AuditConfig};
+    use crate::models::{Permission, Role};
+    use crate::modules::capa::{CapaPriority, CapaSource, CapaType, NewCapa};
+    use crate::modules::user_manager::FileAuthManager;
+    use tempfile::TempDir;
+
+    fn project_with_capa() -> (TempDir, String) {
+        let temp_dir = TempDir::new().unwrap();
+        let project_path = temp_dir.path();
+        let _ = initialize_audit_system(AuditConfig {
+            project_path: project_path.to_string_lossy().to_string(),
+            retention_days: 30,
+            daily_rotation: false,
+            max_file_size_mb: 10,
+            require_checksums: false,
+        });
+        fs::create_dir_all(project_path.join("config")).unwrap();
+        fs::write(project_path.join("config").join("config.json"), r#"{"version":"1.0","data":{}}"#).unwrap();
+        FileAuthManager::from_project_path(project_path)
+            .unwrap()
+            .add_user("quality", "password123", Some(vec![Role {
+                name: "QualityEngineer".to_string(),
+                permissions: vec![Permission::WriteDocuments],
+            }]))
+            .unwrap();
+        let capa = CapaManager::new(project_path)
+            .unwrap()
+            .create_capa(
+                NewCapa {
+                    title: "Occlusion alarm late".to_string(),
+                    description: "Alarm raised after 45 s".to_string(),
+                    source: CapaSource::Complaint,
+                    source_reference: None,
+                    capa_type: CapaType::Corrective,
+                    priority: CapaPriority::High,
+                    owner: "qa".to_string(),
+                    due_date: None,
+                },
+                "quality",
+            )
+            .unwrap();
+        (temp_dir, capa.id)
+    }
+
+    fn upload(record_id: &str, file_name: &str) -> NewAttachment {
+        NewAttachment {
+            target: AttachmentTarget::Capa,
+            record_id: record_id.to_string(),
+            file_name: file_name.to_string(),
+            name: None,
+            comment: Some("Bench test".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_versions_fingerprints_and_verification() {
+        let (temp_dir, capa_id) = project_with_capa();
+        let project_path = temp_dir.path();
+        let manager = AttachmentManager::new(project_path).unwrap();
+
+        let first = manager.add(upload(&capa_id, "/tmp/alarm-test.log"), b"alarm after 28 s: PASS\n", "qa").unwrap();
+        assert_eq!(first.id, "ATT-001");
+        assert_eq!(first.name, "alarm-test.log");
+        assert_eq!(first.versions[0].mime_type, "text/plain");
+        assert_eq!(first.versions[0].sha256, blob_hash(b"alarm after 28 s: PASS\n"));
+
+        // Same name on the same record adds a version; identical content is refused
+        let second = manager.add(upload(&capa_id, "alarm-test.log"), b"alarm after 27 s: PASS\n", "qa").unwrap();
+        assert_eq!(second.id, "ATT-001");
+        assert_eq!(second.versions.len(), 2);
+        assert!(manager.add(upload(&capa_id, "alarm-test.log"), b"alarm after 27 s: PASS\n", "qa").is_err());
+
+        let (_, version, data) = manager.read_content("ATT-001", Some(1)).unwrap();
+        assert_eq!(version.version, 1);
+        assert_eq!(data, b"alarm after 28 s: PASS\n");
+        assert_eq!(manager.read_content("ATT-001", None).unwrap().2, b"alarm after 27 s: PASS\n");
+
+        let png = manager.add(upload(&capa_id, "screen.png"), b"\x89PNG\r\n\x1a\n\x00\x00", "qa").unwrap();
+        assert_eq!(png.id, "ATT-002");
+        assert_eq!(png.versions[0].mime_type, "image/png");
+        assert_eq!(manager.list(Some(AttachmentTarget::Capa), Some(&capa_id)).unwrap().len(), 2);
+        assert!(manager.list(Some(AttachmentTarget::Risk), None).unwrap().is_empty());
+
+        // Unknown records and oversized files are rejected
+        assert!(manager.add(upload("CAPA-999", "x.log"), b"x", "qa").is_err());
+        crate::modules::storage::set_project_setting(project_path, MAX_SIZE_SETTING, JsonValue::Number(1.0)).unwrap();
+        let error = manager.add(upload(&capa_id, "big.bin"), &vec![7u8; 2 * 1024 * 1024], "qa").unwrap_err();
+        assert!(error.to_string().contains("limit"), "{error}");
+
+        assert!(manager.verify().unwrap().is_valid());
+        let object = project_path.join("attachments/objects").join(&png.versions[0].sha256[..2]).join(&png.versions[0].sha256[2..]);
+        let backup_root = project_path.join("backups/attachments/objects");
+        assert_eq!(manager.back_up(AttachmentTarget::Capa, &capa_id, &backup_root).unwrap().len(), 3);
+
+        fs::write(&object, b"tampered").unwrap();
+        let report = manager.verify().unwrap();
+        assert_eq!(report.versions_checked, 3);
+        assert_eq!(report.problems.len(), 1);
+        assert!(manager.read_content("ATT-002", None).is_err());
+
+        assert_eq!(manager.restore(&[png.versions[0].sha256.clone()], &backup_root).unwrap(), 1);
+        assert!(manager.verify().unwrap().is_valid());
+
+        let export = temp_dir.path().join("export");
+        assert_eq!(manager.export(&export).unwrap(), 2);
+        let sums = fs::read_to_string(export.join("SHA256SUMS")).unwrap();
+        assert!(sums.contains(&format!("capa/{capa_id}/screen.png")), "{sums}");
+        assert_eq!(fs::read(export.join("capa").join(&capa_id).join("alarm-test.log")).unwrap(), b"alarm after 27 s: PASS\n");
+    }
+}
@@ -0,0 +1,141 @@
+//! MIME type detection for attachments
+//!
+//! The type is taken from the file's leading bytes where the format has a
+//! signature, so a renamed file is still recognised. The extension only
+//! refines formats the signature cannot tell apart (ZIP-based Office files,
+//! plain-text formats) or names formats that have no signature.
+
+use std::path::Path;
+
+/// Type recorded when nothing more specific is known
+pub const DEFAULT_MIME_TYPE: &str = "application/octet-stream";
+
+/// Leading-byte signatures, checked in order
+const SIGNATURES: &[(&[u8], &str)] = &[
+    (b"%PDF-", "application/pdf"),
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"II*\x00", "image/tiff"),
+    (b"MM\x00*", "image/tiff"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"\xd0\xcf\x11\xe0\xa1\xb1\x1a\xe1", "application/x-ole-storage"),
+    (b"SQLite format 3\x00", "application/vnd.sqlite3"),
+    (b"ISO-10303-21;", "model/step"),
+    (b"AC10", "image/vnd.dwg"),
+];
+
+/// Types known by extension
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("pdf", "application/pdf"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("tif", "image/tiff"),
+    ("tiff", "image/tiff"),
+    ("bmp", "image/bmp"),
+    ("svg", "image/svg+xml"),
+    ("zip", "application/zip"),
+    ("gz", "application/gzip"),
+    ("docx", "application/vnd.openxmlformats-officedocument.wordprocessingml.document"),
+    ("xlsx", "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+    ("pptx", "application/vnd.openxmlformats-officedocument.presentationml.presentation"),
+    ("doc", "application/msword"),
+    ("xls", "application/vnd.ms-excel"),
+    ("step", "model/step"),
+    ("stp", "model/step"),
+    ("stl", "model/stl"),
+    ("iges", "model/iges"),
+    ("igs", "model/iges"),
+    ("dxf", "image/vnd.dxf"),
+    ("dwg", "image/vnd.dwg"),
+    ("txt", "text/plain"),
+    ("log", "text/plain"),
+    ("tap", "text/plain"),
+    ("md", "text/markdown"),
+    ("csv", "text/csv"),
+    ("html", "text/html"),
+    ("htm", "text/html"),
+    ("xml", "application/xml"),
+    ("json", "application/json"),
+];
+
+/// MIME type of `data`, using `file_name` to refine or fill in the signature
+pub fn detect_mime_type(data: &[u8], file_name: &str) -> String {
+    let by_extension = Path::new(file_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_lowercase)
+        .and_then(|ext| EXTENSIONS.iter().find(|(known, _)| *known == ext).map(|(_, mime)| *mime));
+
+    if let Some((_, signature_type)) = SIGNATURES.iter().find(|(signature, _)| data.starts_with(signature)) {
+        // Containers: the extension says what is inside
+        let container = matches!(*signature_type, "application/zip" | "application/x-ole-storage");
+        return match by_extension {
+            Some(mime) if container && mime != "application/gzip" => mime.to_string(),
+            _ => (*signature_type).to_string(),
+        };
+    }
+
+    if looks_like_text(data) {
+        let start = data.iter().position(|byte| !byte.is_ascii_whitespace()).unwrap_or(data.len());
+        return match by_extension {
+            Some(mime) if is_text_only(mime) || matches!(mime, "model/stl" | "image/vnd.dxf") => mime.to_string(),
+            _ if data[start..].starts_with(b"<?xml") => "application/xml".to_string(),
+            _ if data.starts_with(b"solid ") => "model/stl".to_string(),
+            _ => "text/plain".to_string(),
+        };
+    }
+
+    match by_extension {
+        // A binary file named like a text format is not that format
+        Some(mime) if !is_text_only(mime) => mime.to_string(),
+        _ => DEFAULT_MIME_TYPE.to_string(),
+    }
+}
+
+/// Formats that are always text
+fn is_text_only(mime: &str) -> bool {
+    mime.starts_with("text/") || matches!(mime, "application/xml" | "application/json" | "image/svg+xml" | "model/iges")
+}
+
+/// UTF-8 without NUL bytes in the first 8 KiB
+fn looks_like_text(data: &[u8]) -> bool {
+    let sample = &data[..data.len().min(8192)];
+    if sample.contains(&0) {
+        return false;
+    }
+    match std::str::from_utf8(sample) {
+        Ok(_) => true,
+        // The sample may end inside a multi-byte character
+        Err(error) => error.error_len().is_none() && sample.len() == 8192,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_mime_type() {
+        assert_eq!(detect_mime_type(b"%PDF-1.7\n...", "signed.bin"), "application/pdf");
+        assert_eq!(detect_mime_type(b"\x89PNG\r\n\x1a\n\x00\x00", "screenshot.png"), "image/png");
+        // Renamed files are identified by content
+        assert_eq!(detect_mime_type(b"\xff\xd8\xff\xe0\x00\x10JFIF", "photo.txt"), "image/jpeg");
+        assert_eq!(
+            detect_mime_type(b"PK\x03\x04\x14\x00", "report.docx"),
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+        );
+        assert_eq!(detect_mime_type(b"PK\x03\x04\x14\x00", "bundle.bin"), "application/zip");
+        assert_eq!(detect_mime_type(b"ISO-10303-21;\nHEADER;", "housing.stp"), "model/step");
+        assert_eq!(detect_mime_type(b"test result: ok. 12 passed", "run.log"), "text/plain");
+        assert_eq!(detect_mime_type(b"id,result\nTC-1,pass\n", "results.csv"), "text/csv");
+        assert_eq!(detect_mime_type(b"<?xml version=\"1.0\"?><testsuite/>", "junit"), "application/xml");
+        assert_eq!(detect_mime_type(b"\x00\x01\x02\x03", "results.csv"), DEFAULT_MIME_TYPE);
+        assert_eq!(detect_mime_type(b"\x00\x01\x02\x03", "enclosure.stl"), "model/stl");
+        assert_eq!(detect_mime_type(b"", "empty"), "text/plain");
+    }
+}
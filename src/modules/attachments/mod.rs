@@ -0,0 +1,29 @@
+//! Attachments Module
+//!
+//! Binary evidence files (test logs, screenshots, signed PDFs, CAD exports)
+//! attached to documents, risks, requirements, test executions and CAPAs.
+//! Files are fingerprinted with SHA-256, typed by content, limited in size,
+//! versioned under their name on the record, included in document backups and
+//! listed in the DHF as objective evidence.
+
+pub mod attachment;
+pub mod manager;
+pub mod mime;
+pub mod store;
+
+#[allow(unused_imports)]
+pub use attachment::{Attachment, AttachmentTarget, AttachmentVersion};
+#[allow(unused_imports)]
+pub use manager::{
+    AttachmentManager, AttachmentVerification, BackedUpAttachment, NewAttachment, DEFAULT_MAX_SIZE_MB,
+    MAX_SIZE_SETTING,
+};
+#[allow(unused_imports)]
+pub use mime::detect_mime_type;
+#[allow(unused_imports)]
+pub use store::{blob_hash, BlobStore};
+
+/// Whether any file has been attached in the project
+pub fn has_attachments(project_path: &std::path::Path) -> bool {
+    project_path.join("attachments").join("objects").is_dir()
+}
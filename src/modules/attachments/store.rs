@@ -0,0 +1,130 @@
+//! Content-addressed store for attachment files
+//!
+//! Files live in `attachments/objects/`, one per distinct content, named by the
+//! SHA-256 of the bytes (`objects/ab/cdef…`). Uploading the same file twice, or
+//! to two records, stores it once. Objects are sealed inside an encrypted
+//! project and re-hashed on every read.
+
+use crate::error::{QmsError, QmsResult};
+use crate::modules::document_control::content_store::is_hash;
+use crate::modules::storage::encryption;
+use crate::utils::encode_hex;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// SHA-256 of `data` as lowercase hex
+pub fn blob_hash(data: &[u8]) -> String {
+    encode_hex(&Sha256::digest(data))
+}
+
+/// Content-addressed object store for binary files
+pub struct BlobStore {
+    root: PathBuf,
+}
+
+impl BlobStore {
+    /// The project's attachment store
+    pub fn new(project_path: &Path) -> Self {
+        Self::at(&project_path.join("attachments").join("objects"))
+    }
+
+    /// A store rooted at `root`, e.g. a copy kept with backups
+    pub fn at(root: &Path) -> Self {
+        Self { root: root.to_path_buf() }
+    }
+
+    fn object_path(&self, hash: &str) -> PathBuf {
+        self.root.join(&hash[..2]).join(&hash[2..])
+    }
+
+    /// Whether an object with this hash is stored
+    pub fn contains(&self, hash: &str) -> bool {
+        is_hash(hash) && self.object_path(hash).is_file()
+    }
+
+    /// Store `data` and return its hash; data already present is not written again
+    pub fn put(&self, data: &[u8]) -> QmsResult<String> {
+        let hash = blob_hash(data);
+        let path = self.object_path(&hash);
+        if path.is_file() {
+            return Ok(hash);
+        }
+        let dir = path.parent().expect("object paths have a fan-out directory");
+        fs::create_dir_all(dir)?;
+
+        // Write under a temporary name so a crash never leaves a truncated object;
+        // the seal is bound to the object's final path
+        let temp_path = dir.join(format!("{}.tmp-{}", &hash[2..], std::process::id()));
+        fs::write(&temp_path, encryption::seal_bytes(&path, data)?)?;
+        fs::rename(&temp_path, &path)?;
+        Ok(hash)
+    }
+
+    /// Read an object, verifying that its contents still match its hash
+    pub fn get(&self, hash: &str) -> QmsResult<Vec<u8>> {
+        if !is_hash(hash) {
+            return Err(QmsError::validation_error(&format!("Invalid object hash '{hash}'")));
+        }
+        let path = self.object_path(hash);
+        if !path.is_file() {
+            return Err(QmsError::not_found(&format!("Attachment object {hash} is missing")));
+        }
+        let data = encryption::read(&path)?;
+        if blob_hash(&data) != hash {
+            return Err(QmsError::validation_error(&format!(
+                "Attachment object {hash} failed its integrity check (contents do not match the hash)"
+            )));
+        }
+        Ok(data)
+    }
+
+    /// Copy an object into `other`, sealed for its new path in an encrypted project
+    ///
+    /// Returns whether anything was copied; objects `other` already holds are skipped.
+    pub fn copy_to(&self, hash: &str, other: &BlobStore) -> QmsResult<bool> {
+        if other.contains(hash) {
+            return Ok(false);
+        }
+        let data = self.get(hash)?;
+        let target = other.object_path(hash);
+        let dir = target.parent().expect("object paths have a fan-out directory");
+        fs::create_dir_all(dir)?;
+        let temp_path = dir.join(format!("{}.tmp-{}", &hash[2..], std::process::id()));
+        fs::write(&temp_path, encryption::seal_bytes(&target, &data)?)?;
+        fs::rename(&temp_path, &target)?;
+        Ok(true)
+    }
+
+    /// Delete an object, e.g. one that failed its integrity check before it is restored
+    pub fn remove(&self, hash: &str) -> QmsResult<()> {
+        if !is_hash(hash) {
+            return Err(QmsError::validation_error(&format!("Invalid object hash '{hash}'")));
+        }
+        fs::remove_file(self.object_path(hash))?;
+        Ok(())
+    }
+
+    /// Hashes of every stored object
+    pub fn list(&self) -> QmsResult<BTreeSet<String>> {
+        let mut hashes = BTreeSet::new();
+        if !self.root.is_dir() {
+            return Ok(hashes);
+        }
+        for fan_out in fs::read_dir(&self.root)? {
+            let fan_out = fan_out?;
+            if !fan_out.file_type()?.is_dir() {
+                continue;
+            }
+            let prefix = fan_out.file_name().to_string_lossy().into_owned();
+            for entry in fs::read_dir(fan_out.path())? {
+                let hash = format!("{prefix}{}", entry?.file_name().to_string_lossy());
+                if is_hash(&hash) {
+                    hashes.insert(hash);
+                }
+            }
+        }
+        Ok(hashes)
+    }
+}
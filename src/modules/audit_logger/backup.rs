@@ -186,7 +186,7 @@ impl AuditBackupManager {
 
     /// Backup a single file
     fn backup_file(&self, source_path: &Path, backup_path: &Path, stats: &mut BackupStats) -> QmsResult<()> {
-        let file_content = fs::read(source_path)
+        let file_content = crate::modules::storage::encryption::read(source_path)
             .map_err(|e| QmsError::io_error(&format!("Failed to read source file: {e}")))?;
 
         let original_size = file_content.len() as u64;
@@ -197,13 +197,13 @@ impl AuditBackupManager {
             let compressed_content = self.compress_data(&file_content)?;
             let compressed_path = backup_path.with_extension("log.gz");
             
-            fs::write(&compressed_path, &compressed_content)
+            crate::modules::storage::encryption::write_bytes(&compressed_path, &compressed_content)
                 .map_err(|e| QmsError::io_error(&format!("Failed to write compressed backup: {e}")))?;
 
             stats.bytes_compressed += compressed_content.len() as u64;
         } else {
             // Copy file without compression
-            fs::write(backup_path, &file_content)
+            crate::modules::storage::encryption::copy(source_path, backup_path)
                 .map_err(|e| QmsError::io_error(&format!("Failed to write backup: {e}")))?;
 
             stats.bytes_compressed += original_size;
@@ -337,7 +337,7 @@ impl AuditBackupManager {
         let metadata_file = metadata_dir.join(format!("{}.json", backup_info.backup_id));
         let metadata_json = self.backup_info_to_json(backup_info)?;
 
-        fs::write(metadata_file, metadata_json)
+        crate::modules::storage::encryption::write(metadata_file, metadata_json)
             .map_err(|e| QmsError::io_error(&format!("Failed to save backup metadata: {e}")))?;
 
         Ok(())
@@ -393,7 +393,7 @@ impl AuditBackupManager {
 
     /// Load backup metadata from file
     fn load_backup_metadata(&self, metadata_path: &Path) -> QmsResult<BackupInfo> {
-        let content = fs::read_to_string(metadata_path)
+        let content = crate::modules::storage::encryption::read_to_string(metadata_path)
             .map_err(|e| QmsError::io_error(&format!("Failed to read metadata file: {e}")))?;
 
         self.parse_backup_info_json(&content)
@@ -546,14 +546,14 @@ impl AuditBackupManager {
                 self.copy_directory(&source_path, &dest_path)?;
             } else if source_path.extension().is_some_and(|ext| ext == "gz") {
                 // Decompress file
-                let compressed_content = fs::read(&source_path)
+                let compressed_content = crate::modules::storage::encryption::read(&source_path)
                     .map_err(|e| QmsError::io_error(&format!("Failed to read compressed file: {e}")))?;
                 let decompressed_content = self.decompress_data(&compressed_content)?;
                 let original_path = dest_path.with_extension("");
-                fs::write(&original_path, decompressed_content)
+                crate::modules::storage::encryption::write(&original_path, String::from_utf8_lossy(&decompressed_content))
                     .map_err(|e| QmsError::io_error(&format!("Failed to write decompressed file: {e}")))?;
             } else {
-                fs::copy(&source_path, &dest_path)
+                crate::modules::storage::encryption::copy(&source_path, &dest_path)
                     .map_err(|e| QmsError::io_error(&format!("Failed to copy file: {e}")))?;
             }
         }
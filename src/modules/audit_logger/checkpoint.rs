@@ -0,0 +1,796 @@
+// Signed Audit Checkpoints
+// Anchors the audit hash chain with periodic Merkle roots signed by a server key,
+// so a chain rewritten end-to-end or truncated after the fact no longer verifies.
+//
+// The signing seed lives in a key store outside the project. The project only
+// carries the public key, and verification trusts it only when its fingerprint
+// matches the key store or a fingerprint the verifier recorded out of band, so
+// whoever can write the audit directory cannot swap in a key of their own.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use ed25519_dalek::{Signature, SigningKey, Verifier, VerifyingKey};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use crate::error::{QmsError, QmsResult};
+use crate::json_utils::{JsonSerializable, JsonValue};
+use crate::models::AuditEntry;
+use crate::modules::user_manager::signing_keys::{key_fingerprint, sign_message};
+use crate::utils::{current_timestamp, decode_hex, encode_hex};
+
+const CHECKPOINT_FORMAT: &str = "qms-audit-checkpoint-v1";
+
+/// Environment variable naming the checkpoint key store directory
+pub const KEY_DIR_ENV: &str = "QMS_CHECKPOINT_KEY_DIR";
+
+/// A signed statement that a contiguous range of audit log entries existed
+/// with a given Merkle root at the time the checkpoint was taken
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditCheckpoint {
+    pub sequence: u64,
+    pub date: String,                        // Day the checkpoint covers (YYYY-MM-DD)
+    pub created_at: u64,
+    pub first_entry: usize,                  // Index of the first covered line in audit.log
+    pub entry_count: usize,
+    pub merkle_root: String,                 // SHA-256 Merkle root of the covered lines (hex)
+    pub last_entry_hash: String,             // Chain checksum of the last covered entry
+    pub previous_checkpoint: Option<String>, // Hash of the preceding checkpoint
+    pub daily_file: Option<String>,          // Rotated daily file name, if any
+    pub daily_file_sha256: Option<String>,
+    pub key_fingerprint: String,
+    pub signature: String,                   // Ed25519 signature over the manifest (hex)
+}
+
+/// Kind of problem found while verifying checkpoints
+#[derive(Debug, Clone, PartialEq)]
+pub enum CheckpointIssueKind {
+    InvalidSignature,
+    BrokenCheckpointChain,
+    MerkleRootMismatch,
+    Truncated,
+    MissingDailyFile,
+    DailyFileModified,
+    UnanchoredDailyFile,
+    BrokenTailLink,
+}
+
+/// A single checkpoint verification failure
+#[derive(Debug, Clone)]
+pub struct CheckpointIssue {
+    pub sequence: Option<u64>,
+    pub kind: CheckpointIssueKind,
+    pub detail: String,
+}
+
+/// Result of verifying the audit trail against its signed checkpoints
+#[derive(Debug, Clone)]
+pub struct CheckpointVerificationResult {
+    pub is_valid: bool,
+    pub checkpoints_verified: usize,
+    pub entries_anchored: usize,
+    pub entries_after_last_checkpoint: usize,
+    pub issues: Vec<CheckpointIssue>,
+}
+
+impl AuditCheckpoint {
+    /// Canonical byte string covered by the server signature
+    pub fn signed_manifest(&self) -> String {
+        format!(
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
+            CHECKPOINT_FORMAT,
+            self.sequence,
+            self.date,
+            self.created_at,
+            self.first_entry,
+            self.entry_count,
+            self.merkle_root,
+            self.last_entry_hash,
+            self.previous_checkpoint.as_deref().unwrap_or(""),
+            self.daily_file.as_deref().unwrap_or(""),
+            self.daily_file_sha256.as_deref().unwrap_or("")
+        )
+    }
+
+    /// Hash linking this checkpoint to the next one
+    pub fn checkpoint_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.signed_manifest().as_bytes());
+        hasher.update(self.signature.as_bytes());
+        encode_hex(&hasher.finalize())
+    }
+
+    /// Index one past the last covered line
+    pub const fn end_entry(&self) -> usize {
+        self.first_entry + self.entry_count
+    }
+
+    fn verify_signature(&self, key: &VerifyingKey) -> bool {
+        if self.key_fingerprint != key_fingerprint(key) {
+            return false;
+        }
+        let bytes: [u8; 64] = match decode_hex(&self.signature).ok().and_then(|b| b.try_into().ok()) {
+            Some(bytes) => bytes,
+            None => return false,
+        };
+        key.verify(self.signed_manifest().as_bytes(), &Signature::from_bytes(&bytes)).is_ok()
+    }
+
+    fn to_json(&self) -> String {
+        let optional = |value: &Option<String>| value.clone().map_or(JsonValue::Null, JsonValue::String);
+        let mut obj = HashMap::new();
+        obj.insert("format".to_string(), JsonValue::String(CHECKPOINT_FORMAT.to_string()));
+        obj.insert("sequence".to_string(), JsonValue::Number(self.sequence as f64));
+        obj.insert("date".to_string(), JsonValue::String(self.date.clone()));
+        obj.insert("created_at".to_string(), JsonValue::Number(self.created_at as f64));
+        obj.insert("first_entry".to_string(), JsonValue::Number(self.first_entry as f64));
+        obj.insert("entry_count".to_string(), JsonValue::Number(self.entry_count as f64));
+        obj.insert("merkle_root".to_string(), JsonValue::String(self.merkle_root.clone()));
+        obj.insert("last_entry_hash".to_string(), JsonValue::String(self.last_entry_hash.clone()));
+        obj.insert("previous_checkpoint".to_string(), optional(&self.previous_checkpoint));
+        obj.insert("daily_file".to_string(), optional(&self.daily_file));
+        obj.insert("daily_file_sha256".to_string(), optional(&self.daily_file_sha256));
+        obj.insert("key_fingerprint".to_string(), JsonValue::String(self.key_fingerprint.clone()));
+        obj.insert("signature".to_string(), JsonValue::String(self.signature.clone()));
+        JsonValue::Object(obj).json_to_string()
+    }
+
+    fn from_json(json: &str) -> QmsResult<Self> {
+        let obj = match JsonValue::parse(json)? {
+            JsonValue::Object(obj) => obj,
+            _ => return Err(QmsError::parse_error("Checkpoint must be a JSON object")),
+        };
+        let string = |field: &str| -> QmsResult<String> {
+            obj.get(field)
+                .and_then(JsonValue::as_string)
+                .cloned()
+                .ok_or_else(|| QmsError::parse_error(&format!("Checkpoint missing field: {field}")))
+        };
+        let number = |field: &str| -> QmsResult<f64> {
+            obj.get(field)
+                .and_then(JsonValue::as_number)
+                .ok_or_else(|| QmsError::parse_error(&format!("Checkpoint missing field: {field}")))
+        };
+        let optional = |field: &str| obj.get(field).and_then(JsonValue::as_string).cloned();
+
+        Ok(Self {
+            sequence: number("sequence")? as u64,
+            date: string("date")?,
+            created_at: number("created_at")? as u64,
+            first_entry: number("first_entry")? as usize,
+            entry_count: number("entry_count")? as usize,
+            merkle_root: string("merkle_root")?,
+            last_entry_hash: string("last_entry_hash")?,
+            previous_checkpoint: optional("previous_checkpoint"),
+            daily_file: optional("daily_file"),
+            daily_file_sha256: optional("daily_file_sha256"),
+            key_fingerprint: string("key_fingerprint")?,
+            signature: string("signature")?,
+        })
+    }
+}
+
+/// Compute the SHA-256 Merkle root of a list of audit log lines.
+/// Leaves and interior nodes are domain-separated; an odd node is promoted unchanged.
+pub fn merkle_root(lines: &[&str]) -> String {
+    if lines.is_empty() {
+        return encode_hex(&Sha256::digest(b""));
+    }
+
+    let mut level: Vec<[u8; 32]> = lines
+        .iter()
+        .map(|line| {
+            let mut hasher = Sha256::new();
+            hasher.update([0x00]);
+            hasher.update(line.as_bytes());
+            hasher.finalize().into()
+        })
+        .collect();
+
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => {
+                    let mut hasher = Sha256::new();
+                    hasher.update([0x01]);
+                    hasher.update(left);
+                    hasher.update(right);
+                    hasher.finalize().into()
+                }
+                [single] => *single,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+
+    encode_hex(&level[0])
+}
+
+/// Checkpoint the current audit log (entries since the previous checkpoint),
+/// signing with the project's seed in `key_dir`
+pub fn create_checkpoint(project_path: &Path, key_dir: &Path, date: &str) -> QmsResult<AuditCheckpoint> {
+    let log_path = project_path.join("audit").join("audit.log");
+    let content = crate::modules::storage::encryption::read_to_string(&log_path)
+        .map_err(|e| QmsError::io_error(&format!("Cannot read audit log: {e}")))?;
+    write_checkpoint(project_path, key_dir, date, &content, None)
+}
+
+/// Checkpoint a rotated daily log. `snapshot` is the uncompressed content that
+/// was rotated and `daily_file` the artifact left in `audit/daily`.
+pub fn create_rotation_checkpoint(
+    project_path: &Path,
+    key_dir: &Path,
+    date: &str,
+    snapshot: &str,
+    daily_file: &Path,
+) -> QmsResult<AuditCheckpoint> {
+    write_checkpoint(project_path, key_dir, date, snapshot, Some(daily_file))
+}
+
+/// Number of entries in `content` not yet covered by a checkpoint
+pub fn unanchored_entries(project_path: &Path, content: &str) -> QmsResult<usize> {
+    let anchored = load_checkpoints(project_path)?.last().map_or(0, AuditCheckpoint::end_entry);
+    Ok(log_lines(content).len().saturating_sub(anchored))
+}
+
+fn write_checkpoint(
+    project_path: &Path,
+    key_dir: &Path,
+    date: &str,
+    content: &str,
+    daily_file: Option<&Path>,
+) -> QmsResult<AuditCheckpoint> {
+    let lines = log_lines(content);
+    let previous = load_checkpoints(project_path)?.pop();
+    let first_entry = previous.as_ref().map_or(0, AuditCheckpoint::end_entry);
+
+    if lines.len() <= first_entry {
+        return Err(QmsError::validation_error("No new audit entries since the last checkpoint"));
+    }
+
+    let covered = &lines[first_entry..];
+    let last_entry_hash = AuditEntry::from_json(covered[covered.len() - 1])
+        .map(|entry| entry.checksum)
+        .map_err(|e| QmsError::parse_error(&format!("Last audit entry is malformed: {e}")))?;
+
+    let (daily_name, daily_hash) = match daily_file {
+        Some(path) => {
+            let bytes = crate::modules::storage::encryption::read(path)
+                .map_err(|e| QmsError::io_error(&format!("Cannot read daily log {}: {e}", path.display())))?;
+            (
+                path.file_name().map(|n| n.to_string_lossy().to_string()),
+                Some(encode_hex(&Sha256::digest(&bytes))),
+            )
+        }
+        None => (None, None),
+    };
+
+    let signing_key = load_or_create_server_key(project_path, key_dir)?;
+    let mut checkpoint = AuditCheckpoint {
+        sequence: previous.as_ref().map_or(1, |p| p.sequence + 1),
+        date: date.to_string(),
+        created_at: current_timestamp(),
+        first_entry,
+        entry_count: covered.len(),
+        merkle_root: merkle_root(covered),
+        last_entry_hash,
+        previous_checkpoint: previous.as_ref().map(AuditCheckpoint::checkpoint_hash),
+        daily_file: daily_name,
+        daily_file_sha256: daily_hash,
+        key_fingerprint: key_fingerprint(&signing_key.verifying_key()),
+        signature: String::new(),
+    };
+    checkpoint.signature = sign_message(&signing_key, checkpoint.signed_manifest().as_bytes());
+
+    let dir = checkpoints_dir(project_path);
+    std::fs::create_dir_all(&dir)?;
+    crate::fs_utils::atomic_write(&checkpoint_path(project_path, checkpoint.sequence), &checkpoint.to_json())?;
+
+    Ok(checkpoint)
+}
+
+/// Load all checkpoints in sequence order
+pub fn load_checkpoints(project_path: &Path) -> QmsResult<Vec<AuditCheckpoint>> {
+    let dir = checkpoints_dir(project_path);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut checkpoints = Vec::new();
+    for entry in std::fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            checkpoints.push(AuditCheckpoint::from_json(&crate::modules::storage::encryption::read_to_string(&path)?)?);
+        }
+    }
+    checkpoints.sort_by_key(|c| c.sequence);
+    Ok(checkpoints)
+}
+
+/// Verify the audit trail against its signed checkpoints.
+///
+/// `start` selects the first checkpoint to trust (a sequence number or a date);
+/// earlier checkpoints are skipped, which allows verification past a retention
+/// cleanup. Every later checkpoint must be signed by the server key, link to its
+/// predecessor, and match both the main audit log and its rotated daily file.
+///
+/// `trusted_fingerprint` is the server key fingerprint recorded out of band;
+/// without it the key is taken from the checkpoint key store in `key_dir`.
+pub fn verify_from_checkpoint(
+    project_path: &Path,
+    key_dir: &Path,
+    start: Option<&str>,
+    trusted_fingerprint: Option<&str>,
+) -> QmsResult<CheckpointVerificationResult> {
+    let checkpoints = load_checkpoints(project_path)?;
+    if checkpoints.is_empty() {
+        return Err(QmsError::not_found("No audit checkpoints found"));
+    }
+
+    let start_index = match start {
+        None => 0,
+        Some(selector) => checkpoints
+            .iter()
+            .position(|c| c.sequence.to_string() == selector || c.date == selector)
+            .ok_or_else(|| QmsError::not_found(&format!("No checkpoint matches '{selector}'")))?,
+    };
+
+    let server_key = trusted_verifying_key(project_path, key_dir, trusted_fingerprint)?;
+    let log_content = crate::modules::storage::encryption::read_to_string(project_path.join("audit").join("audit.log")).unwrap_or_default();
+    let lines = log_lines(&log_content);
+    let daily_dir = project_path.join("audit").join("daily");
+
+    // Only the most recent rotation of a given day owns the daily file
+    let mut daily_owner: HashMap<String, u64> = HashMap::new();
+    for checkpoint in &checkpoints {
+        if let Some(ref name) = checkpoint.daily_file {
+            daily_owner.insert(name.clone(), checkpoint.sequence);
+        }
+    }
+
+    let mut issues = Vec::new();
+    let mut verified = 0;
+    let mut issue = |sequence: Option<u64>, kind: CheckpointIssueKind, detail: String| {
+        issues.push(CheckpointIssue { sequence, kind, detail });
+    };
+
+    for (index, checkpoint) in checkpoints.iter().enumerate().skip(start_index) {
+        let seq = Some(checkpoint.sequence);
+        let mut ok = true;
+
+        if !checkpoint.verify_signature(&server_key) {
+            ok = false;
+            issue(seq, CheckpointIssueKind::InvalidSignature, "Server signature does not verify".to_string());
+        }
+
+        if index > start_index {
+            let previous = &checkpoints[index - 1];
+            if checkpoint.sequence != previous.sequence + 1
+                || checkpoint.first_entry != previous.end_entry()
+                || checkpoint.previous_checkpoint.as_deref() != Some(previous.checkpoint_hash().as_str())
+            {
+                ok = false;
+                issue(seq, CheckpointIssueKind::BrokenCheckpointChain, format!(
+                    "Does not link to checkpoint {}", previous.sequence
+                ));
+            }
+        }
+
+        if lines.len() < checkpoint.end_entry() {
+            ok = false;
+            issue(seq, CheckpointIssueKind::Truncated, format!(
+                "audit.log has {} entries but checkpoint covers entries up to {}",
+                lines.len(), checkpoint.end_entry()
+            ));
+        } else if merkle_root(&lines[checkpoint.first_entry..checkpoint.end_entry()]) != checkpoint.merkle_root {
+            ok = false;
+            issue(seq, CheckpointIssueKind::MerkleRootMismatch, format!(
+                "Entries {}..{} of audit.log were altered", checkpoint.first_entry, checkpoint.end_entry()
+            ));
+        }
+
+        if let Some(ref name) = checkpoint.daily_file {
+            if daily_owner.get(name) == Some(&checkpoint.sequence) {
+                if let Err(problem) = verify_daily_file(&daily_dir.join(name), checkpoint) {
+                    ok = false;
+                    issue(seq, problem.0, problem.1);
+                }
+            }
+        }
+
+        if ok {
+            verified += 1;
+        }
+    }
+
+    // Every rotated daily file must be anchored by a checkpoint
+    if daily_dir.exists() {
+        for entry in std::fs::read_dir(&daily_dir)?.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if entry.path().is_file() && !daily_owner.contains_key(&name) {
+                issue(None, CheckpointIssueKind::UnanchoredDailyFile, format!(
+                    "{name} is not covered by any checkpoint"
+                ));
+            }
+        }
+    }
+
+    // Entries written after the last checkpoint must chain from it
+    let last = &checkpoints[checkpoints.len() - 1];
+    let tail = lines.len().saturating_sub(last.end_entry());
+    if tail > 0 {
+        let linked = AuditEntry::from_json(lines[last.end_entry()])
+            .map(|entry| entry.previous_hash.as_deref() == Some(last.last_entry_hash.as_str()))
+            .unwrap_or(false);
+        if !linked {
+            issue(Some(last.sequence), CheckpointIssueKind::BrokenTailLink, format!(
+                "Entry {} does not chain from the last checkpointed entry", last.end_entry()
+            ));
+        }
+    }
+
+    Ok(CheckpointVerificationResult {
+        is_valid: issues.is_empty(),
+        checkpoints_verified: verified,
+        entries_anchored: last.end_entry() - checkpoints[start_index].first_entry,
+        entries_after_last_checkpoint: tail,
+        issues,
+    })
+}
+
+fn verify_daily_file(path: &Path, checkpoint: &AuditCheckpoint) -> Result<(), (CheckpointIssueKind, String)> {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let bytes = crate::modules::storage::encryption::read(path)
+        .map_err(|_| (CheckpointIssueKind::MissingDailyFile, format!("{name} has been removed")))?;
+
+    if checkpoint.daily_file_sha256.as_deref() != Some(encode_hex(&Sha256::digest(&bytes)).as_str()) {
+        return Err((CheckpointIssueKind::DailyFileModified, format!("{name} does not match its checkpointed hash")));
+    }
+
+    // Uncompressed daily logs can also be checked entry by entry
+    if name.ends_with(".log") {
+        let content = String::from_utf8_lossy(&bytes);
+        let lines = log_lines(&content);
+        if lines.len() != checkpoint.end_entry()
+            || merkle_root(&lines[checkpoint.first_entry..]) != checkpoint.merkle_root
+        {
+            return Err((CheckpointIssueKind::DailyFileModified, format!("{name} entries do not match the checkpoint")));
+        }
+    }
+
+    Ok(())
+}
+
+fn log_lines(content: &str) -> Vec<&str> {
+    content.lines().filter(|line| !line.trim().is_empty()).collect()
+}
+
+fn checkpoints_dir(project_path: &Path) -> PathBuf {
+    project_path.join("audit").join("checkpoints")
+}
+
+fn checkpoint_path(project_path: &Path, sequence: u64) -> PathBuf {
+    checkpoints_dir(project_path).join(format!("CP-{sequence:06}.json"))
+}
+
+/// Public half of the server key, kept in the project for verifiers
+fn public_key_path(project_path: &Path) -> PathBuf {
+    project_path.join("audit").join("keys").join("checkpoint_signing.json")
+}
+
+/// Directory holding checkpoint signing seeds, outside any project
+///
+/// `QMS_CHECKPOINT_KEY_DIR` overrides the default of `~/.qms/keys/checkpoints`.
+pub fn key_store_dir() -> QmsResult<PathBuf> {
+    if let Ok(dir) = std::env::var(KEY_DIR_ENV) {
+        return Ok(PathBuf::from(dir));
+    }
+    let home = if cfg!(windows) {
+        std::env::var("USERPROFILE").or_else(|_| std::env::var("HOME"))
+    } else {
+        std::env::var("HOME")
+    }
+    .map_err(|_| QmsError::validation_error(&format!("Cannot locate the checkpoint key store; set {KEY_DIR_ENV}")))?;
+    Ok(PathBuf::from(home).join(".qms").join("keys").join("checkpoints"))
+}
+
+/// Key store entry for a project: its id from project.json, or a hash of its location
+fn seed_path(project_path: &Path, key_dir: &Path) -> PathBuf {
+    let project_id = crate::modules::storage::encryption::read_to_string(project_path.join("project.json"))
+        .ok()
+        .and_then(|content| match JsonValue::parse(&content) {
+            Ok(JsonValue::Object(obj)) => obj.get("id").and_then(JsonValue::as_string).cloned(),
+            _ => None,
+        })
+        .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    let name = match project_id {
+        Some(id) => id,
+        None => {
+            let location = project_path.canonicalize().unwrap_or_else(|_| project_path.to_path_buf());
+            encode_hex(&Sha256::digest(location.to_string_lossy().as_bytes()))[..32].to_string()
+        }
+    };
+    key_dir.join(format!("{name}.json"))
+}
+
+/// Load the server checkpoint key, generating it on first use
+fn load_or_create_server_key(project_path: &Path, key_dir: &Path) -> QmsResult<SigningKey> {
+    if let Some(signing_key) = load_stored_seed(project_path, key_dir)? {
+        return Ok(signing_key);
+    }
+
+    let seed: [u8; 32] = rand::thread_rng().gen();
+    let signing_key = SigningKey::from_bytes(&seed);
+    let verifying_key = signing_key.verifying_key();
+    let fingerprint = key_fingerprint(&verifying_key);
+
+    let mut stored = HashMap::new();
+    stored.insert("algorithm".to_string(), JsonValue::String("Ed25519".to_string()));
+    stored.insert("seed".to_string(), JsonValue::String(encode_hex(&seed)));
+    stored.insert("fingerprint".to_string(), JsonValue::String(fingerprint.clone()));
+    stored.insert("project_path".to_string(), JsonValue::String(project_path.to_string_lossy().to_string()));
+    stored.insert("created_at".to_string(), JsonValue::Number(current_timestamp() as f64));
+    let store_path = seed_path(project_path, key_dir);
+    if let Some(parent) = store_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    crate::fs_utils::atomic_write(&store_path, &JsonValue::Object(stored).json_to_string())?;
+    crate::fs_utils::set_secure_file_permissions(&store_path)?;
+
+    let mut public = HashMap::new();
+    public.insert("algorithm".to_string(), JsonValue::String("Ed25519".to_string()));
+    public.insert("public_key".to_string(), JsonValue::String(encode_hex(verifying_key.as_bytes())));
+    public.insert("fingerprint".to_string(), JsonValue::String(fingerprint));
+    public.insert("created_at".to_string(), JsonValue::Number(current_timestamp() as f64));
+    let path = public_key_path(project_path);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    crate::modules::storage::encryption::atomic_write(&path, &JsonValue::Object(public).json_to_string())?;
+
+    Ok(signing_key)
+}
+
+fn load_stored_seed(project_path: &Path, key_dir: &Path) -> QmsResult<Option<SigningKey>> {
+    let path = seed_path(project_path, key_dir);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let seed = seed_from_hex(&key_file_field(&path, "seed")?)?;
+    Ok(Some(SigningKey::from_bytes(&seed)))
+}
+
+fn seed_from_hex(seed: &str) -> QmsResult<[u8; 32]> {
+    decode_hex(seed)?
+        .try_into()
+        .map_err(|_| QmsError::validation_error("Checkpoint key seed must be 32 bytes"))
+}
+
+/// Fingerprint of the checkpoint key for a project, for recording out of band
+pub fn server_key_fingerprint(project_path: &Path, key_dir: &Path) -> QmsResult<String> {
+    Ok(key_fingerprint(&load_or_create_server_key(project_path, key_dir)?.verifying_key()))
+}
+
+/// Verifying key the checkpoints must be signed with
+///
+/// With a fingerprint the project's public key is used only if it matches;
+/// otherwise the key comes from the seed in the key store, never from the
+/// project alone.
+fn trusted_verifying_key(
+    project_path: &Path,
+    key_dir: &Path,
+    trusted_fingerprint: Option<&str>,
+) -> QmsResult<VerifyingKey> {
+    let Some(fingerprint) = trusted_fingerprint else {
+        return load_stored_seed(project_path, key_dir)?
+            .map(|signing_key| signing_key.verifying_key())
+            .ok_or_else(|| QmsError::not_found(
+                "No trusted checkpoint key on this machine; pass the fingerprint recorded when checkpoints were set up",
+            ));
+    };
+
+    let path = public_key_path(project_path);
+    if !path.exists() {
+        return Err(QmsError::not_found("Checkpoint public key not found"));
+    }
+    let bytes: [u8; 32] = decode_hex(&key_file_field(&path, "public_key")?)?
+        .try_into()
+        .map_err(|_| QmsError::validation_error("Checkpoint public key must be 32 bytes"))?;
+    let key = VerifyingKey::from_bytes(&bytes)
+        .map_err(|e| QmsError::validation_error(&format!("Invalid checkpoint public key: {e}")))?;
+    if key_fingerprint(&key) != fingerprint.trim() {
+        return Err(QmsError::validation_error(
+            "Checkpoint public key in the project does not match the trusted fingerprint",
+        ));
+    }
+    Ok(key)
+}
+
+fn key_file_field(path: &Path, field: &str) -> QmsResult<String> {
+    match JsonValue::parse(&crate::modules::storage::encryption::read_to_string(path)?)? {
+        JsonValue::Object(obj) => obj
+            .get(field)
+            .and_then(JsonValue::as_string)
+            .cloned()
+            .ok_or_else(|| QmsError::parse_error(&format!("Checkpoint key missing field: {field}"))),
+        _ => Err(QmsError::parse_error("Checkpoint key file must be a JSON object")),
+    }
+}
+
+/// Format a checkpoint verification result for display
+pub fn format_checkpoint_verification(result: &CheckpointVerificationResult) -> String {
+    let mut output = String::new();
+    output.push_str(&format!("Checkpoints verified: {}\n", result.checkpoints_verified));
+    output.push_str(&format!("Entries anchored: {}\n", result.entries_anchored));
+    output.push_str(&format!("Entries after last checkpoint: {}\n", result.entries_after_last_checkpoint));
+    output.push_str(&format!("Status: {}\n", if result.is_valid { "INTACT" } else { "COMPROMISED" }));
+
+    for issue in &result.issues {
+        let label = issue.sequence.map_or_else(|| "-".to_string(), |s| format!("CP-{s:06}"));
+        output.push_str(&format!("  [{label}] {:?}: {}\n", issue.kind, issue.detail));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AuditAction;
+    use crate::modules::audit_logger::entry::AuditEntryBuilder;
+    use crate::modules::audit_logger::integrity::append_audit_entry_with_chain;
+    use tempfile::tempdir;
+
+    fn append_entries(project_path: &Path, count: usize) {
+        let log_path = project_path.join("audit").join("audit.log");
+        std::fs::create_dir_all(log_path.parent().unwrap()).unwrap();
+        for i in 0..count {
+            let entry = AuditEntryBuilder::new(
+                "tester".to_string(),
+                AuditAction::Create,
+                "Document".to_string(),
+                format!("DOC-{i:03}"),
+            ).build();
+            append_audit_entry_with_chain(&log_path, entry).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_merkle_root_detects_changes() {
+        let root = merkle_root(&["a", "b", "c"]);
+        assert_eq!(root, merkle_root(&["a", "b", "c"]));
+        assert_ne!(root, merkle_root(&["a", "b"]));
+        assert_ne!(root, merkle_root(&["a", "c", "b"]));
+    }
+
+    #[test]
+    fn test_checkpoints_chain_and_verify() {
+        let temp_dir = tempdir().unwrap();
+        let project_path = temp_dir.path();
+        let key_store = tempdir().unwrap();
+        let key_dir = key_store.path();
+
+        append_entries(project_path, 3);
+        let first = create_checkpoint(project_path, key_dir, "2024-01-01").unwrap();
+        append_entries(project_path, 2);
+        let second = create_checkpoint(project_path, key_dir, "2024-01-02").unwrap();
+
+        assert_eq!(first.entry_count, 3);
+        assert_eq!(second.first_entry, 3);
+        assert_eq!(second.previous_checkpoint, Some(first.checkpoint_hash()));
+
+        append_entries(project_path, 1);
+        let result = verify_from_checkpoint(project_path, key_dir, None, None).unwrap();
+        assert!(result.is_valid, "{:?}", result.issues);
+        assert_eq!(result.checkpoints_verified, 2);
+        assert_eq!(result.entries_after_last_checkpoint, 1);
+
+        let result = verify_from_checkpoint(project_path, key_dir, Some("2024-01-02"), None).unwrap();
+        assert!(result.is_valid);
+        assert_eq!(result.entries_anchored, 2);
+    }
+
+    #[test]
+    fn test_truncation_detected() {
+        let temp_dir = tempdir().unwrap();
+        let project_path = temp_dir.path();
+        let key_store = tempdir().unwrap();
+        let key_dir = key_store.path();
+
+        append_entries(project_path, 4);
+        create_checkpoint(project_path, key_dir, "2024-01-01").unwrap();
+
+        // Drop the last entry, as an attacker removing a record would
+        let log_path = project_path.join("audit").join("audit.log");
+        let content = std::fs::read_to_string(&log_path).unwrap();
+        let kept: Vec<&str> = content.lines().take(3).collect();
+        std::fs::write(&log_path, kept.join("\n") + "\n").unwrap();
+
+        let result = verify_from_checkpoint(project_path, key_dir, None, None).unwrap();
+        assert!(!result.is_valid);
+        assert!(result.issues.iter().any(|i| i.kind == CheckpointIssueKind::Truncated));
+    }
+
+    #[test]
+    fn test_rewritten_chain_detected() {
+        let temp_dir = tempdir().unwrap();
+        let project_path = temp_dir.path();
+        let key_store = tempdir().unwrap();
+        let key_dir = key_store.path();
+
+        append_entries(project_path, 3);
+        create_checkpoint(project_path, key_dir, "2024-01-01").unwrap();
+
+        // Rebuild a fully consistent hash chain from scratch
+        let log_path = project_path.join("audit").join("audit.log");
+        std::fs::remove_file(&log_path).unwrap();
+        append_entries(project_path, 3);
+        assert!(crate::modules::audit_logger::integrity::verify_audit_chain(&log_path).unwrap().is_valid);
+
+        let result = verify_from_checkpoint(project_path, key_dir, None, None).unwrap();
+        assert!(result.issues.iter().any(|i| i.kind == CheckpointIssueKind::MerkleRootMismatch));
+    }
+
+    #[test]
+    fn test_removed_daily_file_and_forged_checkpoint_detected() {
+        let temp_dir = tempdir().unwrap();
+        let project_path = temp_dir.path();
+        let key_store = tempdir().unwrap();
+        let key_dir = key_store.path();
+
+        append_entries(project_path, 2);
+        let daily_dir = project_path.join("audit").join("daily");
+        std::fs::create_dir_all(&daily_dir).unwrap();
+        let daily = daily_dir.join("2024-01-01.log");
+        std::fs::copy(project_path.join("audit").join("audit.log"), &daily).unwrap();
+        let snapshot = std::fs::read_to_string(&daily).unwrap();
+        create_rotation_checkpoint(project_path, key_dir, "2024-01-01", &snapshot, &daily).unwrap();
+        assert!(verify_from_checkpoint(project_path, key_dir, None, None).unwrap().is_valid);
+
+        std::fs::remove_file(&daily).unwrap();
+        let result = verify_from_checkpoint(project_path, key_dir, None, None).unwrap();
+        assert!(result.issues.iter().any(|i| i.kind == CheckpointIssueKind::MissingDailyFile));
+
+        let path = checkpoint_path(project_path, 1);
+        let mut checkpoint = AuditCheckpoint::from_json(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        checkpoint.entry_count = 1;
+        std::fs::write(&path, checkpoint.to_json()).unwrap();
+        let result = verify_from_checkpoint(project_path, key_dir, None, None).unwrap();
+        assert!(result.issues.iter().any(|i| i.kind == CheckpointIssueKind::InvalidSignature));
+    }
+
+    #[test]
+    fn test_seed_kept_outside_project_and_swapped_key_rejected() {
+        let temp_dir = tempdir().unwrap();
+        let project_path = temp_dir.path();
+        let key_store = tempdir().unwrap();
+        let key_dir = key_store.path();
+
+        append_entries(project_path, 2);
+        let checkpoint = create_checkpoint(project_path, key_dir, "2024-01-01").unwrap();
+        let public_path = public_key_path(project_path);
+        assert!(key_file_field(&public_path, "seed").is_err());
+        assert!(seed_path(project_path, key_dir).exists());
+        let fingerprint = checkpoint.key_fingerprint.clone();
+        assert_eq!(server_key_fingerprint(project_path, key_dir).unwrap(), fingerprint);
+        assert!(verify_from_checkpoint(project_path, key_dir, None, Some(&fingerprint)).unwrap().is_valid);
+
+        // Re-sign the checkpoint with a key of the writer's own and publish it in the project
+        let forged_key = SigningKey::from_bytes(&[7u8; 32]);
+        let mut public = HashMap::new();
+        public.insert("public_key".to_string(), JsonValue::String(encode_hex(forged_key.verifying_key().as_bytes())));
+        std::fs::write(&public_path, JsonValue::Object(public).json_to_string()).unwrap();
+        let path = checkpoint_path(project_path, 1);
+        let mut forged = AuditCheckpoint::from_json(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        forged.key_fingerprint = key_fingerprint(&forged_key.verifying_key());
+        forged.signature = sign_message(&forged_key, forged.signed_manifest().as_bytes());
+        std::fs::write(&path, forged.to_json()).unwrap();
+
+        let result = verify_from_checkpoint(project_path, key_dir, None, None).unwrap();
+        assert!(result.issues.iter().any(|i| i.kind == CheckpointIssueKind::InvalidSignature));
+        assert!(verify_from_checkpoint(project_path, key_dir, None, Some(&fingerprint)).is_err());
+
+        // Without a local key or a recorded fingerprint nothing is trusted
+        std::fs::remove_file(seed_path(project_path, key_dir)).unwrap();
+        assert!(verify_from_checkpoint(project_path, key_dir, None, None).is_err());
+    }
+}
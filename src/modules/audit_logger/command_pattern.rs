@@ -124,7 +124,7 @@ impl AuditCommand for CreateAuditEntryCommand {
             .append(true)
             .open(&self.storage_path)?;
         
-        let json_entry = self.entry.to_json();
+        let json_entry = crate::modules::storage::encryption::seal(&self.storage_path, &self.entry.to_json())?;
         writeln!(file, "{json_entry}")?;
         file.flush()?;
         
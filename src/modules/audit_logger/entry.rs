@@ -76,6 +76,7 @@ pub struct AuditStats {
 /// Single Responsibility: Writing audit entries to files
 pub struct AuditEntryLogger {
     current_file: Option<File>,
+    current_path: Option<PathBuf>,
 }
 
 impl AuditEntryLogger {
@@ -83,18 +84,24 @@ impl AuditEntryLogger {
     pub fn new() -> Self {
         Self {
             current_file: None,
+            current_path: None,
         }
     }
 
-    /// Set the current file for writing
-    pub fn set_file(&mut self, file: File) {
+    /// Set the current file for writing and the path it was opened at
+    pub fn set_file(&mut self, file: File, path: PathBuf) {
         self.current_file = Some(file);
+        self.current_path = Some(path);
     }
 
     /// Write an audit entry to the current file
     pub fn write_entry(&mut self, entry: &AuditEntry) -> QmsResult<()> {
         if let Some(ref mut file) = self.current_file {
-            let json_line = format!("{}\n", entry.to_json());
+            let json = entry.to_json();
+            let json_line = match &self.current_path {
+                Some(path) => format!("{}\n", crate::modules::storage::encryption::seal(path, &json)?),
+                None => format!("{json}\n"),
+            };
             file.write_all(json_line.as_bytes())
                 .map_err(|e| QmsError::io_error(&format!("Failed to write audit entry: {e}")))?;
             file.flush()
@@ -108,6 +115,7 @@ impl AuditEntryLogger {
     /// Close the current file
     pub fn close_file(&mut self) {
         self.current_file = None;
+        self.current_path = None;
     }
 }
 
@@ -146,8 +154,8 @@ impl AuditFileRotator {
         Ok(())
     }
 
-    /// Check if rotation is needed and return new file if so
-    pub fn check_rotation_needed(&mut self) -> QmsResult<Option<File>> {
+    /// Check if rotation is needed and return the new file and its path if so
+    pub fn check_rotation_needed(&mut self) -> QmsResult<Option<(File, PathBuf)>> {
         let today = current_date_string();
 
         if self.config.daily_rotation && self.current_date != today {
@@ -162,7 +170,7 @@ impl AuditFileRotator {
     }
 
     /// Open a log file for the current date
-    fn open_log_file(&self) -> QmsResult<File> {
+    fn open_log_file(&self) -> QmsResult<(File, PathBuf)> {
         let filename = if self.config.daily_rotation {
             format!("{}.log", self.current_date)
         } else {
@@ -173,11 +181,12 @@ impl AuditFileRotator {
             .join("audit")
             .join(&filename);
 
-        OpenOptions::new()
+        let file = OpenOptions::new()
             .create(true)
             .append(true)
             .open(&file_path)
-            .map_err(|e| QmsError::io_error(&format!("Failed to open audit log file: {e}")))
+            .map_err(|e| QmsError::io_error(&format!("Failed to open audit log file: {e}")))?;
+        Ok((file, file_path))
     }
 
     /// Clean up old audit logs based on retention policy
@@ -328,8 +337,8 @@ impl AuditLoggerOrchestrator {
 
     /// Ensure a log file is open for writing
     fn ensure_file_open(&mut self) -> QmsResult<()> {
-        if let Some(file) = self.rotator.check_rotation_needed()? {
-            self.logger.set_file(file);
+        if let Some((file, path)) = self.rotator.check_rotation_needed()? {
+            self.logger.set_file(file, path);
         }
         Ok(())
     }
@@ -337,8 +346,8 @@ impl AuditLoggerOrchestrator {
     /// Log an audit entry (main public interface)
     pub fn log_entry(&mut self, entry: &AuditEntry) -> QmsResult<()> {
         // Check if rotation is needed and update file if so
-        if let Some(file) = self.rotator.check_rotation_needed()? {
-            self.logger.set_file(file);
+        if let Some((file, path)) = self.rotator.check_rotation_needed()? {
+            self.logger.set_file(file, path);
         }
 
         // Write the entry
@@ -6,6 +6,7 @@
 
 use crate::prelude::*;
 use crate::modules::audit_logger::{AuditSearchEngine, AuditSearchCriteria};
+use crate::modules::report_generator::pdf::{PdfDocument, PdfTable, SignatureManifestation};
 use crate::json_utils::JsonSerializable;
 use std::collections::HashMap;
 use std::fs;
@@ -177,126 +178,91 @@ impl AuditExportEngine {
     
     /// Export to comprehensive PDF format (medical device compliant audit report)
     fn export_pdf(&self, entries: &[crate::models::AuditEntry], options: &ExportOptions) -> QmsResult<u64> {
-        let mut content = String::new();
-        let line_width = 100;
         let timestamp = crate::utils::current_iso8601_timestamp();
+        let mut pdf = PdfDocument::new("Medical Device Audit Log Report")
+            .with_footer_note(&format!("21 CFR Part 820 & ISO 13485 audit trail - generated {timestamp}"));
 
         if options.include_headers {
-            // PDF Header with compliance information
-            content.push_str(&"=".repeat(line_width));
-            content.push('\n');
-            content.push_str(&Self::center_text("MEDICAL DEVICE AUDIT LOG REPORT", line_width));
-            content.push('\n');
-            content.push_str(&Self::center_text("21 CFR Part 820 & ISO 13485 Compliant Audit Trail", line_width));
-            content.push('\n');
-            content.push_str(&"=".repeat(line_width));
-            content.push('\n');
-            content.push('\n');
-
             // Document metadata
-            content.push_str("REPORT INFORMATION\n");
-            content.push_str(&"-".repeat(50));
-            content.push('\n');
-            content.push_str(&format!("Generated: {}\n", timestamp));
-            content.push_str(&format!("Total Audit Entries: {}\n", entries.len()));
-            content.push_str(&format!("Project Path: {}\n", self.project_path.display()));
-            content.push_str("Regulatory Compliance: 21 CFR Part 820.70, ISO 13485:2016\n");
-            content.push_str("Audit Standard: Electronic records and signatures (21 CFR Part 11)\n");
-            content.push_str("Data Integrity: Maintained with cryptographic checksums\n");
-            content.push('\n');
+            pdf.heading(1, "Report Information");
+            pdf.field("Generated", &timestamp);
+            pdf.field("Total Audit Entries", &entries.len().to_string());
+            pdf.field("Project Path", &self.project_path.display().to_string());
+            pdf.field("Regulatory Compliance", "21 CFR Part 820.70, ISO 13485:2016");
+            pdf.field("Audit Standard", "Electronic records and signatures (21 CFR Part 11)");
+            pdf.field("Data Integrity", "Maintained with cryptographic checksums");
 
             // Audit statistics
             let stats = Self::calculate_audit_statistics(entries);
-            content.push_str("AUDIT TRAIL STATISTICS\n");
-            content.push_str(&"-".repeat(50));
-            content.push('\n');
-            content.push_str(&format!("Total Entries: {}\n", stats.total_entries));
-            content.push_str(&format!("Unique Users: {}\n", stats.unique_users));
-            content.push_str(&format!("Date Range: {} to {}\n", stats.earliest_entry, stats.latest_entry));
-            content.push_str(&format!("Most Active User: {} ({} actions)\n", stats.most_active_user, stats.most_active_user_count));
-            content.push('\n');
-
-            content.push_str("ACTION TYPE DISTRIBUTION:\n");
-            for (action, count) in &stats.action_distribution {
+            pdf.heading(2, "Audit Trail Statistics");
+            pdf.field("Total Entries", &stats.total_entries.to_string());
+            pdf.field("Unique Users", &stats.unique_users.to_string());
+            pdf.field("Date Range", &format!("{} to {}", stats.earliest_entry, stats.latest_entry));
+            pdf.field("Most Active User", &format!("{} ({} actions)", stats.most_active_user, stats.most_active_user_count));
+
+            let mut distribution = PdfTable::new(&["Action Type", "Count", "Share"]);
+            let mut actions: Vec<_> = stats.action_distribution.iter().collect();
+            actions.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+            for (action, count) in actions {
                 let percentage = if stats.total_entries > 0 { (*count as f64 / stats.total_entries as f64) * 100.0 } else { 0.0 };
-                content.push_str(&format!("  {:?}: {} ({:.1}%)\n", action, count, percentage));
+                distribution.add_row(vec![action.clone(), count.to_string(), format!("{percentage:.1}%")]);
             }
-            content.push('\n');
-
-            content.push_str(&"=".repeat(line_width));
-            content.push('\n');
-            content.push_str(&Self::center_text("DETAILED AUDIT ENTRIES", line_width));
-            content.push('\n');
-            content.push_str(&"=".repeat(line_width));
-            content.push('\n');
-            content.push('\n');
+            pdf.heading(3, "Action Type Distribution");
+            pdf.table(distribution);
         }
 
         // Detailed audit entries
+        pdf.heading(1, "Detailed Audit Entries");
+        let mut table = PdfTable::new(&["#", "Timestamp", "User", "Action", "Entity", "Change / Details", "Checksum"]);
         for (i, entry) in entries.iter().enumerate() {
-            content.push_str(&format!("AUDIT ENTRY #{}: {}\n", i + 1, entry.id));
-            content.push_str(&"-".repeat(80));
-            content.push('\n');
-
-            content.push_str(&format!("Timestamp: {}\n", entry.timestamp));
-            content.push_str(&format!("User ID: {}\n", entry.user_id));
-            content.push_str(&format!("Action Type: {:?}\n", entry.action));
-            content.push_str(&format!("Entity Type: {}\n", entry.entity_type));
-            content.push_str(&format!("Entity ID: {}\n", entry.entity_id));
-
+            let mut details = Vec::new();
             if let Some(old_value) = &entry.old_value {
-                content.push_str(&format!("Previous Value: {}\n", old_value));
+                details.push(format!("Previous: {old_value}"));
             }
-
             if let Some(new_value) = &entry.new_value {
-                content.push_str(&format!("New Value: {}\n", new_value));
-            }
-
-            content.push_str(&format!("Details: {}\n", entry.details.as_ref().unwrap_or(&"N/A".to_string())));
-            content.push_str(&format!("Data Integrity Checksum: {}\n", entry.checksum));
-
-            if let Some(signature) = &entry.signature {
-                content.push_str(&format!("Digital Signature: {:?}\n", signature));
+                details.push(format!("New: {new_value}"));
             }
-
-            content.push('\n');
-            content.push_str(&"~".repeat(80));
-            content.push('\n');
-            content.push('\n');
+            details.push(entry.details.clone().unwrap_or_else(|| "N/A".to_string()));
+
+            table.add_row(vec![
+                (i + 1).to_string(),
+                entry.timestamp.clone(),
+                entry.user_id.clone(),
+                format!("{:?}", entry.action),
+                format!("{} {}", entry.entity_type, entry.entity_id),
+                details.join("\n"),
+                entry.checksum.chars().take(16).collect(),
+            ]);
+        }
+        pdf.table(table);
+
+        // Electronic signatures recorded against exported entries
+        let signatures: Vec<SignatureManifestation> = entries.iter()
+            .filter_map(|entry| entry.signature.as_ref())
+            .map(SignatureManifestation::from)
+            .collect();
+        if !signatures.is_empty() {
+            pdf.signatures(signatures);
         }
 
         // Footer with compliance statement
         if options.include_headers {
-            content.push_str(&"=".repeat(line_width));
-            content.push('\n');
-            content.push_str(&Self::center_text("COMPLIANCE CERTIFICATION", line_width));
-            content.push('\n');
-            content.push_str(&"=".repeat(line_width));
-            content.push('\n');
-            content.push('\n');
-            content.push_str("This audit trail report certifies compliance with:\n");
-            content.push_str("• 21 CFR Part 820.70 - Production and process controls\n");
-            content.push_str("• 21 CFR Part 11 - Electronic records and electronic signatures\n");
-            content.push_str("• ISO 13485:2016 - Medical devices quality management systems\n");
-            content.push_str("• ISO 14971:2019 - Risk management for medical devices\n");
-            content.push('\n');
-            content.push_str("All audit entries maintain data integrity through cryptographic checksums.\n");
-            content.push_str("Electronic signatures ensure non-repudiation and authenticity.\n");
-            content.push_str("Audit trail is tamper-evident and maintains chronological sequence.\n");
-            content.push('\n');
-            content.push_str(&format!("Report certified: {}\n", timestamp));
-            content.push_str("Document format: Text-based PDF (convert using pandoc/wkhtmltopdf)\n");
+            pdf.heading(1, "Compliance Certification");
+            pdf.paragraph("This audit trail report certifies compliance with:");
+            pdf.bullet("21 CFR Part 820.70 - Production and process controls");
+            pdf.bullet("21 CFR Part 11 - Electronic records and electronic signatures");
+            pdf.bullet("ISO 13485:2016 - Medical devices quality management systems");
+            pdf.bullet("ISO 14971:2019 - Risk management for medical devices");
+            pdf.paragraph("All audit entries maintain data integrity through cryptographic checksums. \
+                Electronic signatures ensure non-repudiation and authenticity. \
+                Audit trail is tamper-evident and maintains chronological sequence.");
+            pdf.field("Report certified", &timestamp);
         }
 
         // Write to file with atomic operation for data integrity
-        fs::write(&options.output_path, content.as_bytes())
-            .map_err(|e| QmsError::io_error(&e.to_string()))?;
-
-        Ok(content.len() as u64)
+        pdf.write_to(&options.output_path)
     }
 
-
-
     /// Calculate comprehensive audit statistics
     fn calculate_audit_statistics(entries: &[crate::models::AuditEntry]) -> AuditStatistics {
         let total_entries = entries.len();
@@ -337,16 +303,6 @@ impl AuditExportEngine {
         }
     }
 
-    /// Center text within a given width for PDF formatting
-    fn center_text(text: &str, width: usize) -> String {
-        if text.len() >= width {
-            return text.to_string();
-        }
-
-        let padding = (width - text.len()) / 2;
-        format!("{}{}", " ".repeat(padding), text)
-    }
-
     /// Export to CSV format
     fn export_csv(&self, entries: &[crate::models::AuditEntry], options: &ExportOptions) -> QmsResult<u64> {
         let mut content = String::new();
@@ -588,6 +544,45 @@ mod tests {
         assert_eq!(options.max_entries, Some(100));
     }
 
+    #[test]
+    fn test_pdf_export_is_native_pdf() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("audit.pdf");
+        let entry = crate::models::AuditEntry {
+            id: "entry-1".to_string(),
+            timestamp: "2024-01-15T10:00:00Z".to_string(),
+            user_id: "qa.lead".to_string(),
+            session_id: None,
+            action: crate::models::AuditAction::Approve,
+            entity_type: "Document".to_string(),
+            entity_id: "DOC-001".to_string(),
+            old_value: None,
+            new_value: Some("Approved".to_string()),
+            details: None,
+            ip_address: None,
+            signature: Some(crate::models::ElectronicSignature {
+                user_id: "qa.lead".to_string(),
+                timestamp: "2024-01-15T10:00:00Z".to_string(),
+                meaning: "Approved for release".to_string(),
+                signed_data_hash: "abc123".to_string(),
+                certificate_info: None,
+            }),
+            checksum: "0123456789abcdef0123".to_string(),
+            previous_hash: None,
+        };
+
+        let engine = AuditExportEngine::new(dir.path().to_path_buf());
+        let options = ExportOptions::new(ExportFormat::PDF, output.clone());
+        let size = engine.export_pdf(&[entry], &options).unwrap();
+
+        let pdf = fs::read_to_string(&output).unwrap();
+        assert_eq!(pdf.len() as u64, size);
+        assert!(pdf.starts_with("%PDF-1.4"));
+        assert!(pdf.contains("(DOC-001)") || pdf.contains("(Document DOC-001)"));
+        assert!(pdf.contains("(Approved for release)"));
+        assert!(!pdf.contains("pandoc"));
+    }
+
     #[test]
     fn test_export_engine_creation() {
         let engine = AuditExportEngine::new(PathBuf::from("/tmp/test"));
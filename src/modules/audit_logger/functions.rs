@@ -60,7 +60,7 @@ pub fn log_entry_to_chain(entry: &AuditEntry) -> QmsResult<()> {
                 .append(true)
                 .open(&log_path)?;
 
-            let json_entry = entry.to_json();
+            let json_entry = crate::modules::storage::encryption::seal(&log_path, &entry.to_json())?;
             writeln!(file, "{}", json_entry)?;
             file.flush()?;
 
@@ -93,7 +93,7 @@ pub fn log_entry_to_chain(entry: &AuditEntry) -> QmsResult<()> {
                 .append(true)
                 .open(&log_path)?;
 
-            let json_entry = entry.to_json();
+            let json_entry = crate::modules::storage::encryption::seal(&log_path, &entry.to_json())?;
             writeln!(file, "{}", json_entry)?;
             file.flush()?;
 
@@ -538,6 +538,7 @@ pub fn search_audit_logs(criteria: &AuditSearchCriteria) -> QmsResult<Vec<AuditE
 
             let line = line_result
                 .map_err(|e| QmsError::io_error(&format!("Failed to read audit line: {e}")))?;
+            let line = crate::modules::storage::encryption::open_line(&file_path, &line)?;
             
             if let Ok(entry) = AuditEntry::from_json(&line) {
                 if matches_criteria(&entry, criteria) {
@@ -618,7 +619,7 @@ pub fn rotate_audit_logs() -> QmsResult<String> {
     let current_log = audit_dir.join("audit.log");
     if current_log.exists() {
         let daily_log = daily_dir.join(format!("{current_date}.log"));
-        std::fs::rename(&current_log, &daily_log)
+        crate::modules::storage::encryption::rename(&current_log, &daily_log)
             .map_err(|e| QmsError::io_error(&format!("Failed to rotate log file: {e}")))?;
         
         Ok(format!("Log rotated to {}", daily_log.display()))
@@ -6,6 +6,7 @@ use std::io::{BufRead, BufReader, Write};
 use std::path::Path;
 use crate::models::{AuditEntry, AuditAction};
 use crate::modules::audit_logger::entry::AuditEntryBuilder;
+use crate::modules::storage::encryption;
 use crate::error::{QmsResult, QmsError};
 use crate::json_utils::JsonSerializable;
 
@@ -43,6 +44,7 @@ pub fn get_last_entry_hash(log_path: &Path) -> QmsResult<Option<String>> {
     // Read all entries to find the last one
     for line in reader.lines() {
         let line = line.map_err(|e| QmsError::domain_error(&format!("Error reading log line: {e}")))?;
+        let line = encryption::open_line(log_path, &line)?;
         
         if line.trim().is_empty() {
             continue;
@@ -105,8 +107,8 @@ pub fn append_audit_entry_with_chain(log_path: &Path, mut entry: AuditEntry) ->
         .open(log_path)
         .map_err(|e| QmsError::domain_error(&format!("Cannot open audit log for writing: {e}")))?;
     
-    // Write the entry as JSON line
-    writeln!(file, "{}", entry.to_json())
+    // Write the entry as JSON line (sealed when the project is encrypted)
+    writeln!(file, "{}", encryption::seal(log_path, &entry.to_json())?)
         .map_err(|e| QmsError::domain_error(&format!("Cannot write to audit log: {e}")))?;
     
     file.flush()
@@ -139,6 +141,7 @@ pub fn verify_audit_chain(log_path: &Path) -> QmsResult<ChainVerificationResult>
     for line in reader.lines() {
         line_number += 1;
         let line = line.map_err(|e| QmsError::domain_error(&format!("Error reading log line {line_number}: {e}")))?;
+        let line = encryption::open_line(log_path, &line)?;
         
         if line.trim().is_empty() {
             continue;
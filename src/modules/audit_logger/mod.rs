@@ -2,6 +2,7 @@ pub mod entry;
 pub mod export;
 pub mod functions;
 pub mod integrity;
+pub mod checkpoint;
 pub mod regulatory;
 pub mod rotation;
 pub mod search;
@@ -45,6 +46,14 @@ pub use integrity::{
     verify_audit_file, initialize_audit_chain, export_chain_verification_report
 };
 
+// Re-export checkpoint functions
+#[allow(unused_imports)]
+pub use checkpoint::{
+    AuditCheckpoint, CheckpointIssue, CheckpointIssueKind, CheckpointVerificationResult,
+    create_checkpoint, load_checkpoints, verify_from_checkpoint, format_checkpoint_verification,
+    server_key_fingerprint, key_store_dir as checkpoint_key_store_dir, KEY_DIR_ENV as CHECKPOINT_KEY_DIR_ENV
+};
+
 // Re-export rotation functions
 #[allow(unused_imports)]
 pub use rotation::{
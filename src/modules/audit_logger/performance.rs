@@ -226,7 +226,7 @@ impl PerformanceAuditLogger {
         // Write all buffered entries in a single operation for efficiency
         let mut content = String::new();
         for entry in &self.buffer {
-            content.push_str(&entry.content);
+            content.push_str(&crate::modules::storage::encryption::seal(&audit_log_path, &entry.content)?);
             content.push('\n');
         }
 
@@ -367,6 +367,7 @@ impl PerformanceAuditLogger {
         for line in reader.lines() {
             let line = line
                 .map_err(|e| QmsError::io_error(&format!("Failed to read line: {e}")))?;
+            let line = crate::modules::storage::encryption::open_line(file_path, &line)?;
 
             if line.contains(pattern) {
                 if matched_count >= offset {
@@ -475,6 +476,7 @@ impl PerformanceAuditLogger {
         for line in reader.lines() {
             let line = line
                 .map_err(|e| QmsError::io_error(&format!("Failed to read log line: {e}")))?;
+            let line = crate::modules::storage::encryption::open_line(log_path, &line)?;
             
             self.update_search_index(&line)?;
         }
@@ -321,7 +321,7 @@ impl RegulatoryCompliance {
     fn load_entries_from_file(&self, file_path: &Path) -> QmsResult<Vec<AuditEntry>> {
         use std::fs;
         
-        let content = fs::read_to_string(file_path)?;
+        let content = crate::modules::storage::encryption::read_to_string(file_path)?;
         let mut entries = Vec::new();
 
         for line in content.lines() {
@@ -9,6 +9,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use crate::error::{QmsError, QmsResult};
 use crate::utils::{current_date_string, current_timestamp};
 use crate::modules::audit_logger::functions::log_system_event;
+use crate::modules::audit_logger::checkpoint::{create_rotation_checkpoint, key_store_dir, unanchored_entries};
 
 /// Configuration for audit log rotation and retention
 #[derive(Debug, Clone)]
@@ -58,7 +59,7 @@ pub fn check_and_rotate_daily_logs(project_path: &Path) -> QmsResult<bool> {
                     
                     // If the file was modified more than 24 hours ago, rotate it
                     if seconds_diff > 86400 { // 24 hours
-                        return rotate_to_daily_log(project_path, &current_date);
+                        return rotate_to_daily_log(project_path, &key_store_dir()?, &current_date);
                     }
                 }
             }
@@ -68,8 +69,9 @@ pub fn check_and_rotate_daily_logs(project_path: &Path) -> QmsResult<bool> {
     Ok(false)
 }
 
-/// Rotate current audit log to daily directory
-pub fn rotate_to_daily_log(project_path: &Path, date: &str) -> QmsResult<bool> {
+/// Rotate current audit log to daily directory, anchoring it with a checkpoint
+/// signed by the project's seed in `key_dir`
+pub fn rotate_to_daily_log(project_path: &Path, key_dir: &Path, date: &str) -> QmsResult<bool> {
     let audit_dir = project_path.join("audit");
     let current_log = audit_dir.join("audit.log");
     let daily_dir = audit_dir.join("daily");
@@ -79,21 +81,40 @@ pub fn rotate_to_daily_log(project_path: &Path, date: &str) -> QmsResult<bool> {
         return Ok(false);
     }
     
+    // Nothing to rotate when every entry is already anchored by a checkpoint
+    let current = crate::modules::storage::encryption::read_to_string(&current_log)
+        .map_err(|e| QmsError::io_error(&format!("Failed to read audit log: {e}")))?;
+    if unanchored_entries(project_path, &current)? == 0 {
+        return Ok(false);
+    }
+    
     // Create daily directory if it doesn't exist
     create_dir_all(&daily_dir)
         .map_err(|e| QmsError::domain_error(&format!("Failed to create daily directory: {e}")))?;
     
     // Copy current log to daily directory (preserve original for chain continuity)
-    std::fs::copy(&current_log, &daily_log)
+    crate::modules::storage::encryption::copy(&current_log, &daily_log)
         .map_err(|e| QmsError::domain_error(&format!("Failed to copy log for rotation: {e}")))?;
+    let snapshot = crate::modules::storage::encryption::read_to_string(&daily_log)
+        .map_err(|e| QmsError::io_error(&format!("Failed to read rotated log: {e}")))?;
     
     // Log the rotation event in the original log
     log_system_event("AUDIT_LOG_ROTATED", &format!("Daily log rotated to {}", daily_log.display()))?;
     
     // Compress the daily log if enabled
-    if should_compress() {
+    let artifact = if should_compress() {
         compress_log_file(&daily_log)?;
-    }
+        daily_log.with_extension("log.gz")
+    } else {
+        daily_log.clone()
+    };
+    
+    // Anchor the rotated entries with a signed checkpoint so later truncation is detectable
+    let checkpoint = create_rotation_checkpoint(project_path, key_dir, date, &snapshot, &artifact)?;
+    log_system_event("AUDIT_CHECKPOINT_CREATED", &format!(
+        "Checkpoint {} covers entries {}..{} (root {})",
+        checkpoint.sequence, checkpoint.first_entry, checkpoint.end_entry(), checkpoint.merkle_root
+    ))?;
     
     Ok(true)
 }
@@ -112,7 +133,7 @@ fn compress_log_file(log_path: &Path) -> QmsResult<()> {
     }
 
     // Read the original log file
-    let original_data = std::fs::read(log_path)
+    let original_data = crate::modules::storage::encryption::read(log_path)
         .map_err(|e| QmsError::io_error(&format!("Failed to read log file: {e}")))?;
 
     // Compress using our stdlib-only compression
@@ -120,7 +141,7 @@ fn compress_log_file(log_path: &Path) -> QmsResult<()> {
 
     // Write compressed file with .gz extension
     let compressed_path = log_path.with_extension("log.gz");
-    std::fs::write(&compressed_path, &compressed_data)
+    crate::modules::storage::encryption::write_bytes(&compressed_path, &compressed_data)
         .map_err(|e| QmsError::io_error(&format!("Failed to write compressed file: {e}")))?;
 
     // Remove original file after successful compression
@@ -317,7 +338,7 @@ pub struct RotationStats {
     pub compressed_files_count: usize,
 }
 
-/// Schedule automatic daily rotation (would be called by a scheduler)
+/// Daily rotation and retention cleanup, run by the `rotate-audit-logs` scheduled job
 pub fn schedule_daily_rotation(project_path: &Path) -> QmsResult<()> {
     // Check and perform daily rotation
     if check_and_rotate_daily_logs(project_path)? {
@@ -368,6 +389,31 @@ mod tests {
         assert_eq!(stats.daily_files_count, 0);
         assert_eq!(stats.total_daily_size, 0);
     }
+
+    #[test]
+    fn test_rotation_skipped_when_every_entry_is_checkpointed() {
+        use crate::models::AuditAction;
+        use crate::modules::audit_logger::checkpoint::create_checkpoint;
+        use crate::modules::audit_logger::entry::AuditEntryBuilder;
+        use crate::modules::audit_logger::integrity::append_audit_entry_with_chain;
+
+        let temp_dir = tempdir().unwrap();
+        let project_path = temp_dir.path();
+        let key_store = tempdir().unwrap();
+        let log_path = project_path.join("audit").join("audit.log");
+        create_dir_all(log_path.parent().unwrap()).unwrap();
+        let entry = AuditEntryBuilder::new(
+            "tester".to_string(),
+            AuditAction::Create,
+            "Document".to_string(),
+            "DOC-001".to_string(),
+        ).build();
+        append_audit_entry_with_chain(&log_path, entry).unwrap();
+        create_checkpoint(project_path, key_store.path(), "2024-01-01").unwrap();
+
+        assert!(!rotate_to_daily_log(project_path, key_store.path(), "2024-01-02").unwrap());
+        assert!(!project_path.join("audit").join("daily").exists());
+    }
 }
 
 /// Stdlib-only compression for audit log data using multiple techniques
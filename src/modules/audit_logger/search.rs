@@ -189,7 +189,7 @@ impl AuditSearchEngine {
 
     /// Search a specific audit log file
     fn search_file(&self, file_path: &Path, criteria: &AuditSearchCriteria) -> QmsResult<Vec<AuditEntry>> {
-        let content = fs::read_to_string(file_path)?;
+        let content = crate::modules::storage::encryption::read_to_string(file_path)?;
         let mut matching_entries = Vec::new();
 
         for line in content.lines() {
@@ -307,7 +307,7 @@ impl AuditReader for FileAuditStorage {
             return Ok(Vec::new());
         }
         
-        let content = std::fs::read_to_string(log_path)?;
+        let content = crate::modules::storage::encryption::read_to_string(log_path)?;
         let mut entries = Vec::new();
         
         for line in content.lines() {
@@ -370,9 +370,9 @@ impl AuditWriter for FileAuditStorage {
         let mut file = OpenOptions::new()
             .create(true)
             .append(true)
-            .open(log_path)?;
+            .open(&log_path)?;
         
-        let json_line = format!("{}\n", entry.to_json());
+        let json_line = format!("{}\n", crate::modules::storage::encryption::seal(&log_path, &entry.to_json())?);
         file.write_all(json_line.as_bytes())?;
         file.flush()?;
         
@@ -393,10 +393,10 @@ impl AuditWriter for FileAuditStorage {
         let mut file = OpenOptions::new()
             .create(true)
             .append(true)
-            .open(log_path)?;
+            .open(&log_path)?;
         
         for entry in entries {
-            let json_line = format!("{}\n", entry.to_json());
+            let json_line = format!("{}\n", crate::modules::storage::encryption::seal(&log_path, &entry.to_json())?);
             file.write_all(json_line.as_bytes())?;
         }
         
@@ -1,10 +1,15 @@
-//! Audit log digital signatures for integrity verification
-//! Uses SHA-256 for cryptographically secure signatures
+//! Electronic signatures for 21 CFR Part 11 compliance
+//! Signatures are Ed25519 digital signatures over a manifest that binds the
+//! signer, meaning, timestamp and the SHA-256 of the signed record content.
 
 use crate::error::{QmsError, QmsResult};
-use crate::modules::audit_logger::AuditEntry;
+use crate::json_utils::{JsonError, JsonSerializable, JsonValue};
+use crate::modules::user_manager::mfa::MfaStore;
+use crate::modules::user_manager::signing_keys::{key_fingerprint, sign_message, SigningKeyStore};
+use ed25519_dalek::SigningKey;
 use sha2::{Sha256, Digest};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::collections::HashMap;
+use std::path::PathBuf;
 
 /// Electronic signature data structure
 #[derive(Debug, Clone)]
@@ -19,16 +24,43 @@ pub struct ElectronicSignature {
     pub signature_method: SignatureMethod,  // How signature was created
     pub certificate_info: Option<String>,   // Digital certificate info
     pub reason: Option<String>,             // Reason for signing
+    pub content_hash: Option<String>,       // SHA-256 of the signed record content
+    pub key_fingerprint: Option<String>,    // Fingerprint of the signer's public key
+    pub signature_value: Option<String>,    // Ed25519 signature over the manifest (hex)
 }
 
 /// Methods for creating electronic signatures
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 #[allow(dead_code)] // Future implementation variants
 pub enum SignatureMethod {
     Password,           // Username/password authentication
     Biometric,          // Biometric verification (future)
-    DigitalCertificate, // PKI certificate (future)
-    TwoFactor,          // Two-factor authentication
+    DigitalCertificate, // Ed25519 key pair unlocked by the signer's password
+    TwoFactor,          // Ed25519 key unlocked by password, plus a TOTP re-challenge
+}
+
+impl SignatureMethod {
+    /// Parse a method from its stored name, defaulting to Password
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "Biometric" => SignatureMethod::Biometric,
+            "DigitalCertificate" => SignatureMethod::DigitalCertificate,
+            "TwoFactor" => SignatureMethod::TwoFactor,
+            _ => SignatureMethod::Password,
+        }
+    }
+
+    /// Whether a signature made this way meets a policy's minimum method
+    pub const fn satisfies(&self, minimum: &SignatureMethod) -> bool {
+        match minimum {
+            SignatureMethod::Password => true,
+            SignatureMethod::DigitalCertificate => {
+                matches!(self, SignatureMethod::DigitalCertificate | SignatureMethod::TwoFactor)
+            }
+            SignatureMethod::TwoFactor => matches!(self, SignatureMethod::TwoFactor),
+            SignatureMethod::Biometric => matches!(self, SignatureMethod::Biometric),
+        }
+    }
 }
 
 /// Electronic signature requirements for different actions
@@ -49,6 +81,8 @@ pub struct SignaturePolicy {
 pub struct ElectronicSignatureManager {
     project_path: PathBuf,
     requirements: SignatureRequirements,
+    key_store: SigningKeyStore,
+    mfa_store: MfaStore,
 }
 
 /// Signature verification result
@@ -88,9 +122,65 @@ impl ElectronicSignature {
             signature_method,
             certificate_info: None,
             reason,
+            content_hash: None,
+            key_fingerprint: None,
+            signature_value: None,
         })
     }
 
+    /// Create a digitally signed electronic signature bound to the record content
+    pub fn sign(
+        user_id: String,
+        meaning: String,
+        entity_type: String,
+        entity_id: String,
+        record_content: &[u8],
+        reason: Option<String>,
+        signing_key: &SigningKey,
+    ) -> QmsResult<Self> {
+        let mut signature = Self::new(
+            user_id,
+            meaning,
+            entity_type,
+            entity_id,
+            SignatureMethod::DigitalCertificate,
+            reason,
+        )?;
+
+        let fingerprint = key_fingerprint(&signing_key.verifying_key());
+        signature.content_hash = Some(hash_record_content(record_content));
+        signature.certificate_info = Some(format!("Ed25519 key {fingerprint}"));
+        signature.key_fingerprint = Some(fingerprint);
+        signature.signature_value = Some(sign_message(signing_key, signature.signed_manifest().as_bytes()));
+
+        Ok(signature)
+    }
+
+    /// Canonical manifest covered by the digital signature
+    pub fn signed_manifest(&self) -> String {
+        format!(
+            "qms-esig-v1\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
+            self.id,
+            self.user_id,
+            self.timestamp,
+            self.meaning,
+            self.entity_type,
+            self.entity_id,
+            self.content_hash.as_deref().unwrap_or(""),
+            self.reason.as_deref().unwrap_or("")
+        )
+    }
+
+    /// Check whether this signature carries a digital signature
+    pub const fn is_digitally_signed(&self) -> bool {
+        self.signature_value.is_some() && self.key_fingerprint.is_some() && self.content_hash.is_some()
+    }
+
+    /// Check whether the signature was made over the given record content
+    pub fn matches_record(&self, record_content: &[u8]) -> bool {
+        self.content_hash.as_deref() == Some(hash_record_content(record_content).as_str())
+    }
+
     /// Verify the signature hash
     pub fn verify_hash(&self) -> bool {
         let signature_data = format!("{}|{}|{}|{}|{}", 
@@ -101,21 +191,32 @@ impl ElectronicSignature {
     }
 }
 
+/// SHA-256 of the signed record content (hex)
+pub fn hash_record_content(record_content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(record_content))
+}
+
 impl JsonSerializable for ElectronicSignature {
     fn to_json(&self) -> String {
-        format!(
-            r#"{{"id": "{}", "user_id": "{}", "timestamp": "{}", "meaning": "{}", "signature_hash": "{}", "entity_type": "{}", "entity_id": "{}", "signature_method": "{:?}", "certificate_info": {}, "reason": {}}}"#,
-            self.id,
-            self.user_id,
-            self.timestamp,
-            self.meaning,
-            self.signature_hash,
-            self.entity_type,
-            self.entity_id,
-            self.signature_method,
-            self.certificate_info.as_ref().map(|s| format!("\"{s}\"")).unwrap_or_else(|| "null".to_string()),
-            self.reason.as_ref().map(|s| format!("\"{s}\"")).unwrap_or_else(|| "null".to_string())
-        )
+        let optional = |value: &Option<String>| {
+            value.as_ref().map_or(JsonValue::Null, |s| JsonValue::String(s.clone()))
+        };
+
+        let mut obj = HashMap::new();
+        obj.insert("id".to_string(), JsonValue::String(self.id.clone()));
+        obj.insert("user_id".to_string(), JsonValue::String(self.user_id.clone()));
+        obj.insert("timestamp".to_string(), JsonValue::String(self.timestamp.clone()));
+        obj.insert("meaning".to_string(), JsonValue::String(self.meaning.clone()));
+        obj.insert("signature_hash".to_string(), JsonValue::String(self.signature_hash.clone()));
+        obj.insert("entity_type".to_string(), JsonValue::String(self.entity_type.clone()));
+        obj.insert("entity_id".to_string(), JsonValue::String(self.entity_id.clone()));
+        obj.insert("signature_method".to_string(), JsonValue::String(format!("{:?}", self.signature_method)));
+        obj.insert("certificate_info".to_string(), optional(&self.certificate_info));
+        obj.insert("reason".to_string(), optional(&self.reason));
+        obj.insert("content_hash".to_string(), optional(&self.content_hash));
+        obj.insert("key_fingerprint".to_string(), optional(&self.key_fingerprint));
+        obj.insert("signature_value".to_string(), optional(&self.signature_value));
+        JsonValue::Object(obj).json_to_string()
     }
 
     fn from_json(json: &str) -> Result<Self, JsonError> {
@@ -129,6 +230,9 @@ impl JsonSerializable for ElectronicSignature {
                     None => Err(JsonError::InvalidFormat(format!("Missing required field '{field}'"))),
                 }
             };
+            let extract_optional_field = |obj: &HashMap<String, JsonValue>, field: &str| -> Option<String> {
+                obj.get(field).and_then(|v| v.as_string().cloned())
+            };
             
             let user_id = extract_string_field(&obj, "user_id")?;
             let timestamp = extract_string_field(&obj, "timestamp")?;
@@ -136,6 +240,9 @@ impl JsonSerializable for ElectronicSignature {
             let signature_hash = extract_string_field(&obj, "signature_hash")?;
             let entity_type = extract_string_field(&obj, "entity_type")?;
             let entity_id = extract_string_field(&obj, "entity_id")?;
+            let signature_method = extract_optional_field(&obj, "signature_method")
+                .map(|name| SignatureMethod::from_name(&name))
+                .unwrap_or(SignatureMethod::Password);
 
             Ok(ElectronicSignature {
                 id: extract_string_field(&obj, "id")?,
@@ -145,9 +252,12 @@ impl JsonSerializable for ElectronicSignature {
                 signature_hash,
                 entity_type,
                 entity_id,
-                signature_method: SignatureMethod::Password, // Default
-                certificate_info: None,
-                reason: None,
+                signature_method,
+                certificate_info: extract_optional_field(&obj, "certificate_info"),
+                reason: extract_optional_field(&obj, "reason"),
+                content_hash: extract_optional_field(&obj, "content_hash"),
+                key_fingerprint: extract_optional_field(&obj, "key_fingerprint"),
+                signature_value: extract_optional_field(&obj, "signature_value"),
             })
         } else {
             Err(JsonError::InvalidFormat("Expected JSON object".to_string()))
@@ -175,6 +285,14 @@ impl Default for SignatureRequirements {
             requires_reason: true,
         });
         
+        // Requirement approval requires signature
+        requirements.insert("requirement_approve".to_string(), SignaturePolicy {
+            required: true,
+            meaning: "Requirement approved".to_string(),
+            minimum_method: SignatureMethod::Password,
+            requires_reason: false,
+        });
+        
         // Risk acceptance requires signature
         requirements.insert("risk_accept".to_string(), SignaturePolicy {
             required: true,
@@ -183,6 +301,38 @@ impl Default for SignatureRequirements {
             requires_reason: true,
         });
         
+        // CAPA closure requires signature
+        requirements.insert("capa_close".to_string(), SignaturePolicy {
+            required: true,
+            meaning: "CAPA closed as effective".to_string(),
+            minimum_method: SignatureMethod::Password,
+            requires_reason: false,
+        });
+        
+        // Nonconformance disposition requires signature from each required role
+        requirements.insert("ncr_disposition".to_string(), SignaturePolicy {
+            required: true,
+            meaning: "Nonconformance disposition approved".to_string(),
+            minimum_method: SignatureMethod::Password,
+            requires_reason: false,
+        });
+        
+        // Design changes require a signature from each CCB member
+        requirements.insert("ccb_approval".to_string(), SignaturePolicy {
+            required: true,
+            meaning: "Design change approved by Change Control Board".to_string(),
+            minimum_method: SignatureMethod::Password,
+            requires_reason: false,
+        });
+        
+        // Design review minutes are finalized by every attendee's signature
+        requirements.insert("design_review_signoff".to_string(), SignaturePolicy {
+            required: true,
+            meaning: "Design review minutes approved".to_string(),
+            minimum_method: SignatureMethod::Password,
+            requires_reason: false,
+        });
+        
         // System configuration changes require signature
         requirements.insert("system_config".to_string(), SignaturePolicy {
             required: true,
@@ -199,6 +349,8 @@ impl ElectronicSignatureManager {
     /// Create new signature manager
     pub fn new(project_path: PathBuf) -> Self {
         Self {
+            key_store: SigningKeyStore::new(&project_path),
+            mfa_store: MfaStore::new(&project_path),
             project_path,
             requirements: SignatureRequirements::default(),
         }
@@ -217,13 +369,36 @@ impl ElectronicSignatureManager {
         self.requirements.requirements.get(action)
     }
 
-    /// Create electronic signature for an action
+    /// Create electronic signature for an action.
+    /// The signer's password unlocks their private key; the signature covers
+    /// the SHA-256 of `record_content`, linking it to the exact record signed.
     pub fn create_signature(
         &self,
         user_id: String,
+        password: &str,
+        action: &str,
+        entity_type: String,
+        entity_id: String,
+        record_content: &[u8],
+        reason: Option<String>,
+    ) -> QmsResult<ElectronicSignature> {
+        self.create_signature_with_code(user_id, password, None, action, entity_type, entity_id, record_content, reason)
+    }
+
+    /// Create electronic signature, re-challenging signers enrolled in MFA.
+    /// Enrolled signers must supply a TOTP or recovery code with every signing
+    /// and the signature is recorded as `TwoFactor`, otherwise as
+    /// `DigitalCertificate`; the action's policy minimum method must be met.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_signature_with_code(
+        &self,
+        user_id: String,
+        password: &str,
+        code: Option<&str>,
         action: &str,
         entity_type: String,
         entity_id: String,
+        record_content: &[u8],
         reason: Option<String>,
     ) -> QmsResult<ElectronicSignature> {
         // Get policy for this action
@@ -235,15 +410,43 @@ impl ElectronicSignatureManager {
             return Err(QmsError::validation_error("Reason is required for this signature"));
         }
 
+        // The method this signer can reach, checked before any key or code is used
+        let method = if self.mfa_store.is_enrolled(&user_id) {
+            SignatureMethod::TwoFactor
+        } else {
+            SignatureMethod::DigitalCertificate
+        };
+        if !method.satisfies(&policy.minimum_method) {
+            let hint = if policy.minimum_method == SignatureMethod::TwoFactor {
+                "; enroll with 'qms user mfa enroll'"
+            } else {
+                ""
+            };
+            return Err(QmsError::permission_error(&format!(
+                "Action {action} requires {:?} signing, but {user_id} can only sign with {method:?}{hint}",
+                policy.minimum_method
+            )));
+        }
+
+        // Unlocking the key authenticates the signer
+        let signing_key = self.key_store.unlock(&user_id, password)?;
+
+        // Second identification component, checked after the password
+        let second_factor = self.mfa_store.challenge(&user_id, code)?;
+
         // Create the signature
-        let signature = ElectronicSignature::new(
+        let mut signature = ElectronicSignature::sign(
             user_id,
             policy.meaning.clone(),
             entity_type,
             entity_id,
-            policy.minimum_method.clone(),
+            record_content,
             reason,
+            &signing_key,
         )?;
+        if second_factor.is_some() {
+            signature.signature_method = method;
+        }
 
         // Store the signature
         self.store_signature(&signature)?;
@@ -254,15 +457,10 @@ impl ElectronicSignatureManager {
         Ok(signature)
     }
 
-    /// Verify an electronic signature
+    /// Verify an electronic signature against the signer's stored public key
     pub fn verify_signature(&self, signature_id: &str) -> QmsResult<SignatureVerification> {
         let signature = self.load_signature(signature_id)?;
-        let is_valid = signature.verify_hash();
-        let verification_details = if is_valid {
-            "Signature hash verification passed".to_string()
-        } else {
-            "Signature hash verification failed".to_string()
-        };
+        let (is_valid, verification_details) = self.check_signature(&signature);
 
         Ok(SignatureVerification {
             is_valid,
@@ -272,6 +470,43 @@ impl ElectronicSignatureManager {
         })
     }
 
+    /// Verify an electronic signature and that it was applied to `record_content`
+    pub fn verify_signature_for_record(&self, signature_id: &str, record_content: &[u8]) -> QmsResult<SignatureVerification> {
+        let mut verification = self.verify_signature(signature_id)?;
+
+        if verification.is_valid && !verification.signature.matches_record(record_content) {
+            verification.is_valid = false;
+            verification.verification_details =
+                "Record content has changed since it was signed".to_string();
+        }
+
+        Ok(verification)
+    }
+
+    /// Check a signature's cryptographic validity, returning the outcome and details
+    fn check_signature(&self, signature: &ElectronicSignature) -> (bool, String) {
+        if !signature.verify_hash() {
+            return (false, "Signature manifest hash verification failed".to_string());
+        }
+
+        let (Some(fingerprint), Some(signature_value)) =
+            (&signature.key_fingerprint, &signature.signature_value)
+        else {
+            return (false, "Signature has no digital signature (legacy hash-only record)".to_string());
+        };
+
+        match self.key_store.verify(
+            &signature.user_id,
+            fingerprint,
+            signature.signed_manifest().as_bytes(),
+            signature_value,
+        ) {
+            Ok(true) => (true, format!("Ed25519 signature verified with key {fingerprint}")),
+            Ok(false) => (false, "Ed25519 signature does not match the signed manifest".to_string()),
+            Err(e) => (false, format!("Public key lookup failed: {e}")),
+        }
+    }
+
     /// Store signature to file system
     fn store_signature(&self, signature: &ElectronicSignature) -> QmsResult<()> {
         let signatures_dir = self.project_path.join("signatures");
@@ -280,7 +515,7 @@ impl ElectronicSignatureManager {
         let signature_file = signatures_dir.join(format!("{}.json", signature.id));
         let signature_json = signature.to_json();
         
-        crate::fs_utils::atomic_write(&signature_file, &signature_json)?;
+        crate::modules::storage::encryption::atomic_write(&signature_file, &signature_json)?;
         
         Ok(())
     }
@@ -293,7 +528,7 @@ impl ElectronicSignatureManager {
             return Err(QmsError::not_found(&format!("Signature not found: {signature_id}")));
         }
 
-        let signature_json = std::fs::read_to_string(&signature_file)?;
+        let signature_json = crate::modules::storage::encryption::read_to_string(&signature_file)?;
         ElectronicSignature::from_json(&signature_json)
             .map_err(|e| QmsError::validation_error(&e.to_string()))
     }
@@ -322,7 +557,7 @@ impl ElectronicSignatureManager {
             let path = entry.path();
 
             if path.extension().is_some_and(|ext| ext == "json") {
-                if let Ok(signature_json) = std::fs::read_to_string(&path) {
+                if let Ok(signature_json) = crate::modules::storage::encryption::read_to_string(&path) {
                     if let Ok(signature) = ElectronicSignature::from_json(&signature_json) {
                         if signature.entity_type == entity_type && signature.entity_id == entity_id {
                             signatures.push(signature);
@@ -355,7 +590,7 @@ impl ElectronicSignatureManager {
 
         // Check if there's a valid signature with the required meaning
         let has_valid_signature = signatures.iter().any(|sig| {
-            sig.meaning == policy.meaning && sig.verify_hash()
+            sig.meaning == policy.meaning && self.check_signature(sig).0
         });
 
         Ok(has_valid_signature)
@@ -406,6 +641,13 @@ pub fn format_signature_verification(verification: &SignatureVerification) -> St
     }
     
     output.push_str(&format!("  Hash: {}\n", verification.signature.signature_hash));
+
+    if let Some(ref content_hash) = verification.signature.content_hash {
+        output.push_str(&format!("  Record SHA-256: {content_hash}\n"));
+    }
+    if let Some(ref fingerprint) = verification.signature.key_fingerprint {
+        output.push_str(&format!("  Key Fingerprint: {fingerprint}\n"));
+    }
     
     output.push_str(&format!("\nVerification Details: {}\n", verification.verification_details));
     
@@ -415,6 +657,17 @@ pub fn format_signature_verification(verification: &SignatureVerification) -> St
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::modules::audit_logger::{initialize_audit_system, AuditConfig};
+
+    fn init_audit_for_test(project_path: &std::path::Path) {
+        let _ = initialize_audit_system(AuditConfig {
+            project_path: project_path.to_string_lossy().to_string(),
+            retention_days: 30,
+            daily_rotation: false,
+            max_file_size_mb: 10,
+            require_checksums: false,
+        });
+    }
 
     #[test]
     fn test_electronic_signature_creation() {
@@ -456,6 +709,130 @@ mod tests {
         assert!(requirements.requirements.contains_key("system_config"));
     }
 
+    #[test]
+    fn test_digital_signature_bound_to_record() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        init_audit_for_test(temp_dir.path());
+        let key_store = SigningKeyStore::new(temp_dir.path());
+        key_store.generate_key_pair("testuser", "password123").unwrap();
+        let manager = ElectronicSignatureManager::new(temp_dir.path().to_path_buf());
+
+        let record = b"{\"id\": \"DOC-001\", \"version\": \"1.0\"}";
+        let signature = manager.create_signature(
+            "testuser".to_string(),
+            "password123",
+            "document_approve",
+            "Document".to_string(),
+            "DOC-001".to_string(),
+            record,
+            None,
+        ).expect("Should create signature");
+
+        assert_eq!(signature.signature_method, SignatureMethod::DigitalCertificate);
+        assert!(signature.is_digitally_signed());
+
+        let verification = manager.verify_signature_for_record(&signature.id, record).unwrap();
+        assert!(verification.is_valid, "{}", verification.verification_details);
+
+        let tampered = manager.verify_signature_for_record(&signature.id, b"changed").unwrap();
+        assert!(!tampered.is_valid);
+    }
+
+    #[test]
+    fn test_forged_signature_rejected() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        init_audit_for_test(temp_dir.path());
+        SigningKeyStore::new(temp_dir.path()).generate_key_pair("testuser", "password123").unwrap();
+        let manager = ElectronicSignatureManager::new(temp_dir.path().to_path_buf());
+
+        assert!(manager.create_signature(
+            "testuser".to_string(),
+            "wrong-password",
+            "document_approve",
+            "Document".to_string(),
+            "DOC-001".to_string(),
+            b"record",
+            None,
+        ).is_err());
+
+        // A hash-only signature written directly to disk must not verify
+        let forged = ElectronicSignature::new(
+            "testuser".to_string(),
+            "Document approved".to_string(),
+            "Document".to_string(),
+            "DOC-001".to_string(),
+            SignatureMethod::Password,
+            None,
+        ).unwrap();
+        manager.store_signature(&forged).unwrap();
+
+        let verification = manager.verify_signature(&forged.id).unwrap();
+        assert!(!verification.is_valid);
+    }
+
+    #[test]
+    fn test_signature_rechallenges_enrolled_signer() {
+        use crate::modules::user_manager::mfa::{base32_decode, totp_code};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        init_audit_for_test(temp_dir.path());
+        SigningKeyStore::new(temp_dir.path()).generate_key_pair("testuser", "password123").unwrap();
+        let manager = ElectronicSignatureManager::new(temp_dir.path().to_path_buf());
+
+        let sign = |code: Option<&str>| manager.create_signature_with_code(
+            "testuser".to_string(),
+            "password123",
+            code,
+            "document_approve",
+            "Document".to_string(),
+            "DOC-001".to_string(),
+            b"record",
+            None,
+        );
+
+        let setup = MfaStore::new(temp_dir.path()).enroll("testuser", "QMS").unwrap();
+        assert!(sign(None).is_err());
+
+        let code = totp_code(&base32_decode(&setup.secret).unwrap(), crate::utils::current_timestamp());
+        let signature = sign(Some(&code)).unwrap();
+        assert_eq!(signature.signature_method, SignatureMethod::TwoFactor);
+        assert!(manager.verify_signature(&signature.id).unwrap().is_valid);
+
+        // Recovery codes also satisfy the re-challenge, once each
+        assert!(sign(Some(&setup.recovery_codes[0])).is_ok());
+        assert!(sign(Some(&setup.recovery_codes[0])).is_err());
+    }
+
+    #[test]
+    fn test_policy_minimum_method_enforced() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        init_audit_for_test(temp_dir.path());
+        SigningKeyStore::new(temp_dir.path()).generate_key_pair("testuser", "password123").unwrap();
+        let mut manager = ElectronicSignatureManager::new(temp_dir.path().to_path_buf());
+        manager.requirements.requirements.insert("biometric_only".to_string(), SignaturePolicy {
+            required: true,
+            meaning: "Biometric approval".to_string(),
+            minimum_method: SignatureMethod::Biometric,
+            requires_reason: false,
+        });
+
+        let sign = |action: &str| manager.create_signature(
+            "testuser".to_string(),
+            "password123",
+            action,
+            "Document".to_string(),
+            "DOC-001".to_string(),
+            b"record",
+            Some("configuration change".to_string()),
+        );
+        assert!(sign("biometric_only").is_err());
+        assert!(sign("system_config").is_err());
+        assert_eq!(sign("document_approve").unwrap().signature_method, SignatureMethod::DigitalCertificate);
+
+        assert!(SignatureMethod::TwoFactor.satisfies(&SignatureMethod::DigitalCertificate));
+        assert!(!SignatureMethod::DigitalCertificate.satisfies(&SignatureMethod::TwoFactor));
+    }
+
     #[test]
     fn test_signature_manager() {
         let temp_dir = std::path::PathBuf::from("/tmp/test_signatures");
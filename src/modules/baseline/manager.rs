@@ -0,0 +1,481 @@
+//! Baseline Manager
+//!
+//! Captures baselines under `baselines/<name>.json`. Each entity's serialized
+//! form is kept in `baselines/objects/`, named by the SHA-256 of its canonical
+//! JSON, so unchanged entities are stored once across baselines and reports can
+//! be rebuilt from exactly what was frozen.
+
+use crate::json_utils::{JsonSerializable, JsonValue};
+use crate::modules::audit_logger::functions::audit_log_create;
+use crate::modules::document_control::document::Document;
+use crate::modules::document_control::service::{DocumentIndexEntry, DocumentService};
+use crate::modules::risk_manager::risk::{RiskItem, RiskManager};
+use crate::modules::storage::{encryption, StorageEntity};
+use crate::modules::traceability::links::{TraceabilityLink, TraceabilityManager};
+use crate::modules::traceability::requirement::{Requirement, RequirementManager};
+use crate::prelude::*;
+use crate::utils::encode_hex;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::record::{Baseline, BaselineDiff, BaselineItem, BaselineKind};
+
+/// Name used for the current, unfrozen state of the design record
+pub const WORKING_STATE: &str = "working";
+
+/// An entity captured from the working state with its serialized form
+struct CapturedEntity {
+    item: BaselineItem,
+    snapshot: String,
+}
+
+/// Configuration baseline manager
+pub struct BaselineManager {
+    project_path: PathBuf,
+    baselines_dir: PathBuf,
+}
+
+impl BaselineManager {
+    /// Create new baseline manager for a project
+    pub fn new(project_path: &Path) -> QmsResult<Self> {
+        let baselines_dir = project_path.join("baselines");
+        fs::create_dir_all(&baselines_dir)?;
+        Ok(Self {
+            project_path: project_path.to_path_buf(),
+            baselines_dir,
+        })
+    }
+
+    /// Freeze the current design record under `name`
+    pub fn create(&self, name: &str, description: &str, created_by: &str) -> QmsResult<Baseline> {
+        validate_name(name)?;
+        if self.exists(name) {
+            return Err(QmsError::already_exists(&format!("Baseline {name} already exists")));
+        }
+
+        let captured = self.capture()?;
+        for entity in &captured {
+            self.store_snapshot(&entity.item.sha256, &entity.snapshot)?;
+        }
+        let baseline = Baseline {
+            name: name.to_string(),
+            description: description.to_string(),
+            created_by: created_by.to_string(),
+            created_at: crate::utils::current_iso8601_timestamp(),
+            items: captured.into_iter().map(|entity| entity.item).collect(),
+        };
+
+        encryption::atomic_write(&self.baseline_path(name), &baseline.to_json())?;
+        audit_log_create("Baseline", name, &format!("{} item(s): {description}", baseline.items.len()))?;
+        Ok(baseline)
+    }
+
+    /// Whether a baseline with this name exists
+    pub fn exists(&self, name: &str) -> bool {
+        validate_name(name).is_ok() && self.baseline_path(name).is_file()
+    }
+
+    /// Load a baseline
+    pub fn load(&self, name: &str) -> QmsResult<Baseline> {
+        if !self.exists(name) {
+            return Err(QmsError::not_found(&format!("Baseline {name} not found")));
+        }
+        let content = encryption::read_to_string(self.baseline_path(name))?;
+        Baseline::from_json(&content).map_err(|e| QmsError::parse_error(&format!("Invalid baseline {name}: {e}")))
+    }
+
+    /// List baselines, oldest first
+    pub fn list(&self) -> QmsResult<Vec<Baseline>> {
+        let mut baselines = Vec::new();
+        for entry in fs::read_dir(&self.baselines_dir)? {
+            let path = entry?.path();
+            if !path.is_file() || !path.extension().is_some_and(|ext| ext == "json") {
+                continue;
+            }
+            let content = encryption::read_to_string(&path)?;
+            baselines.push(
+                Baseline::from_json(&content)
+                    .map_err(|e| QmsError::parse_error(&format!("Invalid baseline {}: {e}", path.display())))?,
+            );
+        }
+        baselines.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.name.cmp(&b.name)));
+        Ok(baselines)
+    }
+
+    /// The current design record as an unsaved baseline named `working`
+    pub fn working_state(&self) -> QmsResult<Baseline> {
+        Ok(Baseline {
+            name: WORKING_STATE.to_string(),
+            description: "Current working state".to_string(),
+            created_by: String::new(),
+            created_at: crate::utils::current_iso8601_timestamp(),
+            items: self.capture()?.into_iter().map(|entity| entity.item).collect(),
+        })
+    }
+
+    /// Compare two baselines; `working` on either side stands for the current state
+    pub fn compare(&self, from: &str, to: &str) -> QmsResult<BaselineDiff> {
+        let load = |name: &str| {
+            if name == WORKING_STATE {
+                self.working_state()
+            } else {
+                self.load(name)
+            }
+        };
+        Ok(load(from)?.diff(&load(to)?))
+    }
+
+    /// Check every snapshot of a baseline against its fingerprint
+    pub fn verify(&self, name: &str) -> QmsResult<Vec<String>> {
+        let baseline = self.load(name)?;
+        let mut problems = Vec::new();
+        for item in &baseline.items {
+            if let Err(e) = self.snapshot(item) {
+                problems.push(format!("{} {}: {e}", item.kind.name(), item.id));
+            }
+        }
+        Ok(problems)
+    }
+
+    /// Serialized form of an entity as frozen in a baseline, verified against its fingerprint
+    pub fn snapshot(&self, item: &BaselineItem) -> QmsResult<String> {
+        let path = self.object_path(&item.sha256)?;
+        if !path.is_file() {
+            return Err(QmsError::not_found(&format!("Snapshot {} is missing", item.sha256)));
+        }
+        let snapshot = encryption::read_to_string(&path)?;
+        if fingerprint(&snapshot) != item.sha256 {
+            return Err(QmsError::validation_error(&format!(
+                "Snapshot {} failed its integrity check (contents do not match the fingerprint)",
+                item.sha256
+            )));
+        }
+        Ok(snapshot)
+    }
+
+    /// Documents as frozen in a baseline
+    pub fn documents(&self, baseline: &Baseline) -> QmsResult<Vec<Document>> {
+        baseline
+            .items_of(BaselineKind::Document)
+            .map(|item| {
+                Document::from_json(&self.snapshot(item)?)
+                    .map_err(|e| QmsError::parse_error(&format!("Invalid document snapshot {}: {e}", item.id)))
+            })
+            .collect()
+    }
+
+    /// Document index entries as frozen in a baseline, for document reports
+    pub fn document_index(&self, baseline: &Baseline) -> QmsResult<Vec<DocumentIndexEntry>> {
+        Ok(self
+            .documents(baseline)?
+            .into_iter()
+            .map(|document| DocumentIndexEntry {
+                doc_type: format!("{:?}", document.doc_type),
+                status: format!("{:?}", document.status),
+                id: document.id,
+                title: document.title,
+                version: document.version,
+                created_at: document.created_at,
+                updated_at: document.updated_at,
+                file_path: document.file_path,
+                author: document.created_by,
+            })
+            .collect())
+    }
+
+    /// Requirements as frozen in a baseline
+    pub fn requirements(&self, baseline: &Baseline) -> QmsResult<Vec<Requirement>> {
+        self.entities(baseline, BaselineKind::Requirement)
+    }
+
+    /// Risks as frozen in a baseline
+    pub fn risks(&self, baseline: &Baseline) -> QmsResult<Vec<RiskItem>> {
+        self.entities(baseline, BaselineKind::Risk)
+    }
+
+    /// Trace links as frozen in a baseline
+    pub fn trace_links(&self, baseline: &Baseline) -> QmsResult<Vec<TraceabilityLink>> {
+        self.entities(baseline, BaselineKind::TraceLink)
+    }
+
+    fn entities<T: StorageEntity>(&self, baseline: &Baseline, kind: BaselineKind) -> QmsResult<Vec<T>> {
+        baseline.items_of(kind).map(|item| T::from_storage_json(&self.snapshot(item)?)).collect()
+    }
+
+    /// Capture every entity of the working state, ordered by kind and ID
+    fn capture(&self) -> QmsResult<Vec<CapturedEntity>> {
+        let mut captured = Vec::new();
+
+        let documents = DocumentService::new(self.project_path.clone());
+        for entry in documents.list_documents()? {
+            let document = documents.read_document(&entry.id)?;
+            captured.push(CapturedEntity::new(
+                BaselineKind::Document,
+                &document.id,
+                &document.title,
+                &document.version,
+                document.to_json(),
+            ));
+        }
+
+        for requirement in RequirementManager::new(&self.project_path)?.list_requirements() {
+            captured.push(CapturedEntity::new(
+                BaselineKind::Requirement,
+                &requirement.req_id,
+                &requirement.title,
+                &requirement.updated_at,
+                requirement.to_json(),
+            ));
+        }
+
+        for risk in RiskManager::new(&self.project_path)?.list_all_risks()? {
+            captured.push(CapturedEntity::new(
+                BaselineKind::Risk,
+                &risk.hazard_id,
+                &risk.hazard_description,
+                &risk.updated_at,
+                risk.to_storage_json(),
+            ));
+        }
+
+        for link in TraceabilityManager::new(&self.project_path)?.get_trace_links()? {
+            captured.push(CapturedEntity::new(
+                BaselineKind::TraceLink,
+                &link.id,
+                &format!("{} {} {}", link.source_id, link.link_type, link.target_id),
+                &link.created_at,
+                link.to_json(),
+            ));
+        }
+
+        // Test case files are captured as stored; the test case loader does not read them back in full
+        let tests_dir = self.project_path.join("tests");
+        if tests_dir.is_dir() {
+            for entry in fs::read_dir(&tests_dir)? {
+                let path = entry?.path();
+                if !path.is_file() || !path.extension().is_some_and(|ext| ext == "json") {
+                    continue;
+                }
+                let snapshot = encryption::read_to_string(&path)?;
+                let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+                let (test_id, title) = match JsonValue::parse(&snapshot)? {
+                    JsonValue::Object(obj) => {
+                        let field = |name: &str| obj.get(name).and_then(|v| v.as_string().cloned());
+                        (field("test_id").unwrap_or(stem), field("title").unwrap_or_default())
+                    }
+                    _ => (stem, String::new()),
+                };
+                captured.push(CapturedEntity::new(BaselineKind::TestCase, &test_id, &title, "", snapshot));
+            }
+        }
+
+        captured.sort_by(|a, b| a.item.kind.cmp(&b.item.kind).then_with(|| a.item.id.cmp(&b.item.id)));
+        Ok(captured)
+    }
+
+    fn store_snapshot(&self, hash: &str, snapshot: &str) -> QmsResult<()> {
+        let path = self.object_path(hash)?;
+        if path.is_file() {
+            return Ok(());
+        }
+        let dir = path.parent().expect("object paths have a fan-out directory");
+        fs::create_dir_all(dir)?;
+
+        // Write under a temporary name so a crash never leaves a truncated snapshot;
+        // the seal is bound to the snapshot's final path
+        let temp_path = dir.join(format!("{}.tmp-{}", &hash[2..], std::process::id()));
+        fs::write(&temp_path, encryption::seal(&path, snapshot)?)?;
+        fs::rename(&temp_path, &path)?;
+        Ok(())
+    }
+
+    fn object_path(&self, hash: &str) -> QmsResult<PathBuf> {
+        if hash.len() != 64 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(QmsError::validation_error(&format!("Invalid snapshot fingerprint '{hash}'")));
+        }
+        Ok(self.baselines_dir.join("objects").join(&hash[..2]).join(format!("{}.json", &hash[2..])))
+    }
+
+    fn baseline_path(&self, name: &str) -> PathBuf {
+        self.baselines_dir.join(format!("{name}.json"))
+    }
+}
+
+impl CapturedEntity {
+    fn new(kind: BaselineKind, id: &str, title: &str, revision: &str, snapshot: String) -> Self {
+        Self {
+            item: BaselineItem {
+                kind,
+                id: id.to_string(),
+                title: title.to_string(),
+                revision: revision.to_string(),
+                sha256: fingerprint(&snapshot),
+            },
+            snapshot,
+        }
+    }
+}
+
+/// SHA-256 of an entity's canonical JSON, independent of field order and whitespace.
+/// Risk records are not strict JSON (enum levels are written bare), so anything that
+/// does not parse is fingerprinted as stored.
+fn fingerprint(snapshot: &str) -> String {
+    match JsonValue::parse(snapshot) {
+        Ok(value) => encode_hex(&Sha256::digest(value.to_canonical_string().as_bytes())),
+        Err(_) => encode_hex(&Sha256::digest(snapshot.as_bytes())),
+    }
+}
+
+/// Baseline names are used as file names: letters, digits, '.', '-' and '_'
+fn validate_name(name: &str) -> QmsResult<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name != WORKING_STATE
+        && !name.starts_with('.')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(QmsError::validation_error(&format!(
+            "Invalid baseline name '{name}': use up to 64 letters, digits, '.', '-' or '_' (and not '{WORKING_STATE}')"
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::audit_logger::{initialize_audit_system, AuditConfig};
+    use crate::modules::baseline::record::ChangeKind;
+    use crate::modules::document_control::document::DocumentType;
+    use crate::modules::traceability::links::TraceLinkType;
+    use crate::modules::traceability::requirement::{RequirementCategory, RequirementUpdate};
+
+    fn setup() -> (tempfile::TempDir, BaselineManager) {
+        let dir = tempfile::tempdir().unwrap();
+        let _ = initialize_audit_system(AuditConfig {
+            project_path: dir.path().to_string_lossy().to_string(),
+            retention_days: 30,
+            daily_rotation: false,
+            max_file_size_mb: 10,
+            require_checksums: false,
+        });
+
+        let mut requirements = RequirementManager::new(dir.path()).unwrap();
+        for (req_id, title) in [("REQ-001", "Flow accuracy"), ("REQ-002", "Occlusion alarm")] {
+            requirements.create_requirement(
+                "project-001".to_string(),
+                req_id.to_string(),
+                title.to_string(),
+                format!("{title} shall be specified"),
+                RequirementCategory::Functional,
+                "alice".to_string(),
+            ).unwrap();
+        }
+        TraceabilityManager::new(dir.path()).unwrap()
+            .create_trace_link("REQ-002", "REQ-001", TraceLinkType::DerivedFrom).unwrap();
+        let mut risk_manager = RiskManager::new(dir.path()).unwrap();
+        risk_manager.initialize().unwrap();
+        risk_manager.create_risk("Over-infusion", "Rate drift", "Overdose").unwrap();
+        DocumentService::new(dir.path().to_path_buf()).create_document(
+            "Pump SRS".to_string(),
+            "Flow accuracy 5%".to_string(),
+            DocumentType::SoftwareRequirementsSpecification,
+            "alice".to_string(),
+        ).unwrap();
+        fs::create_dir_all(dir.path().join("tests")).unwrap();
+        fs::write(dir.path().join("tests").join("TC-001.json"), r#"{"test_id":"TC-001","title":"Flow test"}"#).unwrap();
+
+        let manager = BaselineManager::new(dir.path()).unwrap();
+        (dir, manager)
+    }
+
+    #[test]
+    fn test_baseline_captures_every_entity_kind() {
+        let (_dir, manager) = setup();
+        let baseline = manager.create("R1.0", "First release", "alice").unwrap();
+
+        for kind in BaselineKind::ALL {
+            assert!(baseline.items_of(kind).count() > 0, "no {} captured", kind.name());
+        }
+        assert_eq!(baseline.find(BaselineKind::TestCase, "TC-001").unwrap().title, "Flow test");
+        assert_eq!(manager.load("R1.0").unwrap(), baseline);
+        assert!(manager.compare("R1.0", WORKING_STATE).unwrap().is_empty());
+
+        assert!(manager.create("R1.0", "Again", "alice").is_err());
+        assert!(manager.create("../R1", "Escape", "alice").is_err());
+        assert!(manager.create(WORKING_STATE, "Reserved", "alice").is_err());
+        assert!(manager.verify("R1.0").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_compare_reports_added_removed_and_changed_entities() {
+        let (dir, manager) = setup();
+        manager.create("R1.0", "First release", "alice").unwrap();
+
+        let mut requirements = RequirementManager::new(dir.path()).unwrap();
+        let mut update = RequirementUpdate::new();
+        update.description = Some("Deliver within 2% of set rate".to_string());
+        requirements.update_requirement("REQ-001", update).unwrap();
+        RiskManager::new(dir.path()).unwrap().create_risk("Air in line", "Priming", "Embolism").unwrap();
+        fs::remove_file(dir.path().join("tests").join("TC-001.json")).unwrap();
+
+        let diff = manager.compare("R1.0", WORKING_STATE).unwrap();
+        assert_eq!(diff.counts(BaselineKind::Requirement), (0, 0, 1));
+        assert_eq!(diff.counts(BaselineKind::Risk), (1, 0, 0));
+        assert_eq!(diff.counts(BaselineKind::TestCase), (0, 1, 0));
+        assert_eq!(diff.counts(BaselineKind::Document), (0, 0, 0));
+
+        manager.create("R1.1", "Tightened accuracy", "alice").unwrap();
+        let between = manager.compare("R1.0", "R1.1").unwrap();
+        assert_eq!(between.changes, diff.changes);
+        let change = between.changes.iter().find(|c| c.kind == BaselineKind::Requirement).unwrap();
+        assert_eq!((change.id.as_str(), change.change), ("REQ-001", ChangeKind::Changed));
+    }
+
+    #[test]
+    fn test_entities_are_rebuilt_as_frozen_and_tampering_is_detected() {
+        let (dir, manager) = setup();
+        let baseline = manager.create("R1.0", "First release", "alice").unwrap();
+
+        let mut requirements = RequirementManager::new(dir.path()).unwrap();
+        let mut update = RequirementUpdate::new();
+        update.description = Some("Deliver within 2% of set rate".to_string());
+        requirements.update_requirement("REQ-001", update).unwrap();
+
+        let frozen = manager.requirements(&baseline).unwrap();
+        let req = frozen.iter().find(|r| r.req_id == "REQ-001").unwrap();
+        assert_eq!(req.description, "Flow accuracy shall be specified");
+        assert_eq!(manager.risks(&baseline).unwrap().len(), 1);
+        assert_eq!(manager.trace_links(&baseline).unwrap()[0].source_id, "REQ-002");
+        assert_eq!(manager.document_index(&baseline).unwrap()[0].title, "Pump SRS");
+
+        let item = baseline.find(BaselineKind::Requirement, "REQ-001").unwrap();
+        let path = manager.object_path(&item.sha256).unwrap();
+        let tampered = fs::read_to_string(&path).unwrap().replace("shall be specified", "is optional");
+        fs::write(&path, tampered).unwrap();
+        assert_eq!(manager.verify("R1.0").unwrap().len(), 1);
+        assert!(manager.requirements(&baseline).is_err());
+    }
+
+    #[test]
+    fn test_baselines_are_sealed_in_an_encrypted_project() {
+        let (dir, manager) = setup();
+        encryption::enable(dir.path(), &[encryption::KeySource::Passphrase("correct horse battery".to_string())])
+            .unwrap();
+        let baseline = manager.create("R1.0", "First release", "alice").unwrap();
+
+        let mut stored = vec![manager.baseline_path("R1.0")];
+        stored.extend(baseline.items.iter().map(|item| manager.object_path(&item.sha256).unwrap()));
+        for path in &stored {
+            let raw = fs::read_to_string(path).unwrap();
+            assert!(raw.starts_with(encryption::SEALED_PREFIX), "{} is not sealed", path.display());
+        }
+
+        assert_eq!(manager.load("R1.0").unwrap(), baseline);
+        assert!(manager.verify("R1.0").unwrap().is_empty());
+        let frozen = manager.requirements(&baseline).unwrap();
+        assert!(frozen.iter().any(|r| r.description == "Flow accuracy shall be specified"));
+    }
+}
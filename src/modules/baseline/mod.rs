@@ -0,0 +1,15 @@
+//! Configuration Baseline Module
+//!
+//! Configuration management per ISO 13485:2016 Section 7.5.9 and FDA 21 CFR
+//! 820.30(j): named baselines freeze the revision and fingerprint of every
+//! document, requirement, risk, trace link and test case, can be compared with
+//! each other or the working state, and let the DHF, RTM and RMF be generated
+//! for a released configuration.
+
+pub mod manager;
+pub mod record;
+
+#[allow(unused_imports)]
+pub use manager::{BaselineManager, WORKING_STATE};
+#[allow(unused_imports)]
+pub use record::{Baseline, BaselineDiff, BaselineItem, BaselineKind, ChangeKind, ItemChange};
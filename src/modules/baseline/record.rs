@@ -0,0 +1,254 @@
+//! Configuration Baseline Model
+//!
+//! A baseline freezes the design record at a point in time: the revision and
+//! fingerprint of every document, requirement, risk, trace link and test case.
+
+use crate::json_utils::{JsonError, JsonSerializable, JsonValue};
+use crate::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+
+/// Kind of entity captured in a baseline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BaselineKind {
+    Document,
+    Requirement,
+    Risk,
+    TraceLink,
+    TestCase,
+}
+
+impl BaselineKind {
+    /// Every kind, in report order
+    pub const ALL: [BaselineKind; 5] = [
+        BaselineKind::Document,
+        BaselineKind::Requirement,
+        BaselineKind::Risk,
+        BaselineKind::TraceLink,
+        BaselineKind::TestCase,
+    ];
+
+    pub fn parse(s: &str) -> QmsResult<Self> {
+        match s.to_lowercase().replace(['-', '_'], "").as_str() {
+            "document" | "documents" | "doc" | "docs" => Ok(BaselineKind::Document),
+            "requirement" | "requirements" | "req" | "reqs" => Ok(BaselineKind::Requirement),
+            "risk" | "risks" => Ok(BaselineKind::Risk),
+            "tracelink" | "tracelinks" | "link" | "links" | "trace" => Ok(BaselineKind::TraceLink),
+            "testcase" | "testcases" | "test" | "tests" => Ok(BaselineKind::TestCase),
+            _ => Err(QmsError::validation_error(&format!(
+                "Unknown baseline entity type: {s} (expected document, requirement, risk, trace-link or test-case)"
+            ))),
+        }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            BaselineKind::Document => "document",
+            BaselineKind::Requirement => "requirement",
+            BaselineKind::Risk => "risk",
+            BaselineKind::TraceLink => "trace-link",
+            BaselineKind::TestCase => "test-case",
+        }
+    }
+
+    pub const fn plural_name(&self) -> &'static str {
+        match self {
+            BaselineKind::Document => "Documents",
+            BaselineKind::Requirement => "Requirements",
+            BaselineKind::Risk => "Risks",
+            BaselineKind::TraceLink => "Trace Links",
+            BaselineKind::TestCase => "Test Cases",
+        }
+    }
+}
+
+/// One entity as it stood when the baseline was taken
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaselineItem {
+    pub kind: BaselineKind,
+    pub id: String,       // Document ID, REQ-001, HAZ-001, link ID or TC-001
+    pub title: String,
+    pub revision: String, // Document version, or last update time where entities have no version
+    pub sha256: String,   // Fingerprint of the entity's canonical JSON; also names its snapshot
+}
+
+/// Named, immutable snapshot of the design record
+#[derive(Debug, Clone, PartialEq)]
+pub struct Baseline {
+    pub name: String, // e.g. R2.1
+    pub description: String,
+    pub created_by: String,
+    pub created_at: String,
+    pub items: Vec<BaselineItem>,
+}
+
+impl Baseline {
+    /// Items of one kind
+    pub fn items_of(&self, kind: BaselineKind) -> impl Iterator<Item = &BaselineItem> {
+        self.items.iter().filter(move |item| item.kind == kind)
+    }
+
+    /// The captured item for an entity, if the baseline holds it
+    pub fn find(&self, kind: BaselineKind, id: &str) -> Option<&BaselineItem> {
+        self.items.iter().find(|item| item.kind == kind && item.id == id)
+    }
+
+    /// Compare this baseline (the older side) against `other`
+    pub fn diff(&self, other: &Baseline) -> BaselineDiff {
+        let index = |baseline: &Baseline| -> BTreeMap<(BaselineKind, String), BaselineItem> {
+            baseline.items.iter().map(|item| ((item.kind, item.id.clone()), item.clone())).collect()
+        };
+        let from = index(self);
+        let to = index(other);
+
+        let mut changes = Vec::new();
+        for (key, old) in &from {
+            match to.get(key) {
+                None => changes.push(ItemChange::new(ChangeKind::Removed, Some(old), None)),
+                Some(new) if new.sha256 != old.sha256 => {
+                    changes.push(ItemChange::new(ChangeKind::Changed, Some(old), Some(new)));
+                }
+                Some(_) => {}
+            }
+        }
+        for (key, new) in &to {
+            if !from.contains_key(key) {
+                changes.push(ItemChange::new(ChangeKind::Added, None, Some(new)));
+            }
+        }
+        changes.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.id.cmp(&b.id)));
+
+        BaselineDiff {
+            from: self.name.clone(),
+            to: other.name.clone(),
+            changes,
+        }
+    }
+}
+
+/// How an entity differs between two baselines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl ChangeKind {
+    pub const fn display_name(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "Added",
+            ChangeKind::Removed => "Removed",
+            ChangeKind::Changed => "Changed",
+        }
+    }
+}
+
+/// One entity that differs between two baselines
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemChange {
+    pub kind: BaselineKind,
+    pub id: String,
+    pub title: String,
+    pub change: ChangeKind,
+    pub from_revision: Option<String>,
+    pub to_revision: Option<String>,
+}
+
+impl ItemChange {
+    fn new(change: ChangeKind, from: Option<&BaselineItem>, to: Option<&BaselineItem>) -> Self {
+        let item = to.or(from).expect("a change has at least one side");
+        Self {
+            kind: item.kind,
+            id: item.id.clone(),
+            title: item.title.clone(),
+            change,
+            from_revision: from.map(|i| i.revision.clone()),
+            to_revision: to.map(|i| i.revision.clone()),
+        }
+    }
+}
+
+/// Differences between two baselines, or a baseline and the working state
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaselineDiff {
+    pub from: String,
+    pub to: String,
+    pub changes: Vec<ItemChange>,
+}
+
+impl BaselineDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Number of (added, removed, changed) entities of one kind
+    pub fn counts(&self, kind: BaselineKind) -> (usize, usize, usize) {
+        let count = |change: ChangeKind| {
+            self.changes.iter().filter(|c| c.kind == kind && c.change == change).count()
+        };
+        (count(ChangeKind::Added), count(ChangeKind::Removed), count(ChangeKind::Changed))
+    }
+}
+
+fn get_string(obj: &HashMap<String, JsonValue>, field: &str) -> Result<String, JsonError> {
+    match obj.get(field) {
+        Some(JsonValue::String(s)) => Ok(s.clone()),
+        Some(_) => Err(JsonError::InvalidFormat(format!("Field '{field}' is not a string"))),
+        None => Err(JsonError::InvalidFormat(format!("Missing required field '{field}'"))),
+    }
+}
+
+impl JsonSerializable for Baseline {
+    fn to_json(&self) -> String {
+        let items = self.items.iter().map(|item| {
+            let mut obj = HashMap::new();
+            obj.insert("kind".to_string(), JsonValue::String(item.kind.name().to_string()));
+            obj.insert("id".to_string(), JsonValue::String(item.id.clone()));
+            obj.insert("title".to_string(), JsonValue::String(item.title.clone()));
+            obj.insert("revision".to_string(), JsonValue::String(item.revision.clone()));
+            obj.insert("sha256".to_string(), JsonValue::String(item.sha256.clone()));
+            JsonValue::Object(obj)
+        }).collect();
+
+        let mut obj = HashMap::new();
+        obj.insert("version".to_string(), JsonValue::String("1.0".to_string()));
+        obj.insert("name".to_string(), JsonValue::String(self.name.clone()));
+        obj.insert("description".to_string(), JsonValue::String(self.description.clone()));
+        obj.insert("created_by".to_string(), JsonValue::String(self.created_by.clone()));
+        obj.insert("created_at".to_string(), JsonValue::String(self.created_at.clone()));
+        obj.insert("items".to_string(), JsonValue::Array(items));
+        JsonValue::Object(obj).json_to_string()
+    }
+
+    fn from_json(json: &str) -> Result<Self, JsonError> {
+        let obj = match JsonValue::parse(json)? {
+            JsonValue::Object(obj) => obj,
+            _ => return Err(JsonError::InvalidFormat("Expected JSON object".to_string())),
+        };
+
+        let mut items = Vec::new();
+        if let Some(JsonValue::Array(values)) = obj.get("items") {
+            for value in values {
+                let JsonValue::Object(item) = value else {
+                    return Err(JsonError::InvalidFormat("Baseline item is not an object".to_string()));
+                };
+                items.push(BaselineItem {
+                    kind: BaselineKind::parse(&get_string(item, "kind")?)
+                        .map_err(|e| JsonError::InvalidFormat(e.to_string()))?,
+                    id: get_string(item, "id")?,
+                    title: get_string(item, "title").unwrap_or_default(),
+                    revision: get_string(item, "revision").unwrap_or_default(),
+                    sha256: get_string(item, "sha256")?,
+                });
+            }
+        }
+
+        Ok(Baseline {
+            name: get_string(&obj, "name")?,
+            description: get_string(&obj, "description").unwrap_or_default(),
+            created_by: get_string(&obj, "created_by")?,
+            created_at: get_string(&obj, "created_at")?,
+            items,
+        })
+    }
+}
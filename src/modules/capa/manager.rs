@@ -0,0 +1,635 @@
+//! CAPA Manager
+//!
+//! Persists CAPA records under `capa/<id>.json` and enforces the CAPA workflow:
+//! root-cause analysis before action planning, completed actions before the
+//! effectiveness check, and an electronic signature to close. Creating a CAPA
+//! needs write access to quality records; accepting its effectiveness check
+//! and closing it need one of the approver roles.
+
+use crate::json_utils::JsonSerializable;
+use crate::models::{Permission, Role};
+use crate::modules::audit_logger::functions::{audit_log_action, audit_log_create, audit_log_update};
+use crate::modules::audit_logger::signatures::{ElectronicSignatureManager, SignatureVerification};
+use crate::modules::risk_manager::risk::RiskManager;
+use crate::modules::traceability::links::{TraceLinkType, TraceabilityLink, TraceabilityManager};
+use crate::modules::user_manager::FileAuthManager;
+use crate::modules::storage::{self, encryption};
+use crate::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::record::{
+    CapaAction, CapaActionStatus, CapaPriority, CapaRecord, CapaSource, CapaStatus, CapaType,
+    EffectivenessCheck, FishboneCause, RootCauseAnalysis,
+};
+
+/// Signature policy action used when closing a CAPA
+pub const CAPA_CLOSE_ACTION: &str = "capa_close";
+
+/// Roles allowed to record a CAPA's effectiveness check and close it
+pub const CAPA_APPROVER_ROLES: &[&str] = &["QualityEngineer", "Administrator"];
+
+/// Input for a new CAPA record
+#[derive(Debug, Clone)]
+pub struct NewCapa {
+    pub title: String,
+    pub description: String,
+    pub source: CapaSource,
+    pub source_reference: Option<String>,
+    pub capa_type: CapaType,
+    pub priority: CapaPriority,
+    pub owner: String,
+    pub due_date: Option<String>,
+}
+
+/// CAPA record manager
+pub struct CapaManager {
+    project_path: PathBuf,
+    capa_dir: PathBuf,
+}
+
+impl CapaManager {
+    /// Create new CAPA manager for a project
+    pub fn new(project_path: &Path) -> QmsResult<Self> {
+        let capa_dir = project_path.join("capa");
+        fs::create_dir_all(&capa_dir)?;
+        Ok(Self {
+            project_path: project_path.to_path_buf(),
+            capa_dir,
+        })
+    }
+
+    /// Create a new CAPA record in `Open` status
+    pub fn create_capa(&self, new_capa: NewCapa, created_by: &str) -> QmsResult<CapaRecord> {
+        if new_capa.title.trim().is_empty() {
+            return Err(QmsError::validation_error("CAPA title cannot be empty"));
+        }
+        if new_capa.description.trim().is_empty() {
+            return Err(QmsError::validation_error("CAPA description cannot be empty"));
+        }
+        self.require_permission(created_by, &Permission::WriteDocuments)?;
+
+        let timestamp = crate::utils::current_iso8601_timestamp();
+        let record = CapaRecord {
+            id: self.next_capa_id()?,
+            title: new_capa.title,
+            description: new_capa.description,
+            source: new_capa.source,
+            source_reference: new_capa.source_reference,
+            capa_type: new_capa.capa_type,
+            priority: new_capa.priority,
+            status: CapaStatus::Open,
+            root_cause: RootCauseAnalysis::default(),
+            actions: Vec::new(),
+            effectiveness: None,
+            linked_risks: Vec::new(),
+            owner: new_capa.owner,
+            due_date: new_capa.due_date,
+            created_by: created_by.to_string(),
+            created_at: timestamp.clone(),
+            updated_at: timestamp,
+            closed_by: None,
+            closed_at: None,
+            closure_signature_id: None,
+            cancellation_reason: None,
+        };
+
+        self.save_capa(&record)?;
+
+        audit_log_create(
+            "CAPA",
+            &record.id,
+            &format!("{}|{}|{}", record.source.display_name(), record.capa_type.display_name(), record.title),
+        )?;
+
+        Ok(record)
+    }
+
+    /// Load a CAPA record
+    pub fn load_capa(&self, capa_id: &str) -> QmsResult<CapaRecord> {
+        let path = self.capa_path(capa_id)?;
+        if !path.exists() {
+            return Err(QmsError::not_found(&format!("CAPA {capa_id} not found")));
+        }
+        let content = encryption::read_to_string(&path)?;
+        CapaRecord::from_json(&content)
+            .map_err(|e| QmsError::parse_error(&format!("Invalid CAPA record {capa_id}: {e}")))
+    }
+
+    /// Check whether a CAPA record exists
+    pub fn capa_exists(&self, capa_id: &str) -> bool {
+        self.capa_path(capa_id).is_ok_and(|path| path.exists())
+    }
+
+    /// List CAPA records, optionally filtered by status, ordered by ID
+    pub fn list_capas(&self, status: Option<&CapaStatus>) -> QmsResult<Vec<CapaRecord>> {
+        let mut records = Vec::new();
+        for entry in fs::read_dir(&self.capa_dir)? {
+            let path = entry?.path();
+            let is_record = path.extension().is_some_and(|ext| ext == "json")
+                && path.file_stem().and_then(|s| s.to_str()).is_some_and(|s| s.starts_with("CAPA-"));
+            if !is_record {
+                continue;
+            }
+            let content = encryption::read_to_string(&path)?;
+            let record = CapaRecord::from_json(&content)
+                .map_err(|e| QmsError::parse_error(&format!("Invalid CAPA record {}: {e}", path.display())))?;
+            if status.map_or(true, |s| &record.status == s) {
+                records.push(record);
+            }
+        }
+        records.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(records)
+    }
+
+    /// Record the root-cause analysis (5-why chain and fishbone causes)
+    pub fn record_root_cause(
+        &self,
+        capa_id: &str,
+        five_whys: Vec<String>,
+        fishbone: Vec<FishboneCause>,
+        root_cause_statement: &str,
+        analyzed_by: &str,
+    ) -> QmsResult<CapaRecord> {
+        let mut record = self.load_capa(capa_id)?;
+        if !matches!(record.status, CapaStatus::Open | CapaStatus::Investigation | CapaStatus::ActionPlanned) {
+            return Err(QmsError::validation_error(&format!(
+                "Root cause cannot be changed while CAPA {capa_id} is in {} status",
+                record.status.display_name()
+            )));
+        }
+        if root_cause_statement.trim().is_empty() {
+            return Err(QmsError::validation_error("Root cause statement cannot be empty"));
+        }
+
+        let old_statement = record.root_cause.root_cause_statement.clone();
+        record.root_cause = RootCauseAnalysis {
+            five_whys,
+            fishbone,
+            root_cause_statement: root_cause_statement.to_string(),
+            analyzed_by: Some(analyzed_by.to_string()),
+            analyzed_at: Some(crate::utils::current_iso8601_timestamp()),
+        };
+        if record.status == CapaStatus::Open {
+            record.status = CapaStatus::Investigation;
+        }
+        self.touch_and_save(&mut record)?;
+
+        audit_log_update("CAPA", capa_id, &old_statement, root_cause_statement)?;
+
+        Ok(record)
+    }
+
+    /// Add an action to the CAPA action plan
+    pub fn add_action(
+        &self,
+        capa_id: &str,
+        description: &str,
+        owner: &str,
+        due_date: Option<String>,
+    ) -> QmsResult<CapaAction> {
+        let mut record = self.load_capa(capa_id)?;
+        if !record.root_cause.is_complete() {
+            return Err(QmsError::validation_error(&format!(
+                "CAPA {capa_id} needs a root cause before actions can be planned"
+            )));
+        }
+        if !matches!(record.status, CapaStatus::Investigation | CapaStatus::ActionPlanned | CapaStatus::Implementation) {
+            return Err(QmsError::validation_error(&format!(
+                "Actions cannot be added while CAPA {capa_id} is in {} status",
+                record.status.display_name()
+            )));
+        }
+        if description.trim().is_empty() {
+            return Err(QmsError::validation_error("Action description cannot be empty"));
+        }
+
+        let action = CapaAction {
+            id: format!("ACT-{:03}", record.actions.len() + 1),
+            description: description.to_string(),
+            owner: owner.to_string(),
+            due_date,
+            status: CapaActionStatus::Planned,
+            completed_at: None,
+        };
+        record.actions.push(action.clone());
+        if record.status == CapaStatus::Investigation {
+            record.status = CapaStatus::ActionPlanned;
+        }
+        self.touch_and_save(&mut record)?;
+
+        audit_log_action("CAPA_ACTION_ADDED", "CAPA", &format!("{capa_id}|{}|{description}", action.id))?;
+
+        Ok(action)
+    }
+
+    /// Update the status of an action; starting work moves the CAPA into implementation
+    pub fn update_action_status(
+        &self,
+        capa_id: &str,
+        action_id: &str,
+        status: CapaActionStatus,
+    ) -> QmsResult<CapaRecord> {
+        let mut record = self.load_capa(capa_id)?;
+        if !matches!(record.status, CapaStatus::ActionPlanned | CapaStatus::Implementation) {
+            return Err(QmsError::validation_error(&format!(
+                "Actions cannot be updated while CAPA {capa_id} is in {} status",
+                record.status.display_name()
+            )));
+        }
+
+        let action = record.actions.iter_mut()
+            .find(|a| a.id == action_id)
+            .ok_or_else(|| QmsError::not_found(&format!("Action {action_id} not found in {capa_id}")))?;
+        let old_status = action.status.display_name();
+        action.completed_at = match status {
+            CapaActionStatus::Completed => Some(crate::utils::current_iso8601_timestamp()),
+            _ => None,
+        };
+        action.status = status.clone();
+
+        record.status = CapaStatus::Implementation;
+        self.touch_and_save(&mut record)?;
+
+        audit_log_update("CAPA", &format!("{capa_id}/{action_id}"), old_status, status.display_name())?;
+
+        Ok(record)
+    }
+
+    /// Record the effectiveness check once every action is done.
+    ///
+    /// An ineffective result sends the CAPA back to action planning.
+    pub fn record_effectiveness(
+        &self,
+        capa_id: &str,
+        method: &str,
+        criteria: &str,
+        effective: bool,
+        evidence: &str,
+        checked_by: &str,
+    ) -> QmsResult<CapaRecord> {
+        self.require_approver(checked_by)?;
+        let mut record = self.load_capa(capa_id)?;
+        if record.status != CapaStatus::Implementation {
+            return Err(QmsError::validation_error(&format!(
+                "Effectiveness can only be checked during implementation (CAPA {capa_id} is {})",
+                record.status.display_name()
+            )));
+        }
+        if let Some(open) = record.actions.iter().find(|a| !a.status.is_done()) {
+            return Err(QmsError::validation_error(&format!(
+                "Action {} is still {}", open.id, open.status.display_name()
+            )));
+        }
+        if !record.actions.iter().any(|a| a.status == CapaActionStatus::Completed) {
+            return Err(QmsError::validation_error("At least one action must be completed"));
+        }
+        if method.trim().is_empty() || criteria.trim().is_empty() {
+            return Err(QmsError::validation_error("Effectiveness method and criteria are required"));
+        }
+
+        record.effectiveness = Some(EffectivenessCheck {
+            method: method.to_string(),
+            criteria: criteria.to_string(),
+            effective,
+            evidence: evidence.to_string(),
+            checked_by: checked_by.to_string(),
+            checked_at: crate::utils::current_iso8601_timestamp(),
+        });
+        record.status = if effective { CapaStatus::EffectivenessCheck } else { CapaStatus::ActionPlanned };
+        self.touch_and_save(&mut record)?;
+
+        audit_log_action(
+            if effective { "CAPA_EFFECTIVE" } else { "CAPA_NOT_EFFECTIVE" },
+            "CAPA",
+            &format!("{capa_id}|{method}|{evidence}"),
+        )?;
+
+        Ok(record)
+    }
+
+    /// Close a CAPA with an electronic signature bound to its final content
+    pub fn close_capa(&self, capa_id: &str, user_id: &str, password: &str, code: Option<&str>) -> QmsResult<CapaRecord> {
+        self.require_approver(user_id)?;
+        let mut record = self.load_capa(capa_id)?;
+        let effective = record.effectiveness.as_ref().is_some_and(|e| e.effective);
+        if record.status != CapaStatus::EffectivenessCheck || !effective {
+            return Err(QmsError::validation_error(&format!(
+                "CAPA {capa_id} cannot be closed until actions are verified effective"
+            )));
+        }
+
+        let signature_manager = ElectronicSignatureManager::new(self.project_path.clone());
+        let signature = signature_manager.create_signature_with_code(
+            user_id.to_string(),
+            password,
+            code,
+            CAPA_CLOSE_ACTION,
+            "CAPA".to_string(),
+            capa_id.to_string(),
+            &record.signing_content(),
+            None,
+        )?;
+
+        record.status = CapaStatus::Closed;
+        record.closed_by = Some(user_id.to_string());
+        record.closed_at = Some(crate::utils::current_iso8601_timestamp());
+        record.closure_signature_id = Some(signature.id.clone());
+        self.touch_and_save(&mut record)?;
+
+        audit_log_action("CAPA_CLOSED", "CAPA", &format!("{capa_id}|{}", signature.id))?;
+
+        Ok(record)
+    }
+
+    /// Verify the closure signature still matches the stored record
+    pub fn verify_closure(&self, capa_id: &str) -> QmsResult<SignatureVerification> {
+        let record = self.load_capa(capa_id)?;
+        let signature_id = record.closure_signature_id.as_ref()
+            .ok_or_else(|| QmsError::validation_error(&format!("CAPA {capa_id} has no closure signature")))?;
+        ElectronicSignatureManager::new(self.project_path.clone())
+            .verify_signature_for_record(signature_id, &record.signing_content())
+    }
+
+    /// Cancel an open CAPA with a justification
+    pub fn cancel_capa(&self, capa_id: &str, reason: &str) -> QmsResult<CapaRecord> {
+        let mut record = self.load_capa(capa_id)?;
+        if record.status.is_terminal() {
+            return Err(QmsError::validation_error(&format!(
+                "CAPA {capa_id} is already {}", record.status.display_name()
+            )));
+        }
+        if reason.trim().is_empty() {
+            return Err(QmsError::validation_error("A cancellation reason is required"));
+        }
+
+        let old_status = record.status.display_name();
+        record.status = CapaStatus::Cancelled;
+        record.cancellation_reason = Some(reason.to_string());
+        self.touch_and_save(&mut record)?;
+
+        audit_log_update("CAPA", capa_id, old_status, &format!("Cancelled: {reason}"))?;
+
+        Ok(record)
+    }
+
+    /// Link a CAPA to a risk and flag that risk for review
+    ///
+    /// The link is recorded as a traceability link from the CAPA to the risk's
+    /// hazard, alongside the CAPA's own `linked_risks`.
+    pub fn link_risk(&self, capa_id: &str, risk_id: &str) -> QmsResult<CapaRecord> {
+        let mut record = self.load_capa(capa_id)?;
+        if record.status.is_terminal() {
+            return Err(QmsError::validation_error(&format!(
+                "CAPA {capa_id} is {}", record.status.display_name()
+            )));
+        }
+        if record.linked_risks.iter().any(|r| r == risk_id) {
+            return Err(QmsError::validation_error(&format!("{capa_id} is already linked to risk {risk_id}")));
+        }
+
+        let risk_manager = RiskManager::new(&self.project_path)?;
+        let mut risk = risk_manager.load_risk(risk_id)?;
+        let link = storage::with_transaction(&self.project_path, || {
+            let link = TraceabilityManager::new(&self.project_path)?
+                .create_trace_link(capa_id, &risk.hazard_id, TraceLinkType::Related)?;
+            risk.review_required = true;
+            risk.updated_at = crate::utils::current_iso8601_timestamp();
+            risk_manager.save_risk(&risk)?;
+            Ok(link)
+        })?;
+
+        record.linked_risks.push(risk_id.to_string());
+        self.touch_and_save(&mut record)?;
+
+        audit_log_action("CAPA_RISK_LINKED", "CAPA", &format!("{capa_id}|{risk_id}|{}", link.id))?;
+
+        Ok(record)
+    }
+
+    /// Create a traceability link from a CAPA to a requirement, test, risk or document
+    pub fn link_trace(&self, capa_id: &str, target_id: &str, link_type: TraceLinkType) -> QmsResult<TraceabilityLink> {
+        if !self.capa_exists(capa_id) {
+            return Err(QmsError::not_found(&format!("CAPA {capa_id} not found")));
+        }
+        let trace_manager = TraceabilityManager::new(&self.project_path)?;
+        let link = trace_manager.create_trace_link(capa_id, target_id, link_type)?;
+
+        audit_log_action("CAPA_TRACE_LINKED", "CAPA", &format!("{capa_id}|{target_id}|{}", link.id))?;
+
+        Ok(link)
+    }
+
+    /// Get traceability links involving a CAPA
+    pub fn trace_links(&self, capa_id: &str) -> QmsResult<Vec<TraceabilityLink>> {
+        TraceabilityManager::new(&self.project_path)?.get_links_for_entity(capa_id)
+    }
+
+    fn project_user_roles(&self, user_id: &str) -> QmsResult<Vec<Role>> {
+        FileAuthManager::from_project_path(&self.project_path)?
+            .load_user(user_id)
+            .map(|user| user.roles)
+            .map_err(|_| QmsError::permission_error(&format!("User {user_id} is not a user of this project")))
+    }
+
+    fn require_permission(&self, user_id: &str, permission: &Permission) -> QmsResult<()> {
+        if !self.project_user_roles(user_id)?.iter().any(|role| role.permissions.contains(permission)) {
+            return Err(QmsError::permission_error(&format!("User {user_id} lacks the {permission:?} permission")));
+        }
+        Ok(())
+    }
+
+    fn require_approver(&self, user_id: &str) -> QmsResult<()> {
+        if !self.project_user_roles(user_id)?.iter().any(|role| CAPA_APPROVER_ROLES.contains(&role.name.as_str())) {
+            return Err(QmsError::permission_error(&format!(
+                "User {user_id} does not hold a CAPA approver role ({})", CAPA_APPROVER_ROLES.join(", ")
+            )));
+        }
+        Ok(())
+    }
+
+    fn capa_path(&self, capa_id: &str) -> QmsResult<PathBuf> {
+        if capa_id.is_empty() || capa_id.contains(['/', '\\']) || capa_id.contains("..") {
+            return Err(QmsError::validation_error(&format!("Invalid CAPA ID: {capa_id}")));
+        }
+        Ok(self.capa_dir.join(format!("{capa_id}.json")))
+    }
+
+    fn next_capa_id(&self) -> QmsResult<String> {
+        let mut max = 0u32;
+        for entry in fs::read_dir(&self.capa_dir)? {
+            let name = entry?.file_name().to_string_lossy().to_string();
+            if let Some(number) = name.strip_prefix("CAPA-").and_then(|n| n.strip_suffix(".json")) {
+                max = max.max(number.parse().unwrap_or(0));
+            }
+        }
+        Ok(format!("CAPA-{:03}", max + 1))
+    }
+
+    fn touch_and_save(&self, record: &mut CapaRecord) -> QmsResult<()> {
+        record.updated_at = crate::utils::current_iso8601_timestamp();
+        self.save_capa(record)
+    }
+
+    fn save_capa(&self, record: &CapaRecord) -> QmsResult<()> {
+        encryption::atomic_write(&self.capa_path(&record.id)?, &record.to_json())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::audit_logger::{initialize_audit_system, AuditConfig};
+    use crate::modules::capa::record::FishboneCategory;
+    use crate::modules::user_manager::signing_keys::SigningKeyStore;
+
+    fn setup() -> (tempfile::TempDir, CapaManager) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let _ = initialize_audit_system(AuditConfig {
+            project_path: temp_dir.path().to_string_lossy().to_string(),
+            retention_days: 30,
+            daily_rotation: false,
+            max_file_size_mb: 10,
+            require_checksums: false,
+        });
+        add_user(temp_dir.path(), "quality", "QualityEngineer", vec![Permission::WriteDocuments]);
+        let manager = CapaManager::new(temp_dir.path()).unwrap();
+        (temp_dir, manager)
+    }
+
+    fn add_user(project_path: &Path, username: &str, role: &str, permissions: Vec<Permission>) {
+        FileAuthManager::from_project_path(project_path)
+            .unwrap()
+            .add_user(username, "password123", Some(vec![Role { name: role.to_string(), permissions }]))
+            .unwrap();
+    }
+
+    fn new_capa(title: &str) -> NewCapa {
+        NewCapa {
+            title: title.to_string(),
+            description: "Infusion rate drift reported by customer".to_string(),
+            source: CapaSource::Complaint,
+            source_reference: Some("CMP-042".to_string()),
+            capa_type: CapaType::Corrective,
+            priority: CapaPriority::High,
+            owner: "quality".to_string(),
+            due_date: None,
+        }
+    }
+
+    fn drive_to_effective(manager: &CapaManager, id: &str) {
+        manager.record_root_cause(
+            id,
+            vec!["Rate drifted".to_string(), "Sensor not recalibrated".to_string()],
+            vec![FishboneCause { category: FishboneCategory::Method, cause: "No calibration interval".to_string() }],
+            "Calibration procedure lacks a defined interval",
+            "quality",
+        ).unwrap();
+        let action = manager.add_action(id, "Add 6-month calibration interval", "eng", None).unwrap();
+        manager.update_action_status(id, &action.id, CapaActionStatus::Completed).unwrap();
+        manager.record_effectiveness(id, "Field data review", "No drift reports for 90 days", true, "PMS-2024-Q3", "quality").unwrap();
+    }
+
+    #[test]
+    fn test_capa_ids_are_sequential_and_round_trip() {
+        let (_dir, manager) = setup();
+        let first = manager.create_capa(new_capa("Rate drift"), "quality").unwrap();
+        let second = manager.create_capa(new_capa("Label misprint"), "quality").unwrap();
+        assert_eq!(first.id, "CAPA-001");
+        assert_eq!(second.id, "CAPA-002");
+
+        let loaded = manager.load_capa("CAPA-001").unwrap();
+        assert_eq!(loaded.title, "Rate drift");
+        assert_eq!(loaded.source, CapaSource::Complaint);
+        assert_eq!(loaded.source_reference.as_deref(), Some("CMP-042"));
+        assert_eq!(manager.list_capas(Some(&CapaStatus::Open)).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_workflow_enforces_root_cause_and_completed_actions() {
+        let (_dir, manager) = setup();
+        let capa = manager.create_capa(new_capa("Rate drift"), "quality").unwrap();
+
+        assert!(manager.add_action(&capa.id, "Fix it", "eng", None).is_err());
+
+        manager.record_root_cause(&capa.id, vec!["why".to_string()], Vec::new(), "Root cause", "quality").unwrap();
+        let action = manager.add_action(&capa.id, "Fix it", "eng", None).unwrap();
+        manager.update_action_status(&capa.id, &action.id, CapaActionStatus::InProgress).unwrap();
+        assert!(manager.record_effectiveness(&capa.id, "Review", "No recurrence", true, "", "quality").is_err());
+
+        manager.update_action_status(&capa.id, &action.id, CapaActionStatus::Completed).unwrap();
+        let record = manager.record_effectiveness(&capa.id, "Review", "No recurrence", false, "", "quality").unwrap();
+        assert_eq!(record.status, CapaStatus::ActionPlanned);
+    }
+
+    #[test]
+    fn test_close_requires_effectiveness_and_signature() {
+        let (dir, manager) = setup();
+        SigningKeyStore::new(dir.path()).generate_key_pair("quality", "password123").unwrap();
+        let capa = manager.create_capa(new_capa("Rate drift"), "quality").unwrap();
+
+        assert!(manager.close_capa(&capa.id, "quality", "password123", None).is_err());
+
+        drive_to_effective(&manager, &capa.id);
+        assert!(manager.close_capa(&capa.id, "quality", "wrong-password", None).is_err());
+
+        let closed = manager.close_capa(&capa.id, "quality", "password123", None).unwrap();
+        assert_eq!(closed.status, CapaStatus::Closed);
+        assert!(closed.closure_signature_id.is_some());
+        assert!(manager.verify_closure(&capa.id).unwrap().is_valid);
+        assert!(manager.cancel_capa(&capa.id, "duplicate").is_err());
+    }
+
+    #[test]
+    fn test_create_and_approval_require_permissions() {
+        let (dir, manager) = setup();
+        add_user(dir.path(), "auditor", "Auditor", vec![Permission::ReadDocuments]);
+        add_user(dir.path(), "dev", "Developer", vec![Permission::WriteDocuments]);
+        SigningKeyStore::new(dir.path()).generate_key_pair("dev", "password123").unwrap();
+
+        let denied = manager.create_capa(new_capa("Rate drift"), "auditor").unwrap_err();
+        assert!(matches!(denied, QmsError::Permission(_)));
+        let unknown = manager.create_capa(new_capa("Rate drift"), "unknown").unwrap_err();
+        assert!(matches!(unknown, QmsError::Permission(_)));
+
+        let capa = manager.create_capa(new_capa("Rate drift"), "dev").unwrap();
+        drive_to_effective(&manager, &capa.id);
+        let denied = manager.close_capa(&capa.id, "dev", "password123", None).unwrap_err();
+        assert!(matches!(denied, QmsError::Permission(_)));
+        assert!(matches!(
+            manager.record_effectiveness(&capa.id, "Review", "No recurrence", true, "", "dev"),
+            Err(QmsError::Permission(_))
+        ));
+    }
+
+    #[test]
+    fn test_link_risk_flags_risk_for_review() {
+        let (dir, manager) = setup();
+        let mut risk_manager = RiskManager::new(dir.path()).unwrap();
+        risk_manager.initialize().unwrap();
+        let risk = risk_manager.create_risk("Over-infusion", "Rate drift", "Overdose").unwrap();
+
+        let capa = manager.create_capa(new_capa("Rate drift"), "quality").unwrap();
+        let record = manager.link_risk(&capa.id, &risk.id).unwrap();
+        assert_eq!(record.linked_risks, vec![risk.id.clone()]);
+        assert!(manager.link_risk(&capa.id, &risk.id).is_err());
+
+        let linked = RiskManager::new(dir.path()).unwrap().load_risk(&risk.id).unwrap();
+        assert!(linked.review_required);
+        assert!(linked.post_market_data.is_empty());
+
+        let links = manager.trace_links(&capa.id).unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].target_id, risk.hazard_id);
+        assert_eq!(links[0].target_type, "Risk");
+    }
+
+    #[test]
+    fn test_capa_ids_cannot_escape_capa_dir() {
+        let (_dir, manager) = setup();
+        for id in ["../users/admin", "CAPA-001/..", "..\\CAPA-001", ""] {
+            assert!(manager.load_capa(id).is_err());
+            assert!(!manager.capa_exists(id));
+        }
+    }
+}
@@ -0,0 +1,17 @@
+//! Corrective and Preventive Action (CAPA) Module
+//!
+//! Implements the CAPA process required by FDA 21 CFR 820.100 and ISO 13485:2016
+//! Sections 8.5.2 / 8.5.3: records raised from complaints, audit findings,
+//! nonconformances and surveillance data, root-cause analysis, action planning,
+//! effectiveness verification and signed closure.
+
+pub mod manager;
+pub mod record;
+
+#[allow(unused_imports)]
+pub use manager::{CapaManager, NewCapa, CAPA_CLOSE_ACTION};
+#[allow(unused_imports)]
+pub use record::{
+    CapaAction, CapaActionStatus, CapaPriority, CapaRecord, CapaSource, CapaStatus, CapaType,
+    EffectivenessCheck, FishboneCategory, FishboneCause, RootCauseAnalysis,
+};
@@ -0,0 +1,535 @@
+//! CAPA Record Model
+//!
+//! Data model for Corrective and Preventive Action records per FDA 21 CFR 820.100
+//! and ISO 13485:2016 Sections 8.5.2 / 8.5.3.
+
+use crate::json_utils::{JsonError, JsonSerializable, JsonValue};
+use crate::prelude::*;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Origin of a CAPA record
+#[derive(Debug, Clone, PartialEq)]
+pub enum CapaSource {
+    Complaint,       // Customer complaint
+    AuditFinding,    // Internal or external audit finding
+    Nonconformance,  // Product or process nonconformance
+    Surveillance,    // Post-market surveillance data
+    Other(String),   // Any other documented source
+}
+
+impl CapaSource {
+    /// Get display name for CAPA source
+    pub fn display_name(&self) -> &str {
+        match self {
+            CapaSource::Complaint => "Complaint",
+            CapaSource::AuditFinding => "Audit Finding",
+            CapaSource::Nonconformance => "Nonconformance",
+            CapaSource::Surveillance => "Surveillance",
+            CapaSource::Other(name) => name,
+        }
+    }
+
+    fn from_display_name(name: &str) -> Self {
+        match name {
+            "Complaint" => CapaSource::Complaint,
+            "Audit Finding" => CapaSource::AuditFinding,
+            "Nonconformance" => CapaSource::Nonconformance,
+            "Surveillance" => CapaSource::Surveillance,
+            other => CapaSource::Other(other.to_string()),
+        }
+    }
+}
+
+impl FromStr for CapaSource {
+    type Err = QmsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "complaint" | "customer-complaint" => Ok(CapaSource::Complaint),
+            "audit" | "audit-finding" | "audit_finding" | "auditfinding" => Ok(CapaSource::AuditFinding),
+            "nonconformance" | "nc" | "ncr" => Ok(CapaSource::Nonconformance),
+            "surveillance" | "post-market" | "pms" => Ok(CapaSource::Surveillance),
+            "" => Err(QmsError::validation_error("CAPA source cannot be empty")),
+            other => Ok(CapaSource::Other(other.to_string())),
+        }
+    }
+}
+
+/// Corrective (fix an existing problem) or preventive (avoid a potential one)
+#[derive(Debug, Clone, PartialEq)]
+pub enum CapaType {
+    Corrective,
+    Preventive,
+}
+
+impl CapaType {
+    /// Get display name for CAPA type
+    pub const fn display_name(&self) -> &'static str {
+        match self {
+            CapaType::Corrective => "Corrective",
+            CapaType::Preventive => "Preventive",
+        }
+    }
+}
+
+impl FromStr for CapaType {
+    type Err = QmsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "corrective" | "ca" => Ok(CapaType::Corrective),
+            "preventive" | "pa" => Ok(CapaType::Preventive),
+            _ => Err(QmsError::validation_error(&format!("Invalid CAPA type: {s} (expected corrective or preventive)"))),
+        }
+    }
+}
+
+/// CAPA priority
+#[derive(Debug, Clone, PartialEq)]
+pub enum CapaPriority {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl CapaPriority {
+    /// Get display name for CAPA priority
+    pub const fn display_name(&self) -> &'static str {
+        match self {
+            CapaPriority::Low => "Low",
+            CapaPriority::Medium => "Medium",
+            CapaPriority::High => "High",
+            CapaPriority::Critical => "Critical",
+        }
+    }
+}
+
+impl FromStr for CapaPriority {
+    type Err = QmsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "low" => Ok(CapaPriority::Low),
+            "medium" => Ok(CapaPriority::Medium),
+            "high" => Ok(CapaPriority::High),
+            "critical" => Ok(CapaPriority::Critical),
+            _ => Err(QmsError::validation_error(&format!("Invalid CAPA priority: {s}"))),
+        }
+    }
+}
+
+/// CAPA lifecycle status
+#[derive(Debug, Clone, PartialEq)]
+pub enum CapaStatus {
+    Open,               // Recorded, not yet investigated
+    Investigation,      // Root cause analysis under way
+    ActionPlanned,      // Action plan defined
+    Implementation,     // Actions being carried out
+    EffectivenessCheck, // Actions complete and verified effective, awaiting closure
+    Closed,             // Closed with electronic signature
+    Cancelled,          // Cancelled with justification
+}
+
+impl CapaStatus {
+    /// Get display name for CAPA status
+    pub const fn display_name(&self) -> &'static str {
+        match self {
+            CapaStatus::Open => "Open",
+            CapaStatus::Investigation => "Investigation",
+            CapaStatus::ActionPlanned => "Action Planned",
+            CapaStatus::Implementation => "Implementation",
+            CapaStatus::EffectivenessCheck => "Effectiveness Check",
+            CapaStatus::Closed => "Closed",
+            CapaStatus::Cancelled => "Cancelled",
+        }
+    }
+
+    /// Whether the record can no longer change
+    pub const fn is_terminal(&self) -> bool {
+        matches!(self, CapaStatus::Closed | CapaStatus::Cancelled)
+    }
+}
+
+impl FromStr for CapaStatus {
+    type Err = QmsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "open" => Ok(CapaStatus::Open),
+            "investigation" => Ok(CapaStatus::Investigation),
+            "actionplanned" => Ok(CapaStatus::ActionPlanned),
+            "implementation" => Ok(CapaStatus::Implementation),
+            "effectivenesscheck" => Ok(CapaStatus::EffectivenessCheck),
+            "closed" => Ok(CapaStatus::Closed),
+            "cancelled" | "canceled" => Ok(CapaStatus::Cancelled),
+            _ => Err(QmsError::validation_error(&format!("Invalid CAPA status: {s}"))),
+        }
+    }
+}
+
+/// Ishikawa (fishbone) cause categories - the classic 6M
+#[derive(Debug, Clone, PartialEq)]
+pub enum FishboneCategory {
+    Man,
+    Machine,
+    Method,
+    Material,
+    Measurement,
+    Environment,
+}
+
+impl FishboneCategory {
+    /// Get display name for fishbone category
+    pub const fn display_name(&self) -> &'static str {
+        match self {
+            FishboneCategory::Man => "Man",
+            FishboneCategory::Machine => "Machine",
+            FishboneCategory::Method => "Method",
+            FishboneCategory::Material => "Material",
+            FishboneCategory::Measurement => "Measurement",
+            FishboneCategory::Environment => "Environment",
+        }
+    }
+}
+
+impl FromStr for FishboneCategory {
+    type Err = QmsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "man" | "people" | "personnel" => Ok(FishboneCategory::Man),
+            "machine" | "equipment" => Ok(FishboneCategory::Machine),
+            "method" | "process" => Ok(FishboneCategory::Method),
+            "material" | "materials" => Ok(FishboneCategory::Material),
+            "measurement" => Ok(FishboneCategory::Measurement),
+            "environment" | "mother-nature" => Ok(FishboneCategory::Environment),
+            _ => Err(QmsError::validation_error(&format!(
+                "Invalid fishbone category: {s} (expected man, machine, method, material, measurement or environment)"
+            ))),
+        }
+    }
+}
+
+/// Single contributing cause on the fishbone diagram
+#[derive(Debug, Clone, PartialEq)]
+pub struct FishboneCause {
+    pub category: FishboneCategory,
+    pub cause: String,
+}
+
+/// Root-cause analysis combining 5-why and fishbone techniques
+#[derive(Debug, Clone, Default)]
+pub struct RootCauseAnalysis {
+    pub five_whys: Vec<String>,           // Successive "why?" answers, most immediate first
+    pub fishbone: Vec<FishboneCause>,     // Contributing causes by category
+    pub root_cause_statement: String,     // Concluded root cause
+    pub analyzed_by: Option<String>,      // Analyst
+    pub analyzed_at: Option<String>,      // ISO 8601 timestamp
+}
+
+impl RootCauseAnalysis {
+    /// Whether a root cause has been concluded
+    pub fn is_complete(&self) -> bool {
+        !self.root_cause_statement.trim().is_empty()
+    }
+}
+
+/// Status of an individual CAPA action
+#[derive(Debug, Clone, PartialEq)]
+pub enum CapaActionStatus {
+    Planned,
+    InProgress,
+    Completed,
+    Cancelled,
+}
+
+impl CapaActionStatus {
+    /// Get display name for action status
+    pub const fn display_name(&self) -> &'static str {
+        match self {
+            CapaActionStatus::Planned => "Planned",
+            CapaActionStatus::InProgress => "In Progress",
+            CapaActionStatus::Completed => "Completed",
+            CapaActionStatus::Cancelled => "Cancelled",
+        }
+    }
+
+    /// Whether the action no longer needs work
+    pub const fn is_done(&self) -> bool {
+        matches!(self, CapaActionStatus::Completed | CapaActionStatus::Cancelled)
+    }
+}
+
+impl FromStr for CapaActionStatus {
+    type Err = QmsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "planned" => Ok(CapaActionStatus::Planned),
+            "inprogress" | "started" => Ok(CapaActionStatus::InProgress),
+            "completed" | "complete" | "done" => Ok(CapaActionStatus::Completed),
+            "cancelled" | "canceled" => Ok(CapaActionStatus::Cancelled),
+            _ => Err(QmsError::validation_error(&format!("Invalid action status: {s}"))),
+        }
+    }
+}
+
+/// Planned corrective or preventive action
+#[derive(Debug, Clone)]
+pub struct CapaAction {
+    pub id: String,                    // ACT-001, ACT-002... within the CAPA
+    pub description: String,
+    pub owner: String,
+    pub due_date: Option<String>,      // ISO 8601 date
+    pub status: CapaActionStatus,
+    pub completed_at: Option<String>,  // ISO 8601 timestamp
+}
+
+/// Verification that the implemented actions were effective
+#[derive(Debug, Clone)]
+pub struct EffectivenessCheck {
+    pub method: String,      // How effectiveness was verified
+    pub criteria: String,    // Acceptance criteria
+    pub effective: bool,     // Outcome
+    pub evidence: String,    // Reference to objective evidence
+    pub checked_by: String,
+    pub checked_at: String,  // ISO 8601 timestamp
+}
+
+/// Corrective and Preventive Action record
+#[derive(Debug, Clone)]
+pub struct CapaRecord {
+    pub id: String,                          // CAPA-001, CAPA-002...
+    pub title: String,
+    pub description: String,
+    pub source: CapaSource,
+    pub source_reference: Option<String>,    // ID of the complaint, finding, NC or surveillance entry
+    pub capa_type: CapaType,
+    pub priority: CapaPriority,
+    pub status: CapaStatus,
+    pub root_cause: RootCauseAnalysis,
+    pub actions: Vec<CapaAction>,
+    pub effectiveness: Option<EffectivenessCheck>,
+    pub linked_risks: Vec<String>,           // Risk IDs updated by this CAPA
+    pub owner: String,
+    pub due_date: Option<String>,
+    pub created_by: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub closed_by: Option<String>,
+    pub closed_at: Option<String>,
+    pub closure_signature_id: Option<String>,
+    pub cancellation_reason: Option<String>,
+}
+
+impl CapaRecord {
+    /// Deterministic byte representation bound by the closure signature.
+    ///
+    /// Built by hand rather than from `to_json` so field order is stable.
+    pub fn signing_content(&self) -> Vec<u8> {
+        let mut content = String::from("qms-capa-closure-v1\n");
+        content.push_str(&format!("id={}\n", self.id));
+        content.push_str(&format!("title={}\n", self.title));
+        content.push_str(&format!("source={}\n", self.source.display_name()));
+        content.push_str(&format!("source_reference={}\n", self.source_reference.as_deref().unwrap_or("")));
+        content.push_str(&format!("type={}\n", self.capa_type.display_name()));
+        content.push_str(&format!("root_cause={}\n", self.root_cause.root_cause_statement));
+        for action in &self.actions {
+            content.push_str(&format!("action={}|{}|{}\n", action.id, action.status.display_name(), action.description));
+        }
+        if let Some(ref check) = self.effectiveness {
+            content.push_str(&format!(
+                "effectiveness={}|{}|{}|{}\n",
+                check.effective, check.method, check.criteria, check.evidence
+            ));
+        }
+        for risk in &self.linked_risks {
+            content.push_str(&format!("risk={risk}\n"));
+        }
+        content.into_bytes()
+    }
+}
+
+fn optional_string(value: &Option<String>) -> JsonValue {
+    value.as_ref().map_or(JsonValue::Null, |s| JsonValue::String(s.clone()))
+}
+
+fn string_array(values: &[String]) -> JsonValue {
+    JsonValue::Array(values.iter().map(|v| JsonValue::String(v.clone())).collect())
+}
+
+fn get_string(obj: &HashMap<String, JsonValue>, field: &str) -> Result<String, JsonError> {
+    match obj.get(field) {
+        Some(JsonValue::String(s)) => Ok(s.clone()),
+        Some(_) => Err(JsonError::InvalidFormat(format!("Field '{field}' is not a string"))),
+        None => Err(JsonError::InvalidFormat(format!("Missing required field '{field}'"))),
+    }
+}
+
+fn get_optional_string(obj: &HashMap<String, JsonValue>, field: &str) -> Option<String> {
+    obj.get(field).and_then(|v| v.as_string().cloned())
+}
+
+fn get_string_array(obj: &HashMap<String, JsonValue>, field: &str) -> Vec<String> {
+    match obj.get(field) {
+        Some(JsonValue::Array(items)) => items.iter().filter_map(|v| v.as_string().cloned()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn invalid(err: QmsError) -> JsonError {
+    JsonError::InvalidFormat(err.to_string())
+}
+
+impl JsonSerializable for CapaRecord {
+    fn to_json(&self) -> String {
+        let mut root_cause = HashMap::new();
+        root_cause.insert("five_whys".to_string(), string_array(&self.root_cause.five_whys));
+        root_cause.insert(
+            "fishbone".to_string(),
+            JsonValue::Array(
+                self.root_cause.fishbone.iter().map(|c| {
+                    let mut cause = HashMap::new();
+                    cause.insert("category".to_string(), JsonValue::String(c.category.display_name().to_string()));
+                    cause.insert("cause".to_string(), JsonValue::String(c.cause.clone()));
+                    JsonValue::Object(cause)
+                }).collect(),
+            ),
+        );
+        root_cause.insert("root_cause_statement".to_string(), JsonValue::String(self.root_cause.root_cause_statement.clone()));
+        root_cause.insert("analyzed_by".to_string(), optional_string(&self.root_cause.analyzed_by));
+        root_cause.insert("analyzed_at".to_string(), optional_string(&self.root_cause.analyzed_at));
+
+        let actions = self.actions.iter().map(|a| {
+            let mut action = HashMap::new();
+            action.insert("id".to_string(), JsonValue::String(a.id.clone()));
+            action.insert("description".to_string(), JsonValue::String(a.description.clone()));
+            action.insert("owner".to_string(), JsonValue::String(a.owner.clone()));
+            action.insert("due_date".to_string(), optional_string(&a.due_date));
+            action.insert("status".to_string(), JsonValue::String(a.status.display_name().to_string()));
+            action.insert("completed_at".to_string(), optional_string(&a.completed_at));
+            JsonValue::Object(action)
+        }).collect();
+
+        let effectiveness = self.effectiveness.as_ref().map_or(JsonValue::Null, |check| {
+            let mut obj = HashMap::new();
+            obj.insert("method".to_string(), JsonValue::String(check.method.clone()));
+            obj.insert("criteria".to_string(), JsonValue::String(check.criteria.clone()));
+            obj.insert("effective".to_string(), JsonValue::Bool(check.effective));
+            obj.insert("evidence".to_string(), JsonValue::String(check.evidence.clone()));
+            obj.insert("checked_by".to_string(), JsonValue::String(check.checked_by.clone()));
+            obj.insert("checked_at".to_string(), JsonValue::String(check.checked_at.clone()));
+            JsonValue::Object(obj)
+        });
+
+        let mut obj = HashMap::new();
+        obj.insert("version".to_string(), JsonValue::String("1.0".to_string()));
+        obj.insert("id".to_string(), JsonValue::String(self.id.clone()));
+        obj.insert("title".to_string(), JsonValue::String(self.title.clone()));
+        obj.insert("description".to_string(), JsonValue::String(self.description.clone()));
+        obj.insert("source".to_string(), JsonValue::String(self.source.display_name().to_string()));
+        obj.insert("source_reference".to_string(), optional_string(&self.source_reference));
+        obj.insert("capa_type".to_string(), JsonValue::String(self.capa_type.display_name().to_string()));
+        obj.insert("priority".to_string(), JsonValue::String(self.priority.display_name().to_string()));
+        obj.insert("status".to_string(), JsonValue::String(self.status.display_name().to_string()));
+        obj.insert("root_cause".to_string(), JsonValue::Object(root_cause));
+        obj.insert("actions".to_string(), JsonValue::Array(actions));
+        obj.insert("effectiveness".to_string(), effectiveness);
+        obj.insert("linked_risks".to_string(), string_array(&self.linked_risks));
+        obj.insert("owner".to_string(), JsonValue::String(self.owner.clone()));
+        obj.insert("due_date".to_string(), optional_string(&self.due_date));
+        obj.insert("created_by".to_string(), JsonValue::String(self.created_by.clone()));
+        obj.insert("created_at".to_string(), JsonValue::String(self.created_at.clone()));
+        obj.insert("updated_at".to_string(), JsonValue::String(self.updated_at.clone()));
+        obj.insert("closed_by".to_string(), optional_string(&self.closed_by));
+        obj.insert("closed_at".to_string(), optional_string(&self.closed_at));
+        obj.insert("closure_signature_id".to_string(), optional_string(&self.closure_signature_id));
+        obj.insert("cancellation_reason".to_string(), optional_string(&self.cancellation_reason));
+        JsonValue::Object(obj).json_to_string()
+    }
+
+    fn from_json(json: &str) -> Result<Self, JsonError> {
+        let obj = match JsonValue::parse(json)? {
+            JsonValue::Object(obj) => obj,
+            _ => return Err(JsonError::InvalidFormat("Expected JSON object".to_string())),
+        };
+
+        let root_cause = match obj.get("root_cause") {
+            Some(JsonValue::Object(rc)) => {
+                let mut fishbone = Vec::new();
+                if let Some(JsonValue::Array(causes)) = rc.get("fishbone") {
+                    for cause in causes {
+                        if let JsonValue::Object(c) = cause {
+                            fishbone.push(FishboneCause {
+                                category: FishboneCategory::from_str(&get_string(c, "category")?).map_err(invalid)?,
+                                cause: get_string(c, "cause")?,
+                            });
+                        }
+                    }
+                }
+                RootCauseAnalysis {
+                    five_whys: get_string_array(rc, "five_whys"),
+                    fishbone,
+                    root_cause_statement: get_optional_string(rc, "root_cause_statement").unwrap_or_default(),
+                    analyzed_by: get_optional_string(rc, "analyzed_by"),
+                    analyzed_at: get_optional_string(rc, "analyzed_at"),
+                }
+            }
+            _ => RootCauseAnalysis::default(),
+        };
+
+        let mut actions = Vec::new();
+        if let Some(JsonValue::Array(items)) = obj.get("actions") {
+            for item in items {
+                if let JsonValue::Object(a) = item {
+                    actions.push(CapaAction {
+                        id: get_string(a, "id")?,
+                        description: get_string(a, "description")?,
+                        owner: get_string(a, "owner")?,
+                        due_date: get_optional_string(a, "due_date"),
+                        status: CapaActionStatus::from_str(&get_string(a, "status")?).map_err(invalid)?,
+                        completed_at: get_optional_string(a, "completed_at"),
+                    });
+                }
+            }
+        }
+
+        let effectiveness = match obj.get("effectiveness") {
+            Some(JsonValue::Object(e)) => Some(EffectivenessCheck {
+                method: get_string(e, "method")?,
+                criteria: get_string(e, "criteria")?,
+                effective: e.get("effective").and_then(JsonValue::as_bool).unwrap_or(false),
+                evidence: get_string(e, "evidence")?,
+                checked_by: get_string(e, "checked_by")?,
+                checked_at: get_string(e, "checked_at")?,
+            }),
+            _ => None,
+        };
+
+        Ok(CapaRecord {
+            id: get_string(&obj, "id")?,
+            title: get_string(&obj, "title")?,
+            description: get_string(&obj, "description")?,
+            source: CapaSource::from_display_name(&get_string(&obj, "source")?),
+            source_reference: get_optional_string(&obj, "source_reference"),
+            capa_type: CapaType::from_str(&get_string(&obj, "capa_type")?).map_err(invalid)?,
+            priority: CapaPriority::from_str(&get_string(&obj, "priority")?).map_err(invalid)?,
+            status: CapaStatus::from_str(&get_string(&obj, "status")?).map_err(invalid)?,
+            root_cause,
+            actions,
+            effectiveness,
+            linked_risks: get_string_array(&obj, "linked_risks"),
+            owner: get_string(&obj, "owner")?,
+            due_date: get_optional_string(&obj, "due_date"),
+            created_by: get_string(&obj, "created_by")?,
+            created_at: get_string(&obj, "created_at")?,
+            updated_at: get_string(&obj, "updated_at")?,
+            closed_by: get_optional_string(&obj, "closed_by"),
+            closed_at: get_optional_string(&obj, "closed_at"),
+            closure_signature_id: get_optional_string(&obj, "closure_signature_id"),
+            cancellation_reason: get_optional_string(&obj, "cancellation_reason"),
+        })
+    }
+}
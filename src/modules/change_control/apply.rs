@@ -0,0 +1,289 @@
+//! Change Application
+//!
+//! Validates and applies the proposed changes of an approved change request
+//! through the owning services (`DocumentService`, `RequirementManager`,
+//! `RiskManager`). The files those services write are snapshotted first and the
+//! changes run in one storage transaction; if any change fails, the transaction
+//! is rolled back and every snapshotted file is restored so the bundle is
+//! applied all-or-nothing. The audit trail is append-only, so the entries the
+//! services already wrote stay; each reverted change gets a `CHANGE_REVERTED`
+//! entry recording that they no longer describe the project.
+
+use crate::modules::audit_logger::functions::audit_log_action;
+use crate::modules::document_control::service::DocumentService;
+use crate::modules::document_control::version::VersionChangeType;
+use crate::modules::risk_manager::risk::{RiskDetectability, RiskManager, RiskOccurrence, RiskSeverity};
+use crate::modules::storage;
+use crate::modules::traceability::requirement::{
+    RequirementCategory, RequirementManager, RequirementPriority, RequirementStatus, RequirementUpdate,
+    VerificationMethod,
+};
+use crate::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::record::{ChangeTarget, ProposedChange};
+
+/// Check that a proposed change names allowed fields with parseable values.
+///
+/// Does not touch the project; used when changes are added to a request.
+pub fn validate_fields(change: &ProposedChange) -> QmsResult<()> {
+    if change.fields.is_empty() {
+        return Err(QmsError::validation_error(&format!(
+            "Change to {} {} has no field updates", change.target.display_name(), change.entity_id
+        )));
+    }
+    let allowed = change.target.allowed_fields();
+    for (field, value) in &change.fields {
+        if !allowed.contains(&field.as_str()) {
+            return Err(QmsError::validation_error(&format!(
+                "Field '{field}' cannot be changed on a {} (allowed: {})",
+                change.target.display_name(),
+                allowed.join(", ")
+            )));
+        }
+        match (&change.target, field.as_str()) {
+            (ChangeTarget::Document, "change_type") => {
+                parse_version_change_type(value)?;
+            }
+            (ChangeTarget::Risk, "severity") => {
+                parse_severity(value)?;
+            }
+            (ChangeTarget::Risk, "occurrence") => {
+                parse_occurrence(value)?;
+            }
+            (ChangeTarget::Risk, "detectability") => {
+                parse_detectability(value)?;
+            }
+            _ if value.trim().is_empty() && field != "content" => {
+                return Err(QmsError::validation_error(&format!("Field '{field}' cannot be empty")));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Check that every changed entity exists in the project
+pub fn verify_targets_exist(project_path: &Path, changes: &[ProposedChange]) -> QmsResult<()> {
+    let requirements = RequirementManager::new(project_path)?;
+    let risks = RiskManager::new(project_path)?;
+
+    for change in changes {
+        let exists = match change.target {
+            ChangeTarget::Document => project_path.join("documents").join(&change.entity_id).join("metadata.json").exists(),
+            ChangeTarget::Requirement => requirements.get_requirement_by_req_id(&change.entity_id).is_some(),
+            ChangeTarget::Risk => risks.load_risk(&change.entity_id).is_ok(),
+        };
+        if !exists {
+            return Err(QmsError::not_found(&format!(
+                "{} {} not found", change.target.display_name(), change.entity_id
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Apply all changes, rolling back storage and restoring the snapshotted files if any change fails
+///
+/// After a rollback every change that was attempted is marked reverted in the audit trail.
+pub fn apply_atomically(project_path: &Path, changes: &[ProposedChange], applied_by: &str, change_id: &str) -> QmsResult<()> {
+    let snapshot = FileSnapshot::capture(&affected_paths(project_path, changes))?;
+
+    let mut attempted = 0;
+    let result = storage::with_transaction(project_path, || {
+        changes.iter().try_for_each(|change| {
+            attempted += 1;
+            apply_change(project_path, change, applied_by, change_id).map_err(|e| {
+                QmsError::domain_error(&format!(
+                    "Failed to apply change to {} {}: {e}", change.target.display_name(), change.entity_id
+                ))
+            })
+        })
+    });
+
+    if let Err(e) = result {
+        snapshot.restore()?;
+        for change in &changes[..attempted] {
+            audit_log_action(
+                "CHANGE_REVERTED",
+                change.target.display_name(),
+                &format!("{}|{change_id}", change.entity_id),
+            )?;
+        }
+        return Err(e);
+    }
+    Ok(())
+}
+
+fn apply_change(project_path: &Path, change: &ProposedChange, applied_by: &str, change_id: &str) -> QmsResult<()> {
+    let value = |name: &str| change.fields.iter().find(|(f, _)| f == name).map(|(_, v)| v.clone());
+
+    match change.target {
+        ChangeTarget::Document => {
+            let change_type = value("change_type").map(|v| parse_version_change_type(&v)).transpose()?;
+            DocumentService::new(project_path.to_path_buf()).update_document(
+                &change.entity_id,
+                value("title"),
+                value("content"),
+                change_type,
+                Some(format!("{change_id}: {}", change.description)),
+                applied_by.to_string(),
+            )?;
+        }
+        ChangeTarget::Requirement => {
+            let updates = RequirementUpdate {
+                title: value("title"),
+                description: value("description"),
+                category: value("category").map(|v| RequirementCategory::from_str(&v)),
+                priority: value("priority").map(|v| RequirementPriority::from_str(&v)),
+                status: value("status").map(|v| RequirementStatus::from_str(&v)),
+                source: value("source"),
+                rationale: value("rationale"),
+                acceptance_criteria: value("acceptance_criteria"),
+                verification_method: value("verification_method").map(|v| VerificationMethod::from_str(&v)),
+            };
+            RequirementManager::new(project_path)?.update_requirement(&change.entity_id, updates)?;
+        }
+        ChangeTarget::Risk => {
+            let mut manager = RiskManager::new(project_path)?;
+            let mut risk = manager.load_risk(&change.entity_id)?;
+            if let Some(v) = value("hazard_description") {
+                risk.hazard_description = v;
+            }
+            if let Some(v) = value("hazardous_situation") {
+                risk.hazardous_situation = v;
+            }
+            if let Some(v) = value("harm") {
+                risk.harm = v;
+            }
+            if let Some(v) = value("severity") {
+                risk.severity = parse_severity(&v)?;
+            }
+            if let Some(v) = value("occurrence") {
+                risk.occurrence = parse_occurrence(&v)?;
+            }
+            if let Some(v) = value("detectability") {
+                risk.detectability = parse_detectability(&v)?;
+            }
+            risk.calculate_rpn();
+            risk.assess_risk_level_with(&manager.policy()?);
+            risk.updated_at = crate::utils::current_iso8601_timestamp();
+            manager.update_risk(&risk)?;
+        }
+    }
+    Ok(())
+}
+
+/// Files and directories the owning services write when applying `changes`
+fn affected_paths(project_path: &Path, changes: &[ProposedChange]) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for change in changes {
+        match change.target {
+            ChangeTarget::Document => {
+                paths.push(project_path.join("documents").join(&change.entity_id));
+                paths.push(project_path.join("documents").join("index.json"));
+                paths.push(project_path.join("backups").join("backup_index.json"));
+            }
+            ChangeTarget::Requirement => {
+                paths.push(project_path.join("trace").join("requirements.json"));
+            }
+            ChangeTarget::Risk => {
+                paths.push(project_path.join("risks").join(format!("{}.json", change.entity_id)));
+                paths.push(project_path.join("risks").join("index.json"));
+            }
+        }
+    }
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// In-memory copy of a set of files and directory trees
+struct FileSnapshot {
+    roots: Vec<PathBuf>,
+    files: Vec<(PathBuf, Vec<u8>)>,
+}
+
+impl FileSnapshot {
+    fn capture(roots: &[PathBuf]) -> QmsResult<Self> {
+        let mut files = Vec::new();
+        for root in roots {
+            collect_files(root, &mut files)?;
+        }
+        Ok(Self { roots: roots.to_vec(), files })
+    }
+
+    /// Restore captured files and remove any created since the capture
+    fn restore(&self) -> QmsResult<()> {
+        let mut current = Vec::new();
+        for root in &self.roots {
+            collect_files(root, &mut current)?;
+        }
+        for (path, _) in &current {
+            if !self.files.iter().any(|(p, _)| p == path) {
+                fs::remove_file(path)?;
+            }
+        }
+        for (path, content) in &self.files {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, content)?;
+        }
+        Ok(())
+    }
+}
+
+fn collect_files(path: &Path, files: &mut Vec<(PathBuf, Vec<u8>)>) -> QmsResult<()> {
+    if path.is_dir() {
+        for entry in fs::read_dir(path)? {
+            collect_files(&entry?.path(), files)?;
+        }
+    } else if path.is_file() {
+        files.push((path.to_path_buf(), fs::read(path)?));
+    }
+    Ok(())
+}
+
+fn parse_version_change_type(value: &str) -> QmsResult<VersionChangeType> {
+    match value.to_lowercase().as_str() {
+        "major" => Ok(VersionChangeType::Major),
+        "minor" => Ok(VersionChangeType::Minor),
+        "patch" => Ok(VersionChangeType::Patch),
+        _ => Err(QmsError::validation_error(&format!("Invalid change_type: {value} (expected major, minor or patch)"))),
+    }
+}
+
+fn parse_severity(value: &str) -> QmsResult<RiskSeverity> {
+    match value.to_lowercase().as_str() {
+        "5" | "catastrophic" => Ok(RiskSeverity::Catastrophic),
+        "4" | "critical" => Ok(RiskSeverity::Critical),
+        "3" | "major" => Ok(RiskSeverity::Major),
+        "2" | "minor" => Ok(RiskSeverity::Minor),
+        "1" | "negligible" => Ok(RiskSeverity::Negligible),
+        _ => Err(QmsError::validation_error(&format!("Invalid severity: {value} (expected 1-5)"))),
+    }
+}
+
+fn parse_occurrence(value: &str) -> QmsResult<RiskOccurrence> {
+    match value.to_lowercase().as_str() {
+        "5" | "frequent" => Ok(RiskOccurrence::Frequent),
+        "4" | "probable" => Ok(RiskOccurrence::Probable),
+        "3" | "occasional" => Ok(RiskOccurrence::Occasional),
+        "2" | "remote" => Ok(RiskOccurrence::Remote),
+        "1" | "improbable" => Ok(RiskOccurrence::Improbable),
+        _ => Err(QmsError::validation_error(&format!("Invalid occurrence: {value} (expected 1-5)"))),
+    }
+}
+
+fn parse_detectability(value: &str) -> QmsResult<RiskDetectability> {
+    match value.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+        "5" | "verylow" => Ok(RiskDetectability::VeryLow),
+        "4" | "low" => Ok(RiskDetectability::Low),
+        "3" | "moderate" => Ok(RiskDetectability::Moderate),
+        "2" | "high" => Ok(RiskDetectability::High),
+        "1" | "veryhigh" => Ok(RiskDetectability::VeryHigh),
+        _ => Err(QmsError::validation_error(&format!("Invalid detectability: {value} (expected 1-5)"))),
+    }
+}
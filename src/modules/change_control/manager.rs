@@ -0,0 +1,487 @@
+//! Change Control Manager
+//!
+//! Persists change requests under `changes/<id>.json` and drives the design
+//! change workflow: assemble proposed changes, submit to the Change Control
+//! Board with an automatic impact analysis, collect a signature from every CCB
+//! member, then apply the whole bundle atomically.
+
+use crate::json_utils::JsonSerializable;
+use crate::modules::audit_logger::functions::{audit_log_action, audit_log_create, audit_log_update};
+use crate::modules::audit_logger::signatures::{ElectronicSignatureManager, SignatureVerification};
+use crate::modules::traceability::impact::ImpactAnalyzer;
+use crate::modules::storage::encryption;
+use crate::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::apply;
+use super::record::{CcbApproval, ChangeRequest, ChangeState, ChangeTarget, ImpactSummary, ProposedChange};
+
+/// Signature policy action used for CCB approvals
+pub const CCB_APPROVAL_ACTION: &str = "ccb_approval";
+
+/// Change request manager
+pub struct ChangeControlManager {
+    project_path: PathBuf,
+    changes_dir: PathBuf,
+}
+
+impl ChangeControlManager {
+    /// Create new change control manager for a project
+    pub fn new(project_path: &Path) -> QmsResult<Self> {
+        let changes_dir = project_path.join("changes");
+        fs::create_dir_all(&changes_dir)?;
+        Ok(Self {
+            project_path: project_path.to_path_buf(),
+            changes_dir,
+        })
+    }
+
+    /// Open a new change request in `Draft`
+    pub fn create_change(&self, title: &str, reason: &str, requested_by: &str) -> QmsResult<ChangeRequest> {
+        if title.trim().is_empty() {
+            return Err(QmsError::validation_error("Change request title cannot be empty"));
+        }
+        if reason.trim().is_empty() {
+            return Err(QmsError::validation_error("A reason for change is required"));
+        }
+
+        let timestamp = crate::utils::current_iso8601_timestamp();
+        let record = ChangeRequest {
+            id: self.next_change_id()?,
+            title: title.to_string(),
+            reason: reason.to_string(),
+            state: ChangeState::Draft,
+            changes: Vec::new(),
+            impact: Vec::new(),
+            ccb_members: Vec::new(),
+            approvals: Vec::new(),
+            requested_by: requested_by.to_string(),
+            created_at: timestamp.clone(),
+            updated_at: timestamp,
+            submitted_at: None,
+            implemented_by: None,
+            implemented_at: None,
+            rejection_reason: None,
+        };
+
+        self.save_change(&record)?;
+        audit_log_create("ChangeRequest", &record.id, &record.title)?;
+        Ok(record)
+    }
+
+    /// Load a change request
+    pub fn load_change(&self, change_id: &str) -> QmsResult<ChangeRequest> {
+        let path = self.change_path(change_id);
+        if !path.exists() {
+            return Err(QmsError::not_found(&format!("Change request {change_id} not found")));
+        }
+        let content = encryption::read_to_string(&path)?;
+        ChangeRequest::from_json(&content)
+            .map_err(|e| QmsError::parse_error(&format!("Invalid change request {change_id}: {e}")))
+    }
+
+    /// List change requests, optionally filtered by state, ordered by ID
+    pub fn list_changes(&self, state: Option<&ChangeState>) -> QmsResult<Vec<ChangeRequest>> {
+        let mut records = Vec::new();
+        for entry in fs::read_dir(&self.changes_dir)? {
+            let path = entry?.path();
+            let is_record = path.extension().is_some_and(|ext| ext == "json")
+                && path.file_stem().and_then(|s| s.to_str()).is_some_and(|s| s.starts_with("ECR-"));
+            if !is_record {
+                continue;
+            }
+            let content = encryption::read_to_string(&path)?;
+            let record = ChangeRequest::from_json(&content)
+                .map_err(|e| QmsError::parse_error(&format!("Invalid change request {}: {e}", path.display())))?;
+            if state.map_or(true, |s| &record.state == s) {
+                records.push(record);
+            }
+        }
+        records.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(records)
+    }
+
+    /// Add a proposed modification to a draft change request
+    pub fn add_change(&self, change_id: &str, change: ProposedChange) -> QmsResult<ChangeRequest> {
+        let mut record = self.load_change(change_id)?;
+        if record.state != ChangeState::Draft {
+            return Err(QmsError::validation_error(&format!(
+                "Changes can only be added to a draft change request ({change_id} is {})",
+                record.state.display_name()
+            )));
+        }
+        apply::validate_fields(&change)?;
+        apply::verify_targets_exist(&self.project_path, std::slice::from_ref(&change))?;
+        if record.changes.iter().any(|c| c.target == change.target && c.entity_id == change.entity_id) {
+            return Err(QmsError::validation_error(&format!(
+                "{change_id} already modifies {} {}", change.target.display_name(), change.entity_id
+            )));
+        }
+
+        let details = format!("{change_id}|{}|{}", change.target.display_name(), change.entity_id);
+        record.changes.push(change);
+        self.touch_and_save(&mut record)?;
+
+        audit_log_action("CHANGE_ITEM_ADDED", "ChangeRequest", &details)?;
+        Ok(record)
+    }
+
+    /// Submit to the CCB. Runs the impact analysis for every requirement change.
+    pub fn submit(&self, change_id: &str, ccb_members: Vec<String>, user_id: &str) -> QmsResult<ChangeRequest> {
+        let mut record = self.load_change(change_id)?;
+        if record.changes.is_empty() {
+            return Err(QmsError::validation_error(&format!("{change_id} has no proposed changes")));
+        }
+        let mut members: Vec<String> = Vec::new();
+        for member in ccb_members.iter().map(|m| m.trim()).filter(|m| !m.is_empty()) {
+            if !members.iter().any(|m| m == member) {
+                members.push(member.to_string());
+            }
+        }
+        if members.is_empty() {
+            return Err(QmsError::validation_error("At least one CCB member is required"));
+        }
+        apply::verify_targets_exist(&self.project_path, &record.changes)?;
+
+        let analyzer = ImpactAnalyzer::new(&self.project_path)?;
+        let mut impact = Vec::new();
+        for change in record.changes.iter().filter(|c| c.target == ChangeTarget::Requirement) {
+            let analysis = analyzer.analyze_requirement_impact(&change.entity_id, &change.description)?;
+            impact.push(ImpactSummary {
+                entity_id: change.entity_id.clone(),
+                direct_impacts: analysis.direct_impacts.iter().map(|i| i.entity_id.clone()).collect(),
+                indirect_impacts: analysis.indirect_impacts.iter().map(|i| i.entity_id.clone()).collect(),
+                effort_hours: analysis.total_effort_estimate,
+                risk_assessment: analysis.risk_assessment,
+                recommended_actions: analysis.recommended_actions,
+            });
+        }
+
+        record.impact = impact;
+        record.ccb_members = members;
+        record.approvals.clear();
+        record.submitted_at = Some(crate::utils::current_iso8601_timestamp());
+        self.transition(&mut record, ChangeState::UnderReview, user_id)?;
+        Ok(record)
+    }
+
+    /// Sign the change request as a CCB member.
+    ///
+    /// Once every member has signed the request moves to `Approved`.
+    pub fn approve(
+        &self,
+        change_id: &str,
+        user_id: &str,
+        password: &str,
+        code: Option<&str>,
+        comments: Option<&str>,
+    ) -> QmsResult<ChangeRequest> {
+        let mut record = self.load_change(change_id)?;
+        if record.state != ChangeState::UnderReview {
+            return Err(QmsError::validation_error(&format!(
+                "{change_id} is not under CCB review ({})", record.state.display_name()
+            )));
+        }
+        if !record.ccb_members.iter().any(|m| m == user_id) {
+            return Err(QmsError::permission_error(&format!("{user_id} is not a CCB member for {change_id}")));
+        }
+        if record.approvals.iter().any(|a| a.user_id == user_id) {
+            return Err(QmsError::validation_error(&format!("{user_id} has already approved {change_id}")));
+        }
+
+        let signature = ElectronicSignatureManager::new(self.project_path.clone()).create_signature_with_code(
+            user_id.to_string(),
+            password,
+            code,
+            CCB_APPROVAL_ACTION,
+            "ChangeRequest".to_string(),
+            change_id.to_string(),
+            &record.signing_content(),
+            None,
+        )?;
+
+        record.approvals.push(CcbApproval {
+            user_id: user_id.to_string(),
+            signature_id: signature.id.clone(),
+            signed_at: signature.timestamp.clone(),
+            comments: comments.map(str::to_string),
+        });
+        audit_log_action("CHANGE_CCB_SIGNED", "ChangeRequest", &format!("{change_id}|{user_id}|{}", signature.id))?;
+
+        if record.outstanding_approvers().is_empty() {
+            self.transition(&mut record, ChangeState::Approved, user_id)?;
+        } else {
+            self.touch_and_save(&mut record)?;
+        }
+        Ok(record)
+    }
+
+    /// Reject the change request as a CCB member
+    pub fn reject(&self, change_id: &str, user_id: &str, reason: &str) -> QmsResult<ChangeRequest> {
+        if reason.trim().is_empty() {
+            return Err(QmsError::validation_error("A rejection reason is required"));
+        }
+        let mut record = self.load_change(change_id)?;
+        if !record.ccb_members.iter().any(|m| m == user_id) {
+            return Err(QmsError::permission_error(&format!("{user_id} is not a CCB member for {change_id}")));
+        }
+        record.rejection_reason = Some(reason.to_string());
+        self.transition(&mut record, ChangeState::Rejected, user_id)?;
+        Ok(record)
+    }
+
+    /// Withdraw a submitted change request back to `Draft`, discarding signatures
+    pub fn withdraw(&self, change_id: &str, user_id: &str) -> QmsResult<ChangeRequest> {
+        let mut record = self.load_change(change_id)?;
+        record.approvals.clear();
+        record.impact.clear();
+        record.submitted_at = None;
+        self.transition(&mut record, ChangeState::Draft, user_id)?;
+        Ok(record)
+    }
+
+    /// Cancel a change request that has not been approved
+    pub fn cancel(&self, change_id: &str, user_id: &str) -> QmsResult<ChangeRequest> {
+        let mut record = self.load_change(change_id)?;
+        self.transition(&mut record, ChangeState::Cancelled, user_id)?;
+        Ok(record)
+    }
+
+    /// Verify every CCB signature still matches the stored change request
+    pub fn verify_approvals(&self, change_id: &str) -> QmsResult<Vec<(String, SignatureVerification)>> {
+        let record = self.load_change(change_id)?;
+        let signature_manager = ElectronicSignatureManager::new(self.project_path.clone());
+        let content = record.signing_content();
+        record.approvals.iter().map(|approval| {
+            let verification = signature_manager.verify_signature_for_record(&approval.signature_id, &content)?;
+            Ok((approval.user_id.clone(), verification))
+        }).collect()
+    }
+
+    /// Apply an approved change request to the controlled records.
+    ///
+    /// All changes are applied or none are: on failure every touched file is
+    /// restored and the request stays `Approved`.
+    pub fn implement(&self, change_id: &str, user_id: &str) -> QmsResult<ChangeRequest> {
+        let mut record = self.load_change(change_id)?;
+        if record.state != ChangeState::Approved {
+            return Err(QmsError::validation_error(&format!(
+                "{change_id} must be approved by the CCB before it is implemented ({})",
+                record.state.display_name()
+            )));
+        }
+        for (member, verification) in self.verify_approvals(change_id)? {
+            if !verification.is_valid {
+                return Err(QmsError::validation_error(&format!(
+                    "CCB signature by {member} on {change_id} is no longer valid"
+                )));
+            }
+        }
+        apply::verify_targets_exist(&self.project_path, &record.changes)?;
+
+        if let Err(e) = apply::apply_atomically(&self.project_path, &record.changes, user_id, change_id) {
+            audit_log_action("CHANGE_APPLY_ROLLED_BACK", "ChangeRequest", &format!("{change_id}|{e}"))?;
+            return Err(e);
+        }
+
+        record.implemented_by = Some(user_id.to_string());
+        record.implemented_at = Some(crate::utils::current_iso8601_timestamp());
+        self.transition(&mut record, ChangeState::Implemented, user_id)?;
+        Ok(record)
+    }
+
+    fn transition(&self, record: &mut ChangeRequest, target: ChangeState, actor_id: &str) -> QmsResult<()> {
+        if !record.state.can_transition_to(&target) {
+            return Err(QmsError::validation_error(&format!(
+                "Invalid change request transition from {} to {}",
+                record.state.display_name(),
+                target.display_name()
+            )));
+        }
+        let old_state = record.state.display_name();
+        record.state = target;
+        self.touch_and_save(record)?;
+
+        audit_log_update(
+            "ChangeRequest",
+            &record.id,
+            old_state,
+            &format!("{} by {actor_id}", record.state.display_name()),
+        )?;
+        Ok(())
+    }
+
+    fn change_path(&self, change_id: &str) -> PathBuf {
+        self.changes_dir.join(format!("{change_id}.json"))
+    }
+
+    fn next_change_id(&self) -> QmsResult<String> {
+        let mut max = 0u32;
+        for entry in fs::read_dir(&self.changes_dir)? {
+            let name = entry?.file_name().to_string_lossy().to_string();
+            if let Some(number) = name.strip_prefix("ECR-").and_then(|n| n.strip_suffix(".json")) {
+                max = max.max(number.parse().unwrap_or(0));
+            }
+        }
+        Ok(format!("ECR-{:03}", max + 1))
+    }
+
+    fn touch_and_save(&self, record: &mut ChangeRequest) -> QmsResult<()> {
+        record.updated_at = crate::utils::current_iso8601_timestamp();
+        self.save_change(record)
+    }
+
+    fn save_change(&self, record: &ChangeRequest) -> QmsResult<()> {
+        encryption::atomic_write(&self.change_path(&record.id), &record.to_json())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::audit_logger::{initialize_audit_system, AuditConfig};
+    use crate::modules::document_control::document::DocumentType;
+    use crate::modules::document_control::service::DocumentService;
+    use crate::modules::risk_manager::risk::RiskManager;
+    use crate::modules::traceability::requirement::{RequirementCategory, RequirementManager};
+    use crate::modules::user_manager::signing_keys::SigningKeyStore;
+
+    struct Fixture {
+        dir: tempfile::TempDir,
+        manager: ChangeControlManager,
+        document_id: String,
+        risk_id: String,
+    }
+
+    fn setup() -> Fixture {
+        let dir = tempfile::tempdir().unwrap();
+        let _ = initialize_audit_system(AuditConfig {
+            project_path: dir.path().to_string_lossy().to_string(),
+            retention_days: 30,
+            daily_rotation: false,
+            max_file_size_mb: 10,
+            require_checksums: false,
+        });
+        for user in ["alice", "bob"] {
+            SigningKeyStore::new(dir.path()).generate_key_pair(user, "password123").unwrap();
+        }
+
+        RequirementManager::new(dir.path()).unwrap().create_requirement(
+            "project-001".to_string(),
+            "REQ-001".to_string(),
+            "Flow accuracy".to_string(),
+            "Deliver within 5% of set rate".to_string(),
+            RequirementCategory::Functional,
+            "alice".to_string(),
+        ).unwrap();
+        let mut risk_manager = RiskManager::new(dir.path()).unwrap();
+        risk_manager.initialize().unwrap();
+        let risk = risk_manager.create_risk("Over-infusion", "Rate drift", "Overdose").unwrap();
+        let document = DocumentService::new(dir.path().to_path_buf()).create_document(
+            "Pump SRS".to_string(),
+            "Flow accuracy 5%".to_string(),
+            DocumentType::SoftwareRequirementsSpecification,
+            "alice".to_string(),
+        ).unwrap();
+
+        let manager = ChangeControlManager::new(dir.path()).unwrap();
+        Fixture { dir, manager, document_id: document.id, risk_id: risk.id }
+    }
+
+    fn change(target: ChangeTarget, entity_id: &str, fields: &[(&str, &str)]) -> ProposedChange {
+        ProposedChange {
+            target,
+            entity_id: entity_id.to_string(),
+            fields: fields.iter().map(|(f, v)| (f.to_string(), v.to_string())).collect(),
+            description: "Tighten flow accuracy".to_string(),
+        }
+    }
+
+    fn approved_change(f: &Fixture) -> ChangeRequest {
+        let ecr = f.manager.create_change("Tighten flow accuracy", "Field complaints", "alice").unwrap();
+        f.manager.add_change(&ecr.id, change(ChangeTarget::Requirement, "REQ-001", &[("description", "Deliver within 2% of set rate")])).unwrap();
+        f.manager.add_change(&ecr.id, change(ChangeTarget::Document, &f.document_id, &[("content", "Flow accuracy 2%"), ("change_type", "minor")])).unwrap();
+        f.manager.add_change(&ecr.id, change(ChangeTarget::Risk, &f.risk_id, &[("occurrence", "2")])).unwrap();
+        f.manager.submit(&ecr.id, vec!["alice".to_string(), "bob".to_string()], "alice").unwrap();
+        f.manager.approve(&ecr.id, "alice", "password123", None, None).unwrap();
+        f.manager.approve(&ecr.id, "bob", "password123", None, Some("OK")).unwrap()
+    }
+
+    #[test]
+    fn test_add_change_validates_fields_and_targets() {
+        let f = setup();
+        let ecr = f.manager.create_change("Rename", "Clarity", "alice").unwrap();
+        assert_eq!(ecr.id, "ECR-001");
+
+        assert!(f.manager.add_change(&ecr.id, change(ChangeTarget::Requirement, "REQ-001", &[("owner", "bob")])).is_err());
+        assert!(f.manager.add_change(&ecr.id, change(ChangeTarget::Requirement, "REQ-999", &[("title", "x")])).is_err());
+        assert!(f.manager.add_change(&ecr.id, change(ChangeTarget::Risk, &f.risk_id, &[("severity", "9")])).is_err());
+        assert!(f.manager.submit(&ecr.id, vec!["alice".to_string()], "alice").is_err());
+
+        f.manager.add_change(&ecr.id, change(ChangeTarget::Requirement, "REQ-001", &[("title", "Flow rate accuracy")])).unwrap();
+        assert!(f.manager.submit(&ecr.id, Vec::new(), "alice").is_err());
+        let submitted = f.manager.submit(&ecr.id, vec!["alice".to_string()], "alice").unwrap();
+        assert_eq!(submitted.state, ChangeState::UnderReview);
+        assert_eq!(submitted.impact.len(), 1);
+        assert!(f.manager.add_change(&ecr.id, change(ChangeTarget::Risk, &f.risk_id, &[("harm", "x")])).is_err());
+    }
+
+    #[test]
+    fn test_implement_requires_all_ccb_signatures_and_applies_bundle() {
+        let f = setup();
+        let ecr = f.manager.create_change("Tighten flow accuracy", "Field complaints", "alice").unwrap();
+        f.manager.add_change(&ecr.id, change(ChangeTarget::Requirement, "REQ-001", &[("description", "Deliver within 2% of set rate")])).unwrap();
+        f.manager.submit(&ecr.id, vec!["alice".to_string(), "bob".to_string()], "alice").unwrap();
+
+        assert!(f.manager.approve(&ecr.id, "carol", "password123", None, None).is_err());
+        let partial = f.manager.approve(&ecr.id, "alice", "password123", None, None).unwrap();
+        assert_eq!(partial.outstanding_approvers(), vec!["bob"]);
+        assert!(f.manager.implement(&ecr.id, "alice").is_err());
+        f.manager.withdraw(&ecr.id, "alice").unwrap();
+        f.manager.cancel(&ecr.id, "alice").unwrap();
+
+        let ecr = approved_change(&f);
+        assert_eq!(ecr.state, ChangeState::Approved);
+        let implemented = f.manager.implement(&ecr.id, "alice").unwrap();
+        assert_eq!(implemented.state, ChangeState::Implemented);
+
+        let requirements = RequirementManager::new(f.dir.path()).unwrap();
+        assert_eq!(requirements.get_requirement_by_req_id("REQ-001").unwrap().description, "Deliver within 2% of set rate");
+        let document = DocumentService::new(f.dir.path().to_path_buf()).read_document(&f.document_id).unwrap();
+        assert_eq!(document.content, "Flow accuracy 2%");
+        assert_eq!(document.version, "1.1.0");
+        let risk = RiskManager::new(f.dir.path()).unwrap().load_risk(&f.risk_id).unwrap();
+        assert_eq!(risk.occurrence, crate::modules::risk_manager::risk::RiskOccurrence::Remote);
+    }
+
+    #[test]
+    fn test_failed_apply_rolls_back_every_change() {
+        let f = setup();
+        let ecr = approved_change(&f);
+
+        // Corrupt the document so the second change fails after the first is written
+        let metadata = f.dir.path().join("documents").join(&f.document_id).join("metadata.json");
+        fs::write(&metadata, "not json").unwrap();
+        assert!(f.manager.implement(&ecr.id, "alice").is_err());
+
+        let requirements = RequirementManager::new(f.dir.path()).unwrap();
+        assert_eq!(requirements.get_requirement_by_req_id("REQ-001").unwrap().description, "Deliver within 5% of set rate");
+        assert_eq!(fs::read_to_string(&metadata).unwrap(), "not json");
+        assert_eq!(f.manager.load_change(&ecr.id).unwrap().state, ChangeState::Approved);
+    }
+
+    #[test]
+    fn test_tampered_change_request_cannot_be_implemented() {
+        let f = setup();
+        let ecr = approved_change(&f);
+
+        let path = f.dir.path().join("changes").join(format!("{}.json", ecr.id));
+        let tampered = fs::read_to_string(&path).unwrap().replace("Deliver within 2% of set rate", "Deliver within 20% of set rate");
+        fs::write(&path, tampered).unwrap();
+
+        assert!(f.manager.implement(&ecr.id, "alice").is_err());
+        let requirements = RequirementManager::new(f.dir.path()).unwrap();
+        assert_eq!(requirements.get_requirement_by_req_id("REQ-001").unwrap().description, "Deliver within 5% of set rate");
+    }
+}
@@ -0,0 +1,16 @@
+//! Engineering Change Control Module
+//!
+//! Design change control per FDA 21 CFR 820.30(i) and ISO 13485:2016
+//! Section 7.3.9: engineering change requests bundle proposed modifications to
+//! documents, requirements and risks, carry an automatic impact analysis, require
+//! a signature from every Change Control Board member and are applied
+//! all-or-nothing once approved.
+
+pub mod apply;
+pub mod manager;
+pub mod record;
+
+#[allow(unused_imports)]
+pub use manager::{ChangeControlManager, CCB_APPROVAL_ACTION};
+#[allow(unused_imports)]
+pub use record::{CcbApproval, ChangeRequest, ChangeState, ChangeTarget, ImpactSummary, ProposedChange};
@@ -0,0 +1,371 @@
+//! Change Request Model
+//!
+//! Engineering change request (ECR) records that bundle proposed modifications
+//! to documents, requirements and risks per FDA 21 CFR 820.30(i).
+
+use crate::json_utils::{JsonError, JsonSerializable, JsonValue};
+use crate::prelude::*;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Change request lifecycle state
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeState {
+    Draft,       // Changes being assembled
+    UnderReview, // Submitted to the CCB with impact analysis
+    Approved,    // All CCB members have signed
+    Implemented, // Changes applied to the controlled records
+    Rejected,    // Rejected by the CCB
+    Cancelled,   // Withdrawn by the requester
+}
+
+impl ChangeState {
+    pub const fn can_transition_to(&self, target: &ChangeState) -> bool {
+        match (self, target) {
+            // From Draft
+            (ChangeState::Draft, ChangeState::UnderReview) => true,
+            (ChangeState::Draft, ChangeState::Cancelled) => true,
+
+            // From UnderReview
+            (ChangeState::UnderReview, ChangeState::Approved) => true,
+            (ChangeState::UnderReview, ChangeState::Rejected) => true,
+            (ChangeState::UnderReview, ChangeState::Draft) => true, // Withdrawn for rework
+            (ChangeState::UnderReview, ChangeState::Cancelled) => true,
+
+            // From Approved
+            (ChangeState::Approved, ChangeState::Implemented) => true,
+
+            _ => false,
+        }
+    }
+
+    pub const fn display_name(&self) -> &'static str {
+        match self {
+            ChangeState::Draft => "Draft",
+            ChangeState::UnderReview => "Under Review",
+            ChangeState::Approved => "Approved",
+            ChangeState::Implemented => "Implemented",
+            ChangeState::Rejected => "Rejected",
+            ChangeState::Cancelled => "Cancelled",
+        }
+    }
+}
+
+impl FromStr for ChangeState {
+    type Err = QmsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "draft" => Ok(ChangeState::Draft),
+            "underreview" | "review" => Ok(ChangeState::UnderReview),
+            "approved" => Ok(ChangeState::Approved),
+            "implemented" => Ok(ChangeState::Implemented),
+            "rejected" => Ok(ChangeState::Rejected),
+            "cancelled" | "canceled" => Ok(ChangeState::Cancelled),
+            _ => Err(QmsError::validation_error(&format!("Unknown change request state: {s}"))),
+        }
+    }
+}
+
+/// Kind of controlled record a proposed change modifies
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeTarget {
+    Document,
+    Requirement,
+    Risk,
+}
+
+impl ChangeTarget {
+    pub const fn display_name(&self) -> &'static str {
+        match self {
+            ChangeTarget::Document => "Document",
+            ChangeTarget::Requirement => "Requirement",
+            ChangeTarget::Risk => "Risk",
+        }
+    }
+
+    /// Fields that may be modified through a change request
+    pub const fn allowed_fields(&self) -> &'static [&'static str] {
+        match self {
+            ChangeTarget::Document => &["title", "content", "change_type"],
+            ChangeTarget::Requirement => &[
+                "title", "description", "category", "priority", "status", "source", "rationale",
+                "acceptance_criteria", "verification_method",
+            ],
+            ChangeTarget::Risk => &[
+                "hazard_description", "hazardous_situation", "harm", "severity", "occurrence", "detectability",
+            ],
+        }
+    }
+}
+
+impl FromStr for ChangeTarget {
+    type Err = QmsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "document" | "doc" => Ok(ChangeTarget::Document),
+            "requirement" | "req" => Ok(ChangeTarget::Requirement),
+            "risk" => Ok(ChangeTarget::Risk),
+            _ => Err(QmsError::validation_error(&format!(
+                "Invalid change target: {s} (expected document, requirement or risk)"
+            ))),
+        }
+    }
+}
+
+/// One proposed modification to a controlled record.
+///
+/// Field updates are kept in insertion order so the signed content is stable.
+#[derive(Debug, Clone)]
+pub struct ProposedChange {
+    pub target: ChangeTarget,
+    pub entity_id: String,          // Document ID, requirement ID (REQ-001) or risk ID
+    pub fields: Vec<(String, String)>,
+    pub description: String,
+}
+
+/// Impact analysis captured when the change request is submitted
+#[derive(Debug, Clone)]
+pub struct ImpactSummary {
+    pub entity_id: String,
+    pub direct_impacts: Vec<String>,   // Entity IDs directly linked to the changed entity
+    pub indirect_impacts: Vec<String>, // Second-degree impacts
+    pub effort_hours: u32,
+    pub risk_assessment: String,
+    pub recommended_actions: Vec<String>,
+}
+
+/// Change Control Board member's signed approval
+#[derive(Debug, Clone)]
+pub struct CcbApproval {
+    pub user_id: String,
+    pub signature_id: String,
+    pub signed_at: String, // ISO 8601 timestamp
+    pub comments: Option<String>,
+}
+
+/// Engineering change request
+#[derive(Debug, Clone)]
+pub struct ChangeRequest {
+    pub id: String, // ECR-001, ECR-002...
+    pub title: String,
+    pub reason: String,
+    pub state: ChangeState,
+    pub changes: Vec<ProposedChange>,
+    pub impact: Vec<ImpactSummary>,
+    pub ccb_members: Vec<String>, // Users whose signatures are required
+    pub approvals: Vec<CcbApproval>,
+    pub requested_by: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub submitted_at: Option<String>,
+    pub implemented_by: Option<String>,
+    pub implemented_at: Option<String>,
+    pub rejection_reason: Option<String>,
+}
+
+impl ChangeRequest {
+    /// Deterministic byte representation bound by each CCB signature.
+    ///
+    /// Covers every proposed field value, so approving one bundle cannot be
+    /// replayed against an edited one.
+    pub fn signing_content(&self) -> Vec<u8> {
+        let mut content = String::from("qms-change-request-v1\n");
+        content.push_str(&format!("id={}\n", self.id));
+        content.push_str(&format!("title={}\n", self.title));
+        content.push_str(&format!("reason={}\n", self.reason));
+        for change in &self.changes {
+            content.push_str(&format!("change={}|{}|{}\n", change.target.display_name(), change.entity_id, change.description));
+            for (field, value) in &change.fields {
+                content.push_str(&format!("  {field}={value}\n"));
+            }
+        }
+        for impact in &self.impact {
+            content.push_str(&format!(
+                "impact={}|{}|{}\n",
+                impact.entity_id,
+                impact.direct_impacts.join(","),
+                impact.indirect_impacts.join(",")
+            ));
+        }
+        content.push_str(&format!("ccb={}\n", self.ccb_members.join(",")));
+        content.into_bytes()
+    }
+
+    /// CCB members who have not yet signed
+    pub fn outstanding_approvers(&self) -> Vec<&str> {
+        self.ccb_members
+            .iter()
+            .filter(|member| !self.approvals.iter().any(|a| &a.user_id == *member))
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+fn optional_string(value: &Option<String>) -> JsonValue {
+    value.as_ref().map_or(JsonValue::Null, |s| JsonValue::String(s.clone()))
+}
+
+fn string_array(values: &[String]) -> JsonValue {
+    JsonValue::Array(values.iter().map(|v| JsonValue::String(v.clone())).collect())
+}
+
+fn get_string(obj: &HashMap<String, JsonValue>, field: &str) -> Result<String, JsonError> {
+    match obj.get(field) {
+        Some(JsonValue::String(s)) => Ok(s.clone()),
+        Some(_) => Err(JsonError::InvalidFormat(format!("Field '{field}' is not a string"))),
+        None => Err(JsonError::InvalidFormat(format!("Missing required field '{field}'"))),
+    }
+}
+
+fn get_optional_string(obj: &HashMap<String, JsonValue>, field: &str) -> Option<String> {
+    obj.get(field).and_then(|v| v.as_string().cloned())
+}
+
+fn get_string_array(obj: &HashMap<String, JsonValue>, field: &str) -> Vec<String> {
+    match obj.get(field) {
+        Some(JsonValue::Array(items)) => items.iter().filter_map(|v| v.as_string().cloned()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn get_objects<'a>(obj: &'a HashMap<String, JsonValue>, field: &str) -> Vec<&'a HashMap<String, JsonValue>> {
+    match obj.get(field) {
+        Some(JsonValue::Array(items)) => items
+            .iter()
+            .filter_map(|item| match item {
+                JsonValue::Object(o) => Some(o),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn invalid(err: QmsError) -> JsonError {
+    JsonError::InvalidFormat(err.to_string())
+}
+
+impl JsonSerializable for ChangeRequest {
+    fn to_json(&self) -> String {
+        let changes = self.changes.iter().map(|c| {
+            // Fields are stored as [name, value] pairs to preserve order
+            let fields = c.fields.iter().map(|(name, value)| {
+                JsonValue::Array(vec![JsonValue::String(name.clone()), JsonValue::String(value.clone())])
+            }).collect();
+            let mut change = HashMap::new();
+            change.insert("target".to_string(), JsonValue::String(c.target.display_name().to_string()));
+            change.insert("entity_id".to_string(), JsonValue::String(c.entity_id.clone()));
+            change.insert("fields".to_string(), JsonValue::Array(fields));
+            change.insert("description".to_string(), JsonValue::String(c.description.clone()));
+            JsonValue::Object(change)
+        }).collect();
+
+        let impact = self.impact.iter().map(|i| {
+            let mut summary = HashMap::new();
+            summary.insert("entity_id".to_string(), JsonValue::String(i.entity_id.clone()));
+            summary.insert("direct_impacts".to_string(), string_array(&i.direct_impacts));
+            summary.insert("indirect_impacts".to_string(), string_array(&i.indirect_impacts));
+            summary.insert("effort_hours".to_string(), JsonValue::Number(f64::from(i.effort_hours)));
+            summary.insert("risk_assessment".to_string(), JsonValue::String(i.risk_assessment.clone()));
+            summary.insert("recommended_actions".to_string(), string_array(&i.recommended_actions));
+            JsonValue::Object(summary)
+        }).collect();
+
+        let approvals = self.approvals.iter().map(|a| {
+            let mut approval = HashMap::new();
+            approval.insert("user_id".to_string(), JsonValue::String(a.user_id.clone()));
+            approval.insert("signature_id".to_string(), JsonValue::String(a.signature_id.clone()));
+            approval.insert("signed_at".to_string(), JsonValue::String(a.signed_at.clone()));
+            approval.insert("comments".to_string(), optional_string(&a.comments));
+            JsonValue::Object(approval)
+        }).collect();
+
+        let mut obj = HashMap::new();
+        obj.insert("version".to_string(), JsonValue::String("1.0".to_string()));
+        obj.insert("id".to_string(), JsonValue::String(self.id.clone()));
+        obj.insert("title".to_string(), JsonValue::String(self.title.clone()));
+        obj.insert("reason".to_string(), JsonValue::String(self.reason.clone()));
+        obj.insert("state".to_string(), JsonValue::String(self.state.display_name().to_string()));
+        obj.insert("changes".to_string(), JsonValue::Array(changes));
+        obj.insert("impact".to_string(), JsonValue::Array(impact));
+        obj.insert("ccb_members".to_string(), string_array(&self.ccb_members));
+        obj.insert("approvals".to_string(), JsonValue::Array(approvals));
+        obj.insert("requested_by".to_string(), JsonValue::String(self.requested_by.clone()));
+        obj.insert("created_at".to_string(), JsonValue::String(self.created_at.clone()));
+        obj.insert("updated_at".to_string(), JsonValue::String(self.updated_at.clone()));
+        obj.insert("submitted_at".to_string(), optional_string(&self.submitted_at));
+        obj.insert("implemented_by".to_string(), optional_string(&self.implemented_by));
+        obj.insert("implemented_at".to_string(), optional_string(&self.implemented_at));
+        obj.insert("rejection_reason".to_string(), optional_string(&self.rejection_reason));
+        JsonValue::Object(obj).json_to_string()
+    }
+
+    fn from_json(json: &str) -> Result<Self, JsonError> {
+        let obj = match JsonValue::parse(json)? {
+            JsonValue::Object(obj) => obj,
+            _ => return Err(JsonError::InvalidFormat("Expected JSON object".to_string())),
+        };
+
+        let mut changes = Vec::new();
+        for c in get_objects(&obj, "changes") {
+            let mut fields = Vec::new();
+            if let Some(JsonValue::Array(pairs)) = c.get("fields") {
+                for pair in pairs {
+                    if let JsonValue::Array(items) = pair {
+                        if let [JsonValue::String(name), JsonValue::String(value)] = items.as_slice() {
+                            fields.push((name.clone(), value.clone()));
+                        }
+                    }
+                }
+            }
+            changes.push(ProposedChange {
+                target: ChangeTarget::from_str(&get_string(c, "target")?).map_err(invalid)?,
+                entity_id: get_string(c, "entity_id")?,
+                fields,
+                description: get_optional_string(c, "description").unwrap_or_default(),
+            });
+        }
+
+        let mut impact = Vec::new();
+        for i in get_objects(&obj, "impact") {
+            impact.push(ImpactSummary {
+                entity_id: get_string(i, "entity_id")?,
+                direct_impacts: get_string_array(i, "direct_impacts"),
+                indirect_impacts: get_string_array(i, "indirect_impacts"),
+                effort_hours: i.get("effort_hours").and_then(JsonValue::as_number).unwrap_or(0.0) as u32,
+                risk_assessment: get_optional_string(i, "risk_assessment").unwrap_or_default(),
+                recommended_actions: get_string_array(i, "recommended_actions"),
+            });
+        }
+
+        let mut approvals = Vec::new();
+        for a in get_objects(&obj, "approvals") {
+            approvals.push(CcbApproval {
+                user_id: get_string(a, "user_id")?,
+                signature_id: get_string(a, "signature_id")?,
+                signed_at: get_string(a, "signed_at")?,
+                comments: get_optional_string(a, "comments"),
+            });
+        }
+
+        Ok(ChangeRequest {
+            id: get_string(&obj, "id")?,
+            title: get_string(&obj, "title")?,
+            reason: get_string(&obj, "reason")?,
+            state: ChangeState::from_str(&get_string(&obj, "state")?).map_err(invalid)?,
+            changes,
+            impact,
+            ccb_members: get_string_array(&obj, "ccb_members"),
+            approvals,
+            requested_by: get_string(&obj, "requested_by")?,
+            created_at: get_string(&obj, "created_at")?,
+            updated_at: get_string(&obj, "updated_at")?,
+            submitted_at: get_optional_string(&obj, "submitted_at"),
+            implemented_by: get_optional_string(&obj, "implemented_by"),
+            implemented_at: get_optional_string(&obj, "implemented_at"),
+            rejection_reason: get_optional_string(&obj, "rejection_reason"),
+        })
+    }
+}
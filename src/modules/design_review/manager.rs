@@ -0,0 +1,583 @@
+//! Design Review Manager
+//!
+//! Persists design reviews under `design_reviews/<id>.json` and drives the
+//! review workflow: capture the scope baseline, record attendance, minutes and
+//! action items, then collect a signature from every attendee. Action items
+//! stay open for closure after the minutes are finalized.
+
+use crate::json_utils::JsonSerializable;
+use crate::modules::audit_logger::functions::{audit_log_action, audit_log_create, audit_log_update};
+use crate::modules::audit_logger::signatures::{ElectronicSignatureManager, SignatureVerification};
+use crate::modules::document_control::service::DocumentService;
+use crate::modules::risk_manager::risk::RiskManager;
+use crate::modules::storage::StorageEntity;
+use crate::modules::traceability::requirement::RequirementManager;
+use crate::modules::storage::encryption;
+use crate::prelude::*;
+use crate::utils::encode_hex;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::record::{
+    ActionItem, Attendee, DesignReview, ReviewOutcome, ReviewPhase, ReviewScope, ReviewSignature, ReviewStatus,
+    ScopeItem, ScopeKind,
+};
+
+/// Signature policy action used for attendee sign-off on the minutes
+pub const DESIGN_REVIEW_SIGN_ACTION: &str = "design_review_signoff";
+
+/// Details for a new design review
+#[derive(Debug, Clone)]
+pub struct NewDesignReview {
+    pub title: String,
+    pub phase: ReviewPhase,
+    pub scheduled_date: Option<String>,
+}
+
+/// Design review manager
+pub struct DesignReviewManager {
+    project_path: PathBuf,
+    reviews_dir: PathBuf,
+}
+
+impl DesignReviewManager {
+    /// Create new design review manager for a project
+    pub fn new(project_path: &Path) -> QmsResult<Self> {
+        let reviews_dir = project_path.join("design_reviews");
+        fs::create_dir_all(&reviews_dir)?;
+        Ok(Self {
+            project_path: project_path.to_path_buf(),
+            reviews_dir,
+        })
+    }
+
+    /// Plan a new design review in `Draft`
+    pub fn create_review(&self, review: NewDesignReview, created_by: &str) -> QmsResult<DesignReview> {
+        if review.title.trim().is_empty() {
+            return Err(QmsError::validation_error("Design review title cannot be empty"));
+        }
+
+        let timestamp = crate::utils::current_iso8601_timestamp();
+        let record = DesignReview {
+            id: self.next_review_id()?,
+            title: review.title,
+            phase: review.phase,
+            scheduled_date: review.scheduled_date,
+            status: ReviewStatus::Draft,
+            scope: None,
+            attendees: Vec::new(),
+            minutes: String::new(),
+            outcome: None,
+            action_items: Vec::new(),
+            signatures: Vec::new(),
+            created_by: created_by.to_string(),
+            created_at: timestamp.clone(),
+            updated_at: timestamp,
+            finalized_at: None,
+            cancellation_reason: None,
+        };
+
+        self.save_review(&record)?;
+        audit_log_create("DesignReview", &record.id, &record.title)?;
+        Ok(record)
+    }
+
+    /// Load a design review
+    pub fn load_review(&self, review_id: &str) -> QmsResult<DesignReview> {
+        let path = self.review_path(review_id);
+        if !path.exists() {
+            return Err(QmsError::not_found(&format!("Design review {review_id} not found")));
+        }
+        let content = encryption::read_to_string(&path)?;
+        DesignReview::from_json(&content)
+            .map_err(|e| QmsError::parse_error(&format!("Invalid design review {review_id}: {e}")))
+    }
+
+    /// List design reviews, optionally filtered by status, ordered by ID
+    pub fn list_reviews(&self, status: Option<&ReviewStatus>) -> QmsResult<Vec<DesignReview>> {
+        let mut records = Vec::new();
+        for entry in fs::read_dir(&self.reviews_dir)? {
+            let path = entry?.path();
+            let is_record = path.extension().is_some_and(|ext| ext == "json")
+                && path.file_stem().and_then(|s| s.to_str()).is_some_and(|s| s.starts_with("DR-"));
+            if !is_record {
+                continue;
+            }
+            let content = encryption::read_to_string(&path)?;
+            let record = DesignReview::from_json(&content)
+                .map_err(|e| QmsError::parse_error(&format!("Invalid design review {}: {e}", path.display())))?;
+            if status.is_none() || status == Some(&record.status) {
+                records.push(record);
+            }
+        }
+        records.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(records)
+    }
+
+    /// Capture the scope baseline: document versions, requirements and the risk register.
+    ///
+    /// Empty `document_ids` or `requirement_ids` capture every document or
+    /// requirement. The whole risk register is always captured. Capturing again
+    /// replaces the previous baseline.
+    pub fn capture_scope(
+        &self,
+        review_id: &str,
+        document_ids: &[String],
+        requirement_ids: &[String],
+        user_id: &str,
+    ) -> QmsResult<DesignReview> {
+        let mut record = self.load_review(review_id)?;
+        Self::require_draft(&record, "The scope")?;
+
+        let mut items = Vec::new();
+        let documents = DocumentService::new(self.project_path.clone());
+        let selected: Vec<String> = if document_ids.is_empty() {
+            documents.list_documents()?.into_iter().map(|entry| entry.id).collect()
+        } else {
+            document_ids.to_vec()
+        };
+        for document_id in &selected {
+            let document = documents.read_document(document_id)?;
+            items.push(ScopeItem {
+                kind: ScopeKind::Document,
+                sha256: fingerprint(&document.content),
+                id: document.id,
+                title: document.title,
+                revision: document.version,
+            });
+        }
+
+        let requirements = RequirementManager::new(&self.project_path)?;
+        let selected = if requirement_ids.is_empty() {
+            requirements.list_requirements()
+        } else {
+            requirement_ids
+                .iter()
+                .map(|req_id| {
+                    requirements
+                        .get_requirement_by_req_id(req_id)
+                        .ok_or_else(|| QmsError::not_found(&format!("Requirement {req_id} not found")))
+                })
+                .collect::<QmsResult<Vec<_>>>()?
+        };
+        for requirement in selected {
+            items.push(ScopeItem {
+                kind: ScopeKind::Requirement,
+                id: requirement.req_id.clone(),
+                title: requirement.title.clone(),
+                revision: requirement.updated_at.clone(),
+                sha256: fingerprint(&requirement.to_json()),
+            });
+        }
+
+        for risk in RiskManager::new(&self.project_path)?.list_all_risks()? {
+            items.push(ScopeItem {
+                kind: ScopeKind::Risk,
+                id: risk.hazard_id.clone(),
+                title: risk.hazard_description.clone(),
+                revision: risk.updated_at.clone(),
+                sha256: fingerprint(&risk.to_storage_json()),
+            });
+        }
+
+        if items.is_empty() {
+            return Err(QmsError::validation_error("There are no documents, requirements or risks to review"));
+        }
+        items.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.id.cmp(&b.id)));
+        items.dedup_by(|a, b| a.kind == b.kind && a.id == b.id);
+
+        let details = format!("{review_id}|{} item(s)", items.len());
+        record.scope = Some(ReviewScope {
+            items,
+            captured_by: user_id.to_string(),
+            captured_at: crate::utils::current_iso8601_timestamp(),
+        });
+        self.touch_and_save(&mut record)?;
+
+        audit_log_action("DESIGN_REVIEW_SCOPE_CAPTURED", "DesignReview", &details)?;
+        Ok(record)
+    }
+
+    /// Add an attendee to a draft review
+    pub fn add_attendee(&self, review_id: &str, user_id: &str, role: &str, independent: bool) -> QmsResult<DesignReview> {
+        let mut record = self.load_review(review_id)?;
+        Self::require_draft(&record, "Attendees")?;
+        if user_id.trim().is_empty() {
+            return Err(QmsError::validation_error("Attendee user ID cannot be empty"));
+        }
+        if record.attendees.iter().any(|a| a.user_id == user_id) {
+            return Err(QmsError::validation_error(&format!("{user_id} is already an attendee of {review_id}")));
+        }
+
+        record.attendees.push(Attendee {
+            user_id: user_id.to_string(),
+            role: role.trim().to_string(),
+            independent,
+        });
+        self.touch_and_save(&mut record)?;
+
+        audit_log_action(
+            "DESIGN_REVIEW_ATTENDEE_ADDED",
+            "DesignReview",
+            &format!("{review_id}|{user_id}|{role}|independent={independent}"),
+        )?;
+        Ok(record)
+    }
+
+    /// Record the minutes and outcome of a draft review
+    pub fn record_minutes(
+        &self,
+        review_id: &str,
+        minutes: &str,
+        outcome: ReviewOutcome,
+        user_id: &str,
+    ) -> QmsResult<DesignReview> {
+        let mut record = self.load_review(review_id)?;
+        Self::require_draft(&record, "Minutes")?;
+        if minutes.trim().is_empty() {
+            return Err(QmsError::validation_error("Review minutes cannot be empty"));
+        }
+
+        let old_outcome = record.outcome.as_ref().map_or("None", ReviewOutcome::display_name);
+        record.minutes = minutes.to_string();
+        record.outcome = Some(outcome);
+        self.touch_and_save(&mut record)?;
+
+        audit_log_update(
+            "DesignReview",
+            review_id,
+            old_outcome,
+            &format!("Minutes recorded ({}) by {user_id}", record.outcome.as_ref().map_or("", ReviewOutcome::display_name)),
+        )?;
+        Ok(record)
+    }
+
+    /// Raise an action item on a draft review
+    pub fn add_action_item(
+        &self,
+        review_id: &str,
+        description: &str,
+        owner: &str,
+        due_date: Option<&str>,
+    ) -> QmsResult<DesignReview> {
+        let mut record = self.load_review(review_id)?;
+        Self::require_draft(&record, "Action items")?;
+        if description.trim().is_empty() {
+            return Err(QmsError::validation_error("Action item description cannot be empty"));
+        }
+        if owner.trim().is_empty() {
+            return Err(QmsError::validation_error("Action item owner cannot be empty"));
+        }
+
+        let action_id = format!("AI-{:03}", record.action_items.len() + 1);
+        record.action_items.push(ActionItem {
+            id: action_id.clone(),
+            description: description.to_string(),
+            owner: owner.to_string(),
+            due_date: due_date.map(str::to_string),
+            closed_by: None,
+            closed_at: None,
+            resolution: None,
+        });
+        self.touch_and_save(&mut record)?;
+
+        audit_log_action("DESIGN_REVIEW_ACTION_ADDED", "DesignReview", &format!("{review_id}|{action_id}|{owner}"))?;
+        Ok(record)
+    }
+
+    /// Close an action item with its resolution, before or after finalization
+    pub fn close_action_item(
+        &self,
+        review_id: &str,
+        action_id: &str,
+        resolution: &str,
+        user_id: &str,
+    ) -> QmsResult<DesignReview> {
+        if resolution.trim().is_empty() {
+            return Err(QmsError::validation_error("A resolution is required to close an action item"));
+        }
+        let mut record = self.load_review(review_id)?;
+        if record.status == ReviewStatus::Cancelled {
+            return Err(QmsError::validation_error(&format!("{review_id} is cancelled")));
+        }
+        let item = record
+            .action_items
+            .iter_mut()
+            .find(|item| item.id == action_id)
+            .ok_or_else(|| QmsError::not_found(&format!("Action item {action_id} not found on {review_id}")))?;
+        if !item.is_open() {
+            return Err(QmsError::validation_error(&format!("Action item {action_id} is already closed")));
+        }
+
+        item.closed_by = Some(user_id.to_string());
+        item.closed_at = Some(crate::utils::current_iso8601_timestamp());
+        item.resolution = Some(resolution.to_string());
+        self.touch_and_save(&mut record)?;
+
+        audit_log_action("DESIGN_REVIEW_ACTION_CLOSED", "DesignReview", &format!("{review_id}|{action_id}|{user_id}"))?;
+        Ok(record)
+    }
+
+    /// Sign the minutes as an attendee.
+    ///
+    /// The first signature freezes the review; once every attendee has signed
+    /// it moves to `Finalized`. At least one attendee must be independent.
+    pub fn sign(&self, review_id: &str, user_id: &str, password: &str, code: Option<&str>) -> QmsResult<DesignReview> {
+        let mut record = self.load_review(review_id)?;
+        if !matches!(record.status, ReviewStatus::Draft | ReviewStatus::Signing) {
+            return Err(QmsError::validation_error(&format!(
+                "{review_id} cannot be signed ({})", record.status.display_name()
+            )));
+        }
+        if record.scope.is_none() {
+            return Err(QmsError::validation_error(&format!("{review_id} has no captured scope")));
+        }
+        if record.outcome.is_none() {
+            return Err(QmsError::validation_error(&format!("{review_id} has no recorded minutes")));
+        }
+        if !record.has_independent_reviewer() {
+            return Err(QmsError::validation_error(&format!(
+                "{review_id} needs at least one independent reviewer (21 CFR 820.30(e))"
+            )));
+        }
+        if !record.attendees.iter().any(|a| a.user_id == user_id) {
+            return Err(QmsError::permission_error(&format!("{user_id} is not an attendee of {review_id}")));
+        }
+        if record.signatures.iter().any(|s| s.user_id == user_id) {
+            return Err(QmsError::validation_error(&format!("{user_id} has already signed {review_id}")));
+        }
+
+        let signature = ElectronicSignatureManager::new(self.project_path.clone()).create_signature_with_code(
+            user_id.to_string(),
+            password,
+            code,
+            DESIGN_REVIEW_SIGN_ACTION,
+            "DesignReview".to_string(),
+            review_id.to_string(),
+            &record.signing_content(),
+            None,
+        )?;
+
+        record.signatures.push(ReviewSignature {
+            user_id: user_id.to_string(),
+            signature_id: signature.id.clone(),
+            signed_at: signature.timestamp.clone(),
+        });
+        audit_log_action("DESIGN_REVIEW_SIGNED", "DesignReview", &format!("{review_id}|{user_id}|{}", signature.id))?;
+
+        let target = if record.outstanding_signers().is_empty() {
+            record.finalized_at = Some(crate::utils::current_iso8601_timestamp());
+            ReviewStatus::Finalized
+        } else {
+            ReviewStatus::Signing
+        };
+        if record.status == target {
+            self.touch_and_save(&mut record)?;
+        } else {
+            self.transition(&mut record, target, user_id)?;
+        }
+        Ok(record)
+    }
+
+    /// Verify every attendee signature still matches the stored review
+    pub fn verify_signatures(&self, review_id: &str) -> QmsResult<Vec<(String, SignatureVerification)>> {
+        let record = self.load_review(review_id)?;
+        let signature_manager = ElectronicSignatureManager::new(self.project_path.clone());
+        let content = record.signing_content();
+        record.signatures.iter().map(|signature| {
+            let verification = signature_manager.verify_signature_for_record(&signature.signature_id, &content)?;
+            Ok((signature.user_id.clone(), verification))
+        }).collect()
+    }
+
+    /// Cancel a review that has not been finalized
+    pub fn cancel(&self, review_id: &str, reason: &str, user_id: &str) -> QmsResult<DesignReview> {
+        if reason.trim().is_empty() {
+            return Err(QmsError::validation_error("A cancellation reason is required"));
+        }
+        let mut record = self.load_review(review_id)?;
+        if !matches!(record.status, ReviewStatus::Draft | ReviewStatus::Signing) {
+            return Err(QmsError::validation_error(&format!(
+                "{review_id} cannot be cancelled ({})", record.status.display_name()
+            )));
+        }
+        record.cancellation_reason = Some(reason.to_string());
+        self.transition(&mut record, ReviewStatus::Cancelled, user_id)?;
+        Ok(record)
+    }
+
+    fn require_draft(record: &DesignReview, what: &str) -> QmsResult<()> {
+        if record.status != ReviewStatus::Draft {
+            return Err(QmsError::validation_error(&format!(
+                "{what} can only be changed while {} is a draft ({})",
+                record.id,
+                record.status.display_name()
+            )));
+        }
+        Ok(())
+    }
+
+    fn transition(&self, record: &mut DesignReview, target: ReviewStatus, actor_id: &str) -> QmsResult<()> {
+        let old_status = record.status.display_name();
+        record.status = target;
+        self.touch_and_save(record)?;
+
+        audit_log_update(
+            "DesignReview",
+            &record.id,
+            old_status,
+            &format!("{} by {actor_id}", record.status.display_name()),
+        )?;
+        Ok(())
+    }
+
+    fn review_path(&self, review_id: &str) -> PathBuf {
+        self.reviews_dir.join(format!("{review_id}.json"))
+    }
+
+    fn next_review_id(&self) -> QmsResult<String> {
+        let mut max = 0u32;
+        for entry in fs::read_dir(&self.reviews_dir)? {
+            let name = entry?.file_name().to_string_lossy().to_string();
+            if let Some(number) = name.strip_prefix("DR-").and_then(|n| n.strip_suffix(".json")) {
+                max = max.max(number.parse().unwrap_or(0));
+            }
+        }
+        Ok(format!("DR-{:03}", max + 1))
+    }
+
+    fn touch_and_save(&self, record: &mut DesignReview) -> QmsResult<()> {
+        record.updated_at = crate::utils::current_iso8601_timestamp();
+        self.save_review(record)
+    }
+
+    fn save_review(&self, record: &DesignReview) -> QmsResult<()> {
+        encryption::atomic_write(&self.review_path(&record.id), &record.to_json())
+    }
+}
+
+/// SHA-256 of document content or a record's canonical JSON
+fn fingerprint(content: &str) -> String {
+    encode_hex(&Sha256::digest(content.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::audit_logger::{initialize_audit_system, AuditConfig};
+    use crate::modules::document_control::document::DocumentType;
+    use crate::modules::traceability::requirement::RequirementCategory;
+    use crate::modules::user_manager::signing_keys::SigningKeyStore;
+
+    fn setup() -> (tempfile::TempDir, DesignReviewManager) {
+        let dir = tempfile::tempdir().unwrap();
+        let _ = initialize_audit_system(AuditConfig {
+            project_path: dir.path().to_string_lossy().to_string(),
+            retention_days: 30,
+            daily_rotation: false,
+            max_file_size_mb: 10,
+            require_checksums: false,
+        });
+        for user in ["alice", "bob"] {
+            SigningKeyStore::new(dir.path()).generate_key_pair(user, "password123").unwrap();
+        }
+
+        RequirementManager::new(dir.path()).unwrap().create_requirement(
+            "project-001".to_string(),
+            "REQ-001".to_string(),
+            "Flow accuracy".to_string(),
+            "Deliver within 5% of set rate".to_string(),
+            RequirementCategory::Functional,
+            "alice".to_string(),
+        ).unwrap();
+        let mut risk_manager = RiskManager::new(dir.path()).unwrap();
+        risk_manager.initialize().unwrap();
+        risk_manager.create_risk("Over-infusion", "Rate drift", "Overdose").unwrap();
+        DocumentService::new(dir.path().to_path_buf()).create_document(
+            "Pump SRS".to_string(),
+            "Flow accuracy 5%".to_string(),
+            DocumentType::SoftwareRequirementsSpecification,
+            "alice".to_string(),
+        ).unwrap();
+
+        let manager = DesignReviewManager::new(dir.path()).unwrap();
+        (dir, manager)
+    }
+
+    fn prepared_review(manager: &DesignReviewManager) -> DesignReview {
+        let review = manager.create_review(
+            NewDesignReview {
+                title: "Design output review".to_string(),
+                phase: ReviewPhase::DesignOutput,
+                scheduled_date: Some("2025-03-01".to_string()),
+            },
+            "alice",
+        ).unwrap();
+        manager.capture_scope(&review.id, &[], &[], "alice").unwrap();
+        manager.add_attendee(&review.id, "alice", "Design Lead", false).unwrap();
+        manager.record_minutes(&review.id, "Outputs meet inputs", ReviewOutcome::ApprovedWithActions, "alice").unwrap();
+        manager.add_action_item(&review.id, "Update alarm thresholds", "alice", Some("2025-04-01")).unwrap()
+    }
+
+    #[test]
+    fn test_scope_captures_documents_requirements_and_risks() {
+        let (_dir, manager) = setup();
+        let review = prepared_review(&manager);
+        assert_eq!(review.id, "DR-001");
+
+        let scope = review.scope.as_ref().unwrap();
+        assert_eq!(scope.items_of(ScopeKind::Document).count(), 1);
+        assert_eq!(scope.items_of(ScopeKind::Requirement).next().unwrap().id, "REQ-001");
+        assert_eq!(scope.items_of(ScopeKind::Risk).count(), 1);
+        assert!(scope.items.iter().all(|item| item.sha256.len() == 64));
+        assert!(manager.capture_scope(&review.id, &[], &["REQ-999".to_string()], "alice").is_err());
+
+        let reloaded = manager.load_review(&review.id).unwrap();
+        assert_eq!(reloaded, review);
+        assert_eq!(manager.list_reviews(Some(&ReviewStatus::Draft)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_finalization_requires_independent_reviewer_and_every_signature() {
+        let (_dir, manager) = setup();
+        let review = prepared_review(&manager);
+
+        assert!(manager.sign(&review.id, "alice", "password123", None).is_err());
+        manager.add_attendee(&review.id, "bob", "Quality", true).unwrap();
+        assert!(manager.add_attendee(&review.id, "bob", "Quality", true).is_err());
+        assert!(manager.sign(&review.id, "carol", "password123", None).is_err());
+
+        let signing = manager.sign(&review.id, "alice", "password123", None).unwrap();
+        assert_eq!(signing.status, ReviewStatus::Signing);
+        assert_eq!(signing.outstanding_signers(), vec!["bob"]);
+        assert!(manager.add_action_item(&review.id, "Late item", "bob", None).is_err());
+
+        let finalized = manager.sign(&review.id, "bob", "password123", None).unwrap();
+        assert_eq!(finalized.status, ReviewStatus::Finalized);
+        assert!(finalized.finalized_at.is_some());
+        assert!(manager.cancel(&review.id, "Too late", "alice").is_err());
+        assert!(manager.verify_signatures(&review.id).unwrap().iter().all(|(_, v)| v.is_valid));
+    }
+
+    #[test]
+    fn test_action_items_close_after_finalization_without_breaking_signatures() {
+        let (dir, manager) = setup();
+        let review = prepared_review(&manager);
+        manager.add_attendee(&review.id, "bob", "Quality", true).unwrap();
+        manager.sign(&review.id, "alice", "password123", None).unwrap();
+        manager.sign(&review.id, "bob", "password123", None).unwrap();
+
+        assert!(manager.close_action_item(&review.id, "AI-001", "", "alice").is_err());
+        let closed = manager.close_action_item(&review.id, "AI-001", "Thresholds updated", "alice").unwrap();
+        assert!(closed.open_action_items().is_empty());
+        assert!(manager.close_action_item(&review.id, "AI-001", "Again", "alice").is_err());
+        assert!(manager.verify_signatures(&review.id).unwrap().iter().all(|(_, v)| v.is_valid));
+
+        // Editing the minutes after sign-off invalidates the signatures
+        let path = dir.path().join("design_reviews").join(format!("{}.json", review.id));
+        let tampered = fs::read_to_string(&path).unwrap().replace("Outputs meet inputs", "Outputs exceed inputs");
+        fs::write(&path, tampered).unwrap();
+        assert!(manager.verify_signatures(&review.id).unwrap().iter().all(|(_, v)| !v.is_valid));
+    }
+}
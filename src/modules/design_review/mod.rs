@@ -0,0 +1,23 @@
+//! Design Review Module
+//!
+//! Formal design reviews per FDA 21 CFR 820.30(e) and ISO 13485:2016 Section
+//! 7.3.5: each review baselines the document versions, requirements and risk
+//! register it covers, records attendees including at least one independent
+//! reviewer, tracks action items to closure and is finalized when every
+//! attendee has signed the minutes.
+
+pub mod manager;
+pub mod record;
+
+#[allow(unused_imports)]
+pub use manager::{DesignReviewManager, NewDesignReview, DESIGN_REVIEW_SIGN_ACTION};
+#[allow(unused_imports)]
+pub use record::{
+    ActionItem, Attendee, DesignReview, ReviewOutcome, ReviewPhase, ReviewScope, ReviewSignature, ReviewStatus,
+    ScopeItem, ScopeKind,
+};
+
+/// Whether any design review has been created in the project
+pub fn has_reviews(project_path: &std::path::Path) -> bool {
+    project_path.join("design_reviews").is_dir()
+}
@@ -0,0 +1,440 @@
+//! Design Review Model
+//!
+//! Formal design review records per FDA 21 CFR 820.30(e): the reviewed scope,
+//! attendees and their independence, minutes, action items and the attendees'
+//! signatures on the finalized minutes.
+
+use crate::json_utils::{JsonError, JsonSerializable, JsonValue};
+use crate::prelude::*;
+use std::collections::HashMap;
+
+/// Design review lifecycle state
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReviewStatus {
+    Draft,     // Scope, attendees and minutes being prepared
+    Signing,   // Minutes frozen, attendee signatures being collected
+    Finalized, // Every attendee has signed
+    Cancelled, // Review abandoned with a reason
+}
+
+impl ReviewStatus {
+    pub fn parse(s: &str) -> QmsResult<Self> {
+        match s.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "draft" => Ok(ReviewStatus::Draft),
+            "signing" | "awaitingsignatures" => Ok(ReviewStatus::Signing),
+            "finalized" | "finalised" => Ok(ReviewStatus::Finalized),
+            "cancelled" | "canceled" => Ok(ReviewStatus::Cancelled),
+            _ => Err(QmsError::validation_error(&format!("Unknown design review status: {s}"))),
+        }
+    }
+
+    pub const fn display_name(&self) -> &'static str {
+        match self {
+            ReviewStatus::Draft => "Draft",
+            ReviewStatus::Signing => "Awaiting Signatures",
+            ReviewStatus::Finalized => "Finalized",
+            ReviewStatus::Cancelled => "Cancelled",
+        }
+    }
+}
+
+/// Design stage the review is held at
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReviewPhase {
+    DesignInput,
+    DesignOutput,
+    Verification,
+    Validation,
+    Transfer,
+    Other(String),
+}
+
+impl ReviewPhase {
+    pub fn parse(s: &str) -> QmsResult<Self> {
+        match s.trim().to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "designinput" | "input" => Ok(ReviewPhase::DesignInput),
+            "designoutput" | "output" => Ok(ReviewPhase::DesignOutput),
+            "verification" => Ok(ReviewPhase::Verification),
+            "validation" => Ok(ReviewPhase::Validation),
+            "transfer" | "designtransfer" => Ok(ReviewPhase::Transfer),
+            "" => Err(QmsError::validation_error("Design review phase cannot be empty")),
+            _ => Ok(ReviewPhase::Other(s.trim().to_string())),
+        }
+    }
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            ReviewPhase::DesignInput => "Design Input",
+            ReviewPhase::DesignOutput => "Design Output",
+            ReviewPhase::Verification => "Verification",
+            ReviewPhase::Validation => "Validation",
+            ReviewPhase::Transfer => "Design Transfer",
+            ReviewPhase::Other(name) => name,
+        }
+    }
+}
+
+/// Conclusion recorded with the minutes
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReviewOutcome {
+    Approved,
+    ApprovedWithActions,
+    NotApproved,
+}
+
+impl ReviewOutcome {
+    pub fn parse(s: &str) -> QmsResult<Self> {
+        match s.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "approved" | "pass" => Ok(ReviewOutcome::Approved),
+            "approvedwithactions" | "conditional" => Ok(ReviewOutcome::ApprovedWithActions),
+            "notapproved" | "rejected" | "fail" => Ok(ReviewOutcome::NotApproved),
+            _ => Err(QmsError::validation_error(&format!(
+                "Invalid review outcome: {s} (expected approved, approved-with-actions or not-approved)"
+            ))),
+        }
+    }
+
+    pub const fn display_name(&self) -> &'static str {
+        match self {
+            ReviewOutcome::Approved => "Approved",
+            ReviewOutcome::ApprovedWithActions => "Approved with Actions",
+            ReviewOutcome::NotApproved => "Not Approved",
+        }
+    }
+}
+
+/// Kind of record captured in the review scope
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ScopeKind {
+    Document,
+    Requirement,
+    Risk,
+}
+
+impl ScopeKind {
+    pub fn parse(s: &str) -> QmsResult<Self> {
+        match s.to_lowercase().as_str() {
+            "document" => Ok(ScopeKind::Document),
+            "requirement" => Ok(ScopeKind::Requirement),
+            "risk" => Ok(ScopeKind::Risk),
+            _ => Err(QmsError::validation_error(&format!("Unknown scope item kind: {s}"))),
+        }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            ScopeKind::Document => "document",
+            ScopeKind::Requirement => "requirement",
+            ScopeKind::Risk => "risk",
+        }
+    }
+}
+
+/// One record as it stood when the scope was captured
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScopeItem {
+    pub kind: ScopeKind,
+    pub id: String,       // Document ID, requirement ID (REQ-001) or hazard ID
+    pub title: String,
+    pub revision: String, // Document version, or last update time for requirements and risks
+    pub sha256: String,   // Fingerprint of the record content
+}
+
+/// Baseline of the design record under review
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewScope {
+    pub items: Vec<ScopeItem>,
+    pub captured_by: String,
+    pub captured_at: String,
+}
+
+impl ReviewScope {
+    /// Items of one kind
+    pub fn items_of(&self, kind: ScopeKind) -> impl Iterator<Item = &ScopeItem> {
+        self.items.iter().filter(move |item| item.kind == kind)
+    }
+}
+
+/// Review participant
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attendee {
+    pub user_id: String,
+    pub role: String,      // e.g. Design Lead, Quality, Clinical
+    pub independent: bool, // No direct responsibility for the design stage under review
+}
+
+/// Action item raised in the review, tracked to closure
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionItem {
+    pub id: String, // AI-001, AI-002...
+    pub description: String,
+    pub owner: String,
+    pub due_date: Option<String>,
+    pub closed_by: Option<String>,
+    pub closed_at: Option<String>,
+    pub resolution: Option<String>,
+}
+
+impl ActionItem {
+    pub const fn is_open(&self) -> bool {
+        self.closed_at.is_none()
+    }
+}
+
+/// Attendee's signature on the minutes
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewSignature {
+    pub user_id: String,
+    pub signature_id: String,
+    pub signed_at: String,
+}
+
+/// Formal design review
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesignReview {
+    pub id: String, // DR-001, DR-002...
+    pub title: String,
+    pub phase: ReviewPhase,
+    pub scheduled_date: Option<String>,
+    pub status: ReviewStatus,
+    pub scope: Option<ReviewScope>,
+    pub attendees: Vec<Attendee>,
+    pub minutes: String,
+    pub outcome: Option<ReviewOutcome>,
+    pub action_items: Vec<ActionItem>,
+    pub signatures: Vec<ReviewSignature>,
+    pub created_by: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub finalized_at: Option<String>,
+    pub cancellation_reason: Option<String>,
+}
+
+impl DesignReview {
+    /// Deterministic byte representation bound by each attendee signature.
+    ///
+    /// Covers the scope fingerprints, attendance, minutes and the action items
+    /// raised. Action item closure is tracked after signing and is left out.
+    pub fn signing_content(&self) -> Vec<u8> {
+        let mut content = String::from("qms-design-review-v1\n");
+        content.push_str(&format!("id={}\n", self.id));
+        content.push_str(&format!("title={}\n", self.title));
+        content.push_str(&format!("phase={}\n", self.phase.display_name()));
+        if let Some(ref scope) = self.scope {
+            for item in &scope.items {
+                content.push_str(&format!("scope={}|{}|{}|{}\n", item.kind.name(), item.id, item.revision, item.sha256));
+            }
+        }
+        for attendee in &self.attendees {
+            content.push_str(&format!("attendee={}|{}|{}\n", attendee.user_id, attendee.role, attendee.independent));
+        }
+        content.push_str(&format!("outcome={}\n", self.outcome.as_ref().map_or("", ReviewOutcome::display_name)));
+        content.push_str(&format!("minutes={}\n", self.minutes));
+        for item in &self.action_items {
+            content.push_str(&format!("action={}|{}|{}\n", item.id, item.owner, item.description));
+        }
+        content.into_bytes()
+    }
+
+    /// Attendees who have not yet signed
+    pub fn outstanding_signers(&self) -> Vec<&str> {
+        self.attendees
+            .iter()
+            .filter(|attendee| !self.signatures.iter().any(|s| s.user_id == attendee.user_id))
+            .map(|attendee| attendee.user_id.as_str())
+            .collect()
+    }
+
+    /// Whether at least one attendee is independent of the design stage
+    pub fn has_independent_reviewer(&self) -> bool {
+        self.attendees.iter().any(|attendee| attendee.independent)
+    }
+
+    /// Action items not yet closed
+    pub fn open_action_items(&self) -> Vec<&ActionItem> {
+        self.action_items.iter().filter(|item| item.is_open()).collect()
+    }
+}
+
+fn optional_string(value: &Option<String>) -> JsonValue {
+    value.as_ref().map_or(JsonValue::Null, |s| JsonValue::String(s.clone()))
+}
+
+fn get_string(obj: &HashMap<String, JsonValue>, field: &str) -> Result<String, JsonError> {
+    match obj.get(field) {
+        Some(JsonValue::String(s)) => Ok(s.clone()),
+        Some(_) => Err(JsonError::InvalidFormat(format!("Field '{field}' is not a string"))),
+        None => Err(JsonError::InvalidFormat(format!("Missing required field '{field}'"))),
+    }
+}
+
+fn get_optional_string(obj: &HashMap<String, JsonValue>, field: &str) -> Option<String> {
+    obj.get(field).and_then(|v| v.as_string().cloned())
+}
+
+fn get_objects<'a>(obj: &'a HashMap<String, JsonValue>, field: &str) -> Vec<&'a HashMap<String, JsonValue>> {
+    match obj.get(field) {
+        Some(JsonValue::Array(items)) => items
+            .iter()
+            .filter_map(|item| match item {
+                JsonValue::Object(o) => Some(o),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn invalid(err: QmsError) -> JsonError {
+    JsonError::InvalidFormat(err.to_string())
+}
+
+impl JsonSerializable for DesignReview {
+    fn to_json(&self) -> String {
+        let scope = self.scope.as_ref().map_or(JsonValue::Null, |scope| {
+            let items = scope.items.iter().map(|item| {
+                let mut obj = HashMap::new();
+                obj.insert("kind".to_string(), JsonValue::String(item.kind.name().to_string()));
+                obj.insert("id".to_string(), JsonValue::String(item.id.clone()));
+                obj.insert("title".to_string(), JsonValue::String(item.title.clone()));
+                obj.insert("revision".to_string(), JsonValue::String(item.revision.clone()));
+                obj.insert("sha256".to_string(), JsonValue::String(item.sha256.clone()));
+                JsonValue::Object(obj)
+            }).collect();
+            let mut obj = HashMap::new();
+            obj.insert("items".to_string(), JsonValue::Array(items));
+            obj.insert("captured_by".to_string(), JsonValue::String(scope.captured_by.clone()));
+            obj.insert("captured_at".to_string(), JsonValue::String(scope.captured_at.clone()));
+            JsonValue::Object(obj)
+        });
+
+        let attendees = self.attendees.iter().map(|a| {
+            let mut obj = HashMap::new();
+            obj.insert("user_id".to_string(), JsonValue::String(a.user_id.clone()));
+            obj.insert("role".to_string(), JsonValue::String(a.role.clone()));
+            obj.insert("independent".to_string(), JsonValue::Bool(a.independent));
+            JsonValue::Object(obj)
+        }).collect();
+
+        let action_items = self.action_items.iter().map(|item| {
+            let mut obj = HashMap::new();
+            obj.insert("id".to_string(), JsonValue::String(item.id.clone()));
+            obj.insert("description".to_string(), JsonValue::String(item.description.clone()));
+            obj.insert("owner".to_string(), JsonValue::String(item.owner.clone()));
+            obj.insert("due_date".to_string(), optional_string(&item.due_date));
+            obj.insert("closed_by".to_string(), optional_string(&item.closed_by));
+            obj.insert("closed_at".to_string(), optional_string(&item.closed_at));
+            obj.insert("resolution".to_string(), optional_string(&item.resolution));
+            JsonValue::Object(obj)
+        }).collect();
+
+        let signatures = self.signatures.iter().map(|s| {
+            let mut obj = HashMap::new();
+            obj.insert("user_id".to_string(), JsonValue::String(s.user_id.clone()));
+            obj.insert("signature_id".to_string(), JsonValue::String(s.signature_id.clone()));
+            obj.insert("signed_at".to_string(), JsonValue::String(s.signed_at.clone()));
+            JsonValue::Object(obj)
+        }).collect();
+
+        let mut obj = HashMap::new();
+        obj.insert("version".to_string(), JsonValue::String("1.0".to_string()));
+        obj.insert("id".to_string(), JsonValue::String(self.id.clone()));
+        obj.insert("title".to_string(), JsonValue::String(self.title.clone()));
+        obj.insert("phase".to_string(), JsonValue::String(self.phase.display_name().to_string()));
+        obj.insert("scheduled_date".to_string(), optional_string(&self.scheduled_date));
+        obj.insert("status".to_string(), JsonValue::String(self.status.display_name().to_string()));
+        obj.insert("scope".to_string(), scope);
+        obj.insert("attendees".to_string(), JsonValue::Array(attendees));
+        obj.insert("minutes".to_string(), JsonValue::String(self.minutes.clone()));
+        obj.insert(
+            "outcome".to_string(),
+            self.outcome.as_ref().map_or(JsonValue::Null, |o| JsonValue::String(o.display_name().to_string())),
+        );
+        obj.insert("action_items".to_string(), JsonValue::Array(action_items));
+        obj.insert("signatures".to_string(), JsonValue::Array(signatures));
+        obj.insert("created_by".to_string(), JsonValue::String(self.created_by.clone()));
+        obj.insert("created_at".to_string(), JsonValue::String(self.created_at.clone()));
+        obj.insert("updated_at".to_string(), JsonValue::String(self.updated_at.clone()));
+        obj.insert("finalized_at".to_string(), optional_string(&self.finalized_at));
+        obj.insert("cancellation_reason".to_string(), optional_string(&self.cancellation_reason));
+        JsonValue::Object(obj).json_to_string()
+    }
+
+    fn from_json(json: &str) -> Result<Self, JsonError> {
+        let obj = match JsonValue::parse(json)? {
+            JsonValue::Object(obj) => obj,
+            _ => return Err(JsonError::InvalidFormat("Expected JSON object".to_string())),
+        };
+
+        let scope = match obj.get("scope") {
+            Some(JsonValue::Object(scope)) => {
+                let mut items = Vec::new();
+                for item in get_objects(scope, "items") {
+                    items.push(ScopeItem {
+                        kind: ScopeKind::parse(&get_string(item, "kind")?).map_err(invalid)?,
+                        id: get_string(item, "id")?,
+                        title: get_optional_string(item, "title").unwrap_or_default(),
+                        revision: get_optional_string(item, "revision").unwrap_or_default(),
+                        sha256: get_string(item, "sha256")?,
+                    });
+                }
+                Some(ReviewScope {
+                    items,
+                    captured_by: get_string(scope, "captured_by")?,
+                    captured_at: get_string(scope, "captured_at")?,
+                })
+            }
+            _ => None,
+        };
+
+        let mut attendees = Vec::new();
+        for a in get_objects(&obj, "attendees") {
+            attendees.push(Attendee {
+                user_id: get_string(a, "user_id")?,
+                role: get_optional_string(a, "role").unwrap_or_default(),
+                independent: matches!(a.get("independent"), Some(JsonValue::Bool(true))),
+            });
+        }
+
+        let mut action_items = Vec::new();
+        for item in get_objects(&obj, "action_items") {
+            action_items.push(ActionItem {
+                id: get_string(item, "id")?,
+                description: get_string(item, "description")?,
+                owner: get_string(item, "owner")?,
+                due_date: get_optional_string(item, "due_date"),
+                closed_by: get_optional_string(item, "closed_by"),
+                closed_at: get_optional_string(item, "closed_at"),
+                resolution: get_optional_string(item, "resolution"),
+            });
+        }
+
+        let mut signatures = Vec::new();
+        for s in get_objects(&obj, "signatures") {
+            signatures.push(ReviewSignature {
+                user_id: get_string(s, "user_id")?,
+                signature_id: get_string(s, "signature_id")?,
+                signed_at: get_string(s, "signed_at")?,
+            });
+        }
+
+        Ok(DesignReview {
+            id: get_string(&obj, "id")?,
+            title: get_string(&obj, "title")?,
+            phase: ReviewPhase::parse(&get_string(&obj, "phase")?).map_err(invalid)?,
+            scheduled_date: get_optional_string(&obj, "scheduled_date"),
+            status: ReviewStatus::parse(&get_string(&obj, "status")?).map_err(invalid)?,
+            scope,
+            attendees,
+            minutes: get_optional_string(&obj, "minutes").unwrap_or_default(),
+            outcome: get_optional_string(&obj, "outcome").map(|o| ReviewOutcome::parse(&o)).transpose().map_err(invalid)?,
+            action_items,
+            signatures,
+            created_by: get_string(&obj, "created_by")?,
+            created_at: get_string(&obj, "created_at")?,
+            updated_at: get_string(&obj, "updated_at")?,
+            finalized_at: get_optional_string(&obj, "finalized_at"),
+            cancellation_reason: get_optional_string(&obj, "cancellation_reason"),
+        })
+    }
+}
@@ -121,6 +121,14 @@ impl ApprovalWorkflow {
         })
     }
 
+    /// Create approval workflow manager for an explicit project directory
+    pub fn for_project(project_path: &std::path::Path) -> Self {
+        ApprovalWorkflow {
+            document_service: DocumentService::new(project_path.to_path_buf()),
+            project_path: project_path.to_string_lossy().to_string(),
+        }
+    }
+
     /// Submit document for review
     pub fn submit_for_review(&mut self, doc_id: &str, submitter_id: &str, submitter_name: &str, comments: Option<&str>) -> QmsResult<()> {
         let document = self.document_service.read_document(doc_id)?;
@@ -266,7 +274,7 @@ impl ApprovalWorkflow {
             return Ok(Vec::new());
         }
 
-        let content = fs::read_to_string(&workflow_file)
+        let content = crate::modules::storage::encryption::read_to_string(&workflow_file)
             .map_err(|e| QmsError::io_error(&format!("Failed to read workflow history: {e}")))?;
 
         let mut entries = Vec::new();
@@ -364,7 +372,7 @@ impl ApprovalWorkflow {
                 .map_err(|e| QmsError::io_error(&format!("Failed to create workflow directory: {e}")))?;
         }
 
-        let entry_line = self.serialize_workflow_entry(entry);
+        let entry_line = crate::modules::storage::encryption::seal(&workflow_file, &self.serialize_workflow_entry(entry))?;
         
         // Append to workflow file
         use std::io::Write;
@@ -477,6 +485,7 @@ impl ApprovalWorkflow {
         );
 
         let audit_file = std::path::Path::new(&self.project_path).join("audit.log");
+        let audit_entry = crate::modules::storage::encryption::seal(&audit_file, &audit_entry)?;
         use std::io::Write;
         let mut file = fs::OpenOptions::new()
             .create(true)
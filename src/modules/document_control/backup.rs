@@ -3,9 +3,11 @@
 //! Implements automatic backup on every save operation with checksum verification
 
 use crate::error::{QmsError, QmsResult};
-use crate::fs_utils::atomic_write;
+use crate::modules::storage::encryption::atomic_write;
 use crate::json_utils::{JsonSerializable, JsonValue, JsonError};
+use crate::modules::attachments::{self, AttachmentManager, AttachmentTarget, BackedUpAttachment, BlobStore};
 use crate::modules::audit_logger::audit_log_action;
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -104,7 +106,6 @@ impl JsonSerializable for BackupMetadata {
 }
 
 /// Document backup manager for automatic backup and recovery operations
-#[allow(dead_code)] // project_path may be used for future backup organization features
 pub struct DocumentBackupManager {
     project_path: PathBuf,
     backup_root: PathBuf,
@@ -187,17 +188,22 @@ impl DocumentBackupManager {
             backup_reason: reason.to_string(),
         };
 
+        // Keep a copy of the document's attachments alongside the backup
+        let attachments = self.back_up_attachments(document_id)?;
+
         // Create backup document content
         let escaped_content = content.replace('"', "\\\"").replace('\n', "\\n").replace('\r', "\\r");
         let backup_content = format!(
             r#"{{
     "backup_metadata": {},
     "document_content": "{}",
+    "attachments": {},
     "backup_timestamp": {},
     "checksum": "{}"
 }}"#,
             metadata.to_json(),
             escaped_content,
+            attachments_json(&attachments),
             timestamp.as_millis() as u64,
             checksum
         );
@@ -229,11 +235,12 @@ impl DocumentBackupManager {
         }
 
         // Read backup content
-        let backup_content = fs::read_to_string(backup_path)
+        let backup_content = crate::modules::storage::encryption::read_to_string(backup_path)
             .map_err(QmsError::Io)?;
 
         // Parse backup content to extract document content
         let backup_json = crate::json_utils::JsonValue::parse(&backup_content)?;
+        let attachment_hashes = backup_attachment_hashes(&backup_json);
         let document_content = if let crate::json_utils::JsonValue::Object(obj) = backup_json {
             match obj.get("document_content") {
                 Some(crate::json_utils::JsonValue::String(s)) => s.clone(),
@@ -243,6 +250,13 @@ impl DocumentBackupManager {
             return Err(QmsError::parse_error("Invalid backup format: expected JSON object"));
         };
 
+        // Every attachment copy must still match its fingerprint
+        let attachment_store = BlobStore::at(&self.attachment_backup_root());
+        if attachment_hashes.iter().any(|hash| attachment_store.get(hash).is_err()) {
+            audit_log_action("BACKUP_CORRUPTION_DETECTED", "DocumentBackup", &metadata.backup_id)?;
+            return Ok(false);
+        }
+
         // Calculate checksum of extracted content
         let calculated_checksum = calculate_content_checksum(&document_content);
 
@@ -279,11 +293,12 @@ impl DocumentBackupManager {
 
         // Read backup content
         let backup_path = Path::new(&metadata.backup_path);
-        let backup_content = fs::read_to_string(backup_path)
+        let backup_content = crate::modules::storage::encryption::read_to_string(backup_path)
             .map_err(QmsError::Io)?;
 
         // Parse backup to extract document content
         let backup_json = crate::json_utils::JsonValue::parse(&backup_content)?;
+        let attachment_hashes = backup_attachment_hashes(&backup_json);
         let document_content = if let crate::json_utils::JsonValue::Object(obj) = backup_json {
             match obj.get("document_content") {
                 Some(crate::json_utils::JsonValue::String(s)) => s.clone(),
@@ -296,6 +311,11 @@ impl DocumentBackupManager {
         // Write recovered content to target path
         atomic_write(recovery_path, &document_content)?;
 
+        // Put back attachment files that are missing or damaged in the project
+        if !attachment_hashes.is_empty() {
+            AttachmentManager::new(&self.project_path)?.restore(&attachment_hashes, &self.attachment_backup_root())?;
+        }
+
         audit_log_action("DOCUMENT_RECOVERED", "DocumentBackup", backup_id)?;
 
         Ok(document_content)
@@ -345,7 +365,7 @@ impl DocumentBackupManager {
             return Err(QmsError::NotFound(format!("Backup metadata not found: {backup_id}")));
         }
 
-        let metadata_content = fs::read_to_string(&metadata_path)
+        let metadata_content = crate::modules::storage::encryption::read_to_string(&metadata_path)
             .map_err(QmsError::Io)?;
 
         BackupMetadata::from_json(&metadata_content)
@@ -407,6 +427,23 @@ impl DocumentBackupManager {
         Ok(deleted_count)
     }
 
+    /// Object store holding the attachment copies of every document backup
+    fn attachment_backup_root(&self) -> PathBuf {
+        self.backup_root.join("attachments").join("objects")
+    }
+
+    /// Copy every version of the document's attachments into the backup object store
+    fn back_up_attachments(&self, document_id: &str) -> QmsResult<Vec<BackedUpAttachment>> {
+        if !attachments::has_attachments(&self.project_path) {
+            return Ok(Vec::new());
+        }
+        AttachmentManager::new(&self.project_path)?.back_up(
+            AttachmentTarget::Document,
+            document_id,
+            &self.attachment_backup_root(),
+        )
+    }
+
     /// Add backup to index
     fn add_to_backup_index(&self, metadata: &BackupMetadata) -> QmsResult<()> {
         let mut index = self.load_backup_index()?;
@@ -434,7 +471,7 @@ impl DocumentBackupManager {
             return Ok(Vec::new());
         }
 
-        let index_content = fs::read_to_string(&index_path)
+        let index_content = crate::modules::storage::encryption::read_to_string(&index_path)
             .map_err(QmsError::Io)?;
 
         let index_json = crate::json_utils::JsonValue::parse(&index_content)?;
@@ -473,6 +510,39 @@ impl DocumentBackupManager {
     }
 }
 
+/// JSON array listing the attachment versions copied with a backup
+fn attachments_json(attachments: &[BackedUpAttachment]) -> String {
+    let entries = attachments
+        .iter()
+        .map(|attachment| {
+            let mut obj = HashMap::new();
+            obj.insert("id".to_string(), JsonValue::String(attachment.id.clone()));
+            obj.insert("name".to_string(), JsonValue::String(attachment.name.clone()));
+            obj.insert("version".to_string(), JsonValue::Number(f64::from(attachment.version)));
+            obj.insert("sha256".to_string(), JsonValue::String(attachment.sha256.clone()));
+            JsonValue::Object(obj)
+        })
+        .collect();
+    JsonValue::Array(entries).json_to_string()
+}
+
+/// Fingerprints of the attachment copies listed in a backup (none in older backups)
+fn backup_attachment_hashes(backup_json: &JsonValue) -> Vec<String> {
+    let JsonValue::Object(obj) = backup_json else {
+        return Vec::new();
+    };
+    match obj.get("attachments") {
+        Some(JsonValue::Array(entries)) => entries
+            .iter()
+            .filter_map(|entry| match entry {
+                JsonValue::Object(fields) => fields.get("sha256").and_then(|hash| hash.as_string().cloned()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
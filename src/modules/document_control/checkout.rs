@@ -44,13 +44,13 @@ pub struct LockStatistics {
 
 /// Helper function to read file content
 fn read_file_content(path: &str) -> QmsResult<String> {
-    fs::read_to_string(path)
+    crate::modules::storage::encryption::read_to_string(path)
         .map_err(|e| QmsError::io_error(&format!("Failed to read file {path}: {e}")))
 }
 
 /// Helper function to write file content
 fn write_file_content(path: &str, content: &str) -> QmsResult<()> {
-    fs::write(path, content)
+    crate::modules::storage::encryption::write(path, content)
         .map_err(|e| QmsError::io_error(&format!("Failed to write file {path}: {e}")))
 }
 
@@ -0,0 +1,381 @@
+//! Content-addressed blob store for document versions
+//!
+//! Version snapshots keep their document text in `documents/objects/`, one
+//! file per chunk named by the SHA-256 of its contents (`objects/ab/cdef…`).
+//! Text is split into chunks at line boundaries chosen from the line contents,
+//! so an edit only produces new chunks around the lines it touches; every
+//! unchanged chunk is shared with earlier versions and with other documents.
+//!
+//! Every read re-hashes the blob and the reassembled text, so a corrupted or
+//! tampered object is reported instead of being returned.
+//!
+//! Objects are written before the manifest that refers to them, so garbage
+//! collection leaves alone anything written within `GC_GRACE_PERIOD`. Reusing
+//! an object that is older than half that period writes it again, which keeps
+//! a chunk picked up by a new version from being collected underneath it.
+
+use crate::error::{QmsError, QmsResult};
+use crate::json_utils::JsonValue;
+use crate::modules::storage::encryption;
+use crate::utils::encode_hex;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime};
+
+/// Objects written or reused this recently are never garbage collected
+pub const GC_GRACE_PERIOD: Duration = Duration::from_secs(60 * 60);
+
+/// Distinguishes temporary files of concurrent writes within the process
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Chunks are at least this large unless the text ends first
+const MIN_CHUNK_SIZE: usize = 512;
+
+/// Chunks are cut at this size even without a content-defined boundary
+const MAX_CHUNK_SIZE: usize = 16 * 1024;
+
+/// A line ends a chunk when its hash has these low bits clear (one line in 16)
+const BOUNDARY_MASK: u64 = 0x0f;
+
+/// SHA-256 of `data` as lowercase hex
+pub fn content_hash(data: &str) -> String {
+    encode_hex(&Sha256::digest(data.as_bytes()))
+}
+
+/// Reference to a text stored as a list of chunks
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkList {
+    /// SHA-256 of the complete text
+    pub sha256: String,
+    /// Length of the complete text in bytes
+    pub size: usize,
+    pub chunks: Vec<String>,
+}
+
+impl ChunkList {
+    pub fn to_json(&self) -> JsonValue {
+        let mut obj = HashMap::new();
+        obj.insert("sha256".to_string(), JsonValue::String(self.sha256.clone()));
+        obj.insert("size".to_string(), JsonValue::Number(self.size as f64));
+        obj.insert(
+            "chunks".to_string(),
+            JsonValue::Array(self.chunks.iter().map(|hash| JsonValue::String(hash.clone())).collect()),
+        );
+        JsonValue::Object(obj)
+    }
+
+    pub fn from_json(value: &JsonValue) -> QmsResult<Self> {
+        let invalid = || QmsError::validation_error("Invalid chunk list in version manifest");
+        let JsonValue::Object(obj) = value else { return Err(invalid()) };
+        let sha256 = match obj.get("sha256") {
+            Some(JsonValue::String(hash)) if is_hash(hash) => hash.clone(),
+            _ => return Err(invalid()),
+        };
+        let size = match obj.get("size") {
+            Some(JsonValue::Number(size)) => *size as usize,
+            _ => return Err(invalid()),
+        };
+        let chunks = match obj.get("chunks") {
+            Some(JsonValue::Array(chunks)) => chunks
+                .iter()
+                .map(|chunk| match chunk {
+                    JsonValue::String(hash) if is_hash(hash) => Ok(hash.clone()),
+                    _ => Err(invalid()),
+                })
+                .collect::<QmsResult<Vec<_>>>()?,
+            _ => return Err(invalid()),
+        };
+        Ok(Self { sha256, size, chunks })
+    }
+}
+
+/// Totals for the objects in a store
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StoreStats {
+    pub objects: usize,
+    pub bytes: u64,
+}
+
+/// Content-addressed object store shared by every document in a project
+pub struct ContentStore {
+    root: PathBuf,
+}
+
+impl ContentStore {
+    pub fn new(project_path: &Path) -> Self {
+        Self { root: project_path.join("documents").join("objects") }
+    }
+
+    fn object_path(&self, hash: &str) -> PathBuf {
+        self.root.join(&hash[..2]).join(&hash[2..])
+    }
+
+    /// Whether an object with this hash is stored
+    pub fn contains(&self, hash: &str) -> bool {
+        is_hash(hash) && self.object_path(hash).is_file()
+    }
+
+    /// Store `data` and return its hash
+    ///
+    /// Data already present is not written again unless the object is old
+    /// enough that a concurrent garbage collection could remove it.
+    pub fn put(&self, data: &str) -> QmsResult<String> {
+        let hash = content_hash(data);
+        let path = self.object_path(&hash);
+        if object_age(&path).is_some_and(|age| age < GC_GRACE_PERIOD / 2) {
+            return Ok(hash);
+        }
+        let dir = path.parent().expect("object paths have a fan-out directory");
+        fs::create_dir_all(dir)?;
+
+        // Write under a temporary name so a crash never leaves a truncated object;
+        // the seal is bound to the object's final path
+        let temp_path = dir.join(format!(
+            "{}.tmp-{}-{}",
+            &hash[2..],
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&temp_path, encryption::seal(&path, data)?)?;
+        fs::rename(&temp_path, &path)?;
+        Ok(hash)
+    }
+
+    /// Read an object, verifying that its contents still match its hash
+    pub fn get(&self, hash: &str) -> QmsResult<String> {
+        if !is_hash(hash) {
+            return Err(QmsError::validation_error(&format!("Invalid object hash '{hash}'")));
+        }
+        let path = self.object_path(hash);
+        if !path.is_file() {
+            return Err(QmsError::not_found(&format!("Object {hash} is missing from the content store")));
+        }
+        let data = encryption::read_to_string(&path)?;
+        if content_hash(&data) != hash {
+            return Err(QmsError::validation_error(&format!(
+                "Object {hash} failed its integrity check (contents do not match the hash)"
+            )));
+        }
+        Ok(data)
+    }
+
+    /// Store `text` as content-defined chunks
+    pub fn put_text(&self, text: &str) -> QmsResult<ChunkList> {
+        let chunks = split_chunks(text).into_iter().map(|chunk| self.put(chunk)).collect::<QmsResult<_>>()?;
+        Ok(ChunkList { sha256: content_hash(text), size: text.len(), chunks })
+    }
+
+    /// Reassemble and verify a text stored with `put_text`
+    pub fn get_text(&self, list: &ChunkList) -> QmsResult<String> {
+        let mut text = String::with_capacity(list.size);
+        for hash in &list.chunks {
+            text.push_str(&self.get(hash)?);
+        }
+        if text.len() != list.size || content_hash(&text) != list.sha256 {
+            return Err(QmsError::validation_error(&format!(
+                "Reassembled text {} failed its integrity check",
+                list.sha256
+            )));
+        }
+        Ok(text)
+    }
+
+    /// Hashes of every stored object
+    pub fn list(&self) -> QmsResult<BTreeSet<String>> {
+        let mut hashes = BTreeSet::new();
+        if !self.root.is_dir() {
+            return Ok(hashes);
+        }
+        for fan_out in fs::read_dir(&self.root)? {
+            let fan_out = fan_out?;
+            if !fan_out.file_type()?.is_dir() {
+                continue;
+            }
+            let prefix = fan_out.file_name().to_string_lossy().into_owned();
+            for entry in fs::read_dir(fan_out.path())? {
+                let hash = format!("{prefix}{}", entry?.file_name().to_string_lossy());
+                if is_hash(&hash) {
+                    hashes.insert(hash);
+                }
+            }
+        }
+        Ok(hashes)
+    }
+
+    /// Number and total on-disk size of the stored objects
+    pub fn stats(&self) -> QmsResult<StoreStats> {
+        let mut stats = StoreStats::default();
+        for hash in self.list()? {
+            stats.objects += 1;
+            stats.bytes += fs::metadata(self.object_path(&hash))?.len();
+        }
+        Ok(stats)
+    }
+
+    /// Delete an object
+    pub fn remove(&self, hash: &str) -> QmsResult<()> {
+        if !is_hash(hash) {
+            return Err(QmsError::validation_error(&format!("Invalid object hash '{hash}'")));
+        }
+        let path = self.object_path(hash);
+        fs::remove_file(&path)?;
+        if let Some(dir) = path.parent() {
+            // Only succeeds once the fan-out directory is empty
+            let _ = fs::remove_dir(dir);
+        }
+        Ok(())
+    }
+
+    /// Delete an object unless it was written less than `min_age` ago
+    ///
+    /// The object is moved aside before it is deleted; if a concurrent `put`
+    /// rewrote it in the meantime it is put back. Returns whether it was removed.
+    pub fn remove_if_older(&self, hash: &str, min_age: Duration) -> QmsResult<bool> {
+        if !is_hash(hash) {
+            return Err(QmsError::validation_error(&format!("Invalid object hash '{hash}'")));
+        }
+        let path = self.object_path(hash);
+        if !object_age(&path).is_some_and(|age| age >= min_age) {
+            return Ok(false);
+        }
+
+        let aside = path.with_file_name(format!("{}.gc-{}", &hash[2..], std::process::id()));
+        fs::rename(&path, &aside)?;
+        if !object_age(&aside).is_some_and(|age| age >= min_age) {
+            if path.exists() {
+                fs::remove_file(&aside)?;
+            } else {
+                fs::rename(&aside, &path)?;
+            }
+            return Ok(false);
+        }
+        fs::remove_file(&aside)?;
+        if let Some(dir) = path.parent() {
+            let _ = fs::remove_dir(dir);
+        }
+        Ok(true)
+    }
+}
+
+/// Time since an object file was last written, if it exists
+fn object_age(path: &Path) -> Option<Duration> {
+    let modified = fs::metadata(path).ok().filter(|m| m.is_file())?.modified().ok()?;
+    Some(SystemTime::now().duration_since(modified).unwrap_or(Duration::ZERO))
+}
+
+/// Whether `value` is a lowercase hex SHA-256 digest
+pub fn is_hash(value: &str) -> bool {
+    value.len() == 64 && value.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+}
+
+/// Split text into chunks whose boundaries depend only on nearby line contents
+pub fn split_chunks(text: &str) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut end = 0;
+    for line in text.split_inclusive('\n') {
+        end += line.len();
+        let boundary = end - start >= MIN_CHUNK_SIZE && line_hash(line) & BOUNDARY_MASK == 0;
+        if boundary || end - start >= MAX_CHUNK_SIZE {
+            // Very long lines are cut at character boundaries
+            while end - start > MAX_CHUNK_SIZE {
+                let mut cut = start + MAX_CHUNK_SIZE;
+                while !text.is_char_boundary(cut) {
+                    cut -= 1;
+                }
+                chunks.push(&text[start..cut]);
+                start = cut;
+            }
+            chunks.push(&text[start..end]);
+            start = end;
+        }
+    }
+    if start < text.len() || chunks.is_empty() {
+        chunks.push(&text[start..]);
+    }
+    chunks
+}
+
+/// FNV-1a hash of a line, ignoring its line ending
+fn line_hash(line: &str) -> u64 {
+    line.trim_end_matches(['\r', '\n'])
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sample_text(sections: usize) -> String {
+        (0..sections)
+            .map(|i| format!("## Section {i}\nThe infusion pump shall satisfy requirement {i} in all modes.\n\n"))
+            .collect()
+    }
+
+    #[test]
+    fn test_chunks_are_shared_between_revisions() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = ContentStore::new(temp_dir.path());
+
+        let original = sample_text(400);
+        let first = store.put_text(&original).unwrap();
+        assert!(first.chunks.len() > 4);
+        assert_eq!(split_chunks(&original).concat(), original);
+        let objects_after_first = store.stats().unwrap().objects;
+
+        // A one-line edit in the middle only adds chunks around that line
+        let revised = original.replacen("requirement 200 in", "requirement 200 (revised) in", 1);
+        let second = store.put_text(&revised).unwrap();
+        let shared = second.chunks.iter().filter(|hash| first.chunks.contains(hash)).count();
+        assert!(shared >= second.chunks.len() - 2, "{shared} of {}", second.chunks.len());
+        assert!(store.stats().unwrap().objects <= objects_after_first + 2);
+
+        assert_eq!(store.get_text(&first).unwrap(), original);
+        assert_eq!(store.get_text(&second).unwrap(), revised);
+        assert_eq!(ChunkList::from_json(&second.to_json()).unwrap(), second);
+
+        // Storing the same text again writes nothing new
+        let objects = store.stats().unwrap().objects;
+        store.put_text(&revised).unwrap();
+        assert_eq!(store.stats().unwrap().objects, objects);
+
+        let empty = store.put_text("").unwrap();
+        assert_eq!(store.get_text(&empty).unwrap(), "");
+    }
+
+    #[test]
+    fn test_corrupted_object_is_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = ContentStore::new(temp_dir.path());
+
+        let list = store.put_text(&sample_text(10)).unwrap();
+        let hash = &list.chunks[0];
+        fs::write(store.object_path(hash), "tampered").unwrap();
+
+        let error = store.get(hash).unwrap_err().to_string();
+        assert!(error.contains("integrity"), "{error}");
+        assert!(store.get_text(&list).is_err());
+
+        store.remove(hash).unwrap();
+        assert!(!store.contains(hash));
+        assert!(store.get(hash).is_err());
+    }
+
+    #[test]
+    fn test_recent_objects_survive_collection() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = ContentStore::new(temp_dir.path());
+
+        let hash = store.put("chunk").unwrap();
+        assert!(!store.remove_if_older(&hash, GC_GRACE_PERIOD).unwrap());
+        assert!(store.contains(&hash));
+        assert!(store.remove_if_older(&hash, Duration::ZERO).unwrap());
+        assert!(!store.contains(&hash));
+        assert!(!store.remove_if_older(&hash, Duration::ZERO).unwrap());
+    }
+}
@@ -0,0 +1,753 @@
+//! Document version comparison
+//! Line- and word-level redline between two document versions
+//! Renders unified diffs, side-by-side terminal views and HTML redlines for review
+
+use crate::modules::document_control::document::{Document, RegulatoryReference};
+use std::fmt::Write;
+
+/// Number of unchanged lines shown around each change by default
+pub const DEFAULT_CONTEXT: usize = 3;
+
+/// Minimum share of unchanged text for a deleted and an inserted line to be
+/// shown as one modified line with word-level changes
+const MODIFIED_LINE_SIMILARITY: f64 = 0.5;
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const CYAN: &str = "\x1b[36m";
+const HIGHLIGHT: &str = "\x1b[7m";
+const HIGHLIGHT_OFF: &str = "\x1b[27m";
+const RESET: &str = "\x1b[0m";
+
+/// Kind of a word-level segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Equal,
+    Inserted,
+    Deleted,
+}
+
+/// Run of text within a modified line
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    pub kind: ChangeKind,
+    pub text: String,
+}
+
+/// One line of the content diff; line numbers are 1-based
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine {
+    Unchanged { old_line: usize, new_line: usize, text: String },
+    Deleted { old_line: usize, text: String },
+    Inserted { new_line: usize, text: String },
+    Modified { old_line: usize, new_line: usize, segments: Vec<Segment> },
+}
+
+impl DiffLine {
+    const fn is_change(&self) -> bool {
+        !matches!(self, DiffLine::Unchanged { .. })
+    }
+
+    const fn old_line(&self) -> Option<usize> {
+        match self {
+            DiffLine::Unchanged { old_line, .. } | DiffLine::Deleted { old_line, .. } | DiffLine::Modified { old_line, .. } => {
+                Some(*old_line)
+            }
+            DiffLine::Inserted { .. } => None,
+        }
+    }
+
+    const fn new_line(&self) -> Option<usize> {
+        match self {
+            DiffLine::Unchanged { new_line, .. } | DiffLine::Inserted { new_line, .. } | DiffLine::Modified { new_line, .. } => {
+                Some(*new_line)
+            }
+            DiffLine::Deleted { .. } => None,
+        }
+    }
+
+    /// Segments of the old side of the line, if it has one
+    fn old_segments(&self) -> Option<Vec<Segment>> {
+        match self {
+            DiffLine::Unchanged { text, .. } => Some(vec![segment(ChangeKind::Equal, text)]),
+            DiffLine::Deleted { text, .. } => Some(vec![segment(ChangeKind::Deleted, text)]),
+            DiffLine::Modified { segments, .. } => {
+                Some(segments.iter().filter(|s| s.kind != ChangeKind::Inserted).cloned().collect())
+            }
+            DiffLine::Inserted { .. } => None,
+        }
+    }
+
+    /// Segments of the new side of the line, if it has one
+    fn new_segments(&self) -> Option<Vec<Segment>> {
+        match self {
+            DiffLine::Unchanged { text, .. } => Some(vec![segment(ChangeKind::Equal, text)]),
+            DiffLine::Inserted { text, .. } => Some(vec![segment(ChangeKind::Inserted, text)]),
+            DiffLine::Modified { segments, .. } => {
+                Some(segments.iter().filter(|s| s.kind != ChangeKind::Deleted).cloned().collect())
+            }
+            DiffLine::Deleted { .. } => None,
+        }
+    }
+}
+
+/// Change to a metadata field; list fields also record added and removed entries
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataChange {
+    pub field: String,
+    pub old: String,
+    pub new: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Output format for a document comparison
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffFormat {
+    Unified,
+    SideBySide,
+    Html,
+}
+
+impl DiffFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "unified" | "diff" => Some(DiffFormat::Unified),
+            "side-by-side" | "side" | "split" => Some(DiffFormat::SideBySide),
+            "html" | "redline" => Some(DiffFormat::Html),
+            _ => None,
+        }
+    }
+}
+
+/// Full comparison of two document versions
+#[derive(Debug, Clone)]
+pub struct DocumentDiff {
+    pub document_id: String,
+    pub title: String,
+    pub version_a: String,
+    pub version_b: String,
+    pub metadata: Vec<MetadataChange>,
+    pub lines: Vec<DiffLine>,
+}
+
+impl DocumentDiff {
+    /// Compare `old` against `new`
+    pub fn between(old: &Document, new: &Document) -> Self {
+        Self {
+            document_id: new.id.clone(),
+            title: new.title.clone(),
+            version_a: old.version.clone(),
+            version_b: new.version.clone(),
+            metadata: diff_metadata(old, new),
+            lines: diff_text(&old.content, &new.content),
+        }
+    }
+
+    pub fn has_changes(&self) -> bool {
+        !self.metadata.is_empty() || self.content_changed()
+    }
+
+    pub fn content_changed(&self) -> bool {
+        self.lines.iter().any(DiffLine::is_change)
+    }
+
+    /// Lines (added, removed, modified)
+    pub fn stats(&self) -> (usize, usize, usize) {
+        self.lines.iter().fold((0, 0, 0), |(added, removed, modified), line| match line {
+            DiffLine::Inserted { .. } => (added + 1, removed, modified),
+            DiffLine::Deleted { .. } => (added, removed + 1, modified),
+            DiffLine::Modified { .. } => (added, removed, modified + 1),
+            DiffLine::Unchanged { .. } => (added, removed, modified),
+        })
+    }
+
+    /// Unified diff of the content, preceded by metadata changes as `#` comments
+    pub fn to_unified(&self, context: usize, color: bool) -> String {
+        let mut out = String::new();
+        for change in &self.metadata {
+            let _ = writeln!(out, "# {}: {} -> {}", change.field, or_none(&change.old), or_none(&change.new));
+        }
+        let _ = writeln!(out, "--- {} v{}", self.document_id, self.version_a);
+        let _ = writeln!(out, "+++ {} v{}", self.document_id, self.version_b);
+
+        for hunk in hunks(&self.lines, context) {
+            let lines = &self.lines[hunk.clone()];
+            let (old_start, old_count) = hunk_range(lines.iter().map(DiffLine::old_line), &self.lines[..hunk.start], true);
+            let (new_start, new_count) = hunk_range(lines.iter().map(DiffLine::new_line), &self.lines[..hunk.start], false);
+            let header = format!("@@ -{old_start},{old_count} +{new_start},{new_count} @@");
+            let _ = writeln!(out, "{}", paint(&header, CYAN, color));
+
+            for block in change_blocks(lines) {
+                if let [DiffLine::Unchanged { text, .. }] = block {
+                    let _ = writeln!(out, " {text}");
+                    continue;
+                }
+                for old in block.iter().filter_map(DiffLine::old_segments) {
+                    let _ = writeln!(out, "{}", render_terminal_line('-', &old, RED, color));
+                }
+                for new in block.iter().filter_map(DiffLine::new_segments) {
+                    let _ = writeln!(out, "{}", render_terminal_line('+', &new, GREEN, color));
+                }
+            }
+        }
+        out
+    }
+
+    /// Two-column terminal view of the changed regions
+    pub fn to_side_by_side(&self, width: usize, context: usize, color: bool) -> String {
+        let column = width.saturating_sub(13).max(40) / 2;
+        let mut out = String::new();
+
+        if !self.metadata.is_empty() {
+            let _ = writeln!(out, "METADATA CHANGES:");
+            for change in &self.metadata {
+                let _ = writeln!(out, "  {:<20} {} -> {}", change.field, or_none(&change.old), or_none(&change.new));
+            }
+            let _ = writeln!(out);
+        }
+        let old_label = format!("v{}", self.version_a);
+        let _ = writeln!(out, "     {old_label:<column$}        v{}", self.version_b);
+        let _ = writeln!(out, "{}", "─".repeat(column * 2 + 13));
+
+        for (index, hunk) in hunks(&self.lines, context).into_iter().enumerate() {
+            if index > 0 {
+                let _ = writeln!(out, "{:^width$}", "⋯", width = column * 2 + 13);
+            }
+            for block in change_blocks(&self.lines[hunk]) {
+                let olds: Vec<_> = block.iter().filter(|l| l.old_line().is_some()).collect();
+                let news: Vec<_> = block.iter().filter(|l| l.new_line().is_some()).collect();
+                for row in 0..olds.len().max(news.len()) {
+                    let old = olds.get(row).copied();
+                    let new = news.get(row).copied();
+                    let marker = match (old, new) {
+                        (Some(DiffLine::Unchanged { .. }), _) => ' ',
+                        (Some(_), Some(_)) => '|',
+                        (Some(_), None) => '<',
+                        _ => '>',
+                    };
+                    let left = side_cell(old.and_then(DiffLine::old_line), old.and_then(DiffLine::old_segments), column, RED, color);
+                    let right = side_cell(new.and_then(DiffLine::new_line), new.and_then(DiffLine::new_segments), column, GREEN, color);
+                    let _ = writeln!(out, "{left} {marker} {}", right.trim_end());
+                }
+            }
+        }
+        if !self.content_changed() {
+            let _ = writeln!(out, "(content unchanged)");
+        }
+        out
+    }
+
+    /// Standalone HTML redline with insertions and deletions marked up
+    pub fn to_html(&self) -> String {
+        let (added, removed, modified) = self.stats();
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        let _ = writeln!(html, "<title>Redline: {} v{} to v{}</title>", escape_html(&self.title), escape_html(&self.version_a), escape_html(&self.version_b));
+        html.push_str("<style>\n");
+        html.push_str("body { font-family: sans-serif; margin: 2em; }\n");
+        html.push_str("table { border-collapse: collapse; }\n");
+        html.push_str("th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: left; vertical-align: top; }\n");
+        html.push_str("table.redline td { border: none; font-family: monospace; white-space: pre-wrap; }\n");
+        html.push_str("td.ln { color: #888; text-align: right; user-select: none; }\n");
+        html.push_str("del { background: #fdd; color: #a00; text-decoration: line-through; }\n");
+        html.push_str("ins { background: #dfd; color: #060; text-decoration: underline; }\n");
+        html.push_str("tr.changed { background: #fffbe6; }\n");
+        html.push_str("</style>\n</head>\n<body>\n");
+
+        let _ = writeln!(html, "<h1>{}</h1>", escape_html(&self.title));
+        let _ = writeln!(
+            html,
+            "<p>Document {} &mdash; version {} compared with {}: {added} lines added, {removed} removed, {modified} modified.</p>",
+            escape_html(&self.document_id),
+            escape_html(&self.version_a),
+            escape_html(&self.version_b)
+        );
+
+        html.push_str("<h2>Metadata</h2>\n");
+        if self.metadata.is_empty() {
+            html.push_str("<p>No metadata changes.</p>\n");
+        } else {
+            html.push_str("<table>\n<tr><th>Field</th><th>Change</th></tr>\n");
+            for change in &self.metadata {
+                let _ = write!(html, "<tr><td>{}</td><td>", escape_html(&change.field));
+                if change.added.is_empty() && change.removed.is_empty() {
+                    let _ = write!(html, "<del>{}</del> <ins>{}</ins>", escape_html(&change.old), escape_html(&change.new));
+                } else {
+                    let items = change.removed.iter().map(|r| format!("<del>{}</del>", escape_html(r)));
+                    let items = items.chain(change.added.iter().map(|a| format!("<ins>{}</ins>", escape_html(a))));
+                    html.push_str(&items.collect::<Vec<_>>().join("<br>"));
+                }
+                html.push_str("</td></tr>\n");
+            }
+            html.push_str("</table>\n");
+        }
+
+        html.push_str("<h2>Content</h2>\n<table class=\"redline\">\n");
+        for line in &self.lines {
+            let (old_line, new_line) = (line.old_line(), line.new_line());
+            let number = |n: Option<usize>| n.map(|n| n.to_string()).unwrap_or_default();
+            let class = if line.is_change() { " class=\"changed\"" } else { "" };
+            let body = match line {
+                DiffLine::Unchanged { text, .. } => escape_html(text),
+                DiffLine::Deleted { text, .. } => format!("<del>{}</del>", escape_html(text)),
+                DiffLine::Inserted { text, .. } => format!("<ins>{}</ins>", escape_html(text)),
+                DiffLine::Modified { segments, .. } => segments
+                    .iter()
+                    .map(|s| match s.kind {
+                        ChangeKind::Equal => escape_html(&s.text),
+                        ChangeKind::Deleted => format!("<del>{}</del>", escape_html(&s.text)),
+                        ChangeKind::Inserted => format!("<ins>{}</ins>", escape_html(&s.text)),
+                    })
+                    .collect(),
+            };
+            let _ = writeln!(
+                html,
+                "<tr{class}><td class=\"ln\">{}</td><td class=\"ln\">{}</td><td>{body}</td></tr>",
+                number(old_line),
+                number(new_line)
+            );
+        }
+        html.push_str("</table>\n</body>\n</html>\n");
+        html
+    }
+}
+
+/// Line-level diff of two texts with word-level detail for modified lines
+pub fn diff_text(old: &str, new: &str) -> Vec<DiffLine> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let edits = diff_sequences(&old_lines, &new_lines);
+
+    let mut lines = Vec::new();
+    let mut deleted: Vec<usize> = Vec::new();
+    let mut inserted: Vec<usize> = Vec::new();
+    for edit in edits {
+        match edit {
+            Edit::Equal(i, j) => {
+                flush_change_block(&old_lines, &new_lines, &mut deleted, &mut inserted, &mut lines);
+                lines.push(DiffLine::Unchanged { old_line: i + 1, new_line: j + 1, text: old_lines[i].to_string() });
+            }
+            Edit::Delete(i) => deleted.push(i),
+            Edit::Insert(j) => inserted.push(j),
+        }
+    }
+    flush_change_block(&old_lines, &new_lines, &mut deleted, &mut inserted, &mut lines);
+    lines
+}
+
+/// Word-level diff of two lines
+pub fn diff_words(old: &str, new: &str) -> Vec<Segment> {
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+    let mut segments: Vec<Segment> = Vec::new();
+    for edit in diff_sequences(&old_tokens, &new_tokens) {
+        let (kind, text) = match edit {
+            Edit::Equal(i, _) => (ChangeKind::Equal, old_tokens[i]),
+            Edit::Delete(i) => (ChangeKind::Deleted, old_tokens[i]),
+            Edit::Insert(j) => (ChangeKind::Inserted, new_tokens[j]),
+        };
+        match segments.last_mut() {
+            Some(last) if last.kind == kind => last.text.push_str(text),
+            _ => segments.push(segment(kind, text)),
+        }
+    }
+    segments
+}
+
+/// Pair the deleted and inserted lines of one change block into modified lines
+fn flush_change_block(
+    old_lines: &[&str],
+    new_lines: &[&str],
+    deleted: &mut Vec<usize>,
+    inserted: &mut Vec<usize>,
+    out: &mut Vec<DiffLine>,
+) {
+    let mut unpaired_inserts = Vec::new();
+    for row in 0..deleted.len().max(inserted.len()) {
+        match (deleted.get(row), inserted.get(row)) {
+            (Some(&i), Some(&j)) => {
+                let segments = diff_words(old_lines[i], new_lines[j]);
+                if similarity(&segments) >= MODIFIED_LINE_SIMILARITY {
+                    out.push(DiffLine::Modified { old_line: i + 1, new_line: j + 1, segments });
+                } else {
+                    out.push(DiffLine::Deleted { old_line: i + 1, text: old_lines[i].to_string() });
+                    unpaired_inserts.push(j);
+                }
+            }
+            (Some(&i), None) => out.push(DiffLine::Deleted { old_line: i + 1, text: old_lines[i].to_string() }),
+            (None, Some(&j)) => unpaired_inserts.push(j),
+            (None, None) => {}
+        }
+    }
+    for j in unpaired_inserts {
+        out.push(DiffLine::Inserted { new_line: j + 1, text: new_lines[j].to_string() });
+    }
+    deleted.clear();
+    inserted.clear();
+}
+
+/// Share of characters left unchanged across both sides
+fn similarity(segments: &[Segment]) -> f64 {
+    let total: usize = segments
+        .iter()
+        .map(|s| if s.kind == ChangeKind::Equal { 2 * s.text.trim().len() } else { s.text.trim().len() })
+        .sum();
+    if total == 0 {
+        return 1.0;
+    }
+    let equal: usize = segments.iter().filter(|s| s.kind == ChangeKind::Equal).map(|s| 2 * s.text.trim().len()).sum();
+    equal as f64 / total as f64
+}
+
+/// Split a line into words, whitespace runs and single punctuation marks
+fn tokenize(line: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut previous = None;
+    for (i, c) in line.char_indices() {
+        let class = char_class(c);
+        if previous.is_some_and(|p| p != class || class == 2) {
+            tokens.push(&line[start..i]);
+            start = i;
+        }
+        previous = Some(class);
+    }
+    if start < line.len() {
+        tokens.push(&line[start..]);
+    }
+    tokens
+}
+
+/// 0 = word character, 1 = whitespace, 2 = punctuation
+fn char_class(c: char) -> u8 {
+    if c.is_alphanumeric() || c == '_' {
+        0
+    } else if c.is_whitespace() {
+        1
+    } else {
+        2
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Edit {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Shortest edit script between two sequences (Myers' O(ND) algorithm)
+fn diff_sequences<T: PartialEq>(a: &[T], b: &[T]) -> Vec<Edit> {
+    // Common prefix and suffix are matched without searching
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..].iter().rev().zip(b[prefix..].iter().rev()).take_while(|(x, y)| x == y).count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut edits: Vec<Edit> = (0..prefix).map(|i| Edit::Equal(i, i)).collect();
+    edits.extend(myers(a_mid, b_mid).into_iter().map(|edit| match edit {
+        Edit::Equal(i, j) => Edit::Equal(i + prefix, j + prefix),
+        Edit::Delete(i) => Edit::Delete(i + prefix),
+        Edit::Insert(j) => Edit::Insert(j + prefix),
+    }));
+    edits.extend((0..suffix).map(|k| Edit::Equal(a.len() - suffix + k, b.len() - suffix + k)));
+    edits
+}
+
+/// Edit distance past which the search gives up and reports a full replacement
+const MAX_EDIT_DISTANCE: isize = 2000;
+
+fn myers<T: PartialEq>(a: &[T], b: &[T]) -> Vec<Edit> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = (n + m).min(MAX_EDIT_DISTANCE);
+    let offset = max + 1;
+    let mut v = vec![0isize; 2 * max as usize + 3];
+    // Step d only reads diagonals -d-1..=d+1 of the step before, so only that window is kept
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+    let mut found = false;
+
+    'search: for d in 0..=max {
+        trace.push(v[(offset - d - 1) as usize..=(offset + d + 1) as usize].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let index = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[index - 1] < v[index + 1]) { v[index + 1] } else { v[index - 1] + 1 };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[index] = x;
+            if x >= n && y >= m {
+                found = true;
+                break 'search;
+            }
+        }
+    }
+    if !found {
+        return (0..a.len()).map(Edit::Delete).chain((0..b.len()).map(Edit::Insert)).collect();
+    }
+
+    // Walk the trace back from (n, m) to recover the edit script
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let window = d + 1;
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && v[(k - 1 + window) as usize] < v[(k + 1 + window) as usize]) { k + 1 } else { k - 1 };
+        let prev_x = v[(prev_k + window) as usize];
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            edits.push(Edit::Equal(x as usize, y as usize));
+        }
+        if d > 0 {
+            if x == prev_x {
+                edits.push(Edit::Insert(prev_y as usize));
+            } else {
+                edits.push(Edit::Delete(prev_x as usize));
+            }
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    edits.reverse();
+    edits
+}
+
+/// Ranges of `lines` to show: every change plus `context` unchanged lines around it
+fn hunks(lines: &[DiffLine], context: usize) -> Vec<std::ops::Range<usize>> {
+    let mut ranges: Vec<std::ops::Range<usize>> = Vec::new();
+    for (index, _) in lines.iter().enumerate().filter(|(_, line)| line.is_change()) {
+        let start = index.saturating_sub(context);
+        let end = (index + context + 1).min(lines.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.end => last.end = last.end.max(end),
+            _ => ranges.push(start..end),
+        }
+    }
+    ranges
+}
+
+/// Split lines into single unchanged lines and maximal runs of changes
+fn change_blocks(lines: &[DiffLine]) -> Vec<&[DiffLine]> {
+    let mut blocks = Vec::new();
+    let mut start = 0;
+    while start < lines.len() {
+        let end = if lines[start].is_change() {
+            start + lines[start..].iter().take_while(|line| line.is_change()).count()
+        } else {
+            start + 1
+        };
+        blocks.push(&lines[start..end]);
+        start = end;
+    }
+    blocks
+}
+
+/// Start and length of one side of a hunk for the `@@` header
+fn hunk_range(numbers: impl Iterator<Item = Option<usize>>, before: &[DiffLine], old: bool) -> (usize, usize) {
+    let numbers: Vec<usize> = numbers.flatten().collect();
+    match numbers.first() {
+        Some(&first) => (first, numbers.len()),
+        // Empty side: report the line before the hunk, as diff(1) does
+        None => {
+            let side = |line: &DiffLine| if old { line.old_line() } else { line.new_line() };
+            (before.iter().rev().find_map(side).unwrap_or(0), 0)
+        }
+    }
+}
+
+fn render_terminal_line(prefix: char, segments: &[Segment], line_color: &str, color: bool) -> String {
+    let mut line = String::new();
+    line.push(prefix);
+    for s in segments {
+        if color && s.kind != ChangeKind::Equal && segments.len() > 1 {
+            let _ = write!(line, "{HIGHLIGHT}{}{HIGHLIGHT_OFF}", s.text);
+        } else {
+            line.push_str(&s.text);
+        }
+    }
+    paint(&line, line_color, color)
+}
+
+fn side_cell(number: Option<usize>, segments: Option<Vec<Segment>>, width: usize, line_color: &str, color: bool) -> String {
+    let Some(segments) = segments else {
+        return " ".repeat(width + 5);
+    };
+    let changed = segments.iter().any(|s| s.kind != ChangeKind::Equal);
+    let mut cell = String::new();
+    let mut used = 0;
+    for s in &segments {
+        let remaining = width.saturating_sub(used);
+        let mut text: String = s.text.chars().take(remaining).collect();
+        if s.text.chars().count() > remaining && remaining > 0 {
+            text.pop();
+            text.push('…');
+        }
+        used += text.chars().count();
+        if color && s.kind != ChangeKind::Equal && segments.len() > 1 {
+            let _ = write!(cell, "{HIGHLIGHT}{text}{HIGHLIGHT_OFF}");
+        } else {
+            cell.push_str(&text);
+        }
+    }
+    let padding = " ".repeat(width - used);
+    let cell = if changed && color { format!("{line_color}{cell}{RESET}") } else { cell };
+    format!("{:>4} {cell}{padding}", number.map(|n| n.to_string()).unwrap_or_default())
+}
+
+fn paint(text: &str, code: &str, color: bool) -> String {
+    if color {
+        format!("{code}{text}{RESET}")
+    } else {
+        text.to_string()
+    }
+}
+
+fn segment(kind: ChangeKind, text: &str) -> Segment {
+    Segment { kind, text: text.to_string() }
+}
+
+const fn or_none(value: &str) -> &str {
+    if value.is_empty() {
+        "(none)"
+    } else {
+        value
+    }
+}
+
+fn diff_metadata(old: &Document, new: &Document) -> Vec<MetadataChange> {
+    let mut changes = Vec::new();
+    let mut scalar = |field: &str, a: String, b: String| {
+        if a != b {
+            changes.push(MetadataChange { field: field.to_string(), old: a, new: b, added: Vec::new(), removed: Vec::new() });
+        }
+    };
+    scalar("Title", old.title.clone(), new.title.clone());
+    scalar("Type", old.doc_type.to_string(), new.doc_type.to_string());
+    scalar("Status", old.status.to_string(), new.status.to_string());
+    scalar("Approved by", old.approved_by.clone().unwrap_or_default(), new.approved_by.clone().unwrap_or_default());
+    scalar("File", old.file_path.clone(), new.file_path.clone());
+
+    let mapping = |r: &RegulatoryReference| format!("{} {}: {}", r.standard, r.section, r.requirement);
+    let lists = [
+        ("Tags", old.tags.clone(), new.tags.clone()),
+        (
+            "Regulatory mappings",
+            old.regulatory_mapping.iter().map(mapping).collect(),
+            new.regulatory_mapping.iter().map(mapping).collect(),
+        ),
+    ];
+    for (field, a, b) in lists {
+        let removed: Vec<String> = a.iter().filter(|item| !b.contains(item)).cloned().collect();
+        let added: Vec<String> = b.iter().filter(|item| !a.contains(item)).cloned().collect();
+        if !added.is_empty() || !removed.is_empty() {
+            changes.push(MetadataChange { field: field.to_string(), old: a.join(", "), new: b.join(", "), added, removed });
+        }
+    }
+    changes
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::document_control::document::{DocumentStatus, DocumentType};
+
+    fn document(version: &str, content: &str) -> Document {
+        Document::new(
+            "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            "123e4567-e89b-12d3-a456-426614174001".to_string(),
+            "Software Requirements".to_string(),
+            content.to_string(),
+            DocumentType::SoftwareRequirementsSpecification,
+            version.to_string(),
+            "author".to_string(),
+            "documents/srs.md".to_string(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_diff_text_line_and_word_changes() {
+        let old = "# SRS\nThe pump shall deliver 5 ml/h.\nAlarm on occlusion.\nObsolete note.\nEnd";
+        let new = "# SRS\nThe pump shall deliver 10 ml/h.\nAlarm on occlusion.\nBattery backup for 2 hours.\nEnd\nAppendix";
+        let lines = diff_text(old, new);
+
+        assert_eq!(lines[0], DiffLine::Unchanged { old_line: 1, new_line: 1, text: "# SRS".to_string() });
+        let DiffLine::Modified { old_line: 2, new_line: 2, segments } = &lines[1] else {
+            panic!("expected a modified line, got {:?}", lines[1]);
+        };
+        assert!(segments.contains(&segment(ChangeKind::Deleted, "5")));
+        assert!(segments.contains(&segment(ChangeKind::Inserted, "10")));
+        assert!(lines.contains(&DiffLine::Deleted { old_line: 4, text: "Obsolete note.".to_string() }));
+        assert!(lines.contains(&DiffLine::Inserted { new_line: 4, text: "Battery backup for 2 hours.".to_string() }));
+        assert_eq!(lines.last(), Some(&DiffLine::Inserted { new_line: 6, text: "Appendix".to_string() }));
+
+        assert!(diff_text("same\ntext", "same\ntext").iter().all(|l| !l.is_change()));
+        assert_eq!(diff_text("", "a").len(), 1);
+    }
+
+    #[test]
+    fn test_unified_output() {
+        let old_doc = document("1.0.0", "a\nb\nc\nd\ne\nf\ng\nh\ni");
+        let mut new_doc = document("1.1.0", "a\nb\nc\nD\ne\nf\ng\nh\ni\nj");
+        new_doc.status = DocumentStatus::InReview;
+        new_doc.tags = vec!["safety".to_string()];
+
+        let diff = DocumentDiff::between(&old_doc, &new_doc);
+        assert_eq!(diff.stats(), (2, 1, 0)); // "d" -> "D" is below the similarity threshold
+        let unified = diff.to_unified(1, false);
+        assert!(unified.contains("# Status: Draft -> InReview"));
+        assert!(unified.contains("# Tags: (none) -> safety"));
+        assert!(unified.contains("@@ -3,3 +3,3 @@\n c\n-d\n+D\n e\n"));
+        assert!(unified.contains("@@ -9,1 +9,2 @@\n i\n+j\n"));
+        assert!(!unified.contains('\x1b'));
+    }
+
+    #[test]
+    fn test_html_redline_and_side_by_side() {
+        let old_doc = document("1.0.0", "Dose <limit> is 5 units per hour");
+        let new_doc = document("1.0.1", "Dose <limit> is 7 units per hour");
+        let diff = DocumentDiff::between(&old_doc, &new_doc);
+
+        let html = diff.to_html();
+        assert!(html.contains("Dose &lt;limit&gt; is <del>5</del><ins>7</ins> units per hour"));
+        assert!(html.contains("No metadata changes."));
+
+        let side = diff.to_side_by_side(80, 3, false);
+        assert!(side.contains("   1 Dose <limit> is 5 units per hour"));
+        assert!(side.contains(" | "));
+    }
+
+    #[test]
+    fn test_edit_scripts_and_size_guard() {
+        let script = |a: &[i32], b: &[i32]| diff_sequences(a, b);
+        let edits = script(&[1, 2, 3, 4, 5], &[1, 3, 4, 6, 5]);
+        assert_eq!(edits.iter().filter(|e| !matches!(e, Edit::Equal(..))).count(), 2);
+        assert!(edits.contains(&Edit::Delete(1)));
+        assert!(edits.contains(&Edit::Insert(3)));
+
+        // Past the edit distance limit the middle is reported as replaced wholesale
+        let a: Vec<i32> = (0..1500).collect();
+        let b: Vec<i32> = (1500..3000).collect();
+        let mut old = vec![-1];
+        old.extend(&a);
+        let mut new = vec![-1];
+        new.extend(&b);
+        let edits = script(&old, &new);
+        assert_eq!(edits[0], Edit::Equal(0, 0));
+        assert_eq!(edits.iter().filter(|e| matches!(e, Edit::Delete(_))).count(), 1500);
+        assert_eq!(edits.iter().filter(|e| matches!(e, Edit::Insert(_))).count(), 1500);
+        assert_eq!(edits[1], Edit::Delete(1));
+        assert_eq!(edits.last(), Some(&Edit::Insert(1500)));
+    }
+}
@@ -5,12 +5,15 @@
 //! - JSON: Full document metadata with content and history
 //! - Markdown: Clean markdown with metadata header
 //! - HTML: HTML format with CSS styling
-//! - PDF: Text-based report format (stdlib-only limitation)
+//! - PDF: Native PDF with running document ID/revision headers and signature manifestation
 
 use crate::error::{QmsError, QmsResult};
 use crate::modules::document_control::document::Document;
 use crate::modules::document_control::service::DocumentService;
 use crate::modules::document_control::version::DocumentVersion;
+use crate::modules::document_control::approval::ApprovalWorkflow;
+use crate::modules::audit_logger::signatures::ElectronicSignatureManager;
+use crate::modules::report_generator::pdf::{PdfDocument, PdfTable, SignatureManifestation};
 use std::fs;
 use std::path::Path;
 
@@ -289,89 +292,86 @@ impl DocumentExporter {
         Ok(html)
     }
 
-    /// Export document to PDF-like text format
+    /// Export document to native PDF format
     fn export_to_pdf(
         document: &Document,
-        _project_path: &Path,
+        project_path: &Path,
         options: &ExportOptions,
     ) -> QmsResult<String> {
-        let mut content = String::new();
-        let line_width = 80;
-
-        // Header
-        content.push_str(&"=".repeat(line_width));
-        content.push('\n');
-        content.push_str(&Self::center_text(&document.title, line_width));
-        content.push('\n');
-        content.push_str(&"=".repeat(line_width));
-        content.push('\n');
-        content.push('\n');
+        let mut pdf = PdfDocument::new(&document.title)
+            .with_document_id(&document.id)
+            .with_revision(&document.version)
+            .with_author(&document.created_by)
+            .with_footer_note(&format!("{:?} - generated on {}", document.status, crate::utils::current_date_string()));
 
         // Metadata section
         if options.include_metadata {
-            content.push_str("DOCUMENT INFORMATION\n");
-            content.push_str(&"-".repeat(line_width));
-            content.push('\n');
-            content.push_str(&format!("Document ID:       {}\n", document.id));
-            content.push_str(&format!("Version:           {}\n", document.version));
-            content.push_str(&format!("Status:            {:?}\n", document.status));
-            content.push_str(&format!("Type:              {:?}\n", document.doc_type));
-            content.push_str(&format!("Created:           {}\n", document.created_at));
-            content.push_str(&format!("Updated:           {}\n", document.updated_at));
-            content.push_str(&format!("Author:            {}\n", document.created_by));
+            pdf.heading(1, "Document Information");
+            pdf.field("Document ID", &document.id);
+            pdf.field("Version", &document.version);
+            pdf.field("Status", &format!("{:?}", document.status));
+            pdf.field("Type", &format!("{:?}", document.doc_type));
+            pdf.field("Created", &document.created_at);
+            pdf.field("Updated", &document.updated_at);
+            pdf.field("Author", &document.created_by);
             if let Some(approved_by) = &document.approved_by {
-                content.push_str(&format!("Approved By:       {approved_by}\n"));
+                pdf.field("Approved By", approved_by);
             }
-            content.push_str(&format!("Checksum:          {}\n", document.checksum));
-            content.push('\n');
-            content.push('\n');
+            pdf.field("Checksum", &document.checksum);
         }
 
         // Document content
-        content.push_str("DOCUMENT CONTENT\n");
-        content.push_str(&"-".repeat(line_width));
-        content.push('\n');
-        content.push('\n');
-        
-        // Format content for text display
-        let formatted_content = Self::format_text_content(&document.content, line_width);
-        content.push_str(&formatted_content);
-        content.push('\n');
-        content.push('\n');
+        pdf.heading(1, "Document Content");
+        pdf.push_markdown(&document.content);
 
         // Version history
         if options.include_history {
-            content.push_str("VERSION HISTORY\n");
-            content.push_str(&"-".repeat(line_width));
-            content.push('\n');
-            content.push_str(&format!("Version {}:\n", document.version));
-            content.push_str(&format!("  Date: {}\n", document.updated_at));
-            content.push_str("  Changes: Current version\n");
-            content.push('\n');
+            pdf.heading(1, "Version History");
+            let mut history = PdfTable::new(&["Version", "Date", "Changes"]);
+            history.add_row(vec![document.version.clone(), document.updated_at.clone(), "Current version".to_string()]);
+            pdf.table(history);
         }
 
         // Regulatory mapping
         if options.include_regulatory_mapping && !document.regulatory_mapping.is_empty() {
-            content.push_str("REGULATORY MAPPING\n");
-            content.push_str(&"-".repeat(line_width));
-            content.push('\n');
+            pdf.heading(1, "Regulatory Mapping");
+            let mut mapping_table = PdfTable::new(&["Standard", "Section", "Requirement"]);
             for mapping in &document.regulatory_mapping {
-                content.push_str(&format!("Standard: {}\n", mapping.standard));
-                content.push_str(&format!("Section:  {}\n", mapping.section));
-                content.push_str(&format!("Requirement: {}\n", mapping.requirement));
-                content.push('\n');
+                mapping_table.add_row(vec![mapping.standard.clone(), mapping.section.clone(), mapping.requirement.clone()]);
             }
+            pdf.table(mapping_table);
         }
 
-        // Footer
-        content.push_str(&"=".repeat(line_width));
-        content.push('\n');
-        content.push_str(&Self::center_text(&format!("Generated on {}", crate::utils::current_date_string()), line_width));
-        content.push('\n');
-        content.push_str(&"=".repeat(line_width));
-        content.push('\n');
+        // Electronic signatures applied to this document
+        pdf.signatures(Self::signature_manifestations(document, project_path));
 
-        Ok(content)
+        Ok(pdf.render())
+    }
+
+    /// Collect approval and Part 11 signatures recorded against a document
+    fn signature_manifestations(document: &Document, project_path: &Path) -> Vec<SignatureManifestation> {
+        let mut manifestations: Vec<SignatureManifestation> = ApprovalWorkflow::for_project(project_path)
+            .get_workflow_history(&document.id)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|entry| entry.signature.as_ref())
+            .map(|signature| SignatureManifestation {
+                signer: format!("{} ({})", signature.signer_name, signature.signer_id),
+                meaning: signature.signing_reason.clone(),
+                signed_at: signature.signature_timestamp.clone(),
+                reason: None,
+                signature_id: None,
+                fingerprint: Some(signature.signature_hash.clone()),
+            })
+            .collect();
+
+        let signature_manager = ElectronicSignatureManager::new(project_path.to_path_buf());
+        for entity_type in ["document", "Document"] {
+            if let Ok(signatures) = signature_manager.list_signatures_for_entity(entity_type, &document.id) {
+                manifestations.extend(signatures.iter().map(SignatureManifestation::from));
+            }
+        }
+        manifestations
     }
 
     /// Convert document to JSON value
@@ -626,54 +626,6 @@ impl DocumentExporter {
         }
         "#
     }
-
-    /// Center text within given width
-    fn center_text(text: &str, width: usize) -> String {
-        if text.len() >= width {
-            return text.to_string();
-        }
-        
-        let padding = (width - text.len()) / 2;
-        format!("{}{}", " ".repeat(padding), text)
-    }
-
-    /// Format text content for fixed-width display
-    fn format_text_content(content: &str, width: usize) -> String {
-        let mut formatted = String::new();
-        
-        for line in content.lines() {
-            if line.len() <= width {
-                formatted.push_str(line);
-                formatted.push('\n');
-            } else {
-                // Simple word wrap
-                let words: Vec<&str> = line.split_whitespace().collect();
-                let mut current_line = String::new();
-                
-                for word in words {
-                    if current_line.len() + word.len() < width {
-                        if !current_line.is_empty() {
-                            current_line.push(' ');
-                        }
-                        current_line.push_str(word);
-                    } else {
-                        if !current_line.is_empty() {
-                            formatted.push_str(&current_line);
-                            formatted.push('\n');
-                        }
-                        current_line = word.to_string();
-                    }
-                }
-                
-                if !current_line.is_empty() {
-                    formatted.push_str(&current_line);
-                    formatted.push('\n');
-                }
-            }
-        }
-        
-        formatted
-    }
 }
 
 #[cfg(test)]
@@ -763,25 +715,6 @@ mod tests {
         assert!(html.contains("<pre><code>"));
     }
 
-    #[test]
-    fn test_center_text() {
-        assert_eq!(DocumentExporter::center_text("Test", 10), "   Test");
-        assert_eq!(DocumentExporter::center_text("LongTextThatExceedsWidth", 10), "LongTextThatExceedsWidth");
-        assert_eq!(DocumentExporter::center_text("Hello", 11), "   Hello");
-    }
-
-    #[test]
-    fn test_format_text_content() {
-        let content = "This is a very long line that should be wrapped at some point to fit within the specified width.";
-        let formatted = DocumentExporter::format_text_content(content, 20);
-        
-        // Should be split into multiple lines
-        assert!(formatted.lines().count() > 1);
-        for line in formatted.lines() {
-            assert!(line.len() <= 20);
-        }
-    }
-
     #[test]
     fn test_document_to_json() {
         let document = create_test_document();
@@ -875,11 +808,13 @@ mod tests {
         let test_dir = create_test_dir();
         let pdf_content = DocumentExporter::export_to_pdf(&document, &test_dir, &options).unwrap();
         
-        assert!(pdf_content.contains("Test Document"));
-        assert!(pdf_content.contains("DOCUMENT INFORMATION"));
-        assert!(pdf_content.contains("DOCUMENT CONTENT"));
-        assert!(pdf_content.contains("REGULATORY MAPPING"));
-        assert!(pdf_content.contains("Document ID:       DOC-20240115-001"));
+        assert!(pdf_content.starts_with("%PDF-1.4"));
+        assert!(pdf_content.contains("(Test Document)"));
+        assert!(pdf_content.contains("(Document Information)"));
+        assert!(pdf_content.contains("(Document Content)"));
+        assert!(pdf_content.contains("(Regulatory Mapping)"));
+        assert!(pdf_content.contains("(DOC-20240115-001)"));
+        assert!(pdf_content.contains("(Electronic Signature Manifestation)"));
         
         cleanup_test_dir(&test_dir);
     }
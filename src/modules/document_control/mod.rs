@@ -1,10 +1,14 @@
 pub mod approval;
 pub mod backup;
 pub mod checkout;
+pub mod content_store;
+pub mod diff;
 pub mod document;
 pub mod export;
 pub mod import;
 pub mod regulatory;
 pub mod service;
 pub mod template;
+pub mod template_data;
+pub mod template_engine;
 pub mod version;
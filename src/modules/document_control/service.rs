@@ -11,6 +11,8 @@ use crate::modules::document_control::document::{Document, DocumentType};
 use crate::modules::document_control::version::{DocumentVersionControl, VersionChangeType, DocumentVersion};
 use crate::modules::document_control::template::{TemplateManager, TemplateContext};
 use crate::modules::document_control::backup::DocumentBackupManager;
+use crate::modules::search;
+use crate::modules::storage::encryption;
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -120,6 +122,23 @@ impl DocumentService {
         Ok(document)
     }
 
+    /// Render a template against the current project data without creating a document
+    pub fn render_template(
+        &self,
+        template_name: &str,
+        project_name: String,
+        user: String,
+        custom_variables: HashMap<String, String>,
+    ) -> QmsResult<String> {
+        let template_manager = TemplateManager::new(self.project_path.clone());
+        let mut context = TemplateContext::new(project_name, user);
+        for (key, value) in custom_variables {
+            context.add_variable(key, value);
+        }
+        let (content, _) = template_manager.create_document_from_template(template_name, String::new(), context)?;
+        Ok(content)
+    }
+
     /// Add a document from an external file
     /// Phase 2.1.3 - Document Add from File
     pub fn add_document_from_file(
@@ -183,7 +202,7 @@ impl DocumentService {
                 .unwrap_or_default()
         ));
 
-        if let Err(e) = fs::copy(source_path, &archive_file) {
+        if let Err(e) = encryption::copy(source_path, &archive_file) {
             // Log warning but don't fail - document is already created
             eprintln!("Warning: Failed to archive original file: {e}");
         }
@@ -324,7 +343,7 @@ impl DocumentService {
         }
 
         // Load document metadata
-        let metadata_content = fs::read_to_string(metadata_file)?;
+        let metadata_content = encryption::read_to_string(metadata_file)?;
         let document = Document::from_json(&metadata_content)?;
 
         // Log audit entry
@@ -430,10 +449,15 @@ impl DocumentService {
 
         // Move document to archive
         let archive_doc_dir = archive_dir.join(document_id);
-        fs::rename(&doc_dir, &archive_doc_dir)?;
+        encryption::rename(&doc_dir, &archive_doc_dir)?;
 
         // Remove from index
         self.remove_from_document_index(document_id)?;
+        search::update_index_or_warn(
+            &self.project_path,
+            &[],
+            &[search::index_key(search::SearchEntity::Document, document_id)],
+        );
 
         // Log audit entry
         audit_log_delete("Document", document_id, &document_data)?;
@@ -449,7 +473,7 @@ impl DocumentService {
             return Ok(Vec::new());
         }
 
-        let index_content = fs::read_to_string(index_file)?;
+        let index_content = encryption::read_to_string(index_file)?;
         let index_data = crate::json_utils::JsonValue::parse(&index_content)?;
 
         let mut documents = Vec::new();
@@ -512,21 +536,23 @@ impl DocumentService {
 
     /// Advanced search with filtering capabilities
     pub fn search_documents_advanced(&self, query: &str, filters: &crate::commands::doc::SearchFilters) -> QmsResult<Vec<DocumentIndexEntry>> {
-        let all_documents = self.list_documents()?;
-        let query_lower = query.to_lowercase();
+        let mut all_documents = self.list_documents()?;
+
+        // Text search in title and content through the project search index,
+        // keeping its ranking
+        if !query.trim().is_empty() {
+            let mut search_query = search::SearchQuery::parse(query)?;
+            search_query.restrict_to(&[search::SearchEntity::Document]);
+            let hits = search::search_project(&self.project_path, &search_query, usize::MAX)?.hits;
+            let rank: HashMap<&str, usize> =
+                hits.iter().enumerate().map(|(position, hit)| (hit.key.as_str(), position)).collect();
+            all_documents.retain(|doc| rank.contains_key(doc.id.as_str()));
+            all_documents.sort_by_key(|doc| rank[doc.id.as_str()]);
+        }
 
         let filtered: Vec<DocumentIndexEntry> = all_documents
             .into_iter()
             .filter(|doc| {
-                // Text search in title and content
-                let title_match = doc.title.to_lowercase().contains(&query_lower);
-                let content_match = self.search_document_content(&doc.id, &query_lower).unwrap_or(false);
-                let text_match = title_match || content_match;
-                
-                if !text_match {
-                    return false;
-                }
-                
                 // Apply filters
                 if let Some(ref doc_type) = filters.document_type {
                     let matches_type = match doc_type.to_lowercase().as_str() {
@@ -568,21 +594,6 @@ impl DocumentService {
         Ok(filtered)
     }
 
-    /// Search within document content
-    fn search_document_content(&self, doc_id: &str, query: &str) -> QmsResult<bool> {
-        let doc_dir = self.project_path.join("documents").join(doc_id);
-        let content_file = doc_dir.join("content.md");
-        
-        if !content_file.exists() {
-            return Ok(false);
-        }
-        
-        match fs::read_to_string(&content_file) {
-            Ok(content) => Ok(content.to_lowercase().contains(query)),
-            Err(_) => Ok(false), // Ignore read errors for search
-        }
-    }
-
     /// Save document files to disk
     fn save_document_files(&self, document: &Document) -> QmsResult<()> {
         let doc_dir = self.project_path.join("documents").join(&document.id);
@@ -592,12 +603,10 @@ impl DocumentService {
 
         // Save content
         let content_file = doc_dir.join("content.md");
-        fs::write(content_file, &document.content)?;
+        encryption::write(content_file, &document.content)?;
 
-        // Save version snapshot
-        let version_file = doc_dir.join("versions").join(format!("{}.json", document.version));
-        let version_data = document.to_json();
-        fs::write(version_file, version_data)?;
+        // Record this state under the current version (content goes to the content store)
+        DocumentVersionControl::save_version_state(&self.project_path, document)?;
 
         Ok(())
     }
@@ -607,7 +616,8 @@ impl DocumentService {
         let doc_dir = self.project_path.join("documents").join(&document.id);
         let metadata_file = doc_dir.join("metadata.json");
         let metadata = document.to_json();
-        fs::write(metadata_file, metadata)?;
+        encryption::write(metadata_file, metadata)?;
+        search::update_index_or_warn(&self.project_path, &[search::records::document_record(document)], &[]);
         Ok(())
     }
 
@@ -618,7 +628,7 @@ impl DocumentService {
         // Load existing index or create new
         let mut documents = Vec::new();
         if index_file.exists() {
-            let index_content = fs::read_to_string(&index_file)?;
+            let index_content = encryption::read_to_string(&index_file)?;
             let index_data = crate::json_utils::JsonValue::parse(&index_content)?;
 
             if let JsonValue::Object(obj) = index_data {
@@ -700,7 +710,7 @@ impl DocumentService {
 
         // Save without schema wrapper for index file (it has its own structure)
         let json_string = index_json.json_to_string();
-        fs::write(&index_file, json_string)?;
+        encryption::write(&index_file, json_string)?;
         Ok(())
     }
 
@@ -737,7 +747,7 @@ impl DocumentService {
 
         // Save without schema wrapper for index file (it has its own structure)
         let json_string = index_json.json_to_string();
-        fs::write(&index_file, json_string)?;
+        encryption::write(&index_file, json_string)?;
         Ok(())
     }
 
@@ -752,7 +762,7 @@ impl DocumentService {
         let index_file = docs_dir.join("index.json");
         if !index_file.exists() {
             let empty_index = r#"{"version": "1.0", "data": []}"#;
-            fs::write(index_file, empty_index)?;
+            encryption::write(index_file, empty_index)?;
         }
 
         Ok(())
@@ -1134,7 +1144,7 @@ impl DocumentService {
         let backup_manager = DocumentBackupManager::new(self.project_path.clone());
 
         // Read document content for backup
-        let document_content = std::fs::read_to_string(&document.file_path)
+        let document_content = encryption::read_to_string(&document.file_path)
             .unwrap_or_else(|_| "Document content unavailable".to_string());
 
         let backup_metadata = backup_manager.create_backup(
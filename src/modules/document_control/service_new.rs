@@ -70,7 +70,7 @@ impl DocumentService {
         let detected_type = self.detect_document_type_from_extension(source_path, &doc_type)?;
 
         // Read file content
-        let content = match fs::read_to_string(source_path) {
+        let content = match crate::modules::storage::encryption::read_to_string(source_path) {
             Ok(content) => content,
             Err(e) => {
                 return Err(QmsError::validation_error(&format!(
@@ -100,7 +100,7 @@ impl DocumentService {
                 .unwrap_or_default()
         ));
 
-        if let Err(e) = fs::copy(source_path, &archive_file) {
+        if let Err(e) = crate::modules::storage::encryption::copy(source_path, &archive_file) {
             // Log warning but don't fail - document is already created
             eprintln!("Warning: Failed to archive original file: {}", e);
         }
@@ -244,7 +244,7 @@ impl DocumentService {
         }
 
         // Load document metadata
-        let metadata_content = fs::read_to_string(metadata_file)?;
+        let metadata_content = crate::modules::storage::encryption::read_to_string(metadata_file)?;
         let document = Document::from_json(&metadata_content)?;
 
         // Log audit entry
@@ -303,7 +303,7 @@ impl DocumentService {
 
         // Move document to archive
         let archive_doc_dir = archive_dir.join(document_id);
-        fs::rename(&doc_dir, &archive_doc_dir)?;
+        crate::modules::storage::encryption::rename(&doc_dir, &archive_doc_dir)?;
 
         // Remove from index
         self.remove_from_document_index(document_id)?;
@@ -322,7 +322,7 @@ impl DocumentService {
             return Ok(Vec::new());
         }
 
-        let index_content = fs::read_to_string(index_file)?;
+        let index_content = crate::modules::storage::encryption::read_to_string(index_file)?;
         let index_data = load_json_with_schema(&index_content, "1.0")?;
 
         let mut documents = Vec::new();
@@ -389,7 +389,7 @@ impl DocumentService {
 
         // Save content
         let content_file = doc_dir.join("content.md");
-        fs::write(content_file, &document.content)?;
+        crate::modules::storage::encryption::write(content_file, &document.content)?;
 
         // Save version snapshot
         let version_file = doc_dir.join("versions").join(format!("{}.json", document.version));
@@ -505,7 +505,7 @@ impl DocumentService {
         let index_file = docs_dir.join("index.json");
         if !index_file.exists() {
             let empty_index = r#"{"version": "1.0", "data": []}"#;
-            fs::write(index_file, empty_index)?;
+            crate::modules::storage::encryption::write(index_file, empty_index)?;
         }
 
         Ok(())
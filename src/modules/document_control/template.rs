@@ -1,9 +1,12 @@
 //! Document template management for QMS
 //! Phase 2.1.9 - Document Templates
-//! Provides predefined document templates with variable substitution
+//! Provides predefined document templates with variable substitution,
+//! conditionals, loops and live project data (see `template_engine`)
 
 use crate::error::{QmsError, QmsResult};
 use crate::modules::document_control::document::DocumentType;
+use crate::modules::document_control::template_data::ProjectTemplateData;
+use crate::modules::document_control::template_engine::{self, Template};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -72,7 +75,6 @@ impl TemplateContext {
 
 /// Template manager for handling document templates
 pub struct TemplateManager {
-    #[allow(dead_code)] // Used for path resolution in template operations
     project_path: PathBuf,
     templates_path: PathBuf,
 }
@@ -226,20 +228,33 @@ Describe the environment in which the software will operate.
 
 ### 3.1 Functional Requirements
 
+{{#each requirements where category=functional}}
+#### {{id}}: {{title}}
+- **Description**: {{description | default:"[Detailed description]"}}
+- **Priority**: {{priority | title}}
+- **Source**: {{source | default:"[Stakeholder/Standard]"}}
+- **Verification Method**: {{verification | title}}
+{{#if acceptance_criteria}}
+- **Acceptance Criteria**: {{acceptance_criteria}}
+{{/if}}
+
+{{else}}
 #### FR-001: [Requirement Name]
 - **Description**: [Detailed description]
 - **Priority**: High/Medium/Low
 - **Source**: [Stakeholder/Standard]
 - **Verification Method**: Test/Analysis/Inspection/Demonstration
 
-#### FR-002: [Requirement Name]
-- **Description**: [Detailed description]
-- **Priority**: High/Medium/Low
-- **Source**: [Stakeholder/Standard]
-- **Verification Method**: Test/Analysis/Inspection/Demonstration
-
+{{/each}}
 ### 3.2 Non-Functional Requirements
 
+{{#each requirements where category!=functional sort by category}}
+#### {{id}}: {{title}} ({{category | title}})
+- **Description**: {{description | default:"[Detailed description]"}}
+- **Priority**: {{priority | title}}
+- **Verification Method**: {{verification | title}}
+
+{{else}}
 #### NFR-001: Performance Requirements
 - **Description**: [Performance criteria]
 
@@ -249,6 +264,7 @@ Describe the environment in which the software will operate.
 #### NFR-003: Security Requirements
 - **Description**: [Security and privacy requirements]
 
+{{/each}}
 ---
 
 ## 4. External Interface Requirements
@@ -640,6 +656,17 @@ Describe how the risk management process is implemented.
 
 ### 3.3 Hazard Identification
 
+{{#each risks}}
+#### {{id}}: {{hazard}}
+- **Hazard Description**: {{hazard}}
+- **Hazardous Situation**: {{situation | default:"[Sequence of events leading to harm]"}}
+- **Harm**: {{harm | default:"[Type of harm that could result]"}}
+- **Severity**: {{severity}} ({{severity_level}})
+- **Occurrence**: {{occurrence}} ({{occurrence_level}})
+- **Detectability**: {{detectability}} ({{detectability_level}})
+- **RPN**: {{rpn}}
+
+{{else}}
 #### HAZARD-001: [Hazard Name]
 - **Hazard Description**: [Description of the hazard]
 - **Hazardous Situation**: [Sequence of events leading to harm]
@@ -649,21 +676,21 @@ Describe how the risk management process is implemented.
 - **Detectability**: [1-5 scale]
 - **RPN**: [Risk Priority Number]
 
-#### HAZARD-002: [Hazard Name]
-- **Hazard Description**: [Description of the hazard]
-- **Hazardous Situation**: [Sequence of events leading to harm]
-- **Harm**: [Type of harm that could result]
-- **Severity**: [1-5 scale]
-- **Occurrence**: [1-5 scale]
-- **Detectability**: [1-5 scale]
-- **RPN**: [Risk Priority Number]
-
+{{/each}}
 ---
 
 ## 4. Risk Evaluation
 
 ### 4.1 Risk Acceptability Assessment
+{{#if risks}}
+| Hazard | Severity | Occurrence | Detectability | RPN | Risk Level | Residual RPN | Residual Level |
+|--------|----------|------------|---------------|-----|------------|--------------|----------------|
+{{#each risks sort by rpn desc}}
+| {{id}} | {{severity_level}} | {{occurrence_level}} | {{detectability_level}} | {{rpn}} | {{level}} | {{residual_rpn}} | {{residual_level}} |
+{{/each}}
+{{else}}
 Evaluate each identified risk against acceptability criteria.
+{{/if}}
 
 ### 4.2 Risk Control Measures
 Document risk control measures for unacceptable risks.
@@ -674,18 +701,23 @@ Document risk control measures for unacceptable risks.
 
 ### 5.1 Risk Control Measures
 
-#### Control Measure CM-001
-- **For Hazard**: [HAZARD-XXX]
-- **Description**: [Control measure description]
-- **Implementation**: [How it will be implemented]
-- **Verification**: [How effectiveness will be verified]
+{{#each risks where mitigations as risk}}
+{{#each risk.mitigations}}
+#### Control Measure CM-{{risk.id}}-{{@number}}
+- **For Hazard**: {{risk.id}}
+- **Description**: {{description}}
+- **Implementation**: {{implementation | default:"[How it will be implemented]"}}
+- **Verification**: {{verification | default:"[How effectiveness will be verified]"}}
 
-#### Control Measure CM-002
+{{/each}}
+{{else}}
+#### Control Measure CM-001
 - **For Hazard**: [HAZARD-XXX]
 - **Description**: [Control measure description]
 - **Implementation**: [How it will be implemented]
 - **Verification**: [How effectiveness will be verified]
 
+{{/each}}
 ### 5.2 Residual Risk Analysis
 Analyze residual risks after control measures.
 
@@ -1127,21 +1159,12 @@ Define user training requirements.
 
     /// Substitute variables in template content
     pub fn substitute_variables(&self, content: &str, context: &TemplateContext) -> QmsResult<String> {
-        let mut result = content.to_string();
-        
-        // Get all variables from context
-        let variables = context.get_all_variables();
-        
-        // Replace each variable
-        for (var_name, var_value) in variables {
-            let placeholder = format!("{{{{{var_name}}}}}");
-            result = result.replace(&placeholder, &var_value);
-        }
-        
-        Ok(result)
+        // Context variables plus live project data (requirements, risks, ...)
+        let data = ProjectTemplateData::new(&self.project_path, &self.templates_path, context);
+        template_engine::render(content, &data)
     }
 
-    /// Validate template file
+    /// Validate template file, returning the variables it uses
     #[allow(dead_code)] // Future feature for template validation
     pub fn validate_template(&self, template_path: &Path) -> QmsResult<Vec<String>> {
         if !template_path.exists() {
@@ -1149,6 +1172,7 @@ Define user training requirements.
         }
 
         let content = fs::read_to_string(template_path)?;
+        Template::parse(&content)?;
         let variables = self.extract_variables(&content);
         
         Ok(variables)
@@ -1435,7 +1459,26 @@ mod tests {
         assert!(content.contains("Test Project"));
         assert!(content.contains("test_user"));
         assert!(matches!(doc_type, DocumentType::SoftwareRequirementsSpecification));
-        
+
+        cleanup_test_dir(&test_dir);
+    }
+
+    #[test]
+    fn test_default_templates_without_project_data() {
+        let test_dir = create_test_dir();
+        let manager = TemplateManager::new(test_dir.clone());
+        let context = TemplateContext::new("Test Project".to_string(), "test_user".to_string());
+
+        // With no requirements or risks yet, the data sections fall back to placeholders
+        let (srs, _) = manager.create_document_from_template("srs_template", String::new(), context.clone()).unwrap();
+        assert!(srs.contains("#### FR-001: [Requirement Name]"));
+        assert!(!srs.contains("{{"));
+
+        let (rmf, _) = manager.create_document_from_template("rmf_template", String::new(), context).unwrap();
+        assert!(rmf.contains("#### HAZARD-001: [Hazard Name]"));
+        assert!(rmf.contains("Evaluate each identified risk against acceptability criteria."));
+        assert!(!rmf.contains("{{"));
+
         cleanup_test_dir(&test_dir);
     }
 
@@ -0,0 +1,308 @@
+//! Live QMS data for document templates
+//!
+//! Binds the template language to the project: besides the context variables
+//! (`PROJECT_NAME`, `DATE`, `USER`, `VERSION` and any `--var`), templates can
+//! read these data sources at render time:
+//!
+//! - `requirements`: `id`, `title`, `description`, `category`, `priority`,
+//!   `status`, `source`, `rationale`, `acceptance_criteria`, `verification`,
+//!   `assigned_to`, `tags`, `linked_tests`, `linked_risks`
+//! - `risks`: `id`, `hazard`, `situation`, `harm`, `severity` and
+//!   `severity_level`, `occurrence` and `occurrence_level`, `detectability` and
+//!   `detectability_level`, `rpn`, `level`, `residual_rpn`, `residual_level`,
+//!   `status`, `category`, `priority`, `assigned_to`, `tags`, `mitigations`
+//!   (each with `description`, `implementation`, `status`, `verification`)
+//! - `tests`: `id`, `title`, `description`, `category`, `priority`, `author`,
+//!   `steps`, `tags`, `last_result`
+//! - `documents`: `id`, `title`, `type`, `version`, `status`, `author`,
+//!   `created_at`, `updated_at`
+//! - `project`: `id`, `name`, `version`, `description`
+//!
+//! Sources are loaded on first use, so a template that never mentions risks
+//! never reads them. Partials are looked up in `templates/partials/<name>.md`
+//! and then `templates/<name>.md`.
+
+use crate::error::{QmsError, QmsResult};
+use crate::json_utils::JsonValue;
+use crate::modules::document_control::service::DocumentService;
+use crate::modules::document_control::template::TemplateContext;
+use crate::modules::document_control::template_engine::TemplateData;
+use crate::modules::risk_manager::risk::{RiskItem, RiskManager};
+use crate::modules::traceability::requirement::{Requirement, RequirementManager};
+use crate::modules::traceability::test_case::{TestCase, TestCaseManager};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Names of the built-in data sources
+pub const DATA_SOURCES: &[&str] = &["requirements", "risks", "tests", "documents", "project"];
+
+/// Template data backed by a project directory
+pub struct ProjectTemplateData {
+    project_path: PathBuf,
+    templates_path: PathBuf,
+    variables: HashMap<String, String>,
+    sources: RefCell<HashMap<String, JsonValue>>,
+}
+
+impl ProjectTemplateData {
+    pub fn new(project_path: &Path, templates_path: &Path, context: &TemplateContext) -> Self {
+        Self {
+            project_path: project_path.to_path_buf(),
+            templates_path: templates_path.to_path_buf(),
+            variables: context.get_all_variables(),
+            sources: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn load_source(&self, name: &str) -> QmsResult<JsonValue> {
+        let path = self.project_path.as_path();
+        Ok(match name {
+            "requirements" => {
+                let manager = RequirementManager::new(path)?;
+                let mut requirements = manager.list_requirements();
+                requirements.sort_by(|a, b| a.req_id.cmp(&b.req_id));
+                JsonValue::Array(requirements.into_iter().map(requirement_value).collect())
+            }
+            "risks" => {
+                let mut risks = RiskManager::new(path)?.load_all_risks()?;
+                risks.sort_by(|a, b| a.hazard_id.cmp(&b.hazard_id));
+                JsonValue::Array(risks.iter().map(risk_value).collect())
+            }
+            "tests" if path.join("tests").is_dir() => {
+                let manager = TestCaseManager::new(path)?;
+                let mut tests = manager.list_test_cases();
+                tests.sort_by(|a, b| a.test_id.cmp(&b.test_id));
+                JsonValue::Array(tests.into_iter().map(test_case_value).collect())
+            }
+            "tests" => JsonValue::Array(Vec::new()),
+            "documents" => {
+                let documents = DocumentService::new(self.project_path.clone()).list_documents()?;
+                JsonValue::Array(
+                    documents
+                        .into_iter()
+                        .map(|entry| {
+                            object(vec![
+                                ("id", text(&entry.id)),
+                                ("title", text(&entry.title)),
+                                ("type", text(&entry.doc_type)),
+                                ("version", text(&entry.version)),
+                                ("status", text(&entry.status)),
+                                ("author", text(&entry.author)),
+                                ("created_at", text(&entry.created_at)),
+                                ("updated_at", text(&entry.updated_at)),
+                            ])
+                        })
+                        .collect(),
+                )
+            }
+            "project" => self.project_value()?,
+            _ => JsonValue::Null,
+        })
+    }
+
+    fn project_value(&self) -> QmsResult<JsonValue> {
+        let path = self.project_path.join("project.json");
+        if !path.exists() {
+            return Ok(JsonValue::Object(HashMap::new()));
+        }
+        let content = fs::read_to_string(&path)?;
+        let JsonValue::Object(fields) = JsonValue::parse(&content)? else {
+            return Err(QmsError::parse_error("project.json is not a JSON object"));
+        };
+        let keep = ["id", "name", "version", "description"];
+        Ok(JsonValue::Object(fields.into_iter().filter(|(key, _)| keep.contains(&key.as_str())).collect()))
+    }
+}
+
+impl TemplateData for ProjectTemplateData {
+    fn value(&self, name: &str) -> QmsResult<Option<JsonValue>> {
+        if let Some(value) = self.variables.get(name) {
+            return Ok(Some(text(value)));
+        }
+        if !DATA_SOURCES.contains(&name) {
+            return Ok(None);
+        }
+        if let Some(value) = self.sources.borrow().get(name) {
+            return Ok(Some(value.clone()));
+        }
+        let value = self.load_source(name)?;
+        self.sources.borrow_mut().insert(name.to_string(), value.clone());
+        Ok(Some(value))
+    }
+
+    fn partial(&self, name: &str) -> QmsResult<Option<String>> {
+        if name.contains("..") || name.starts_with('/') || name.contains('\\') {
+            return Err(QmsError::validation_error(&format!("Invalid partial name '{name}'")));
+        }
+        for candidate in [self.templates_path.join("partials"), self.templates_path.clone()] {
+            let path = candidate.join(format!("{name}.md"));
+            if path.is_file() {
+                return Ok(Some(fs::read_to_string(path)?));
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn text(value: &str) -> JsonValue {
+    JsonValue::String(value.to_string())
+}
+
+fn optional(value: &Option<String>) -> JsonValue {
+    value.as_deref().map_or(JsonValue::Null, text)
+}
+
+fn list(values: &[String]) -> JsonValue {
+    JsonValue::Array(values.iter().map(|value| text(value)).collect())
+}
+
+fn object(fields: Vec<(&str, JsonValue)>) -> JsonValue {
+    JsonValue::Object(fields.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+}
+
+fn requirement_value(requirement: &Requirement) -> JsonValue {
+    object(vec![
+        ("id", text(&requirement.req_id)),
+        ("uuid", text(&requirement.id)),
+        ("title", text(&requirement.title)),
+        ("description", text(&requirement.description)),
+        ("category", text(requirement.category.as_str())),
+        ("priority", text(requirement.priority.as_str())),
+        ("status", text(requirement.status.as_str())),
+        ("source", text(&requirement.source)),
+        ("rationale", text(&requirement.rationale)),
+        ("acceptance_criteria", text(&requirement.acceptance_criteria)),
+        ("verification", text(requirement.verification_method.as_str())),
+        ("assigned_to", optional(&requirement.assigned_to)),
+        ("created_by", text(&requirement.created_by)),
+        ("tags", list(&requirement.tags)),
+        ("linked_tests", list(&requirement.linked_tests)),
+        ("linked_risks", list(&requirement.linked_risks)),
+    ])
+}
+
+fn risk_value(risk: &RiskItem) -> JsonValue {
+    let mitigations = risk
+        .mitigation_measures
+        .iter()
+        .map(|measure| {
+            object(vec![
+                ("description", text(&measure.description)),
+                ("implementation", text(&measure.implementation)),
+                ("status", text(&measure.implementation_status)),
+                ("verification", text(&measure.verification_method)),
+                ("verification_status", text(&format!("{:?}", measure.verification_status))),
+            ])
+        })
+        .collect();
+
+    object(vec![
+        ("id", text(&risk.hazard_id)),
+        ("uuid", text(&risk.id)),
+        ("hazard", text(&risk.hazard_description)),
+        ("situation", text(&risk.hazardous_situation)),
+        ("harm", text(&risk.harm)),
+        ("severity", text(&format!("{:?}", risk.severity))),
+        ("severity_level", JsonValue::Number(risk.severity.clone() as u32 as f64)),
+        ("occurrence", text(&format!("{:?}", risk.occurrence))),
+        ("occurrence_level", JsonValue::Number(risk.occurrence.clone() as u32 as f64)),
+        ("detectability", text(&format!("{:?}", risk.detectability))),
+        ("detectability_level", JsonValue::Number(risk.detectability.clone() as u32 as f64)),
+        ("rpn", JsonValue::Number(f64::from(risk.risk_priority_number))),
+        ("level", text(&format!("{:?}", risk.initial_risk_level))),
+        ("residual_rpn", JsonValue::Number(f64::from(risk.residual_rpn))),
+        ("residual_level", text(&format!("{:?}", risk.residual_risk_level))),
+        ("residual_justification", optional(&risk.residual_risk_justification)),
+        ("status", text(&format!("{:?}", risk.risk_status))),
+        ("category", text(&risk.category)),
+        ("priority", text(&risk.priority)),
+        ("source", text(&risk.source)),
+        ("assigned_to", optional(&risk.assigned_to)),
+        ("tags", list(&risk.tags)),
+        ("mitigations", JsonValue::Array(mitigations)),
+    ])
+}
+
+fn test_case_value(test_case: &TestCase) -> JsonValue {
+    let steps = test_case
+        .steps
+        .iter()
+        .map(|step| object(vec![("action", text(&step.action)), ("expected", text(&step.expected_result))]))
+        .collect();
+    let last_result = test_case
+        .execution_results
+        .last()
+        .map_or(JsonValue::Null, |execution| text(&format!("{:?}", execution.overall_status)));
+
+    object(vec![
+        ("id", text(&test_case.test_id)),
+        ("title", text(&test_case.title)),
+        ("description", text(&test_case.description)),
+        ("category", text(&format!("{:?}", test_case.category))),
+        ("priority", text(&format!("{:?}", test_case.priority))),
+        ("author", text(&test_case.created_by)),
+        ("steps", JsonValue::Array(steps)),
+        ("tags", list(&test_case.tags)),
+        ("last_result", last_result),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::document_control::template_engine::render;
+    use crate::modules::traceability::requirement::RequirementCategory;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_requirements_and_partials_render_from_project() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_path = temp_dir.path();
+        fs::write(project_path.join("project.json"), r#"{"id":"p1","name":"Pump","version":"2.0"}"#).unwrap();
+
+        let mut manager = RequirementManager::new(project_path).unwrap();
+        manager
+            .create_requirement(
+                "p1".to_string(),
+                "REQ-001".to_string(),
+                "Occlusion alarm".to_string(),
+                "Alarm within 30 s of an occlusion".to_string(),
+                RequirementCategory::Safety,
+                "tester".to_string(),
+            )
+            .unwrap();
+        manager
+            .create_requirement(
+                "p1".to_string(),
+                "REQ-002".to_string(),
+                "Flow rate".to_string(),
+                "Deliver 0.1-999 ml/h".to_string(),
+                RequirementCategory::Functional,
+                "tester".to_string(),
+            )
+            .unwrap();
+
+        let templates_path = project_path.join("templates");
+        fs::create_dir_all(templates_path.join("partials")).unwrap();
+        fs::write(templates_path.join("partials").join("req_row.md"), "| {{id}} | {{title}} |\n").unwrap();
+
+        let context = TemplateContext::new("Pump".to_string(), "tester".to_string());
+        let data = ProjectTemplateData::new(project_path, &templates_path, &context);
+        let template = "\
+# {{PROJECT_NAME}} v{{project.version}}
+{{#each requirements where category=safety}}
+{{> req_row}}
+{{/each}}
+Functional: {{requirements where category=functional | count}}
+";
+        let out = render(template, &data).unwrap();
+        assert!(out.starts_with("# Pump v2.0\n"), "{out}");
+        assert!(out.contains("| REQ-001 | Occlusion alarm |\n"), "{out}");
+        assert!(!out.contains("Flow rate |"), "{out}");
+        assert!(out.contains("Functional: 1"), "{out}");
+
+        assert!(data.partial("../secrets").is_err());
+    }
+}
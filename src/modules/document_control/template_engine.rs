@@ -0,0 +1,1055 @@
+//! Template language for document templates
+//!
+//! A small Handlebars-style language rendered against `JsonValue` data:
+//!
+//! - `{{name}}`, `{{risk.harm}}` output a value; `{{name | upper}}` applies
+//!   filters (`upper`, `lower`, `title`, `trim`, `default:"-"`, `truncate:40`,
+//!   `join:", "`, `count`, `first`, `last`, `fixed:1`, `cell`)
+//! - `{{#if cond}} … {{else if cond}} … {{else}} … {{/if}}` where `cond` is a
+//!   value (truthy when non-empty) or a comparison such as `status=approved`
+//!   or `rpn>=50`, combined with `and`, `or` and `not`
+//! - `{{#each source where category=Safety sort by rpn desc limit 10 as r}} …
+//!   {{else}} … {{/each}}` loops; inside the loop bare names resolve against
+//!   the current item first, and `@index`, `@number`, `@first` and `@last` are
+//!   available
+//! - `{{requirements where category=safety | count}}` filters inline
+//! - `{{> partial}}` includes another template; `{{! comment }}` and
+//!   `{{!-- comment --}}` produce nothing
+//!
+//! Text comparisons ignore case. A tag that is alone on its line (apart from
+//! whitespace) is removed together with that line, so block tags do not leave
+//! blank lines. A plain `{{NAME}}` that resolves to nothing is kept as written,
+//! so unfilled placeholders stay visible in the generated document.
+
+use crate::error::{QmsError, QmsResult};
+use crate::json_utils::JsonValue;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Maximum nesting of `{{> partial}}` includes
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// Data a template is rendered against
+pub trait TemplateData {
+    /// Value of a top-level name, or `None` if the name is unknown
+    fn value(&self, name: &str) -> QmsResult<Option<JsonValue>>;
+
+    /// Source of the partial `name`, or `None` if there is no such partial
+    fn partial(&self, name: &str) -> QmsResult<Option<String>>;
+}
+
+impl TemplateData for HashMap<String, JsonValue> {
+    fn value(&self, name: &str) -> QmsResult<Option<JsonValue>> {
+        Ok(self.get(name).cloned())
+    }
+
+    fn partial(&self, _name: &str) -> QmsResult<Option<String>> {
+        Ok(None)
+    }
+}
+
+/// Parse and render `source` in one step
+pub fn render(source: &str, data: &dyn TemplateData) -> QmsResult<String> {
+    Template::parse(source)?.render(data)
+}
+
+/// Parsed template
+#[derive(Debug, Clone)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+impl Template {
+    /// Parse a template, reporting the line of the first syntax error
+    pub fn parse(source: &str) -> QmsResult<Self> {
+        let segments = trim_standalone(split_segments(source)?);
+        let mut parser = Parser { segments, position: 0 };
+        let (nodes, end) = parser.parse_nodes()?;
+        if let Some(tag) = end {
+            return Err(syntax_error(tag.line, &format!("unexpected '{{{{{}}}}}'", tag.content)));
+        }
+        Ok(Self { nodes })
+    }
+
+    pub fn render(&self, data: &dyn TemplateData) -> QmsResult<String> {
+        let mut renderer = Renderer { data, frames: Vec::new(), depth: 0 };
+        let mut out = String::new();
+        renderer.render_nodes(&self.nodes, &mut out)?;
+        Ok(out)
+    }
+}
+
+fn syntax_error(line: usize, message: &str) -> QmsError {
+    QmsError::validation_error(&format!("Template syntax error on line {line}: {message}"))
+}
+
+// ---------------------------------------------------------------------------
+// Segments: text and `{{ }}` tags
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+struct Tag {
+    content: String,
+    line: usize,
+    /// Whether the tag produces no output of its own (blocks, comments)
+    standalone_capable: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Text(String),
+    Tag(Tag),
+}
+
+fn split_segments(source: &str) -> QmsResult<Vec<Segment>> {
+    let mut segments = Vec::new();
+    let mut rest = source;
+    let mut line = 1;
+
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            segments.push(Segment::Text(rest[..start].to_string()));
+        }
+        line += rest[..start].matches('\n').count();
+        let after = &rest[start + 2..];
+        let (content, consumed) = if after.starts_with("!--") {
+            let end = after
+                .find("--}}")
+                .ok_or_else(|| syntax_error(line, "unterminated comment"))?;
+            (&after[..end], end + 4)
+        } else {
+            let end = after.find("}}").ok_or_else(|| syntax_error(line, "missing '}}'"))?;
+            (&after[..end], end + 2)
+        };
+        let content = content.trim().to_string();
+        let standalone_capable = content.starts_with(['#', '/', '!', '>']) || is_else(&content);
+        segments.push(Segment::Tag(Tag { content, line, standalone_capable }));
+        line += after[..consumed].matches('\n').count();
+        rest = &after[consumed..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest.to_string()));
+    }
+    Ok(segments)
+}
+
+fn is_else(content: &str) -> bool {
+    content == "else" || content.starts_with("else ")
+}
+
+/// Drop the line of block and comment tags that stand alone on it
+fn trim_standalone(mut segments: Vec<Segment>) -> Vec<Segment> {
+    let standalone: Vec<bool> = (0..segments.len()).map(|i| is_standalone(&segments, i)).collect();
+    for (i, _) in standalone.iter().enumerate().filter(|(_, standalone)| **standalone) {
+        if let Some(Segment::Text(text)) = i.checked_sub(1).map(|j| &mut segments[j]) {
+            let keep = text.rfind('\n').map_or(0, |newline| newline + 1);
+            text.truncate(keep);
+        }
+        if let Some(Segment::Text(text)) = segments.get_mut(i + 1) {
+            match text.find('\n') {
+                Some(newline) => {
+                    text.drain(..=newline);
+                }
+                None => text.clear(),
+            }
+        }
+    }
+    segments
+}
+
+fn is_standalone(segments: &[Segment], i: usize) -> bool {
+    let Segment::Tag(tag) = &segments[i] else { return false };
+    if !tag.standalone_capable {
+        return false;
+    }
+    let starts_line = match i.checked_sub(1).map(|j| &segments[j]) {
+        None => true,
+        Some(Segment::Text(text)) => {
+            let tail = text.rsplit('\n').next().unwrap_or("");
+            tail.trim().is_empty() && (text.contains('\n') || i == 1)
+        }
+        Some(Segment::Tag(_)) => false,
+    };
+    let ends_line = match segments.get(i + 1) {
+        None => true,
+        Some(Segment::Text(text)) => text.split('\n').next().unwrap_or("").trim().is_empty(),
+        Some(Segment::Tag(_)) => false,
+    };
+    starts_line && ends_line
+}
+
+// ---------------------------------------------------------------------------
+// Syntax tree
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+enum Node {
+    Text(String),
+    Output { selection: Selection, filters: Vec<Filter>, raw: String },
+    If { branches: Vec<(Condition, Vec<Node>)>, otherwise: Vec<Node> },
+    Each { selection: Selection, alias: Option<String>, body: Vec<Node>, empty: Vec<Node> },
+    Include { name: String, line: usize },
+}
+
+/// A value, optionally narrowed as a list with `where`, `sort by` and `limit`
+#[derive(Debug, Clone)]
+struct Selection {
+    value: Operand,
+    condition: Option<Condition>,
+    sort: Option<(String, bool)>,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+enum Operand {
+    Path(String),
+    Literal(String),
+}
+
+#[derive(Debug, Clone)]
+enum Condition {
+    Truthy(Operand),
+    Compare(Operand, CompareOp, Operand),
+    Not(Box<Condition>),
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone)]
+struct Filter {
+    name: String,
+    argument: Option<String>,
+}
+
+struct Parser {
+    segments: Vec<Segment>,
+    position: usize,
+}
+
+impl Parser {
+    /// Parse nodes up to the end of input or an `else` / closing tag, which is returned
+    fn parse_nodes(&mut self) -> QmsResult<(Vec<Node>, Option<Tag>)> {
+        let mut nodes = Vec::new();
+        while let Some(segment) = self.segments.get(self.position).cloned() {
+            self.position += 1;
+            let tag = match segment {
+                Segment::Text(text) => {
+                    if !text.is_empty() {
+                        nodes.push(Node::Text(text));
+                    }
+                    continue;
+                }
+                Segment::Tag(tag) => tag,
+            };
+
+            let content = tag.content.as_str();
+            if content.starts_with('/') || is_else(content) {
+                return Ok((nodes, Some(tag)));
+            }
+            if content.starts_with('!') {
+                continue;
+            }
+            if let Some(name) = content.strip_prefix('>') {
+                let name = name.trim();
+                if name.is_empty() {
+                    return Err(syntax_error(tag.line, "partial name is missing"));
+                }
+                nodes.push(Node::Include { name: name.to_string(), line: tag.line });
+            } else if let Some(condition) = content.strip_prefix("#if") {
+                nodes.push(self.parse_if(condition, &tag)?);
+            } else if let Some(selection) = content.strip_prefix("#each") {
+                nodes.push(self.parse_each(selection, &tag)?);
+            } else if content.starts_with('#') {
+                return Err(syntax_error(tag.line, &format!("unknown block '{content}'")));
+            } else {
+                let (selection, filters) = parse_output(&tokenize(content, tag.line)?, tag.line)?;
+                nodes.push(Node::Output { selection, filters, raw: format!("{{{{{content}}}}}") });
+            }
+        }
+        Ok((nodes, None))
+    }
+
+    fn parse_if(&mut self, condition: &str, tag: &Tag) -> QmsResult<Node> {
+        let mut branches = Vec::new();
+        let mut condition = parse_condition_text(condition, tag.line)?;
+        loop {
+            let (body, end) = self.parse_nodes()?;
+            branches.push((condition, body));
+            let end = end.ok_or_else(|| syntax_error(tag.line, "'{{#if}}' is never closed with '{{/if}}'"))?;
+            match end.content.as_str() {
+                "/if" => return Ok(Node::If { branches, otherwise: Vec::new() }),
+                "else" => {
+                    let (otherwise, end) = self.parse_nodes()?;
+                    return match end {
+                        Some(end) if end.content == "/if" => Ok(Node::If { branches, otherwise }),
+                        Some(end) => Err(syntax_error(end.line, &format!("expected '{{{{/if}}}}', found '{{{{{}}}}}'", end.content))),
+                        None => Err(syntax_error(tag.line, "'{{#if}}' is never closed with '{{/if}}'")),
+                    };
+                }
+                other => match other.strip_prefix("else if") {
+                    Some(next) => condition = parse_condition_text(next, end.line)?,
+                    None => {
+                        return Err(syntax_error(end.line, &format!("expected '{{{{/if}}}}', found '{{{{{other}}}}}'")))
+                    }
+                },
+            }
+        }
+    }
+
+    fn parse_each(&mut self, selection: &str, tag: &Tag) -> QmsResult<Node> {
+        let mut tokens = tokenize(selection, tag.line)?;
+        let alias = match tokens.as_slice() {
+            [.., Token::Word(keyword), Token::Word(name)] if keyword == "as" => {
+                let name = name.clone();
+                tokens.truncate(tokens.len() - 2);
+                Some(name)
+            }
+            _ => None,
+        };
+        let mut cursor = TokenCursor { tokens: &tokens, position: 0, line: tag.line };
+        let selection = cursor.parse_selection()?;
+        if let Some(token) = cursor.peek() {
+            return Err(syntax_error(tag.line, &format!("unexpected '{}' in '{{{{#each}}}}'", token.text())));
+        }
+
+        let unclosed = || syntax_error(tag.line, "'{{#each}}' is never closed with '{{/each}}'");
+        let (body, end) = self.parse_nodes()?;
+        let end = end.ok_or_else(unclosed)?;
+        let empty = match end.content.as_str() {
+            "/each" => Vec::new(),
+            "else" => {
+                let (empty, end) = self.parse_nodes()?;
+                match end {
+                    Some(end) if end.content == "/each" => empty,
+                    Some(end) => {
+                        return Err(syntax_error(end.line, &format!("expected '{{{{/each}}}}', found '{{{{{}}}}}'", end.content)))
+                    }
+                    None => return Err(unclosed()),
+                }
+            }
+            other => return Err(syntax_error(end.line, &format!("expected '{{{{/each}}}}', found '{{{{{other}}}}}'"))),
+        };
+        Ok(Node::Each { selection, alias, body, empty })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Expressions
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Op(CompareOp),
+    Pipe,
+    Colon,
+}
+
+impl Token {
+    fn text(&self) -> String {
+        match self {
+            Token::Word(word) => word.clone(),
+            Token::Quoted(text) => format!("\"{text}\""),
+            Token::Op(_) => "comparison".to_string(),
+            Token::Pipe => "|".to_string(),
+            Token::Colon => ":".to_string(),
+        }
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        matches!(self, Token::Word(w) if w == word)
+    }
+}
+
+fn tokenize(text: &str, line: usize) -> QmsResult<Vec<Token>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '"' | '\'' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&q| q == c)
+                    .ok_or_else(|| syntax_error(line, "unterminated string"))?;
+                tokens.push(Token::Quoted(chars[i + 1..i + 1 + end].iter().collect()));
+                i += end + 2;
+            }
+            '|' => {
+                tokens.push(Token::Pipe);
+                i += 1;
+            }
+            ':' => {
+                tokens.push(Token::Colon);
+                i += 1;
+            }
+            '=' | '!' | '<' | '>' => {
+                let (op, width) = match (c, next) {
+                    ('=', Some('=')) => (CompareOp::Eq, 2),
+                    ('=', _) => (CompareOp::Eq, 1),
+                    ('!', Some('=')) => (CompareOp::Ne, 2),
+                    ('<', Some('=')) => (CompareOp::Le, 2),
+                    ('<', _) => (CompareOp::Lt, 1),
+                    ('>', Some('=')) => (CompareOp::Ge, 2),
+                    ('>', _) => (CompareOp::Gt, 1),
+                    _ => return Err(syntax_error(line, "unexpected '!'")),
+                };
+                tokens.push(Token::Op(op));
+                i += width;
+            }
+            _ => {
+                let start = i;
+                while i < chars.len() && !chars[i].is_whitespace() && !"\"'|:=!<>".contains(chars[i]) {
+                    i += 1;
+                }
+                tokens.push(Token::Word(chars[start..i].iter().collect()));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct TokenCursor<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    line: usize,
+}
+
+impl<'a> TokenCursor<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<&'a Token> {
+        let token = self.tokens.get(self.position);
+        self.position += 1;
+        token
+    }
+
+    fn peek_word(&self, word: &str) -> bool {
+        self.peek().is_some_and(|token| token.is_word(word))
+    }
+
+    fn error(&self, message: &str) -> QmsError {
+        syntax_error(self.line, message)
+    }
+
+    fn parse_operand(&mut self) -> QmsResult<Operand> {
+        match self.next() {
+            Some(Token::Word(word)) => Ok(Operand::Path(word.clone())),
+            Some(Token::Quoted(text)) => Ok(Operand::Literal(text.clone())),
+            Some(other) => Err(self.error(&format!("expected a value, found '{}'", other.text()))),
+            None => Err(self.error("expected a value")),
+        }
+    }
+
+    /// Right-hand side of a comparison: bare words are literals
+    fn parse_literal(&mut self) -> QmsResult<Operand> {
+        match self.next() {
+            Some(Token::Word(word) | Token::Quoted(word)) => Ok(Operand::Literal(word.clone())),
+            _ => Err(self.error("expected a value to compare with")),
+        }
+    }
+
+    fn parse_selection(&mut self) -> QmsResult<Selection> {
+        let value = self.parse_operand()?;
+        let mut selection = Selection { value, condition: None, sort: None, limit: None };
+        loop {
+            if self.peek_word("where") {
+                self.position += 1;
+                selection.condition = Some(self.parse_condition()?);
+            } else if self.peek_word("sort") {
+                self.position += 1;
+                if !self.next().is_some_and(|token| token.is_word("by")) {
+                    return Err(self.error("expected 'sort by <field>'"));
+                }
+                let Some(Token::Word(field)) = self.next() else {
+                    return Err(self.error("expected a field after 'sort by'"));
+                };
+                let descending = match self.peek() {
+                    Some(token) if token.is_word("desc") => true,
+                    Some(token) if token.is_word("asc") => false,
+                    _ => {
+                        selection.sort = Some((field.clone(), false));
+                        continue;
+                    }
+                };
+                self.position += 1;
+                selection.sort = Some((field.clone(), descending));
+            } else if self.peek_word("limit") {
+                self.position += 1;
+                let limit = match self.next() {
+                    Some(Token::Word(n)) => n.parse().ok(),
+                    _ => None,
+                };
+                selection.limit = Some(limit.ok_or_else(|| self.error("expected a number after 'limit'"))?);
+            } else {
+                return Ok(selection);
+            }
+        }
+    }
+
+    fn parse_condition(&mut self) -> QmsResult<Condition> {
+        let mut alternatives = vec![self.parse_conjunction()?];
+        while self.peek_word("or") {
+            self.position += 1;
+            alternatives.push(self.parse_conjunction()?);
+        }
+        Ok(if alternatives.len() == 1 { alternatives.remove(0) } else { Condition::Or(alternatives) })
+    }
+
+    fn parse_conjunction(&mut self) -> QmsResult<Condition> {
+        let mut terms = vec![self.parse_comparison()?];
+        while self.peek_word("and") {
+            self.position += 1;
+            terms.push(self.parse_comparison()?);
+        }
+        Ok(if terms.len() == 1 { terms.remove(0) } else { Condition::And(terms) })
+    }
+
+    fn parse_comparison(&mut self) -> QmsResult<Condition> {
+        if self.peek_word("not") {
+            self.position += 1;
+            return Ok(Condition::Not(Box::new(self.parse_comparison()?)));
+        }
+        let left = self.parse_operand()?;
+        match self.peek() {
+            Some(Token::Op(op)) => {
+                self.position += 1;
+                Ok(Condition::Compare(left, *op, self.parse_literal()?))
+            }
+            _ => Ok(Condition::Truthy(left)),
+        }
+    }
+}
+
+fn parse_condition_text(text: &str, line: usize) -> QmsResult<Condition> {
+    let tokens = tokenize(text, line)?;
+    let mut cursor = TokenCursor { tokens: &tokens, position: 0, line };
+    let condition = cursor.parse_condition()?;
+    match cursor.peek() {
+        Some(token) => Err(syntax_error(line, &format!("unexpected '{}' in condition", token.text()))),
+        None => Ok(condition),
+    }
+}
+
+fn parse_output(tokens: &[Token], line: usize) -> QmsResult<(Selection, Vec<Filter>)> {
+    let mut cursor = TokenCursor { tokens, position: 0, line };
+    let selection = cursor.parse_selection()?;
+    let mut filters = Vec::new();
+    while let Some(token) = cursor.next() {
+        if *token != Token::Pipe {
+            return Err(syntax_error(line, &format!("unexpected '{}'", token.text())));
+        }
+        let Some(Token::Word(name)) = cursor.next() else {
+            return Err(syntax_error(line, "expected a filter name after '|'"));
+        };
+        let argument = if cursor.peek() == Some(&Token::Colon) {
+            cursor.position += 1;
+            match cursor.next() {
+                Some(Token::Word(arg) | Token::Quoted(arg)) => Some(arg.clone()),
+                _ => return Err(syntax_error(line, &format!("filter '{name}' is missing its argument"))),
+            }
+        } else {
+            None
+        };
+        if !FILTERS.contains(&name.as_str()) {
+            return Err(syntax_error(line, &format!("unknown filter '{name}'")));
+        }
+        filters.push(Filter { name: name.clone(), argument });
+    }
+    Ok((selection, filters))
+}
+
+const FILTERS: &[&str] =
+    &["upper", "lower", "title", "trim", "default", "truncate", "join", "count", "first", "last", "fixed", "cell"];
+
+// ---------------------------------------------------------------------------
+// Rendering
+// ---------------------------------------------------------------------------
+
+struct Frame {
+    item: JsonValue,
+    alias: Option<String>,
+    index: usize,
+    len: usize,
+}
+
+struct Renderer<'a> {
+    data: &'a dyn TemplateData,
+    frames: Vec<Frame>,
+    depth: usize,
+}
+
+impl Renderer<'_> {
+    fn render_nodes(&mut self, nodes: &[Node], out: &mut String) -> QmsResult<()> {
+        for node in nodes {
+            match node {
+                Node::Text(text) => out.push_str(text),
+                Node::Output { selection, filters, raw } => {
+                    let value = self.select(selection)?;
+                    match value {
+                        None if filters.is_empty() => out.push_str(raw),
+                        value => {
+                            let value = filters.iter().try_fold(value.unwrap_or(JsonValue::Null), |value, filter| {
+                                apply_filter(value, filter)
+                            })?;
+                            out.push_str(&display(&value));
+                        }
+                    }
+                }
+                Node::If { branches, otherwise } => {
+                    let mut chosen = otherwise;
+                    for (condition, body) in branches {
+                        if self.evaluate(condition)? {
+                            chosen = body;
+                            break;
+                        }
+                    }
+                    self.render_nodes(chosen, out)?;
+                }
+                Node::Each { selection, alias, body, empty } => {
+                    let items = match self.select(selection)? {
+                        Some(JsonValue::Array(items)) => items,
+                        None | Some(JsonValue::Null) => Vec::new(),
+                        Some(single) => vec![single],
+                    };
+                    if items.is_empty() {
+                        self.render_nodes(empty, out)?;
+                    }
+                    let len = items.len();
+                    for (index, item) in items.into_iter().enumerate() {
+                        self.frames.push(Frame { item, alias: alias.clone(), index, len });
+                        let result = self.render_nodes(body, out);
+                        self.frames.pop();
+                        result?;
+                    }
+                }
+                Node::Include { name, line } => {
+                    if self.depth >= MAX_INCLUDE_DEPTH {
+                        return Err(syntax_error(*line, &format!("partial '{name}' is nested too deeply")));
+                    }
+                    let source = self
+                        .data
+                        .partial(name)?
+                        .ok_or_else(|| QmsError::not_found(&format!("Template partial not found: {name}")))?;
+                    let partial = Template::parse(&source)?;
+                    self.depth += 1;
+                    let result = self.render_nodes(&partial.nodes, out);
+                    self.depth -= 1;
+                    result?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Value of a selection; `None` when a path does not resolve
+    fn select(&mut self, selection: &Selection) -> QmsResult<Option<JsonValue>> {
+        let Some(value) = self.operand(&selection.value)? else {
+            return Ok(None);
+        };
+        if selection.condition.is_none() && selection.sort.is_none() && selection.limit.is_none() {
+            return Ok(Some(value));
+        }
+
+        let items = match value {
+            JsonValue::Array(items) => items,
+            JsonValue::Null => Vec::new(),
+            single => vec![single],
+        };
+        let mut selected = Vec::new();
+        for item in items {
+            let keep = match &selection.condition {
+                Some(condition) => self.with_item(item.clone(), |renderer| renderer.evaluate(condition))?,
+                None => true,
+            };
+            if keep {
+                selected.push(item);
+            }
+        }
+        if let Some((field, descending)) = &selection.sort {
+            selected.sort_by(|a, b| {
+                let ordering = compare_values(&lookup_path(a, field), &lookup_path(b, field));
+                if *descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+        }
+        if let Some(limit) = selection.limit {
+            selected.truncate(limit);
+        }
+        Ok(Some(JsonValue::Array(selected)))
+    }
+
+    fn with_item<R>(&mut self, item: JsonValue, f: impl FnOnce(&mut Self) -> QmsResult<R>) -> QmsResult<R> {
+        self.frames.push(Frame { item, alias: None, index: 0, len: 1 });
+        let result = f(self);
+        self.frames.pop();
+        result
+    }
+
+    fn operand(&self, operand: &Operand) -> QmsResult<Option<JsonValue>> {
+        match operand {
+            Operand::Literal(text) => Ok(Some(JsonValue::String(text.clone()))),
+            Operand::Path(path) => self.resolve(path),
+        }
+    }
+
+    fn resolve(&self, path: &str) -> QmsResult<Option<JsonValue>> {
+        if let Ok(number) = path.parse::<f64>() {
+            return Ok(Some(JsonValue::Number(number)));
+        }
+        let (root, rest) = match path.split_once('.') {
+            Some((root, rest)) => (root, Some(rest)),
+            None => (path, None),
+        };
+
+        let base = if let Some(special) = root.strip_prefix('@') {
+            let Some(frame) = self.frames.last() else { return Ok(None) };
+            Some(match special {
+                "index" => JsonValue::Number(frame.index as f64),
+                "number" => JsonValue::Number((frame.index + 1) as f64),
+                "first" => JsonValue::Bool(frame.index == 0),
+                "last" => JsonValue::Bool(frame.index + 1 == frame.len),
+                _ => return Ok(None),
+            })
+        } else if root == "this" {
+            self.frames.last().map(|frame| frame.item.clone())
+        } else if let Some(frame) = self.frames.iter().rev().find(|frame| frame.alias.as_deref() == Some(root)) {
+            Some(frame.item.clone())
+        } else if let Some(value) = self.frames.iter().rev().find_map(|frame| match &frame.item {
+            JsonValue::Object(fields) => fields.get(root).cloned(),
+            _ => None,
+        }) {
+            Some(value)
+        } else {
+            self.data.value(root)?
+        };
+
+        Ok(match (base, rest) {
+            (Some(base), Some(rest)) => Some(lookup_path(&base, rest)),
+            (base, None) => base,
+            (None, Some(_)) => None,
+        })
+    }
+
+    fn evaluate(&self, condition: &Condition) -> QmsResult<bool> {
+        Ok(match condition {
+            Condition::Truthy(operand) => self.operand(operand)?.as_ref().is_some_and(truthy),
+            Condition::Not(inner) => !self.evaluate(inner)?,
+            Condition::And(terms) => {
+                for term in terms {
+                    if !self.evaluate(term)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+            Condition::Or(alternatives) => {
+                for alternative in alternatives {
+                    if self.evaluate(alternative)? {
+                        return Ok(true);
+                    }
+                }
+                false
+            }
+            Condition::Compare(left, op, right) => {
+                let left = self.operand(left)?.unwrap_or(JsonValue::Null);
+                let right = self.operand(right)?.unwrap_or(JsonValue::Null);
+                compare(&left, *op, &right)
+            }
+        })
+    }
+}
+
+/// Follow a dotted path through nested objects
+fn lookup_path(value: &JsonValue, path: &str) -> JsonValue {
+    let mut current = value;
+    for part in path.split('.') {
+        match current {
+            JsonValue::Object(fields) => match fields.get(part) {
+                Some(next) => current = next,
+                None => return JsonValue::Null,
+            },
+            _ => return JsonValue::Null,
+        }
+    }
+    current.clone()
+}
+
+fn truthy(value: &JsonValue) -> bool {
+    match value {
+        JsonValue::Null => false,
+        JsonValue::Bool(b) => *b,
+        JsonValue::Number(n) => *n != 0.0,
+        JsonValue::String(s) => !s.is_empty() && s != "false",
+        JsonValue::Array(items) => !items.is_empty(),
+        JsonValue::Object(fields) => !fields.is_empty(),
+    }
+}
+
+fn as_number(value: &JsonValue) -> Option<f64> {
+    match value {
+        JsonValue::Number(n) => Some(*n),
+        JsonValue::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Numeric order when both sides are numbers, case-insensitive text order otherwise
+fn compare_values(a: &JsonValue, b: &JsonValue) -> Ordering {
+    match (as_number(a), as_number(b)) {
+        (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+        _ => display(a).to_lowercase().cmp(&display(b).to_lowercase()),
+    }
+}
+
+fn compare(left: &JsonValue, op: CompareOp, right: &JsonValue) -> bool {
+    // A list equals a value when it contains it (`tags=safety`)
+    if let JsonValue::Array(items) = left {
+        let contains = items.iter().any(|item| compare_values(item, right) == Ordering::Equal);
+        match op {
+            CompareOp::Eq => return contains,
+            CompareOp::Ne => return !contains,
+            _ => {}
+        }
+    }
+    let ordering = compare_values(left, right);
+    match op {
+        CompareOp::Eq => ordering == Ordering::Equal,
+        CompareOp::Ne => ordering != Ordering::Equal,
+        CompareOp::Lt => ordering == Ordering::Less,
+        CompareOp::Le => ordering != Ordering::Greater,
+        CompareOp::Gt => ordering == Ordering::Greater,
+        CompareOp::Ge => ordering != Ordering::Less,
+    }
+}
+
+/// Text form of a value as it appears in the document
+fn display(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null | JsonValue::Object(_) => String::new(),
+        JsonValue::Bool(b) => if *b { "Yes" } else { "No" }.to_string(),
+        JsonValue::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => format!("{}", *n as i64),
+        JsonValue::Number(n) => n.to_string(),
+        JsonValue::String(s) => s.clone(),
+        JsonValue::Array(items) => items.iter().map(display).collect::<Vec<_>>().join(", "),
+    }
+}
+
+fn apply_filter(value: JsonValue, filter: &Filter) -> QmsResult<JsonValue> {
+    let argument = filter.argument.as_deref();
+    let text = |value: &JsonValue| JsonValue::String(display(value));
+    Ok(match filter.name.as_str() {
+        "upper" => JsonValue::String(display(&value).to_uppercase()),
+        "lower" => JsonValue::String(display(&value).to_lowercase()),
+        "trim" => JsonValue::String(display(&value).trim().to_string()),
+        "title" => JsonValue::String(title_case(&display(&value))),
+        "default" => {
+            if truthy(&value) {
+                value
+            } else {
+                JsonValue::String(argument.unwrap_or("").to_string())
+            }
+        }
+        "truncate" => {
+            let width = filter_number(filter)?.unwrap_or(80.0) as usize;
+            let full = display(&value);
+            if full.chars().count() <= width {
+                JsonValue::String(full)
+            } else {
+                let kept: String = full.chars().take(width.saturating_sub(1)).collect();
+                JsonValue::String(format!("{}…", kept.trim_end()))
+            }
+        }
+        "join" => match &value {
+            JsonValue::Array(items) => {
+                JsonValue::String(items.iter().map(display).collect::<Vec<_>>().join(argument.unwrap_or(", ")))
+            }
+            other => text(other),
+        },
+        "count" => JsonValue::Number(match &value {
+            JsonValue::Array(items) => items.len(),
+            JsonValue::Object(fields) => fields.len(),
+            JsonValue::Null => 0,
+            JsonValue::String(s) => s.chars().count(),
+            _ => 1,
+        } as f64),
+        "first" => match value {
+            JsonValue::Array(items) => items.into_iter().next().unwrap_or(JsonValue::Null),
+            other => other,
+        },
+        "last" => match value {
+            JsonValue::Array(items) => items.into_iter().last().unwrap_or(JsonValue::Null),
+            other => other,
+        },
+        "fixed" => {
+            let places = filter_number(filter)?.unwrap_or(0.0) as usize;
+            match as_number(&value) {
+                Some(n) => JsonValue::String(format!("{n:.places$}")),
+                None => text(&value),
+            }
+        }
+        // Safe inside a Markdown table cell
+        "cell" => JsonValue::String(
+            display(&value).replace('|', "\\|").split_whitespace().collect::<Vec<_>>().join(" "),
+        ),
+        other => return Err(QmsError::validation_error(&format!("Unknown template filter '{other}'"))),
+    })
+}
+
+fn filter_number(filter: &Filter) -> QmsResult<Option<f64>> {
+    filter
+        .argument
+        .as_deref()
+        .map(|arg| {
+            arg.parse().map_err(|_| {
+                QmsError::validation_error(&format!("Filter '{}' needs a number, got '{arg}'", filter.name))
+            })
+        })
+        .transpose()
+}
+
+fn title_case(text: &str) -> String {
+    text.split(['_', ' '])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(fields: &[(&str, JsonValue)]) -> JsonValue {
+        JsonValue::Object(fields.iter().map(|(k, v)| (k.to_string(), v.clone())).collect())
+    }
+
+    fn text(s: &str) -> JsonValue {
+        JsonValue::String(s.to_string())
+    }
+
+    fn sample_data() -> HashMap<String, JsonValue> {
+        let requirement = |id: &str, title: &str, category: &str, tags: &[&str]| {
+            object(&[
+                ("id", text(id)),
+                ("title", text(title)),
+                ("category", text(category)),
+                ("tags", JsonValue::Array(tags.iter().map(|t| text(t)).collect())),
+            ])
+        };
+        let risk = |id: &str, rpn: f64| object(&[("id", text(id)), ("rpn", JsonValue::Number(rpn))]);
+
+        let mut data = HashMap::new();
+        data.insert("PROJECT_NAME".to_string(), text("Infusion Pump"));
+        data.insert(
+            "requirements".to_string(),
+            JsonValue::Array(vec![
+                requirement("REQ-002", "Occlusion alarm | audible", "safety", &["alarm"]),
+                requirement("REQ-001", "Flow rate", "functional", &[]),
+                requirement("REQ-003", "Alarm latency", "Safety", &["alarm", "timing"]),
+            ]),
+        );
+        data.insert("risks".to_string(), JsonValue::Array(vec![risk("HAZ-001", 12.0), risk("HAZ-002", 80.0)]));
+        data
+    }
+
+    #[test]
+    fn test_variables_filters_and_unknown_placeholders() {
+        let data = sample_data();
+        let out = render("{{PROJECT_NAME}} / {{PROJECT_NAME | upper}} / {{OWNER}} / {{OWNER | default:\"n/a\"}}", &data).unwrap();
+        assert_eq!(out, "Infusion Pump / INFUSION PUMP / {{OWNER}} / n/a");
+
+        let out = render("{{requirements where category=safety | count}} safety of {{requirements | count}}", &data).unwrap();
+        assert_eq!(out, "2 safety of 3");
+        assert_eq!(render("{{PROJECT_NAME | truncate:8}}", &data).unwrap(), "Infusio…");
+    }
+
+    #[test]
+    fn test_each_with_where_sort_and_else() {
+        let data = sample_data();
+        let template = "\
+| ID | Title |
+|----|-------|
+{{#each requirements where category=Safety sort by id as req}}
+| {{req.id}} | {{title | cell}}{{#if tags=timing}} ⏱{{/if}} |
+{{else}}
+| - | none |
+{{/each}}
+Total: {{#each risks sort by rpn desc}}{{id}}={{rpn}}{{#if not @last}}, {{/if}}{{/each}}
+";
+        let out = render(template, &data).unwrap();
+        assert_eq!(
+            out,
+            "\
+| ID | Title |
+|----|-------|
+| REQ-002 | Occlusion alarm \\| audible |
+| REQ-003 | Alarm latency ⏱ |
+Total: HAZ-002=80, HAZ-001=12
+"
+        );
+
+        let out = render("{{#each requirements where category=usability}}x{{else}}none{{/each}}", &data).unwrap();
+        assert_eq!(out, "none");
+    }
+
+    #[test]
+    fn test_conditions() {
+        let data = sample_data();
+        let template = "{{#each risks}}{{#if rpn>=50 and id!=HAZ-009}}high{{else if rpn > 10}}medium{{else}}low{{/if}} {{/each}}";
+        assert_eq!(render(template, &data).unwrap(), "medium high ");
+        assert_eq!(render("{{#if missing or not PROJECT_NAME}}a{{else}}b{{/if}}", &data).unwrap(), "b");
+    }
+
+    #[test]
+    fn test_partials_and_syntax_errors() {
+        struct WithPartials(HashMap<String, JsonValue>);
+        impl TemplateData for WithPartials {
+            fn value(&self, name: &str) -> QmsResult<Option<JsonValue>> {
+                self.0.value(name)
+            }
+            fn partial(&self, name: &str) -> QmsResult<Option<String>> {
+                Ok(match name {
+                    "header" => Some("# {{PROJECT_NAME}}\n".to_string()),
+                    "loop" => Some("{{> loop}}".to_string()),
+                    _ => None,
+                })
+            }
+        }
+        let data = WithPartials(sample_data());
+        assert_eq!(render("{{!-- generated --}}\n{{> header}}\nBody", &data).unwrap(), "# Infusion Pump\nBody");
+        assert!(render("{{> loop}}", &data).is_err());
+        assert!(render("{{> missing}}", &data).is_err());
+
+        let error = Template::parse("line one\n{{#each risks}}\n{{id}}\n").unwrap_err().to_string();
+        assert!(error.contains("line 2"), "{error}");
+        assert!(Template::parse("{{#if a}}x{{/each}}").is_err());
+        assert!(Template::parse("{{name | shout}}").is_err());
+        assert!(Template::parse("{{/if}}").is_err());
+    }
+}
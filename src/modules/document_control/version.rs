@@ -1,13 +1,25 @@
 //! Document version control functionality
 //! Phase 2.1.4 - Document Version Control Implementation
 //! Implements semantic versioning with complete version history
+//!
+//! Each `documents/<id>/versions/<ver>.json` is a small manifest holding the
+//! version metadata and document fields; the document text is kept once in
+//! the shared content store (see `content_store`). Version files written
+//! before the content store (full document JSON) are still read, and
+//! `migrate_to_content_store` converts them.
 
 use crate::error::{QmsError, QmsResult};
 use crate::json_utils::{JsonValue, JsonSerializable};
+use crate::modules::document_control::content_store::{ChunkList, ContentStore, GC_GRACE_PERIOD};
 use crate::modules::document_control::document::Document;
-use std::collections::HashMap;
-use std::path::Path;
+use crate::modules::storage::encryption;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
 use std::fs;
+use std::time::Duration;
+
+/// Format tag of version files whose content lives in the content store
+const MANIFEST_FORMAT: &str = "qms-version-v2";
 
 /// Version change type for determining increment rules
 #[derive(Debug, Clone, PartialEq)]
@@ -144,14 +156,38 @@ impl DocumentVersionControl {
             checksum: document.checksum.clone(),
         };
 
-        // Save version file with document data
+        // Save version manifest; the content goes to the content store
         let version_file = versions_dir.join(format!("{}.json", document.version));
-        let version_data = Self::create_version_file_content(document, &version_entry)?;
-        fs::write(version_file, version_data)?;
+        Self::write_version_file(project_path, &version_file, document, &version_entry)?;
 
         Ok(version_entry)
     }
 
+    /// Record the current state of a document under its current version
+    ///
+    /// Keeps the metadata of an existing snapshot for that version; the first
+    /// save of a version records it as created now by the document author.
+    pub fn save_version_state(project_path: &Path, document: &Document) -> QmsResult<()> {
+        let versions_dir = project_path.join("documents").join(&document.id).join("versions");
+        fs::create_dir_all(&versions_dir)?;
+        let version_file = versions_dir.join(format!("{}.json", document.version));
+
+        let version_entry = match Self::load_version_from_file(&version_file, &document.version, &document.id) {
+            Ok(existing) => existing,
+            _ => DocumentVersion {
+                version: document.version.clone(),
+                document_id: document.id.clone(),
+                created_at: crate::utils::current_timestamp(),
+                created_by: document.created_by.clone(),
+                change_type: VersionChangeType::Patch,
+                change_description: "Initial version".to_string(),
+                checksum: document.checksum.clone(),
+            },
+        };
+        let version_entry = DocumentVersion { checksum: document.checksum.clone(), ..version_entry };
+        Self::write_version_file(project_path, &version_file, document, &version_entry)
+    }
+
     /// Get specific version of a document
     pub fn get_document_version(project_path: &Path, document_id: &str, version: &str) -> QmsResult<Document> {
         let version_file = project_path
@@ -164,17 +200,7 @@ impl DocumentVersionControl {
             return Err(QmsError::not_found(&format!("Document version {version} not found")));
         }
 
-        let content = fs::read_to_string(version_file)?;
-        let json_value = JsonValue::parse(&content)?;
-        
-        if let JsonValue::Object(obj) = json_value {
-            if let Some(doc_data) = obj.get("document") {
-                let doc_json = doc_data.json_to_string();
-                return Document::from_json(&doc_json).map_err(|e| QmsError::validation_error(&e.to_string()));
-            }
-        }
-
-        Err(QmsError::validation_error("Invalid version file format"))
+        Self::read_version_document(project_path, &version_file)
     }
 
     /// List all available versions for a document
@@ -235,13 +261,20 @@ impl DocumentVersionControl {
         version: &str,
         document_id: &str,
     ) -> QmsResult<DocumentVersion> {
-        let content = fs::read_to_string(file_path)?;
+        let content = encryption::read_to_string(file_path)?;
         let json_value = JsonValue::parse(&content)?;
         
         if let JsonValue::Object(obj) = json_value {
-            let metadata = obj.get("metadata")
-                .and_then(|v| if let JsonValue::Object(m) = v { Some(m) } else { None })
-                .ok_or_else(|| QmsError::validation_error("Missing metadata in version file"))?;
+            // Early version files hold only the document itself
+            let legacy_metadata;
+            let metadata = match obj.get("metadata") {
+                Some(JsonValue::Object(m)) => m,
+                Some(_) => return Err(QmsError::validation_error("Invalid metadata in version file")),
+                None => {
+                    legacy_metadata = Self::legacy_metadata(&obj);
+                    &legacy_metadata
+                }
+            };
 
             let created_at = metadata.get("created_at")
                 .and_then(|v| if let JsonValue::Number(n) = v { Some(*n as u64) } else { None })
@@ -283,19 +316,16 @@ impl DocumentVersionControl {
         Err(QmsError::validation_error("Invalid version file format"))
     }
 
-    /// Helper function to create version file content
-    fn create_version_file_content(
-        document: &Document,
-        version_entry: &DocumentVersion,
-    ) -> QmsResult<String> {
-        let mut root = HashMap::new();
-        
-        // Add document data
-        let doc_json = document.to_json();
-        let doc_value = JsonValue::parse(&doc_json)?;
-        root.insert("document".to_string(), doc_value);
+    /// Metadata for a version file that only holds the document
+    fn legacy_metadata(document: &HashMap<String, JsonValue>) -> HashMap<String, JsonValue> {
+        ["created_by", "checksum"]
+            .into_iter()
+            .filter_map(|key| document.get(key).map(|value| (key.to_string(), value.clone())))
+            .collect()
+    }
 
-        // Add version metadata
+    /// Helper function to create version file content
+    fn create_version_metadata(version_entry: &DocumentVersion) -> JsonValue {
         let mut metadata = HashMap::new();
         metadata.insert("created_at".to_string(), JsonValue::Number(version_entry.created_at as f64));
         metadata.insert("created_by".to_string(), JsonValue::String(version_entry.created_by.clone()));
@@ -309,14 +339,254 @@ impl DocumentVersionControl {
         };
         metadata.insert("change_type".to_string(), JsonValue::String(change_type_str.to_string()));
 
-        root.insert("metadata".to_string(), JsonValue::Object(metadata));
+        JsonValue::Object(metadata)
+    }
+
+    /// Write a version manifest, storing the document content in the content store
+    fn write_version_file(
+        project_path: &Path,
+        version_file: &Path,
+        document: &Document,
+        version_entry: &DocumentVersion,
+    ) -> QmsResult<()> {
+        let metadata = Self::create_version_metadata(version_entry);
+        let manifest = Self::build_manifest(project_path, document, metadata)?;
+        write_replacing(version_file, &manifest)
+    }
+
+    fn build_manifest(project_path: &Path, document: &Document, metadata: JsonValue) -> QmsResult<String> {
+        let JsonValue::Object(mut fields) = JsonValue::parse(&document.to_json())? else {
+            return Err(QmsError::validation_error("Document did not serialize to a JSON object"));
+        };
+        fields.remove("content");
+        let content = ContentStore::new(project_path).put_text(&document.content)?;
+
+        let mut root = HashMap::new();
+        root.insert("format".to_string(), JsonValue::String(MANIFEST_FORMAT.to_string()));
+        root.insert("document".to_string(), JsonValue::Object(fields));
+        root.insert("content".to_string(), content.to_json());
+        root.insert("metadata".to_string(), metadata);
+        Ok(JsonValue::Object(root).json_to_string())
+    }
+
+    /// Read the document held by a version file in any of its formats
+    fn read_version_document(project_path: &Path, version_file: &Path) -> QmsResult<Document> {
+        let content = encryption::read_to_string(version_file)?;
+        let JsonValue::Object(mut obj) = JsonValue::parse(&content)? else {
+            return Err(QmsError::validation_error("Invalid version file format"));
+        };
 
-        // Convert to JSON string
-        let root_value = JsonValue::Object(root);
-        Ok(root_value.json_to_string())
+        let document = match obj.remove("document") {
+            // Content-store manifest: reassemble and verify the content
+            Some(JsonValue::Object(mut fields)) if obj.contains_key("format") => {
+                let chunks = ChunkList::from_json(obj.get("content").unwrap_or(&JsonValue::Null))?;
+                let text = ContentStore::new(project_path).get_text(&chunks).map_err(|e| {
+                    QmsError::validation_error(&format!("Version file {}: {e}", version_file.display()))
+                })?;
+                fields.insert("content".to_string(), JsonValue::String(text));
+                JsonValue::Object(fields)
+            }
+            Some(document @ JsonValue::Object(_)) => document,
+            Some(_) => return Err(QmsError::validation_error("Invalid version file format")),
+            None if obj.contains_key("id") && obj.contains_key("content") => JsonValue::Object(obj),
+            None => return Err(QmsError::validation_error("Invalid version file format")),
+        };
+
+        Document::from_json(&document.json_to_string()).map_err(|e| QmsError::validation_error(&e.to_string()))
+    }
+
+    /// Every version file in the project, including archived documents
+    fn version_files(project_path: &Path) -> QmsResult<Vec<PathBuf>> {
+        let documents_dir = project_path.join("documents");
+        let mut files = Vec::new();
+        for parent in [documents_dir.clone(), documents_dir.join("archive")] {
+            if !parent.is_dir() {
+                continue;
+            }
+            for entry in fs::read_dir(&parent)? {
+                let versions_dir = entry?.path().join("versions");
+                if !versions_dir.is_dir() {
+                    continue;
+                }
+                for version in fs::read_dir(&versions_dir)? {
+                    let path = version?.path();
+                    if path.extension().and_then(|s| s.to_str()) == Some("json") {
+                        files.push(path);
+                    }
+                }
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    fn is_manifest(version_file: &Path) -> QmsResult<bool> {
+        let content = encryption::read_to_string(version_file)?;
+        let JsonValue::Object(obj) = JsonValue::parse(&content)? else {
+            return Ok(false);
+        };
+        Ok(matches!(obj.get("format"), Some(JsonValue::String(format)) if format == MANIFEST_FORMAT))
+    }
+
+    /// Convert version files that embed the full document into content-store manifests
+    pub fn migrate_to_content_store(project_path: &Path, dry_run: bool) -> QmsResult<MigrationReport> {
+        let store = ContentStore::new(project_path);
+        let objects_before = store.stats()?.bytes;
+        let mut report = MigrationReport::default();
+
+        for version_file in Self::version_files(project_path)? {
+            if Self::is_manifest(&version_file)? {
+                report.already_migrated += 1;
+                continue;
+            }
+            let size = fs::metadata(&version_file)?.len();
+            let result = Self::migrate_version_file(project_path, &version_file, dry_run);
+            match result {
+                Ok(new_size) => {
+                    report.migrated += 1;
+                    report.bytes_before += size;
+                    report.bytes_after += new_size;
+                }
+                Err(e) => report.failures.push(format!("{}: {e}", version_file.display())),
+            }
+        }
+
+        if !dry_run {
+            report.bytes_after += store.stats()?.bytes.saturating_sub(objects_before);
+            if report.migrated > 0 {
+                crate::modules::audit_logger::audit_log_action(
+                    "DOCUMENT_VERSIONS_MIGRATED",
+                    "ContentStore",
+                    &format!("{} version file(s)", report.migrated),
+                )?;
+            }
+        }
+        Ok(report)
+    }
+
+    /// Migrate one version file, returning the size of its manifest
+    fn migrate_version_file(project_path: &Path, version_file: &Path, dry_run: bool) -> QmsResult<u64> {
+        let version = version_file.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+        let document = Self::read_version_document(project_path, version_file)?;
+        let entry = Self::load_version_from_file(version_file, version, &document.id)?;
+        if dry_run {
+            return Ok(0);
+        }
+        Self::write_version_file(project_path, version_file, &document, &entry)?;
+
+        // Prove the manifest reads back to the same document before moving on
+        let stored = Self::read_version_document(project_path, version_file)?;
+        if stored.content != document.content {
+            return Err(QmsError::validation_error("Migrated content does not match the original"));
+        }
+        Ok(fs::metadata(version_file)?.len())
+    }
+
+    /// Check every version manifest and stored object against its hash
+    pub fn verify_content_store(project_path: &Path) -> QmsResult<StoreVerification> {
+        let store = ContentStore::new(project_path);
+        let mut verification = StoreVerification::default();
+        let mut referenced = BTreeSet::new();
+
+        for version_file in Self::version_files(project_path)? {
+            verification.versions_checked += 1;
+            if let Err(e) = Self::read_version_document(project_path, &version_file) {
+                verification.problems.push(format!("{}: {e}", version_file.display()));
+            }
+            referenced.extend(Self::referenced_objects(&version_file).unwrap_or_default());
+        }
+
+        for hash in store.list()? {
+            verification.objects_checked += 1;
+            if let Err(e) = store.get(&hash) {
+                verification.problems.push(e.to_string());
+            }
+            if !referenced.contains(&hash) {
+                verification.unreferenced.push(hash);
+            }
+        }
+        Ok(verification)
+    }
+
+    /// Delete stored objects that no version file refers to, returning how many were removed
+    ///
+    /// Objects younger than `GC_GRACE_PERIOD` are kept: their version file may
+    /// still be in the middle of being written.
+    pub fn collect_unreferenced_objects(project_path: &Path) -> QmsResult<usize> {
+        Self::collect_objects_older_than(project_path, GC_GRACE_PERIOD)
+    }
+
+    fn collect_objects_older_than(project_path: &Path, min_age: Duration) -> QmsResult<usize> {
+        let store = ContentStore::new(project_path);
+        let mut referenced = BTreeSet::new();
+        for version_file in Self::version_files(project_path)? {
+            // Never collect while a manifest cannot be read: its objects would look unreferenced
+            referenced.extend(Self::referenced_objects(&version_file)?);
+        }
+
+        let mut removed = 0;
+        for hash in store.list()? {
+            if !referenced.contains(&hash) && store.remove_if_older(&hash, min_age)? {
+                removed += 1;
+            }
+        }
+        if removed > 0 {
+            crate::modules::audit_logger::audit_log_action(
+                "CONTENT_STORE_GC",
+                "ContentStore",
+                &format!("{removed} object(s)"),
+            )?;
+        }
+        Ok(removed)
+    }
+
+    fn referenced_objects(version_file: &Path) -> QmsResult<Vec<String>> {
+        let content = encryption::read_to_string(version_file)?;
+        match JsonValue::parse(&content)? {
+            JsonValue::Object(obj) if obj.contains_key("format") => {
+                Ok(ChunkList::from_json(obj.get("content").unwrap_or(&JsonValue::Null))?.chunks)
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Outcome of converting version files to content-store manifests
+#[derive(Debug, Clone, Default)]
+pub struct MigrationReport {
+    pub migrated: usize,
+    pub already_migrated: usize,
+    pub failures: Vec<String>,
+    /// Size of the migrated version files before migration
+    pub bytes_before: u64,
+    /// Size of their manifests plus the objects added for them
+    pub bytes_after: u64,
+}
+
+/// Outcome of checking the content store
+#[derive(Debug, Clone, Default)]
+pub struct StoreVerification {
+    pub versions_checked: usize,
+    pub objects_checked: usize,
+    pub problems: Vec<String>,
+    /// Objects no version refers to (reclaimable)
+    pub unreferenced: Vec<String>,
+}
+
+impl StoreVerification {
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
     }
 }
 
+/// Replace a file without leaving it half-written
+fn write_replacing(path: &Path, contents: &str) -> QmsResult<()> {
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, encryption::seal(path, contents)?)?;
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -378,4 +648,99 @@ mod tests {
         assert_eq!(minor_result.unwrap(), "1.1.0");
         assert_eq!(major_result.unwrap(), "2.0.0");
     }
+
+    fn sample_document(version: &str, content: &str) -> Document {
+        Document::new(
+            "0b8c7a4e-5a1f-4d8e-9f3a-2c6d1e7b9a10".to_string(),
+            "6f1e2d3c-4b5a-4697-8877-665544332211".to_string(),
+            "Software Design Description".to_string(),
+            content.to_string(),
+            crate::modules::document_control::document::DocumentType::SoftwareDesignDescription,
+            version.to_string(),
+            "designer".to_string(),
+            "documents/sdd/content.md".to_string(),
+        )
+        .unwrap()
+    }
+
+    fn design_text(revision: usize) -> String {
+        (0..300).map(|i| format!("### Component {i}\nRevision {} of the design for component {i}.\n\n",
+            if i == 150 { revision } else { 1 })).collect()
+    }
+
+    #[test]
+    fn test_versions_share_content_store_objects() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project_path = temp_dir.path();
+
+        let first = sample_document("1.0.0", &design_text(1));
+        DocumentVersionControl::create_version_snapshot(project_path, &first, VersionChangeType::Patch, "Initial", "designer").unwrap();
+        let objects_after_first = ContentStore::new(project_path).stats().unwrap().objects;
+
+        let second = sample_document("1.0.1", &design_text(2));
+        DocumentVersionControl::create_version_snapshot(project_path, &second, VersionChangeType::Patch, "Component 150", "designer").unwrap();
+        let objects_after_second = ContentStore::new(project_path).stats().unwrap().objects;
+        assert!(objects_after_second - objects_after_first <= 2, "{objects_after_first} -> {objects_after_second}");
+
+        // Manifests are small and read back to the full document
+        let manifest = project_path.join("documents").join(&first.id).join("versions").join("1.0.1.json");
+        assert!(fs::metadata(&manifest).unwrap().len() < second.content.len() as u64 / 4);
+        let restored = DocumentVersionControl::get_document_version(project_path, &first.id, "1.0.1").unwrap();
+        assert_eq!(restored.content, second.content);
+        let history = DocumentVersionControl::get_version_history(project_path, &first.id).unwrap();
+        assert_eq!(history[1].change_description, "Component 150");
+
+        // A damaged object is reported on read and by verification
+        let hash = DocumentVersionControl::referenced_objects(&manifest).unwrap()[0].clone();
+        let object = project_path.join("documents").join("objects").join(&hash[..2]).join(&hash[2..]);
+        fs::write(&object, "corrupted").unwrap();
+        assert!(DocumentVersionControl::get_document_version(project_path, &first.id, "1.0.1").is_err());
+        assert!(!DocumentVersionControl::verify_content_store(project_path).unwrap().is_valid());
+    }
+
+    #[test]
+    fn test_migrate_legacy_version_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project_path = temp_dir.path();
+        let document = sample_document("1.0.0", &design_text(1));
+        let versions_dir = project_path.join("documents").join(&document.id).join("versions");
+        fs::create_dir_all(&versions_dir).unwrap();
+
+        // Both earlier formats: the bare document, and the document wrapped with metadata
+        fs::write(versions_dir.join("1.0.0.json"), document.to_json()).unwrap();
+        let mut wrapped = HashMap::new();
+        wrapped.insert("document".to_string(), JsonValue::parse(&sample_document("1.1.0", &design_text(3)).to_json()).unwrap());
+        let mut metadata = HashMap::new();
+        metadata.insert("created_by".to_string(), JsonValue::String("reviewer".to_string()));
+        metadata.insert("change_description".to_string(), JsonValue::String("Status change".to_string()));
+        metadata.insert("change_type".to_string(), JsonValue::String("Minor".to_string()));
+        wrapped.insert("metadata".to_string(), JsonValue::Object(metadata));
+        fs::write(versions_dir.join("1.1.0.json"), JsonValue::Object(wrapped).json_to_string()).unwrap();
+
+        let dry_run = DocumentVersionControl::migrate_to_content_store(project_path, true).unwrap();
+        assert_eq!(dry_run.migrated, 2);
+        assert_eq!(ContentStore::new(project_path).stats().unwrap().objects, 0);
+
+        let report = DocumentVersionControl::migrate_to_content_store(project_path, false).unwrap();
+        assert_eq!((report.migrated, report.failures.len()), (2, 0));
+        assert!(report.bytes_after < report.bytes_before);
+
+        let history = DocumentVersionControl::get_version_history(project_path, &document.id).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].created_by, "reviewer");
+        assert_eq!(history[1].change_type, VersionChangeType::Minor);
+        let restored = DocumentVersionControl::get_document_version(project_path, &document.id, "1.1.0").unwrap();
+        assert_eq!(restored.content, design_text(3));
+
+        let again = DocumentVersionControl::migrate_to_content_store(project_path, false).unwrap();
+        assert_eq!((again.migrated, again.already_migrated), (0, 2));
+        let verification = DocumentVersionControl::verify_content_store(project_path).unwrap();
+        assert!(verification.is_valid() && verification.unreferenced.is_empty(), "{verification:?}");
+
+        // Objects left behind by a removed version are collected once past the grace period
+        fs::remove_file(versions_dir.join("1.1.0.json")).unwrap();
+        assert_eq!(DocumentVersionControl::collect_unreferenced_objects(project_path).unwrap(), 0);
+        assert!(DocumentVersionControl::collect_objects_older_than(project_path, Duration::ZERO).unwrap() > 0);
+        assert!(DocumentVersionControl::get_document_version(project_path, &document.id, "1.0.0").is_ok());
+    }
 }
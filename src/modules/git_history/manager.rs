@@ -0,0 +1,224 @@
+//! Git History Manager
+//!
+//! Reads a local repository with the `git` command-line client (no remote is
+//! contacted) and keeps the imported history in `git/history.json`, so reports
+//! can be regenerated without access to the repository.
+
+use crate::json_utils::JsonSerializable;
+use crate::modules::audit_logger::functions::audit_log_action;
+use crate::modules::storage::encryption;
+use crate::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::record::{extract_references, GitCommit, GitHistory, GitTag};
+
+const RECORD_SEPARATOR: char = '\u{1e}';
+const FIELD_SEPARATOR: char = '\u{1f}';
+
+/// Imports and serves the project's Git history
+pub struct GitHistoryManager {
+    history_path: PathBuf,
+}
+
+impl GitHistoryManager {
+    pub fn new(project_path: &Path) -> QmsResult<Self> {
+        Ok(Self {
+            history_path: project_path.join("git").join("history.json"),
+        })
+    }
+
+    /// Read the history reachable from HEAD and every tag of the repository at `repository`
+    pub fn import(&self, repository: &Path) -> QmsResult<GitHistory> {
+        let repository = repository
+            .canonicalize()
+            .map_err(|_| QmsError::not_found(&format!("Repository {} not found", repository.display())))?;
+
+        let head = run_git(&repository, &["rev-parse", "--verify", "HEAD"])?.trim().to_string();
+        let log = run_git(
+            &repository,
+            &[
+                "log",
+                "--topo-order",
+                "--no-color",
+                "--no-renames",
+                "--name-only",
+                "--format=%x1e%H%x1f%P%x1f%an%x1f%ae%x1f%at%x1f%B%x1f",
+                "HEAD",
+                "--tags",
+            ],
+        )?;
+        let tags = run_git(
+            &repository,
+            &["for-each-ref", "--format=%(refname:short)%1f%(objectname)%1f%(*objectname)", "refs/tags"],
+        )?;
+
+        let history = GitHistory {
+            repository: repository.to_string_lossy().to_string(),
+            imported_at: crate::utils::current_iso8601_timestamp(),
+            head,
+            commits: parse_log(&log)?,
+            tags: parse_tags(&tags),
+        };
+
+        if let Some(dir) = self.history_path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        encryption::atomic_write(&self.history_path, &history.to_json())?;
+        let referencing = history.commits.iter().filter(|commit| !commit.references.is_empty()).count();
+        let _ = audit_log_action(
+            "GIT_HISTORY_IMPORTED",
+            "GitHistory",
+            &format!(
+                "{}: {} commit(s), {} tag(s), {referencing} referencing QMS records",
+                history.repository,
+                history.commits.len(),
+                history.tags.len()
+            ),
+        );
+        Ok(history)
+    }
+
+    /// The most recently imported history
+    pub fn load(&self) -> QmsResult<GitHistory> {
+        if !self.history_path.is_file() {
+            return Err(QmsError::not_found("No Git history imported. Run 'qms git import <REPOSITORY>' first"));
+        }
+        let content = encryption::read_to_string(&self.history_path)?;
+        GitHistory::from_json(&content).map_err(|e| QmsError::parse_error(&format!("Invalid Git history: {e}")))
+    }
+}
+
+/// Run `git` in `repository` and return its standard output
+fn run_git(repository: &Path, args: &[&str]) -> QmsResult<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repository)
+        .args(["-c", "core.quotepath=off"])
+        .args(args)
+        .output()
+        .map_err(|e| QmsError::io_error(&format!("Failed to run git: {e}")))?;
+
+    if !output.status.success() {
+        return Err(QmsError::validation_error(&format!(
+            "git {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parse `git log --name-only` output written with the import format
+fn parse_log(output: &str) -> QmsResult<Vec<GitCommit>> {
+    let mut commits = Vec::new();
+    for record in output.split(RECORD_SEPARATOR).filter(|record| !record.trim().is_empty()) {
+        let fields: Vec<&str> = record.splitn(7, FIELD_SEPARATOR).collect();
+        let [hash, parents, author, email, timestamp, message, files] = fields[..] else {
+            return Err(QmsError::parse_error("Unexpected git log output"));
+        };
+        commits.push(GitCommit {
+            hash: hash.trim().to_string(),
+            parents: parents.split_whitespace().map(str::to_string).collect(),
+            author: author.to_string(),
+            email: email.to_string(),
+            timestamp: timestamp.trim().parse().unwrap_or(0),
+            subject: message.lines().next().unwrap_or("").trim().to_string(),
+            references: extract_references(message),
+            files: files.lines().map(str::trim).filter(|file| !file.is_empty()).map(str::to_string).collect(),
+        });
+    }
+    Ok(commits)
+}
+
+/// Parse `git for-each-ref` output; annotated tags resolve to the commit they tag
+fn parse_tags(output: &str) -> Vec<GitTag> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(FIELD_SEPARATOR);
+            let name = fields.next()?.trim();
+            let object = fields.next()?.trim();
+            let peeled = fields.next().unwrap_or("").trim();
+            (!name.is_empty()).then(|| GitTag {
+                name: name.to_string(),
+                commit: if peeled.is_empty() { object } else { peeled }.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::git_history::record::UNRELEASED;
+    use tempfile::TempDir;
+
+    fn git(repository: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(repository)
+            .args(["-c", "user.name=Dev", "-c", "user.email=dev@example.com", "-c", "commit.gpgsign=false"])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(status.status.success(), "{}", String::from_utf8_lossy(&status.stderr));
+    }
+
+    fn commit(repository: &Path, file: &str, message: &str) {
+        fs::write(repository.join(file), message).unwrap();
+        git(repository, &["add", file]);
+        git(repository, &["commit", "-q", "-m", message]);
+    }
+
+    #[test]
+    fn test_extract_references() {
+        assert_eq!(
+            extract_references("Fix flow limit (REQ-014, HAZ-002)\n\nCloses CAPA-7; see ECR-001 and REQ-014. TC-001 PREQ-9"),
+            vec!["REQ-014", "HAZ-002", "CAPA-7", "ECR-001"]
+        );
+        assert!(extract_references("Bump REQ-abc and RISK-").is_empty());
+    }
+
+    #[test]
+    fn test_import_history_and_releases() {
+        if Command::new("git").arg("--version").output().is_err() {
+            return;
+        }
+        let project = TempDir::new().unwrap();
+        let repository = TempDir::new().unwrap();
+        let repo = repository.path();
+        git(repo, &["init", "-q"]);
+        commit(repo, "pump.rs", "Add pump driver for REQ-001");
+        git(repo, &["tag", "v1.0"]);
+        commit(repo, "alarm.rs", "Occlusion alarm\n\nImplements REQ-002, mitigates HAZ-001");
+        commit(repo, "pump.rs", "Tune flow limit per ECR-001 (REQ-001)");
+        git(repo, &["tag", "-a", "v1.1", "-m", "Release 1.1"]);
+        commit(repo, "README", "Docs");
+
+        let manager = GitHistoryManager::new(project.path()).unwrap();
+        let history = manager.import(repo).unwrap();
+        assert_eq!(manager.load().unwrap(), history);
+        assert_eq!(history.commits.len(), 4);
+        assert_eq!(history.tags.len(), 2);
+        assert_eq!(history.commits[1].references, vec!["ECR-001", "REQ-001"]);
+        assert_eq!(history.commits[1].files, vec!["pump.rs"]);
+        assert_eq!(history.commits[2].subject, "Occlusion alarm");
+
+        let between = history.commits_between(Some("v1.0"), "v1.1").unwrap();
+        assert_eq!(between.len(), 2);
+        assert_eq!(history.commits_referencing("REQ-001").len(), 2);
+        assert_eq!(history.release_of(&history.commits[2].hash), Some("v1.1"));
+        assert_eq!(history.release_of(&history.commits[0].hash), None);
+
+        let releases = history.releases();
+        let summary: Vec<(&str, Option<&str>, usize)> = releases
+            .iter()
+            .map(|release| (release.name.as_str(), release.previous.as_deref(), release.commits.len()))
+            .collect();
+        assert_eq!(summary, vec![(UNRELEASED, Some("v1.1"), 1), ("v1.1", Some("v1.0"), 2), ("v1.0", None, 1)]);
+        assert_eq!(releases[1].references(), vec!["ECR-001", "HAZ-001", "REQ-001", "REQ-002"]);
+        assert!(history.resolve("v2.0").is_err());
+    }
+}
@@ -0,0 +1,20 @@
+//! Git History Module
+//!
+//! Software change history per IEC 62304 Section 8 and FDA 21 CFR 820.30(i):
+//! commits, tags and changed files of a local Git repository are imported and
+//! associated with requirements, risks, CAPAs and change requests through the
+//! record IDs their commit messages reference, so impact analysis can report
+//! what changed between releases and the DHF can list changes per release.
+
+pub mod manager;
+pub mod record;
+
+#[allow(unused_imports)]
+pub use manager::GitHistoryManager;
+#[allow(unused_imports)]
+pub use record::{extract_references, GitCommit, GitHistory, GitTag, RecordKind, Release, UNRELEASED};
+
+/// Whether a Git history has been imported into the project
+pub fn has_history(project_path: &std::path::Path) -> bool {
+    project_path.join("git").join("history.json").is_file()
+}
@@ -0,0 +1,326 @@
+//! Git History Records
+//!
+//! Imported commits and tags, the QMS record IDs commit messages reference and
+//! the release-by-release view of the history used by impact analysis and the DHF.
+
+use crate::json_utils::{JsonError, JsonSerializable, JsonValue};
+use crate::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// Name of the pseudo-release holding commits after the newest tag
+pub const UNRELEASED: &str = "Unreleased";
+
+/// Kind of QMS record a commit message can reference
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RecordKind {
+    Requirement,
+    Risk,
+    Capa,
+    ChangeRequest,
+}
+
+impl RecordKind {
+    /// Kind of record an ID names, from its prefix (REQ-, RISK-/HAZ-, CAPA-, ECR-)
+    pub fn of(id: &str) -> Option<Self> {
+        let (prefix, number) = id.split_once('-')?;
+        if !number.starts_with(|c: char| c.is_ascii_digit())
+            || !number.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return None;
+        }
+        match prefix {
+            "REQ" => Some(Self::Requirement),
+            "RISK" | "HAZ" => Some(Self::Risk),
+            "CAPA" => Some(Self::Capa),
+            "ECR" => Some(Self::ChangeRequest),
+            _ => None,
+        }
+    }
+
+    pub const fn display_name(&self) -> &'static str {
+        match self {
+            Self::Requirement => "Requirement",
+            Self::Risk => "Risk",
+            Self::Capa => "CAPA",
+            Self::ChangeRequest => "Change Request",
+        }
+    }
+}
+
+/// Record IDs referenced in a commit message, in order of first mention
+pub fn extract_references(message: &str) -> Vec<String> {
+    let mut references: Vec<String> = Vec::new();
+    for token in message.split(|c: char| !(c.is_ascii_alphanumeric() || c == '-')) {
+        let token = token.trim_end_matches('-');
+        if RecordKind::of(token).is_some() && !references.iter().any(|id| id == token) {
+            references.push(token.to_string());
+        }
+    }
+    references
+}
+
+/// One commit as imported from the repository
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitCommit {
+    pub hash: String,
+    pub parents: Vec<String>,
+    pub author: String,
+    pub email: String,
+    pub timestamp: u64, // Author date, Unix seconds
+    pub subject: String,
+    pub references: Vec<String>, // REQ-001, HAZ-002, CAPA-003, ECR-004...
+    pub files: Vec<String>,      // Paths changed, relative to the repository root
+}
+
+impl GitCommit {
+    pub fn short_hash(&self) -> &str {
+        &self.hash[..self.hash.len().min(10)]
+    }
+
+    /// Author date as YYYY-MM-DD
+    pub fn date(&self) -> String {
+        crate::utils::iso8601_from_unix(self.timestamp)[..10].to_string()
+    }
+}
+
+/// A tag and the commit it points to
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitTag {
+    pub name: String,
+    pub commit: String,
+}
+
+/// Commits between one tag and the previous one
+#[derive(Debug, Clone)]
+pub struct Release<'a> {
+    pub name: String, // Tag name, or UNRELEASED
+    pub previous: Option<String>,
+    pub commits: Vec<&'a GitCommit>,
+}
+
+impl Release<'_> {
+    /// Record IDs referenced by the release's commits, sorted
+    pub fn references(&self) -> Vec<String> {
+        let mut references: Vec<String> = self
+            .commits
+            .iter()
+            .flat_map(|commit| commit.references.iter().cloned())
+            .collect();
+        references.sort();
+        references.dedup();
+        references
+    }
+}
+
+/// Snapshot of a repository's history
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitHistory {
+    pub repository: String,
+    pub imported_at: String,
+    pub head: String,
+    pub commits: Vec<GitCommit>, // Newest first, parents after children
+    pub tags: Vec<GitTag>,
+}
+
+impl GitHistory {
+    /// Commit named by a tag, `HEAD`, or a full or abbreviated hash
+    pub fn resolve(&self, rev: &str) -> QmsResult<&GitCommit> {
+        let hash = if let Some(tag) = self.tags.iter().find(|tag| tag.name == rev) {
+            tag.commit.as_str()
+        } else if rev == "HEAD" {
+            self.head.as_str()
+        } else {
+            rev
+        };
+
+        let mut matches = self.commits.iter().filter(|commit| {
+            hash.len() >= 4 && hash.chars().all(|c| c.is_ascii_hexdigit()) && commit.hash.starts_with(&hash.to_lowercase())
+        });
+        match (matches.next(), matches.next()) {
+            (Some(commit), None) => Ok(commit),
+            (Some(_), Some(_)) => Err(QmsError::validation_error(&format!("Revision '{rev}' is ambiguous"))),
+            (None, _) => Err(QmsError::not_found(&format!("Revision '{rev}' not found in the imported history"))),
+        }
+    }
+
+    /// Hashes of `hash` and every commit it descends from
+    fn ancestors(&self, hash: &str) -> HashSet<&str> {
+        let index: HashMap<&str, &GitCommit> = self.commits.iter().map(|commit| (commit.hash.as_str(), commit)).collect();
+        let mut seen = HashSet::new();
+        let mut pending = vec![hash];
+        while let Some(hash) = pending.pop() {
+            if let Some(commit) = index.get(hash) {
+                if seen.insert(commit.hash.as_str()) {
+                    pending.extend(commit.parents.iter().map(String::as_str));
+                }
+            }
+        }
+        seen
+    }
+
+    /// Commits reachable from `to` but not from `from`, newest first (`git log from..to`)
+    pub fn commits_between(&self, from: Option<&str>, to: &str) -> QmsResult<Vec<&GitCommit>> {
+        let included = self.ancestors(&self.resolve(to)?.hash);
+        let excluded = match from {
+            Some(from) => self.ancestors(&self.resolve(from)?.hash),
+            None => HashSet::new(),
+        };
+        Ok(self
+            .commits
+            .iter()
+            .filter(|commit| included.contains(commit.hash.as_str()) && !excluded.contains(commit.hash.as_str()))
+            .collect())
+    }
+
+    /// Commits whose message references `id`, newest first
+    pub fn commits_referencing(&self, id: &str) -> Vec<&GitCommit> {
+        self.commits
+            .iter()
+            .filter(|commit| commit.references.iter().any(|reference| reference == id))
+            .collect()
+    }
+
+    /// Tags from oldest to newest, by position of their commit in the history
+    pub fn tags_in_order(&self) -> Vec<&GitTag> {
+        let position: HashMap<&str, usize> = self
+            .commits
+            .iter()
+            .enumerate()
+            .map(|(index, commit)| (commit.hash.as_str(), index))
+            .collect();
+        let mut tags: Vec<&GitTag> = self.tags.iter().filter(|tag| position.contains_key(tag.commit.as_str())).collect();
+        tags.sort_by(|a, b| position[b.commit.as_str()].cmp(&position[a.commit.as_str()]).then_with(|| a.name.cmp(&b.name)));
+        tags
+    }
+
+    /// Changes per release, newest first; commits after the newest tag form UNRELEASED
+    pub fn releases(&self) -> Vec<Release<'_>> {
+        let tags = self.tags_in_order();
+        let mut releases = Vec::new();
+        let mut previous: Option<&str> = None;
+        for tag in &tags {
+            releases.push(Release {
+                name: tag.name.clone(),
+                previous: previous.map(str::to_string),
+                commits: self.commits_between(previous, &tag.name).unwrap_or_default(),
+            });
+            previous = Some(&tag.name);
+        }
+        let unreleased = self.commits_between(previous, "HEAD").unwrap_or_default();
+        if !unreleased.is_empty() {
+            releases.push(Release {
+                name: UNRELEASED.to_string(),
+                previous: previous.map(str::to_string),
+                commits: unreleased,
+            });
+        }
+        releases.reverse();
+        releases
+    }
+
+    /// Oldest tag whose history contains `hash`
+    pub fn release_of(&self, hash: &str) -> Option<&str> {
+        self.tags_in_order()
+            .into_iter()
+            .find(|tag| self.ancestors(&tag.commit).contains(hash))
+            .map(|tag| tag.name.as_str())
+    }
+}
+
+fn get_string(obj: &HashMap<String, JsonValue>, field: &str) -> Result<String, JsonError> {
+    match obj.get(field) {
+        Some(JsonValue::String(s)) => Ok(s.clone()),
+        Some(_) => Err(JsonError::InvalidFormat(format!("Field '{field}' is not a string"))),
+        None => Err(JsonError::InvalidFormat(format!("Missing required field '{field}'"))),
+    }
+}
+
+fn get_strings(obj: &HashMap<String, JsonValue>, field: &str) -> Vec<String> {
+    match obj.get(field) {
+        Some(JsonValue::Array(values)) => values.iter().filter_map(|value| value.as_string().cloned()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn strings(values: &[String]) -> JsonValue {
+    JsonValue::Array(values.iter().cloned().map(JsonValue::String).collect())
+}
+
+impl JsonSerializable for GitHistory {
+    fn to_json(&self) -> String {
+        let commits = self.commits.iter().map(|commit| {
+            let mut obj = HashMap::new();
+            obj.insert("hash".to_string(), JsonValue::String(commit.hash.clone()));
+            obj.insert("parents".to_string(), strings(&commit.parents));
+            obj.insert("author".to_string(), JsonValue::String(commit.author.clone()));
+            obj.insert("email".to_string(), JsonValue::String(commit.email.clone()));
+            obj.insert("timestamp".to_string(), JsonValue::Number(commit.timestamp as f64));
+            obj.insert("subject".to_string(), JsonValue::String(commit.subject.clone()));
+            obj.insert("references".to_string(), strings(&commit.references));
+            obj.insert("files".to_string(), strings(&commit.files));
+            JsonValue::Object(obj)
+        }).collect();
+        let tags = self.tags.iter().map(|tag| {
+            let mut obj = HashMap::new();
+            obj.insert("name".to_string(), JsonValue::String(tag.name.clone()));
+            obj.insert("commit".to_string(), JsonValue::String(tag.commit.clone()));
+            JsonValue::Object(obj)
+        }).collect();
+
+        let mut obj = HashMap::new();
+        obj.insert("version".to_string(), JsonValue::String("1.0".to_string()));
+        obj.insert("repository".to_string(), JsonValue::String(self.repository.clone()));
+        obj.insert("imported_at".to_string(), JsonValue::String(self.imported_at.clone()));
+        obj.insert("head".to_string(), JsonValue::String(self.head.clone()));
+        obj.insert("commits".to_string(), JsonValue::Array(commits));
+        obj.insert("tags".to_string(), JsonValue::Array(tags));
+        JsonValue::Object(obj).json_to_string()
+    }
+
+    fn from_json(json: &str) -> Result<Self, JsonError> {
+        let obj = match JsonValue::parse(json)? {
+            JsonValue::Object(obj) => obj,
+            _ => return Err(JsonError::InvalidFormat("Expected JSON object".to_string())),
+        };
+
+        let mut commits = Vec::new();
+        if let Some(JsonValue::Array(values)) = obj.get("commits") {
+            for value in values {
+                let JsonValue::Object(commit) = value else {
+                    return Err(JsonError::InvalidFormat("Commit is not an object".to_string()));
+                };
+                commits.push(GitCommit {
+                    hash: get_string(commit, "hash")?,
+                    parents: get_strings(commit, "parents"),
+                    author: get_string(commit, "author").unwrap_or_default(),
+                    email: get_string(commit, "email").unwrap_or_default(),
+                    timestamp: commit.get("timestamp").and_then(JsonValue::as_number).unwrap_or(0.0) as u64,
+                    subject: get_string(commit, "subject").unwrap_or_default(),
+                    references: get_strings(commit, "references"),
+                    files: get_strings(commit, "files"),
+                });
+            }
+        }
+
+        let mut tags = Vec::new();
+        if let Some(JsonValue::Array(values)) = obj.get("tags") {
+            for value in values {
+                let JsonValue::Object(tag) = value else {
+                    return Err(JsonError::InvalidFormat("Tag is not an object".to_string()));
+                };
+                tags.push(GitTag {
+                    name: get_string(tag, "name")?,
+                    commit: get_string(tag, "commit")?,
+                });
+            }
+        }
+
+        Ok(GitHistory {
+            repository: get_string(&obj, "repository")?,
+            imported_at: get_string(&obj, "imported_at").unwrap_or_default(),
+            head: get_string(&obj, "head").unwrap_or_default(),
+            commits,
+            tags,
+        })
+    }
+}
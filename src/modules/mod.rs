@@ -1,8 +1,18 @@
+pub mod attachments;
 pub mod audit_logger;
+pub mod baseline;
+pub mod capa;
+pub mod change_control;
+pub mod design_review;
 pub mod document_control;
+pub mod git_history;
+pub mod nonconformance;
 pub mod report_generator;
 pub mod repository;
 pub mod risk_manager;
+pub mod scheduler;
+pub mod search;
+pub mod soup;
 pub mod traceability;
 pub mod user_manager;
 
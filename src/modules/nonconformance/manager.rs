@@ -0,0 +1,467 @@
+//! Nonconformance Manager
+//!
+//! Persists NCRs under `ncr/<id>.json` and drives them through the disposition
+//! workflow. Every state change goes through `transition`, which checks the
+//! `NcrState` table, appends a history entry and writes an audit record.
+
+use crate::json_utils::JsonSerializable;
+use crate::modules::audit_logger::functions::{audit_log_action, audit_log_create};
+use crate::modules::audit_logger::signatures::{ElectronicSignatureManager, SignatureVerification};
+use crate::modules::capa::{CapaManager, CapaPriority, CapaSource, CapaType, NewCapa};
+use crate::modules::user_manager::FileAuthManager;
+use crate::modules::storage::encryption;
+use crate::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::record::{Disposition, DispositionApproval, NcrRecord, NcrSeverity, NcrType};
+use super::workflow::{NcrState, NcrWorkflowEntry};
+
+/// Signature policy action used for disposition approvals
+pub const NCR_DISPOSITION_ACTION: &str = "ncr_disposition";
+
+/// Input for a new nonconformance report
+#[derive(Debug, Clone)]
+pub struct NewNcr {
+    pub title: String,
+    pub description: String,
+    pub nc_type: NcrType,
+    pub severity: NcrSeverity,
+    pub product: Option<String>,
+    pub lot_number: Option<String>,
+    pub quantity: Option<u32>,
+}
+
+/// Nonconformance report manager
+pub struct NcrManager {
+    project_path: PathBuf,
+    ncr_dir: PathBuf,
+}
+
+impl NcrManager {
+    /// Create new NCR manager for a project
+    pub fn new(project_path: &Path) -> QmsResult<Self> {
+        let ncr_dir = project_path.join("ncr");
+        fs::create_dir_all(&ncr_dir)?;
+        Ok(Self {
+            project_path: project_path.to_path_buf(),
+            ncr_dir,
+        })
+    }
+
+    /// Identify a new nonconformance
+    pub fn create_ncr(&self, new_ncr: NewNcr, detected_by: &str) -> QmsResult<NcrRecord> {
+        if new_ncr.title.trim().is_empty() {
+            return Err(QmsError::validation_error("NCR title cannot be empty"));
+        }
+        if new_ncr.description.trim().is_empty() {
+            return Err(QmsError::validation_error("NCR description cannot be empty"));
+        }
+
+        let timestamp = crate::utils::current_iso8601_timestamp();
+        let record = NcrRecord {
+            id: self.next_ncr_id()?,
+            title: new_ncr.title,
+            description: new_ncr.description,
+            nc_type: new_ncr.nc_type,
+            severity: new_ncr.severity,
+            product: new_ncr.product,
+            lot_number: new_ncr.lot_number,
+            quantity: new_ncr.quantity,
+            state: NcrState::Identified,
+            detected_by: detected_by.to_string(),
+            detected_at: timestamp.clone(),
+            containment: None,
+            disposition: None,
+            justification: None,
+            approvals: Vec::new(),
+            capa_id: None,
+            history: Vec::new(),
+            created_at: timestamp.clone(),
+            updated_at: timestamp,
+        };
+
+        self.save_ncr(&record)?;
+
+        audit_log_create(
+            "NCR",
+            &record.id,
+            &format!("{}|{}|{}", record.nc_type.display_name(), record.severity.display_name(), record.title),
+        )?;
+
+        Ok(record)
+    }
+
+    /// Load an NCR
+    pub fn load_ncr(&self, ncr_id: &str) -> QmsResult<NcrRecord> {
+        let path = self.ncr_path(ncr_id);
+        if !path.exists() {
+            return Err(QmsError::not_found(&format!("NCR {ncr_id} not found")));
+        }
+        let content = encryption::read_to_string(&path)?;
+        NcrRecord::from_json(&content)
+            .map_err(|e| QmsError::parse_error(&format!("Invalid NCR record {ncr_id}: {e}")))
+    }
+
+    /// List NCRs, optionally filtered by state, ordered by ID
+    pub fn list_ncrs(&self, state: Option<&NcrState>) -> QmsResult<Vec<NcrRecord>> {
+        let mut records = Vec::new();
+        for entry in fs::read_dir(&self.ncr_dir)? {
+            let path = entry?.path();
+            let is_record = path.extension().is_some_and(|ext| ext == "json")
+                && path.file_stem().and_then(|s| s.to_str()).is_some_and(|s| s.starts_with("NCR-"));
+            if !is_record {
+                continue;
+            }
+            let content = encryption::read_to_string(&path)?;
+            let record = NcrRecord::from_json(&content)
+                .map_err(|e| QmsError::parse_error(&format!("Invalid NCR record {}: {e}", path.display())))?;
+            if state.map_or(true, |s| &record.state == s) {
+                records.push(record);
+            }
+        }
+        records.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(records)
+    }
+
+    /// Record containment actions (segregation, hold, process stop)
+    pub fn contain(&self, ncr_id: &str, actions: &str, user_id: &str) -> QmsResult<NcrRecord> {
+        if actions.trim().is_empty() {
+            return Err(QmsError::validation_error("Containment actions cannot be empty"));
+        }
+        let mut record = self.load_ncr(ncr_id)?;
+        record.containment = Some(actions.to_string());
+        self.transition(&mut record, NcrState::Contained, user_id, None, Some(actions))?;
+        Ok(record)
+    }
+
+    /// Propose a disposition for role approval
+    pub fn propose_disposition(
+        &self,
+        ncr_id: &str,
+        disposition: Disposition,
+        justification: Option<&str>,
+        user_id: &str,
+    ) -> QmsResult<NcrRecord> {
+        let justification = justification.map(str::trim).filter(|j| !j.is_empty());
+        if disposition == Disposition::UseAsIs && justification.is_none() {
+            return Err(QmsError::validation_error("A use-as-is disposition requires a justification"));
+        }
+
+        let mut record = self.load_ncr(ncr_id)?;
+        let comments = format!("{}{}", disposition.display_name(), justification.map(|j| format!(": {j}")).unwrap_or_default());
+        record.disposition = Some(disposition);
+        record.justification = justification.map(str::to_string);
+        record.approvals.clear();
+        self.transition(&mut record, NcrState::DispositionProposed, user_id, None, Some(&comments))?;
+        Ok(record)
+    }
+
+    /// Sign the proposed disposition on behalf of one required role.
+    ///
+    /// The signer must hold `role`, and one user cannot fill more than one role.
+    /// Once every required role has signed the NCR moves to `DispositionApproved`.
+    pub fn approve_disposition(
+        &self,
+        ncr_id: &str,
+        user_id: &str,
+        password: &str,
+        code: Option<&str>,
+        role: &str,
+    ) -> QmsResult<NcrRecord> {
+        let mut record = self.load_ncr(ncr_id)?;
+        if record.state != NcrState::DispositionProposed {
+            return Err(QmsError::validation_error(&format!(
+                "NCR {ncr_id} has no disposition awaiting approval ({})", record.state.display_name()
+            )));
+        }
+
+        let required = record.disposition.as_ref().map_or(&[][..], |d| d.required_roles());
+        if !required.contains(&role) {
+            return Err(QmsError::validation_error(&format!(
+                "Role {role} is not required for this disposition (required: {})", required.join(", ")
+            )));
+        }
+        if record.approvals.iter().any(|a| a.role == role) {
+            return Err(QmsError::validation_error(&format!("Role {role} has already approved {ncr_id}")));
+        }
+        if record.approvals.iter().any(|a| a.user_id == user_id) {
+            return Err(QmsError::validation_error(&format!(
+                "User {user_id} has already signed {ncr_id} for another role"
+            )));
+        }
+
+        let user = FileAuthManager::from_project_path(&self.project_path)?.load_user(user_id)?;
+        if !user.roles.iter().any(|r| r.name == role) {
+            return Err(QmsError::permission_error(&format!("User {user_id} does not hold role {role}")));
+        }
+
+        let signature = ElectronicSignatureManager::new(self.project_path.clone()).create_signature_with_code(
+            user_id.to_string(),
+            password,
+            code,
+            NCR_DISPOSITION_ACTION,
+            "NCR".to_string(),
+            ncr_id.to_string(),
+            &record.disposition_signing_content(role),
+            None,
+        )?;
+
+        record.approvals.push(DispositionApproval {
+            role: role.to_string(),
+            user_id: user_id.to_string(),
+            signature_id: signature.id.clone(),
+            signed_at: signature.timestamp.clone(),
+        });
+        audit_log_action("NCR_DISPOSITION_SIGNED", "NCR", &format!("{ncr_id}|{role}|{}", signature.id))?;
+
+        if record.outstanding_roles().is_empty() {
+            self.transition(&mut record, NcrState::DispositionApproved, user_id, Some(signature.id), None)?;
+        } else {
+            self.touch_and_save(&mut record)?;
+        }
+        Ok(record)
+    }
+
+    /// Reject the proposed disposition, returning the NCR to `Contained`
+    pub fn reject_disposition(&self, ncr_id: &str, reason: &str, user_id: &str) -> QmsResult<NcrRecord> {
+        if reason.trim().is_empty() {
+            return Err(QmsError::validation_error("A rejection reason is required"));
+        }
+        let mut record = self.load_ncr(ncr_id)?;
+        self.transition(&mut record, NcrState::Contained, user_id, None, Some(reason))?;
+        record.disposition = None;
+        record.justification = None;
+        record.approvals.clear();
+        self.touch_and_save(&mut record)?;
+        Ok(record)
+    }
+
+    /// Close an NCR once the approved disposition has been carried out
+    pub fn close_ncr(&self, ncr_id: &str, comments: Option<&str>, user_id: &str) -> QmsResult<NcrRecord> {
+        let mut record = self.load_ncr(ncr_id)?;
+        self.transition(&mut record, NcrState::Closed, user_id, None, comments)?;
+        Ok(record)
+    }
+
+    /// Cancel an NCR raised in error
+    pub fn cancel_ncr(&self, ncr_id: &str, reason: &str, user_id: &str) -> QmsResult<NcrRecord> {
+        if reason.trim().is_empty() {
+            return Err(QmsError::validation_error("A cancellation reason is required"));
+        }
+        let mut record = self.load_ncr(ncr_id)?;
+        self.transition(&mut record, NcrState::Cancelled, user_id, None, Some(reason))?;
+        Ok(record)
+    }
+
+    /// Verify every disposition signature still matches the stored record
+    pub fn verify_disposition(&self, ncr_id: &str) -> QmsResult<Vec<(String, SignatureVerification)>> {
+        let record = self.load_ncr(ncr_id)?;
+        let signature_manager = ElectronicSignatureManager::new(self.project_path.clone());
+        record.approvals.iter().map(|approval| {
+            let verification = signature_manager.verify_signature_for_record(
+                &approval.signature_id,
+                &record.disposition_signing_content(&approval.role),
+            )?;
+            Ok((approval.role.clone(), verification))
+        }).collect()
+    }
+
+    /// Escalate an NCR into a corrective action
+    pub fn escalate_to_capa(&self, ncr_id: &str, user_id: &str) -> QmsResult<NcrRecord> {
+        let mut record = self.load_ncr(ncr_id)?;
+        if let Some(ref capa_id) = record.capa_id {
+            return Err(QmsError::validation_error(&format!("NCR {ncr_id} is already escalated to {capa_id}")));
+        }
+        if record.state == NcrState::Cancelled {
+            return Err(QmsError::validation_error(&format!("NCR {ncr_id} is cancelled")));
+        }
+
+        let priority = match record.severity {
+            NcrSeverity::Minor => CapaPriority::Medium,
+            NcrSeverity::Major => CapaPriority::High,
+            NcrSeverity::Critical => CapaPriority::Critical,
+        };
+        let capa = CapaManager::new(&self.project_path)?.create_capa(
+            NewCapa {
+                title: record.title.clone(),
+                description: record.description.clone(),
+                source: CapaSource::Nonconformance,
+                source_reference: Some(record.id.clone()),
+                capa_type: CapaType::Corrective,
+                priority,
+                owner: user_id.to_string(),
+                due_date: None,
+            },
+            user_id,
+        )?;
+
+        record.capa_id = Some(capa.id.clone());
+        self.touch_and_save(&mut record)?;
+
+        audit_log_action("NCR_ESCALATED", "NCR", &format!("{ncr_id}|{}", capa.id))?;
+
+        Ok(record)
+    }
+
+    /// Apply a workflow transition, record it in the NCR history and the audit trail
+    fn transition(
+        &self,
+        record: &mut NcrRecord,
+        target: NcrState,
+        actor_id: &str,
+        signature_id: Option<String>,
+        comments: Option<&str>,
+    ) -> QmsResult<()> {
+        if !record.state.can_transition_to(&target) {
+            return Err(QmsError::validation_error(&format!(
+                "Invalid NCR transition from {} to {}",
+                record.state.display_name(),
+                target.display_name()
+            )));
+        }
+
+        let entry = NcrWorkflowEntry {
+            from_state: record.state.clone(),
+            to_state: target.clone(),
+            action_timestamp: crate::utils::current_iso8601_timestamp(),
+            actor_id: actor_id.to_string(),
+            signature_id,
+            comments: comments.map(str::to_string),
+        };
+        let action = format!("NCR_{}", target.display_name().to_uppercase().replace(' ', "_"));
+        let details = format!("{}|{}->{}|{actor_id}", record.id, entry.from_state.display_name(), entry.to_state.display_name());
+
+        record.state = target;
+        record.history.push(entry);
+        self.touch_and_save(record)?;
+
+        audit_log_action(&action, "NCR", &details)?;
+        Ok(())
+    }
+
+    fn ncr_path(&self, ncr_id: &str) -> PathBuf {
+        self.ncr_dir.join(format!("{ncr_id}.json"))
+    }
+
+    fn next_ncr_id(&self) -> QmsResult<String> {
+        let mut max = 0u32;
+        for entry in fs::read_dir(&self.ncr_dir)? {
+            let name = entry?.file_name().to_string_lossy().to_string();
+            if let Some(number) = name.strip_prefix("NCR-").and_then(|n| n.strip_suffix(".json")) {
+                max = max.max(number.parse().unwrap_or(0));
+            }
+        }
+        Ok(format!("NCR-{:03}", max + 1))
+    }
+
+    fn touch_and_save(&self, record: &mut NcrRecord) -> QmsResult<()> {
+        record.updated_at = crate::utils::current_iso8601_timestamp();
+        self.save_ncr(record)
+    }
+
+    fn save_ncr(&self, record: &NcrRecord) -> QmsResult<()> {
+        encryption::atomic_write(&self.ncr_path(&record.id), &record.to_json())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Permission, Role};
+    use crate::modules::audit_logger::{initialize_audit_system, AuditConfig};
+    use crate::modules::user_manager::signing_keys::SigningKeyStore;
+
+    fn setup() -> (tempfile::TempDir, NcrManager) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let _ = initialize_audit_system(AuditConfig {
+            project_path: temp_dir.path().to_string_lossy().to_string(),
+            retention_days: 30,
+            daily_rotation: false,
+            max_file_size_mb: 10,
+            require_checksums: false,
+        });
+        let manager = NcrManager::new(temp_dir.path()).unwrap();
+        (temp_dir, manager)
+    }
+
+    fn add_signer(project_path: &Path, username: &str, role: &str) {
+        let auth = FileAuthManager::from_project_path(project_path).unwrap();
+        auth.add_user(username, "password123", Some(vec![Role { name: role.to_string(), permissions: vec![Permission::WriteDocuments] }]))
+            .unwrap();
+        SigningKeyStore::new(project_path).generate_key_pair(username, "password123").unwrap();
+    }
+
+    fn new_ncr() -> NewNcr {
+        NewNcr {
+            title: "Housing crack".to_string(),
+            description: "Hairline crack found at incoming inspection".to_string(),
+            nc_type: NcrType::Product,
+            severity: NcrSeverity::Major,
+            product: Some("PN-1001".to_string()),
+            lot_number: Some("LOT-77".to_string()),
+            quantity: Some(12),
+        }
+    }
+
+    #[test]
+    fn test_workflow_enforces_state_order_and_records_history() {
+        let (_dir, manager) = setup();
+        let ncr = manager.create_ncr(new_ncr(), "inspector").unwrap();
+        assert_eq!(ncr.id, "NCR-001");
+
+        assert!(manager.propose_disposition(&ncr.id, Disposition::Scrap, None, "quality").is_err());
+        manager.contain(&ncr.id, "Lot quarantined", "inspector").unwrap();
+        assert!(manager.propose_disposition(&ncr.id, Disposition::UseAsIs, None, "quality").is_err());
+        manager.propose_disposition(&ncr.id, Disposition::Scrap, None, "quality").unwrap();
+        let rejected = manager.reject_disposition(&ncr.id, "Rework is cheaper", "quality").unwrap();
+        assert_eq!(rejected.state, NcrState::Contained);
+        assert!(rejected.disposition.is_none());
+
+        let loaded = manager.load_ncr(&ncr.id).unwrap();
+        assert_eq!(loaded.history.len(), 3);
+        assert_eq!(loaded.history[2].from_state, NcrState::DispositionProposed);
+        assert_eq!(loaded.quantity, Some(12));
+        assert!(manager.close_ncr(&ncr.id, None, "quality").is_err());
+    }
+
+    #[test]
+    fn test_use_as_is_requires_signature_from_each_role() {
+        let (dir, manager) = setup();
+        add_signer(dir.path(), "qe_user", "QualityEngineer");
+        add_signer(dir.path(), "admin_user", "Administrator");
+
+        let ncr = manager.create_ncr(new_ncr(), "inspector").unwrap();
+        manager.contain(&ncr.id, "Lot quarantined", "inspector").unwrap();
+        manager.propose_disposition(&ncr.id, Disposition::UseAsIs, Some("Cosmetic only"), "qe_user").unwrap();
+
+        assert!(manager.approve_disposition(&ncr.id, "qe_user", "password123", None, "Administrator").is_err());
+        assert!(manager.approve_disposition(&ncr.id, "qe_user", "wrong-password", None, "QualityEngineer").is_err());
+
+        let partial = manager.approve_disposition(&ncr.id, "qe_user", "password123", None, "QualityEngineer").unwrap();
+        assert_eq!(partial.state, NcrState::DispositionProposed);
+        assert_eq!(partial.outstanding_roles(), vec!["Administrator"]);
+
+        let approved = manager.approve_disposition(&ncr.id, "admin_user", "password123", None, "Administrator").unwrap();
+        assert_eq!(approved.state, NcrState::DispositionApproved);
+        assert!(manager.verify_disposition(&ncr.id).unwrap().iter().all(|(_, v)| v.is_valid));
+
+        let closed = manager.close_ncr(&ncr.id, Some("Released to stock"), "qe_user").unwrap();
+        assert_eq!(closed.state, NcrState::Closed);
+    }
+
+    #[test]
+    fn test_escalate_to_capa_links_records() {
+        let (dir, manager) = setup();
+        add_signer(dir.path(), "quality", "QualityEngineer");
+        let ncr = manager.create_ncr(new_ncr(), "inspector").unwrap();
+        let escalated = manager.escalate_to_capa(&ncr.id, "quality").unwrap();
+        let capa_id = escalated.capa_id.clone().unwrap();
+        assert!(manager.escalate_to_capa(&ncr.id, "quality").is_err());
+
+        let capa = CapaManager::new(dir.path()).unwrap().load_capa(&capa_id).unwrap();
+        assert_eq!(capa.source, CapaSource::Nonconformance);
+        assert_eq!(capa.source_reference.as_deref(), Some("NCR-001"));
+        assert_eq!(capa.priority, CapaPriority::High);
+    }
+}
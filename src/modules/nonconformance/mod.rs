@@ -0,0 +1,17 @@
+//! Nonconformance (NCR) Module
+//!
+//! Implements control of nonconforming product per FDA 21 CFR 820.90 and
+//! ISO 13485:2016 Section 8.3: identification, containment, a disposition
+//! (use-as-is, rework, scrap, return-to-vendor) signed by each required role,
+//! and optional escalation into a CAPA.
+
+pub mod manager;
+pub mod record;
+pub mod workflow;
+
+#[allow(unused_imports)]
+pub use manager::{NcrManager, NewNcr, NCR_DISPOSITION_ACTION};
+#[allow(unused_imports)]
+pub use record::{Disposition, DispositionApproval, NcrRecord, NcrSeverity, NcrType};
+#[allow(unused_imports)]
+pub use workflow::{NcrState, NcrWorkflowEntry};
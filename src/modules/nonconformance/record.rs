@@ -0,0 +1,322 @@
+//! Nonconformance Record Model
+//!
+//! Data model for product and process nonconformance reports (NCRs) per
+//! FDA 21 CFR 820.90 and ISO 13485:2016 Section 8.3.
+
+use crate::json_utils::{JsonError, JsonSerializable, JsonValue};
+use crate::prelude::*;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use super::workflow::{NcrState, NcrWorkflowEntry};
+
+/// Whether the nonconformance concerns product or a process
+#[derive(Debug, Clone, PartialEq)]
+pub enum NcrType {
+    Product,
+    Process,
+}
+
+impl NcrType {
+    /// Get display name for NCR type
+    pub const fn display_name(&self) -> &'static str {
+        match self {
+            NcrType::Product => "Product",
+            NcrType::Process => "Process",
+        }
+    }
+}
+
+impl FromStr for NcrType {
+    type Err = QmsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "product" => Ok(NcrType::Product),
+            "process" => Ok(NcrType::Process),
+            _ => Err(QmsError::validation_error(&format!("Invalid NCR type: {s} (expected product or process)"))),
+        }
+    }
+}
+
+/// Severity classification of a nonconformance
+#[derive(Debug, Clone, PartialEq)]
+pub enum NcrSeverity {
+    Minor,
+    Major,
+    Critical,
+}
+
+impl NcrSeverity {
+    /// Get display name for NCR severity
+    pub const fn display_name(&self) -> &'static str {
+        match self {
+            NcrSeverity::Minor => "Minor",
+            NcrSeverity::Major => "Major",
+            NcrSeverity::Critical => "Critical",
+        }
+    }
+}
+
+impl FromStr for NcrSeverity {
+    type Err = QmsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "minor" => Ok(NcrSeverity::Minor),
+            "major" => Ok(NcrSeverity::Major),
+            "critical" => Ok(NcrSeverity::Critical),
+            _ => Err(QmsError::validation_error(&format!("Invalid NCR severity: {s} (expected minor, major or critical)"))),
+        }
+    }
+}
+
+/// Material Review Board disposition of nonconforming product
+#[derive(Debug, Clone, PartialEq)]
+pub enum Disposition {
+    UseAsIs,        // Accept without rework (concession); requires justification
+    Rework,         // Rework to meet specification
+    Scrap,          // Destroy nonconforming product
+    ReturnToVendor, // Return to supplier
+}
+
+impl Disposition {
+    /// Get display name for disposition
+    pub const fn display_name(&self) -> &'static str {
+        match self {
+            Disposition::UseAsIs => "Use As Is",
+            Disposition::Rework => "Rework",
+            Disposition::Scrap => "Scrap",
+            Disposition::ReturnToVendor => "Return To Vendor",
+        }
+    }
+
+    /// Roles that must each sign before the disposition is approved.
+    ///
+    /// Use-as-is is a concession and additionally needs an administrator.
+    pub const fn required_roles(&self) -> &'static [&'static str] {
+        match self {
+            Disposition::UseAsIs => &["QualityEngineer", "Administrator"],
+            Disposition::Rework | Disposition::Scrap | Disposition::ReturnToVendor => &["QualityEngineer"],
+        }
+    }
+}
+
+impl FromStr for Disposition {
+    type Err = QmsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "useasis" => Ok(Disposition::UseAsIs),
+            "rework" => Ok(Disposition::Rework),
+            "scrap" => Ok(Disposition::Scrap),
+            "returntovendor" | "rtv" => Ok(Disposition::ReturnToVendor),
+            _ => Err(QmsError::validation_error(&format!(
+                "Invalid disposition: {s} (expected use-as-is, rework, scrap or return-to-vendor)"
+            ))),
+        }
+    }
+}
+
+/// One role's signed approval of the proposed disposition
+#[derive(Debug, Clone)]
+pub struct DispositionApproval {
+    pub role: String,
+    pub user_id: String,
+    pub signature_id: String,
+    pub signed_at: String, // ISO 8601 timestamp
+}
+
+/// Nonconformance report
+#[derive(Debug, Clone)]
+pub struct NcrRecord {
+    pub id: String, // NCR-001, NCR-002...
+    pub title: String,
+    pub description: String,
+    pub nc_type: NcrType,
+    pub severity: NcrSeverity,
+    pub product: Option<String>,   // Part number or process name
+    pub lot_number: Option<String>,
+    pub quantity: Option<u32>,     // Affected units
+    pub state: NcrState,
+    pub detected_by: String,
+    pub detected_at: String,
+    pub containment: Option<String>, // Containment actions taken
+    pub disposition: Option<Disposition>,
+    pub justification: Option<String>,
+    pub approvals: Vec<DispositionApproval>,
+    pub capa_id: Option<String>,   // CAPA raised from this NCR
+    pub history: Vec<NcrWorkflowEntry>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl NcrRecord {
+    /// Deterministic byte representation bound by a disposition approval signature.
+    ///
+    /// Includes the signing role so one signature cannot stand in for another role.
+    pub fn disposition_signing_content(&self, role: &str) -> Vec<u8> {
+        let mut content = String::from("qms-ncr-disposition-v1\n");
+        content.push_str(&format!("id={}\n", self.id));
+        content.push_str(&format!("title={}\n", self.title));
+        content.push_str(&format!("type={}\n", self.nc_type.display_name()));
+        content.push_str(&format!("product={}\n", self.product.as_deref().unwrap_or("")));
+        content.push_str(&format!("lot={}\n", self.lot_number.as_deref().unwrap_or("")));
+        content.push_str(&format!("quantity={}\n", self.quantity.map(|q| q.to_string()).unwrap_or_default()));
+        content.push_str(&format!(
+            "disposition={}\n",
+            self.disposition.as_ref().map_or("", |d| d.display_name())
+        ));
+        content.push_str(&format!("justification={}\n", self.justification.as_deref().unwrap_or("")));
+        content.push_str(&format!("role={role}\n"));
+        content.into_bytes()
+    }
+
+    /// Required roles that have not yet signed the proposed disposition
+    pub fn outstanding_roles(&self) -> Vec<&'static str> {
+        self.disposition.as_ref().map_or_else(Vec::new, |d| {
+            d.required_roles()
+                .iter()
+                .filter(|role| !self.approvals.iter().any(|a| a.role == **role))
+                .copied()
+                .collect()
+        })
+    }
+}
+
+fn optional_string(value: &Option<String>) -> JsonValue {
+    value.as_ref().map_or(JsonValue::Null, |s| JsonValue::String(s.clone()))
+}
+
+fn get_string(obj: &HashMap<String, JsonValue>, field: &str) -> Result<String, JsonError> {
+    match obj.get(field) {
+        Some(JsonValue::String(s)) => Ok(s.clone()),
+        Some(_) => Err(JsonError::InvalidFormat(format!("Field '{field}' is not a string"))),
+        None => Err(JsonError::InvalidFormat(format!("Missing required field '{field}'"))),
+    }
+}
+
+fn get_optional_string(obj: &HashMap<String, JsonValue>, field: &str) -> Option<String> {
+    obj.get(field).and_then(|v| v.as_string().cloned())
+}
+
+fn invalid(err: QmsError) -> JsonError {
+    JsonError::InvalidFormat(err.to_string())
+}
+
+impl JsonSerializable for NcrRecord {
+    fn to_json(&self) -> String {
+        let approvals = self.approvals.iter().map(|a| {
+            let mut approval = HashMap::new();
+            approval.insert("role".to_string(), JsonValue::String(a.role.clone()));
+            approval.insert("user_id".to_string(), JsonValue::String(a.user_id.clone()));
+            approval.insert("signature_id".to_string(), JsonValue::String(a.signature_id.clone()));
+            approval.insert("signed_at".to_string(), JsonValue::String(a.signed_at.clone()));
+            JsonValue::Object(approval)
+        }).collect();
+
+        let history = self.history.iter().map(|h| {
+            let mut entry = HashMap::new();
+            entry.insert("from_state".to_string(), JsonValue::String(h.from_state.display_name().to_string()));
+            entry.insert("to_state".to_string(), JsonValue::String(h.to_state.display_name().to_string()));
+            entry.insert("action_timestamp".to_string(), JsonValue::String(h.action_timestamp.clone()));
+            entry.insert("actor_id".to_string(), JsonValue::String(h.actor_id.clone()));
+            entry.insert("signature_id".to_string(), optional_string(&h.signature_id));
+            entry.insert("comments".to_string(), optional_string(&h.comments));
+            JsonValue::Object(entry)
+        }).collect();
+
+        let mut obj = HashMap::new();
+        obj.insert("version".to_string(), JsonValue::String("1.0".to_string()));
+        obj.insert("id".to_string(), JsonValue::String(self.id.clone()));
+        obj.insert("title".to_string(), JsonValue::String(self.title.clone()));
+        obj.insert("description".to_string(), JsonValue::String(self.description.clone()));
+        obj.insert("nc_type".to_string(), JsonValue::String(self.nc_type.display_name().to_string()));
+        obj.insert("severity".to_string(), JsonValue::String(self.severity.display_name().to_string()));
+        obj.insert("product".to_string(), optional_string(&self.product));
+        obj.insert("lot_number".to_string(), optional_string(&self.lot_number));
+        obj.insert(
+            "quantity".to_string(),
+            self.quantity.map_or(JsonValue::Null, |q| JsonValue::Number(f64::from(q))),
+        );
+        obj.insert("state".to_string(), JsonValue::String(self.state.display_name().to_string()));
+        obj.insert("detected_by".to_string(), JsonValue::String(self.detected_by.clone()));
+        obj.insert("detected_at".to_string(), JsonValue::String(self.detected_at.clone()));
+        obj.insert("containment".to_string(), optional_string(&self.containment));
+        obj.insert(
+            "disposition".to_string(),
+            self.disposition.as_ref().map_or(JsonValue::Null, |d| JsonValue::String(d.display_name().to_string())),
+        );
+        obj.insert("justification".to_string(), optional_string(&self.justification));
+        obj.insert("approvals".to_string(), JsonValue::Array(approvals));
+        obj.insert("capa_id".to_string(), optional_string(&self.capa_id));
+        obj.insert("history".to_string(), JsonValue::Array(history));
+        obj.insert("created_at".to_string(), JsonValue::String(self.created_at.clone()));
+        obj.insert("updated_at".to_string(), JsonValue::String(self.updated_at.clone()));
+        JsonValue::Object(obj).json_to_string()
+    }
+
+    fn from_json(json: &str) -> Result<Self, JsonError> {
+        let obj = match JsonValue::parse(json)? {
+            JsonValue::Object(obj) => obj,
+            _ => return Err(JsonError::InvalidFormat("Expected JSON object".to_string())),
+        };
+
+        let mut approvals = Vec::new();
+        if let Some(JsonValue::Array(items)) = obj.get("approvals") {
+            for item in items {
+                if let JsonValue::Object(a) = item {
+                    approvals.push(DispositionApproval {
+                        role: get_string(a, "role")?,
+                        user_id: get_string(a, "user_id")?,
+                        signature_id: get_string(a, "signature_id")?,
+                        signed_at: get_string(a, "signed_at")?,
+                    });
+                }
+            }
+        }
+
+        let mut history = Vec::new();
+        if let Some(JsonValue::Array(items)) = obj.get("history") {
+            for item in items {
+                if let JsonValue::Object(h) = item {
+                    history.push(NcrWorkflowEntry {
+                        from_state: NcrState::from_str(&get_string(h, "from_state")?).map_err(invalid)?,
+                        to_state: NcrState::from_str(&get_string(h, "to_state")?).map_err(invalid)?,
+                        action_timestamp: get_string(h, "action_timestamp")?,
+                        actor_id: get_string(h, "actor_id")?,
+                        signature_id: get_optional_string(h, "signature_id"),
+                        comments: get_optional_string(h, "comments"),
+                    });
+                }
+            }
+        }
+
+        let disposition = match get_optional_string(&obj, "disposition") {
+            Some(d) => Some(Disposition::from_str(&d).map_err(invalid)?),
+            None => None,
+        };
+
+        Ok(NcrRecord {
+            id: get_string(&obj, "id")?,
+            title: get_string(&obj, "title")?,
+            description: get_string(&obj, "description")?,
+            nc_type: NcrType::from_str(&get_string(&obj, "nc_type")?).map_err(invalid)?,
+            severity: NcrSeverity::from_str(&get_string(&obj, "severity")?).map_err(invalid)?,
+            product: get_optional_string(&obj, "product"),
+            lot_number: get_optional_string(&obj, "lot_number"),
+            quantity: obj.get("quantity").and_then(JsonValue::as_number).map(|q| q as u32),
+            state: NcrState::from_str(&get_string(&obj, "state")?).map_err(invalid)?,
+            detected_by: get_string(&obj, "detected_by")?,
+            detected_at: get_string(&obj, "detected_at")?,
+            containment: get_optional_string(&obj, "containment"),
+            disposition,
+            justification: get_optional_string(&obj, "justification"),
+            approvals,
+            capa_id: get_optional_string(&obj, "capa_id"),
+            history,
+            created_at: get_string(&obj, "created_at")?,
+            updated_at: get_string(&obj, "updated_at")?,
+        })
+    }
+}
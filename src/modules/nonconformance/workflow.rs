@@ -0,0 +1,99 @@
+//! Nonconformance disposition workflow state machine
+//! Mirrors the document `ApprovalWorkflow` pattern: explicit states, a transition
+//! table, and a history entry for every transition.
+
+use crate::prelude::*;
+use std::str::FromStr;
+
+/// NCR workflow state
+#[derive(Debug, Clone, PartialEq)]
+pub enum NcrState {
+    Identified,          // Nonconformance recorded
+    Contained,           // Affected product segregated / process stopped
+    DispositionProposed, // Disposition proposed, awaiting role approvals
+    DispositionApproved, // All required roles have signed
+    Closed,              // Disposition carried out and verified
+    Cancelled,           // Raised in error
+}
+
+impl NcrState {
+    pub const fn can_transition_to(&self, target: &NcrState) -> bool {
+        match (self, target) {
+            // From Identified
+            (NcrState::Identified, NcrState::Contained) => true,
+            (NcrState::Identified, NcrState::Cancelled) => true,
+
+            // From Contained
+            (NcrState::Contained, NcrState::DispositionProposed) => true,
+            (NcrState::Contained, NcrState::Cancelled) => true,
+
+            // From DispositionProposed
+            (NcrState::DispositionProposed, NcrState::DispositionApproved) => true,
+            (NcrState::DispositionProposed, NcrState::Contained) => true, // Disposition rejected
+            (NcrState::DispositionProposed, NcrState::Cancelled) => true,
+
+            // From DispositionApproved
+            (NcrState::DispositionApproved, NcrState::Closed) => true,
+
+            _ => false,
+        }
+    }
+
+    pub const fn display_name(&self) -> &'static str {
+        match self {
+            NcrState::Identified => "Identified",
+            NcrState::Contained => "Contained",
+            NcrState::DispositionProposed => "Disposition Proposed",
+            NcrState::DispositionApproved => "Disposition Approved",
+            NcrState::Closed => "Closed",
+            NcrState::Cancelled => "Cancelled",
+        }
+    }
+}
+
+impl FromStr for NcrState {
+    type Err = QmsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "identified" => Ok(NcrState::Identified),
+            "contained" => Ok(NcrState::Contained),
+            "dispositionproposed" | "proposed" => Ok(NcrState::DispositionProposed),
+            "dispositionapproved" | "approved" => Ok(NcrState::DispositionApproved),
+            "closed" => Ok(NcrState::Closed),
+            "cancelled" | "canceled" => Ok(NcrState::Cancelled),
+            _ => Err(QmsError::validation_error(&format!("Unknown NCR state: {s}"))),
+        }
+    }
+}
+
+/// NCR workflow history entry
+#[derive(Debug, Clone)]
+pub struct NcrWorkflowEntry {
+    pub from_state: NcrState,
+    pub to_state: NcrState,
+    pub action_timestamp: String,
+    pub actor_id: String,
+    pub signature_id: Option<String>,
+    pub comments: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ncr_state_transitions() {
+        assert!(NcrState::Identified.can_transition_to(&NcrState::Contained));
+        assert!(NcrState::Contained.can_transition_to(&NcrState::DispositionProposed));
+        assert!(NcrState::DispositionProposed.can_transition_to(&NcrState::DispositionApproved));
+        assert!(NcrState::DispositionProposed.can_transition_to(&NcrState::Contained));
+        assert!(NcrState::DispositionApproved.can_transition_to(&NcrState::Closed));
+
+        // Invalid transitions
+        assert!(!NcrState::Identified.can_transition_to(&NcrState::DispositionProposed));
+        assert!(!NcrState::Contained.can_transition_to(&NcrState::DispositionApproved));
+        assert!(!NcrState::DispositionApproved.can_transition_to(&NcrState::Cancelled));
+        assert!(!NcrState::Closed.can_transition_to(&NcrState::Contained));
+    }
+}
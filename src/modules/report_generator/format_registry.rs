@@ -138,7 +138,8 @@ impl FormatRegistry {
     /// Register built-in formats
     fn register_builtin_formats(&self) {
         use crate::modules::report_generator::strategies::format_strategies::{
-            MarkdownFormatStrategy, CSVFormatStrategy, JSONFormatStrategy, HTMLFormatStrategy
+            MarkdownFormatStrategy, CSVFormatStrategy, JSONFormatStrategy, HTMLFormatStrategy,
+            PDFFormatStrategy,
         };
         
         // Register Markdown format
@@ -177,13 +178,13 @@ impl FormatRegistry {
             || Box::new(HTMLFormatStrategy)
         );
         
-        // Register PDF format (using HTML strategy as base)
+        // Register PDF format (native PDF writer)
         let _ = self.register_format(
             "pdf",
             vec!["pdf".to_string()],
             "application/pdf",
             "Portable Document Format",
-            || Box::new(HTMLFormatStrategy) // Would be replaced with actual PDF strategy
+            || Box::new(PDFFormatStrategy)
         );
 
         // Register XML format (using JSON strategy as placeholder)
@@ -110,15 +110,13 @@ impl BaseReportGenerator<AuditEntry> for AuditReportGenerator {
         format: &OutputFormat,
         metadata: &ReportMetadata,
     ) -> QmsResult<String> {
-        // Use the injected formatter
-        let formatted_data = self.formatter.format_data(data, format)?;
-        
-        // Add format-specific wrapper using strategy pattern
+        // Format-specific assembly using strategy pattern
         let strategy = FormatStrategyFactory::create_strategy(format)?;
-        let header = strategy.get_header(metadata, "Audit Trail Report");
-        let footer = strategy.get_footer(metadata);
-        
-        Ok(format!("{}{}{}", header, formatted_data, footer))
+
+        // Use the injected formatter (PDF renders from a Markdown body)
+        let formatted_data = self.formatter.format_data(data, &strategy.body_format(format))?;
+
+        strategy.render_document(metadata, "Audit Trail Report", &formatted_data)
     }
     
     /// Get report type identifier
@@ -175,6 +173,7 @@ impl BaseReportGenerator<AuditEntry> for AuditReportGenerator {
             OutputFormat::CSV,
             OutputFormat::JSON,
             OutputFormat::HTML,
+            OutputFormat::PDF,
         ]
     }
 }
@@ -204,7 +203,7 @@ impl DataCollector<AuditEntry> for FileAuditDataCollector {
             return Ok(Vec::new());
         }
         
-        let content = std::fs::read_to_string(log_path)?;
+        let content = crate::modules::storage::encryption::read_to_string(log_path)?;
         let mut entries = Vec::new();
         
         for line in content.lines() {
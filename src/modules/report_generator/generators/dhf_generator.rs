@@ -11,7 +11,12 @@
 //! - Dependency Inversion: Depends on abstractions, not concrete implementations
 
 use crate::prelude::*;
+use crate::modules::attachments::{self, Attachment, AttachmentManager};
+use crate::modules::baseline::{Baseline, BaselineManager};
+use crate::modules::design_review::{self, DesignReview, DesignReviewManager, ReviewOutcome};
 use crate::modules::document_control::service::{DocumentService, DocumentIndexEntry};
+use crate::modules::git_history::{self, GitHistoryManager, Release};
+use crate::modules::soup::{self, SoupItem, SoupManager};
 use crate::modules::audit_logger::audit_log_action;
 // KISS: Use public re-exports to avoid complex import issues
 use crate::modules::report_generator::{
@@ -29,6 +34,7 @@ pub struct DHFReportGenerator {
     project_path: std::path::PathBuf,
     data_collector: Box<dyn DataCollector<DocumentIndexEntry>>,
     formatter: Box<dyn DataFormatter<DocumentIndexEntry>>,
+    baseline: Option<Baseline>,
 }
 
 impl DHFReportGenerator {
@@ -39,8 +45,20 @@ impl DHFReportGenerator {
             project_path: project_path_buf.clone(),
             data_collector: Box::new(DocumentDataCollector::new(project_path_buf.clone())),
             formatter: Box::new(DHFDataFormatter::new()),
+            baseline: None,
         }
     }
+
+    /// Create a DHF report generator for the released configuration frozen in `baseline`
+    pub fn as_of_baseline(project_path: &std::path::Path, baseline: Baseline) -> QmsResult<Self> {
+        let documents = BaselineManager::new(project_path)?.document_index(&baseline)?;
+        Ok(Self {
+            project_path: project_path.to_path_buf(),
+            data_collector: Box::new(BaselineDocumentCollector::new(baseline.name.clone(), documents)),
+            formatter: Box::new(DHFDataFormatter::new()),
+            baseline: Some(baseline),
+        })
+    }
     
     /// Create with custom implementations for testing or advanced use cases
     pub fn with_dependencies(
@@ -52,6 +70,7 @@ impl DHFReportGenerator {
             project_path,
             data_collector,
             formatter,
+            baseline: None,
         }
     }
     
@@ -103,15 +122,53 @@ impl BaseReportGenerator<DocumentIndexEntry> for DHFReportGenerator {
         format: &OutputFormat,
         metadata: &ReportMetadata,
     ) -> QmsResult<String> {
-        // Use the injected formatter
-        let formatted_data = self.formatter.format_data(data, format)?;
-        
-        // Add format-specific wrapper using strategy pattern
+        // Format-specific assembly using strategy pattern
         let strategy = FormatStrategyFactory::create_strategy(format)?;
-        let header = strategy.get_header(metadata, "Design History File (DHF) Report");
-        let footer = strategy.get_footer(metadata);
-        
-        Ok(format!("{}{}{}", header, formatted_data, footer))
+
+        // Use the injected formatter (PDF renders from a Markdown body)
+        let body_format = strategy.body_format(format);
+        let mut formatted_data = self.formatter.format_data(data, &body_format)?;
+
+        // As of a baseline, only evidence and reviews recorded before it was taken
+        let cutoff = self.baseline.as_ref().map(|baseline| baseline.created_at.as_str());
+
+        // Attached evidence files, listed with their fingerprints
+        if attachments::has_attachments(&self.project_path) {
+            let mut evidence = AttachmentManager::new(&self.project_path)?.list(None, None)?;
+            if let Some(cutoff) = cutoff {
+                for attachment in &mut evidence {
+                    attachment.versions.retain(|version| version.uploaded_at.as_str() <= cutoff);
+                }
+                evidence.retain(|attachment| !attachment.versions.is_empty());
+            }
+            formatted_data.push_str(&format_evidence_section(&evidence, &body_format));
+        }
+
+        // Formal design reviews with their independent reviewers and sign-off
+        if design_review::has_reviews(&self.project_path) {
+            let mut reviews = DesignReviewManager::new(&self.project_path)?.list_reviews(None)?;
+            reviews.retain(|review| cutoff.is_none() || Some(review.created_at.as_str()) <= cutoff);
+            formatted_data.push_str(&format_design_review_section(&reviews, &body_format));
+        }
+
+        // Software changes per release from the imported Git history
+        if git_history::has_history(&self.project_path) {
+            let history = GitHistoryManager::new(&self.project_path)?.load()?;
+            formatted_data.push_str(&format_software_changes_section(&history.releases(), &body_format));
+        }
+
+        // SOUP items in use with their requirements, risks and anomaly evaluations
+        if soup::has_soup(&self.project_path) {
+            let mut items = SoupManager::new(&self.project_path)?.load_register()?.items;
+            items.retain(|item| item.in_use);
+            formatted_data.push_str(&format_soup_section(&items, &body_format));
+        }
+
+        let title = match self.baseline {
+            Some(ref baseline) => format!("Design History File (DHF) Report - Baseline {}", baseline.name),
+            None => "Design History File (DHF) Report".to_string(),
+        };
+        strategy.render_document(metadata, &title, &formatted_data)
     }
     
     /// Get report type identifier
@@ -135,6 +192,7 @@ impl BaseReportGenerator<DocumentIndexEntry> for DHFReportGenerator {
             OutputFormat::CSV,
             OutputFormat::JSON,
             OutputFormat::HTML,
+            OutputFormat::PDF,
         ]
     }
 }
@@ -158,24 +216,7 @@ impl DataCollector<DocumentIndexEntry> for DocumentDataCollector {
     }
     
     fn filter_data(&self, data: &[DocumentIndexEntry], criteria: &DataFilterCriteria) -> QmsResult<Vec<DocumentIndexEntry>> {
-        let mut filtered = data.to_vec();
-        
-        // Apply status filter if specified
-        if let Some(status_filter) = &criteria.status_filter {
-            filtered.retain(|entry| status_filter.contains(&entry.status));
-        }
-        
-        // Apply entity type filter (document type)
-        if let Some(entity_types) = &criteria.entity_types {
-            filtered.retain(|entry| entity_types.contains(&entry.doc_type));
-        }
-        
-        // Apply limit
-        if let Some(limit) = criteria.limit {
-            filtered.truncate(limit);
-        }
-        
-        Ok(filtered)
+        Ok(filter_index_entries(data, criteria))
     }
     
     fn get_collection_stats(&self) -> QmsResult<DataCollectionStats> {
@@ -194,6 +235,303 @@ impl DataCollector<DocumentIndexEntry> for DocumentDataCollector {
 
 /// DHF data formatter
 /// Single Responsibility Principle: Handles only DHF data formatting
+/// Apply status, document type and limit criteria to document index entries
+fn filter_index_entries(data: &[DocumentIndexEntry], criteria: &DataFilterCriteria) -> Vec<DocumentIndexEntry> {
+    let mut filtered = data.to_vec();
+    
+    // Apply status filter if specified
+    if let Some(status_filter) = &criteria.status_filter {
+        filtered.retain(|entry| status_filter.contains(&entry.status));
+    }
+    
+    // Apply entity type filter (document type)
+    if let Some(entity_types) = &criteria.entity_types {
+        filtered.retain(|entry| entity_types.contains(&entry.doc_type));
+    }
+    
+    // Apply limit
+    if let Some(limit) = criteria.limit {
+        filtered.truncate(limit);
+    }
+    
+    filtered
+}
+
+/// Document collector serving the documents frozen in a baseline
+pub struct BaselineDocumentCollector {
+    baseline_name: String,
+    documents: Vec<DocumentIndexEntry>,
+}
+
+impl BaselineDocumentCollector {
+    pub const fn new(baseline_name: String, documents: Vec<DocumentIndexEntry>) -> Self {
+        Self { baseline_name, documents }
+    }
+}
+
+impl DataCollector<DocumentIndexEntry> for BaselineDocumentCollector {
+    fn collect_data(&self) -> QmsResult<Vec<DocumentIndexEntry>> {
+        Ok(self.documents.clone())
+    }
+
+    fn filter_data(&self, data: &[DocumentIndexEntry], criteria: &DataFilterCriteria) -> QmsResult<Vec<DocumentIndexEntry>> {
+        Ok(filter_index_entries(data, criteria))
+    }
+
+    fn get_collection_stats(&self) -> QmsResult<DataCollectionStats> {
+        Ok(DataCollectionStats {
+            total_items: self.documents.len(),
+            filtered_items: self.documents.len(),
+            collection_time_ms: 0,
+            data_sources: vec![format!("baselines/{}.json", self.baseline_name)],
+        })
+    }
+}
+
+/// Objective evidence section for Markdown and HTML bodies
+///
+/// CSV and JSON bodies hold one document table and are left as they are.
+fn format_evidence_section(evidence: &[Attachment], format: &OutputFormat) -> String {
+    let mut section = String::new();
+    let rows = evidence.iter().filter_map(|attachment| attachment.latest().map(|latest| (attachment, latest)));
+    match format {
+        OutputFormat::Markdown => {
+            writeln!(section).unwrap();
+            writeln!(section, "## Objective Evidence (Attachments)").unwrap();
+            writeln!(section).unwrap();
+            writeln!(section, "| ID | Record | Name | Version | Type | Size | SHA-256 |").unwrap();
+            writeln!(section, "|----|--------|------|---------|------|------|---------|").unwrap();
+            for (attachment, latest) in rows {
+                writeln!(
+                    section,
+                    "| {} | {} {} | {} | {} | {} | {} | `{}` |",
+                    attachment.id,
+                    attachment.target.name(),
+                    attachment.record_id,
+                    ReportFormatter::truncate_string(&attachment.name, 40),
+                    latest.version,
+                    latest.mime_type,
+                    latest.size,
+                    latest.sha256
+                ).unwrap();
+            }
+        }
+        OutputFormat::HTML => {
+            writeln!(section, "<h2>Objective Evidence (Attachments)</h2>").unwrap();
+            writeln!(section, "<table>").unwrap();
+            writeln!(section, "<thead>").unwrap();
+            writeln!(section, "<tr><th>ID</th><th>Record</th><th>Name</th><th>Version</th><th>Type</th><th>Size</th><th>SHA-256</th></tr>").unwrap();
+            writeln!(section, "</thead>").unwrap();
+            writeln!(section, "<tbody>").unwrap();
+            for (attachment, latest) in rows {
+                writeln!(
+                    section,
+                    "<tr><td>{}</td><td>{} {}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td><code>{}</code></td></tr>",
+                    ReportFormatter::escape_html(&attachment.id),
+                    attachment.target.name(),
+                    ReportFormatter::escape_html(&attachment.record_id),
+                    ReportFormatter::escape_html(&attachment.name),
+                    latest.version,
+                    ReportFormatter::escape_html(&latest.mime_type),
+                    latest.size,
+                    latest.sha256
+                ).unwrap();
+            }
+            writeln!(section, "</tbody>").unwrap();
+            writeln!(section, "</table>").unwrap();
+        }
+        _ => {}
+    }
+    section
+}
+
+/// Design review section for Markdown and HTML bodies
+fn format_design_review_section(reviews: &[DesignReview], format: &OutputFormat) -> String {
+    let mut section = String::new();
+    let rows = reviews.iter().map(|review| {
+        let independent: Vec<&str> = review
+            .attendees
+            .iter()
+            .filter(|attendee| attendee.independent)
+            .map(|attendee| attendee.user_id.as_str())
+            .collect();
+        (
+            review,
+            review.outcome.as_ref().map_or("-", ReviewOutcome::display_name),
+            if independent.is_empty() { "-".to_string() } else { independent.join(", ") },
+            format!("{}/{}", review.signatures.len(), review.attendees.len()),
+            format!("{}/{}", review.open_action_items().len(), review.action_items.len()),
+        )
+    });
+    match format {
+        OutputFormat::Markdown => {
+            writeln!(section).unwrap();
+            writeln!(section, "## Design Reviews").unwrap();
+            writeln!(section).unwrap();
+            writeln!(section, "| ID | Title | Phase | Status | Outcome | Independent Reviewer | Signatures | Open Actions |").unwrap();
+            writeln!(section, "|----|-------|-------|--------|---------|----------------------|------------|--------------|").unwrap();
+            for (review, outcome, independent, signatures, actions) in rows {
+                writeln!(
+                    section,
+                    "| {} | {} | {} | {} | {} | {} | {} | {} |",
+                    review.id,
+                    ReportFormatter::truncate_string(&review.title, 40),
+                    review.phase.display_name(),
+                    review.status.display_name(),
+                    outcome,
+                    independent,
+                    signatures,
+                    actions
+                ).unwrap();
+            }
+        }
+        OutputFormat::HTML => {
+            writeln!(section, "<h2>Design Reviews</h2>").unwrap();
+            writeln!(section, "<table>").unwrap();
+            writeln!(section, "<thead>").unwrap();
+            writeln!(section, "<tr><th>ID</th><th>Title</th><th>Phase</th><th>Status</th><th>Outcome</th><th>Independent Reviewer</th><th>Signatures</th><th>Open Actions</th></tr>").unwrap();
+            writeln!(section, "</thead>").unwrap();
+            writeln!(section, "<tbody>").unwrap();
+            for (review, outcome, independent, signatures, actions) in rows {
+                writeln!(
+                    section,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    ReportFormatter::escape_html(&review.id),
+                    ReportFormatter::escape_html(&review.title),
+                    ReportFormatter::escape_html(review.phase.display_name()),
+                    review.status.display_name(),
+                    outcome,
+                    ReportFormatter::escape_html(&independent),
+                    signatures,
+                    actions
+                ).unwrap();
+            }
+            writeln!(section, "</tbody>").unwrap();
+            writeln!(section, "</table>").unwrap();
+        }
+        _ => {}
+    }
+    section
+}
+
+/// Software changes per release for Markdown and HTML bodies
+fn format_software_changes_section(releases: &[Release<'_>], format: &OutputFormat) -> String {
+    let mut section = String::new();
+    let heading = |release: &Release<'_>| match release.previous {
+        Some(ref previous) => format!("{} (since {previous})", release.name),
+        None => release.name.clone(),
+    };
+    match format {
+        OutputFormat::Markdown => {
+            writeln!(section).unwrap();
+            writeln!(section, "## Software Changes by Release").unwrap();
+            for release in releases {
+                writeln!(section).unwrap();
+                writeln!(section, "### {}", heading(release)).unwrap();
+                writeln!(section).unwrap();
+                writeln!(section, "| Commit | Date | Author | Summary | References |").unwrap();
+                writeln!(section, "|--------|------|--------|---------|------------|").unwrap();
+                for commit in &release.commits {
+                    writeln!(
+                        section,
+                        "| `{}` | {} | {} | {} | {} |",
+                        commit.short_hash(),
+                        commit.date(),
+                        commit.author,
+                        ReportFormatter::truncate_string(&commit.subject, 60),
+                        commit.references.join(", ")
+                    ).unwrap();
+                }
+            }
+        }
+        OutputFormat::HTML => {
+            writeln!(section, "<h2>Software Changes by Release</h2>").unwrap();
+            for release in releases {
+                writeln!(section, "<h3>{}</h3>", ReportFormatter::escape_html(&heading(release))).unwrap();
+                writeln!(section, "<table>").unwrap();
+                writeln!(section, "<thead>").unwrap();
+                writeln!(section, "<tr><th>Commit</th><th>Date</th><th>Author</th><th>Summary</th><th>References</th></tr>").unwrap();
+                writeln!(section, "</thead>").unwrap();
+                writeln!(section, "<tbody>").unwrap();
+                for commit in &release.commits {
+                    writeln!(
+                        section,
+                        "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                        commit.short_hash(),
+                        commit.date(),
+                        ReportFormatter::escape_html(&commit.author),
+                        ReportFormatter::escape_html(&commit.subject),
+                        commit.references.join(", ")
+                    ).unwrap();
+                }
+                writeln!(section, "</tbody>").unwrap();
+                writeln!(section, "</table>").unwrap();
+            }
+        }
+        _ => {}
+    }
+    section
+}
+
+fn format_soup_section(items: &[SoupItem], format: &OutputFormat) -> String {
+    let mut section = String::new();
+    let requirements = |item: &SoupItem| item.functional_requirements.len() + item.performance_requirements.len();
+    let anomalies = |item: &SoupItem| match item.pending_anomalies() {
+        _ if item.anomalies.is_empty() => "None".to_string(),
+        0 => format!("{} evaluated", item.anomalies.len()),
+        pending => format!("{pending} of {} pending", item.anomalies.len()),
+    };
+    match format {
+        OutputFormat::Markdown => {
+            writeln!(section).unwrap();
+            writeln!(section, "## SOUP Register").unwrap();
+            writeln!(section).unwrap();
+            writeln!(section, "| ID | Name | Version | Supplier | License | Requirements | Risks | Anomalies |").unwrap();
+            writeln!(section, "|----|------|---------|----------|---------|--------------|-------|-----------|").unwrap();
+            for item in items {
+                writeln!(
+                    section,
+                    "| {} | {} | {} | {} | {} | {} | {} | {} |",
+                    item.id,
+                    ReportFormatter::truncate_string(&item.name, 40),
+                    item.version,
+                    ReportFormatter::truncate_string(&item.supplier, 30),
+                    item.license,
+                    requirements(item),
+                    item.risk_ids.join(", "),
+                    anomalies(item)
+                ).unwrap();
+            }
+        }
+        OutputFormat::HTML => {
+            writeln!(section, "<h2>SOUP Register</h2>").unwrap();
+            writeln!(section, "<table>").unwrap();
+            writeln!(section, "<thead>").unwrap();
+            writeln!(section, "<tr><th>ID</th><th>Name</th><th>Version</th><th>Supplier</th><th>License</th><th>Requirements</th><th>Risks</th><th>Anomalies</th></tr>").unwrap();
+            writeln!(section, "</thead>").unwrap();
+            writeln!(section, "<tbody>").unwrap();
+            for item in items {
+                writeln!(
+                    section,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    item.id,
+                    ReportFormatter::escape_html(&item.name),
+                    ReportFormatter::escape_html(&item.version),
+                    ReportFormatter::escape_html(&item.supplier),
+                    ReportFormatter::escape_html(&item.license),
+                    requirements(item),
+                    item.risk_ids.join(", "),
+                    anomalies(item)
+                ).unwrap();
+            }
+            writeln!(section, "</tbody>").unwrap();
+            writeln!(section, "</table>").unwrap();
+        }
+        _ => {}
+    }
+    section
+}
+
 pub struct DHFDataFormatter;
 
 impl DHFDataFormatter {
@@ -103,15 +103,13 @@ impl BaseReportGenerator<RiskIndexEntry> for RiskReportGenerator {
         format: &OutputFormat,
         metadata: &ReportMetadata,
     ) -> QmsResult<String> {
-        // Use the injected formatter
-        let formatted_data = self.formatter.format_data(data, format)?;
-        
-        // Add format-specific wrapper using strategy pattern
+        // Format-specific assembly using strategy pattern
         let strategy = FormatStrategyFactory::create_strategy(format)?;
-        let header = strategy.get_header(metadata, "Risk Management Report");
-        let footer = strategy.get_footer(metadata);
-        
-        Ok(format!("{}{}{}", header, formatted_data, footer))
+
+        // Use the injected formatter (PDF renders from a Markdown body)
+        let formatted_data = self.formatter.format_data(data, &strategy.body_format(format))?;
+
+        strategy.render_document(metadata, "Risk Management Report", &formatted_data)
     }
     
     /// Get report type identifier
@@ -135,6 +133,7 @@ impl BaseReportGenerator<RiskIndexEntry> for RiskReportGenerator {
             OutputFormat::CSV,
             OutputFormat::JSON,
             OutputFormat::HTML,
+            OutputFormat::PDF,
         ]
     }
 }
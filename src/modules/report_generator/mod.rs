@@ -11,6 +11,7 @@ pub mod extensible_formats; // REFACTORED: OCP-compliant extensible format syste
 // Utility modules
 pub mod csv;
 pub mod markdown;
+pub mod pdf; // Native PDF writer shared by all PDF exports
 
 
 
@@ -20,6 +21,7 @@ pub use template_method::*;
 pub use strategies::*;
 pub use factories::*;
 pub use timeout::*;
+pub use pdf::{PdfDocument, PdfTable, SignatureManifestation};
 
 // Canonical Report Generator Implementations
 pub use generators::{
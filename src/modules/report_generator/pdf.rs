@@ -0,0 +1,1002 @@
+//! Native PDF Writer
+//!
+//! Minimal PDF 1.4 writer used by every "PDF" export in the system (report
+//! generator, audit trail, document control, risk register, RTM). It lays out
+//! headings, paragraphs, bullet lists, label/value fields, tables and an
+//! electronic signature manifestation block (21 CFR Part 11.50) onto US Letter
+//! pages, and stamps every page with a running header (title, document ID,
+//! revision) and footer ("Page X of Y").
+//!
+//! Only the PDF standard 14 fonts (Helvetica, Helvetica-Bold, Courier) are
+//! used, so no font data has to be embedded. Content streams are written
+//! uncompressed and all non-ASCII bytes are octal-escaped, which keeps the
+//! output 7-bit clean: it can travel through the String-based export pipelines
+//! unchanged while still being a genuine PDF file.
+
+use crate::prelude::*;
+use std::fmt::Write;
+use std::path::Path;
+
+/// US Letter page size in points
+const PAGE_WIDTH: f32 = 612.0;
+const PAGE_HEIGHT: f32 = 792.0;
+const MARGIN_X: f32 = 54.0;
+const BODY_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN_X;
+/// Top of the body area (below the running header)
+const BODY_TOP: f32 = PAGE_HEIGHT - 66.0;
+/// Bottom of the body area (above the running footer)
+const BODY_BOTTOM: f32 = 66.0;
+
+const TEXT_SIZE: f32 = 10.0;
+const TEXT_LEADING: f32 = 13.0;
+const TABLE_SIZE: f32 = 8.5;
+const TABLE_LEADING: f32 = 11.0;
+const CELL_PADDING: f32 = 3.0;
+const MONO_SIZE: f32 = 8.0;
+const MONO_LEADING: f32 = 10.0;
+const FIELD_LABEL_WIDTH: f32 = 150.0;
+
+/// Helvetica advance widths (1/1000 em) for ASCII 32..=126
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Helvetica-Bold advance widths (1/1000 em) for ASCII 32..=126
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611,
+    975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556,
+    333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611,
+    611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+/// Standard 14 fonts used by the writer
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Font {
+    Regular,
+    Bold,
+    Mono,
+}
+
+impl Font {
+    const fn resource(self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+            Font::Mono => "F3",
+        }
+    }
+
+    /// Advance width of a WinAnsi-encoded byte in 1/1000 em
+    fn byte_width(self, byte: u8) -> u16 {
+        match self {
+            Font::Mono => 600,
+            _ => match byte {
+                32..=126 => {
+                    let table = if self == Font::Bold { &HELVETICA_BOLD_WIDTHS } else { &HELVETICA_WIDTHS };
+                    table[(byte - 32) as usize]
+                }
+                0x95 => 350,  // bullet
+                0x97 => 1000, // em dash
+                _ => 556,
+            },
+        }
+    }
+
+    fn text_width(self, text: &str, size: f32) -> f32 {
+        let units: u32 = encode_text(text).iter().map(|b| u32::from(self.byte_width(*b))).sum();
+        units as f32 * size / 1000.0
+    }
+}
+
+/// Electronic signature manifestation printed on signed records (21 CFR Part 11.50)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignatureManifestation {
+    pub signer: String,
+    pub meaning: String,
+    pub signed_at: String,
+    pub reason: Option<String>,
+    pub signature_id: Option<String>,
+    pub fingerprint: Option<String>,
+}
+
+impl SignatureManifestation {
+    pub fn new(signer: &str, meaning: &str, signed_at: &str) -> Self {
+        Self {
+            signer: signer.to_string(),
+            meaning: meaning.to_string(),
+            signed_at: signed_at.to_string(),
+            reason: None,
+            signature_id: None,
+            fingerprint: None,
+        }
+    }
+}
+
+impl From<&crate::modules::audit_logger::signatures::ElectronicSignature> for SignatureManifestation {
+    fn from(signature: &crate::modules::audit_logger::signatures::ElectronicSignature) -> Self {
+        Self {
+            signer: signature.user_id.clone(),
+            meaning: signature.meaning.clone(),
+            signed_at: signature.timestamp.clone(),
+            reason: signature.reason.clone(),
+            signature_id: Some(signature.id.clone()),
+            fingerprint: signature
+                .key_fingerprint
+                .clone()
+                .or_else(|| Some(signature.signature_hash.clone())),
+        }
+    }
+}
+
+impl From<&crate::models::ElectronicSignature> for SignatureManifestation {
+    fn from(signature: &crate::models::ElectronicSignature) -> Self {
+        Self {
+            signer: signature.user_id.clone(),
+            meaning: signature.meaning.clone(),
+            signed_at: signature.timestamp.clone(),
+            reason: None,
+            signature_id: None,
+            fingerprint: Some(signature.signed_data_hash.clone()),
+        }
+    }
+}
+
+/// Table block: header row plus data rows, wrapped and paginated by the writer
+#[derive(Debug, Clone, Default)]
+pub struct PdfTable {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl PdfTable {
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn add_row(&mut self, row: Vec<String>) -> &mut Self {
+        self.rows.push(row);
+        self
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn column_count(&self) -> usize {
+        self.rows.iter().map(Vec::len).chain(std::iter::once(self.headers.len())).max().unwrap_or(0)
+    }
+
+    fn cell(row: &[String], column: usize) -> &str {
+        row.get(column).map(String::as_str).unwrap_or("")
+    }
+
+    /// Natural column widths, shrunk to the body width when they do not fit
+    fn column_widths(&self) -> Vec<f32> {
+        let columns = self.column_count();
+        if columns == 0 {
+            return Vec::new();
+        }
+
+        let natural: Vec<f32> = (0..columns)
+            .map(|c| {
+                let header = Font::Bold.text_width(Self::cell(&self.headers, c), TABLE_SIZE);
+                let body = self.rows.iter()
+                    .map(|row| Font::Regular.text_width(Self::cell(row, c), TABLE_SIZE))
+                    .fold(0.0, f32::max);
+                header.max(body) + 2.0 * CELL_PADDING + 1.0
+            })
+            .collect();
+
+        let total: f32 = natural.iter().sum();
+        if total <= BODY_WIDTH {
+            // Spread the spare room so tables always span the body width
+            let scale = BODY_WIDTH / total;
+            return natural.iter().map(|w| w * scale).collect();
+        }
+
+        // Every column gets up to a fair share; leftover room goes to the wide ones
+        let fair = BODY_WIDTH / columns as f32;
+        let mut widths: Vec<f32> = natural.iter().map(|w| w.min(fair)).collect();
+        let spare = BODY_WIDTH - widths.iter().sum::<f32>();
+        let deficit: f32 = natural.iter().zip(&widths).map(|(n, w)| n - w).sum();
+        if deficit > 0.0 {
+            for (width, natural) in widths.iter_mut().zip(&natural) {
+                *width += (natural - *width) / deficit * spare;
+            }
+        }
+        widths
+    }
+}
+
+/// Content blocks laid out in document order
+#[derive(Debug, Clone)]
+enum Block {
+    Heading(u8, String),
+    Paragraph(String),
+    Bullet(String),
+    Field(String, String),
+    Table(PdfTable),
+    Signatures(Vec<SignatureManifestation>),
+    Preformatted(String),
+    Rule,
+    PageBreak,
+}
+
+/// PDF document builder
+///
+/// ```ignore
+/// let mut pdf = PdfDocument::new("Risk Register").with_document_id("RMF-001").with_revision("B");
+/// pdf.heading(1, "Summary").field("Total Risks", "12");
+/// pdf.write_to(Path::new("register.pdf"))?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct PdfDocument {
+    title: String,
+    document_id: Option<String>,
+    revision: Option<String>,
+    author: Option<String>,
+    footer_note: Option<String>,
+    blocks: Vec<Block>,
+}
+
+impl PdfDocument {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            document_id: None,
+            revision: None,
+            author: None,
+            footer_note: None,
+            blocks: Vec::new(),
+        }
+    }
+
+    /// Document ID printed in the running header of every page
+    pub fn with_document_id(mut self, document_id: &str) -> Self {
+        self.document_id = Some(document_id.to_string());
+        self
+    }
+
+    /// Revision printed in the running header of every page
+    pub fn with_revision(mut self, revision: &str) -> Self {
+        self.revision = Some(revision.to_string());
+        self
+    }
+
+    pub fn with_author(mut self, author: &str) -> Self {
+        self.author = Some(author.to_string());
+        self
+    }
+
+    /// Short note printed on the left of every footer (e.g. generation time)
+    pub fn with_footer_note(mut self, note: &str) -> Self {
+        self.footer_note = Some(note.to_string());
+        self
+    }
+
+    pub fn heading(&mut self, level: u8, text: &str) -> &mut Self {
+        self.blocks.push(Block::Heading(level.clamp(1, 3), text.to_string()));
+        self
+    }
+
+    pub fn paragraph(&mut self, text: &str) -> &mut Self {
+        self.blocks.push(Block::Paragraph(text.to_string()));
+        self
+    }
+
+    pub fn bullet(&mut self, text: &str) -> &mut Self {
+        self.blocks.push(Block::Bullet(text.to_string()));
+        self
+    }
+
+    /// Label/value line, e.g. "Document ID:  DOC-001"
+    pub fn field(&mut self, label: &str, value: &str) -> &mut Self {
+        self.blocks.push(Block::Field(label.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn table(&mut self, table: PdfTable) -> &mut Self {
+        self.blocks.push(Block::Table(table));
+        self
+    }
+
+    /// Signature manifestation block; an empty list prints "no signatures recorded"
+    pub fn signatures(&mut self, signatures: Vec<SignatureManifestation>) -> &mut Self {
+        self.blocks.push(Block::Signatures(signatures));
+        self
+    }
+
+    /// Monospaced text printed line for line (long lines are hard-wrapped)
+    pub fn preformatted(&mut self, text: &str) -> &mut Self {
+        self.blocks.push(Block::Preformatted(text.to_string()));
+        self
+    }
+
+    pub fn rule(&mut self) -> &mut Self {
+        self.blocks.push(Block::Rule);
+        self
+    }
+
+    pub fn page_break(&mut self) -> &mut Self {
+        self.blocks.push(Block::PageBreak);
+        self
+    }
+
+    /// Append Markdown content (headings, lists, pipe tables, code fences, rules)
+    pub fn push_markdown(&mut self, markdown: &str) -> &mut Self {
+        let mut paragraph: Vec<String> = Vec::new();
+        let mut table_lines: Vec<&str> = Vec::new();
+        let mut code: Option<Vec<&str>> = None;
+
+        for line in markdown.lines() {
+            let trimmed = line.trim();
+
+            if let Some(code_lines) = code.as_mut() {
+                if trimmed.starts_with("```") {
+                    let text = code_lines.join("\n");
+                    self.preformatted(&text);
+                    code = None;
+                } else {
+                    code_lines.push(line);
+                }
+                continue;
+            }
+
+            if trimmed.starts_with('|') {
+                self.flush_paragraph(&mut paragraph);
+                table_lines.push(trimmed);
+                continue;
+            }
+            self.flush_table(&mut table_lines);
+
+            if trimmed.is_empty() || (trimmed.starts_with("<!--") && trimmed.ends_with("-->")) {
+                self.flush_paragraph(&mut paragraph);
+            } else if trimmed.starts_with("```") {
+                self.flush_paragraph(&mut paragraph);
+                code = Some(Vec::new());
+            } else if trimmed.starts_with('#') {
+                self.flush_paragraph(&mut paragraph);
+                let level = trimmed.chars().take_while(|c| *c == '#').count();
+                let text = trimmed.trim_start_matches('#').trim_end_matches('#').trim();
+                self.heading(level.min(3) as u8, &strip_inline_markdown(text));
+            } else if trimmed == "---" || trimmed == "***" || trimmed == "___" {
+                self.flush_paragraph(&mut paragraph);
+                self.rule();
+            } else if let Some(item) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")) {
+                self.flush_paragraph(&mut paragraph);
+                self.bullet(&strip_inline_markdown(item));
+            } else {
+                paragraph.push(strip_inline_markdown(trimmed));
+            }
+        }
+
+        if let Some(code_lines) = code {
+            let text = code_lines.join("\n");
+            self.preformatted(&text);
+        }
+        self.flush_table(&mut table_lines);
+        self.flush_paragraph(&mut paragraph);
+        self
+    }
+
+    fn flush_paragraph(&mut self, lines: &mut Vec<String>) {
+        if !lines.is_empty() {
+            let text = lines.join(" ");
+            self.paragraph(&text);
+            lines.clear();
+        }
+    }
+
+    fn flush_table(&mut self, lines: &mut Vec<&str>) {
+        if lines.is_empty() {
+            return;
+        }
+
+        let split = |line: &str| -> Vec<String> {
+            line.trim_matches('|')
+                .split('|')
+                .map(|cell| strip_inline_markdown(cell.trim()))
+                .collect()
+        };
+        let is_separator = |line: &str| {
+            line.chars().all(|c| matches!(c, '|' | '-' | ':' | ' '))
+        };
+
+        let mut table = PdfTable::default();
+        for (index, line) in lines.iter().enumerate() {
+            if is_separator(line) {
+                continue;
+            }
+            if index == 0 {
+                table.headers = split(line);
+            } else {
+                table.add_row(split(line));
+            }
+        }
+        self.table(table);
+        lines.clear();
+    }
+
+    /// Number of pages the document lays out to
+    pub fn page_count(&self) -> usize {
+        self.layout().len()
+    }
+
+    /// Render the complete PDF file
+    pub fn render(&self) -> String {
+        let mut pages = self.layout();
+        let total = pages.len();
+        for (index, page) in pages.iter_mut().enumerate() {
+            self.stamp_page(page, index + 1, total);
+        }
+        self.assemble(&pages)
+    }
+
+    /// Render and atomically write the PDF, returning its size in bytes
+    pub fn write_to(&self, path: &Path) -> QmsResult<u64> {
+        let pdf = self.render();
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        crate::fs_utils::atomic_write(path, &pdf)?;
+        Ok(pdf.len() as u64)
+    }
+
+    /// Lay out all blocks, returning one content stream per page (without header/footer)
+    fn layout(&self) -> Vec<String> {
+        let mut cursor = PageCursor::new();
+        for block in &self.blocks {
+            match block {
+                Block::Heading(level, text) => cursor.heading(*level, text),
+                Block::Paragraph(text) => {
+                    cursor.wrapped(text, Font::Regular, TEXT_SIZE, TEXT_LEADING, MARGIN_X, BODY_WIDTH);
+                    cursor.y -= 5.0;
+                }
+                Block::Bullet(text) => cursor.bullet(text),
+                Block::Field(label, value) => cursor.field(label, value),
+                Block::Table(table) => cursor.table(table),
+                Block::Signatures(signatures) => cursor.signatures(signatures),
+                Block::Preformatted(text) => cursor.preformatted(text),
+                Block::Rule => {
+                    cursor.ensure(10.0);
+                    cursor.y -= 5.0;
+                    cursor.line(MARGIN_X, cursor.y, PAGE_WIDTH - MARGIN_X, cursor.y, 0.5);
+                    cursor.y -= 5.0;
+                }
+                Block::PageBreak => {
+                    if !cursor.ops.is_empty() {
+                        cursor.new_page();
+                    }
+                }
+            }
+        }
+        cursor.finish()
+    }
+
+    /// Draw the running header and footer onto a laid-out page
+    fn stamp_page(&self, page: &mut String, number: usize, total: usize) {
+        let mut stamp = PageCursor::new();
+        let header_y = PAGE_HEIGHT - 42.0;
+
+        stamp.text(MARGIN_X, header_y, Font::Bold, 9.0, &self.title);
+        let mut identity = Vec::new();
+        if let Some(id) = &self.document_id {
+            identity.push(format!("Document ID: {id}"));
+        }
+        if let Some(revision) = &self.revision {
+            identity.push(format!("Revision: {revision}"));
+        }
+        if !identity.is_empty() {
+            stamp.text_right(PAGE_WIDTH - MARGIN_X, header_y, Font::Regular, 9.0, &identity.join("   "));
+        }
+        stamp.line(MARGIN_X, header_y - 6.0, PAGE_WIDTH - MARGIN_X, header_y - 6.0, 0.5);
+
+        let footer_y = 36.0;
+        stamp.line(MARGIN_X, footer_y + 12.0, PAGE_WIDTH - MARGIN_X, footer_y + 12.0, 0.5);
+        if let Some(note) = &self.footer_note {
+            stamp.text(MARGIN_X, footer_y, Font::Regular, 8.0, note);
+        }
+        stamp.text_right(PAGE_WIDTH - MARGIN_X, footer_y, Font::Regular, 8.0, &format!("Page {number} of {total}"));
+
+        page.push_str(&stamp.ops);
+    }
+
+    /// Serialize pages into PDF objects, cross-reference table and trailer
+    fn assemble(&self, pages: &[String]) -> String {
+        // Fixed objects: 1 catalog, 2 page tree, 3-5 fonts, 6 info; then page/content pairs
+        let page_object = |index: usize| 7 + 2 * index;
+        let mut objects: Vec<String> = Vec::new();
+
+        objects.push("<< /Type /Catalog /Pages 2 0 R >>".to_string());
+        let kids: Vec<String> = (0..pages.len()).map(|i| format!("{} 0 R", page_object(i))).collect();
+        objects.push(format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()));
+        for base_font in ["Helvetica", "Helvetica-Bold", "Courier"] {
+            objects.push(format!(
+                "<< /Type /Font /Subtype /Type1 /BaseFont /{base_font} /Encoding /WinAnsiEncoding >>"
+            ));
+        }
+
+        let mut info = format!("<< /Title {} /Producer (OxiQMS)", pdf_string(&self.title));
+        if let Some(author) = &self.author {
+            let _ = write!(info, " /Author {}", pdf_string(author));
+        }
+        if let Some(id) = &self.document_id {
+            let _ = write!(info, " /Subject {}", pdf_string(id));
+        }
+        info.push_str(" >>");
+        objects.push(info);
+
+        for (index, content) in pages.iter().enumerate() {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH:.0} {PAGE_HEIGHT:.0}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R /F3 5 0 R >> >> /Contents {} 0 R >>",
+                page_object(index) + 1
+            ));
+            objects.push(format!("<< /Length {} >>\nstream\n{content}endstream", content.len()));
+        }
+
+        let mut pdf = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            let _ = write!(pdf, "{} 0 obj\n{object}\nendobj\n", index + 1);
+        }
+
+        let xref_offset = pdf.len();
+        let _ = write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(pdf, "{offset:010} 00000 n ");
+        }
+        let _ = write!(
+            pdf,
+            "trailer\n<< /Size {} /Root 1 0 R /Info 6 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n",
+            objects.len() + 1
+        );
+        pdf
+    }
+}
+
+/// Layout cursor: accumulates drawing operators for the current page
+struct PageCursor {
+    pages: Vec<String>,
+    ops: String,
+    y: f32,
+}
+
+impl PageCursor {
+    const fn new() -> Self {
+        Self { pages: Vec::new(), ops: String::new(), y: BODY_TOP }
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(std::mem::take(&mut self.ops));
+        self.y = BODY_TOP;
+    }
+
+    /// Start a new page unless `height` still fits (a fresh page always accepts)
+    fn ensure(&mut self, height: f32) {
+        if self.y - height < BODY_BOTTOM && self.y < BODY_TOP {
+            self.new_page();
+        }
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        if !self.ops.is_empty() || self.pages.is_empty() {
+            self.new_page();
+        }
+        self.pages
+    }
+
+    fn text(&mut self, x: f32, y: f32, font: Font, size: f32, text: &str) {
+        let _ = writeln!(
+            self.ops,
+            "BT /{} {size:.1} Tf {x:.2} {y:.2} Td {} Tj ET",
+            font.resource(),
+            pdf_string(text)
+        );
+    }
+
+    fn text_right(&mut self, right: f32, y: f32, font: Font, size: f32, text: &str) {
+        let x = right - font.text_width(text, size);
+        self.text(x, y, font, size, text);
+    }
+
+    fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, width: f32) {
+        let _ = writeln!(self.ops, "{width:.2} w {x1:.2} {y1:.2} m {x2:.2} {y2:.2} l S");
+    }
+
+    fn rect(&mut self, x: f32, y: f32, width: f32, height: f32, fill: Option<f32>) {
+        if let Some(gray) = fill {
+            let _ = writeln!(self.ops, "{gray:.2} g {x:.2} {y:.2} {width:.2} {height:.2} re f 0 g");
+        }
+        let _ = writeln!(self.ops, "0.5 w {x:.2} {y:.2} {width:.2} {height:.2} re S");
+    }
+
+    /// Wrap `text` into `width` and emit it line by line, breaking pages as needed
+    fn wrapped(&mut self, text: &str, font: Font, size: f32, leading: f32, x: f32, width: f32) {
+        for line in wrap_text(text, font, size, width) {
+            self.ensure(leading);
+            self.y -= leading;
+            self.text(x, self.y + leading - size, font, size, &line);
+        }
+    }
+
+    fn heading(&mut self, level: u8, text: &str) {
+        let (size, gap) = match level {
+            1 => (16.0, 12.0),
+            2 => (13.0, 9.0),
+            _ => (11.0, 6.0),
+        };
+        let leading = size * 1.3;
+        // Keep the heading together with at least two lines of what follows
+        self.ensure(gap + leading + 2.0 * TEXT_LEADING);
+        if self.y < BODY_TOP {
+            self.y -= gap;
+        }
+        self.wrapped(text, Font::Bold, size, leading, MARGIN_X, BODY_WIDTH);
+        if level == 1 {
+            self.y -= 3.0;
+            self.line(MARGIN_X, self.y, PAGE_WIDTH - MARGIN_X, self.y, 0.75);
+        }
+        self.y -= 4.0;
+    }
+
+    fn bullet(&mut self, text: &str) {
+        let indent = 14.0;
+        for (index, line) in wrap_text(text, Font::Regular, TEXT_SIZE, BODY_WIDTH - indent).iter().enumerate() {
+            self.ensure(TEXT_LEADING);
+            self.y -= TEXT_LEADING;
+            let baseline = self.y + TEXT_LEADING - TEXT_SIZE;
+            if index == 0 {
+                self.text(MARGIN_X + 4.0, baseline, Font::Regular, TEXT_SIZE, "\u{2022}");
+            }
+            self.text(MARGIN_X + indent, baseline, Font::Regular, TEXT_SIZE, line);
+        }
+        self.y -= 2.0;
+    }
+
+    fn field(&mut self, label: &str, value: &str) {
+        let value_width = BODY_WIDTH - FIELD_LABEL_WIDTH;
+        for (index, line) in wrap_text(value, Font::Regular, TEXT_SIZE, value_width).iter().enumerate() {
+            self.ensure(TEXT_LEADING);
+            self.y -= TEXT_LEADING;
+            let baseline = self.y + TEXT_LEADING - TEXT_SIZE;
+            if index == 0 {
+                self.text(MARGIN_X, baseline, Font::Bold, TEXT_SIZE, label);
+            }
+            self.text(MARGIN_X + FIELD_LABEL_WIDTH, baseline, Font::Regular, TEXT_SIZE, line);
+        }
+    }
+
+    fn table(&mut self, table: &PdfTable) {
+        let widths = table.column_widths();
+        if widths.is_empty() {
+            return;
+        }
+        self.y -= 4.0;
+
+        let has_header = table.headers.iter().any(|h| !h.is_empty());
+        let header_height = if has_header { self.row_height(&table.headers, &widths, Font::Bold) } else { 0.0 };
+        // Tallest row that still fits on an otherwise empty page below the header
+        let max_lines = (((BODY_TOP - BODY_BOTTOM - header_height - 2.0 * CELL_PADDING) / TABLE_LEADING).floor() as usize).max(1);
+
+        if has_header {
+            self.ensure(header_height + TABLE_LEADING + 2.0 * CELL_PADDING);
+            self.table_row(&table.headers, &widths, Font::Bold, Some(0.88), max_lines);
+        }
+        for row in &table.rows {
+            let height = self.row_height(row, &widths, Font::Regular).min(max_lines as f32 * TABLE_LEADING + 2.0 * CELL_PADDING);
+            if self.y - height < BODY_BOTTOM {
+                self.new_page();
+                if has_header {
+                    self.table_row(&table.headers, &widths, Font::Bold, Some(0.88), max_lines);
+                }
+            }
+            self.table_row(row, &widths, Font::Regular, None, max_lines);
+        }
+        self.y -= 8.0;
+    }
+
+    fn cell_lines(text: &str, width: f32, font: Font) -> Vec<String> {
+        wrap_text(text, font, TABLE_SIZE, width - 2.0 * CELL_PADDING)
+    }
+
+    fn row_height(&self, row: &[String], widths: &[f32], font: Font) -> f32 {
+        let lines = widths.iter().enumerate()
+            .map(|(c, w)| Self::cell_lines(PdfTable::cell(row, c), *w, font).len())
+            .max()
+            .unwrap_or(1);
+        lines as f32 * TABLE_LEADING + 2.0 * CELL_PADDING
+    }
+
+    fn table_row(&mut self, row: &[String], widths: &[f32], font: Font, fill: Option<f32>, max_lines: usize) {
+        let cells: Vec<Vec<String>> = widths.iter().enumerate()
+            .map(|(c, w)| {
+                let mut lines = Self::cell_lines(PdfTable::cell(row, c), *w, font);
+                if lines.len() > max_lines {
+                    lines.truncate(max_lines);
+                    if let Some(last) = lines.last_mut() {
+                        last.push_str(" ...");
+                    }
+                }
+                lines
+            })
+            .collect();
+        let line_count = cells.iter().map(Vec::len).max().unwrap_or(1);
+        let height = line_count as f32 * TABLE_LEADING + 2.0 * CELL_PADDING;
+        let top = self.y;
+        let bottom = top - height;
+
+        let mut x = MARGIN_X;
+        for (lines, width) in cells.iter().zip(widths) {
+            self.rect(x, bottom, *width, height, fill);
+            for (index, line) in lines.iter().enumerate() {
+                let baseline = top - CELL_PADDING - (index as f32 + 1.0) * TABLE_LEADING + (TABLE_LEADING - TABLE_SIZE);
+                self.text(x + CELL_PADDING, baseline, font, TABLE_SIZE, line);
+            }
+            x += width;
+        }
+        self.y = bottom;
+    }
+
+    fn signatures(&mut self, signatures: &[SignatureManifestation]) {
+        self.ensure(TEXT_LEADING * 3.0);
+        self.y -= 8.0;
+        self.wrapped("Electronic Signature Manifestation", Font::Bold, 11.0, 14.0, MARGIN_X, BODY_WIDTH);
+        self.y -= 2.0;
+
+        if signatures.is_empty() {
+            self.wrapped("No electronic signatures recorded.", Font::Regular, TEXT_SIZE, TEXT_LEADING, MARGIN_X, BODY_WIDTH);
+            self.y -= 5.0;
+            return;
+        }
+
+        let label_width = 90.0;
+        let value_width = BODY_WIDTH - label_width - 2.0 * CELL_PADDING - 6.0;
+        for signature in signatures {
+            let mut fields = vec![
+                ("Signed by", signature.signer.as_str()),
+                ("Meaning", signature.meaning.as_str()),
+                ("Date/Time", signature.signed_at.as_str()),
+            ];
+            if let Some(reason) = &signature.reason {
+                fields.push(("Reason", reason.as_str()));
+            }
+            if let Some(id) = &signature.signature_id {
+                fields.push(("Signature ID", id.as_str()));
+            }
+            if let Some(fingerprint) = &signature.fingerprint {
+                fields.push(("Fingerprint", fingerprint.as_str()));
+            }
+
+            let wrapped: Vec<(&str, Vec<String>)> = fields.iter()
+                .map(|(label, value)| (*label, wrap_text(value, Font::Regular, TABLE_SIZE + 0.5, value_width)))
+                .collect();
+            let lines: usize = wrapped.iter().map(|(_, v)| v.len()).sum();
+            let height = lines as f32 * TABLE_LEADING + 2.0 * CELL_PADDING + 4.0;
+
+            // A manifestation is never split across pages
+            self.ensure(height + 6.0);
+            self.y -= 4.0;
+            let top = self.y;
+            self.rect(MARGIN_X, top - height, BODY_WIDTH, height, Some(0.96));
+            let _ = writeln!(self.ops, "1.5 w {:.2} {:.2} m {:.2} {:.2} l S", MARGIN_X, top, MARGIN_X, top - height);
+
+            let mut y = top - CELL_PADDING - 2.0;
+            for (label, values) in wrapped {
+                for (index, value) in values.iter().enumerate() {
+                    y -= TABLE_LEADING;
+                    let baseline = y + TABLE_LEADING - TABLE_SIZE - 1.0;
+                    if index == 0 {
+                        self.text(MARGIN_X + 6.0, baseline, Font::Bold, TABLE_SIZE + 0.5, label);
+                    }
+                    self.text(MARGIN_X + 6.0 + label_width, baseline, Font::Regular, TABLE_SIZE + 0.5, value);
+                }
+            }
+            self.y = top - height - 4.0;
+        }
+    }
+
+    fn preformatted(&mut self, text: &str) {
+        let columns = (BODY_WIDTH / (0.6 * MONO_SIZE)).floor() as usize;
+        self.y -= 2.0;
+        for raw in text.lines() {
+            let chars: Vec<char> = raw.chars().collect();
+            let chunks: Vec<String> = if chars.is_empty() {
+                vec![String::new()]
+            } else {
+                chars.chunks(columns).map(|c| c.iter().collect()).collect()
+            };
+            for chunk in chunks {
+                self.ensure(MONO_LEADING);
+                self.y -= MONO_LEADING;
+                self.text(MARGIN_X, self.y + MONO_LEADING - MONO_SIZE, Font::Mono, MONO_SIZE, &chunk);
+            }
+        }
+        self.y -= 5.0;
+    }
+}
+
+/// Greedy word wrap; words wider than the line are split by character
+fn wrap_text(text: &str, font: Font, size: f32, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for raw in text.split('\n') {
+        let mut line = String::new();
+        for word in raw.split_whitespace() {
+            let candidate = if line.is_empty() { word.to_string() } else { format!("{line} {word}") };
+            if font.text_width(&candidate, size) <= max_width {
+                line = candidate;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            if font.text_width(word, size) <= max_width {
+                line = word.to_string();
+                continue;
+            }
+            for ch in word.chars() {
+                let mut next = line.clone();
+                next.push(ch);
+                if !line.is_empty() && font.text_width(&next, size) > max_width {
+                    lines.push(std::mem::take(&mut line));
+                }
+                line.push(ch);
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Encode text as WinAnsi bytes. Common typographic characters are mapped,
+/// emoji and pictographs are dropped and anything else becomes '?'.
+fn encode_text(text: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            ' '..='~' => bytes.push(ch as u8),
+            '\t' => bytes.push(b' '),
+            '\u{A0}'..='\u{FF}' => bytes.push(ch as u32 as u8),
+            '\u{20AC}' => bytes.push(0x80),
+            '\u{2018}' => bytes.push(0x91),
+            '\u{2019}' => bytes.push(0x92),
+            '\u{201C}' => bytes.push(0x93),
+            '\u{201D}' => bytes.push(0x94),
+            '\u{2022}' => bytes.push(0x95),
+            '\u{2013}' => bytes.push(0x96),
+            '\u{2014}' => bytes.push(0x97),
+            '\u{2026}' => bytes.push(0x85),
+            '\u{2122}' => bytes.push(0x99),
+            '\u{2264}' => bytes.extend_from_slice(b"<="),
+            '\u{2265}' => bytes.extend_from_slice(b">="),
+            '\u{2192}' => bytes.extend_from_slice(b"->"),
+            '\u{2190}' => bytes.extend_from_slice(b"<-"),
+            c if c.is_control() => {}
+            c if c >= '\u{2600}' || ('\u{200B}'..='\u{200D}').contains(&c) || ('\u{FE00}'..='\u{FE0F}').contains(&c) => {}
+            _ => bytes.push(b'?'),
+        }
+    }
+    bytes
+}
+
+/// PDF literal string with escapes; bytes outside ASCII are written as octal
+fn pdf_string(text: &str) -> String {
+    let mut out = String::from("(");
+    for byte in encode_text(text) {
+        match byte {
+            b'(' | b')' | b'\\' => {
+                out.push('\\');
+                out.push(byte as char);
+            }
+            0x20..=0x7E => out.push(byte as char),
+            _ => {
+                let _ = write!(out, "\\{byte:03o}");
+            }
+        }
+    }
+    out.push(')');
+    out
+}
+
+/// Drop inline Markdown markup that has no meaning in the rendered PDF
+fn strip_inline_markdown(text: &str) -> String {
+    let mut out = text.replace("**", "").replace("__", "").replace('`', "");
+
+    // [label](target) -> label (target)
+    while let Some(start) = out.find('[') {
+        let Some(mid) = out[start..].find("](").map(|i| start + i) else { break };
+        let Some(end) = out[mid..].find(')').map(|i| mid + i) else { break };
+        let label = out[start + 1..mid].to_string();
+        let target = out[mid + 2..end].to_string();
+        out.replace_range(start..=end, &format!("{label} ({target})"));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn page_objects(pdf: &str) -> usize {
+        pdf.matches("/Type /Page /Parent").count()
+    }
+
+    #[test]
+    fn test_render_produces_valid_structure() {
+        let mut pdf = PdfDocument::new("Design Verification Report")
+            .with_document_id("DOC-001")
+            .with_revision("B");
+        pdf.heading(1, "Summary").paragraph("All requirements verified (see table).");
+        let mut table = PdfTable::new(&["Requirement", "Result"]);
+        table.add_row(vec!["REQ-001".to_string(), "Pass".to_string()]);
+        pdf.table(table);
+
+        let output = pdf.render();
+        assert!(output.starts_with("%PDF-1.4\n"));
+        assert!(output.ends_with("%%EOF\n"));
+        assert!(output.is_ascii());
+        assert!(output.contains("(Document ID: DOC-001   Revision: B)"));
+        assert!(output.contains("(Page 1 of 1)"));
+        assert!(output.contains("(see table\\).)"));
+        assert_eq!(page_objects(&output), 1);
+
+        // Every xref offset must point at the start of its object
+        let xref_at: usize = output.rsplit("startxref\n").next().unwrap().lines().next().unwrap().parse().unwrap();
+        assert!(output[xref_at..].starts_with("xref\n"));
+        let entries: Vec<&str> = output[xref_at..].lines().skip(3).take_while(|l| !l.starts_with("trailer")).collect();
+        for (index, entry) in entries.iter().enumerate() {
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(output[offset..].starts_with(&format!("{} 0 obj", index + 1)));
+        }
+    }
+
+    #[test]
+    fn test_long_tables_paginate_with_page_numbers() {
+        let mut pdf = PdfDocument::new("Risk Register");
+        let mut table = PdfTable::new(&["ID", "Description", "RPN"]);
+        for i in 0..200 {
+            table.add_row(vec![format!("HAZ-{i:03}"), "Battery overheating during charge cycle".to_string(), "48".to_string()]);
+        }
+        pdf.table(table);
+        pdf.signatures(vec![SignatureManifestation::new("qa.lead", "Approved", "2024-05-01T10:00:00Z")]);
+
+        let pages = pdf.page_count();
+        assert!(pages > 3);
+        let output = pdf.render();
+        assert_eq!(page_objects(&output), pages);
+        assert!(output.contains(&format!("(Page {pages} of {pages})")));
+        // Header row is repeated on continuation pages
+        assert_eq!(output.matches("(Description)").count(), pages);
+        assert!(output.contains("(Electronic Signature Manifestation)"));
+        assert!(output.contains("(qa.lead)"));
+    }
+
+    #[test]
+    fn test_markdown_and_encoding() {
+        let mut pdf = PdfDocument::new("Report");
+        pdf.push_markdown("# Title\n\nSome **bold** text — with ≥ 50 items 📊\n\n| A | B |\n|---|---|\n| 1 | 2 |\n\n- item\n");
+        let output = pdf.render();
+        assert!(output.contains("(Some bold text \\227 with >= 50 items )"));
+        assert!(output.contains("(A)") && output.contains("(2)"));
+        assert!(output.contains("(\\225)"));
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("out").join("report.pdf");
+        let size = pdf.write_to(&path).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), size);
+    }
+}
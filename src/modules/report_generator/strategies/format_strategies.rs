@@ -38,6 +38,16 @@ pub trait FormatStrategy {
 
     /// Validate format-specific content
     fn validate_content(&self, content: &str) -> QmsResult<ValidationResult>;
+
+    /// Format the data formatter should produce as the document body
+    fn body_format(&self, requested: &OutputFormat) -> OutputFormat {
+        requested.clone()
+    }
+
+    /// Assemble the final document from a formatted body
+    fn render_document(&self, metadata: &ReportMetadata, title: &str, body: &str) -> QmsResult<String> {
+        Ok(format!("{}{}{}", self.get_header(metadata, title), body, self.get_footer(metadata)))
+    }
 }
 
 /// Markdown format strategy
@@ -336,6 +346,78 @@ impl FormatStrategy for HTMLFormatStrategy {
     }
 }
 
+/// PDF format strategy
+/// Single Responsibility Principle: Renders a Markdown body into a native PDF document
+pub struct PDFFormatStrategy;
+
+impl FormatStrategy for PDFFormatStrategy {
+    fn get_header(&self, _metadata: &ReportMetadata, _title: &str) -> String {
+        // Running headers are drawn on every page by the PDF writer
+        String::new()
+    }
+
+    fn get_footer(&self, _metadata: &ReportMetadata) -> String {
+        // Running footers are drawn on every page by the PDF writer
+        String::new()
+    }
+
+    fn format_name(&self) -> &'static str {
+        "PDF"
+    }
+
+    fn file_extension(&self) -> &'static str {
+        "pdf"
+    }
+
+    fn mime_type(&self) -> &'static str {
+        "application/pdf"
+    }
+
+    fn validate_content(&self, content: &str) -> QmsResult<ValidationResult> {
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+
+        if !content.starts_with("%PDF-") {
+            errors.push("Missing %PDF- file header".to_string());
+        }
+        if !content.trim_end().ends_with("%%EOF") {
+            errors.push("Missing %%EOF marker".to_string());
+        }
+        if !content.contains("\nxref\n") || !content.contains("\ntrailer\n") {
+            errors.push("Missing cross-reference table or trailer".to_string());
+        }
+        if !content.contains("/Type /Page ") {
+            warnings.push("PDF contains no pages".to_string());
+        }
+
+        Ok(ValidationResult {
+            is_valid: errors.is_empty(),
+            errors,
+            warnings,
+        })
+    }
+
+    fn body_format(&self, _requested: &OutputFormat) -> OutputFormat {
+        OutputFormat::Markdown
+    }
+
+    fn render_document(&self, metadata: &ReportMetadata, title: &str, body: &str) -> QmsResult<String> {
+        use crate::modules::report_generator::pdf::PdfDocument;
+
+        let mut pdf = PdfDocument::new(title)
+            .with_document_id(&metadata.report_type)
+            .with_revision(&metadata.version)
+            .with_author(&metadata.generated_by)
+            .with_footer_note(&format!("Generated {} by {}", metadata.generated_at, metadata.generated_by));
+        pdf.field("Project", &metadata.project_path);
+        if !metadata.compliance_standards.is_empty() {
+            pdf.field("Compliance", &metadata.compliance_standards.join(", "));
+        }
+        pdf.push_markdown(body);
+        Ok(pdf.render())
+    }
+}
+
 /// Factory for creating format strategies
 /// Open/Closed Principle: New format strategies can be added without modifying this factory
 /// Dependency Inversion Principle: Returns trait objects, not concrete types
@@ -417,6 +499,28 @@ mod tests {
         assert_eq!(strategy.mime_type(), "text/html");
     }
 
+    #[test]
+    fn test_pdf_strategy_renders_native_pdf() {
+        let strategy = FormatStrategyFactory::create_strategy(&OutputFormat::PDF).unwrap();
+        assert_eq!(strategy.format_name(), "PDF");
+        assert_eq!(strategy.mime_type(), "application/pdf");
+        assert_eq!(strategy.body_format(&OutputFormat::PDF), OutputFormat::Markdown);
+
+        let metadata = ReportMetadata {
+            report_type: "risk_management".to_string(),
+            version: "1.0".to_string(),
+            generated_at: "2024-01-15T10:00:00Z".to_string(),
+            generated_by: "qa".to_string(),
+            project_path: "/tmp/project".to_string(),
+            compliance_standards: vec!["ISO 14971".to_string()],
+        };
+        let pdf = strategy.render_document(&metadata, "Risk Management Report", "# Risks\n\n| ID | RPN |\n|---|---|\n| HAZ-001 | 12 |\n").unwrap();
+        assert!(pdf.starts_with("%PDF-"));
+        assert!(pdf.contains("(HAZ-001)"));
+        assert!(strategy.validate_content(&pdf).unwrap().is_valid);
+        assert!(!strategy.validate_content("# Not a PDF").unwrap().is_valid);
+    }
+
     #[test]
     fn test_format_strategy_factory() {
         let markdown_strategy = FormatStrategyFactory::create_strategy(&OutputFormat::Markdown).unwrap();
@@ -276,7 +276,8 @@ impl Repository {
     "risk_approval_required": true,
     "user_session_timeout": 3600,
     "max_document_size_mb": 100,
-    "compliance_mode": "FDA_21CFR820"
+    "compliance_mode": "FDA_21CFR820",
+    "storage_backend": "filesystem"
   }
 }"#;
 
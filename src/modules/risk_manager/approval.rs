@@ -168,7 +168,7 @@ impl RiskApprovalManager {
             return Ok(());
         }
         
-        let content = fs::read_to_string(&workflow_path)
+        let content = crate::modules::storage::encryption::read_to_string(&workflow_path)
             .map_err(|e| format!("Failed to read workflow file: {e}"))?;
         
         // Parse workflow entries (simplified JSON parsing)
@@ -220,7 +220,7 @@ impl RiskApprovalManager {
         json_content.push_str("\n  }\n");
         json_content.push_str("}\n");
         
-        fs::write(&workflow_path, json_content)
+        crate::modules::storage::encryption::write(&workflow_path, json_content)
             .map_err(|e| format!("Failed to save workflow data: {e}"))?;
         
         Ok(())
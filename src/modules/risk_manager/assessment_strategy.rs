@@ -12,6 +12,7 @@
 
 use crate::prelude::*;
 use super::{RiskSeverity, RiskOccurrence, RiskDetectability, RiskLevel};
+use super::policy::RiskPolicy;
 
 /// Strategy pattern interface for risk assessment algorithms
 /// Interface Segregation Principle: Focused interface for risk assessment
@@ -21,6 +22,24 @@ pub trait RiskAssessmentStrategy {
     
     /// Assess risk level based on calculated RPN
     fn assess_risk_level(&self, rpn: u32) -> RiskLevel;
+
+    /// Classify a risk under the project's acceptability policy
+    /// Strategies keep their own RPN bands when the policy uses detectability;
+    /// otherwise the policy's severity × probability matrix decides
+    fn classify(
+        &self,
+        severity: &RiskSeverity,
+        occurrence: &RiskOccurrence,
+        _detectability: &RiskDetectability,
+        rpn: u32,
+        policy: &RiskPolicy,
+    ) -> RiskLevel {
+        if policy.use_detectability {
+            self.assess_risk_level(rpn)
+        } else {
+            policy.cell_level(severity, occurrence)
+        }
+    }
     
     /// Get strategy name for audit trail purposes
     fn strategy_name(&self) -> &'static str;
@@ -44,12 +63,19 @@ impl RiskAssessmentStrategy for ISO14971AssessmentStrategy {
     }
     
     fn assess_risk_level(&self, rpn: u32) -> RiskLevel {
-        match rpn {
-            100..=125 => RiskLevel::Unacceptable,
-            25..=99 => RiskLevel::ALARP,
-            1..=24 => RiskLevel::Acceptable,
-            _ => RiskLevel::Acceptable,
-        }
+        RiskPolicy::default().rpn_level(rpn)
+    }
+
+    fn classify(
+        &self,
+        severity: &RiskSeverity,
+        occurrence: &RiskOccurrence,
+        detectability: &RiskDetectability,
+        _rpn: u32,
+        policy: &RiskPolicy,
+    ) -> RiskLevel {
+        // The standard strategy applies the policy as written
+        policy.classify(severity, occurrence, detectability)
     }
     
     fn strategy_name(&self) -> &'static str {
@@ -194,12 +220,20 @@ impl RiskAssessmentStrategyFactory {
 /// Dependency Inversion Principle: Depends on RiskAssessmentStrategy abstraction
 pub struct RiskAssessmentContext {
     strategy: Box<dyn RiskAssessmentStrategy>,
+    policy: RiskPolicy,
 }
 
 impl RiskAssessmentContext {
     /// Create new assessment context with specified strategy
+    /// Uses the current project's risk acceptability policy
     pub fn new(strategy: Box<dyn RiskAssessmentStrategy>) -> Self {
-        Self { strategy }
+        Self { strategy, policy: RiskPolicy::active() }
+    }
+
+    /// Assess against an explicit risk acceptability policy
+    pub fn with_policy(mut self, policy: RiskPolicy) -> Self {
+        self.policy = policy;
+        self
     }
     
     /// Create context with strategy from factory
@@ -221,13 +255,14 @@ impl RiskAssessmentContext {
         // Calculate RPN using selected strategy
         let rpn = self.strategy.calculate_rpn(severity, occurrence, detectability);
         
-        // Assess risk level using selected strategy
-        let risk_level = self.strategy.assess_risk_level(rpn);
+        // Assess risk level using selected strategy under the acceptability policy
+        let risk_level = self.strategy.classify(severity, occurrence, detectability, rpn, &self.policy);
         
         Ok(RiskAssessmentResult {
             rpn,
             risk_level,
             strategy_used: self.strategy.strategy_name().to_string(),
+            policy_version: self.policy.version.clone(),
             severity: severity.clone(),
             occurrence: occurrence.clone(),
             detectability: detectability.clone(),
@@ -244,6 +279,11 @@ impl RiskAssessmentContext {
     pub fn current_strategy_name(&self) -> &str {
         self.strategy.strategy_name()
     }
+
+    /// Risk acceptability policy used for assessments
+    pub const fn policy(&self) -> &RiskPolicy {
+        &self.policy
+    }
 }
 
 /// Result of risk assessment operation
@@ -252,6 +292,7 @@ pub struct RiskAssessmentResult {
     pub rpn: u32,
     pub risk_level: RiskLevel,
     pub strategy_used: String,
+    pub policy_version: String,
     pub severity: RiskSeverity,
     pub occurrence: RiskOccurrence,
     pub detectability: RiskDetectability,
@@ -265,6 +306,7 @@ impl RiskAssessmentResult {
     "rpn": {},
     "risk_level": "{:?}",
     "strategy_used": "{}",
+    "policy_version": "{}",
     "severity": "{:?}",
     "occurrence": "{:?}",
     "detectability": "{:?}"
@@ -272,6 +314,7 @@ impl RiskAssessmentResult {
             self.rpn,
             self.risk_level,
             self.strategy_used,
+            self.policy_version,
             self.severity,
             self.occurrence,
             self.detectability
@@ -321,4 +364,26 @@ mod tests {
         assert_eq!(result.strategy_used, "Conservative_HighRisk");
         assert_eq!(result.rpn, 23); // (3 * 3 * 2) * 1.25 = 22.5 rounded to 23
     }
+
+    #[test]
+    fn test_assessment_records_policy_version() {
+        let mut policy = RiskPolicy {
+            use_detectability: false,
+            ..RiskPolicy::default()
+        };
+        policy.version = "2.3".to_string();
+        policy.matrix[2][2] = RiskLevel::Unacceptable; // Major × Occasional
+
+        let context = RiskAssessmentContext::with_strategy_type("iso14971").unwrap().with_policy(policy);
+        let result = context.assess_risk(
+            &RiskSeverity::Major,
+            &RiskOccurrence::Occasional,
+            &RiskDetectability::VeryHigh
+        ).unwrap();
+
+        assert_eq!(result.rpn, 9);
+        assert_eq!(result.risk_level, RiskLevel::Unacceptable);
+        assert_eq!(result.policy_version, "2.3");
+        assert!(result.to_json().contains("\"policy_version\": \"2.3\""));
+    }
 }
@@ -6,9 +6,9 @@
 //! Medical Device Compliance: Implements ISO 14971 risk assessment methodology
 
 use crate::prelude::*;
-use crate::constants::iso_14971;
 use crate::utils::RiskCalculator;
 use super::risk::{RiskItem, RiskSeverity, RiskOccurrence, RiskDetectability, RiskLevel, MitigationMeasure, VerificationStatus, RiskStatus};
+use super::policy::RiskPolicy;
 use super::repository::RiskRepository;
 use super::validator::RiskValidator;
 
@@ -63,6 +63,7 @@ impl<R: RiskRepository, V: RiskValidator> RiskBusinessLogic<R, V> {
             detectability: RiskDetectability::High,
             risk_priority_number: 0,
             initial_risk_level: RiskLevel::Acceptable,
+            policy_version: String::new(),
             mitigation_measures: Vec::new(),
             residual_severity: RiskSeverity::Minor,
             residual_occurrence: RiskOccurrence::Remote,
@@ -227,7 +228,9 @@ impl<R: RiskRepository, V: RiskValidator> RiskBusinessLogic<R, V> {
             &risk.detectability,
         );
         
-        risk.initial_risk_level = self.determine_risk_level(risk.risk_priority_number);
+        let policy = RiskPolicy::active();
+        risk.initial_risk_level = policy.classify(&risk.severity, &risk.occurrence, &risk.detectability);
+        risk.policy_version = policy.version;
         
         // Validate calculated RPN
         self.validator.validate_rpn(risk.risk_priority_number)?;
@@ -243,7 +246,11 @@ impl<R: RiskRepository, V: RiskValidator> RiskBusinessLogic<R, V> {
             &risk.residual_detectability,
         );
         
-        risk.residual_risk_level = self.determine_risk_level(risk.residual_rpn);
+        risk.residual_risk_level = RiskPolicy::active().classify(
+            &risk.residual_severity,
+            &risk.residual_occurrence,
+            &risk.residual_detectability,
+        );
         
         Ok(())
     }
@@ -280,15 +287,11 @@ impl<R: RiskRepository, V: RiskValidator> RiskBusinessLogic<R, V> {
         Ok(())
     }
     
-    /// Determine risk level based on RPN value per ISO 14971
+    /// Determine risk level based on RPN value per the project's acceptability policy
     fn determine_risk_level(&self, rpn: u32) -> RiskLevel {
-        match rpn {
-            rpn if rpn >= iso_14971::RPN_UNACCEPTABLE_THRESHOLD => RiskLevel::Unacceptable,
-            rpn if rpn >= iso_14971::RPN_ALARP_THRESHOLD => RiskLevel::ALARP,
-            _ => RiskLevel::Acceptable,
-        }
+        RiskPolicy::active().rpn_level(rpn)
     }
-    
+
     /// Get risk by ID
     pub fn get_risk(&self, risk_id: &str) -> QmsResult<RiskItem> {
         self.repository.load_risk(risk_id)
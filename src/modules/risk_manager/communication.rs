@@ -458,7 +458,7 @@ impl RiskCommunicationManager {
     fn load_communications(&mut self) -> QmsResult<()> {
         let comm_file = format!("{}/risks/communications/communications.json", self.project_path);
         if Path::new(&comm_file).exists() {
-            let content = fs::read_to_string(&comm_file)?;
+            let content = crate::modules::storage::encryption::read_to_string(&comm_file)?;
             if !content.trim().is_empty() {
                 self.communications = self.parse_communications_json(&content)?;
             }
@@ -470,7 +470,7 @@ impl RiskCommunicationManager {
     fn save_communications(&self) -> QmsResult<()> {
         let comm_file = format!("{}/risks/communications/communications.json", self.project_path);
         let json = self.serialize_communications_json();
-        fs::write(&comm_file, json)?;
+        crate::modules::storage::encryption::write(&comm_file, json)?;
         Ok(())
     }
 
@@ -209,7 +209,7 @@ impl FMEAManager {
         let index_path = fmea_dir.join("index.json");
         if !index_path.exists() {
             let index_data = r#"{"version": "1.0", "data": []}"#;
-            std::fs::write(&index_path, index_data)?;
+            crate::modules::storage::encryption::write(&index_path, index_data)?;
         }
         
         Ok(())
@@ -328,7 +328,7 @@ impl FMEAManager {
             return Err(QmsError::not_found(&format!("FMEA analysis not found: {fmea_id}")));
         }
         
-        let content = std::fs::read_to_string(&file_path)?;
+        let content = crate::modules::storage::encryption::read_to_string(&file_path)?;
         let mut analysis = self.parse_fmea_json(&content)?;
         
         // TODO: Load failure modes from separate files in future enhancement
@@ -347,7 +347,7 @@ impl FMEAManager {
         let json_content = self.serialize_fmea_to_json(analysis)?;
         
         // Use direct write for now to avoid Windows temp file issues
-        std::fs::write(&file_path, &json_content)
+        crate::modules::storage::encryption::write(&file_path, &json_content)
             .map_err(|e| QmsError::io_error(&format!("Failed to write FMEA file: {e}")))?;
         
         // Update index
@@ -364,7 +364,7 @@ impl FMEAManager {
             return Ok(Vec::new());
         }
         
-        let content = std::fs::read_to_string(&index_path)?;
+        let content = crate::modules::storage::encryption::read_to_string(&index_path)?;
         let fmea_ids = self.parse_fmea_index(&content)?;
         
         let mut analyses = Vec::new();
@@ -565,7 +565,7 @@ impl FMEAManager {
         // Read existing index
         let mut existing_ids = Vec::new();
         if index_path.exists() {
-            let content = std::fs::read_to_string(&index_path)?;
+            let content = crate::modules::storage::encryption::read_to_string(&index_path)?;
             existing_ids = self.parse_fmea_index(&content)?;
         }
         
@@ -591,7 +591,7 @@ impl FMEAManager {
         index_json.push('}');
         
         // Use direct write for now to avoid Windows temp file issues
-        std::fs::write(&index_path, &index_json)
+        crate::modules::storage::encryption::write(&index_path, &index_json)
             .map_err(|e| QmsError::io_error(&format!("Failed to write FMEA index: {e}")))?;
         Ok(())
     }
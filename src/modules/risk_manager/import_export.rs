@@ -7,6 +7,7 @@
 use crate::prelude::*;
 use crate::modules::risk_manager::risk::{RiskItem, RiskManager, RiskSeverity, RiskOccurrence, RiskDetectability, RiskLevel, RiskStatus};
 use crate::modules::audit_logger::functions::{audit_log_action, audit_log_create};
+use crate::modules::report_generator::pdf::{PdfDocument, PdfTable, SignatureManifestation};
 use std::fs;
 use std::path::Path;
 use std::collections::HashMap;
@@ -45,7 +46,7 @@ impl ImportFormat {
 pub enum ExportFormat {
     Csv,           // Standard CSV export
     Json,          // Complete JSON export
-    Pdf,           // Text-based PDF report
+    Pdf,           // Native PDF report
     FmeaTemplate,  // FMEA CSV template
 }
 
@@ -512,7 +513,7 @@ impl RiskImporter {
         let detectability = self.convert_detectability(risk_data.detectability)?;
         let rpn = (risk_data.severity as u32) * (risk_data.occurrence as u32) * (risk_data.detectability as u32);
 
-        Ok(RiskItem {
+        let mut risk = RiskItem {
             id: crate::utils::generate_uuid(),
             project_id: self.manager.get_project_id().to_string(),
             hazard_id: risk_data.hazard_id.clone(),
@@ -523,13 +524,14 @@ impl RiskImporter {
             occurrence: occurrence.clone(),
             detectability: detectability.clone(),
             risk_priority_number: rpn,
-            initial_risk_level: RiskManager::assess_risk_level(rpn),
+            initial_risk_level: RiskLevel::Acceptable,
+            policy_version: String::new(),
             mitigation_measures: Vec::new(),
             residual_severity: severity,
             residual_occurrence: occurrence,
             residual_detectability: detectability,
             residual_rpn: rpn,
-            residual_risk_level: RiskManager::assess_risk_level(rpn),
+            residual_risk_level: RiskLevel::Acceptable,
             residual_risk_justification: None,
             residual_risk_approved: false,
             residual_risk_approved_by: None,
@@ -554,7 +556,9 @@ impl RiskImporter {
             post_market_data: Vec::new(),
             review_required: false,
             next_review_date: None,
-        })
+        };
+        risk.assess_risk_level_with(&self.manager.policy()?);
+        Ok(risk)
     }
 
     /// Update existing risk from import data
@@ -564,7 +568,7 @@ impl RiskImporter {
         risk.occurrence = self.convert_occurrence(risk_data.occurrence)?;
         risk.detectability = self.convert_detectability(risk_data.detectability)?;
         risk.risk_priority_number = (risk_data.severity as u32) * (risk_data.occurrence as u32) * (risk_data.detectability as u32);
-        risk.initial_risk_level = RiskManager::assess_risk_level(risk.risk_priority_number);
+        risk.assess_risk_level_with(&self.manager.policy()?);
         risk.updated_at = crate::utils::format_timestamp(crate::utils::current_timestamp());
 
         // Update description fields if they're more detailed in import
@@ -784,15 +788,16 @@ impl RiskExporter {
         Ok(json_content)
     }
 
-    /// Generate PDF export content (text-based)
+    /// Generate PDF export content
     fn generate_pdf_export(&self, risks: &[RiskItem], options: &ExportOptions) -> QmsResult<String> {
-        let mut pdf_content = String::new();
-        
-        // PDF header (text format)
-        pdf_content.push_str("RISK MANAGEMENT REPORT\n");
-        pdf_content.push_str("========================\n\n");
-        pdf_content.push_str(&format!("Generated: {}\n", crate::utils::format_timestamp(crate::utils::current_timestamp())));
-        pdf_content.push_str(&format!("Total Risks: {}\n\n", risks.len()));
+        let generated = crate::utils::format_timestamp(crate::utils::current_timestamp());
+        let policy = self.manager.policy()?;
+        let mut pdf = PdfDocument::new("Risk Management Report")
+            .with_footer_note(&format!("Generated {generated}"));
+
+        pdf.field("Generated", &generated);
+        pdf.field("Total Risks", &risks.len().to_string());
+        pdf.field("Risk Policy", &format!("{} (v{})", policy.name, policy.version));
 
         if options.summary_only {
             // Summary report
@@ -808,25 +813,35 @@ impl RiskExporter {
             
             let avg_rpn = if !risks.is_empty() { total_rpn / risks.len() as u32 } else { 0 };
             
-            pdf_content.push_str("RISK SUMMARY\n");
-            pdf_content.push_str("============\n\n");
-            pdf_content.push_str(&format!("High RPN Risks (≥50): {high_rpn_count}\n"));
-            pdf_content.push_str(&format!("Average RPN: {avg_rpn}\n\n"));
+            pdf.heading(1, "Risk Summary");
+            pdf.field("High RPN Risks (>=50)", &high_rpn_count.to_string());
+            pdf.field("Average RPN", &avg_rpn.to_string());
         } else {
             // Detailed report
-            pdf_content.push_str("DETAILED RISK LIST\n");
-            pdf_content.push_str("==================\n\n");
-            
+            pdf.heading(1, "Detailed Risk List");
+            let mut table = PdfTable::new(&["Risk ID", "Description", "RPN", "Risk Level", "Status"]);
             for risk in risks {
-                pdf_content.push_str(&format!("Risk ID: {}\n", risk.hazard_id));
-                pdf_content.push_str(&format!("Description: {}\n", risk.hazard_description));
-                pdf_content.push_str(&format!("RPN: {} ({:?})\n", risk.risk_priority_number, risk.initial_risk_level));
-                pdf_content.push_str(&format!("Status: {:?}\n", risk.risk_status));
-                pdf_content.push_str("---\n\n");
+                table.add_row(vec![
+                    risk.hazard_id.clone(),
+                    risk.hazard_description.clone(),
+                    risk.risk_priority_number.to_string(),
+                    format!("{:?}", risk.initial_risk_level),
+                    format!("{:?}", risk.risk_status),
+                ]);
             }
+            pdf.table(table);
+
+            let approvals: Vec<SignatureManifestation> = risks.iter()
+                .filter_map(|risk| {
+                    let approver = risk.approved_by.as_ref()?;
+                    let date = risk.approval_date.clone().unwrap_or_default();
+                    Some(SignatureManifestation::new(approver, &format!("Approved risk {}", risk.hazard_id), &date))
+                })
+                .collect();
+            pdf.signatures(approvals);
         }
 
-        Ok(pdf_content)
+        Ok(pdf.render())
     }
 
     /// Generate FMEA template export
@@ -14,7 +14,9 @@
  */
 
 use crate::prelude::*;
+use crate::modules::baseline::{Baseline, BaselineManager};
 use crate::modules::risk_manager::risk::{RiskManager, RiskItem, RiskLevel};
+use crate::modules::report_generator::pdf::PdfDocument;
 use std::collections::HashMap;
 
 /// ISO 14971 compliance validator and report generator
@@ -64,6 +66,7 @@ pub struct ComplianceReport {
 pub struct RMFGenerator {
     project_path: PathBuf,
     template_path: PathBuf,
+    baseline: Option<Baseline>, // Render the file from the risks frozen in this baseline
 }
 
 /// RMF generation options
@@ -653,7 +656,7 @@ impl ISO14971Validator {
         let mut config = HashMap::new();
         
         if config_path.exists() {
-            let content = std::fs::read_to_string(&config_path)?;
+            let content = crate::modules::storage::encryption::read_to_string(&config_path)?;
             // Basic JSON parsing for project configuration
             if let Some(id_start) = content.find(r#""id":"#) {
                 let search_start = id_start + 5; // After "id":"
@@ -744,27 +747,49 @@ impl RMFGenerator {
         Ok(Self {
             project_path: project_path.to_path_buf(),
             template_path,
+            baseline: None,
         })
     }
 
+    /// Render the file from the risks frozen in `baseline`
+    pub fn with_baseline(mut self, baseline: Baseline) -> Self {
+        self.baseline = Some(baseline);
+        self
+    }
+
     /// Generate Risk Management File
     pub fn generate_rmf(&self, output_path: &Path, options: &RMFOptions) -> QmsResult<()> {
         println!("📋 Generating Risk Management File...");
         
         // Load template
         let template_content = if self.template_path.exists() {
-            std::fs::read_to_string(&self.template_path)?
+            crate::modules::storage::encryption::read_to_string(&self.template_path)?
         } else {
             self.get_default_rmf_template()
         };
         
-        // Load risk data
-        let risk_manager = RiskManager::new(&self.project_path)?;
-        let risks = risk_manager.list_all_risks()?;
+        // Load risk data, as frozen in the baseline when one is set
+        let risks = match self.baseline {
+            Some(ref baseline) => BaselineManager::new(&self.project_path)?.risks(baseline)?,
+            None => RiskManager::new(&self.project_path)?.list_all_risks()?,
+        };
         
         // Generate content based on template and risk data
         let rmf_content = self.populate_rmf_template(&template_content, &risks, options)?;
         
+        // Submissions need a genuine PDF; other formats keep the populated template
+        let rmf_content = match options.output_format {
+            RMFFormat::PDF => {
+                let mut pdf = PdfDocument::new("Risk Management File")
+                    .with_document_id("RMF")
+                    .with_revision(self.revision())
+                    .with_footer_note(&format!("ISO 14971:2019 - {}", options.regulatory_jurisdiction));
+                pdf.push_markdown(&rmf_content);
+                pdf.render()
+            }
+            _ => rmf_content,
+        };
+
         // Write to output file
         std::fs::write(output_path, rmf_content)?;
         
@@ -784,7 +809,7 @@ impl RMFGenerator {
         content = content.replace("{{PROJECT_NAME}}", "Medical Device Project");
         content = content.replace("{{DATE}}", &crate::utils::current_date_string());
         content = content.replace("{{USER}}", "System");
-        content = content.replace("{{VERSION}}", "1.0");
+        content = content.replace("{{VERSION}}", self.revision());
         
         // Add risk analysis data
         if !risks.is_empty() {
@@ -799,6 +824,11 @@ impl RMFGenerator {
         Ok(content)
     }
 
+    /// Document revision: the baseline name, or 1.0 for the working state
+    fn revision(&self) -> &str {
+        self.baseline.as_ref().map_or("1.0", |baseline| baseline.name.as_str())
+    }
+
     fn generate_risk_analysis_section(&self, risks: &[RiskItem]) -> String {
         let mut section = String::from("### 3.3 Hazard Identification\n\n");
         
@@ -10,6 +10,7 @@ pub mod categorization;
 pub mod approval;
 pub mod communication;
 pub mod metrics;
+pub mod policy;
 
 // SOLID Principle Enhancement Modules
 pub mod assessment_strategy;
@@ -42,13 +43,15 @@ pub use fmea::{
 
 pub use iso14971::{
     ISO14971Validator, ComplianceStatus,
-    RMFOptions, RMFFormat
+    RMFGenerator, RMFOptions, RMFFormat
 };
 
 pub use scoring::{
     RiskMatrix, MatrixColor, RiskScoring
 };
 
+pub use policy::RiskPolicy;
+
 pub use reporting::{
     RiskReporter, ReportType, ReportFormat, TimePeriod, RiskTrend
 };
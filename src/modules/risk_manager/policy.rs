@@ -0,0 +1,445 @@
+//! Risk Acceptability Policy
+//!
+//! ISO 14971:2019 Section 4.4 requires the manufacturer to define its own
+//! criteria for risk acceptability in the risk management plan. This module
+//! holds those criteria per project in `config/risk_policy.json`: the scales
+//! and their labels, the severity × probability classification matrix and
+//! whether detectability takes part in the decision.
+//!
+//! Projects without a policy file use the built-in policy, which reproduces the
+//! RPN bands used before policies existed (1-24 Acceptable, 25-99 ALARP,
+//! 100-125 Unacceptable).
+
+use crate::constants::iso_14971;
+use crate::fs_utils::atomic_write;
+use crate::json_utils::{JsonError, JsonValue};
+use crate::prelude::*;
+use super::risk::{escape_json_string, RiskDetectability, RiskLevel, RiskOccurrence, RiskSeverity};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Policy file name inside the project's `config` directory
+pub const RISK_POLICY_FILE: &str = "risk_policy.json";
+
+/// Version of the built-in policy
+pub const DEFAULT_POLICY_VERSION: &str = "1.0";
+
+/// Number of levels on the severity, occurrence and detectability enums
+const ENUM_LEVELS: usize = 5;
+
+/// Project risk acceptability policy
+///
+/// `matrix` rows follow `severity_scale` and columns follow
+/// `probability_scale`, both from the lowest level to the highest. Scales may
+/// have 2 to 5 levels; the five built-in severity and occurrence levels are
+/// spread evenly over a shorter scale.
+///
+/// When `use_detectability` is set, acceptability follows the RPN
+/// (severity × occurrence × detectability) against `alarp_threshold` and
+/// `unacceptable_threshold`. Otherwise the matrix cell decides and
+/// detectability is recorded for FMEA only.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskPolicy {
+    pub version: String,
+    pub name: String,
+    pub use_detectability: bool,
+    pub alarp_threshold: u32,
+    pub unacceptable_threshold: u32,
+    pub severity_scale: Vec<String>,
+    pub probability_scale: Vec<String>,
+    pub detectability_scale: Vec<String>,
+    pub matrix: Vec<Vec<RiskLevel>>,
+}
+
+impl Default for RiskPolicy {
+    fn default() -> Self {
+        let alarp_threshold = iso_14971::RPN_ALARP_THRESHOLD;
+        let unacceptable_threshold = iso_14971::RPN_UNACCEPTABLE_THRESHOLD;
+
+        // Starting matrix for projects that switch detectability off: the RPN
+        // bands evaluated at moderate detectability
+        let moderate = RiskDetectability::Moderate as u32;
+        let matrix = (1..=ENUM_LEVELS as u32)
+            .map(|severity| {
+                (1..=ENUM_LEVELS as u32)
+                    .map(|occurrence| {
+                        rpn_band(severity * occurrence * moderate, alarp_threshold, unacceptable_threshold)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            version: DEFAULT_POLICY_VERSION.to_string(),
+            name: "ISO 14971 RPN bands".to_string(),
+            use_detectability: true,
+            alarp_threshold,
+            unacceptable_threshold,
+            severity_scale: to_strings(&["Negligible", "Minor", "Major", "Critical", "Catastrophic"]),
+            probability_scale: to_strings(&["Improbable", "Remote", "Occasional", "Probable", "Frequent"]),
+            detectability_scale: to_strings(&["Very High", "High", "Moderate", "Low", "Very Low"]),
+            matrix,
+        }
+    }
+}
+
+impl RiskPolicy {
+    /// Location of the policy file for a project
+    pub fn policy_path(project_path: &Path) -> PathBuf {
+        project_path.join("config").join(RISK_POLICY_FILE)
+    }
+
+    /// Load the project's policy, or the built-in policy if it has none
+    pub fn load(project_path: &Path) -> QmsResult<Self> {
+        let path = Self::policy_path(project_path);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(&path)?;
+        let policy = Self::from_json(&content)
+            .map_err(|e| QmsError::parse_error(&format!("Invalid risk policy {}: {e}", path.display())))?;
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    /// Policy of the current project
+    ///
+    /// For callers without a project path at hand. Falls back to the built-in
+    /// policy outside a project or when the policy file cannot be used.
+    pub fn active() -> Self {
+        let Some(project_path) = crate::utils::get_current_project_path_safe() else {
+            return Self::default();
+        };
+        Self::load(&project_path).unwrap_or_else(|e| {
+            eprintln!("Warning: {e}; using the built-in risk policy");
+            Self::default()
+        })
+    }
+
+    /// Validate and write the policy to the project
+    pub fn save(&self, project_path: &Path) -> QmsResult<()> {
+        self.validate()?;
+        let path = Self::policy_path(project_path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        atomic_write(&path, &self.to_json())
+    }
+
+    /// Check that scales, matrix and thresholds are consistent
+    pub fn validate(&self) -> QmsResult<()> {
+        if self.version.trim().is_empty() {
+            return Err(QmsError::validation_error("Risk policy version is required"));
+        }
+        for (axis, scale) in [
+            ("severity", &self.severity_scale),
+            ("probability", &self.probability_scale),
+        ] {
+            if !(2..=ENUM_LEVELS).contains(&scale.len()) {
+                return Err(QmsError::validation_error(&format!(
+                    "Risk policy {axis} scale must have 2 to {ENUM_LEVELS} levels, found {}",
+                    scale.len()
+                )));
+            }
+        }
+        if self.detectability_scale.len() != ENUM_LEVELS {
+            return Err(QmsError::validation_error(&format!(
+                "Risk policy detectability scale must have {ENUM_LEVELS} labels"
+            )));
+        }
+        if self.matrix.len() != self.severity_scale.len()
+            || self.matrix.iter().any(|row| row.len() != self.probability_scale.len())
+        {
+            return Err(QmsError::validation_error(&format!(
+                "Risk policy matrix must be {} x {} (severity x probability)",
+                self.severity_scale.len(),
+                self.probability_scale.len()
+            )));
+        }
+        if self.alarp_threshold == 0 || self.alarp_threshold > self.unacceptable_threshold {
+            return Err(QmsError::validation_error(
+                "Risk policy thresholds must satisfy 0 < alarp_threshold <= unacceptable_threshold",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Acceptability of a risk under this policy
+    pub fn classify(
+        &self,
+        severity: &RiskSeverity,
+        occurrence: &RiskOccurrence,
+        detectability: &RiskDetectability,
+    ) -> RiskLevel {
+        if self.use_detectability {
+            let rpn = severity.clone() as u32 * occurrence.clone() as u32 * detectability.clone() as u32;
+            self.rpn_level(rpn)
+        } else {
+            self.cell_level(severity, occurrence)
+        }
+    }
+
+    /// Acceptability of an RPN against the policy thresholds
+    pub const fn rpn_level(&self, rpn: u32) -> RiskLevel {
+        rpn_band(rpn, self.alarp_threshold, self.unacceptable_threshold)
+    }
+
+    /// Classification of the severity × probability matrix cell
+    pub fn cell_level(&self, severity: &RiskSeverity, occurrence: &RiskOccurrence) -> RiskLevel {
+        self.matrix[self.severity_index(severity)][self.probability_index(occurrence)].clone()
+    }
+
+    /// Matrix row holding a severity
+    pub fn severity_index(&self, severity: &RiskSeverity) -> usize {
+        scale_index(severity.clone() as usize, self.severity_scale.len())
+    }
+
+    /// Matrix column holding an occurrence
+    pub fn probability_index(&self, occurrence: &RiskOccurrence) -> usize {
+        scale_index(occurrence.clone() as usize, self.probability_scale.len())
+    }
+
+    pub fn severity_label(&self, severity: &RiskSeverity) -> &str {
+        &self.severity_scale[self.severity_index(severity)]
+    }
+
+    pub fn probability_label(&self, occurrence: &RiskOccurrence) -> &str {
+        &self.probability_scale[self.probability_index(occurrence)]
+    }
+
+    pub fn detectability_label(&self, detectability: &RiskDetectability) -> &str {
+        &self.detectability_scale[detectability.clone() as usize - 1]
+    }
+
+    /// One-line description of the acceptability criteria for reports
+    pub fn criteria_summary(&self) -> String {
+        if self.use_detectability {
+            format!(
+                "RPN (S x O x D): Acceptable < {}, ALARP {}-{}, Unacceptable >= {}",
+                self.alarp_threshold,
+                self.alarp_threshold,
+                self.unacceptable_threshold.saturating_sub(1),
+                self.unacceptable_threshold
+            )
+        } else {
+            format!(
+                "{} x {} severity x probability matrix (detectability not used)",
+                self.severity_scale.len(),
+                self.probability_scale.len()
+            )
+        }
+    }
+
+    /// Serialize the policy to its file format
+    pub fn to_json(&self) -> String {
+        let labels = |scale: &[String]| {
+            scale
+                .iter()
+                .map(|label| format!("\"{}\"", escape_json_string(label)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let rows = self
+            .matrix
+            .iter()
+            .map(|row| {
+                let cells = row.iter().map(|level| format!("\"{level:?}\"")).collect::<Vec<_>>().join(", ");
+                format!("    [{cells}]")
+            })
+            .collect::<Vec<_>>()
+            .join(",\n");
+
+        format!(
+            "{{\n  \"version\": \"{}\",\n  \"name\": \"{}\",\n  \"use_detectability\": {},\n  \
+             \"alarp_threshold\": {},\n  \"unacceptable_threshold\": {},\n  \
+             \"severity_scale\": [{}],\n  \"probability_scale\": [{}],\n  \
+             \"detectability_scale\": [{}],\n  \"matrix\": [\n{}\n  ]\n}}\n",
+            escape_json_string(&self.version),
+            escape_json_string(&self.name),
+            self.use_detectability,
+            self.alarp_threshold,
+            self.unacceptable_threshold,
+            labels(&self.severity_scale),
+            labels(&self.probability_scale),
+            labels(&self.detectability_scale),
+            rows
+        )
+    }
+
+    /// Parse a policy file; fields left out take the built-in values
+    pub fn from_json(content: &str) -> Result<Self, JsonError> {
+        let obj = match JsonValue::parse(content)? {
+            JsonValue::Object(obj) => obj,
+            _ => return Err(JsonError::InvalidFormat("Expected JSON object".to_string())),
+        };
+        let defaults = Self::default();
+
+        let matrix = match obj.get("matrix") {
+            Some(JsonValue::Array(rows)) => rows
+                .iter()
+                .map(|row| match row {
+                    JsonValue::Array(cells) => cells.iter().map(parse_level).collect(),
+                    _ => Err(JsonError::InvalidFormat("Matrix rows must be arrays".to_string())),
+                })
+                .collect::<Result<Vec<Vec<RiskLevel>>, JsonError>>()?,
+            Some(_) => return Err(JsonError::InvalidFormat("Field 'matrix' is not an array".to_string())),
+            None => defaults.matrix,
+        };
+
+        Ok(Self {
+            version: get_string(&obj, "version")?.unwrap_or(defaults.version),
+            name: get_string(&obj, "name")?.unwrap_or(defaults.name),
+            use_detectability: obj
+                .get("use_detectability")
+                .and_then(JsonValue::as_bool)
+                .unwrap_or(defaults.use_detectability),
+            alarp_threshold: get_u32(&obj, "alarp_threshold")?.unwrap_or(defaults.alarp_threshold),
+            unacceptable_threshold: get_u32(&obj, "unacceptable_threshold")?
+                .unwrap_or(defaults.unacceptable_threshold),
+            severity_scale: get_strings(&obj, "severity_scale")?.unwrap_or(defaults.severity_scale),
+            probability_scale: get_strings(&obj, "probability_scale")?.unwrap_or(defaults.probability_scale),
+            detectability_scale: get_strings(&obj, "detectability_scale")?
+                .unwrap_or(defaults.detectability_scale),
+            matrix,
+        })
+    }
+}
+
+/// Classify an RPN against ALARP and unacceptable thresholds
+const fn rpn_band(rpn: u32, alarp_threshold: u32, unacceptable_threshold: u32) -> RiskLevel {
+    if rpn >= unacceptable_threshold {
+        RiskLevel::Unacceptable
+    } else if rpn >= alarp_threshold {
+        RiskLevel::ALARP
+    } else {
+        RiskLevel::Acceptable
+    }
+}
+
+/// Map a 1-5 enum value onto a scale of `levels` entries
+const fn scale_index(value: usize, levels: usize) -> usize {
+    (value.saturating_sub(1) * levels) / ENUM_LEVELS
+}
+
+fn to_strings(labels: &[&str]) -> Vec<String> {
+    labels.iter().map(|label| (*label).to_string()).collect()
+}
+
+fn parse_level(value: &JsonValue) -> Result<RiskLevel, JsonError> {
+    match value.as_string().map(|s| s.to_lowercase()).as_deref() {
+        Some("acceptable") => Ok(RiskLevel::Acceptable),
+        Some("alarp") => Ok(RiskLevel::ALARP),
+        Some("unacceptable") => Ok(RiskLevel::Unacceptable),
+        _ => Err(JsonError::InvalidFormat(format!(
+            "Invalid matrix cell {value}: expected Acceptable, ALARP or Unacceptable"
+        ))),
+    }
+}
+
+fn get_string(obj: &HashMap<String, JsonValue>, field: &str) -> Result<Option<String>, JsonError> {
+    match obj.get(field) {
+        Some(JsonValue::String(s)) => Ok(Some(s.clone())),
+        Some(_) => Err(JsonError::InvalidFormat(format!("Field '{field}' is not a string"))),
+        None => Ok(None),
+    }
+}
+
+fn get_u32(obj: &HashMap<String, JsonValue>, field: &str) -> Result<Option<u32>, JsonError> {
+    match obj.get(field) {
+        Some(JsonValue::Number(n)) if *n >= 0.0 => Ok(Some(*n as u32)),
+        Some(_) => Err(JsonError::InvalidFormat(format!("Field '{field}' is not a positive number"))),
+        None => Ok(None),
+    }
+}
+
+fn get_strings(obj: &HashMap<String, JsonValue>, field: &str) -> Result<Option<Vec<String>>, JsonError> {
+    match obj.get(field) {
+        Some(JsonValue::Array(items)) => items
+            .iter()
+            .map(|item| {
+                item.as_string()
+                    .cloned()
+                    .ok_or_else(|| JsonError::InvalidFormat(format!("Field '{field}' must hold strings")))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some),
+        Some(_) => Err(JsonError::InvalidFormat(format!("Field '{field}' is not an array"))),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_default_policy_matches_legacy_bands() {
+        let policy = RiskPolicy::default();
+        assert!(policy.validate().is_ok());
+        assert_eq!(policy.rpn_level(24), RiskLevel::Acceptable);
+        assert_eq!(policy.rpn_level(25), RiskLevel::ALARP);
+        assert_eq!(policy.rpn_level(99), RiskLevel::ALARP);
+        assert_eq!(policy.rpn_level(100), RiskLevel::Unacceptable);
+        assert_eq!(
+            policy.classify(&RiskSeverity::Critical, &RiskOccurrence::Probable, &RiskDetectability::Low),
+            RiskLevel::ALARP
+        );
+    }
+
+    #[test]
+    fn test_matrix_policy_ignores_detectability() {
+        let mut policy = RiskPolicy {
+            use_detectability: false,
+            severity_scale: to_strings(&["Low", "Medium", "High"]),
+            probability_scale: to_strings(&["Unlikely", "Likely", "Certain"]),
+            matrix: vec![
+                vec![RiskLevel::Acceptable, RiskLevel::Acceptable, RiskLevel::ALARP],
+                vec![RiskLevel::Acceptable, RiskLevel::ALARP, RiskLevel::Unacceptable],
+                vec![RiskLevel::ALARP, RiskLevel::Unacceptable, RiskLevel::Unacceptable],
+            ],
+            ..RiskPolicy::default()
+        };
+        policy.version = "2.1".to_string();
+        assert!(policy.validate().is_ok());
+
+        // Catastrophic falls in the top row, Remote in the bottom column
+        assert_eq!(policy.severity_label(&RiskSeverity::Catastrophic), "High");
+        assert_eq!(policy.probability_label(&RiskOccurrence::Remote), "Unlikely");
+        for detectability in [RiskDetectability::VeryHigh, RiskDetectability::VeryLow] {
+            assert_eq!(
+                policy.classify(&RiskSeverity::Catastrophic, &RiskOccurrence::Remote, &detectability),
+                RiskLevel::ALARP
+            );
+        }
+        assert_eq!(
+            policy.classify(&RiskSeverity::Major, &RiskOccurrence::Occasional, &RiskDetectability::VeryHigh),
+            RiskLevel::ALARP
+        );
+    }
+
+    #[test]
+    fn test_policy_file_round_trip_and_validation() {
+        let dir = tempdir().unwrap();
+        assert_eq!(RiskPolicy::load(dir.path()).unwrap(), RiskPolicy::default());
+
+        let mut policy = RiskPolicy {
+            version: "3.0".to_string(),
+            use_detectability: false,
+            ..RiskPolicy::default()
+        };
+        policy.save(dir.path()).unwrap();
+        assert_eq!(RiskPolicy::load(dir.path()).unwrap(), policy);
+
+        policy.matrix.pop();
+        assert!(policy.save(dir.path()).is_err());
+
+        std::fs::write(
+            RiskPolicy::policy_path(dir.path()),
+            r#"{"version": "4", "matrix": [["Acceptable", "Bad"]]}"#,
+        )
+        .unwrap();
+        assert!(RiskPolicy::load(dir.path()).is_err());
+    }
+}
@@ -8,7 +8,9 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use crate::prelude::*;
-use super::risk::RiskItem;
+use super::policy::RiskPolicy;
+use crate::modules::report_generator::pdf::PdfDocument;
+use super::risk::{escape_json_string, RiskItem, RiskLevel};
 
 /// Risk report types for different analysis needs
 #[derive(Debug, Clone)]
@@ -69,6 +71,7 @@ pub struct RiskAnalytics {
 pub struct RiskReporter {
     project_path: PathBuf,
     reports_dir: PathBuf,
+    policy: RiskPolicy,
 }
 
 impl RiskReporter {
@@ -84,8 +87,23 @@ impl RiskReporter {
         Ok(RiskReporter {
             project_path: project_path.to_path_buf(),
             reports_dir,
+            policy: RiskPolicy::load(project_path)?,
         })
     }
+
+    /// Count risks by initial acceptability: (unacceptable, ALARP, acceptable)
+    fn acceptability_counts(risks: &[RiskItem]) -> (usize, usize, usize) {
+        risks.iter().fold((0, 0, 0), |(u, a, ok), risk| match risk.initial_risk_level {
+            RiskLevel::Unacceptable => (u + 1, a, ok),
+            RiskLevel::ALARP => (u, a + 1, ok),
+            RiskLevel::Acceptable => (u, a, ok + 1),
+        })
+    }
+
+    /// Risks whose assessment predates the project's current policy version
+    fn stale_assessment_count(&self, risks: &[RiskItem]) -> usize {
+        risks.iter().filter(|risk| risk.policy_version != self.policy.version).count()
+    }
     
     /// Generate analytics from risk data
     pub fn generate_analytics(&self, risks: &[RiskItem]) -> RiskAnalytics {
@@ -152,9 +170,11 @@ impl RiskReporter {
             ReportFormat::JSON => self.generate_json_summary(risks, &analytics),
             ReportFormat::HTML => self.generate_html_summary(risks, &analytics),
             ReportFormat::PDF => {
-                // For PDF, generate HTML first then note that PDF conversion would require external tool
-                let html = self.generate_html_summary(risks, &analytics)?;
-                Ok(format!("<!-- PDF Generation Note: This HTML can be converted to PDF using tools like wkhtmltopdf -->\n{html}"))
+                // Render the Markdown summary through the native PDF writer
+                let markdown = self.generate_markdown_summary(risks, &analytics)?;
+                let mut pdf = PdfDocument::new("Risk Management Summary Report");
+                pdf.push_markdown(&markdown);
+                Ok(pdf.render())
             }
         }
     }
@@ -174,6 +194,15 @@ impl RiskReporter {
         report.push_str(&format!("- **High Priority Risks (RPN > 100):** {}\n", analytics.high_priority_count));
         report.push_str(&format!("- **Risks with Mitigation:** {}\n", analytics.risks_with_mitigation));
         report.push_str(&format!("- **Risks without Mitigation:** {}\n\n", analytics.risks_without_mitigation));
+
+        // Acceptability under the project's risk policy
+        let (unacceptable, alarp, acceptable) = Self::acceptability_counts(risks);
+        report.push_str("## Risk Acceptability\n\n");
+        report.push_str(&format!("- **Policy:** {} (v{})\n", self.policy.name, self.policy.version));
+        report.push_str(&format!("- **Criteria:** {}\n", self.policy.criteria_summary()));
+        report.push_str(&format!("- **Unacceptable:** {unacceptable}\n"));
+        report.push_str(&format!("- **ALARP:** {alarp}\n"));
+        report.push_str(&format!("- **Acceptable:** {acceptable}\n\n"));
         
         // Risk Distribution
         report.push_str("## Risk Distribution\n\n");
@@ -227,6 +256,13 @@ impl RiskReporter {
         if analytics.average_rpn > 50.0 {
             report.push_str("📈 **Process Improvement:** Consider systematic risk reduction strategies.\n\n");
         }
+        let stale = self.stale_assessment_count(risks);
+        if stale > 0 {
+            report.push_str(&format!(
+                "🔁 **Re-assessment:** {stale} risks were assessed under a policy other than v{}.\n\n",
+                self.policy.version
+            ));
+        }
         
         Ok(report)
     }
@@ -234,13 +270,13 @@ impl RiskReporter {
     /// Generate CSV format summary
     fn generate_csv_summary(&self, risks: &[RiskItem], _analytics: &RiskAnalytics) -> QmsResult<String> {
         let mut csv = String::new();
-        csv.push_str("Hazard ID,Project ID,Hazard Description,Harm,RPN,Severity,Occurrence,Detectability,Risk Status,Mitigation Count,Created At,Updated At\n");
+        csv.push_str("Hazard ID,Project ID,Hazard Description,Harm,RPN,Severity,Occurrence,Detectability,Risk Status,Mitigation Count,Created At,Updated At,Risk Level,Policy Version\n");
         
         for risk in risks {
             let mitigation_count = risk.mitigation_measures.len();
             
             csv.push_str(&format!(
-                "{},{},{},{},{},{:?},{:?},{:?},{:?},{},{},{}\n",
+                "{},{},{},{},{},{:?},{:?},{:?},{:?},{},{},{},{:?},{}\n",
                 risk.hazard_id,
                 risk.project_id,
                 risk.hazard_description.replace(',', ";"),
@@ -252,7 +288,9 @@ impl RiskReporter {
                 risk.risk_status,
                 mitigation_count,
                 risk.created_at,
-                risk.updated_at
+                risk.updated_at,
+                risk.initial_risk_level,
+                risk.policy_version
             ));
         }
         
@@ -264,6 +302,11 @@ impl RiskReporter {
         let mut json = String::new();
         json.push_str("{\n");
         json.push_str(&format!("  \"generated_at\": \"{} UTC\",\n", get_current_timestamp_string()));
+        json.push_str(&format!(
+            "  \"policy\": {{\"name\": \"{}\", \"version\": \"{}\"}},\n",
+            escape_json_string(&self.policy.name),
+            escape_json_string(&self.policy.version)
+        ));
         json.push_str("  \"summary\": {\n");
         json.push_str(&format!("    \"total_risks\": {},\n", analytics.total_risks));
         json.push_str(&format!("    \"average_rpn\": {:.1},\n", analytics.average_rpn));
@@ -297,6 +340,8 @@ impl RiskReporter {
             json.push_str(&format!("      \"occurrence\": \"{:?}\",\n", risk.occurrence));
             json.push_str(&format!("      \"detectability\": \"{:?}\",\n", risk.detectability));
             json.push_str(&format!("      \"rpn\": {},\n", risk.risk_priority_number));
+            json.push_str(&format!("      \"risk_level\": \"{:?}\",\n", risk.initial_risk_level));
+            json.push_str(&format!("      \"policy_version\": \"{}\",\n", escape_json_string(&risk.policy_version)));
             json.push_str(&format!("      \"risk_status\": \"{:?}\",\n", risk.risk_status));
             json.push_str(&format!("      \"mitigation_count\": {},\n", risk.mitigation_measures.len()));
             json.push_str(&format!("      \"created_at\": \"{}\",\n", risk.created_at));
@@ -422,6 +467,7 @@ mod tests {
             detectability: RiskDetectability::Moderate,
             risk_priority_number: rpn,
             initial_risk_level: if rpn > 50 { RiskLevel::Unacceptable } else { RiskLevel::Acceptable },
+            policy_version: String::new(),
             mitigation_measures: if has_mitigation { 
                 vec![MitigationMeasure {
                     id: "MIT-001".to_string(),
@@ -511,7 +557,29 @@ mod tests {
         assert!(report.contains("High Priority Risks"));
         assert!(report.contains("RISK-001"));
     }
-    
+
+    #[test]
+    fn test_report_uses_project_risk_policy() {
+        let temp_dir = TempDir::new().unwrap();
+        let policy = RiskPolicy {
+            name: "Class III criteria".to_string(),
+            version: "2.0".to_string(),
+            ..RiskPolicy::default()
+        };
+        policy.save(temp_dir.path()).unwrap();
+        let reporter = RiskReporter::new(temp_dir.path()).unwrap();
+
+        let mut current = create_test_risk("RISK-001", 120, true);
+        current.policy_version = "2.0".to_string();
+        let risks = vec![current, create_test_risk("RISK-002", 50, false)];
+
+        let report = reporter.generate_summary_report(&risks, ReportFormat::Markdown).unwrap();
+
+        assert!(report.contains("**Policy:** Class III criteria (v2.0)"));
+        assert!(report.contains("**Unacceptable:** 1"));
+        assert!(report.contains("1 risks were assessed under a policy other than v2.0"));
+    }
+
     #[test]
     fn test_csv_report_generation() {
         let temp_dir = TempDir::new().unwrap();
@@ -555,4 +623,19 @@ mod tests {
         assert!(report.contains("RISK-001"));
         assert!(report.contains("class=\"high-rpn\"")); // High RPN styling (>50)
     }
+
+    #[test]
+    fn test_pdf_report_generation() {
+        let temp_dir = TempDir::new().unwrap();
+        let reporter = RiskReporter::new(temp_dir.path()).unwrap();
+        
+        let risks = vec![create_test_risk("RISK-001", 150, true)];
+        
+        let report = reporter.generate_summary_report(&risks, ReportFormat::PDF).unwrap();
+        
+        assert!(report.starts_with("%PDF-1.4"));
+        assert!(report.contains("(Risk Management Summary Report)"));
+        assert!(report.contains("(RISK-001)"));
+        assert!(!report.contains("wkhtmltopdf"));
+    }
 }
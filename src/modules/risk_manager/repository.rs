@@ -90,7 +90,7 @@ impl RiskRepository for FileRiskRepository {
         let json_content = format!("{{\"id\":\"{}\",\"hazard_id\":\"{}\"}}", risk.id, risk.hazard_id);
         
         // Write to file with audit logging
-        fs::write(&file_path, json_content)?;
+        crate::modules::storage::encryption::write(&file_path, json_content)?;
         
         // Log persistence operation for audit trail
         crate::audit::log_audit(&format!(
@@ -6,7 +6,8 @@
 //! Medical Device Compliance: Maintains ISO 14971 compliance through abstraction
 
 use crate::prelude::*;
-use super::risk::{RiskItem, RiskSeverity, RiskOccurrence, RiskDetectability, RiskIndexEntry, RiskFilter};
+use super::policy::RiskPolicy;
+use super::risk::{RiskItem, RiskSeverity, RiskOccurrence, RiskDetectability, RiskIndexEntry, RiskFilter, RiskLevel};
 
 /// Risk Service Trait - DIP: Abstraction for risk management operations
 ///
@@ -122,6 +123,7 @@ impl RiskService for FileRiskService {
     
     fn get_risk_statistics(&self) -> QmsResult<RiskStatistics> {
         let risks = self.risk_manager.list_risks(None)?;
+        let policy = RiskPolicy::active();
         
         let mut stats = RiskStatistics::default();
         stats.total_risks = risks.len();
@@ -131,17 +133,11 @@ impl RiskService for FileRiskService {
                 stats.high_priority_risks += 1;
             }
             
-            // Categorize by risk level using ISO 14971 thresholds
-            match risk.rpn {
-                rpn if rpn >= crate::constants::iso_14971::RPN_UNACCEPTABLE_THRESHOLD => {
-                    stats.unacceptable_risks += 1;
-                }
-                rpn if rpn >= crate::constants::iso_14971::RPN_ALARP_THRESHOLD => {
-                    stats.alarp_risks += 1;
-                }
-                _ => {
-                    stats.acceptable_risks += 1;
-                }
+            // Categorize by risk level using the project's acceptability thresholds
+            match policy.rpn_level(risk.rpn) {
+                RiskLevel::Unacceptable => stats.unacceptable_risks += 1,
+                RiskLevel::ALARP => stats.alarp_risks += 1,
+                RiskLevel::Acceptable => stats.acceptable_risks += 1,
             }
         }
         
@@ -202,6 +198,7 @@ impl RiskService for MockRiskService {
             detectability: super::risk::RiskDetectability::High,
             risk_priority_number: 6, // 2 * 2 * 1.5 (rounded)
             initial_risk_level: super::risk::RiskLevel::Acceptable,
+            policy_version: String::new(),
             mitigation_measures: Vec::new(),
             residual_severity: RiskSeverity::Minor,
             residual_occurrence: RiskOccurrence::Remote,
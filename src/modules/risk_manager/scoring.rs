@@ -7,6 +7,7 @@
 #![allow(dead_code)] // Allow dead code during development - functions will be used in future tasks
 
 use crate::modules::risk_manager::{RiskSeverity, RiskOccurrence, RiskDetectability, RiskLevel};
+use crate::modules::risk_manager::policy::RiskPolicy;
 use crate::utils::RiskCalculator; // REFACTORED: Use centralized risk calculator
 use std::fmt::{self, Display};
 
@@ -29,10 +30,14 @@ pub enum MatrixColor {
 }
 
 /// Risk Matrix Generator per ISO 14971
+///
+/// Rows and columns follow the scales of the project's risk acceptability
+/// policy; each cell shows the highest built-in level falling into it.
 pub struct RiskMatrix {
     matrix: Vec<Vec<RiskMatrixPosition>>,
     title: String,
     version: String,
+    policy: RiskPolicy,
 }
 
 impl Default for RiskMatrix {
@@ -42,23 +47,36 @@ impl Default for RiskMatrix {
 }
 
 impl RiskMatrix {
-    /// Create a new 5x5 risk matrix per ISO 14971 guidelines
+    /// Create a risk matrix for the current project's acceptability policy
     pub fn new() -> Self {
+        Self::with_policy(RiskPolicy::active())
+    }
+
+    /// Create a risk matrix for an explicit acceptability policy
+    pub fn with_policy(policy: RiskPolicy) -> Self {
         let mut matrix = Vec::new();
         
-        // Build 5x5 matrix (Severity × Occurrence)
-        for severity_val in 1..=5 {
+        // Build the policy's Severity × Occurrence grid
+        for row_idx in 0..policy.severity_scale.len() {
             let mut row = Vec::new();
-            let severity = Self::value_to_severity(severity_val);
+            let severity = (1..=5u32)
+                .rev()
+                .map(Self::value_to_severity)
+                .find(|s| policy.severity_index(s) == row_idx)
+                .unwrap_or(RiskSeverity::Negligible);
             
-            for occurrence_val in 1..=5 {
-                let occurrence = Self::value_to_occurrence(occurrence_val);
+            for col_idx in 0..policy.probability_scale.len() {
+                let occurrence = (1..=5u32)
+                    .rev()
+                    .map(Self::value_to_occurrence)
+                    .find(|o| policy.probability_index(o) == col_idx)
+                    .unwrap_or(RiskOccurrence::Improbable);
                 // Use moderate detectability for matrix display (most common scenario)
                 let detectability = RiskDetectability::Moderate;
                 
                 // REFACTORED: Use centralized RiskCalculator instead of inline calculation
                 let rpn = RiskCalculator::calculate_rpn(&severity, &occurrence, &detectability);
-                let risk_level = Self::calculate_risk_level(rpn);
+                let risk_level = policy.classify(&severity, &occurrence, &detectability);
                 let color_code = Self::risk_level_to_color(&risk_level);
                 
                 let position = RiskMatrixPosition {
@@ -77,9 +95,15 @@ impl RiskMatrix {
         Self {
             matrix,
             title: "ISO 14971 Risk Assessment Matrix".to_string(),
-            version: "1.0".to_string(),
+            version: policy.version.clone(),
+            policy,
         }
     }
+
+    /// Acceptability policy the matrix was built from
+    pub const fn policy(&self) -> &RiskPolicy {
+        &self.policy
+    }
     
     /// Generate ASCII representation of the risk matrix
     pub fn generate_ascii_matrix(&self) -> String {
@@ -87,34 +111,32 @@ impl RiskMatrix {
         
         // Header
         output.push_str(&format!("┌─ {} (v{}) ─┐\n", self.title, self.version));
+        output.push_str(&format!("│ Policy: {}\n", self.policy.name));
         output.push_str("│ Medical Device Risk Matrix (Severity × Occurrence)\n");
         output.push_str("│ 🟢 = Acceptable  🟡 = ALARP  🔴 = Unacceptable\n");
         output.push_str("└─────────────────────────────────────────────────┘\n\n");
         
         // Column headers (Occurrence)
         output.push_str("         │");
-        for occ_val in 1..=5 {
-            let occurrence = Self::value_to_occurrence(occ_val);
-            output.push_str(&format!(" {:^12} │", Self::occurrence_short_name(&occurrence)));
+        for label in &self.policy.probability_scale {
+            output.push_str(&format!(" {:^12} │", Self::short_label(label, 12)));
         }
         output.push('\n');
         
         // Separator
         output.push_str("─────────┼");
-        for _ in 1..=5 {
+        for _ in &self.policy.probability_scale {
             output.push_str("──────────────┼");
         }
         output.push('\n');
         
         // Matrix rows (Severity - highest to lowest for proper risk matrix display)
-        for severity_val in (1..=5).rev() {
-            let severity = Self::value_to_severity(severity_val);
-            let severity_name = Self::severity_short_name(&severity);
+        for (row_idx, row) in self.matrix.iter().enumerate().rev() {
+            let severity_name = Self::short_label(&self.policy.severity_scale[row_idx], 8);
             
             output.push_str(&format!("{severity_name:^9}│"));
             
-            for occurrence_val in 1..=5 {
-                let position = &self.matrix[(severity_val - 1) as usize][(occurrence_val - 1) as usize];
+            for position in row {
                 let symbol = Self::color_to_symbol(&position.color_code);
                 let rpn_display = if position.rpn >= 100 { 
                     format!("{}*", position.rpn) 
@@ -133,13 +155,34 @@ impl RiskMatrix {
         output.push_str("* High-priority risks requiring immediate attention\n\n");
         
         // Risk level thresholds
-        output.push_str("Risk Level Thresholds:\n");
-        output.push_str("• 🟢 Acceptable:   RPN 1-24   (No immediate action required)\n");
-        output.push_str("• 🟡 ALARP:       RPN 25-99  (Mitigation recommended)\n");
-        output.push_str("• 🔴 Unacceptable: RPN 100-125 (Immediate action required)\n");
+        output.push_str(&self.threshold_legend());
         
         output
     }
+
+    /// Acceptability criteria legend for the matrix policy
+    fn threshold_legend(&self) -> String {
+        let policy = &self.policy;
+        if !policy.use_detectability {
+            return format!(
+                "Risk Level Criteria:\n• Cell colours follow the {} x {} severity × probability matrix\n\
+                 • Detectability is not used for acceptability decisions\n",
+                policy.severity_scale.len(),
+                policy.probability_scale.len()
+            );
+        }
+
+        format!(
+            "Risk Level Thresholds:\n\
+             • 🟢 Acceptable:   RPN 1-{}   (No immediate action required)\n\
+             • 🟡 ALARP:       RPN {}-{}  (Mitigation recommended)\n\
+             • 🔴 Unacceptable: RPN {}-125 (Immediate action required)\n",
+            policy.alarp_threshold.saturating_sub(1),
+            policy.alarp_threshold,
+            policy.unacceptable_threshold.saturating_sub(1),
+            policy.unacceptable_threshold
+        )
+    }
     
     /// Generate detailed risk matrix report
     pub fn generate_detailed_report(&self) -> String {
@@ -152,28 +195,40 @@ impl RiskMatrix {
         
         output.push_str("ISO 14971 Risk Management for Medical Devices\n");
         output.push_str("This matrix assists in systematic risk assessment and control.\n\n");
+        output.push_str(&format!("Acceptability policy: {} (v{})\n", self.policy.name, self.policy.version));
+        output.push_str(&format!("Criteria: {}\n\n", self.policy.criteria_summary()));
         
         // Risk assessment scales
-        output.push_str("SEVERITY SCALE (Medical Device Context):\n");
-        output.push_str("  5 - Catastrophic: Death or permanent disability\n");
-        output.push_str("  4 - Critical:     Serious injury requiring medical intervention\n");
-        output.push_str("  3 - Major:        Moderate injury requiring treatment\n");
-        output.push_str("  2 - Minor:        Minor injury, first aid required\n");
-        output.push_str("  1 - Negligible:   No injury expected\n\n");
-        
-        output.push_str("OCCURRENCE SCALE (Probability of Occurrence):\n");
-        output.push_str("  5 - Frequent:     Very likely (>1 in 10 uses)\n");
-        output.push_str("  4 - Probable:     Likely (1 in 100 to 1 in 10)\n");
-        output.push_str("  3 - Occasional:   Possible (1 in 1,000 to 1 in 100)\n");
-        output.push_str("  2 - Remote:       Unlikely (1 in 10,000 to 1 in 1,000)\n");
-        output.push_str("  1 - Improbable:   Very unlikely (<1 in 10,000)\n\n");
-        
-        output.push_str("DETECTABILITY SCALE (Ability to Detect Before Harm):\n");
-        output.push_str("  1 - Very High:    Almost certain detection (>99%)\n");
-        output.push_str("  2 - High:         Good chance of detection (90-99%)\n");
-        output.push_str("  3 - Moderate:     Moderate chance (50-89%)\n");
-        output.push_str("  4 - Low:          Poor chance (10-49%)\n");
-        output.push_str("  5 - Very Low:     Cannot detect (<10%)\n\n");
+        let defaults = RiskPolicy::default();
+        if self.policy.severity_scale != defaults.severity_scale
+            || self.policy.probability_scale != defaults.probability_scale
+            || self.policy.detectability_scale != defaults.detectability_scale
+        {
+            output.push_str(&Self::scale_section("SEVERITY SCALE", &self.policy.severity_scale));
+            output.push_str(&Self::scale_section("PROBABILITY SCALE", &self.policy.probability_scale));
+            output.push_str(&Self::scale_section("DETECTABILITY SCALE", &self.policy.detectability_scale));
+        } else {
+            output.push_str("SEVERITY SCALE (Medical Device Context):\n");
+            output.push_str("  5 - Catastrophic: Death or permanent disability\n");
+            output.push_str("  4 - Critical:     Serious injury requiring medical intervention\n");
+            output.push_str("  3 - Major:        Moderate injury requiring treatment\n");
+            output.push_str("  2 - Minor:        Minor injury, first aid required\n");
+            output.push_str("  1 - Negligible:   No injury expected\n\n");
+        
+            output.push_str("OCCURRENCE SCALE (Probability of Occurrence):\n");
+            output.push_str("  5 - Frequent:     Very likely (>1 in 10 uses)\n");
+            output.push_str("  4 - Probable:     Likely (1 in 100 to 1 in 10)\n");
+            output.push_str("  3 - Occasional:   Possible (1 in 1,000 to 1 in 100)\n");
+            output.push_str("  2 - Remote:       Unlikely (1 in 10,000 to 1 in 1,000)\n");
+            output.push_str("  1 - Improbable:   Very unlikely (<1 in 10,000)\n\n");
+        
+            output.push_str("DETECTABILITY SCALE (Ability to Detect Before Harm):\n");
+            output.push_str("  1 - Very High:    Almost certain detection (>99%)\n");
+            output.push_str("  2 - High:         Good chance of detection (90-99%)\n");
+            output.push_str("  3 - Moderate:     Moderate chance (50-89%)\n");
+            output.push_str("  4 - Low:          Poor chance (10-49%)\n");
+            output.push_str("  5 - Very Low:     Cannot detect (<10%)\n\n");
+        }
         
         // Add the ASCII matrix
         output.push_str(&self.generate_ascii_matrix());
@@ -181,19 +236,19 @@ impl RiskMatrix {
         // Risk management guidance
         output.push_str("\nRISK MANAGEMENT GUIDANCE:\n");
         output.push_str("═══════════════════════════════════════════════════════════════\n");
-        output.push_str("🔴 UNACCEPTABLE RISKS (RPN 100-125):\n");
+        output.push_str("🔴 UNACCEPTABLE RISKS:\n");
         output.push_str("   • Immediate risk reduction required\n");
         output.push_str("   • Product cannot be released until risks are mitigated\n");
         output.push_str("   • Consider design changes, safety features, warnings\n");
         output.push_str("   • Document all mitigation measures\n\n");
         
-        output.push_str("🟡 ALARP RISKS (RPN 25-99):\n");
+        output.push_str("🟡 ALARP RISKS:\n");
         output.push_str("   • Risk reduction efforts should be applied\n");
         output.push_str("   • Cost-benefit analysis of mitigation measures\n");
         output.push_str("   • Document justification if risk is accepted\n");
         output.push_str("   • Consider additional controls where practicable\n\n");
         
-        output.push_str("🟢 ACCEPTABLE RISKS (RPN 1-24):\n");
+        output.push_str("🟢 ACCEPTABLE RISKS:\n");
         output.push_str("   • No immediate action required\n");
         output.push_str("   • Monitor during post-market surveillance\n");
         output.push_str("   • Document risk acceptance rationale\n");
@@ -233,20 +288,17 @@ impl RiskMatrix {
     
     /// Get position in matrix for given severity and occurrence
     pub fn get_position(&self, severity: &RiskSeverity, occurrence: &RiskOccurrence) -> Option<&RiskMatrixPosition> {
-        let severity_idx = (severity.clone() as u32 - 1) as usize;
-        let occurrence_idx = (occurrence.clone() as u32 - 1) as usize;
+        let severity_idx = self.policy.severity_index(severity);
+        let occurrence_idx = self.policy.probability_index(occurrence);
         
-        if severity_idx < 5 && occurrence_idx < 5 {
-            Some(&self.matrix[severity_idx][occurrence_idx])
-        } else {
-            None
-        }
+        self.matrix.get(severity_idx).and_then(|row| row.get(occurrence_idx))
     }
     
     /// Calculate statistics for the risk matrix
     pub fn calculate_statistics(&self) -> RiskMatrixStatistics {
+        let total_positions = self.matrix.iter().map(Vec::len).sum::<usize>() as u32;
         let mut stats = RiskMatrixStatistics {
-            total_positions: 25,
+            total_positions,
             acceptable_count: 0,
             alarp_count: 0,
             unacceptable_count: 0,
@@ -271,7 +323,7 @@ impl RiskMatrix {
             }
         }
         
-        stats.avg_rpn = total_rpn as f64 / 25.0;
+        stats.avg_rpn = total_rpn as f64 / f64::from(total_positions.max(1));
         stats
     }
     
@@ -297,12 +349,19 @@ impl RiskMatrix {
         }
     }
     
-    const fn calculate_risk_level(rpn: u32) -> RiskLevel {
-        match rpn {
-            100..=125 => RiskLevel::Unacceptable,
-            25..=99 => RiskLevel::ALARP,
-            _ => RiskLevel::Acceptable,
+    /// Upper-case scale label cut to the width of a matrix cell
+    fn short_label(label: &str, width: usize) -> String {
+        label.to_uppercase().chars().take(width).collect()
+    }
+
+    /// Numbered listing of a custom policy scale, highest level first
+    fn scale_section(title: &str, labels: &[String]) -> String {
+        let mut section = format!("{title} (Risk Policy):\n");
+        for (idx, label) in labels.iter().enumerate().rev() {
+            section.push_str(&format!("  {} - {label}\n", idx + 1));
         }
+        section.push('\n');
+        section
     }
     
     const fn risk_level_to_color(risk_level: &RiskLevel) -> MatrixColor {
@@ -321,26 +380,6 @@ impl RiskMatrix {
         }
     }
     
-    const fn severity_short_name(severity: &RiskSeverity) -> &'static str {
-        match severity {
-            RiskSeverity::Catastrophic => "CATAS",
-            RiskSeverity::Critical => "CRIT",
-            RiskSeverity::Major => "MAJOR",
-            RiskSeverity::Minor => "MINOR",
-            RiskSeverity::Negligible => "NEGL",
-        }
-    }
-    
-    const fn occurrence_short_name(occurrence: &RiskOccurrence) -> &'static str {
-        match occurrence {
-            RiskOccurrence::Frequent => "FREQUENT",
-            RiskOccurrence::Probable => "PROBABLE",
-            RiskOccurrence::Occasional => "OCCASIONAL",
-            RiskOccurrence::Remote => "REMOTE",
-            RiskOccurrence::Improbable => "IMPROBABLE",
-        }
-    }
-    
     const fn severity_description(severity: &RiskSeverity) -> &'static str {
         match severity {
             RiskSeverity::Catastrophic => "Death or permanent disability",
@@ -477,8 +516,9 @@ impl RiskScoring {
         occurrence: &RiskOccurrence, 
         detectability: &RiskDetectability
     ) -> String {
+        let policy = RiskPolicy::active();
         let rpn = Self::calculate_rpn(severity, occurrence, detectability);
-        let risk_level = Self::rpn_to_risk_level(rpn);
+        let risk_level = policy.classify(severity, occurrence, detectability);
         let explanation = Self::explain_risk_level(&risk_level);
         let priority = Self::suggest_mitigation_priority(rpn);
         
@@ -490,11 +530,13 @@ impl RiskScoring {
              • RPN: {}\n\
              • Risk Level: {:?}\n\
              • Explanation: {}\n\
-             • Mitigation Priority: {}",
+             • Mitigation Priority: {}\n\
+             • Risk Policy: {} (v{})",
             severity, severity.clone() as u32,
             occurrence, occurrence.clone() as u32,
             detectability, detectability.clone() as u32,
-            rpn, risk_level, explanation, priority
+            rpn, risk_level, explanation, priority,
+            policy.name, policy.version
         )
     }
 }
@@ -595,6 +637,37 @@ mod tests {
         assert!(ascii.contains("RPN"));
     }
     
+    #[test]
+    fn test_matrix_follows_policy_dimensions() {
+        let policy = RiskPolicy {
+            version: "2.0".to_string(),
+            use_detectability: false,
+            severity_scale: vec!["Low".to_string(), "Medium".to_string(), "High".to_string()],
+            probability_scale: vec!["Rare".to_string(), "Likely".to_string()],
+            matrix: vec![
+                vec![RiskLevel::Acceptable, RiskLevel::ALARP],
+                vec![RiskLevel::ALARP, RiskLevel::Unacceptable],
+                vec![RiskLevel::Unacceptable, RiskLevel::Unacceptable],
+            ],
+            ..RiskPolicy::default()
+        };
+        let matrix = RiskMatrix::with_policy(policy);
+        
+        let stats = matrix.calculate_statistics();
+        assert_eq!(stats.total_positions, 6);
+        assert_eq!(stats.unacceptable_count, 3);
+        
+        let pos = matrix.get_position(&RiskSeverity::Catastrophic, &RiskOccurrence::Improbable).unwrap();
+        assert_eq!(pos.risk_level, RiskLevel::Unacceptable);
+        assert_eq!(pos.color_code, MatrixColor::Red);
+        
+        let ascii = matrix.generate_ascii_matrix();
+        assert!(ascii.contains("(v2.0)"));
+        assert!(ascii.contains("LIKELY"));
+        assert!(ascii.contains("MEDIUM"));
+        assert!(!ascii.contains("RPN 100-125"));
+    }
+    
     #[test]
     fn test_csv_export() {
         let matrix = RiskMatrix::new();
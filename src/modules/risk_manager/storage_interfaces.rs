@@ -117,7 +117,7 @@ impl FileRiskStorage {
         
         // Create empty index file if it doesn't exist
         if !self.index_file.exists() {
-            std::fs::write(&self.index_file, "{\"version\": \"1.0\", \"risks\": []}")?;
+            crate::modules::storage::encryption::write(&self.index_file, "{\"version\": \"1.0\", \"risks\": []}")?;
         }
         
         Ok(())
@@ -232,7 +232,7 @@ impl FileRiskStorage {
 impl RiskReader for FileRiskStorage {
     fn load_risk(&self, risk_id: &str) -> QmsResult<RiskItem> {
         let file_path = self.get_risk_file_path(risk_id);
-        let content = std::fs::read_to_string(file_path)?;
+        let content = crate::modules::storage::encryption::read_to_string(file_path)?;
 
         // Simple JSON parsing for RiskItem
         let risk = self.parse_risk_json(&content)?;
@@ -280,7 +280,7 @@ impl RiskWriter for FileRiskStorage {
         let file_path = self.get_risk_file_path(&risk.id);
         let json_content = self.risk_to_json(risk);
 
-        std::fs::write(file_path, json_content)?;
+        crate::modules::storage::encryption::write(file_path, json_content)?;
 
         // Update index
         self.update_index_entry(risk)?;
@@ -414,7 +414,7 @@ impl RiskBackupManager for FileRiskStorage {
             let entry = entry?;
             if entry.path().extension().map_or(false, |ext| ext == "json") {
                 let dest = risks_backup.join(entry.file_name());
-                std::fs::copy(entry.path(), dest)?;
+                crate::modules::storage::encryption::copy(entry.path(), dest)?;
             }
         }
         
@@ -440,7 +440,7 @@ impl RiskBackupManager for FileRiskStorage {
             let entry = entry?;
             if entry.path().extension().map_or(false, |ext| ext == "json") {
                 let dest = self.risks_dir.join(entry.file_name());
-                std::fs::copy(entry.path(), dest)?;
+                crate::modules::storage::encryption::copy(entry.path(), dest)?;
             }
         }
         
@@ -458,7 +458,7 @@ impl RiskBackupManager for FileRiskStorage {
         for entry in std::fs::read_dir(&risks_backup)? {
             let entry = entry?;
             if entry.path().extension().map_or(false, |ext| ext == "json") {
-                let content = std::fs::read_to_string(entry.path())?;
+                let content = crate::modules::storage::encryption::read_to_string(entry.path())?;
                 // Simple validation - check if it contains basic JSON structure
                 if !content.trim().starts_with('{') || !content.trim().ends_with('}') {
                     return Ok(false);
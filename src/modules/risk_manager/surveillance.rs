@@ -213,7 +213,7 @@ impl SurveillanceManager {
         let index_path = surveillance_dir.join("index.json");
         if !index_path.exists() {
             let initial_index = r#"{"version": "1.0", "data": []}"#;
-            fs::write(&index_path, initial_index)?;
+            crate::modules::storage::encryption::write(&index_path, initial_index)?;
         }
 
         // Audit initialization
@@ -322,7 +322,9 @@ impl SurveillanceManager {
         };
         
         risk.risk_priority_number = severity_val * occurrence_val * detectability_val;
-        risk.initial_risk_level = RiskManager::assess_risk_level(risk.risk_priority_number);
+        let policy = self.risk_manager.policy()?;
+        risk.initial_risk_level = policy.classify(&risk.severity, &risk.occurrence, &risk.detectability);
+        risk.policy_version = policy.version;
         risk.updated_at = crate::utils::current_iso8601_timestamp();
 
         // Mark surveillance data as requiring risk reassessment if RPN increased significantly
@@ -583,7 +585,7 @@ impl SurveillanceManager {
         let file_path = surveillance_dir.join(format!("{}.json", data.id));
         let json_content = self.surveillance_data_to_json(data)?;
         
-        fs::write(&file_path, json_content)?;
+        crate::modules::storage::encryption::write(&file_path, json_content)?;
         
         Ok(())
     }
@@ -600,7 +602,7 @@ impl SurveillanceManager {
 
     /// Load surveillance data from specific path
     fn load_surveillance_data_from_path(&self, file_path: &Path) -> QmsResult<SurveillanceData> {
-        let content = fs::read_to_string(file_path)?;
+        let content = crate::modules::storage::encryption::read_to_string(file_path)?;
         self.parse_surveillance_json(&content)
     }
 
@@ -0,0 +1,239 @@
+//! Cron Expressions
+//!
+//! Five-field `minute hour day-of-month month day-of-week` schedules, evaluated
+//! in UTC. Fields accept `*`, single values, lists (`1,15`), ranges (`1-5`)
+//! and steps (`*/15`, `0-30/10`); day-of-week runs 0-7 with both 0 and 7 for
+//! Sunday. As in classic cron, when both day fields are restricted a time
+//! matches if either of them does. `@hourly`, `@daily`, `@weekly`,
+//! `@monthly` and `@yearly` are accepted as shorthands.
+
+use crate::prelude::*;
+use std::fmt;
+
+/// Parsed cron schedule
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl CronSchedule {
+    /// Parse a cron expression
+    pub fn parse(expression: &str) -> QmsResult<Self> {
+        let expression = expression.trim();
+        let expanded = match expression {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(QmsError::validation_error(&format!(
+                "Cron expression '{expression}' must have 5 fields: minute hour day-of-month month day-of-week"
+            )));
+        }
+
+        let mut days_of_week = parse_field(fields[4], 0, 7, "day-of-week")?;
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            expression: expression.to_string(),
+            minutes: parse_field(fields[0], 0, 59, "minute")?,
+            hours: parse_field(fields[1], 0, 23, "hour")?,
+            days_of_month: parse_field(fields[2], 1, 31, "day-of-month")?,
+            months: parse_field(fields[3], 1, 12, "month")?,
+            days_of_week,
+            any_day_of_month: fields[2] == "*",
+            any_day_of_week: fields[4] == "*",
+        })
+    }
+
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// Whether the schedule fires in the minute containing `timestamp`
+    pub fn matches(&self, timestamp: u64) -> bool {
+        let time = UtcTime::from_timestamp(timestamp);
+        bit(self.minutes, time.minute)
+            && bit(self.hours, time.hour)
+            && bit(self.months, time.month)
+            && self.matches_day(&time)
+    }
+
+    /// Start of the first minute after `timestamp` at which the schedule fires
+    ///
+    /// Looks up to about four years ahead, which covers 29 February schedules.
+    pub fn next_after(&self, timestamp: u64) -> Option<u64> {
+        let mut candidate = (timestamp / 60 + 1) * 60;
+        let limit = candidate + 4 * 366 * 86_400;
+        while candidate < limit {
+            let time = UtcTime::from_timestamp(candidate);
+            if !bit(self.months, time.month) || !self.matches_day(&time) {
+                candidate = (candidate / 86_400 + 1) * 86_400;
+            } else if !bit(self.hours, time.hour) {
+                candidate = (candidate / 3_600 + 1) * 3_600;
+            } else if !bit(self.minutes, time.minute) {
+                candidate += 60;
+            } else {
+                return Some(candidate);
+            }
+        }
+        None
+    }
+
+    const fn matches_day(&self, time: &UtcTime) -> bool {
+        let day_of_month = bit(self.days_of_month, time.day);
+        let day_of_week = bit(self.days_of_week, time.weekday);
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (false, false) => day_of_month || day_of_week,
+            _ => day_of_month && day_of_week,
+        }
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+/// Calendar fields of a Unix timestamp in UTC
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UtcTime {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub weekday: u32, // 0 = Sunday
+}
+
+impl UtcTime {
+    pub fn from_timestamp(timestamp: u64) -> Self {
+        let days = (timestamp / 86_400) as i64;
+        let seconds = timestamp % 86_400;
+
+        // Civil-from-days (proleptic Gregorian calendar)
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+        let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 } as u32;
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+        Self {
+            year,
+            month,
+            day,
+            hour: (seconds / 3_600) as u32,
+            minute: (seconds % 3_600 / 60) as u32,
+            weekday: (days + 4).rem_euclid(7) as u32,
+        }
+    }
+}
+
+impl fmt::Display for UtcTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+            self.year, self.month, self.day, self.hour, self.minute
+        )
+    }
+}
+
+const fn bit(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+/// Parse one field into a bit mask of allowed values
+fn parse_field(field: &str, min: u32, max: u32, name: &str) -> QmsResult<u64> {
+    let invalid = || QmsError::validation_error(&format!("Invalid cron {name} field '{field}'"));
+    let mut mask = 0u64;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            return Err(invalid());
+        }
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (start.parse().map_err(|_| invalid())?, end.parse().map_err(|_| invalid())?)
+        } else {
+            let value: u32 = range.parse().map_err(|_| invalid())?;
+            // `5/15` means "from 5 every 15"
+            (value, if part.contains('/') { max } else { value })
+        };
+        if start < min || end > max || start > end {
+            return Err(invalid());
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-02-29 13:45:00 UTC, a Thursday
+    const LEAP_DAY: u64 = 1_709_214_300;
+
+    #[test]
+    fn test_utc_time_from_timestamp() {
+        assert_eq!(
+            UtcTime::from_timestamp(LEAP_DAY),
+            UtcTime { year: 2024, month: 2, day: 29, hour: 13, minute: 45, weekday: 4 }
+        );
+        assert_eq!(UtcTime::from_timestamp(0).to_string(), "1970-01-01 00:00 UTC");
+    }
+
+    #[test]
+    fn test_parse_and_match() {
+        assert!(CronSchedule::parse("45 13 * * *").unwrap().matches(LEAP_DAY));
+        assert!(CronSchedule::parse("*/15 9-17 * * 1-5").unwrap().matches(LEAP_DAY));
+        assert!(!CronSchedule::parse("*/15 9-17 * * 0,6").unwrap().matches(LEAP_DAY));
+        assert!(CronSchedule::parse("45 13 29 2 *").unwrap().matches(LEAP_DAY));
+        // Either restricted day field may match
+        assert!(CronSchedule::parse("45 13 1 * 4").unwrap().matches(LEAP_DAY));
+        assert!(CronSchedule::parse("0 0 * * 7").unwrap().matches(1_709_424_000)); // Sunday 2024-03-03
+
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("* * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+    }
+
+    #[test]
+    fn test_next_after() {
+        let daily = CronSchedule::parse("@daily").unwrap();
+        assert_eq!(daily.next_after(LEAP_DAY), Some(1_709_251_200)); // 2024-03-01 00:00
+
+        let leap = CronSchedule::parse("0 12 29 2 *").unwrap();
+        let next = leap.next_after(LEAP_DAY).unwrap();
+        assert_eq!(UtcTime::from_timestamp(next).to_string(), "2028-02-29 12:00 UTC");
+    }
+}
@@ -0,0 +1,134 @@
+//! Job Run History
+//!
+//! Every scheduled or manual job run is appended as one JSON line to
+//! `scheduler/history.jsonl` in the project, next to the audit trail entry
+//! written for the same run.
+
+use crate::json_utils::JsonValue;
+use crate::prelude::*;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Outcome of one job run
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobRun {
+    pub job: String,
+    pub kind: String,
+    pub started_at: u64,
+    pub finished_at: u64,
+    pub success: bool,
+    pub summary: String,
+}
+
+impl JobRun {
+    fn to_json_line(&self) -> String {
+        format!(
+            "{{\"job\":\"{}\",\"kind\":\"{}\",\"started_at\":{},\"finished_at\":{},\"success\":{},\"summary\":\"{}\"}}",
+            escape_json(&self.job),
+            escape_json(&self.kind),
+            self.started_at,
+            self.finished_at,
+            self.success,
+            escape_json(&self.summary)
+        )
+    }
+
+    fn from_json_line(line: &str) -> Option<Self> {
+        let JsonValue::Object(obj) = JsonValue::parse(line).ok()? else {
+            return None;
+        };
+        let text = |key: &str| obj.get(key).and_then(JsonValue::as_string).cloned();
+        let number = |key: &str| obj.get(key).and_then(JsonValue::as_number).map(|n| n as u64);
+        Some(Self {
+            job: text("job")?,
+            kind: text("kind")?,
+            started_at: number("started_at")?,
+            finished_at: number("finished_at")?,
+            success: obj.get("success").and_then(JsonValue::as_bool)?,
+            summary: text("summary").unwrap_or_default(),
+        })
+    }
+}
+
+/// Filter for [`query_history`]
+#[derive(Debug, Clone, Default)]
+pub struct HistoryQuery {
+    pub job: Option<String>,
+    pub failed_only: bool,
+    pub limit: Option<usize>,
+}
+
+pub fn history_path(project_path: &Path) -> PathBuf {
+    project_path.join("scheduler").join("history.jsonl")
+}
+
+/// Append a run to the project's job history
+pub fn record_run(project_path: &Path, run: &JobRun) -> QmsResult<()> {
+    let path = history_path(project_path);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", crate::modules::storage::encryption::seal(&path, &run.to_json_line())?)?;
+    Ok(())
+}
+
+/// Runs matching the query, most recent first
+pub fn query_history(project_path: &Path, query: &HistoryQuery) -> QmsResult<Vec<JobRun>> {
+    let path = history_path(project_path);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = crate::modules::storage::encryption::read_to_string(&path)?;
+    let runs = content
+        .lines()
+        .rev()
+        .filter_map(JobRun::from_json_line)
+        .filter(|run| query.job.is_none() || query.job.as_deref() == Some(run.job.as_str()))
+        .filter(|run| !query.failed_only || !run.success)
+        .take(query.limit.unwrap_or(usize::MAX))
+        .collect();
+    Ok(runs)
+}
+
+fn escape_json(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_record_and_query_history() {
+        let temp_dir = tempdir().unwrap();
+        let run = |job: &str, started_at: u64, success: bool| JobRun {
+            job: job.to_string(),
+            kind: "audit-backup".to_string(),
+            started_at,
+            finished_at: started_at + 2,
+            success,
+            summary: format!("run \"{started_at}\"\nok"),
+        };
+        for entry in [run("nightly", 100, true), run("weekly", 200, false), run("nightly", 300, false)] {
+            record_run(temp_dir.path(), &entry).unwrap();
+        }
+
+        let all = query_history(temp_dir.path(), &HistoryQuery::default()).unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0], run("nightly", 300, false));
+
+        let nightly = HistoryQuery { job: Some("nightly".to_string()), limit: Some(1), ..Default::default() };
+        assert_eq!(query_history(temp_dir.path(), &nightly).unwrap(), vec![run("nightly", 300, false)]);
+
+        let failed = HistoryQuery { failed_only: true, ..Default::default() };
+        assert_eq!(query_history(temp_dir.path(), &failed).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,310 @@
+//! Scheduled Jobs
+//!
+//! The recurring maintenance tasks the scheduler can run and the named,
+//! cron-scheduled entries that are persisted under `schedules` in the
+//! project's `config/config.json`.
+
+use super::cron::CronSchedule;
+use crate::json_utils::JsonValue;
+use crate::modules::audit_logger::backup::AuditBackupManager;
+use crate::modules::audit_logger::rotation;
+use crate::modules::document_control::backup::DocumentBackupManager;
+use crate::modules::report_generator::{AuditReportGenerator, DHFReportGenerator, RiskReportGenerator};
+use crate::modules::risk_manager::risk::RiskAlert;
+use crate::modules::risk_manager::RiskManager;
+use crate::modules::storage::{project_setting, set_project_setting};
+use crate::prelude::*;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Project config key holding the schedule list
+pub const SCHEDULES_KEY: &str = "schedules";
+
+/// Reports that can be generated on a schedule
+pub const REPORT_KINDS: [&str; 3] = ["dhf", "risks", "audit"];
+
+/// A task the scheduler knows how to run
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobKind {
+    /// Generate a report into `reports/scheduled/`
+    Report { report: String, format: String },
+    /// Daily audit log rotation and retention cleanup
+    RotateAuditLogs,
+    /// `AuditBackupManager::create_backup`
+    AuditBackup,
+    /// `DocumentBackupManager::cleanup_old_backups`
+    DocumentBackupCleanup { retention_days: u64 },
+    /// `RiskManager::escalate_high_risks`
+    RiskEscalation { rpn_threshold: u32 },
+}
+
+impl JobKind {
+    /// Build a job from its CLI/config name and `key=value` options
+    pub fn from_parts(kind: &str, options: &HashMap<String, String>) -> QmsResult<Self> {
+        let number = |key: &str, default: u64| -> QmsResult<u64> {
+            match options.get(key) {
+                Some(value) => value.parse().map_err(|_| {
+                    QmsError::validation_error(&format!("Option '{key}' must be a whole number, got '{value}'"))
+                }),
+                None => Ok(default),
+            }
+        };
+
+        let job = match kind {
+            "report" => {
+                let report = options.get("report").map(String::as_str).unwrap_or("dhf");
+                if !REPORT_KINDS.contains(&report) {
+                    return Err(QmsError::validation_error(&format!(
+                        "Unknown report '{report}'. Expected one of: {}",
+                        REPORT_KINDS.join(", ")
+                    )));
+                }
+                let format = options.get("format").map(String::as_str).unwrap_or("md");
+                crate::modules::report_generator::interfaces::report_interfaces::OutputFormat::from_str(format)?;
+                JobKind::Report { report: report.to_string(), format: format.to_string() }
+            }
+            "rotate-audit-logs" => JobKind::RotateAuditLogs,
+            "audit-backup" => JobKind::AuditBackup,
+            "document-backup-cleanup" => JobKind::DocumentBackupCleanup {
+                retention_days: number("retention_days", 2555)?,
+            },
+            "risk-escalation" => JobKind::RiskEscalation {
+                rpn_threshold: u32::try_from(number("rpn_threshold", 100)?)
+                    .map_err(|_| QmsError::validation_error("Option 'rpn_threshold' is too large"))?,
+            },
+            other => {
+                return Err(QmsError::validation_error(&format!(
+                    "Unknown job '{other}'. Expected one of: report, rotate-audit-logs, audit-backup, \
+                     document-backup-cleanup, risk-escalation"
+                )))
+            }
+        };
+        Ok(job)
+    }
+
+    /// Name used on the command line and in the project config
+    pub const fn name(&self) -> &'static str {
+        match self {
+            JobKind::Report { .. } => "report",
+            JobKind::RotateAuditLogs => "rotate-audit-logs",
+            JobKind::AuditBackup => "audit-backup",
+            JobKind::DocumentBackupCleanup { .. } => "document-backup-cleanup",
+            JobKind::RiskEscalation { .. } => "risk-escalation",
+        }
+    }
+
+    /// Job options as `key=value` pairs
+    pub fn options(&self) -> Vec<(&'static str, String)> {
+        match self {
+            JobKind::Report { report, format } => vec![("report", report.clone()), ("format", format.clone())],
+            JobKind::DocumentBackupCleanup { retention_days } => vec![("retention_days", retention_days.to_string())],
+            JobKind::RiskEscalation { rpn_threshold } => vec![("rpn_threshold", rpn_threshold.to_string())],
+            JobKind::RotateAuditLogs | JobKind::AuditBackup => Vec::new(),
+        }
+    }
+
+    /// Run the job against a project and summarise what it did
+    pub fn run(&self, project_path: &Path) -> QmsResult<String> {
+        match self {
+            JobKind::Report { report, format } => {
+                let output_dir = project_path.join("reports").join("scheduled");
+                std::fs::create_dir_all(&output_dir)?;
+                let output = output_dir.join(format!("{report}_{}.{format}", crate::utils::current_timestamp()));
+                let output_str = output.to_string_lossy();
+                match report.as_str() {
+                    "dhf" => DHFReportGenerator::new(project_path).generate_report(format, Some(&output_str))?,
+                    "risks" => RiskReportGenerator::new(project_path).generate_report(format, Some(&output_str))?,
+                    _ => AuditReportGenerator::new(project_path).generate_report(format, Some(&output_str), None)?,
+                };
+                Ok(format!("{report} report written to {}", output.display()))
+            }
+            JobKind::RotateAuditLogs => {
+                rotation::schedule_daily_rotation(project_path)?;
+                Ok("Audit log rotation check completed".to_string())
+            }
+            JobKind::AuditBackup => {
+                let stats = AuditBackupManager::new(project_path.to_path_buf()).create_backup()?;
+                if !stats.errors.is_empty() {
+                    return Err(QmsError::io_error(&format!(
+                        "Audit backup finished with {} errors: {}",
+                        stats.errors.len(),
+                        stats.errors.join("; ")
+                    )));
+                }
+                Ok(format!(
+                    "Backed up {} audit files ({} bytes)",
+                    stats.files_backed_up, stats.bytes_backed_up
+                ))
+            }
+            JobKind::DocumentBackupCleanup { retention_days } => {
+                let removed = DocumentBackupManager::new(project_path.to_path_buf())
+                    .cleanup_old_backups(*retention_days, "scheduler")?;
+                Ok(format!("Removed {removed} document backups older than {retention_days} days"))
+            }
+            JobKind::RiskEscalation { rpn_threshold } => {
+                let alerts = RiskManager::new(project_path)?.escalate_high_risks(*rpn_threshold)?;
+                if alerts.is_empty() {
+                    return Ok("No risks require escalation".to_string());
+                }
+                let count = |alert: RiskAlert| alerts.iter().filter(|a| **a == alert).count();
+                Ok(format!(
+                    "{} risk alerts: {} high RPN (>= {rpn_threshold}), {} escalation required, \
+                     {} overdue mitigation, {} overdue assessment, {} pending verification",
+                    alerts.len(),
+                    count(RiskAlert::HighRPN),
+                    count(RiskAlert::EscalationRequired),
+                    count(RiskAlert::OverdueMitigation),
+                    count(RiskAlert::OverdueAssessment),
+                    count(RiskAlert::PendingVerification),
+                ))
+            }
+        }
+    }
+}
+
+/// A named job with its cron schedule
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledJob {
+    pub name: String,
+    pub schedule: CronSchedule,
+    pub job: JobKind,
+    pub enabled: bool,
+    pub created_by: String,
+    pub created_at: u64,
+}
+
+impl ScheduledJob {
+    pub fn new(name: &str, schedule: CronSchedule, job: JobKind, created_by: &str) -> QmsResult<Self> {
+        let valid_name = !name.is_empty()
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid_name {
+            return Err(QmsError::validation_error(
+                "Schedule names may only contain letters, digits, '-' and '_'",
+            ));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            schedule,
+            job,
+            enabled: true,
+            created_by: created_by.to_string(),
+            created_at: crate::utils::current_timestamp(),
+        })
+    }
+
+    fn to_json(&self) -> JsonValue {
+        let options = self
+            .job
+            .options()
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), JsonValue::String(value)))
+            .collect();
+
+        let mut obj = HashMap::new();
+        obj.insert("name".to_string(), JsonValue::String(self.name.clone()));
+        obj.insert("schedule".to_string(), JsonValue::String(self.schedule.expression().to_string()));
+        obj.insert("job".to_string(), JsonValue::String(self.job.name().to_string()));
+        obj.insert("options".to_string(), JsonValue::Object(options));
+        obj.insert("enabled".to_string(), JsonValue::Bool(self.enabled));
+        obj.insert("created_by".to_string(), JsonValue::String(self.created_by.clone()));
+        obj.insert("created_at".to_string(), JsonValue::Number(self.created_at as f64));
+        JsonValue::Object(obj)
+    }
+
+    fn from_json(json: &JsonValue) -> QmsResult<Self> {
+        let JsonValue::Object(obj) = json else {
+            return Err(QmsError::parse_error("Schedule entry must be a JSON object"));
+        };
+        let text = |key: &str| -> QmsResult<String> {
+            match obj.get(key) {
+                Some(JsonValue::String(value)) => Ok(value.clone()),
+                _ => Err(QmsError::parse_error(&format!("Schedule entry is missing '{key}'"))),
+            }
+        };
+
+        let mut options = HashMap::new();
+        if let Some(JsonValue::Object(values)) = obj.get("options") {
+            for (key, value) in values {
+                if let JsonValue::String(value) = value {
+                    options.insert(key.clone(), value.clone());
+                }
+            }
+        }
+
+        Ok(Self {
+            name: text("name")?,
+            schedule: CronSchedule::parse(&text("schedule")?)?,
+            job: JobKind::from_parts(&text("job")?, &options)?,
+            enabled: !matches!(obj.get("enabled"), Some(JsonValue::Bool(false))),
+            created_by: text("created_by").unwrap_or_else(|_| "unknown".to_string()),
+            created_at: obj.get("created_at").and_then(JsonValue::as_number).unwrap_or(0.0) as u64,
+        })
+    }
+}
+
+/// Load the project's schedules from `config/config.json`
+pub fn load_schedules(project_path: &Path) -> QmsResult<Vec<ScheduledJob>> {
+    match project_setting(project_path, SCHEDULES_KEY)? {
+        Some(JsonValue::Array(entries)) => entries.iter().map(ScheduledJob::from_json).collect(),
+        Some(_) => Err(QmsError::parse_error("Project config 'schedules' must be an array")),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Replace the project's schedules in `config/config.json`
+pub fn save_schedules(project_path: &Path, schedules: &[ScheduledJob]) -> QmsResult<()> {
+    let entries = schedules.iter().map(ScheduledJob::to_json).collect();
+    set_project_setting(project_path, SCHEDULES_KEY, JsonValue::Array(entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_job_kind_from_parts() {
+        let mut options = HashMap::new();
+        options.insert("report".to_string(), "risks".to_string());
+        options.insert("format".to_string(), "csv".to_string());
+        assert_eq!(
+            JobKind::from_parts("report", &options).unwrap(),
+            JobKind::Report { report: "risks".to_string(), format: "csv".to_string() }
+        );
+        assert_eq!(
+            JobKind::from_parts("risk-escalation", &HashMap::new()).unwrap(),
+            JobKind::RiskEscalation { rpn_threshold: 100 }
+        );
+
+        options.insert("format".to_string(), "docx".to_string());
+        assert!(JobKind::from_parts("report", &options).is_err());
+        assert!(JobKind::from_parts("defragment", &HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_schedules_round_trip_through_project_config() {
+        let temp_dir = tempdir().unwrap();
+        let project = temp_dir.path();
+        assert!(load_schedules(project).unwrap().is_empty());
+
+        let mut cleanup = ScheduledJob::new(
+            "weekly-cleanup",
+            CronSchedule::parse("0 3 * * 0").unwrap(),
+            JobKind::DocumentBackupCleanup { retention_days: 90 },
+            "admin",
+        )
+        .unwrap();
+        cleanup.enabled = false;
+        let backup = ScheduledJob::new(
+            "nightly-backup",
+            CronSchedule::parse("@daily").unwrap(),
+            JobKind::AuditBackup,
+            "admin",
+        )
+        .unwrap();
+
+        save_schedules(project, &[cleanup.clone(), backup.clone()]).unwrap();
+        assert_eq!(load_schedules(project).unwrap(), vec![cleanup, backup]);
+        assert!(ScheduledJob::new("bad name", CronSchedule::parse("@daily").unwrap(), JobKind::AuditBackup, "admin").is_err());
+    }
+}
@@ -0,0 +1,21 @@
+//! Background Job Scheduler
+//!
+//! Runs recurring QMS maintenance on cron-like schedules stored in the
+//! project config: report generation, audit log rotation, audit backups,
+//! document backup retention and high-risk escalation. Schedules are run by
+//! `qms daemon` or alongside `qms serve`; every run is audit-logged and kept
+//! in a queryable history.
+
+pub mod cron;
+pub mod history;
+pub mod jobs;
+pub mod runner;
+
+#[allow(unused_imports)]
+pub use cron::{CronSchedule, UtcTime};
+#[allow(unused_imports)]
+pub use history::{query_history, record_run, HistoryQuery, JobRun};
+#[allow(unused_imports)]
+pub use jobs::{load_schedules, save_schedules, JobKind, ScheduledJob, REPORT_KINDS, SCHEDULES_KEY};
+#[allow(unused_imports)]
+pub use runner::{Scheduler, SchedulerHandle};
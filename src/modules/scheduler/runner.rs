@@ -0,0 +1,172 @@
+//! Scheduler Runner
+//!
+//! Evaluates the project's schedules once per minute and runs the jobs that
+//! are due. Used by `qms daemon run` in the foreground and by `qms serve` on
+//! a background thread. Each run is audit-logged (`SCHEDULED_JOB_RUN` or
+//! `SCHEDULED_JOB_FAILED`) and recorded in the job history.
+
+use super::history::{record_run, JobRun};
+use super::jobs::{load_schedules, ScheduledJob};
+use crate::modules::audit_logger::functions::audit_log_action;
+use crate::prelude::*;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Missed minutes caught up after the process was suspended or busy
+const MAX_CATCH_UP_MINUTES: u64 = 60;
+
+pub struct Scheduler {
+    project_path: PathBuf,
+}
+
+impl Scheduler {
+    pub fn new(project_path: &Path) -> Self {
+        Self { project_path: project_path.to_path_buf() }
+    }
+
+    /// Run one job now, recording the outcome
+    pub fn run_job(&self, job: &ScheduledJob) -> JobRun {
+        let started_at = crate::utils::current_timestamp();
+        let result = job.job.run(&self.project_path);
+        let run = JobRun {
+            job: job.name.clone(),
+            kind: job.job.name().to_string(),
+            started_at,
+            finished_at: crate::utils::current_timestamp(),
+            success: result.is_ok(),
+            summary: match result {
+                Ok(summary) => summary,
+                Err(e) => e.to_string(),
+            },
+        };
+
+        let action = if run.success { "SCHEDULED_JOB_RUN" } else { "SCHEDULED_JOB_FAILED" };
+        let _ = audit_log_action(action, "ScheduledJob", &format!("{}: {}", run.job, run.summary));
+        if let Err(e) = record_run(&self.project_path, &run) {
+            eprintln!("Warning: Failed to record run of '{}': {e}", run.job);
+        }
+        run
+    }
+
+    /// Run a schedule by name, whether or not it is due or enabled
+    pub fn run_named(&self, name: &str) -> QmsResult<JobRun> {
+        let job = load_schedules(&self.project_path)?
+            .into_iter()
+            .find(|job| job.name == name)
+            .ok_or_else(|| QmsError::not_found(&format!("Schedule '{name}' not found")))?;
+        Ok(self.run_job(&job))
+    }
+
+    /// Run every enabled schedule that fires in the minute containing `timestamp`
+    pub fn run_due(&self, timestamp: u64) -> QmsResult<Vec<JobRun>> {
+        let runs = load_schedules(&self.project_path)?
+            .iter()
+            .filter(|job| job.enabled && job.schedule.matches(timestamp))
+            .map(|job| self.run_job(job))
+            .collect();
+        Ok(runs)
+    }
+
+    /// Check schedules every minute until `stop` is set
+    pub fn run_until(&self, stop: &AtomicBool) {
+        let mut last_minute = crate::utils::current_timestamp() / 60;
+        while !stop.load(Ordering::Relaxed) {
+            thread::sleep(Duration::from_secs(1));
+            let minute = crate::utils::current_timestamp() / 60;
+            if minute <= last_minute {
+                continue;
+            }
+
+            let first = (last_minute + 1).max(minute.saturating_sub(MAX_CATCH_UP_MINUTES - 1));
+            for due_minute in first..=minute {
+                if stop.load(Ordering::Relaxed) {
+                    break;
+                }
+                if let Err(e) = self.run_due(due_minute * 60) {
+                    eprintln!("Scheduler: failed to load schedules: {e}");
+                }
+            }
+            last_minute = minute;
+        }
+    }
+
+    /// Start the scheduler on a background thread
+    pub fn spawn(project_path: &Path) -> SchedulerHandle {
+        let stop = Arc::new(AtomicBool::new(false));
+        let scheduler = Self::new(project_path);
+        let thread_stop = Arc::clone(&stop);
+        let thread = thread::spawn(move || scheduler.run_until(&thread_stop));
+        SchedulerHandle { stop, thread: Some(thread) }
+    }
+}
+
+/// Background scheduler thread, stopped when dropped
+pub struct SchedulerHandle {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl SchedulerHandle {
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for SchedulerHandle {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::scheduler::cron::CronSchedule;
+    use crate::modules::scheduler::history::{query_history, HistoryQuery};
+    use crate::modules::scheduler::jobs::{save_schedules, JobKind};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_run_due_records_history() {
+        let temp_dir = tempdir().unwrap();
+        let project = temp_dir.path();
+        std::fs::create_dir_all(project.join("backups")).unwrap();
+
+        let hourly = ScheduledJob::new(
+            "hourly-cleanup",
+            CronSchedule::parse("@hourly").unwrap(),
+            JobKind::DocumentBackupCleanup { retention_days: 30 },
+            "admin",
+        )
+        .unwrap();
+        let mut disabled = hourly.clone();
+        disabled.name = "disabled-cleanup".to_string();
+        disabled.enabled = false;
+        save_schedules(project, &[hourly, disabled]).unwrap();
+
+        let scheduler = Scheduler::new(project);
+        assert!(scheduler.run_due(1_709_214_300).unwrap().is_empty()); // 13:45
+        let runs = scheduler.run_due(1_709_215_200).unwrap(); // 14:00
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].job, "hourly-cleanup");
+        assert!(runs[0].success, "{}", runs[0].summary);
+
+        // Manual runs ignore the enabled flag
+        scheduler.run_named("disabled-cleanup").unwrap();
+        assert!(scheduler.run_named("missing").is_err());
+
+        let history = query_history(project, &HistoryQuery::default()).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].job, "disabled-cleanup");
+    }
+}
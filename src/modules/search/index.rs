@@ -0,0 +1,673 @@
+//! Inverted Index
+//!
+//! Maps each stemmed term to the records containing it, with the token
+//! positions in the record's title and body so phrases can be matched without
+//! re-reading the records. Results are ranked with BM25, counting title
+//! matches more than body matches.
+//!
+//! The index is one line-oriented file, `search/index.txt`, holding a record
+//! line (`R`) per record followed by a posting line (`P`) per term and record.
+//! It is rewritten under a process-wide lock whenever a record changes.
+
+use super::query::{FieldFilter, QueryTerm, SearchQuery, TextScope};
+use super::records::{collect_records, SearchEntity, SearchRecord};
+use super::text::{analyze, normalize_keyword, stem, token_spans};
+use crate::json_utils::JsonValue;
+use crate::modules::storage::encryption;
+use crate::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const FORMAT_HEADER: &str = "QMS-SEARCH-INDEX\t1";
+
+/// BM25 term-frequency saturation and length normalisation
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+
+/// Weight of a title occurrence relative to a body occurrence
+const TITLE_BOOST: f64 = 2.5;
+
+const SNIPPET_CHARS: usize = 160;
+
+/// Serialises read-modify-write cycles on index files within the process
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+/// Token positions of one term in one record
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Posting {
+    title: Vec<u32>,
+    body: Vec<u32>,
+}
+
+impl Posting {
+    fn positions(&self, scope: TextScope) -> (&[u32], &[u32]) {
+        match scope {
+            TextScope::Any => (&self.title, &self.body),
+            TextScope::Title => (&self.title, &[]),
+            TextScope::Body => (&[], &self.body),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IndexedRecord {
+    entity: SearchEntity,
+    id: String,
+    title: String,
+    body: String,
+    /// Normalised attribute values
+    attributes: Vec<(String, String)>,
+    title_len: u32,
+    body_len: u32,
+    terms: Vec<String>,
+}
+
+impl IndexedRecord {
+    fn matches_filter(&self, filter: &FieldFilter) -> bool {
+        let found = if filter.field == "entity" {
+            filter.values.iter().any(|value| value == self.entity.name())
+        } else {
+            self.attributes
+                .iter()
+                .any(|(field, value)| *field == filter.field && filter.values.contains(value))
+        };
+        found != filter.negated
+    }
+}
+
+/// Ranked search result
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub entity: SearchEntity,
+    pub key: String,
+    pub id: String,
+    pub title: String,
+    pub score: f64,
+    pub snippet: String,
+}
+
+impl SearchHit {
+    pub fn to_json(&self) -> JsonValue {
+        let mut object = HashMap::new();
+        object.insert("entity".to_string(), JsonValue::String(self.entity.name().to_string()));
+        object.insert("key".to_string(), JsonValue::String(self.key.clone()));
+        object.insert("id".to_string(), JsonValue::String(self.id.clone()));
+        object.insert("title".to_string(), JsonValue::String(self.title.clone()));
+        object.insert("score".to_string(), JsonValue::Number((self.score * 1000.0).round() / 1000.0));
+        object.insert("snippet".to_string(), JsonValue::String(self.snippet.clone()));
+        JsonValue::Object(object)
+    }
+}
+
+/// Hits of a search, best first, and the number of records that matched
+#[derive(Debug, Clone, Default)]
+pub struct SearchResults {
+    pub total: usize,
+    pub hits: Vec<SearchHit>,
+}
+
+impl SearchResults {
+    pub fn to_json(&self) -> JsonValue {
+        let mut object = HashMap::new();
+        object.insert("total".to_string(), JsonValue::Number(self.total as f64));
+        object.insert("hits".to_string(), JsonValue::Array(self.hits.iter().map(SearchHit::to_json).collect()));
+        JsonValue::Object(object)
+    }
+}
+
+/// Persistent full-text index of a project
+#[derive(Debug)]
+pub struct SearchIndex {
+    path: PathBuf,
+    records: BTreeMap<String, IndexedRecord>,
+    postings: HashMap<String, HashMap<String, Posting>>,
+}
+
+/// Location of a project's index file
+pub fn index_file(project_path: &Path) -> PathBuf {
+    project_path.join("search").join("index.txt")
+}
+
+impl SearchIndex {
+    /// Empty index for the project
+    pub fn new(project_path: &Path) -> Self {
+        Self { path: index_file(project_path), records: BTreeMap::new(), postings: HashMap::new() }
+    }
+
+    /// Load the project's index; a project without one gets an empty index
+    pub fn load(project_path: &Path) -> QmsResult<Self> {
+        let mut index = Self::new(project_path);
+        if !index.path.exists() {
+            return Ok(index);
+        }
+        let content = encryption::read_to_string(&index.path)?;
+        index.parse(&content).map_err(|line| {
+            QmsError::validation_error(&format!(
+                "Search index {} is unreadable at line {line}; rebuild it with 'qms search reindex'",
+                index.path.display()
+            ))
+        })?;
+        Ok(index)
+    }
+
+    /// Build an index over `records`
+    pub fn build(project_path: &Path, records: &[SearchRecord]) -> Self {
+        let mut index = Self::new(project_path);
+        for record in records {
+            index.upsert(record);
+        }
+        index
+    }
+
+    pub fn save(&self) -> QmsResult<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        encryption::write(&self.path, self.serialize())?;
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Add or replace a record
+    pub fn upsert(&mut self, record: &SearchRecord) {
+        let key = record.index_key();
+        self.remove(&key);
+
+        let title_tokens = analyze(&format!("{} {}", record.id, record.title));
+        let body_tokens = analyze(&record.body);
+        let mut postings: HashMap<String, Posting> = HashMap::new();
+        for (position, token) in title_tokens.iter().enumerate() {
+            postings.entry(token.clone()).or_default().title.push(position as u32);
+        }
+        for (position, token) in body_tokens.iter().enumerate() {
+            postings.entry(token.clone()).or_default().body.push(position as u32);
+        }
+
+        let mut attributes = vec![("id".to_string(), normalize_keyword(&record.id))];
+        attributes.extend(
+            record
+                .attributes
+                .iter()
+                .map(|(field, value)| (field.clone(), normalize_keyword(value)))
+                .filter(|(_, value)| !value.is_empty()),
+        );
+
+        let terms = postings.keys().cloned().collect();
+        for (term, posting) in postings {
+            self.postings.entry(term).or_default().insert(key.clone(), posting);
+        }
+        self.records.insert(
+            key,
+            IndexedRecord {
+                entity: record.entity,
+                id: record.id.clone(),
+                title: record.title.clone(),
+                body: record.body.clone(),
+                attributes,
+                title_len: title_tokens.len() as u32,
+                body_len: body_tokens.len() as u32,
+                terms,
+            },
+        );
+    }
+
+    /// Remove a record by index key; returns whether it was present
+    pub fn remove(&mut self, key: &str) -> bool {
+        let Some(record) = self.records.remove(key) else {
+            return false;
+        };
+        for term in &record.terms {
+            if let Some(records) = self.postings.get_mut(term) {
+                records.remove(key);
+                if records.is_empty() {
+                    self.postings.remove(term);
+                }
+            }
+        }
+        true
+    }
+
+    /// Records matching `query`, best first, at most `limit` of them
+    pub fn search(&self, query: &SearchQuery, limit: usize) -> SearchResults {
+        let positive: Vec<&QueryTerm> = query.terms.iter().filter(|term| !term.negated).collect();
+        let average_len = if self.records.is_empty() {
+            1.0
+        } else {
+            let total: u64 = self.records.values().map(|r| u64::from(r.title_len + r.body_len)).sum();
+            (total as f64 / self.records.len() as f64).max(1.0)
+        };
+
+        // Every positive term must match; scores add up across terms
+        let mut scores: Option<HashMap<&str, f64>> = None;
+        for term in &positive {
+            let matches = self.term_matches(term);
+            let idf = self.idf(matches.len());
+            let term_scores: HashMap<&str, f64> = matches
+                .into_iter()
+                .filter(|(key, _)| match &scores {
+                    Some(current) => current.contains_key(key),
+                    None => true,
+                })
+                .map(|(key, (title_tf, body_tf))| {
+                    let record = &self.records[key];
+                    let tf = TITLE_BOOST * f64::from(title_tf) + f64::from(body_tf);
+                    let length = f64::from(record.title_len + record.body_len) / average_len;
+                    let score = idf * term.tokens.len() as f64 * tf * (K1 + 1.0) / (tf + K1 * (1.0 - B + B * length));
+                    let previous = scores.as_ref().and_then(|current| current.get(key)).copied().unwrap_or(0.0);
+                    (key, previous + score)
+                })
+                .collect();
+            scores = Some(term_scores);
+        }
+        let scores = scores.unwrap_or_else(|| self.records.keys().map(|key| (key.as_str(), 0.0)).collect());
+
+        let excluded: HashSet<&str> = query
+            .terms
+            .iter()
+            .filter(|term| term.negated)
+            .flat_map(|term| self.term_matches(term).into_keys())
+            .collect();
+
+        let mut ranked: Vec<(&str, f64)> = scores
+            .into_iter()
+            .filter(|(key, _)| !excluded.contains(key))
+            .filter(|(key, _)| {
+                let record = &self.records[*key];
+                query.filters.iter().all(|filter| record.matches_filter(filter))
+            })
+            .collect();
+        ranked.sort_by(|(a_key, a_score), (b_key, b_score)| {
+            b_score
+                .partial_cmp(a_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| {
+                    let (a, b) = (&self.records[*a_key], &self.records[*b_key]);
+                    (a.entity, &a.id).cmp(&(b.entity, &b.id))
+                })
+        });
+
+        let highlight: HashSet<&str> =
+            positive.iter().flat_map(|term| term.tokens.iter().map(String::as_str)).collect();
+        SearchResults {
+            total: ranked.len(),
+            hits: ranked
+                .into_iter()
+                .take(limit)
+                .map(|(key, score)| {
+                    let record = &self.records[key];
+                    SearchHit {
+                        entity: record.entity,
+                        key: key.split_once(':').map(|(_, id)| id).unwrap_or(key).to_string(),
+                        id: record.id.clone(),
+                        title: record.title.clone(),
+                        score,
+                        snippet: snippet(&record.body, &highlight),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Records containing `term` in its scope, with title and body frequencies
+    fn term_matches(&self, term: &QueryTerm) -> HashMap<&str, (u32, u32)> {
+        let Some(first) = self.postings.get(&term.tokens[0]) else {
+            return HashMap::new();
+        };
+        let mut matches = HashMap::new();
+        for (key, posting) in first {
+            let (title, body) = posting.positions(term.scope);
+            let counts = if term.is_phrase() {
+                let rest: Option<Vec<&Posting>> = term.tokens[1..]
+                    .iter()
+                    .map(|token| self.postings.get(token).and_then(|records| records.get(key)))
+                    .collect();
+                let Some(rest) = rest else { continue };
+                (
+                    phrase_count(title, rest.iter().map(|posting| posting.title.as_slice())),
+                    phrase_count(body, rest.iter().map(|posting| posting.body.as_slice())),
+                )
+            } else {
+                (title.len() as u32, body.len() as u32)
+            };
+            if counts.0 + counts.1 > 0 {
+                matches.insert(key.as_str(), counts);
+            }
+        }
+        matches
+    }
+
+    fn idf(&self, document_frequency: usize) -> f64 {
+        let n = self.records.len() as f64;
+        let df = document_frequency as f64;
+        (1.0 + (n - df + 0.5) / (df + 0.5)).ln()
+    }
+
+    fn serialize(&self) -> String {
+        let mut out = String::with_capacity(self.records.values().map(|r| r.body.len() + 256).sum());
+        out.push_str(FORMAT_HEADER);
+        out.push('\n');
+        for (key, record) in &self.records {
+            let attributes: Vec<String> =
+                record.attributes.iter().map(|(field, value)| format!("{field}={value}")).collect();
+            let fields = [
+                "R",
+                &escape(key),
+                record.entity.name(),
+                &escape(&record.id),
+                &escape(&record.title),
+                &record.title_len.to_string(),
+                &record.body_len.to_string(),
+                &attributes.join(" "),
+                &escape(&record.body),
+            ];
+            out.push_str(&fields.join("\t"));
+            out.push('\n');
+        }
+
+        let mut terms: Vec<&String> = self.postings.keys().collect();
+        terms.sort();
+        for term in terms {
+            let mut records: Vec<(&String, &Posting)> = self.postings[term].iter().collect();
+            records.sort_by(|a, b| a.0.cmp(b.0));
+            for (key, posting) in records {
+                let fields =
+                    ["P", term, &escape(key), &join_positions(&posting.title), &join_positions(&posting.body)];
+                out.push_str(&fields.join("\t"));
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    /// Parse index file content; the error is the offending line number
+    fn parse(&mut self, content: &str) -> Result<(), usize> {
+        let mut lines = content.lines().enumerate();
+        match lines.next() {
+            Some((_, header)) if header == FORMAT_HEADER => {}
+            _ => return Err(1),
+        }
+        for (number, line) in lines {
+            let fields: Vec<&str> = line.split('\t').collect();
+            let parsed = match fields.as_slice() {
+                ["R", key, entity, id, title, title_len, body_len, attributes, body] => {
+                    self.parse_record(key, entity, id, title, title_len, body_len, attributes, body)
+                }
+                ["P", term, key, title, body] => self.parse_posting(term, key, title, body),
+                [""] => Some(()),
+                _ => None,
+            };
+            parsed.ok_or(number + 1)?;
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn parse_record(
+        &mut self,
+        key: &str,
+        entity: &str,
+        id: &str,
+        title: &str,
+        title_len: &str,
+        body_len: &str,
+        attributes: &str,
+        body: &str,
+    ) -> Option<()> {
+        let attributes = attributes
+            .split(' ')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| pair.split_once('=').map(|(field, value)| (field.to_string(), value.to_string())))
+            .collect::<Option<Vec<_>>>()?;
+        let record = IndexedRecord {
+            entity: SearchEntity::parse(entity)?,
+            id: unescape(id),
+            title: unescape(title),
+            body: unescape(body),
+            attributes,
+            title_len: title_len.parse().ok()?,
+            body_len: body_len.parse().ok()?,
+            terms: Vec::new(),
+        };
+        self.records.insert(unescape(key), record);
+        Some(())
+    }
+
+    fn parse_posting(&mut self, term: &str, key: &str, title: &str, body: &str) -> Option<()> {
+        let key = unescape(key);
+        let record = self.records.get_mut(&key)?;
+        record.terms.push(term.to_string());
+        let posting = Posting { title: parse_positions(title)?, body: parse_positions(body)? };
+        self.postings.entry(term.to_string()).or_default().insert(key, posting);
+        Some(())
+    }
+}
+
+/// Number of places where `first` positions are followed by each of `rest` in turn
+fn phrase_count<'a>(first: &[u32], rest: impl Iterator<Item = &'a [u32]> + Clone) -> u32 {
+    first
+        .iter()
+        .filter(|&&start| {
+            rest.clone()
+                .enumerate()
+                .all(|(offset, positions)| positions.binary_search(&(start + offset as u32 + 1)).is_ok())
+        })
+        .count() as u32
+}
+
+/// Up to `SNIPPET_CHARS` of `body` around the first highlighted token
+fn snippet(body: &str, highlight: &HashSet<&str>) -> String {
+    let spans = token_spans(body);
+    let hit = spans.iter().find(|(start, end)| highlight.contains(stem(&body[*start..*end].to_lowercase()).as_str()));
+
+    let centre = hit.map_or(0, |(start, _)| *start);
+    let mut start = centre.saturating_sub(SNIPPET_CHARS / 3);
+    while !body.is_char_boundary(start) {
+        start -= 1;
+    }
+    // Begin at a word boundary unless the hit is near the start
+    if start > 0 {
+        if let Some(space) = body[start..centre].find(char::is_whitespace) {
+            start += space + 1;
+        }
+    }
+    let text: String = body[start..].chars().take(SNIPPET_CHARS).collect();
+    let truncated = start + text.len() < body.len();
+    let mut snippet = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if start > 0 {
+        snippet.insert(0, '…');
+    }
+    if truncated {
+        snippet.push('…');
+    }
+    snippet
+}
+
+fn join_positions(positions: &[u32]) -> String {
+    positions.iter().map(u32::to_string).collect::<Vec<_>>().join(",")
+}
+
+fn parse_positions(text: &str) -> Option<Vec<u32>> {
+    if text.is_empty() {
+        return Some(Vec::new());
+    }
+    text.split(',').map(|position| position.parse().ok()).collect()
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            other => escaped.push(other),
+        }
+    }
+    escaped
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Apply changes to the project's index
+///
+/// Projects are indexed in full the first time they are searched; until then
+/// there is no index to update and this does nothing.
+pub fn update_index(project_path: &Path, upserts: &[SearchRecord], removals: &[String]) -> QmsResult<()> {
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if !index_file(project_path).exists() {
+        return Ok(());
+    }
+    let mut index = SearchIndex::load(project_path)?;
+    let mut changed = !upserts.is_empty();
+    for record in upserts {
+        index.upsert(record);
+    }
+    for key in removals {
+        changed |= index.remove(key);
+    }
+    if changed {
+        index.save()?;
+    }
+    Ok(())
+}
+
+/// `update_index` for save paths: a stale index must not fail the save itself
+pub fn update_index_or_warn(project_path: &Path, upserts: &[SearchRecord], removals: &[String]) {
+    if let Err(e) = update_index(project_path, upserts, removals) {
+        eprintln!("Warning: Search index not updated: {e} (run 'qms search reindex')");
+    }
+}
+
+/// Rebuild the project's index from every stored record; returns the record count
+pub fn reindex(project_path: &Path) -> QmsResult<usize> {
+    let records = collect_records(project_path)?;
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let index = SearchIndex::build(project_path, &records);
+    index.save()?;
+    Ok(index.len())
+}
+
+/// Search the project, building its index first if it has none
+pub fn search_project(project_path: &Path, query: &SearchQuery, limit: usize) -> QmsResult<SearchResults> {
+    if !index_file(project_path).exists() {
+        reindex(project_path)?;
+    }
+    let index = {
+        let _guard = INDEX_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        SearchIndex::load(project_path)?
+    };
+    Ok(index.search(query, limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn record(entity: SearchEntity, key: &str, title: &str, body: &str) -> SearchRecord {
+        SearchRecord::new(entity, key, &key.to_uppercase(), title).text(body)
+    }
+
+    fn sample_index(project: &Path) -> SearchIndex {
+        SearchIndex::build(
+            project,
+            &[
+                record(SearchEntity::Document, "doc-1", "Software Requirements Specification", "The alarm latency shall not exceed 2 seconds.\tTabbed")
+                    .attribute("type", "SRS")
+                    .attribute("status", "Approved"),
+                record(SearchEntity::Document, "doc-2", "Design Description", "Alarms are raised by the monitor; latency is logged.")
+                    .attribute("type", "SDD")
+                    .attribute("status", "Draft"),
+                record(SearchEntity::Requirement, "req-1", "Alarm latency", "Occlusion alarms within 2 s")
+                    .attribute("status", "Approved"),
+                record(SearchEntity::Risk, "haz-1", "Delayed occlusion alarm", "Patient receives no therapy")
+                    .attribute("severity", "Critical"),
+            ],
+        )
+    }
+
+    fn ids(results: &SearchResults) -> Vec<&str> {
+        results.hits.iter().map(|hit| hit.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_ranked_phrase_and_field_queries() {
+        let dir = tempdir().unwrap();
+        let index = sample_index(dir.path());
+
+        // Title matches rank first; all entity kinds are searched
+        let results = index.search(&SearchQuery::parse("alarm").unwrap(), 10);
+        assert_eq!(results.total, 4);
+        assert!(matches!(ids(&results)[0], "REQ-1" | "HAZ-1"));
+
+        // The phrase must be contiguous: doc-2 has both words apart
+        let results = index.search(&SearchQuery::parse("\"alarm latency\"").unwrap(), 10);
+        assert_eq!(ids(&results), vec!["REQ-1", "DOC-1"]);
+        assert!(results.hits[1].snippet.contains("alarm latency shall"));
+
+        let results = index.search(&SearchQuery::parse("type:SRS status:approved \"alarm latency\"").unwrap(), 10);
+        assert_eq!(ids(&results), vec!["DOC-1"]);
+
+        let results = index.search(&SearchQuery::parse("status:approved -entity:document").unwrap(), 10);
+        assert_eq!(ids(&results), vec!["REQ-1"]);
+
+        let results = index.search(&SearchQuery::parse("occlusion -title:delayed").unwrap(), 10);
+        assert_eq!(ids(&results), vec!["REQ-1"]);
+
+        // IDs are searchable as text
+        let results = index.search(&SearchQuery::parse("haz-1").unwrap(), 10);
+        assert_eq!(ids(&results), vec!["HAZ-1"]);
+    }
+
+    #[test]
+    fn test_incremental_update_and_persistence() {
+        let dir = tempdir().unwrap();
+        let project = dir.path();
+        sample_index(project).save().unwrap();
+
+        let changed = record(SearchEntity::Document, "doc-2", "Design Description", "Pump motor control");
+        update_index(project, &[changed], &["risk:haz-1".to_string()]).unwrap();
+
+        let index = SearchIndex::load(project).unwrap();
+        assert_eq!(index.len(), 3);
+        assert_eq!(ids(&index.search(&SearchQuery::parse("alarms").unwrap(), 10)), vec!["REQ-1", "DOC-1"]);
+        assert_eq!(ids(&index.search(&SearchQuery::parse("motor").unwrap(), 10)), vec!["DOC-2"]);
+        let hit = &index.search(&SearchQuery::parse("tabbed").unwrap(), 10).hits[0];
+        assert_eq!(hit.key, "doc-1");
+        assert!(hit.snippet.ends_with("Tabbed"));
+
+        // Without an index there is nothing to update
+        let empty = tempdir().unwrap();
+        update_index(empty.path(), &[record(SearchEntity::Test, "tc-1", "Smoke", "")], &[]).unwrap();
+        assert!(!index_file(empty.path()).exists());
+
+        std::fs::write(index_file(project), "not an index").unwrap();
+        assert!(SearchIndex::load(project).is_err());
+    }
+}
@@ -0,0 +1,20 @@
+//! Full-Text Search
+//!
+//! Persistent inverted index over documents, requirements, risks and test
+//! cases. Records are re-indexed as they are saved, queries support stemmed
+//! terms, phrases and `field:value` filters, and results from every entity
+//! kind are ranked together. Used by `qms search` and `/api/search`.
+
+pub mod index;
+pub mod query;
+pub mod records;
+pub mod text;
+
+#[allow(unused_imports)]
+pub use index::{
+    index_file, reindex, search_project, update_index, update_index_or_warn, SearchHit, SearchIndex, SearchResults,
+};
+#[allow(unused_imports)]
+pub use query::{FieldFilter, QueryTerm, SearchQuery, TextScope};
+#[allow(unused_imports)]
+pub use records::{index_key, SearchEntity, SearchRecord};
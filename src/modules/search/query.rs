@@ -0,0 +1,238 @@
+//! Search Queries
+//!
+//! Query syntax:
+//!
+//! - `alarm latency` — records containing every word (stemmed)
+//! - `"alarm latency"` — the words as a phrase
+//! - `title:alarm`, `body:"free flow"` — text restricted to the title or body
+//! - `type:SRS status:approved` — attribute filters; repeating a field ORs
+//!   its values, different fields must all match
+//! - `entity:risk` — only one kind of record (document, requirement, risk, test)
+//! - a leading `-` excludes matches: `-status:draft`, `-"dose error"`
+
+use super::records::SearchEntity;
+use super::text::{analyze, normalize_keyword};
+use crate::prelude::*;
+
+/// Part of a record that a text term is matched against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextScope {
+    Any,
+    Title,
+    Body,
+}
+
+/// Word or phrase to match, as stemmed tokens
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryTerm {
+    pub tokens: Vec<String>,
+    pub scope: TextScope,
+    pub negated: bool,
+}
+
+impl QueryTerm {
+    pub fn is_phrase(&self) -> bool {
+        self.tokens.len() > 1
+    }
+}
+
+/// Attribute filter; matches when the record has any of `values` for `field`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldFilter {
+    pub field: String,
+    pub values: Vec<String>,
+    pub negated: bool,
+}
+
+/// Parsed search query
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchQuery {
+    pub terms: Vec<QueryTerm>,
+    pub filters: Vec<FieldFilter>,
+}
+
+impl SearchQuery {
+    /// Parse a query string
+    pub fn parse(input: &str) -> QmsResult<Self> {
+        let mut query = Self::default();
+        for clause in split_clauses(input)? {
+            query.add_clause(clause)?;
+        }
+        Ok(query)
+    }
+
+    /// Restrict results to the given kinds of record
+    pub fn restrict_to(&mut self, entities: &[SearchEntity]) {
+        if !entities.is_empty() {
+            let values = entities.iter().map(|entity| entity.name().to_string()).collect();
+            self.filters.push(FieldFilter { field: "entity".to_string(), values, negated: false });
+        }
+    }
+
+    /// Whether the query has no positive text terms, so it only filters
+    pub fn is_filter_only(&self) -> bool {
+        self.terms.iter().all(|term| term.negated)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty() && self.filters.is_empty()
+    }
+
+    fn add_clause(&mut self, clause: Clause) -> QmsResult<()> {
+        let scope = match clause.field.as_deref() {
+            None => TextScope::Any,
+            Some("title") => TextScope::Title,
+            Some("body" | "text" | "content") => TextScope::Body,
+            Some(field) => {
+                let field = canonical_field(field);
+                let value = if field == "entity" {
+                    SearchEntity::parse(&clause.value)
+                        .ok_or_else(|| {
+                            QmsError::validation_error(&format!(
+                                "Unknown entity '{}': use document, requirement, risk or test",
+                                clause.value
+                            ))
+                        })?
+                        .name()
+                        .to_string()
+                } else {
+                    normalize_keyword(&clause.value)
+                };
+                if value.is_empty() {
+                    return Err(QmsError::validation_error(&format!("Filter '{field}:' needs a value")));
+                }
+                match self
+                    .filters
+                    .iter_mut()
+                    .find(|filter| filter.field == field && filter.negated == clause.negated)
+                {
+                    Some(filter) if !clause.negated => filter.values.push(value),
+                    _ => self.filters.push(FieldFilter { field, values: vec![value], negated: clause.negated }),
+                }
+                return Ok(());
+            }
+        };
+
+        // Unquoted words that split into several tokens (`alarm-latency`,
+        // `REQ-001`) are matched as phrases too
+        let tokens = analyze(&clause.value);
+        if !tokens.is_empty() {
+            self.terms.push(QueryTerm { tokens, scope, negated: clause.negated });
+        }
+        Ok(())
+    }
+}
+
+/// Field names accepted as synonyms
+fn canonical_field(field: &str) -> String {
+    match field {
+        "tags" => "tag".to_string(),
+        "kind" | "is" | "in" => "entity".to_string(),
+        "owner" | "created_by" => "author".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// One whitespace-separated piece of a query
+#[derive(Debug)]
+struct Clause {
+    field: Option<String>,
+    value: String,
+    negated: bool,
+}
+
+fn split_clauses(input: &str) -> QmsResult<Vec<Clause>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut clauses = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i].is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let negated = chars[i] == '-';
+        if negated {
+            i += 1;
+        }
+
+        let mut field = None;
+        if chars.get(i) != Some(&'"') {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            if i > start && chars.get(i) == Some(&':') && i + 1 < chars.len() && !chars[i + 1].is_whitespace() {
+                let name: String = chars[start..i].iter().collect();
+                if name.chars().all(|c| c.is_alphabetic() || c == '_') {
+                    field = Some(name.to_lowercase());
+                    i += 1;
+                } else {
+                    i = start;
+                }
+            } else {
+                i = start;
+            }
+        }
+
+        let value: String = if chars.get(i) == Some(&'"') {
+            let start = i + 1;
+            let end = chars[start..]
+                .iter()
+                .position(|&c| c == '"')
+                .map(|offset| start + offset)
+                .ok_or_else(|| QmsError::validation_error("Unterminated quote in search query"))?;
+            i = end + 1;
+            chars[start..end].iter().collect()
+        } else {
+            let start = i;
+            while i < chars.len() && !chars[i].is_whitespace() {
+                i += 1;
+            }
+            chars[start..i].iter().collect()
+        };
+
+        clauses.push(Clause { field, value, negated });
+    }
+    Ok(clauses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_terms_phrases_and_filters() {
+        let query = SearchQuery::parse("type:SRS status:approved \"alarm latency\" pumps -title:draft").unwrap();
+        assert_eq!(
+            query.terms,
+            vec![
+                QueryTerm { tokens: vec!["alarm".into(), "latenc".into()], scope: TextScope::Any, negated: false },
+                QueryTerm { tokens: vec!["pump".into()], scope: TextScope::Any, negated: false },
+                QueryTerm { tokens: vec!["draft".into()], scope: TextScope::Title, negated: true },
+            ]
+        );
+        assert_eq!(
+            query.filters,
+            vec![
+                FieldFilter { field: "type".into(), values: vec!["srs".into()], negated: false },
+                FieldFilter { field: "status".into(), values: vec!["approved".into()], negated: false },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_repeated_fields_and_entities() {
+        let query = SearchQuery::parse("status:\"In Review\" status:draft -tags:legacy entity:risks 10:30").unwrap();
+        assert_eq!(query.filters[0].values, vec!["inreview", "draft"]);
+        assert_eq!(query.filters[1], FieldFilter { field: "tag".into(), values: vec!["legacy".into()], negated: true });
+        assert_eq!(query.filters[2].values, vec!["risk"]);
+        // Not a field: the name is numeric
+        assert_eq!(query.terms[0].tokens, vec!["10", "30"]);
+
+        assert!(SearchQuery::parse("entity:widget").is_err());
+        assert!(SearchQuery::parse("\"unterminated").is_err());
+        assert!(SearchQuery::parse("status:approved").unwrap().is_filter_only());
+    }
+}
@@ -0,0 +1,208 @@
+//! Searchable Records
+//!
+//! Flattens documents, requirements, risks and test cases into the title,
+//! body and attribute fields that the index stores.
+
+use crate::modules::document_control::document::Document;
+use crate::modules::document_control::service::DocumentService;
+use crate::modules::risk_manager::risk::{RiskItem, RiskManager};
+use crate::modules::traceability::requirement::{Requirement, RequirementManager};
+use crate::modules::traceability::test_case::{TestCase, TestCaseManager};
+use crate::prelude::*;
+use std::path::Path;
+
+/// Kind of record held in the search index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SearchEntity {
+    Document,
+    Requirement,
+    Risk,
+    Test,
+}
+
+impl SearchEntity {
+    pub const ALL: [Self; 4] = [Self::Document, Self::Requirement, Self::Risk, Self::Test];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Document => "document",
+            Self::Requirement => "requirement",
+            Self::Risk => "risk",
+            Self::Test => "test",
+        }
+    }
+
+    /// Parse an entity name, accepting plurals and common abbreviations
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "document" | "documents" | "doc" | "docs" => Some(Self::Document),
+            "requirement" | "requirements" | "req" | "reqs" => Some(Self::Requirement),
+            "risk" | "risks" | "hazard" | "hazards" => Some(Self::Risk),
+            "test" | "tests" | "testcase" | "testcases" => Some(Self::Test),
+            _ => None,
+        }
+    }
+}
+
+/// Text and attributes of one record, ready to index
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchRecord {
+    pub entity: SearchEntity,
+    /// Storage ID, unique within the entity kind
+    pub key: String,
+    /// User-facing ID (REQ-001, HAZ-001, ...)
+    pub id: String,
+    pub title: String,
+    pub body: String,
+    /// Filterable `field:value` pairs; a field may repeat
+    pub attributes: Vec<(String, String)>,
+}
+
+impl SearchRecord {
+    pub fn new(entity: SearchEntity, key: &str, id: &str, title: &str) -> Self {
+        Self {
+            entity,
+            key: key.to_string(),
+            id: id.to_string(),
+            title: title.to_string(),
+            body: String::new(),
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Append a paragraph of body text
+    pub fn text(mut self, text: &str) -> Self {
+        if !text.trim().is_empty() {
+            if !self.body.is_empty() {
+                self.body.push_str("\n\n");
+            }
+            self.body.push_str(text.trim());
+        }
+        self
+    }
+
+    pub fn attribute(mut self, field: &str, value: &str) -> Self {
+        if !value.trim().is_empty() {
+            self.attributes.push((field.to_string(), value.to_string()));
+        }
+        self
+    }
+
+    pub fn attributes<'a>(self, field: &str, values: impl IntoIterator<Item = &'a String>) -> Self {
+        values.into_iter().fold(self, |record, value| record.attribute(field, value))
+    }
+
+    /// Key of the record in the index
+    pub fn index_key(&self) -> String {
+        index_key(self.entity, &self.key)
+    }
+}
+
+/// Index key of the record with storage ID `key`
+pub fn index_key(entity: SearchEntity, key: &str) -> String {
+    format!("{}:{key}", entity.name())
+}
+
+pub fn document_record(document: &Document) -> SearchRecord {
+    let standards: Vec<String> = document.regulatory_mapping.iter().map(|r| r.standard.clone()).collect();
+    let mut record = SearchRecord::new(SearchEntity::Document, &document.id, &document.id, &document.title)
+        .text(&document.content)
+        .attribute("type", &document.doc_type.to_string())
+        .attribute("status", &document.status.to_string())
+        .attribute("version", &document.version)
+        .attribute("author", &document.created_by)
+        .attributes("tag", &document.tags)
+        .attributes("standard", &standards);
+    if let Some(approver) = &document.approved_by {
+        record = record.attribute("approver", approver);
+    }
+    record
+}
+
+pub fn requirement_record(requirement: &Requirement) -> SearchRecord {
+    let standards: Vec<String> = requirement.regulatory_mapping.iter().map(|r| r.standard.clone()).collect();
+    let mut record =
+        SearchRecord::new(SearchEntity::Requirement, &requirement.id, &requirement.req_id, &requirement.title)
+            .text(&requirement.description)
+            .text(&requirement.rationale)
+            .text(&requirement.acceptance_criteria)
+            .attribute("category", requirement.category.as_str())
+            .attribute("priority", requirement.priority.as_str())
+            .attribute("status", requirement.status.as_str())
+            .attribute("verification", requirement.verification_method.as_str())
+            .attribute("source", &requirement.source)
+            .attribute("author", &requirement.created_by)
+            .attributes("tag", &requirement.tags)
+            .attributes("standard", &standards);
+    if let Some(assignee) = &requirement.assigned_to {
+        record = record.attribute("assignee", assignee);
+    }
+    record
+}
+
+pub fn risk_record(risk: &RiskItem) -> SearchRecord {
+    let mut record = SearchRecord::new(SearchEntity::Risk, &risk.id, &risk.hazard_id, &risk.hazard_description)
+        .text(&risk.hazardous_situation)
+        .text(&risk.harm);
+    for measure in &risk.mitigation_measures {
+        record = record.text(&measure.description).text(&measure.implementation);
+    }
+    if let Some(justification) = &risk.residual_risk_justification {
+        record = record.text(justification);
+    }
+    record = record
+        .attribute("severity", &format!("{:?}", risk.severity))
+        .attribute("level", &format!("{:?}", risk.initial_risk_level))
+        .attribute("residual", &format!("{:?}", risk.residual_risk_level))
+        .attribute("status", &format!("{:?}", risk.risk_status))
+        .attribute("priority", &risk.priority)
+        .attribute("category", &risk.category)
+        .attribute("source", &risk.source)
+        .attributes("tag", &risk.tags)
+        .attributes("standard", &risk.regulatory_references);
+    if let Some(assignee) = &risk.assigned_to {
+        record = record.attribute("assignee", assignee);
+    }
+    record
+}
+
+pub fn test_case_record(test_case: &TestCase) -> SearchRecord {
+    let mut record =
+        SearchRecord::new(SearchEntity::Test, &test_case.test_id, &test_case.test_id, &test_case.title)
+            .text(&test_case.description);
+    if let Some(preconditions) = &test_case.preconditions {
+        record = record.text(preconditions);
+    }
+    for step in &test_case.steps {
+        record = record.text(&step.action).text(&step.expected_result);
+    }
+    record
+        .attribute("category", &format!("{:?}", test_case.category))
+        .attribute("priority", &format!("{:?}", test_case.priority))
+        .attribute("author", &test_case.created_by)
+        .attributes("tag", &test_case.tags)
+}
+
+/// Every searchable record in the project
+pub fn collect_records(project_path: &Path) -> QmsResult<Vec<SearchRecord>> {
+    let mut records = Vec::new();
+
+    let documents = DocumentService::new(project_path.to_path_buf());
+    for entry in documents.list_documents()? {
+        match documents.read_document(&entry.id) {
+            Ok(document) => records.push(document_record(&document)),
+            Err(e) => eprintln!("Warning: Skipping document {} in search index: {e}", entry.id),
+        }
+    }
+
+    let requirements = RequirementManager::new(project_path)?;
+    records.extend(requirements.list_requirements().into_iter().map(requirement_record));
+
+    records.extend(RiskManager::new(project_path)?.load_all_risks()?.iter().map(risk_record));
+
+    if project_path.join("tests").is_dir() {
+        records.extend(TestCaseManager::new(project_path)?.list_test_cases().into_iter().map(test_case_record));
+    }
+
+    Ok(records)
+}
@@ -0,0 +1,311 @@
+//! Text Analysis
+//!
+//! Splits text into lowercase alphanumeric tokens and reduces English words
+//! to their stems with the Porter algorithm, so "alarms", "alarmed" and
+//! "alarming" all index as "alarm". The same analysis is applied to indexed
+//! text and to queries.
+
+/// Tokens of `text` in order, stemmed
+pub fn analyze(text: &str) -> Vec<String> {
+    tokenize(text).into_iter().map(|token| stem(&token)).collect()
+}
+
+/// Lowercase runs of letters and digits
+pub fn tokenize(text: &str) -> Vec<String> {
+    token_spans(text).into_iter().map(|(start, end)| text[start..end].to_lowercase()).collect()
+}
+
+/// Byte ranges of the tokens in `text`
+pub fn token_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                spans.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        spans.push((s, text.len()));
+    }
+    spans
+}
+
+/// Keyword form used for attribute values: lowercase letters and digits only
+///
+/// `In Review`, `in-review` and `InReview` all normalise to `inreview`.
+pub fn normalize_keyword(value: &str) -> String {
+    value.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+/// Porter stem of a lowercase token; non-ASCII and short tokens are returned as is
+pub fn stem(token: &str) -> String {
+    if token.len() <= 2 || !token.bytes().all(|b| b.is_ascii_lowercase()) {
+        return token.to_string();
+    }
+    let mut word = Stemmer { b: token.as_bytes().to_vec() };
+    word.step1ab();
+    word.step1c();
+    word.step2();
+    word.step3();
+    word.step4();
+    word.step5();
+    String::from_utf8(word.b).unwrap_or_else(|_| token.to_string())
+}
+
+/// Porter stemmer state: the word being reduced
+struct Stemmer {
+    b: Vec<u8>,
+}
+
+impl Stemmer {
+    /// Whether the letter at `i` is a consonant
+    fn is_consonant(&self, i: usize) -> bool {
+        match self.b[i] {
+            b'a' | b'e' | b'i' | b'o' | b'u' => false,
+            b'y' => i == 0 || !self.is_consonant(i - 1),
+            _ => true,
+        }
+    }
+
+    /// Number of vowel-consonant sequences in the first `len` letters
+    fn measure(&self, len: usize) -> usize {
+        let mut count = 0;
+        let mut i = 0;
+        while i < len && self.is_consonant(i) {
+            i += 1;
+        }
+        loop {
+            while i < len && !self.is_consonant(i) {
+                i += 1;
+            }
+            if i >= len {
+                return count;
+            }
+            while i < len && self.is_consonant(i) {
+                i += 1;
+            }
+            count += 1;
+        }
+    }
+
+    fn has_vowel(&self, len: usize) -> bool {
+        (0..len).any(|i| !self.is_consonant(i))
+    }
+
+    /// Whether the first `len` letters end in a double consonant
+    fn double_consonant(&self, len: usize) -> bool {
+        len >= 2 && self.b[len - 1] == self.b[len - 2] && self.is_consonant(len - 1)
+    }
+
+    /// Whether the first `len` letters end consonant-vowel-consonant, the last not w, x or y
+    fn cvc(&self, len: usize) -> bool {
+        len >= 3
+            && self.is_consonant(len - 3)
+            && !self.is_consonant(len - 2)
+            && self.is_consonant(len - 1)
+            && !matches!(self.b[len - 1], b'w' | b'x' | b'y')
+    }
+
+    fn ends(&self, suffix: &str) -> bool {
+        self.b.ends_with(suffix.as_bytes())
+    }
+
+    /// Length of the stem left when `suffix` is removed
+    fn stem_len(&self, suffix: &str) -> usize {
+        self.b.len() - suffix.len()
+    }
+
+    fn set_suffix(&mut self, suffix: &str, replacement: &str) {
+        let len = self.stem_len(suffix);
+        self.b.truncate(len);
+        self.b.extend_from_slice(replacement.as_bytes());
+    }
+
+    /// Replace `suffix` when the remaining stem has a measure above `min_measure`
+    fn replace_if(&mut self, suffix: &str, replacement: &str, min_measure: usize) -> bool {
+        if !self.ends(suffix) {
+            return false;
+        }
+        if self.measure(self.stem_len(suffix)) > min_measure {
+            self.set_suffix(suffix, replacement);
+        }
+        true
+    }
+
+    /// Plurals and -ed / -ing
+    fn step1ab(&mut self) {
+        if self.ends("sses") || self.ends("ies") {
+            let suffix = if self.ends("sses") { "sses" } else { "ies" };
+            let replacement = if suffix == "sses" { "ss" } else { "i" };
+            self.set_suffix(suffix, replacement);
+        } else if self.ends("s") && !self.ends("ss") {
+            self.b.pop();
+        }
+
+        if self.ends("eed") {
+            if self.measure(self.stem_len("eed")) > 0 {
+                self.b.pop();
+            }
+            return;
+        }
+        let suffix = if self.ends("ed") {
+            "ed"
+        } else if self.ends("ing") {
+            "ing"
+        } else {
+            return;
+        };
+        if !self.has_vowel(self.stem_len(suffix)) {
+            return;
+        }
+        self.set_suffix(suffix, "");
+        if self.ends("at") || self.ends("bl") || self.ends("iz") {
+            self.b.push(b'e');
+        } else if self.double_consonant(self.b.len()) {
+            if !matches!(self.b[self.b.len() - 1], b'l' | b's' | b'z') {
+                self.b.pop();
+            }
+        } else if self.measure(self.b.len()) == 1 && self.cvc(self.b.len()) {
+            self.b.push(b'e');
+        }
+    }
+
+    /// Terminal y to i when there is another vowel in the stem
+    fn step1c(&mut self) {
+        if self.ends("y") && self.has_vowel(self.b.len() - 1) {
+            let last = self.b.len() - 1;
+            self.b[last] = b'i';
+        }
+    }
+
+    /// Double suffixes to single ones, e.g. -ization to -ize
+    fn step2(&mut self) {
+        const RULES: &[(&str, &str)] = &[
+            ("ational", "ate"),
+            ("tional", "tion"),
+            ("enci", "ence"),
+            ("anci", "ance"),
+            ("izer", "ize"),
+            ("bli", "ble"),
+            ("alli", "al"),
+            ("entli", "ent"),
+            ("eli", "e"),
+            ("ousli", "ous"),
+            ("ization", "ize"),
+            ("ation", "ate"),
+            ("ator", "ate"),
+            ("alism", "al"),
+            ("iveness", "ive"),
+            ("fulness", "ful"),
+            ("ousness", "ous"),
+            ("aliti", "al"),
+            ("iviti", "ive"),
+            ("biliti", "ble"),
+            ("logi", "log"),
+        ];
+        for (suffix, replacement) in RULES {
+            if self.replace_if(suffix, replacement, 0) {
+                return;
+            }
+        }
+    }
+
+    /// -ic-, -full, -ness and similar
+    fn step3(&mut self) {
+        const RULES: &[(&str, &str)] = &[
+            ("icate", "ic"),
+            ("ative", ""),
+            ("alize", "al"),
+            ("iciti", "ic"),
+            ("ical", "ic"),
+            ("ful", ""),
+            ("ness", ""),
+        ];
+        for (suffix, replacement) in RULES {
+            if self.replace_if(suffix, replacement, 0) {
+                return;
+            }
+        }
+    }
+
+    /// Remove -ant, -ence and similar from stems with measure above one
+    fn step4(&mut self) {
+        const SUFFIXES: &[&str] = &[
+            "al", "ance", "ence", "er", "ic", "able", "ible", "ant", "ement", "ment", "ent", "ion", "ou", "ism",
+            "ate", "iti", "ous", "ive", "ize",
+        ];
+        // Longest matching suffix wins (ement before ment before ent)
+        let Some(suffix) = SUFFIXES.iter().filter(|s| self.ends(s)).max_by_key(|s| s.len()) else {
+            return;
+        };
+        let len = self.stem_len(suffix);
+        if *suffix == "ion" && !(len > 0 && matches!(self.b[len - 1], b's' | b't')) {
+            return;
+        }
+        if self.measure(len) > 1 {
+            self.b.truncate(len);
+        }
+    }
+
+    /// Final -e and -ll
+    fn step5(&mut self) {
+        let len = self.b.len();
+        if self.ends("e") {
+            let m = self.measure(len - 1);
+            if m > 1 || (m == 1 && !self.cvc(len - 1)) {
+                self.b.pop();
+            }
+        }
+        let len = self.b.len();
+        if self.b[len - 1] == b'l' && self.double_consonant(len) && self.measure(len) > 1 {
+            self.b.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_and_normalize() {
+        assert_eq!(tokenize("Alarm-latency: REQ-001 (≤ 2s)"), vec!["alarm", "latency", "req", "001", "2s"]);
+        assert_eq!(normalize_keyword("In Review"), "inreview");
+        assert_eq!(normalize_keyword("safety-critical"), "safetycritical");
+    }
+
+    #[test]
+    fn test_porter_stems() {
+        let cases = [
+            ("caresses", "caress"),
+            ("ponies", "poni"),
+            ("cats", "cat"),
+            ("agreed", "agre"),
+            ("plastered", "plaster"),
+            ("motoring", "motor"),
+            ("hopping", "hop"),
+            ("filing", "file"),
+            ("happy", "happi"),
+            ("relational", "relat"),
+            ("conditional", "condit"),
+            ("validation", "valid"),
+            ("hopefulness", "hope"),
+            ("electrical", "electr"),
+            ("adjustment", "adjust"),
+            ("controlling", "control"),
+            ("alarms", "alarm"),
+            ("alarming", "alarm"),
+            ("requirements", "requir"),
+            ("required", "requir"),
+        ];
+        for (word, expected) in cases {
+            assert_eq!(stem(word), expected, "stem of {word}");
+        }
+        assert_eq!(analyze("Occlusion alarms"), analyze("occlusion alarm"));
+    }
+}
@@ -0,0 +1,556 @@
+//! SOUP Manager
+//!
+//! Keeps the SOUP register in `soup/register.json` and every imported SBOM in
+//! `soup/imports/<SBOM-NNN>.json`. Importing an SBOM reconciles the register:
+//! new components become SOUP items, version changes update the matching item
+//! and keep its requirements, risks and anomaly evaluations, components no
+//! longer listed are marked out of use, and listed vulnerabilities are added as
+//! anomalies pending evaluation.
+
+use crate::json_utils::JsonSerializable;
+use crate::modules::audit_logger::functions::audit_log_action;
+use crate::modules::change_control::{ChangeControlManager, ChangeRequest};
+use crate::modules::risk_manager::risk::RiskManager;
+use crate::modules::storage::encryption;
+use crate::prelude::*;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::record::{
+    match_components, AnomalyEvaluation, SbomDiff, SbomFormat, SbomImport, SoupAnomaly, SoupItem, SoupRegister,
+};
+use super::sbom::parse_sbom;
+
+/// What an SBOM import changed in the register
+#[derive(Debug, Clone)]
+pub struct SoupImportResult {
+    pub import: SbomImport,
+    pub created: Vec<String>,                  // New SOUP item IDs
+    pub updated: Vec<(String, String, String)>, // (SOUP ID, old version, new version)
+    pub retired: Vec<String>,                  // Items no longer in the SBOM
+    pub anomalies_added: usize,
+    pub diff: Option<SbomDiff>, // Against the previous import
+}
+
+/// SOUP register manager
+pub struct SoupManager {
+    project_path: PathBuf,
+    soup_dir: PathBuf,
+}
+
+impl SoupManager {
+    pub fn new(project_path: &Path) -> QmsResult<Self> {
+        Ok(Self {
+            project_path: project_path.to_path_buf(),
+            soup_dir: project_path.join("soup"),
+        })
+    }
+
+    fn register_path(&self) -> PathBuf {
+        self.soup_dir.join("register.json")
+    }
+
+    fn imports_dir(&self) -> PathBuf {
+        self.soup_dir.join("imports")
+    }
+
+    /// The SOUP register; empty before the first import
+    pub fn load_register(&self) -> QmsResult<SoupRegister> {
+        let path = self.register_path();
+        if !path.is_file() {
+            return Ok(SoupRegister::default());
+        }
+        let content = encryption::read_to_string(&path)?;
+        SoupRegister::from_json(&content).map_err(|e| QmsError::parse_error(&format!("Invalid SOUP register: {e}")))
+    }
+
+    fn save_register(&self, register: &SoupRegister) -> QmsResult<()> {
+        fs::create_dir_all(&self.soup_dir)?;
+        encryption::atomic_write(&self.register_path(), &register.to_json())
+    }
+
+    /// Import an SBOM read from `file` and reconcile the register with it
+    pub fn import_sbom(
+        &self,
+        file: &str,
+        content: &str,
+        format: Option<SbomFormat>,
+        imported_by: &str,
+    ) -> QmsResult<SoupImportResult> {
+        let parsed = parse_sbom(content, format)?;
+        let previous = self.list_imports()?.pop();
+        let now = crate::utils::current_iso8601_timestamp();
+        let import = SbomImport {
+            id: format!("SBOM-{:03}", self.next_number("SBOM-", &self.import_ids()?)),
+            file: file.to_string(),
+            format: parsed.format,
+            imported_at: now.clone(),
+            imported_by: imported_by.to_string(),
+            components: parsed.components,
+            vulnerabilities: parsed.vulnerabilities,
+        };
+
+        let mut register = self.load_register()?;
+        let mut result = SoupImportResult {
+            import: import.clone(),
+            created: Vec::new(),
+            updated: Vec::new(),
+            retired: Vec::new(),
+            anomalies_added: 0,
+            diff: previous.map(|previous| previous.diff(&import)),
+        };
+
+        // Match against items in use first, so a version change updates the current item
+        let in_use: Vec<usize> = (0..register.items.len()).filter(|&i| register.items[i].in_use).collect();
+        let old: Vec<(String, String)> = in_use
+            .iter()
+            .map(|&i| (register.items[i].key(), register.items[i].version.clone()))
+            .collect();
+        let new: Vec<(String, String)> = import.components.iter().map(|c| (c.key(), c.version.clone())).collect();
+        let matching = match_components(&old, &new);
+
+        let mut item_of_component: HashMap<usize, usize> = HashMap::new();
+        for &(i, j) in &matching.same {
+            item_of_component.insert(j, in_use[i]);
+        }
+        for &(i, j) in &matching.changed {
+            let item = &mut register.items[in_use[i]];
+            result.updated.push((item.id.clone(), item.version.clone(), import.components[j].version.clone()));
+            item.previous_version = Some(std::mem::replace(&mut item.version, import.components[j].version.clone()));
+            item_of_component.insert(j, in_use[i]);
+        }
+        for &i in &matching.removed {
+            let item = &mut register.items[in_use[i]];
+            item.in_use = false;
+            item.updated_at = now.clone();
+            result.retired.push(item.id.clone());
+        }
+        for &j in &matching.added {
+            let component = &import.components[j];
+            let key = component.key();
+            // A component listed again at a version it had before reactivates that item
+            let index = match register
+                .items
+                .iter()
+                .position(|item| !item.in_use && item.key() == key && item.version == component.version)
+            {
+                Some(index) => {
+                    register.items[index].in_use = true;
+                    index
+                }
+                None => {
+                    let ids: Vec<String> = register.items.iter().map(|item| item.id.clone()).collect();
+                    let id = format!("SOUP-{:03}", self.next_number("SOUP-", &ids));
+                    result.created.push(id.clone());
+                    register.items.push(SoupItem {
+                        id,
+                        name: component.name.clone(),
+                        version: component.version.clone(),
+                        previous_version: None,
+                        supplier: String::new(),
+                        purl: None,
+                        license: String::new(),
+                        description: String::new(),
+                        functional_requirements: Vec::new(),
+                        performance_requirements: Vec::new(),
+                        risk_ids: Vec::new(),
+                        anomalies: Vec::new(),
+                        in_use: true,
+                        last_import: String::new(),
+                        created_at: now.clone(),
+                        updated_at: now.clone(),
+                    });
+                    register.items.len() - 1
+                }
+            };
+            item_of_component.insert(j, index);
+        }
+
+        // Refresh metadata of every listed item from the SBOM
+        for (&j, &index) in &item_of_component {
+            let component = &import.components[j];
+            let item = &mut register.items[index];
+            item.name = component.name.clone();
+            // A sparser SBOM keeps what an earlier one recorded
+            if !component.supplier.is_empty() {
+                item.supplier = component.supplier.clone();
+            }
+            if component.purl.is_some() {
+                item.purl = component.purl.clone();
+            }
+            if !component.license.is_empty() {
+                item.license = component.license.clone();
+            }
+            if !component.description.is_empty() {
+                item.description = component.description.clone();
+            }
+            item.last_import = import.id.clone();
+            item.updated_at = now.clone();
+        }
+
+        for vulnerability in &import.vulnerabilities {
+            for (j, component) in import.components.iter().enumerate() {
+                if !vulnerability.affects.contains(&component.bom_ref) {
+                    continue;
+                }
+                let Some(&index) = item_of_component.get(&j) else { continue };
+                let item = &mut register.items[index];
+                if item.anomalies.iter().any(|anomaly| anomaly.id == vulnerability.id) {
+                    continue;
+                }
+                item.anomalies.push(SoupAnomaly {
+                    id: vulnerability.id.clone(),
+                    description: vulnerability.description.clone(),
+                    severity: vulnerability.severity.clone(),
+                    source: vulnerability.source.clone(),
+                    reported_version: item.version.clone(),
+                    evaluation: AnomalyEvaluation::Pending,
+                    rationale: String::new(),
+                    evaluated_by: None,
+                    evaluated_at: None,
+                });
+                result.anomalies_added += 1;
+            }
+        }
+
+        fs::create_dir_all(self.imports_dir())?;
+        encryption::atomic_write(&self.imports_dir().join(format!("{}.json", import.id)), &import.to_json())?;
+        self.save_register(&register)?;
+        let _ = audit_log_action(
+            "SBOM_IMPORTED",
+            "SoupRegister",
+            &format!(
+                "{} from {file} ({}): {} component(s), {} new, {} updated, {} retired, {} anomaly(ies)",
+                import.id,
+                import.format.name(),
+                import.components.len(),
+                result.created.len(),
+                result.updated.len(),
+                result.retired.len(),
+                result.anomalies_added
+            ),
+        );
+        Ok(result)
+    }
+
+    /// Next number after the highest `<prefix>NNN` in `ids`
+    fn next_number(&self, prefix: &str, ids: &[String]) -> u32 {
+        ids.iter()
+            .filter_map(|id| id.strip_prefix(prefix)?.parse::<u32>().ok())
+            .max()
+            .unwrap_or(0)
+            + 1
+    }
+
+    fn import_ids(&self) -> QmsResult<Vec<String>> {
+        let dir = self.imports_dir();
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut ids = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                if let Some(stem) = path.file_stem() {
+                    ids.push(stem.to_string_lossy().to_string());
+                }
+            }
+        }
+        ids.sort();
+        Ok(ids)
+    }
+
+    /// Imported SBOMs, oldest first
+    pub fn list_imports(&self) -> QmsResult<Vec<SbomImport>> {
+        self.import_ids()?.iter().map(|id| self.load_import(id)).collect()
+    }
+
+    pub fn load_import(&self, id: &str) -> QmsResult<SbomImport> {
+        let path = self.imports_dir().join(format!("{id}.json"));
+        if id.contains(['/', '\\']) || !path.is_file() {
+            return Err(QmsError::not_found(&format!("SBOM import {id} not found")));
+        }
+        let content = encryption::read_to_string(&path)?;
+        SbomImport::from_json(&content).map_err(|e| QmsError::parse_error(&format!("Invalid SBOM import {id}: {e}")))
+    }
+
+    /// Differences between two imports; by default the last two
+    pub fn diff(&self, from: Option<&str>, to: Option<&str>) -> QmsResult<SbomDiff> {
+        match (from, to) {
+            (Some(from), Some(to)) => Ok(self.load_import(from)?.diff(&self.load_import(to)?)),
+            (Some(from), None) => {
+                let latest = self
+                    .list_imports()?
+                    .pop()
+                    .ok_or_else(|| QmsError::not_found("No SBOM imported"))?;
+                Ok(self.load_import(from)?.diff(&latest))
+            }
+            (None, _) => {
+                let mut imports = self.list_imports()?;
+                if imports.len() < 2 {
+                    return Err(QmsError::validation_error("At least two SBOM imports are needed for a diff"));
+                }
+                let latest = imports.pop().unwrap_or_else(|| unreachable!());
+                let previous = imports.pop().unwrap_or_else(|| unreachable!());
+                Ok(previous.diff(&latest))
+            }
+        }
+    }
+
+    /// Raise a change request covering the differences between two imports
+    pub fn raise_change_request(&self, diff: &SbomDiff, requested_by: &str) -> QmsResult<ChangeRequest> {
+        if diff.is_empty() {
+            return Err(QmsError::validation_error(&format!(
+                "No SOUP changes between {} and {}",
+                diff.from, diff.to
+            )));
+        }
+        ChangeControlManager::new(&self.project_path)?.create_change(
+            &format!("Update SOUP from {} to {}", diff.from, diff.to),
+            &diff.summary(),
+            requested_by,
+        )
+    }
+
+    /// A SOUP item by ID, or by name when exactly one item in use has it
+    pub fn get(&self, id: &str) -> QmsResult<SoupItem> {
+        let register = self.load_register()?;
+        if let Some(item) = register.items.iter().find(|item| item.id == id) {
+            return Ok(item.clone());
+        }
+        let mut named = register.items.iter().filter(|item| item.in_use && item.name == id);
+        match (named.next(), named.next()) {
+            (Some(item), None) => Ok(item.clone()),
+            (Some(_), Some(_)) => Err(QmsError::validation_error(&format!(
+                "Several SOUP items are named {id}; use the SOUP ID"
+            ))),
+            (None, _) => Err(QmsError::not_found(&format!("SOUP item {id} not found"))),
+        }
+    }
+
+    /// Apply `change` to one item and save the register
+    fn update<F>(&self, id: &str, action: &str, details: &str, change: F) -> QmsResult<SoupItem>
+    where
+        F: FnOnce(&mut SoupItem) -> QmsResult<()>,
+    {
+        let id = self.get(id)?.id;
+        let mut register = self.load_register()?;
+        let item = register
+            .items
+            .iter_mut()
+            .find(|item| item.id == id)
+            .ok_or_else(|| QmsError::not_found(&format!("SOUP item {id} not found")))?;
+        change(item)?;
+        item.updated_at = crate::utils::current_iso8601_timestamp();
+        let updated = item.clone();
+        self.save_register(&register)?;
+        let _ = audit_log_action(action, "SoupItem", &format!("{id}: {details}"));
+        Ok(updated)
+    }
+
+    /// Record a functional or performance requirement the item must meet (IEC 62304 5.3.3)
+    pub fn add_requirement(&self, id: &str, performance: bool, requirement: &str) -> QmsResult<SoupItem> {
+        let requirement = requirement.trim();
+        if requirement.is_empty() {
+            return Err(QmsError::validation_error("Requirement text cannot be empty"));
+        }
+        let kind = if performance { "performance" } else { "functional" };
+        self.update(id, "SOUP_REQUIREMENT_ADDED", &format!("{kind}: {requirement}"), |item| {
+            let requirements = if performance {
+                &mut item.performance_requirements
+            } else {
+                &mut item.functional_requirements
+            };
+            if requirements.iter().any(|existing| existing == requirement) {
+                return Err(QmsError::already_exists(&format!("{} already has this requirement", item.id)));
+            }
+            requirements.push(requirement.to_string());
+            Ok(())
+        })
+    }
+
+    /// Link the item to a hazard in the risk register
+    pub fn link_risk(&self, id: &str, risk_id: &str) -> QmsResult<SoupItem> {
+        let risks = RiskManager::new(&self.project_path)?.list_risks(None)?;
+        let hazard_id = risks
+            .iter()
+            .find(|risk| risk.hazard_id == risk_id || risk.id == risk_id)
+            .map(|risk| risk.hazard_id.clone())
+            .ok_or_else(|| QmsError::not_found(&format!("Risk {risk_id} not found")))?;
+        self.update(id, "SOUP_RISK_LINKED", &hazard_id.clone(), |item| {
+            if item.risk_ids.contains(&hazard_id) {
+                return Err(QmsError::already_exists(&format!("{} is already linked to {hazard_id}", item.id)));
+            }
+            item.risk_ids.push(hazard_id);
+            Ok(())
+        })
+    }
+
+    /// Record a known anomaly not listed in an SBOM, e.g. from the supplier's issue tracker
+    pub fn add_anomaly(&self, id: &str, anomaly_id: &str, description: &str, severity: &str) -> QmsResult<SoupItem> {
+        let anomaly_id = anomaly_id.trim();
+        if anomaly_id.is_empty() {
+            return Err(QmsError::validation_error("Anomaly ID cannot be empty"));
+        }
+        self.update(id, "SOUP_ANOMALY_ADDED", anomaly_id, |item| {
+            if item.anomalies.iter().any(|anomaly| anomaly.id == anomaly_id) {
+                return Err(QmsError::already_exists(&format!("{} already lists anomaly {anomaly_id}", item.id)));
+            }
+            item.anomalies.push(SoupAnomaly {
+                id: anomaly_id.to_string(),
+                description: description.to_string(),
+                severity: severity.to_string(),
+                source: "manual".to_string(),
+                reported_version: item.version.clone(),
+                evaluation: AnomalyEvaluation::Pending,
+                rationale: String::new(),
+                evaluated_by: None,
+                evaluated_at: None,
+            });
+            Ok(())
+        })
+    }
+
+    /// Record whether a known anomaly can cause or contribute to a hazard (IEC 62304 7.1.3)
+    pub fn evaluate_anomaly(
+        &self,
+        id: &str,
+        anomaly_id: &str,
+        evaluation: AnomalyEvaluation,
+        rationale: &str,
+        evaluated_by: &str,
+    ) -> QmsResult<SoupItem> {
+        if evaluation == AnomalyEvaluation::Pending {
+            return Err(QmsError::validation_error("An evaluation cannot be set back to pending"));
+        }
+        if rationale.trim().is_empty() {
+            return Err(QmsError::validation_error("An anomaly evaluation requires a rationale"));
+        }
+        self.update(id, "SOUP_ANOMALY_EVALUATED", &format!("{anomaly_id}: {}", evaluation.name()), |item| {
+            let item_id = item.id.clone();
+            let anomaly = item
+                .anomalies
+                .iter_mut()
+                .find(|anomaly| anomaly.id == anomaly_id)
+                .ok_or_else(|| QmsError::not_found(&format!("Anomaly {anomaly_id} not found on {item_id}")))?;
+            anomaly.evaluation = evaluation;
+            anomaly.rationale = rationale.trim().to_string();
+            anomaly.evaluated_by = Some(evaluated_by.to_string());
+            anomaly.evaluated_at = Some(crate::utils::current_iso8601_timestamp());
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const CYCLONEDX_V1: &str = r#"{
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "metadata": {"component": {"name": "pump-firmware", "version": "2.0.0"}},
+        "components": [
+            {"bom-ref": "serde@1.0.190", "type": "library", "name": "serde", "version": "1.0.190",
+             "purl": "pkg:cargo/serde@1.0.190", "licenses": [{"expression": "MIT OR Apache-2.0"}],
+             "supplier": {"name": "serde-rs"}},
+            {"bom-ref": "syn@1.0.109", "name": "syn", "version": "1.0.109", "purl": "pkg:cargo/syn@1.0.109",
+             "components": [{"bom-ref": "syn-derive", "name": "syn-derive", "version": "0.1.0"}]},
+            {"bom-ref": "syn@2.0.38", "name": "syn", "version": "2.0.38", "purl": "pkg:cargo/syn@2.0.38",
+             "licenses": [{"license": {"id": "MIT"}}, {"license": {"name": "Custom"}}]}
+        ],
+        "vulnerabilities": [
+            {"id": "RUSTSEC-2023-0001", "source": {"name": "RustSec"}, "ratings": [{"severity": "high"}],
+             "description": "Stack overflow on deeply nested input", "affects": [{"ref": "serde@1.0.190"}]}
+        ]
+    }"#;
+
+    const SPDX_V2: &str = r#"{
+        "spdxVersion": "SPDX-2.3",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "documentDescribes": ["SPDXRef-firmware"],
+        "packages": [
+            {"SPDXID": "SPDXRef-firmware", "name": "pump-firmware", "versionInfo": "2.1.0"},
+            {"SPDXID": "SPDXRef-serde", "name": "serde", "versionInfo": "1.0.193",
+             "supplier": "Organization: serde-rs", "licenseConcluded": "NOASSERTION",
+             "licenseDeclared": "MIT OR Apache-2.0",
+             "externalRefs": [{"referenceCategory": "PACKAGE-MANAGER", "referenceType": "purl",
+                               "referenceLocator": "pkg:cargo/serde@1.0.193"}]},
+            {"SPDXID": "SPDXRef-syn", "name": "syn", "versionInfo": "2.0.38",
+             "externalRefs": [{"referenceType": "purl", "referenceLocator": "pkg:cargo/syn@2.0.38"}]},
+            {"SPDXID": "SPDXRef-log", "name": "log", "versionInfo": "0.4.20", "supplier": "NOASSERTION"}
+        ]
+    }"#;
+
+    #[test]
+    fn test_parse_sbom_formats() {
+        let cyclonedx = parse_sbom(CYCLONEDX_V1, None).unwrap();
+        assert_eq!(cyclonedx.format, SbomFormat::CycloneDx);
+        let names: Vec<&str> = cyclonedx.components.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["serde", "syn", "syn-derive", "syn"]);
+        assert_eq!(cyclonedx.components[0].license, "MIT OR Apache-2.0");
+        assert_eq!(cyclonedx.components[0].supplier, "serde-rs");
+        assert_eq!(cyclonedx.components[0].key(), "pkg:cargo/serde");
+        assert_eq!(cyclonedx.components[3].license, "MIT AND Custom");
+        assert_eq!(cyclonedx.vulnerabilities[0].severity, "high");
+        assert_eq!(cyclonedx.vulnerabilities[0].affects, vec!["serde@1.0.190"]);
+
+        let spdx = parse_sbom(SPDX_V2, None).unwrap();
+        assert_eq!(spdx.format, SbomFormat::Spdx);
+        let names: Vec<&str> = spdx.components.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["serde", "syn", "log"]);
+        assert_eq!(spdx.components[0].supplier, "serde-rs");
+        assert_eq!(spdx.components[0].license, "MIT OR Apache-2.0");
+        assert_eq!(spdx.components[2].supplier, "");
+        assert_eq!(spdx.components[2].key(), "log");
+
+        assert!(parse_sbom(r#"{"packages": []}"#, None).is_err());
+        assert!(parse_sbom("not json", None).is_err());
+    }
+
+    #[test]
+    fn test_import_reconciles_register_and_diff() {
+        let project = TempDir::new().unwrap();
+        let manager = SoupManager::new(project.path()).unwrap();
+
+        let first = manager.import_sbom("bom.cdx.json", CYCLONEDX_V1, None, "alice").unwrap();
+        assert_eq!(first.import.id, "SBOM-001");
+        assert_eq!(first.created.len(), 4);
+        assert_eq!(first.anomalies_added, 1);
+        assert!(first.diff.is_none());
+
+        manager.add_requirement("serde", false, "Deserializes pump configuration files").unwrap();
+        manager
+            .evaluate_anomaly("SOUP-001", "RUSTSEC-2023-0001", AnomalyEvaluation::NotApplicable, "Input is size-limited", "bob")
+            .unwrap();
+        assert!(manager.get("syn").is_err());
+        assert!(manager.add_requirement("SOUP-001", false, "Deserializes pump configuration files").is_err());
+
+        let second = manager.import_sbom("bom.spdx.json", SPDX_V2, None, "alice").unwrap();
+        assert_eq!(second.import.id, "SBOM-002");
+        assert_eq!(second.updated, vec![("SOUP-001".to_string(), "1.0.190".to_string(), "1.0.193".to_string())]);
+        assert_eq!(second.created, vec!["SOUP-005"]);
+        assert_eq!(second.retired.len(), 2);
+
+        let serde = manager.get("SOUP-001").unwrap();
+        assert_eq!(serde.version, "1.0.193");
+        assert_eq!(serde.previous_version.as_deref(), Some("1.0.190"));
+        assert_eq!(serde.functional_requirements.len(), 1);
+        assert_eq!(serde.anomalies[0].evaluation, AnomalyEvaluation::NotApplicable);
+        assert_eq!(serde.supplier, "serde-rs");
+        assert_eq!(manager.get("syn").unwrap().version, "2.0.38");
+
+        let diff = second.diff.unwrap();
+        assert_eq!(diff, manager.diff(None, None).unwrap());
+        let added: Vec<&str> = diff.added.iter().map(|c| c.name.as_str()).collect();
+        let removed: Vec<&str> = diff.removed.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(added, vec!["log"]);
+        assert_eq!(removed, vec!["syn", "syn-derive"]);
+        assert_eq!(diff.changed.len(), 1);
+        assert!(diff.summary().contains("~ serde 1.0.190 -> 1.0.193"));
+        assert!(manager.diff(Some("SBOM-002"), None).unwrap().is_empty());
+        assert!(manager.link_risk("SOUP-001", "HAZ-001").is_err());
+    }
+}
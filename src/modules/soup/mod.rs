@@ -0,0 +1,26 @@
+//! SOUP Module
+//!
+//! Software of unknown provenance per IEC 62304 Sections 5.3 and 8: a register
+//! of every SOUP item with its version, functional and performance
+//! requirements, linked hazards and evaluated known anomalies, populated from
+//! CycloneDX or SPDX JSON SBOMs. Each import is kept so two SBOMs can be
+//! compared and the differences raised as a change request.
+
+pub mod manager;
+pub mod record;
+pub mod sbom;
+
+#[allow(unused_imports)]
+pub use manager::{SoupImportResult, SoupManager};
+#[allow(unused_imports)]
+pub use record::{
+    component_key, AnomalyEvaluation, SbomComponent, SbomDiff, SbomFormat, SbomImport, SbomVulnerability,
+    SoupAnomaly, SoupItem, SoupRegister,
+};
+#[allow(unused_imports)]
+pub use sbom::{parse_sbom, ParsedSbom};
+
+/// Whether the project has a SOUP register
+pub fn has_soup(project_path: &std::path::Path) -> bool {
+    project_path.join("soup").join("register.json").is_file()
+}
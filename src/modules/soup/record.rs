@@ -0,0 +1,506 @@
+//! SOUP Records
+//!
+//! Register entries for software of unknown provenance per IEC 62304 Sections
+//! 5.3.3, 5.3.4, 7.1.3 and 8.1.2, and the SBOM imports they are populated from.
+
+use crate::json_utils::{JsonError, JsonSerializable, JsonValue};
+use crate::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+
+/// SBOM file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SbomFormat {
+    CycloneDx,
+    Spdx,
+}
+
+impl SbomFormat {
+    pub fn parse(s: &str) -> QmsResult<Self> {
+        match s.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "cyclonedx" | "cdx" => Ok(Self::CycloneDx),
+            "spdx" => Ok(Self::Spdx),
+            _ => Err(QmsError::validation_error(&format!("Unknown SBOM format: {s} (expected cyclonedx or spdx)"))),
+        }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::CycloneDx => "CycloneDX",
+            Self::Spdx => "SPDX",
+        }
+    }
+}
+
+/// Outcome of evaluating a known anomaly against the device's use of a SOUP item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnomalyEvaluation {
+    Pending,        // Not yet evaluated
+    NotApplicable,  // The anomaly cannot occur in the device's use of the item
+    Acceptable,     // Can occur; the resulting risk is acceptable
+    RequiresAction, // Needs a risk control, workaround or version change
+}
+
+impl AnomalyEvaluation {
+    pub fn parse(s: &str) -> QmsResult<Self> {
+        match s.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "pending" => Ok(Self::Pending),
+            "notapplicable" | "na" => Ok(Self::NotApplicable),
+            "acceptable" | "accepted" => Ok(Self::Acceptable),
+            "requiresaction" | "action" => Ok(Self::RequiresAction),
+            _ => Err(QmsError::validation_error(&format!(
+                "Unknown anomaly evaluation: {s} (expected not-applicable, acceptable or requires-action)"
+            ))),
+        }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::NotApplicable => "not-applicable",
+            Self::Acceptable => "acceptable",
+            Self::RequiresAction => "requires-action",
+        }
+    }
+
+    pub const fn display_name(&self) -> &'static str {
+        match self {
+            Self::Pending => "Pending",
+            Self::NotApplicable => "Not Applicable",
+            Self::Acceptable => "Acceptable",
+            Self::RequiresAction => "Requires Action",
+        }
+    }
+}
+
+/// A published anomaly (bug, advisory, CVE) of a SOUP item
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoupAnomaly {
+    pub id: String, // e.g. CVE-2024-1234, RUSTSEC-2024-0001
+    pub description: String,
+    pub severity: String,
+    pub source: String,
+    pub reported_version: String, // Item version the anomaly was recorded against
+    pub evaluation: AnomalyEvaluation,
+    pub rationale: String,
+    pub evaluated_by: Option<String>,
+    pub evaluated_at: Option<String>,
+}
+
+/// One SOUP item in the register
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoupItem {
+    pub id: String, // SOUP-001, SOUP-002...
+    pub name: String,
+    pub version: String,
+    pub previous_version: Option<String>, // Set when an import changed the version
+    pub supplier: String,
+    pub purl: Option<String>,
+    pub license: String,
+    pub description: String,
+    pub functional_requirements: Vec<String>,
+    pub performance_requirements: Vec<String>,
+    pub risk_ids: Vec<String>, // Hazard IDs in the risk register
+    pub anomalies: Vec<SoupAnomaly>,
+    pub in_use: bool, // Cleared when the latest SBOM no longer lists the item
+    pub last_import: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl SoupItem {
+    /// Identity of the item across SBOMs, ignoring version
+    pub fn key(&self) -> String {
+        component_key(self.purl.as_deref(), &self.name)
+    }
+
+    pub fn pending_anomalies(&self) -> usize {
+        self.anomalies
+            .iter()
+            .filter(|anomaly| anomaly.evaluation == AnomalyEvaluation::Pending)
+            .count()
+    }
+
+    pub fn has_requirements(&self) -> bool {
+        !self.functional_requirements.is_empty() || !self.performance_requirements.is_empty()
+    }
+}
+
+/// Identity of a component: its package URL without version, qualifiers and
+/// subpath, or its lowercased name when there is no package URL
+pub fn component_key(purl: Option<&str>, name: &str) -> String {
+    match purl {
+        Some(purl) if !purl.is_empty() => {
+            let end = purl.find(['?', '#']).unwrap_or(purl.len());
+            let purl = &purl[..end];
+            match purl.rfind('@') {
+                Some(at) if at > purl.rfind('/').unwrap_or(0) => purl[..at].to_string(),
+                _ => purl.to_string(),
+            }
+        }
+        _ => name.to_lowercase(),
+    }
+}
+
+/// A component as listed in an SBOM
+#[derive(Debug, Clone, PartialEq)]
+pub struct SbomComponent {
+    pub bom_ref: String, // CycloneDX bom-ref or SPDX SPDXID
+    pub name: String,
+    pub version: String,
+    pub supplier: String,
+    pub purl: Option<String>,
+    pub license: String,
+    pub description: String,
+}
+
+impl SbomComponent {
+    pub fn key(&self) -> String {
+        component_key(self.purl.as_deref(), &self.name)
+    }
+}
+
+/// A vulnerability listed in an SBOM
+#[derive(Debug, Clone, PartialEq)]
+pub struct SbomVulnerability {
+    pub id: String,
+    pub description: String,
+    pub severity: String,
+    pub source: String,
+    pub affects: Vec<String>, // bom-refs of the affected components
+}
+
+/// One imported SBOM, kept so later imports can be compared with it
+#[derive(Debug, Clone, PartialEq)]
+pub struct SbomImport {
+    pub id: String, // SBOM-001, SBOM-002...
+    pub file: String,
+    pub format: SbomFormat,
+    pub imported_at: String,
+    pub imported_by: String,
+    pub components: Vec<SbomComponent>,
+    pub vulnerabilities: Vec<SbomVulnerability>,
+}
+
+impl SbomImport {
+    /// Compare this import (the older side) against `other`
+    pub fn diff(&self, other: &SbomImport) -> SbomDiff {
+        let old: Vec<(String, String)> = self.components.iter().map(|c| (c.key(), c.version.clone())).collect();
+        let new: Vec<(String, String)> = other.components.iter().map(|c| (c.key(), c.version.clone())).collect();
+        let matching = match_components(&old, &new);
+
+        let mut diff = SbomDiff {
+            from: self.id.clone(),
+            to: other.id.clone(),
+            added: matching.added.iter().map(|&i| other.components[i].clone()).collect(),
+            removed: matching.removed.iter().map(|&i| self.components[i].clone()).collect(),
+            changed: matching
+                .changed
+                .iter()
+                .map(|&(i, j)| (self.components[i].clone(), other.components[j].clone()))
+                .collect(),
+        };
+        diff.added.sort_by(|a, b| a.name.cmp(&b.name));
+        diff.removed.sort_by(|a, b| a.name.cmp(&b.name));
+        diff.changed.sort_by(|a, b| a.0.name.cmp(&b.0.name));
+        diff
+    }
+}
+
+/// Components added, removed or changed in version between two SBOM imports
+#[derive(Debug, Clone, PartialEq)]
+pub struct SbomDiff {
+    pub from: String,
+    pub to: String,
+    pub added: Vec<SbomComponent>,
+    pub removed: Vec<SbomComponent>,
+    pub changed: Vec<(SbomComponent, SbomComponent)>,
+}
+
+impl SbomDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// One line per difference, for change request reasons
+    pub fn summary(&self) -> String {
+        let mut lines = vec![format!(
+            "SOUP changes from {} to {}: {} added, {} removed, {} version change(s)",
+            self.from,
+            self.to,
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len()
+        )];
+        lines.extend(self.added.iter().map(|c| format!("+ {} {}", c.name, c.version)));
+        lines.extend(self.removed.iter().map(|c| format!("- {} {}", c.name, c.version)));
+        lines.extend(self.changed.iter().map(|(old, new)| format!("~ {} {} -> {}", old.name, old.version, new.version)));
+        lines.join("\n")
+    }
+}
+
+/// How the components of two lists correspond, by index
+#[derive(Debug, Default)]
+pub struct ComponentMatching {
+    pub same: Vec<(usize, usize)>,
+    pub changed: Vec<(usize, usize)>,
+    pub added: Vec<usize>,
+    pub removed: Vec<usize>,
+}
+
+/// Match (key, version) lists: equal versions of a key pair first, the rest of
+/// a key pair up in version order as version changes, leftovers are added or removed
+pub fn match_components(old: &[(String, String)], new: &[(String, String)]) -> ComponentMatching {
+    let mut groups: BTreeMap<&str, (Vec<usize>, Vec<usize>)> = BTreeMap::new();
+    for (index, (key, _)) in old.iter().enumerate() {
+        groups.entry(key).or_default().0.push(index);
+    }
+    for (index, (key, _)) in new.iter().enumerate() {
+        groups.entry(key).or_default().1.push(index);
+    }
+
+    let mut matching = ComponentMatching::default();
+    for (_, (mut olds, mut news)) in groups {
+        olds.retain(|&i| match news.iter().position(|&j| new[j].1 == old[i].1) {
+            Some(position) => {
+                matching.same.push((i, news.remove(position)));
+                false
+            }
+            None => true,
+        });
+        olds.sort_by(|&a, &b| old[a].1.cmp(&old[b].1));
+        news.sort_by(|&a, &b| new[a].1.cmp(&new[b].1));
+        let paired = olds.len().min(news.len());
+        matching.changed.extend(olds.iter().copied().zip(news.iter().copied()));
+        matching.removed.extend(&olds[paired..]);
+        matching.added.extend(&news[paired..]);
+    }
+    matching.same.sort_unstable();
+    matching.changed.sort_unstable();
+    matching.added.sort_unstable();
+    matching.removed.sort_unstable();
+    matching
+}
+
+/// The SOUP register
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SoupRegister {
+    pub items: Vec<SoupItem>,
+}
+
+fn get_string(obj: &HashMap<String, JsonValue>, field: &str) -> Result<String, JsonError> {
+    match obj.get(field) {
+        Some(JsonValue::String(s)) => Ok(s.clone()),
+        Some(_) => Err(JsonError::InvalidFormat(format!("Field '{field}' is not a string"))),
+        None => Err(JsonError::InvalidFormat(format!("Missing required field '{field}'"))),
+    }
+}
+
+fn get_optional(obj: &HashMap<String, JsonValue>, field: &str) -> Option<String> {
+    match obj.get(field) {
+        Some(JsonValue::String(s)) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+fn get_strings(obj: &HashMap<String, JsonValue>, field: &str) -> Vec<String> {
+    match obj.get(field) {
+        Some(JsonValue::Array(values)) => values.iter().filter_map(|value| value.as_string().cloned()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn get_objects<'a>(obj: &'a HashMap<String, JsonValue>, field: &str) -> Vec<&'a HashMap<String, JsonValue>> {
+    match obj.get(field) {
+        Some(JsonValue::Array(values)) => values
+            .iter()
+            .filter_map(|value| match value {
+                JsonValue::Object(obj) => Some(obj),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn strings(values: &[String]) -> JsonValue {
+    JsonValue::Array(values.iter().cloned().map(JsonValue::String).collect())
+}
+
+fn optional(value: &Option<String>) -> JsonValue {
+    value.clone().map_or(JsonValue::Null, JsonValue::String)
+}
+
+fn anomaly_to_json(anomaly: &SoupAnomaly) -> JsonValue {
+    let mut obj = HashMap::new();
+    obj.insert("id".to_string(), JsonValue::String(anomaly.id.clone()));
+    obj.insert("description".to_string(), JsonValue::String(anomaly.description.clone()));
+    obj.insert("severity".to_string(), JsonValue::String(anomaly.severity.clone()));
+    obj.insert("source".to_string(), JsonValue::String(anomaly.source.clone()));
+    obj.insert("reported_version".to_string(), JsonValue::String(anomaly.reported_version.clone()));
+    obj.insert("evaluation".to_string(), JsonValue::String(anomaly.evaluation.name().to_string()));
+    obj.insert("rationale".to_string(), JsonValue::String(anomaly.rationale.clone()));
+    obj.insert("evaluated_by".to_string(), optional(&anomaly.evaluated_by));
+    obj.insert("evaluated_at".to_string(), optional(&anomaly.evaluated_at));
+    JsonValue::Object(obj)
+}
+
+fn anomaly_from_json(obj: &HashMap<String, JsonValue>) -> Result<SoupAnomaly, JsonError> {
+    Ok(SoupAnomaly {
+        id: get_string(obj, "id")?,
+        description: get_string(obj, "description").unwrap_or_default(),
+        severity: get_string(obj, "severity").unwrap_or_default(),
+        source: get_string(obj, "source").unwrap_or_default(),
+        reported_version: get_string(obj, "reported_version").unwrap_or_default(),
+        evaluation: AnomalyEvaluation::parse(&get_string(obj, "evaluation")?)
+            .map_err(|e| JsonError::InvalidFormat(e.to_string()))?,
+        rationale: get_string(obj, "rationale").unwrap_or_default(),
+        evaluated_by: get_optional(obj, "evaluated_by"),
+        evaluated_at: get_optional(obj, "evaluated_at"),
+    })
+}
+
+fn component_to_json(component: &SbomComponent) -> JsonValue {
+    let mut obj = HashMap::new();
+    obj.insert("bom_ref".to_string(), JsonValue::String(component.bom_ref.clone()));
+    obj.insert("name".to_string(), JsonValue::String(component.name.clone()));
+    obj.insert("version".to_string(), JsonValue::String(component.version.clone()));
+    obj.insert("supplier".to_string(), JsonValue::String(component.supplier.clone()));
+    obj.insert("purl".to_string(), optional(&component.purl));
+    obj.insert("license".to_string(), JsonValue::String(component.license.clone()));
+    obj.insert("description".to_string(), JsonValue::String(component.description.clone()));
+    JsonValue::Object(obj)
+}
+
+fn component_from_json(obj: &HashMap<String, JsonValue>) -> Result<SbomComponent, JsonError> {
+    Ok(SbomComponent {
+        bom_ref: get_string(obj, "bom_ref").unwrap_or_default(),
+        name: get_string(obj, "name")?,
+        version: get_string(obj, "version").unwrap_or_default(),
+        supplier: get_string(obj, "supplier").unwrap_or_default(),
+        purl: get_optional(obj, "purl"),
+        license: get_string(obj, "license").unwrap_or_default(),
+        description: get_string(obj, "description").unwrap_or_default(),
+    })
+}
+
+impl JsonSerializable for SoupRegister {
+    fn to_json(&self) -> String {
+        let items = self.items.iter().map(|item| {
+            let mut obj = HashMap::new();
+            obj.insert("id".to_string(), JsonValue::String(item.id.clone()));
+            obj.insert("name".to_string(), JsonValue::String(item.name.clone()));
+            obj.insert("version".to_string(), JsonValue::String(item.version.clone()));
+            obj.insert("previous_version".to_string(), optional(&item.previous_version));
+            obj.insert("supplier".to_string(), JsonValue::String(item.supplier.clone()));
+            obj.insert("purl".to_string(), optional(&item.purl));
+            obj.insert("license".to_string(), JsonValue::String(item.license.clone()));
+            obj.insert("description".to_string(), JsonValue::String(item.description.clone()));
+            obj.insert("functional_requirements".to_string(), strings(&item.functional_requirements));
+            obj.insert("performance_requirements".to_string(), strings(&item.performance_requirements));
+            obj.insert("risk_ids".to_string(), strings(&item.risk_ids));
+            obj.insert("anomalies".to_string(), JsonValue::Array(item.anomalies.iter().map(anomaly_to_json).collect()));
+            obj.insert("in_use".to_string(), JsonValue::Bool(item.in_use));
+            obj.insert("last_import".to_string(), JsonValue::String(item.last_import.clone()));
+            obj.insert("created_at".to_string(), JsonValue::String(item.created_at.clone()));
+            obj.insert("updated_at".to_string(), JsonValue::String(item.updated_at.clone()));
+            JsonValue::Object(obj)
+        }).collect();
+
+        let mut obj = HashMap::new();
+        obj.insert("version".to_string(), JsonValue::String("1.0".to_string()));
+        obj.insert("items".to_string(), JsonValue::Array(items));
+        JsonValue::Object(obj).json_to_string()
+    }
+
+    fn from_json(json: &str) -> Result<Self, JsonError> {
+        let obj = match JsonValue::parse(json)? {
+            JsonValue::Object(obj) => obj,
+            _ => return Err(JsonError::InvalidFormat("Expected JSON object".to_string())),
+        };
+
+        let mut items = Vec::new();
+        for item in get_objects(&obj, "items") {
+            items.push(SoupItem {
+                id: get_string(item, "id")?,
+                name: get_string(item, "name")?,
+                version: get_string(item, "version").unwrap_or_default(),
+                previous_version: get_optional(item, "previous_version"),
+                supplier: get_string(item, "supplier").unwrap_or_default(),
+                purl: get_optional(item, "purl"),
+                license: get_string(item, "license").unwrap_or_default(),
+                description: get_string(item, "description").unwrap_or_default(),
+                functional_requirements: get_strings(item, "functional_requirements"),
+                performance_requirements: get_strings(item, "performance_requirements"),
+                risk_ids: get_strings(item, "risk_ids"),
+                anomalies: get_objects(item, "anomalies")
+                    .into_iter()
+                    .map(anomaly_from_json)
+                    .collect::<Result<_, _>>()?,
+                in_use: item.get("in_use").and_then(JsonValue::as_bool).unwrap_or(true),
+                last_import: get_string(item, "last_import").unwrap_or_default(),
+                created_at: get_string(item, "created_at").unwrap_or_default(),
+                updated_at: get_string(item, "updated_at").unwrap_or_default(),
+            });
+        }
+        Ok(SoupRegister { items })
+    }
+}
+
+impl JsonSerializable for SbomImport {
+    fn to_json(&self) -> String {
+        let vulnerabilities = self.vulnerabilities.iter().map(|vulnerability| {
+            let mut obj = HashMap::new();
+            obj.insert("id".to_string(), JsonValue::String(vulnerability.id.clone()));
+            obj.insert("description".to_string(), JsonValue::String(vulnerability.description.clone()));
+            obj.insert("severity".to_string(), JsonValue::String(vulnerability.severity.clone()));
+            obj.insert("source".to_string(), JsonValue::String(vulnerability.source.clone()));
+            obj.insert("affects".to_string(), strings(&vulnerability.affects));
+            JsonValue::Object(obj)
+        }).collect();
+
+        let mut obj = HashMap::new();
+        obj.insert("version".to_string(), JsonValue::String("1.0".to_string()));
+        obj.insert("id".to_string(), JsonValue::String(self.id.clone()));
+        obj.insert("file".to_string(), JsonValue::String(self.file.clone()));
+        obj.insert("format".to_string(), JsonValue::String(self.format.name().to_string()));
+        obj.insert("imported_at".to_string(), JsonValue::String(self.imported_at.clone()));
+        obj.insert("imported_by".to_string(), JsonValue::String(self.imported_by.clone()));
+        obj.insert("components".to_string(), JsonValue::Array(self.components.iter().map(component_to_json).collect()));
+        obj.insert("vulnerabilities".to_string(), JsonValue::Array(vulnerabilities));
+        JsonValue::Object(obj).json_to_string()
+    }
+
+    fn from_json(json: &str) -> Result<Self, JsonError> {
+        let obj = match JsonValue::parse(json)? {
+            JsonValue::Object(obj) => obj,
+            _ => return Err(JsonError::InvalidFormat("Expected JSON object".to_string())),
+        };
+
+        let vulnerabilities = get_objects(&obj, "vulnerabilities")
+            .into_iter()
+            .map(|vulnerability| {
+                Ok(SbomVulnerability {
+                    id: get_string(vulnerability, "id")?,
+                    description: get_string(vulnerability, "description").unwrap_or_default(),
+                    severity: get_string(vulnerability, "severity").unwrap_or_default(),
+                    source: get_string(vulnerability, "source").unwrap_or_default(),
+                    affects: get_strings(vulnerability, "affects"),
+                })
+            })
+            .collect::<Result<_, JsonError>>()?;
+
+        Ok(SbomImport {
+            id: get_string(&obj, "id")?,
+            file: get_string(&obj, "file").unwrap_or_default(),
+            format: SbomFormat::parse(&get_string(&obj, "format")?)
+                .map_err(|e| JsonError::InvalidFormat(e.to_string()))?,
+            imported_at: get_string(&obj, "imported_at").unwrap_or_default(),
+            imported_by: get_string(&obj, "imported_by").unwrap_or_default(),
+            components: get_objects(&obj, "components")
+                .into_iter()
+                .map(component_from_json)
+                .collect::<Result<_, _>>()?,
+            vulnerabilities,
+        })
+    }
+}
@@ -0,0 +1,197 @@
+//! SBOM parsing
+//!
+//! Reads the components and vulnerabilities of CycloneDX (1.x JSON) and SPDX
+//! (2.x JSON) documents, such as those produced for a Cargo.lock by
+//! cargo-cyclonedx or cargo-sbom.
+
+use crate::json_utils::JsonValue;
+use crate::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+use super::record::{SbomComponent, SbomFormat, SbomVulnerability};
+
+/// Contents of one SBOM document
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedSbom {
+    pub format: SbomFormat,
+    pub components: Vec<SbomComponent>,
+    pub vulnerabilities: Vec<SbomVulnerability>,
+}
+
+type JsonObject = HashMap<String, JsonValue>;
+
+/// Parse an SBOM, detecting its format unless `format` is given
+pub fn parse_sbom(content: &str, format: Option<SbomFormat>) -> QmsResult<ParsedSbom> {
+    let document = match JsonValue::parse(content) {
+        Ok(JsonValue::Object(obj)) => obj,
+        Ok(_) => return Err(QmsError::parse_error("SBOM is not a JSON object")),
+        Err(e) => return Err(QmsError::parse_error(&format!("SBOM is not valid JSON: {e}"))),
+    };
+
+    let format = match format {
+        Some(format) => format,
+        None if text(&document, "bomFormat").eq_ignore_ascii_case("CycloneDX") => SbomFormat::CycloneDx,
+        None if text(&document, "spdxVersion").starts_with("SPDX-") => SbomFormat::Spdx,
+        None => {
+            return Err(QmsError::validation_error(
+                "Unrecognized SBOM: expected CycloneDX JSON (bomFormat) or SPDX JSON (spdxVersion)",
+            ))
+        }
+    };
+
+    match format {
+        SbomFormat::CycloneDx => Ok(parse_cyclonedx(&document)),
+        SbomFormat::Spdx => Ok(parse_spdx(&document)),
+    }
+}
+
+/// String field of an object, empty when absent
+fn text(obj: &JsonObject, field: &str) -> String {
+    obj.get(field).and_then(JsonValue::as_string).map(|s| s.trim().to_string()).unwrap_or_default()
+}
+
+fn object<'a>(obj: &'a JsonObject, field: &str) -> Option<&'a JsonObject> {
+    match obj.get(field) {
+        Some(JsonValue::Object(obj)) => Some(obj),
+        _ => None,
+    }
+}
+
+fn objects<'a>(obj: &'a JsonObject, field: &str) -> impl Iterator<Item = &'a JsonObject> {
+    let values = match obj.get(field) {
+        Some(JsonValue::Array(values)) => values.as_slice(),
+        _ => &[],
+    };
+    values.iter().filter_map(|value| match value {
+        JsonValue::Object(obj) => Some(obj),
+        _ => None,
+    })
+}
+
+fn non_empty(value: String) -> Option<String> {
+    (!value.is_empty()).then_some(value)
+}
+
+fn parse_cyclonedx(document: &JsonObject) -> ParsedSbom {
+    let mut components = Vec::new();
+    let mut pending: Vec<&JsonObject> = objects(document, "components").collect();
+    pending.reverse();
+    while let Some(component) = pending.pop() {
+        let nested: Vec<&JsonObject> = objects(component, "components").collect();
+        pending.extend(nested.into_iter().rev());
+
+        let name = match text(component, "group") {
+            group if group.is_empty() => text(component, "name"),
+            group => format!("{group}/{}", text(component, "name")),
+        };
+        if name.is_empty() {
+            continue;
+        }
+        let supplier = object(component, "supplier")
+            .map(|supplier| text(supplier, "name"))
+            .and_then(non_empty)
+            .or_else(|| non_empty(text(component, "publisher")))
+            .unwrap_or_else(|| text(component, "author"));
+        let licenses: Vec<String> = objects(component, "licenses")
+            .filter_map(|entry| {
+                non_empty(text(entry, "expression")).or_else(|| {
+                    object(entry, "license").and_then(|license| {
+                        non_empty(text(license, "id")).or_else(|| non_empty(text(license, "name")))
+                    })
+                })
+            })
+            .collect();
+
+        components.push(SbomComponent {
+            bom_ref: non_empty(text(component, "bom-ref")).unwrap_or_else(|| name.clone()),
+            name,
+            version: text(component, "version"),
+            supplier,
+            purl: non_empty(text(component, "purl")),
+            license: licenses.join(" AND "),
+            description: text(component, "description"),
+        });
+    }
+
+    let vulnerabilities = objects(document, "vulnerabilities")
+        .filter_map(|vulnerability| {
+            let id = non_empty(text(vulnerability, "id"))?;
+            Some(SbomVulnerability {
+                id,
+                description: non_empty(text(vulnerability, "description"))
+                    .unwrap_or_else(|| text(vulnerability, "detail")),
+                severity: objects(vulnerability, "ratings")
+                    .map(|rating| text(rating, "severity"))
+                    .find(|severity| !severity.is_empty())
+                    .unwrap_or_default(),
+                source: object(vulnerability, "source").map(|source| text(source, "name")).unwrap_or_default(),
+                affects: objects(vulnerability, "affects")
+                    .map(|affected| text(affected, "ref"))
+                    .filter(|reference| !reference.is_empty())
+                    .collect(),
+            })
+        })
+        .collect();
+
+    ParsedSbom {
+        format: SbomFormat::CycloneDx,
+        components,
+        vulnerabilities,
+    }
+}
+
+/// SPDX value with NOASSERTION and NONE treated as absent
+fn spdx_text(obj: &JsonObject, field: &str) -> Option<String> {
+    non_empty(text(obj, field)).filter(|value| value != "NOASSERTION" && value != "NONE")
+}
+
+fn parse_spdx(document: &JsonObject) -> ParsedSbom {
+    // The packages the document describes are the product itself, not SOUP
+    let mut described: HashSet<String> = match document.get("documentDescribes") {
+        Some(JsonValue::Array(values)) => values.iter().filter_map(|value| value.as_string().cloned()).collect(),
+        _ => HashSet::new(),
+    };
+    let document_id = non_empty(text(document, "SPDXID")).unwrap_or_else(|| "SPDXRef-DOCUMENT".to_string());
+    described.extend(
+        objects(document, "relationships")
+            .filter(|relationship| {
+                text(relationship, "spdxElementId") == document_id && text(relationship, "relationshipType") == "DESCRIBES"
+            })
+            .map(|relationship| text(relationship, "relatedSpdxElement")),
+    );
+
+    let components = objects(document, "packages")
+        .filter(|package| !described.contains(&text(package, "SPDXID")))
+        .filter_map(|package| {
+            let name = non_empty(text(package, "name"))?;
+            let supplier = spdx_text(package, "supplier")
+                .or_else(|| spdx_text(package, "originator"))
+                .map(|supplier| match supplier.split_once(':') {
+                    Some(("Organization" | "Person" | "Tool", rest)) => rest.trim().to_string(),
+                    _ => supplier,
+                })
+                .unwrap_or_default();
+            let purl = objects(package, "externalRefs")
+                .find(|reference| text(reference, "referenceType") == "purl")
+                .and_then(|reference| non_empty(text(reference, "referenceLocator")));
+
+            Some(SbomComponent {
+                bom_ref: non_empty(text(package, "SPDXID")).unwrap_or_else(|| name.clone()),
+                name,
+                version: spdx_text(package, "versionInfo").unwrap_or_default(),
+                supplier,
+                purl,
+                license: spdx_text(package, "licenseConcluded")
+                    .or_else(|| spdx_text(package, "licenseDeclared"))
+                    .unwrap_or_default(),
+                description: non_empty(text(package, "description")).unwrap_or_else(|| text(package, "summary")),
+            })
+        })
+        .collect();
+
+    ParsedSbom {
+        format: SbomFormat::Spdx,
+        components,
+        vulnerabilities: Vec::new(),
+    }
+}
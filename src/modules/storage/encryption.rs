@@ -0,0 +1,1215 @@
+/// Encryption at Rest
+///
+/// Project data (every file under the project root outside [`CLEAR_PATHS`],
+/// and the rows of the SQLite database) is sealed with ChaCha20-Poly1305 under a
+/// random 256-bit project data key. The data key never reaches the disk in
+/// clear: `config/encryption.json` holds it wrapped by one or more
+/// key-encryption keys, derived from an administrator passphrase (Argon2) or
+/// taken from a key file.
+///
+/// Sealed content is one text line, `qms-enc:v1:<key_id>:<hex(nonce || ciphertext)>`,
+/// so a whole JSON file and a single audit log line use the same format.
+/// Binary files (attachment objects, compressed archives) are sealed whole as
+/// `qms-enc:bin:v1:<key_id>:` followed by the raw nonce and ciphertext, and go
+/// through [`read`] and [`write_bytes`]. The associated data of every seal is
+/// the key id and the normalised project-relative path of the file, so sealed
+/// content only opens at the path it was written to: [`copy`] and [`rename`]
+/// re-seal content for its new location.
+/// Readers go through [`read_to_string`], [`read`] or [`open_line`] and writers
+/// through [`seal`], [`write`] or [`atomic_write`]; all of them pass content
+/// through unchanged outside an encrypted project, which keeps encryption
+/// transparent to the managers. Inside one, readers reject content that is not
+/// sealed.
+///
+/// Unlocked keys are held per project for the life of the process. A locked
+/// project is unlocked on first use from `QMS_PROJECT_PASSPHRASE` or
+/// `QMS_PROJECT_KEY_FILE`, or explicitly with [`unlock`].
+
+use crate::prelude::*;
+use crate::json_utils::JsonValue;
+use crate::modules::storage::sqlite_storage::SqliteDatabase;
+use crate::modules::storage::storage_interfaces::StorageConfig;
+use crate::utils::{current_timestamp, decode_hex, encode_hex};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Key ring file, relative to the project root
+pub const KEYRING_FILE: &str = "config/encryption.json";
+
+/// Marker that starts every sealed line
+pub const SEALED_PREFIX: &str = "qms-enc:v1:";
+
+/// Marker that starts every sealed binary file
+pub const SEALED_BINARY_PREFIX: &str = "qms-enc:bin:v1:";
+
+/// Environment variable holding the passphrase used to unlock a project
+pub const PASSPHRASE_ENV: &str = "QMS_PROJECT_PASSPHRASE";
+
+/// Environment variable naming the key file used to unlock a project
+pub const KEY_FILE_ENV: &str = "QMS_PROJECT_KEY_FILE";
+
+/// Project paths that stay in clear; everything else under the project root is encrypted
+///
+/// The project file and `config` (settings and the key ring) are read to find and
+/// unlock the project, templates are shared, and exports and reports are written
+/// for readers outside the QMS. The application log (`logs`) and lock files
+/// (`*.lock`) are runtime state and also stay in clear.
+pub const CLEAR_PATHS: &[&str] = &["project.json", "config", "documents/templates", "audit/exports", "reports", "logs"];
+
+/// Directories holding binary files, sealed with [`SEALED_BINARY_PREFIX`] even when
+/// their content is text; other files are sealed as binary only when they are not UTF-8
+const BINARY_DIRS: &[&str] = &["attachments/objects"];
+
+/// Shortest passphrase accepted for wrapping a project key
+pub const MIN_PASSPHRASE_LENGTH: usize = 12;
+
+const NONCE_LENGTH: usize = 12;
+
+/// Unlocked data keys by canonical project root, current key first
+static UNLOCKED: OnceLock<Mutex<HashMap<PathBuf, Vec<ProjectKey>>>> = OnceLock::new();
+
+/// Secret that unlocks a project's data key
+#[derive(Clone)]
+pub enum KeySource {
+    Passphrase(String),
+    KeyFile(PathBuf),
+}
+
+impl KeySource {
+    /// Unlock source configured in the environment, passphrase first
+    pub fn from_env() -> Option<Self> {
+        if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+            return Some(KeySource::Passphrase(passphrase));
+        }
+        std::env::var(KEY_FILE_ENV).ok().map(|path| KeySource::KeyFile(PathBuf::from(path)))
+    }
+
+    /// Write a new random key file; fails if the file already exists
+    pub fn generate_key_file(path: &Path) -> QmsResult<Self> {
+        if path.exists() {
+            return Err(QmsError::already_exists(&format!("Key file already exists: {}", path.display())));
+        }
+        let secret: [u8; 32] = rand::thread_rng().gen();
+        crate::fs_utils::atomic_write(path, &format!("{}\n", encode_hex(&secret)))?;
+        crate::fs_utils::set_secure_file_permissions(path)?;
+        Ok(KeySource::KeyFile(path.to_path_buf()))
+    }
+
+    const fn method(&self) -> &'static str {
+        match self {
+            KeySource::Passphrase(_) => "passphrase",
+            KeySource::KeyFile(_) => "key_file",
+        }
+    }
+
+    /// Key-encryption key for this source and salt
+    fn derive_kek(&self, salt: &[u8]) -> QmsResult<[u8; 32]> {
+        let material = match self {
+            KeySource::Passphrase(passphrase) => argon2::hash_raw(passphrase.as_bytes(), salt, &argon2::Config::default())
+                .map_err(|e| QmsError::domain_error(&format!("Key derivation failed: {e}")))?,
+            KeySource::KeyFile(path) => {
+                let content = fs::read(path).map_err(|e| {
+                    QmsError::io_error(&format!("Failed to read key file {}: {e}", path.display()))
+                })?;
+                let mut hasher = Sha256::new();
+                hasher.update(salt);
+                hasher.update(&content);
+                hasher.finalize().to_vec()
+            }
+        };
+        let mut kek = [0u8; 32];
+        kek.copy_from_slice(&material[..32]);
+        Ok(kek)
+    }
+
+    fn validate(&self) -> QmsResult<()> {
+        match self {
+            KeySource::Passphrase(passphrase) if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH => {
+                Err(QmsError::validation_error(&format!(
+                    "Passphrase must be at least {MIN_PASSPHRASE_LENGTH} characters"
+                )))
+            }
+            KeySource::KeyFile(path) if !path.is_file() => {
+                Err(QmsError::not_found(&format!("Key file not found: {}", path.display())))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Project data key
+#[derive(Clone)]
+pub struct ProjectKey {
+    key_id: String,
+    key: [u8; 32],
+}
+
+impl ProjectKey {
+    fn generate() -> Self {
+        let key: [u8; 32] = rand::thread_rng().gen();
+        let key_id: [u8; 8] = rand::thread_rng().gen();
+        Self { key_id: encode_hex(&key_id), key }
+    }
+
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// Seal `plaintext` stored at project-relative `location` into a single `qms-enc:v1:` line
+    pub fn seal(&self, location: &str, plaintext: &str) -> QmsResult<String> {
+        let header = format!("{SEALED_PREFIX}{}", self.key_id);
+        let sealed = aead_seal(&self.key, &associated_data(&header, location), plaintext.as_bytes())?;
+        Ok(format!("{header}:{}", encode_hex(&sealed)))
+    }
+
+    /// Open a line produced by [`ProjectKey::seal`] for the same `location`
+    pub fn open(&self, location: &str, sealed: &str) -> QmsResult<String> {
+        let (key_id, payload) = split_sealed(sealed)?;
+        if key_id != self.key_id {
+            return Err(QmsError::validation_error(&format!(
+                "Content is sealed with key {key_id}, not {}", self.key_id
+            )));
+        }
+        let header = format!("{SEALED_PREFIX}{key_id}");
+        let plaintext = aead_open(&self.key, &associated_data(&header, location), &decode_hex(payload)?)?;
+        String::from_utf8(plaintext).map_err(|_| QmsError::parse_error("Decrypted content is not UTF-8"))
+    }
+
+    /// Seal binary `plaintext` stored at `location` as `qms-enc:bin:v1:<key_id>:` and the raw nonce and ciphertext
+    pub fn seal_bytes(&self, location: &str, plaintext: &[u8]) -> QmsResult<Vec<u8>> {
+        let header = format!("{SEALED_BINARY_PREFIX}{}", self.key_id);
+        let mut sealed = format!("{header}:").into_bytes();
+        sealed.extend(aead_seal(&self.key, &associated_data(&header, location), plaintext)?);
+        Ok(sealed)
+    }
+
+    /// Open content produced by [`ProjectKey::seal_bytes`] for the same `location`
+    pub fn open_bytes(&self, location: &str, sealed: &[u8]) -> QmsResult<Vec<u8>> {
+        let (key_id, payload) = split_sealed_bytes(sealed)?;
+        if key_id != self.key_id {
+            return Err(QmsError::validation_error(&format!(
+                "Content is sealed with key {key_id}, not {}", self.key_id
+            )));
+        }
+        let header = format!("{SEALED_BINARY_PREFIX}{key_id}");
+        aead_open(&self.key, &associated_data(&header, location), payload)
+    }
+}
+
+/// Data key wrapped under one key-encryption key
+#[derive(Debug, Clone)]
+struct WrappedKey {
+    key_id: String,
+    method: String,
+    salt: String,       // hex
+    sealed_key: String, // hex(nonce || ciphertext), key id as associated data
+}
+
+impl WrappedKey {
+    fn wrap(key: &ProjectKey, source: &KeySource) -> QmsResult<Self> {
+        let salt: [u8; 16] = rand::thread_rng().gen();
+        let kek = source.derive_kek(&salt)?;
+        let sealed = aead_seal(&kek, key.key_id.as_bytes(), &key.key)?;
+        Ok(Self {
+            key_id: key.key_id.clone(),
+            method: source.method().to_string(),
+            salt: encode_hex(&salt),
+            sealed_key: encode_hex(&sealed),
+        })
+    }
+
+    fn unwrap(&self, source: &KeySource) -> Option<ProjectKey> {
+        if self.method != source.method() {
+            return None;
+        }
+        let kek = source.derive_kek(&decode_hex(&self.salt).ok()?).ok()?;
+        let key = aead_open(&kek, self.key_id.as_bytes(), &decode_hex(&self.sealed_key).ok()?).ok()?;
+        let key: [u8; 32] = key.try_into().ok()?;
+        Some(ProjectKey { key_id: self.key_id.clone(), key })
+    }
+}
+
+/// Contents of `config/encryption.json`
+#[derive(Debug, Clone)]
+pub struct Keyring {
+    /// Key used for new writes
+    pub key_id: String,
+    pub created_at: u64,
+    pub rekeyed_at: Option<u64>,
+    wraps: Vec<WrappedKey>,
+}
+
+impl Keyring {
+    pub fn load(project_path: &Path) -> QmsResult<Self> {
+        let path = project_path.join(KEYRING_FILE);
+        if !path.exists() {
+            return Err(QmsError::not_found("Project data is not encrypted"));
+        }
+        Self::from_json(&fs::read_to_string(&path)?)
+    }
+
+    fn save(&self, project_path: &Path) -> QmsResult<()> {
+        let path = project_path.join(KEYRING_FILE);
+        crate::fs_utils::atomic_write(&path, &self.to_json())?;
+        crate::fs_utils::set_secure_file_permissions(&path)
+    }
+
+    /// Unlock methods available for the current key
+    pub fn methods(&self) -> Vec<String> {
+        let mut methods: Vec<String> = self
+            .wraps
+            .iter()
+            .filter(|w| w.key_id == self.key_id)
+            .map(|w| w.method.clone())
+            .collect();
+        methods.dedup();
+        methods
+    }
+
+    /// Every key in the ring that `source` unwraps, current key first
+    fn unlock(&self, source: &KeySource) -> QmsResult<Vec<ProjectKey>> {
+        let mut keys: Vec<ProjectKey> = Vec::new();
+        for wrap in &self.wraps {
+            if keys.iter().any(|k| k.key_id == wrap.key_id) {
+                continue;
+            }
+            if let Some(key) = wrap.unwrap(source) {
+                keys.push(key);
+            }
+        }
+        keys.sort_by_key(|k| k.key_id != self.key_id);
+        match keys.first() {
+            Some(key) if key.key_id == self.key_id => Ok(keys),
+            _ => Err(QmsError::authentication_error("Wrong passphrase or key file for this project")),
+        }
+    }
+
+    fn to_json(&self) -> String {
+        let wraps: Vec<JsonValue> = self
+            .wraps
+            .iter()
+            .map(|w| {
+                let mut obj = HashMap::new();
+                obj.insert("key_id".to_string(), JsonValue::String(w.key_id.clone()));
+                obj.insert("method".to_string(), JsonValue::String(w.method.clone()));
+                obj.insert("salt".to_string(), JsonValue::String(w.salt.clone()));
+                obj.insert("sealed_key".to_string(), JsonValue::String(w.sealed_key.clone()));
+                JsonValue::Object(obj)
+            })
+            .collect();
+
+        let mut obj = HashMap::new();
+        obj.insert("version".to_string(), JsonValue::String("1.0".to_string()));
+        obj.insert("cipher".to_string(), JsonValue::String("chacha20-poly1305".to_string()));
+        obj.insert("key_id".to_string(), JsonValue::String(self.key_id.clone()));
+        obj.insert("created_at".to_string(), JsonValue::Number(self.created_at as f64));
+        if let Some(rekeyed_at) = self.rekeyed_at {
+            obj.insert("rekeyed_at".to_string(), JsonValue::Number(rekeyed_at as f64));
+        }
+        obj.insert("wraps".to_string(), JsonValue::Array(wraps));
+        JsonValue::Object(obj).json_to_string()
+    }
+
+    fn from_json(json: &str) -> QmsResult<Self> {
+        let JsonValue::Object(obj) = JsonValue::parse(json)? else {
+            return Err(QmsError::parse_error("Key ring must be a JSON object"));
+        };
+        let wraps = match obj.get("wraps") {
+            Some(JsonValue::Array(items)) => items
+                .iter()
+                .map(|item| match item {
+                    JsonValue::Object(w) => Ok(WrappedKey {
+                        key_id: string_field(w, "key_id")?,
+                        method: string_field(w, "method")?,
+                        salt: string_field(w, "salt")?,
+                        sealed_key: string_field(w, "sealed_key")?,
+                    }),
+                    _ => Err(QmsError::parse_error("Invalid key ring entry")),
+                })
+                .collect::<QmsResult<Vec<_>>>()?,
+            _ => return Err(QmsError::parse_error("Missing or invalid field 'wraps'")),
+        };
+        Ok(Self {
+            key_id: string_field(&obj, "key_id")?,
+            created_at: obj.get("created_at").and_then(JsonValue::as_number).unwrap_or(0.0) as u64,
+            rekeyed_at: obj.get("rekeyed_at").and_then(JsonValue::as_number).map(|n| n as u64),
+            wraps,
+        })
+    }
+}
+
+/// Root of the encrypted project containing `path`, if any
+pub fn project_root(path: &Path) -> Option<PathBuf> {
+    locate(path).map(|(root, _)| root)
+}
+
+/// Root of the encrypted project containing `path` and `path` relative to it
+///
+/// `.` and `..` are resolved lexically, so every spelling of a path inside the
+/// project has the same relative path.
+fn locate(path: &Path) -> Option<(PathBuf, PathBuf)> {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().ok()?.join(path)
+    };
+    let mut normalized = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    let dir = normalized.ancestors().find(|dir| dir.join(KEYRING_FILE).is_file())?;
+    let relative = normalized.strip_prefix(dir).ok()?.to_path_buf();
+    Some((fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf()), relative))
+}
+
+/// Whether the project keeps its data encrypted
+pub fn is_encrypted(project_path: &Path) -> bool {
+    project_path.join(KEYRING_FILE).is_file()
+}
+
+/// Whether the project's data key is available to this process
+pub fn is_unlocked(project_path: &Path) -> bool {
+    project_root(project_path).is_some_and(|root| unlocked_keys(&root).is_some())
+}
+
+/// Unlock the project's data key for the rest of the process
+pub fn unlock(project_path: &Path, source: &KeySource) -> QmsResult<()> {
+    let keys = Keyring::load(project_path)?.unlock(source)?;
+    register(project_path, keys);
+    Ok(())
+}
+
+/// Forget the project's unlocked key
+pub fn lock(project_path: &Path) {
+    let root = fs::canonicalize(project_path).unwrap_or_else(|_| project_path.to_path_buf());
+    if let Ok(mut unlocked) = registry().lock() {
+        unlocked.remove(&root);
+    }
+}
+
+/// Encrypt a project: create its data key, wrap it under `sources` and seal existing data
+///
+/// Returns the number of files and database rows sealed.
+pub fn enable(project_path: &Path, sources: &[KeySource]) -> QmsResult<usize> {
+    if is_encrypted(project_path) {
+        return Err(QmsError::already_exists("Project data is already encrypted"));
+    }
+    let key = ProjectKey::generate();
+    let keyring = Keyring {
+        key_id: key.key_id.clone(),
+        created_at: current_timestamp(),
+        rekeyed_at: None,
+        wraps: wrap_all(&key, sources)?,
+    };
+    keyring.save(project_path)?;
+    register(project_path, vec![key.clone()]);
+
+    reseal_project(
+        project_path,
+        |content, line_oriented, location| reseal_content(content, &[], Some(&key), line_oriented, location),
+        |data, location| reseal_bytes(data, &[], Some(&key), location),
+    )
+}
+
+/// Replace the project's data key and the secrets wrapping it
+///
+/// All data is re-encrypted under a new key. Until that finishes the ring also
+/// carries the old key wrapped under the new secrets, so an interrupted rekey
+/// can be run again with the new passphrase or key file.
+pub fn rekey(project_path: &Path, current: &KeySource, new_sources: &[KeySource]) -> QmsResult<usize> {
+    let keyring = Keyring::load(project_path)?;
+    let old_keys = keyring.unlock(current)?;
+    let new_key = ProjectKey::generate();
+
+    let mut transition = wrap_all(&new_key, new_sources)?;
+    for old_key in &old_keys {
+        transition.extend(wrap_all(old_key, new_sources)?);
+    }
+    Keyring {
+        key_id: new_key.key_id.clone(),
+        created_at: keyring.created_at,
+        rekeyed_at: Some(current_timestamp()),
+        wraps: transition,
+    }
+    .save(project_path)?;
+    let mut all_keys = vec![new_key.clone()];
+    all_keys.extend(old_keys.iter().cloned());
+    register(project_path, all_keys);
+
+    let count = reseal_project(
+        project_path,
+        |content, line_oriented, location| {
+            reseal_content(content, &old_keys, Some(&new_key), line_oriented, location)
+        },
+        |data, location| reseal_bytes(data, &old_keys, Some(&new_key), location),
+    )?;
+
+    Keyring {
+        key_id: new_key.key_id.clone(),
+        created_at: keyring.created_at,
+        rekeyed_at: Some(current_timestamp()),
+        wraps: wrap_all(&new_key, new_sources)?,
+    }
+    .save(project_path)?;
+    register(project_path, vec![new_key]);
+    Ok(count)
+}
+
+/// Decrypt a project in place and remove its key ring
+pub fn disable(project_path: &Path, source: &KeySource) -> QmsResult<usize> {
+    let keys = Keyring::load(project_path)?.unlock(source)?;
+    let count = reseal_project(
+        project_path,
+        |content, _, location| open_content(content, &keys, location),
+        |data, location| open_bytes_with(data, &keys, location),
+    )?;
+    fs::remove_file(project_path.join(KEYRING_FILE))?;
+    lock(project_path);
+    Ok(count)
+}
+
+/// Write a decrypted copy of the whole project to `destination`
+///
+/// Needs only the project directory and its passphrase or key file, so it works
+/// for disaster recovery on a machine that never ran the project. The copy has
+/// no key ring and opens as an ordinary unencrypted project.
+pub fn export_plaintext(project_path: &Path, source: &KeySource, destination: &Path) -> QmsResult<usize> {
+    let keys = Keyring::load(project_path)?.unlock(source)?;
+    if destination.exists() && fs::read_dir(destination)?.next().is_some() {
+        return Err(QmsError::already_exists(&format!(
+            "Export destination is not empty: {}", destination.display()
+        )));
+    }
+
+    let mut decrypted = 0;
+    for file in walk_files(project_path)? {
+        let relative = file.strip_prefix(project_path).unwrap_or(&file);
+        if relative == Path::new(KEYRING_FILE) {
+            continue;
+        }
+        let target = destination.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let location = location_of(relative);
+        let data = fs::read(&file)?;
+        if data.starts_with(SEALED_BINARY_PREFIX.as_bytes()) {
+            fs::write(&target, open_bytes_with(&data, &keys, &location)?)?;
+            decrypted += 1;
+            continue;
+        }
+        match String::from_utf8(data) {
+            Ok(content) if content.contains(SEALED_PREFIX) => {
+                fs::write(&target, open_content(&content, &keys, &location)?)?;
+                decrypted += 1;
+            }
+            _ => {
+                fs::copy(&file, &target)?;
+            }
+        }
+    }
+
+    let config = StorageConfig::for_project(project_path)?;
+    if let Ok(relative) = config.database_path(project_path).strip_prefix(project_path) {
+        let database_copy = destination.join(relative);
+        let location = location_of(relative);
+        if database_copy.is_file() {
+            decrypted += SqliteDatabase::open(&database_copy, None)?
+                .rewrite_rows(|data| open_content(data, &keys, &location))?;
+        }
+    }
+    Ok(decrypted)
+}
+
+/// Read a file, decrypting sealed content
+///
+/// Drop-in for `std::fs::read_to_string`: files outside an encrypted project or
+/// in [`CLEAR_PATHS`] are returned unchanged. Inside an encrypted project every
+/// non-blank line must be sealed for this path.
+pub fn read_to_string<P: AsRef<Path>>(path: P) -> std::io::Result<String> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)?;
+    let Some(location) = sealed_location(path) else {
+        return Ok(content);
+    };
+    if content.trim().is_empty() {
+        return Ok(content);
+    }
+    let keys = keys_for(path).map_err(to_io_error)?.unwrap_or_default();
+    map_lines(&content, |line| open_sealed_line(line, &keys, &location, path)).map_err(to_io_error)
+}
+
+/// Read a binary file, decrypting sealed content
+///
+/// Drop-in for `std::fs::read`, with the same rules as [`read_to_string`]; text
+/// sealed line by line is returned as its plaintext bytes.
+pub fn read<P: AsRef<Path>>(path: P) -> std::io::Result<Vec<u8>> {
+    let path = path.as_ref();
+    let data = fs::read(path)?;
+    let Some(location) = sealed_location(path) else {
+        return Ok(data);
+    };
+    if data.is_empty() {
+        return Ok(data);
+    }
+    let keys = keys_for(path).map_err(to_io_error)?.unwrap_or_default();
+    if data.starts_with(SEALED_BINARY_PREFIX.as_bytes()) {
+        return open_bytes_with(&data, &keys, &location).map_err(to_io_error);
+    }
+    match String::from_utf8(data) {
+        Ok(content) => map_lines(&content, |line| open_sealed_line(line, &keys, &location, path))
+            .map(String::into_bytes)
+            .map_err(to_io_error),
+        Err(_) => Err(to_io_error(unsealed_error(path))),
+    }
+}
+
+/// Decrypt one line read from a file at `path`, leaving lines outside an encrypted project unchanged
+pub fn open_line(path: &Path, line: &str) -> QmsResult<String> {
+    let Some(location) = sealed_location(path) else {
+        return Ok(line.to_string());
+    };
+    if line.trim().is_empty() {
+        return Ok(line.to_string());
+    }
+    let keys = keys_for(path)?.unwrap_or_default();
+    open_sealed_line(line, &keys, &location, path)
+}
+
+/// Content to store at `path`: sealed inside an encrypted project, unchanged elsewhere
+///
+/// The seal is bound to `path`. Line-oriented logs (`.log`, `.jsonl`) are
+/// sealed line by line, anything else as a single line, so an entry can be
+/// appended to a log.
+pub fn seal(path: &Path, content: &str) -> QmsResult<String> {
+    let Some(location) = sealed_location(path) else {
+        return Ok(content.to_string());
+    };
+    match keys_for(path)? {
+        Some(keys) => seal_content(content, &keys[0], is_line_oriented(path), &location),
+        None => Ok(content.to_string()),
+    }
+}
+
+/// Binary content to store at `path`: sealed inside an encrypted project, unchanged elsewhere
+pub fn seal_bytes(path: &Path, contents: &[u8]) -> QmsResult<Vec<u8>> {
+    let Some(location) = sealed_location(path) else {
+        return Ok(contents.to_vec());
+    };
+    match keys_for(path)? {
+        Some(keys) => keys[0].seal_bytes(&location, contents),
+        None => Ok(contents.to_vec()),
+    }
+}
+
+/// Write a file, sealing it inside an encrypted project
+///
+/// Drop-in for `std::fs::write` with text contents.
+pub fn write<P: AsRef<Path>, C: AsRef<str>>(path: P, contents: C) -> std::io::Result<()> {
+    let path = path.as_ref();
+    let sealed = seal(path, contents.as_ref()).map_err(to_io_error)?;
+    fs::write(path, sealed)
+}
+
+/// Write a file atomically, sealing it inside an encrypted project
+///
+/// Drop-in for [`crate::fs_utils::atomic_write`].
+pub fn atomic_write(path: &Path, content: &str) -> QmsResult<()> {
+    crate::fs_utils::atomic_write(path, &seal(path, content)?)
+}
+
+/// Write a binary file, sealing it inside an encrypted project
+///
+/// Drop-in for `std::fs::write` with binary contents.
+pub fn write_bytes<P: AsRef<Path>>(path: P, contents: &[u8]) -> std::io::Result<()> {
+    let path = path.as_ref();
+    fs::write(path, seal_bytes(path, contents).map_err(to_io_error)?)
+}
+
+/// Copy a file, sealing the copy for its new path
+///
+/// Drop-in for `std::fs::copy`. Sealed content only opens at the path it was
+/// written to, so it is decrypted and sealed again rather than copied as is.
+pub fn copy<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> std::io::Result<u64> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    if !is_encrypted_path(from) && !is_encrypted_path(to) {
+        return fs::copy(from, to);
+    }
+    let plaintext = read(from)?;
+    let length = plaintext.len() as u64;
+    let binary = sealed_location(to).is_some_and(|location| is_binary_location(&location));
+    match String::from_utf8(plaintext) {
+        Ok(text) if !binary => write(to, text)?,
+        Ok(text) => write_bytes(to, text.as_bytes())?,
+        Err(error) => write_bytes(to, error.as_bytes())?,
+    }
+    Ok(length)
+}
+
+/// Move a file or directory, sealing moved content for its new path
+///
+/// Drop-in for `std::fs::rename`.
+pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> std::io::Result<()> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    if !is_encrypted_path(from) && !is_encrypted_path(to) {
+        return fs::rename(from, to);
+    }
+    if !from.is_dir() {
+        copy(from, to)?;
+        return fs::remove_file(from);
+    }
+    for file in walk_files(from).map_err(to_io_error)? {
+        let target = to.join(file.strip_prefix(from).unwrap_or(&file));
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        copy(&file, &target)?;
+    }
+    fs::remove_dir_all(from)
+}
+
+/// Whether data stored at `path` belongs to an encrypted project
+pub fn is_encrypted_path(path: &Path) -> bool {
+    project_root(path).is_some()
+}
+
+/// Project-relative location of `path` if it is encrypted in its project
+fn sealed_location(path: &Path) -> Option<String> {
+    let (_, relative) = locate(path)?;
+    is_data_file(&relative).then(|| location_of(&relative))
+}
+
+/// Normalised project-relative path bound into the associated data of a seal
+fn location_of(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Whether a project-relative path holds encrypted project data
+fn is_data_file(relative: &Path) -> bool {
+    !CLEAR_PATHS.iter().any(|clear| relative.starts_with(clear))
+        && relative.extension().and_then(|e| e.to_str()) != Some("lock")
+}
+
+/// Whether content at `location` is always sealed as binary
+fn is_binary_location(location: &str) -> bool {
+    BINARY_DIRS.iter().any(|dir| Path::new(location).starts_with(dir))
+}
+
+/// Whether the file at `path` is a log appended one line at a time
+fn is_line_oriented(path: &Path) -> bool {
+    matches!(path.extension().and_then(|e| e.to_str()), Some("log" | "jsonl"))
+}
+
+/// Keys for the encrypted project containing `path`, unlocking from the environment if needed
+fn keys_for(path: &Path) -> QmsResult<Option<Vec<ProjectKey>>> {
+    let Some(root) = project_root(path) else {
+        return Ok(None);
+    };
+    if let Some(keys) = unlocked_keys(&root) {
+        return Ok(Some(keys));
+    }
+    match KeySource::from_env() {
+        Some(source) => {
+            unlock(&root, &source)?;
+            Ok(unlocked_keys(&root))
+        }
+        None => Err(QmsError::permission_error(&format!(
+            "Project data in {} is encrypted and locked; set {PASSPHRASE_ENV} or {KEY_FILE_ENV}",
+            root.display()
+        ))),
+    }
+}
+
+fn registry() -> &'static Mutex<HashMap<PathBuf, Vec<ProjectKey>>> {
+    UNLOCKED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn unlocked_keys(root: &Path) -> Option<Vec<ProjectKey>> {
+    registry().lock().ok()?.get(root).cloned()
+}
+
+fn register(project_path: &Path, keys: Vec<ProjectKey>) {
+    let root = fs::canonicalize(project_path).unwrap_or_else(|_| project_path.to_path_buf());
+    if let Ok(mut unlocked) = registry().lock() {
+        unlocked.insert(root, keys);
+    }
+}
+
+fn wrap_all(key: &ProjectKey, sources: &[KeySource]) -> QmsResult<Vec<WrappedKey>> {
+    if sources.is_empty() {
+        return Err(QmsError::validation_error("A passphrase or key file is required"));
+    }
+    sources
+        .iter()
+        .map(|source| {
+            source.validate()?;
+            WrappedKey::wrap(key, source)
+        })
+        .collect()
+}
+
+/// Open every sealed line of `content` stored at `location` with `keys`, leaving plain lines unchanged
+fn open_content(content: &str, keys: &[ProjectKey], location: &str) -> QmsResult<String> {
+    map_lines(content, |line| open_with(line, keys, location))
+}
+
+/// Seal plaintext `content` for `location`, line by line for line-oriented logs
+fn seal_content(content: &str, key: &ProjectKey, line_oriented: bool, location: &str) -> QmsResult<String> {
+    if !line_oriented {
+        return key.seal(location, content);
+    }
+    map_lines(content, |line| {
+        if line.trim().is_empty() {
+            Ok(line.to_string())
+        } else {
+            key.seal(location, line)
+        }
+    })
+}
+
+/// Open `content` with `keys`, then seal it with `seal_with` (or leave it in
+/// clear when `None`)
+///
+/// Line-oriented logs are sealed line by line so they stay appendable; any
+/// other file is sealed as a whole.
+fn reseal_content(
+    content: &str,
+    keys: &[ProjectKey],
+    seal_with: Option<&ProjectKey>,
+    line_oriented: bool,
+    location: &str,
+) -> QmsResult<String> {
+    let plaintext = open_content(content, keys, location)?;
+    match seal_with {
+        Some(key) => seal_content(&plaintext, key, line_oriented, location),
+        None => Ok(plaintext),
+    }
+}
+
+/// Binary counterpart of [`reseal_content`]
+fn reseal_bytes(data: &[u8], keys: &[ProjectKey], seal_with: Option<&ProjectKey>, location: &str) -> QmsResult<Vec<u8>> {
+    let plaintext = open_bytes_with(data, keys, location)?;
+    match seal_with {
+        Some(key) => key.seal_bytes(location, &plaintext),
+        None => Ok(plaintext),
+    }
+}
+
+/// Open sealed binary content stored at `location` with `keys`, leaving other content unchanged
+fn open_bytes_with(data: &[u8], keys: &[ProjectKey], location: &str) -> QmsResult<Vec<u8>> {
+    if !data.starts_with(SEALED_BINARY_PREFIX.as_bytes()) {
+        return Ok(data.to_vec());
+    }
+    let (key_id, _) = split_sealed_bytes(data)?;
+    match keys.iter().find(|key| key.key_id == key_id) {
+        Some(key) => key.open_bytes(location, data),
+        None => Err(QmsError::permission_error(&format!(
+            "Content is sealed with unknown project key {key_id}"
+        ))),
+    }
+}
+
+fn map_lines(content: &str, f: impl Fn(&str) -> QmsResult<String>) -> QmsResult<String> {
+    let mut output = String::with_capacity(content.len());
+    for line in content.lines() {
+        output.push_str(&f(line)?);
+        output.push('\n');
+    }
+    if !content.ends_with('\n') {
+        output.pop();
+    }
+    Ok(output)
+}
+
+fn open_with(line: &str, keys: &[ProjectKey], location: &str) -> QmsResult<String> {
+    if !line.starts_with(SEALED_PREFIX) {
+        return Ok(line.to_string());
+    }
+    let (key_id, _) = split_sealed(line)?;
+    match keys.iter().find(|key| key.key_id == key_id) {
+        Some(key) => key.open(location, line),
+        None => Err(QmsError::permission_error(&format!(
+            "Content is sealed with unknown project key {key_id}"
+        ))),
+    }
+}
+
+/// Open one line of the encrypted file at `path`, rejecting lines that are not sealed
+fn open_sealed_line(line: &str, keys: &[ProjectKey], location: &str, path: &Path) -> QmsResult<String> {
+    if line.trim().is_empty() {
+        return Ok(line.to_string());
+    }
+    if !line.starts_with(SEALED_PREFIX) {
+        return Err(unsealed_error(path));
+    }
+    open_with(line, keys, location)
+}
+
+fn unsealed_error(path: &Path) -> QmsError {
+    QmsError::validation_error(&format!(
+        "{} is in an encrypted project but is not sealed; it may have been replaced outside the QMS",
+        path.display()
+    ))
+}
+
+/// Rewrite every data file and database row of the project
+fn reseal_project(
+    project_path: &Path,
+    transform: impl Fn(&str, bool, &str) -> QmsResult<String>,
+    transform_bytes: impl Fn(&[u8], &str) -> QmsResult<Vec<u8>>,
+) -> QmsResult<usize> {
+    let config = StorageConfig::for_project(project_path)?;
+    let database_path = config.database_path(project_path);
+    // The database file and its journals; its rows are sealed one by one below
+    let database_prefix = database_path.to_string_lossy().into_owned();
+
+    let mut count = 0;
+    for file in walk_files(project_path)? {
+        let relative = file.strip_prefix(project_path).unwrap_or(&file);
+        if !is_data_file(relative) || file.to_string_lossy().starts_with(&database_prefix) {
+            continue;
+        }
+        let location = location_of(relative);
+        let data = fs::read(&file)?;
+        let text = match std::str::from_utf8(&data) {
+            Ok(text) if !data.starts_with(SEALED_BINARY_PREFIX.as_bytes()) && !is_binary_location(&location) => text,
+            _ => {
+                let resealed = transform_bytes(&data, &location)?;
+                if resealed != data {
+                    let temp_path = file.with_extension("reseal-tmp");
+                    fs::write(&temp_path, &resealed)?;
+                    fs::rename(&temp_path, &file)?;
+                    count += 1;
+                }
+                continue;
+            }
+        };
+        let resealed = transform(text, is_line_oriented(&file), &location)?;
+        if resealed != text {
+            crate::fs_utils::atomic_write(&file, &resealed)?;
+            count += 1;
+        }
+    }
+
+    if let Some(location) = sealed_location(&database_path).filter(|_| database_path.is_file()) {
+        count += SqliteDatabase::open(&database_path, config.timeout_seconds)?
+            .rewrite_rows(|data| transform(data, false, &location))?;
+    }
+    Ok(count)
+}
+
+fn walk_files(dir: &Path) -> QmsResult<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    if !dir.is_dir() {
+        return Ok(files);
+    }
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in fs::read_dir(&current)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.is_file() {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Associated data of a seal: its header and the project-relative path it is stored at
+fn associated_data(header: &str, location: &str) -> Vec<u8> {
+    format!("{header}\n{location}").into_bytes()
+}
+
+fn aead_seal(key: &[u8; 32], associated_data: &[u8], plaintext: &[u8]) -> QmsResult<Vec<u8>> {
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+    let nonce: [u8; NONCE_LENGTH] = rand::thread_rng().gen();
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: plaintext, aad: associated_data })
+        .map_err(|_| QmsError::domain_error("Encryption failed"))?;
+    let mut sealed = nonce.to_vec();
+    sealed.extend(ciphertext);
+    Ok(sealed)
+}
+
+fn aead_open(key: &[u8; 32], associated_data: &[u8], sealed: &[u8]) -> QmsResult<Vec<u8>> {
+    if sealed.len() < NONCE_LENGTH {
+        return Err(QmsError::validation_error("Sealed content is truncated"));
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LENGTH);
+    ChaCha20Poly1305::new(Key::from_slice(key))
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: associated_data })
+        .map_err(|_| QmsError::validation_error("Sealed content failed authentication (wrong key or tampered data)"))
+}
+
+/// Split `qms-enc:v1:<key_id>:<payload>` into key id and payload
+fn split_sealed(sealed: &str) -> QmsResult<(&str, &str)> {
+    sealed
+        .trim_end()
+        .strip_prefix(SEALED_PREFIX)
+        .and_then(|rest| rest.split_once(':'))
+        .ok_or_else(|| QmsError::parse_error("Malformed sealed content"))
+}
+
+/// Split `qms-enc:bin:v1:<key_id>:<nonce || ciphertext>` into key id and payload
+fn split_sealed_bytes(sealed: &[u8]) -> QmsResult<(&str, &[u8])> {
+    let malformed = || QmsError::parse_error("Malformed sealed content");
+    let rest = sealed.strip_prefix(SEALED_BINARY_PREFIX.as_bytes()).ok_or_else(malformed)?;
+    let separator = rest.iter().position(|&byte| byte == b':').ok_or_else(malformed)?;
+    let key_id = std::str::from_utf8(&rest[..separator]).map_err(|_| malformed())?;
+    Ok((key_id, &rest[separator + 1..]))
+}
+
+fn to_io_error(error: QmsError) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, error.to_string())
+}
+
+fn string_field(obj: &HashMap<String, JsonValue>, field: &str) -> QmsResult<String> {
+    match obj.get(field) {
+        Some(JsonValue::String(s)) => Ok(s.clone()),
+        _ => Err(QmsError::parse_error(&format!("Missing or invalid field '{field}'"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn passphrase() -> KeySource {
+        KeySource::Passphrase("correct horse battery".to_string())
+    }
+
+    #[test]
+    fn test_seal_and_open_round_trip() {
+        let key = ProjectKey::generate();
+        let sealed = key.seal("risks/RISK-001.json", "{\"id\": \"RISK-001\"}").unwrap();
+        assert!(sealed.starts_with(SEALED_PREFIX));
+        assert!(!sealed.contains("RISK-001"));
+        assert_eq!(key.open("risks/RISK-001.json", &sealed).unwrap(), "{\"id\": \"RISK-001\"}");
+
+        // Flipping a ciphertext byte fails authentication
+        let mut tampered = sealed.clone();
+        let last = tampered.pop().unwrap();
+        tampered.push(if last == '0' { '1' } else { '0' });
+        assert!(key.open("risks/RISK-001.json", &tampered).is_err());
+
+        // Sealed content only opens at the path it was sealed for
+        assert!(key.open("risks/RISK-002.json", &sealed).is_err());
+        let sealed_bytes = key.seal_bytes("attachments/objects/ab/cd", b"data").unwrap();
+        assert!(key.open_bytes("attachments/objects/ab/ef", &sealed_bytes).is_err());
+    }
+
+    #[test]
+    fn test_enable_seals_existing_data_and_reads_transparently() {
+        let temp_dir = tempdir().unwrap();
+        let project = temp_dir.path();
+        fs::create_dir_all(project.join("risks")).unwrap();
+        fs::create_dir_all(project.join("audit")).unwrap();
+        fs::create_dir_all(project.join("config")).unwrap();
+        fs::write(project.join("risks/RISK-001.json"), "{\"id\": \"RISK-001\"}").unwrap();
+        fs::write(project.join("audit/audit.log"), "{\"a\": 1}\n{\"a\": 2}\n").unwrap();
+        fs::write(project.join("config/config.json"), "{\"version\": \"1.0\", \"data\": {}}").unwrap();
+
+        let sealed = enable(project, &[passphrase()]).unwrap();
+        assert_eq!(sealed, 2);
+
+        let raw_risk = fs::read_to_string(project.join("risks/RISK-001.json")).unwrap();
+        assert!(raw_risk.starts_with(SEALED_PREFIX));
+        let raw_log = fs::read_to_string(project.join("audit/audit.log")).unwrap();
+        assert_eq!(raw_log.lines().count(), 2);
+        assert!(raw_log.lines().all(|line| line.starts_with(SEALED_PREFIX)));
+        // Configuration outside the data directories stays in clear
+        assert!(fs::read_to_string(project.join("config/config.json")).unwrap().starts_with('{'));
+
+        assert_eq!(read_to_string(project.join("risks/RISK-001.json")).unwrap(), "{\"id\": \"RISK-001\"}");
+        assert_eq!(read_to_string(project.join("audit/audit.log")).unwrap(), "{\"a\": 1}\n{\"a\": 2}\n");
+
+        // New writes are sealed, appended log lines open one at a time
+        write(project.join("risks/RISK-002.json"), "{\"id\": \"RISK-002\"}").unwrap();
+        assert!(fs::read_to_string(project.join("risks/RISK-002.json")).unwrap().starts_with(SEALED_PREFIX));
+        let line = seal(&project.join("audit/audit.log"), "{\"a\": 3}").unwrap();
+        assert_eq!(open_line(&project.join("audit/audit.log"), &line).unwrap(), "{\"a\": 3}");
+
+        // A locked project cannot be read without its secret
+        lock(project);
+        assert!(read_to_string(project.join("risks/RISK-001.json")).is_err());
+        assert!(unlock(project, &KeySource::Passphrase("wrong passphrase!".to_string())).is_err());
+        unlock(project, &passphrase()).unwrap();
+        assert!(read_to_string(project.join("risks/RISK-001.json")).is_ok());
+    }
+
+    #[test]
+    fn test_binary_files_are_sealed_whole() {
+        let temp_dir = tempdir().unwrap();
+        let project = temp_dir.path().join("project");
+        let object = project.join("attachments/objects/ab/cdef");
+        fs::create_dir_all(object.parent().unwrap()).unwrap();
+        let png = b"\x89PNG\r\n\x1a\n\x00\xffbinary".to_vec();
+        fs::write(&object, &png).unwrap();
+
+        enable(&project, &[passphrase()]).unwrap();
+        let raw = fs::read(&object).unwrap();
+        assert!(raw.starts_with(SEALED_BINARY_PREFIX.as_bytes()));
+        assert!(!raw.windows(6).any(|window| window == b"binary"));
+        assert_eq!(read(&object).unwrap(), png);
+
+        write_bytes(&object, b"\x00\x01\x02").unwrap();
+        assert!(fs::read(&object).unwrap().starts_with(SEALED_BINARY_PREFIX.as_bytes()));
+        assert_eq!(read(&object).unwrap(), b"\x00\x01\x02");
+
+        let export = temp_dir.path().join("export");
+        export_plaintext(&project, &passphrase(), &export).unwrap();
+        assert_eq!(fs::read(export.join("attachments/objects/ab/cdef")).unwrap(), b"\x00\x01\x02");
+
+        disable(&project, &passphrase()).unwrap();
+        assert_eq!(fs::read(&object).unwrap(), b"\x00\x01\x02");
+    }
+
+    #[test]
+    fn test_rekey_export_and_disable() {
+        let temp_dir = tempdir().unwrap();
+        let project = temp_dir.path().join("project");
+        fs::create_dir_all(project.join("documents")).unwrap();
+        fs::write(project.join("documents/index.json"), "{\"documents\": []}").unwrap();
+        enable(&project, &[passphrase()]).unwrap();
+        let old_key_id = Keyring::load(&project).unwrap().key_id;
+
+        let key_file = KeySource::generate_key_file(&temp_dir.path().join("project.key")).unwrap();
+        rekey(&project, &passphrase(), std::slice::from_ref(&key_file)).unwrap();
+        let keyring = Keyring::load(&project).unwrap();
+        assert_ne!(keyring.key_id, old_key_id);
+        assert_eq!(keyring.methods(), vec!["key_file".to_string()]);
+        assert!(fs::read_to_string(project.join("documents/index.json")).unwrap().contains(&keyring.key_id));
+
+        // The old passphrase no longer unlocks the project
+        lock(&project);
+        assert!(unlock(&project, &passphrase()).is_err());
+
+        let export = temp_dir.path().join("export");
+        assert_eq!(export_plaintext(&project, &key_file, &export).unwrap(), 1);
+        assert_eq!(fs::read_to_string(export.join("documents/index.json")).unwrap(), "{\"documents\": []}");
+        assert!(!export.join(KEYRING_FILE).exists());
+
+        disable(&project, &key_file).unwrap();
+        assert!(!is_encrypted(&project));
+        assert_eq!(fs::read_to_string(project.join("documents/index.json")).unwrap(), "{\"documents\": []}");
+    }
+
+    #[test]
+    fn test_swapped_and_unsealed_files_are_rejected() {
+        let temp_dir = tempdir().unwrap();
+        let project = temp_dir.path();
+        fs::create_dir_all(project.join("risks")).unwrap();
+        fs::write(project.join("risks/RISK-001.json"), "{\"id\": \"RISK-001\"}").unwrap();
+        fs::write(project.join("risks/RISK-002.json"), "{\"id\": \"RISK-002\"}").unwrap();
+        enable(project, &[passphrase()]).unwrap();
+
+        // A sealed file copied over another one fails authentication
+        let first = fs::read(project.join("risks/RISK-001.json")).unwrap();
+        fs::write(project.join("risks/RISK-002.json"), &first).unwrap();
+        assert!(read_to_string(project.join("risks/RISK-002.json")).is_err());
+
+        // Plaintext dropped into an encrypted project is not accepted
+        fs::write(project.join("risks/RISK-003.json"), "{\"id\": \"RISK-003\"}").unwrap();
+        assert!(read_to_string(project.join("risks/RISK-003.json")).is_err());
+        assert!(read(project.join("risks/RISK-003.json")).is_err());
+        assert!(open_line(&project.join("audit/audit.log"), "{\"a\": 1}").is_err());
+
+        // copy and rename seal the content again for its new path
+        copy(project.join("risks/RISK-001.json"), project.join("risks/RISK-004.json")).unwrap();
+        assert_eq!(read_to_string(project.join("risks/RISK-004.json")).unwrap(), "{\"id\": \"RISK-001\"}");
+        fs::create_dir_all(project.join("documents/DOC-1")).unwrap();
+        write(project.join("documents/DOC-1/content.md"), "line one\nline two\n").unwrap();
+        rename(project.join("documents/DOC-1"), project.join("documents/archive/DOC-1")).unwrap();
+        assert!(!project.join("documents/DOC-1").exists());
+        assert_eq!(
+            read_to_string(project.join("documents/archive/DOC-1/content.md")).unwrap(),
+            "line one\nline two\n"
+        );
+    }
+
+    #[test]
+    fn test_every_file_outside_the_clear_list_is_sealed() {
+        let temp_dir = tempdir().unwrap();
+        let project = temp_dir.path().join("project");
+        let files = [
+            ("project.json", "{\"id\": \"p1\"}"),
+            ("config/config.json", "{\"version\": \"1.0\", \"data\": {}}"),
+            ("documents/templates/sop.md", "# SOP"),
+            ("audit/exports/audit.csv", "timestamp,action"),
+            ("reports/dhf.md", "# DHF"),
+            ("documents/DOC-1/content.md", "# Design input"),
+            ("capa/CAPA-001.json", "{}"),
+            ("ncr/NCR-001.json", "{}"),
+            ("changes/ECR-001.json", "{}"),
+            ("signatures/SIG-1.json", "{}"),
+            ("users/alice/mfa.json", "{\"secret\": \"JBSWY3DPEHPK3PXP\"}"),
+            ("backups/backup_index.json", "{\"backups\": []}"),
+            ("design_reviews/DR-001.json", "{}"),
+            ("baselines/R1.json", "{}"),
+            ("soup/register.json", "{}"),
+            ("git/history.json", "{}"),
+            ("scheduler/history.jsonl", "{\"job\": \"backup\"}\n{\"job\": \"report\"}\n"),
+            ("audit/audit.log", "{\"a\": 1}\n"),
+        ];
+        for (relative, content) in files {
+            let path = project.join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        fs::create_dir_all(project.join("audit/archive")).unwrap();
+        fs::write(project.join("audit/archive/audit-2026-01-01.log.gz"), b"\x1f\x8b\x08\x00\xff").unwrap();
+
+        enable(&project, &[passphrase()]).unwrap();
+
+        // Writes after encryption is enabled, including document backups
+        let _ = crate::modules::audit_logger::initialize_audit_system(crate::modules::audit_logger::AuditConfig {
+            project_path: project.to_string_lossy().to_string(),
+            retention_days: 30,
+            daily_rotation: false,
+            max_file_size_mb: 10,
+            require_checksums: false,
+        });
+        let backups = crate::modules::document_control::backup::DocumentBackupManager::new(project.clone());
+        backups
+            .create_backup("DOC-1", "1.0", &project.join("documents/DOC-1/content.md"), "# Design input", "alice", "save")
+            .unwrap();
+        atomic_write(&project.join("capa/CAPA-002.json"), "{}").unwrap();
+        write(project.join("config/risk_policy.json"), "{}").unwrap();
+
+        let clear = |relative: &Path| CLEAR_PATHS.iter().any(|path| relative.starts_with(path));
+        for file in walk_files(&project).unwrap() {
+            let relative = file.strip_prefix(&project).unwrap();
+            let data = fs::read(&file).unwrap();
+            if clear(relative) {
+                assert!(!data.starts_with(b"qms-enc:"), "{} should stay in clear", relative.display());
+                continue;
+            }
+            assert!(
+                data.starts_with(SEALED_PREFIX.as_bytes()) || data.starts_with(SEALED_BINARY_PREFIX.as_bytes()),
+                "{} is not sealed",
+                relative.display()
+            );
+        }
+        assert!(fs::read_dir(project.join("backups/documents")).unwrap().next().is_some());
+    }
+}
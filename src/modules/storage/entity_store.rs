@@ -0,0 +1,206 @@
+/// Project Entity Storage
+///
+/// Connects the managers to the storage backend selected in the project's
+/// configuration. Managers hold an `EntityStore` and go through its reader and
+/// writer instead of touching files, so the same code runs on JSON files or on
+/// the embedded SQLite database.
+
+use crate::prelude::*;
+use crate::modules::storage::file_storage::{FileStorageReader, FileStorageWriter, JsonCollectionFile};
+use crate::modules::storage::sqlite_storage::{SqliteDatabase, SqliteStorage};
+use crate::modules::storage::storage_interfaces::*;
+use crate::modules::search::{self, SearchEntity, SearchRecord};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Where a collection lives when the project uses file storage
+#[derive(Debug, Clone)]
+pub enum FileLayout {
+    /// One `<id>.json` file per entity; `excluded` names non-entity files in the directory
+    Directory { dir: PathBuf, excluded: Vec<String> },
+    /// All entities in one JSON document under `array_key`
+    Collection { path: PathBuf, array_key: String },
+}
+
+/// Entity persisted in a project collection
+pub trait ProjectEntity: StorageEntity + 'static {
+    /// Collection name, used as the database collection key
+    const COLLECTION: &'static str;
+
+    /// File layout used by the file backend
+    fn file_layout(project_path: &Path) -> FileLayout;
+
+    /// Kind of search record, for collections kept in the project search index
+    const SEARCH_ENTITY: Option<SearchEntity> = None;
+
+    /// Searchable form of the entity
+    fn search_record(&self) -> Option<SearchRecord> {
+        None
+    }
+}
+
+/// Reader and writer for one collection on a chosen backend
+pub struct EntityStore<T> {
+    reader: Box<dyn StorageReader<T>>,
+    writer: Box<dyn StorageWriter<T>>,
+    storage_type: StorageType,
+}
+
+impl<T: ProjectEntity> EntityStore<T> {
+    /// Open the collection on the backend configured for the project
+    pub fn open(project_path: &Path) -> QmsResult<Self> {
+        let config = StorageConfig::for_project(project_path)?;
+        Self::open_with(project_path, &config)
+    }
+
+    /// Open the collection on an explicit backend
+    pub fn open_with(project_path: &Path, config: &StorageConfig) -> QmsResult<Self> {
+        let mut store = Self::open_backend(project_path, config)?;
+        if let Some(entity) = T::SEARCH_ENTITY {
+            let database = match config.storage_type {
+                StorageType::Database => Some(SqliteDatabase::for_project(project_path, config)?),
+                _ => None,
+            };
+            store.writer = Box::new(IndexingWriter {
+                inner: store.writer,
+                project_path: project_path.to_path_buf(),
+                entity,
+                database,
+            });
+        }
+        Ok(store)
+    }
+
+    fn open_backend(project_path: &Path, config: &StorageConfig) -> QmsResult<Self> {
+        match config.storage_type {
+            StorageType::FileSystem => {
+                let (reader, writer): (Box<dyn StorageReader<T>>, Box<dyn StorageWriter<T>>) =
+                    match T::file_layout(project_path) {
+                        FileLayout::Directory { dir, excluded } => {
+                            let reader = excluded
+                                .iter()
+                                .fold(FileStorageReader::new(&dir), |reader, name| reader.excluding(name));
+                            (Box::new(reader), Box::new(FileStorageWriter::new(&dir)?))
+                        }
+                        FileLayout::Collection { path, array_key } => (
+                            Box::new(JsonCollectionFile::new(&path, &array_key)),
+                            Box::new(JsonCollectionFile::new(&path, &array_key)),
+                        ),
+                    };
+                Ok(Self { reader, writer, storage_type: StorageType::FileSystem })
+            }
+            StorageType::Database => {
+                let database = SqliteDatabase::for_project(project_path, config)?;
+                Ok(Self {
+                    reader: Box::new(SqliteStorage::<T>::new(database.clone(), T::COLLECTION)),
+                    writer: Box::new(SqliteStorage::<T>::new(database, T::COLLECTION)),
+                    storage_type: StorageType::Database,
+                })
+            }
+            ref other => Err(QmsError::validation_error(&format!(
+                "Storage backend '{}' is not supported for project data",
+                other.as_str()
+            ))),
+        }
+    }
+}
+
+impl<T> EntityStore<T> {
+    pub fn reader(&self) -> &dyn StorageReader<T> {
+        self.reader.as_ref()
+    }
+
+    pub fn writer(&self) -> &dyn StorageWriter<T> {
+        self.writer.as_ref()
+    }
+
+    pub const fn storage_type(&self) -> &StorageType {
+        &self.storage_type
+    }
+
+    /// Whether the collection is held in the project database
+    pub fn is_database(&self) -> bool {
+        self.storage_type == StorageType::Database
+    }
+}
+
+/// Writer that keeps the project search index in step with the collection
+///
+/// The index is updated after the write succeeds and, on the SQLite backend,
+/// only once the enclosing transaction commits, so a rolled back write never
+/// reaches the index. An index failure is reported but never fails the write.
+struct IndexingWriter<T> {
+    inner: Box<dyn StorageWriter<T>>,
+    project_path: PathBuf,
+    entity: SearchEntity,
+    database: Option<Arc<SqliteDatabase>>,
+}
+
+impl<T: ProjectEntity> StorageWriter<T> for IndexingWriter<T> {
+    fn save(&self, item: &T) -> QmsResult<()> {
+        self.inner.save(item)?;
+        self.index_items(std::slice::from_ref(item))
+    }
+
+    fn save_batch(&self, items: &[T]) -> QmsResult<()> {
+        self.inner.save_batch(items)?;
+        self.index_items(items)
+    }
+
+    fn delete(&self, id: &str) -> QmsResult<()> {
+        self.inner.delete(id)?;
+        self.update_index(Vec::new(), vec![search::index_key(self.entity, id)])
+    }
+
+    fn delete_batch(&self, ids: &[String]) -> QmsResult<()> {
+        self.inner.delete_batch(ids)?;
+        let keys = ids.iter().map(|id| search::index_key(self.entity, id)).collect();
+        self.update_index(Vec::new(), keys)
+    }
+}
+
+impl<T: ProjectEntity> IndexingWriter<T> {
+    fn index_items(&self, items: &[T]) -> QmsResult<()> {
+        let records = items.iter().filter_map(ProjectEntity::search_record).collect();
+        self.update_index(records, Vec::new())
+    }
+
+    /// Apply an index update now, or when the open transaction commits
+    fn update_index(&self, records: Vec<SearchRecord>, removed: Vec<String>) -> QmsResult<()> {
+        let project_path = self.project_path.clone();
+        let update = move || search::update_index_or_warn(&project_path, &records, &removed);
+        match &self.database {
+            Some(database) => database.after_commit(update),
+            None => {
+                update();
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Run `operation` atomically on the project's storage
+///
+/// On the SQLite backend every write made through any `EntityStore` of the
+/// project is committed together or rolled back on error. File storage has no
+/// transactions, so writes there take effect as they are made.
+pub fn with_transaction<R>(project_path: &Path, operation: impl FnOnce() -> QmsResult<R>) -> QmsResult<R> {
+    let config = StorageConfig::for_project(project_path)?;
+    match config.storage_type {
+        StorageType::Database => SqliteDatabase::for_project(project_path, &config)?.with_transaction(operation),
+        _ => operation(),
+    }
+}
+
+/// Copy every entity of a collection from one backend to another
+///
+/// Returns the number of entities copied.
+pub fn copy_collection<T: ProjectEntity>(
+    project_path: &Path,
+    from: &StorageConfig,
+    to: &StorageConfig,
+) -> QmsResult<usize> {
+    let items = EntityStore::<T>::open_with(project_path, from)?.reader().read_all()?;
+    EntityStore::<T>::open_with(project_path, to)?.writer().save_batch(&items)?;
+    Ok(items.len())
+}
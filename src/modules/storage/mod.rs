@@ -4,15 +4,22 @@
 
 pub mod storage_interfaces;
 pub mod file_storage;
+pub mod sqlite_storage;
+pub mod entity_store;
+pub mod encryption;
 
 // Re-export key interfaces
 pub use storage_interfaces::{
     StorageReader, StorageWriter, StorageSearcher, StorageIndexer, BackupManager,
     TransactionManager, StorageFactory, StorageConfig, StorageType, StorageMetrics,
-    MetricsCollector, IndexStats, BackupInfo
+    MetricsCollector, IndexStats, BackupInfo, StorageEntity, project_setting, set_project_setting
 };
 
 pub use file_storage::{
     FileStorageReader, FileStorageWriter, FileStorageSearcher, FileStorageIndexer,
-    FileBackupManager, FileStorageFactory
+    FileBackupManager, FileStorageFactory, JsonCollectionFile
 };
+
+pub use sqlite_storage::{SqliteDatabase, SqliteSearchCriteria, SqliteStorage};
+
+pub use entity_store::{copy_collection, with_transaction, EntityStore, FileLayout, ProjectEntity};
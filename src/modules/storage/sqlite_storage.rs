@@ -0,0 +1,630 @@
+/// SQLite Storage Implementation
+///
+/// Embedded database backend for the storage interfaces. Every entity is a row
+/// in a single `entities` table keyed by `(collection, id)`, holding the same
+/// JSON document the file backend writes, so listing a collection is one
+/// indexed query instead of one file read per entity.
+///
+/// All handles for a database share one connection per process, which lets a
+/// transaction begun through `SqliteDatabase` cover writes made by any manager
+/// for the same project. Nested transactions on the same thread become
+/// savepoints. An open transaction belongs to the thread that began it: other
+/// threads wait for it to commit or roll back before they touch the connection,
+/// so their statements never land inside someone else's transaction.
+
+use crate::prelude::*;
+use crate::modules::storage::encryption;
+use crate::modules::storage::storage_interfaces::*;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, OnceLock};
+use std::thread::{self, ThreadId};
+use std::time::Duration;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS entities (
+        collection TEXT NOT NULL,
+        id TEXT NOT NULL,
+        data TEXT NOT NULL,
+        updated_at TEXT NOT NULL,
+        PRIMARY KEY (collection, id)
+    ) WITHOUT ROWID;
+";
+
+/// Open databases, one shared connection per database file
+static DATABASES: OnceLock<Mutex<HashMap<PathBuf, Arc<SqliteDatabase>>>> = OnceLock::new();
+
+fn db_error(e: rusqlite::Error) -> QmsError {
+    QmsError::io_error(&format!("SQLite error: {e}"))
+}
+
+fn lock_error<T>(_: std::sync::PoisonError<T>) -> QmsError {
+    QmsError::Lock("SQLite connection lock poisoned".to_string())
+}
+
+/// Shared connection to a project database
+pub struct SqliteDatabase {
+    path: PathBuf,
+    connection: Mutex<Connection>,
+    transaction: Mutex<TransactionState>,
+    transaction_released: Condvar,
+}
+
+/// Transaction open on the shared connection
+#[derive(Default)]
+struct TransactionState {
+    /// Thread that began the transaction
+    owner: Option<ThreadId>,
+    depth: usize,
+    /// Work deferred until the outermost transaction commits
+    on_commit: Vec<Box<dyn FnOnce() + Send>>,
+    /// Length of `on_commit` when each open savepoint began
+    savepoint_marks: Vec<usize>,
+}
+
+/// Handle for an open transaction or savepoint
+#[derive(Debug)]
+pub struct SqliteTransaction {
+    depth: usize,
+}
+
+impl SqliteDatabase {
+    /// Open (creating if needed) the database at `path`
+    ///
+    /// Returns the existing connection when the database is already open.
+    pub fn open(path: &Path, timeout_seconds: Option<u64>) -> QmsResult<Arc<Self>> {
+        let databases = DATABASES.get_or_init(|| Mutex::new(HashMap::new()));
+        let mut databases = databases.lock().map_err(lock_error)?;
+        if let Some(database) = databases.get(path) {
+            return Ok(database.clone());
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let connection = Connection::open(path).map_err(db_error)?;
+        connection
+            .busy_timeout(Duration::from_secs(timeout_seconds.unwrap_or(30)))
+            .map_err(db_error)?;
+        connection.pragma_update(None, "journal_mode", "WAL").map_err(db_error)?;
+        connection.pragma_update(None, "synchronous", "FULL").map_err(db_error)?;
+        connection.execute_batch(SCHEMA).map_err(db_error)?;
+
+        let database = Arc::new(Self {
+            path: path.to_path_buf(),
+            connection: Mutex::new(connection),
+            transaction: Mutex::new(TransactionState::default()),
+            transaction_released: Condvar::new(),
+        });
+        databases.insert(path.to_path_buf(), database.clone());
+        Ok(database)
+    }
+
+    /// Database of a project, at the location given by its storage configuration
+    pub fn for_project(project_path: &Path, config: &StorageConfig) -> QmsResult<Arc<Self>> {
+        Self::open(&config.database_path(project_path), config.timeout_seconds)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Run `operation` in a transaction, committing on success and rolling back on error
+    pub fn with_transaction<R>(&self, operation: impl FnOnce() -> QmsResult<R>) -> QmsResult<R> {
+        let transaction = self.begin_transaction()?;
+        match operation() {
+            Ok(result) => {
+                self.commit_transaction(transaction)?;
+                Ok(result)
+            }
+            Err(e) => {
+                self.rollback_transaction(transaction)?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Replace the stored JSON of every row with `transform(data)` in one transaction
+    ///
+    /// Used to encrypt, re-encrypt or decrypt a whole database. Returns the
+    /// number of rows that changed.
+    pub fn rewrite_rows(&self, transform: impl Fn(&str) -> QmsResult<String>) -> QmsResult<usize> {
+        self.with_transaction(|| {
+            let rows = self.with_connection(|c| {
+                let mut statement = c.prepare("SELECT collection, id, data FROM entities")?;
+                let rows = statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+                rows.collect::<rusqlite::Result<Vec<(String, String, String)>>>()
+            })?;
+
+            let mut changed = 0;
+            for (collection, id, data) in rows {
+                let rewritten = transform(&data)?;
+                if rewritten != data {
+                    self.with_connection(|c| {
+                        c.execute(
+                            "UPDATE entities SET data = ?1 WHERE collection = ?2 AND id = ?3",
+                            params![rewritten, collection, id],
+                        )
+                    })?;
+                    changed += 1;
+                }
+            }
+            Ok(changed)
+        })
+    }
+
+    /// Run `action` once the current transaction commits
+    ///
+    /// Outside a transaction the action runs straight away. Actions queued in a
+    /// transaction or savepoint that rolls back are dropped.
+    pub fn after_commit(&self, action: impl FnOnce() + Send + 'static) -> QmsResult<()> {
+        let mut state = self.claim()?;
+        if state.depth == 0 {
+            drop(state);
+            action();
+        } else {
+            state.on_commit.push(Box::new(action));
+        }
+        Ok(())
+    }
+
+    /// Wait until no other thread has a transaction open
+    ///
+    /// The returned guard keeps other threads from beginning one until it drops.
+    fn claim(&self) -> QmsResult<MutexGuard<'_, TransactionState>> {
+        let current = thread::current().id();
+        let mut state = self.transaction.lock().map_err(lock_error)?;
+        while state.owner.is_some_and(|owner| owner != current) {
+            state = self.transaction_released.wait(state).map_err(lock_error)?;
+        }
+        Ok(state)
+    }
+
+    /// Close the outermost transaction and wake threads waiting for it
+    fn release(&self, state: &mut TransactionState) -> Vec<Box<dyn FnOnce() + Send>> {
+        state.owner = None;
+        state.depth = 0;
+        state.savepoint_marks.clear();
+        self.transaction_released.notify_all();
+        std::mem::take(&mut state.on_commit)
+    }
+
+    fn with_connection<R>(&self, f: impl FnOnce(&Connection) -> rusqlite::Result<R>) -> QmsResult<R> {
+        let _state = self.claim()?;
+        self.execute(f)
+    }
+
+    /// Run `f` on the connection; callers must hold the transaction state
+    fn execute<R>(&self, f: impl FnOnce(&Connection) -> rusqlite::Result<R>) -> QmsResult<R> {
+        let connection = self.connection.lock().map_err(lock_error)?;
+        f(&connection).map_err(db_error)
+    }
+}
+
+impl TransactionManager for SqliteDatabase {
+    type Transaction = SqliteTransaction;
+
+    fn begin_transaction(&self) -> QmsResult<SqliteTransaction> {
+        let mut state = self.claim()?;
+        if state.depth == 0 {
+            self.execute(|c| c.execute_batch("BEGIN IMMEDIATE"))?;
+            state.owner = Some(thread::current().id());
+        } else {
+            self.execute(|c| c.execute_batch(&format!("SAVEPOINT sp_{}", state.depth)))?;
+            let mark = state.on_commit.len();
+            state.savepoint_marks.push(mark);
+        }
+        state.depth += 1;
+        Ok(SqliteTransaction { depth: state.depth })
+    }
+
+    fn commit_transaction(&self, transaction: SqliteTransaction) -> QmsResult<()> {
+        let mut state = self.claim()?;
+        if transaction.depth != state.depth {
+            return Err(QmsError::invalid_operation("Transactions must be committed innermost first"));
+        }
+        if state.depth > 1 {
+            self.execute(|c| c.execute_batch(&format!("RELEASE sp_{}", state.depth - 1)))?;
+            state.savepoint_marks.pop();
+            state.depth -= 1;
+            return Ok(());
+        }
+
+        if let Err(e) = self.execute(|c| c.execute_batch("COMMIT")) {
+            // Never leave the connection held by a transaction that cannot finish
+            let _ = self.execute(|c| c.execute_batch("ROLLBACK"));
+            self.release(&mut state);
+            return Err(e);
+        }
+        let on_commit = self.release(&mut state);
+        drop(state);
+        for action in on_commit {
+            action();
+        }
+        Ok(())
+    }
+
+    fn rollback_transaction(&self, transaction: SqliteTransaction) -> QmsResult<()> {
+        let mut state = self.claim()?;
+        if transaction.depth != state.depth {
+            return Err(QmsError::invalid_operation("Transactions must be rolled back innermost first"));
+        }
+        if state.depth > 1 {
+            self.execute(|c| c.execute_batch(&format!("ROLLBACK TO sp_{0}; RELEASE sp_{0}", state.depth - 1)))?;
+            let mark = state.savepoint_marks.pop().unwrap_or(0);
+            state.on_commit.truncate(mark);
+            state.depth -= 1;
+            return Ok(());
+        }
+
+        let result = self.execute(|c| c.execute_batch("ROLLBACK"));
+        self.release(&mut state);
+        result
+    }
+}
+
+/// Search criteria for SQLite storage
+#[derive(Debug, Clone, Default)]
+pub struct SqliteSearchCriteria {
+    /// Match entities whose stored JSON contains this text
+    pub text_contains: Option<String>,
+}
+
+impl SqliteSearchCriteria {
+    fn like_pattern(&self) -> String {
+        match &self.text_contains {
+            Some(text) => format!(
+                "%{}%",
+                text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+            ),
+            None => "%".to_string(),
+        }
+    }
+}
+
+/// One entity collection in a SQLite database
+///
+/// Implements reading, writing and searching; multi-entity atomicity comes
+/// from wrapping calls in `SqliteDatabase::with_transaction`.
+pub struct SqliteStorage<T> {
+    database: Arc<SqliteDatabase>,
+    collection: String,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> SqliteStorage<T>
+where
+    T: StorageEntity,
+{
+    pub fn new(database: Arc<SqliteDatabase>, collection: &str) -> Self {
+        Self {
+            database,
+            collection: collection.to_string(),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn parse_rows(&self, rows: Vec<String>) -> QmsResult<Vec<T>> {
+        rows.iter().map(|data| self.parse_row(data)).collect()
+    }
+
+    /// Parse one stored row, decrypting it when the project is encrypted
+    fn parse_row(&self, data: &str) -> QmsResult<T> {
+        T::from_storage_json(&encryption::open_line(self.database.path(), data)?)
+    }
+
+    /// Rows of the collection matching `criteria`, in id order
+    ///
+    /// Encrypted rows cannot be matched by SQL, so they are filtered after decryption.
+    fn search_rows(&self, criteria: &SqliteSearchCriteria, offset: usize, limit: usize) -> QmsResult<Vec<T>> {
+        if encryption::is_encrypted_path(self.database.path()) {
+            let needle = criteria.text_contains.as_deref().unwrap_or("");
+            let rows = self.database.with_connection(|c| {
+                let mut statement = c.prepare_cached("SELECT data FROM entities WHERE collection = ?1 ORDER BY id")?;
+                let rows = statement.query_map(params![self.collection], |row| row.get(0))?;
+                rows.collect::<rusqlite::Result<Vec<String>>>()
+            })?;
+            let mut matches = Vec::new();
+            for data in rows {
+                let json = encryption::open_line(self.database.path(), &data)?;
+                if json.contains(needle) {
+                    matches.push(T::from_storage_json(&json)?);
+                }
+            }
+            return Ok(matches.into_iter().skip(offset).take(limit).collect());
+        }
+
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let offset = i64::try_from(offset).unwrap_or(i64::MAX);
+        let rows = self.database.with_connection(|c| {
+            let mut statement = c.prepare_cached(
+                "SELECT data FROM entities WHERE collection = ?1 AND data LIKE ?2 ESCAPE '\\'
+                 ORDER BY id LIMIT ?3 OFFSET ?4",
+            )?;
+            let rows = statement.query_map(
+                params![self.collection, criteria.like_pattern(), limit, offset],
+                |row| row.get(0),
+            )?;
+            rows.collect::<rusqlite::Result<Vec<String>>>()
+        })?;
+        self.parse_rows(rows)
+    }
+}
+
+impl<T> StorageReader<T> for SqliteStorage<T>
+where
+    T: StorageEntity,
+{
+    fn read(&self, id: &str) -> QmsResult<T> {
+        let data: Option<String> = self.database.with_connection(|c| {
+            c.query_row(
+                "SELECT data FROM entities WHERE collection = ?1 AND id = ?2",
+                params![self.collection, id],
+                |row| row.get(0),
+            )
+            .optional()
+        })?;
+        match data {
+            Some(data) => self.parse_row(&data),
+            None => Err(QmsError::not_found(&format!("{id} not found"))),
+        }
+    }
+
+    fn read_all(&self) -> QmsResult<Vec<T>> {
+        let rows = self.database.with_connection(|c| {
+            let mut statement = c.prepare_cached("SELECT data FROM entities WHERE collection = ?1 ORDER BY id")?;
+            let rows = statement.query_map(params![self.collection], |row| row.get(0))?;
+            rows.collect::<rusqlite::Result<Vec<String>>>()
+        })?;
+        self.parse_rows(rows)
+    }
+
+    fn exists(&self, id: &str) -> QmsResult<bool> {
+        self.database.with_connection(|c| {
+            c.query_row(
+                "SELECT EXISTS(SELECT 1 FROM entities WHERE collection = ?1 AND id = ?2)",
+                params![self.collection, id],
+                |row| row.get(0),
+            )
+        })
+    }
+
+    fn count(&self) -> QmsResult<usize> {
+        let count: i64 = self.database.with_connection(|c| {
+            c.query_row(
+                "SELECT COUNT(*) FROM entities WHERE collection = ?1",
+                params![self.collection],
+                |row| row.get(0),
+            )
+        })?;
+        Ok(count as usize)
+    }
+}
+
+impl<T> StorageWriter<T> for SqliteStorage<T>
+where
+    T: StorageEntity,
+{
+    fn save(&self, item: &T) -> QmsResult<()> {
+        self.save_batch(std::slice::from_ref(item))
+    }
+
+    fn save_batch(&self, items: &[T]) -> QmsResult<()> {
+        let timestamp = crate::utils::current_iso8601_timestamp();
+        let rows = items
+            .iter()
+            .map(|item| Ok((item.storage_id(), encryption::seal(self.database.path(), &item.to_storage_json())?)))
+            .collect::<QmsResult<Vec<_>>>()?;
+        self.database.with_transaction(|| {
+            self.database.with_connection(|c| {
+                let mut statement = c.prepare_cached(
+                    "INSERT INTO entities (collection, id, data, updated_at) VALUES (?1, ?2, ?3, ?4)
+                     ON CONFLICT (collection, id) DO UPDATE SET data = excluded.data, updated_at = excluded.updated_at",
+                )?;
+                for (id, data) in &rows {
+                    statement.execute(params![self.collection, id, data, timestamp])?;
+                }
+                Ok(())
+            })
+        })
+    }
+
+    fn delete(&self, id: &str) -> QmsResult<()> {
+        self.delete_batch(&[id.to_string()])
+    }
+
+    fn delete_batch(&self, ids: &[String]) -> QmsResult<()> {
+        self.database.with_transaction(|| {
+            self.database.with_connection(|c| {
+                let mut statement = c.prepare_cached("DELETE FROM entities WHERE collection = ?1 AND id = ?2")?;
+                for id in ids {
+                    statement.execute(params![self.collection, id])?;
+                }
+                Ok(())
+            })
+        })
+    }
+}
+
+impl<T> StorageSearcher<T, SqliteSearchCriteria> for SqliteStorage<T>
+where
+    T: StorageEntity,
+{
+    fn search(&self, criteria: &SqliteSearchCriteria) -> QmsResult<Vec<T>> {
+        self.search_paginated(criteria, 0, usize::MAX)
+    }
+
+    fn search_paginated(&self, criteria: &SqliteSearchCriteria, offset: usize, limit: usize) -> QmsResult<Vec<T>> {
+        self.search_rows(criteria, offset, limit)
+    }
+
+    fn count_search_results(&self, criteria: &SqliteSearchCriteria) -> QmsResult<usize> {
+        if encryption::is_encrypted_path(self.database.path()) {
+            return Ok(self.search_rows(criteria, 0, usize::MAX)?.len());
+        }
+        let count: i64 = self.database.with_connection(|c| {
+            c.query_row(
+                "SELECT COUNT(*) FROM entities WHERE collection = ?1 AND data LIKE ?2 ESCAPE '\\'",
+                params![self.collection, criteria.like_pattern()],
+                |row| row.get(0),
+            )
+        })?;
+        Ok(count as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Note {
+        id: String,
+        text: String,
+    }
+
+    impl StorageEntity for Note {
+        fn storage_id(&self) -> String {
+            self.id.clone()
+        }
+
+        fn to_storage_json(&self) -> String {
+            format!("{{\"id\": \"{}\", \"text\": \"{}\"}}", self.id, self.text)
+        }
+
+        fn from_storage_json(json: &str) -> QmsResult<Self> {
+            let value = crate::json_utils::JsonValue::parse(json)?;
+            let crate::json_utils::JsonValue::Object(obj) = value else {
+                return Err(QmsError::parse_error("expected object"));
+            };
+            let field = |name: &str| obj.get(name).and_then(|v| v.as_string()).cloned().unwrap_or_default();
+            Ok(Self { id: field("id"), text: field("text") })
+        }
+    }
+
+    fn note(id: &str, text: &str) -> Note {
+        Note { id: id.to_string(), text: text.to_string() }
+    }
+
+    #[test]
+    fn test_sqlite_storage_crud_and_search() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let database = SqliteDatabase::open(&temp_dir.path().join("qms.db"), None).unwrap();
+        let notes = SqliteStorage::<Note>::new(database.clone(), "notes");
+        let others = SqliteStorage::<Note>::new(database, "others");
+
+        notes.save_batch(&[note("N-2", "second"), note("N-1", "first 100%")]).unwrap();
+        others.save(&note("N-1", "other collection")).unwrap();
+        notes.save(&note("N-2", "second, edited")).unwrap();
+
+        assert_eq!(notes.count().unwrap(), 2);
+        assert_eq!(notes.read("N-2").unwrap().text, "second, edited");
+        assert!(matches!(notes.read("N-3"), Err(QmsError::NotFound(_))));
+        assert_eq!(
+            notes.read_all().unwrap(),
+            vec![note("N-1", "first 100%"), note("N-2", "second, edited")]
+        );
+
+        let criteria = SqliteSearchCriteria { text_contains: Some("100%".to_string()) };
+        assert_eq!(notes.search(&criteria).unwrap(), vec![note("N-1", "first 100%")]);
+        assert_eq!(notes.search_paginated(&SqliteSearchCriteria::default(), 1, 5).unwrap().len(), 1);
+
+        notes.delete("N-1").unwrap();
+        assert!(!notes.exists("N-1").unwrap());
+        assert!(others.exists("N-1").unwrap());
+    }
+
+    #[test]
+    fn test_sqlite_transaction_rollback_and_savepoints() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let database = SqliteDatabase::open(&temp_dir.path().join("qms.db"), None).unwrap();
+        let notes = SqliteStorage::<Note>::new(database.clone(), "notes");
+
+        let result: QmsResult<()> = database.with_transaction(|| {
+            notes.save(&note("N-1", "kept only if committed"))?;
+            Err(QmsError::domain_error("second step failed"))
+        });
+        assert!(result.is_err());
+        assert_eq!(notes.count().unwrap(), 0);
+
+        database
+            .with_transaction(|| {
+                notes.save(&note("N-1", "outer"))?;
+                let inner: QmsResult<()> = database.with_transaction(|| {
+                    notes.save(&note("N-2", "inner"))?;
+                    Err(QmsError::domain_error("inner failed"))
+                });
+                assert!(inner.is_err());
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(notes.read_all().unwrap(), vec![note("N-1", "outer")]);
+
+        // Reopening returns the shared connection
+        let reopened = SqliteDatabase::open(&temp_dir.path().join("qms.db"), None).unwrap();
+        assert_eq!(SqliteStorage::<Note>::new(reopened, "notes").count().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_transactions_on_other_threads_do_not_nest() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let database = SqliteDatabase::open(&temp_dir.path().join("qms.db"), None).unwrap();
+        let notes = SqliteStorage::<Note>::new(database.clone(), "notes");
+
+        let (started_tx, started_rx) = std::sync::mpsc::channel();
+        let other = {
+            let database = database.clone();
+            thread::spawn(move || {
+                started_rx.recv().unwrap();
+                let notes = SqliteStorage::<Note>::new(database.clone(), "notes");
+                database.with_transaction(|| notes.save(&note("N-2", "other thread")))
+            })
+        };
+
+        let result: QmsResult<()> = database.with_transaction(|| {
+            notes.save(&note("N-1", "rolled back"))?;
+            started_tx.send(()).unwrap();
+            thread::sleep(Duration::from_millis(100));
+            Err(QmsError::domain_error("first transaction failed"))
+        });
+        assert!(result.is_err());
+        other.join().unwrap().unwrap();
+
+        // The other thread waited instead of joining the failed transaction
+        assert_eq!(notes.read_all().unwrap(), vec![note("N-2", "other thread")]);
+    }
+
+    #[test]
+    fn test_after_commit_runs_only_for_committed_work() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let database = SqliteDatabase::open(&temp_dir.path().join("qms.db"), None).unwrap();
+        let ran = Arc::new(Mutex::new(Vec::new()));
+        let record = |label: &'static str| {
+            let ran = ran.clone();
+            move || ran.lock().unwrap().push(label)
+        };
+
+        database.after_commit(record("immediate")).unwrap();
+        let _: QmsResult<()> = database.with_transaction(|| {
+            database.after_commit(record("rolled back"))?;
+            Err(QmsError::domain_error("failed"))
+        });
+        database
+            .with_transaction(|| {
+                database.after_commit(record("outer"))?;
+                let inner: QmsResult<()> = database.with_transaction(|| {
+                    database.after_commit(record("inner rolled back"))?;
+                    Err(QmsError::domain_error("inner failed"))
+                });
+                assert!(inner.is_err());
+                database.with_transaction(|| database.after_commit(record("inner committed")))?;
+                assert_eq!(ran.lock().unwrap().len(), 1);
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(*ran.lock().unwrap(), vec!["immediate", "outer", "inner committed"]);
+    }
+}
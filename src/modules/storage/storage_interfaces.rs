@@ -6,7 +6,24 @@
 /// - Single Responsibility: Each interface has one clear purpose
 
 use crate::prelude::*;
-use std::path::Path;
+use crate::json_utils::JsonValue;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Entity that can be persisted by any storage backend
+///
+/// Backends store the entity's JSON text keyed by its storage ID, so file and
+/// database storage hold identical documents.
+pub trait StorageEntity: Clone + Send + Sync + Sized {
+    /// Unique key of the entity within its collection
+    fn storage_id(&self) -> String;
+
+    /// Serialize the entity to the JSON document that is stored
+    fn to_storage_json(&self) -> String;
+
+    /// Deserialize an entity from a stored JSON document
+    fn from_storage_json(json: &str) -> QmsResult<Self>;
+}
 
 /// Generic storage reader interface - Interface Segregation Principle
 /// Focused solely on reading operations
@@ -171,6 +188,33 @@ pub enum StorageType {
     Cloud,
 }
 
+impl StorageType {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            StorageType::FileSystem => "filesystem",
+            StorageType::Database => "sqlite",
+            StorageType::Memory => "memory",
+            StorageType::Cloud => "cloud",
+        }
+    }
+}
+
+impl FromStr for StorageType {
+    type Err = QmsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "filesystem" | "file" | "files" | "json" => Ok(StorageType::FileSystem),
+            "sqlite" | "database" | "db" => Ok(StorageType::Database),
+            "memory" => Ok(StorageType::Memory),
+            "cloud" => Ok(StorageType::Cloud),
+            _ => Err(QmsError::validation_error(&format!(
+                "Unknown storage backend: {s} (expected filesystem or sqlite)"
+            ))),
+        }
+    }
+}
+
 impl Default for StorageConfig {
     fn default() -> Self {
         Self {
@@ -184,6 +228,113 @@ impl Default for StorageConfig {
     }
 }
 
+/// Key in `config/config.json` selecting the project's storage backend
+pub const STORAGE_BACKEND_KEY: &str = "storage_backend";
+
+/// Default SQLite database file, relative to the project root
+pub const DEFAULT_DATABASE_FILE: &str = "qms.db";
+
+impl StorageConfig {
+    /// Storage configuration of a project, read from `config/config.json`
+    ///
+    /// Projects without a `storage_backend` entry use file storage.
+    /// `enable_encryption` reflects whether the project has an encryption key ring.
+    pub fn for_project(project_path: &Path) -> QmsResult<Self> {
+        let mut config = Self {
+            enable_encryption: super::encryption::is_encrypted(project_path),
+            ..Self::default()
+        };
+        let config_file = project_config_file(project_path);
+        if !config_file.exists() {
+            return Ok(config);
+        }
+
+        let json = JsonValue::parse(&std::fs::read_to_string(&config_file)?)?;
+        if let Some(JsonValue::Object(data)) = config_data(&json) {
+            if let Some(backend) = data.get(STORAGE_BACKEND_KEY).and_then(|v| v.as_string()) {
+                config.storage_type = StorageType::from_str(backend)?;
+            }
+            if let Some(path) = data.get("storage_connection").and_then(|v| v.as_string()) {
+                config.connection_string = Some(path.clone());
+            }
+        }
+        Ok(config)
+    }
+
+    /// Record the storage backend in the project's `config/config.json`
+    pub fn set_project_backend(project_path: &Path, storage_type: &StorageType) -> QmsResult<()> {
+        set_project_setting(
+            project_path,
+            STORAGE_BACKEND_KEY,
+            JsonValue::String(storage_type.as_str().to_string()),
+        )
+    }
+
+    /// Database file for this configuration, resolved against the project root
+    pub fn database_path(&self, project_path: &Path) -> PathBuf {
+        match &self.connection_string {
+            Some(path) if Path::new(path).is_absolute() => PathBuf::from(path),
+            Some(path) => project_path.join(path),
+            None => project_path.join(DEFAULT_DATABASE_FILE),
+        }
+    }
+}
+
+/// Value of a setting in the project's `config/config.json`, if present
+pub fn project_setting(project_path: &Path, key: &str) -> QmsResult<Option<JsonValue>> {
+    let config_file = project_config_file(project_path);
+    if !config_file.exists() {
+        return Ok(None);
+    }
+    let json = JsonValue::parse(&std::fs::read_to_string(&config_file)?)?;
+    match config_data(&json) {
+        Some(JsonValue::Object(data)) => Ok(data.get(key).cloned()),
+        _ => Ok(None),
+    }
+}
+
+/// Store a setting in the project's `config/config.json`, creating the file if needed
+pub fn set_project_setting(project_path: &Path, key: &str, value: JsonValue) -> QmsResult<()> {
+    let config_file = project_config_file(project_path);
+    let mut json = if config_file.exists() {
+        JsonValue::parse(&std::fs::read_to_string(&config_file)?)?
+    } else {
+        let mut root = std::collections::HashMap::new();
+        root.insert("version".to_string(), JsonValue::String("1.0".to_string()));
+        root.insert("data".to_string(), JsonValue::Object(std::collections::HashMap::new()));
+        JsonValue::Object(root)
+    };
+
+    let JsonValue::Object(root) = &mut json else {
+        return Err(QmsError::parse_error("Project config must be a JSON object"));
+    };
+    let data = root
+        .entry("data".to_string())
+        .or_insert_with(|| JsonValue::Object(std::collections::HashMap::new()));
+    let JsonValue::Object(data) = data else {
+        return Err(QmsError::parse_error("Project config 'data' must be a JSON object"));
+    };
+    data.insert(key.to_string(), value);
+
+    if let Some(parent) = config_file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    crate::fs_utils::atomic_write(&config_file, &json.json_to_string())?;
+    Ok(())
+}
+
+fn project_config_file(project_path: &Path) -> PathBuf {
+    project_path.join("config").join("config.json")
+}
+
+/// Settings object of a project config (`{"version", "data": {...}}`)
+fn config_data(json: &JsonValue) -> Option<&JsonValue> {
+    match json {
+        JsonValue::Object(root) => root.get("data"),
+        _ => None,
+    }
+}
+
 /// Storage metrics for monitoring and observability
 #[derive(Debug, Clone)]
 pub struct StorageMetrics {
@@ -229,6 +380,30 @@ mod tests {
         assert!(!config.enable_encryption);
     }
     
+    #[test]
+    fn test_project_backend_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let project = temp_dir.path();
+
+        // No config file: file storage
+        assert_eq!(StorageConfig::for_project(project).unwrap().storage_type, StorageType::FileSystem);
+
+        std::fs::create_dir_all(project.join("config")).unwrap();
+        std::fs::write(
+            project.join("config").join("config.json"),
+            r#"{"version": "1.0", "data": {"audit_retention_days": 2555}}"#,
+        ).unwrap();
+        StorageConfig::set_project_backend(project, &StorageType::Database).unwrap();
+
+        let config = StorageConfig::for_project(project).unwrap();
+        assert_eq!(config.storage_type, StorageType::Database);
+        assert_eq!(config.database_path(project), project.join(DEFAULT_DATABASE_FILE));
+
+        // Other settings are preserved
+        let content = std::fs::read_to_string(project.join("config").join("config.json")).unwrap();
+        assert!(content.contains("audit_retention_days"));
+    }
+
     #[test]
     fn test_index_stats() {
         let stats = IndexStats {
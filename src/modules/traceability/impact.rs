@@ -1,8 +1,10 @@
 use crate::prelude::*;
+use crate::modules::git_history::{GitHistoryManager, RecordKind};
 use crate::modules::traceability::links::{TraceabilityManager, TraceabilityLink};
-use crate::modules::traceability::requirement::{Requirement, RequirementPriority};
+use crate::modules::traceability::requirement::{Requirement, RequirementManager, RequirementPriority};
+use crate::modules::traceability::source_scan::SOURCE_FILE_ENTITY;
 use crate::audit::log_audit;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
 use std::fs;
 
@@ -82,6 +84,17 @@ pub struct ImpactAnalysis {
     pub stakeholder_summary: HashMap<String, Vec<String>>,
 }
 
+/// A requirement changed between two revisions of the imported Git history
+#[derive(Debug, Clone)]
+pub struct TouchedRequirement {
+    pub requirement_id: String,
+    pub title: String,
+    /// Short hashes of the commits that reference it or change a file linked to it
+    pub commits: Vec<String>,
+    /// Changed files linked to it by `qms trace scan`
+    pub files: Vec<String>,
+}
+
 /// Impact analysis engine
 pub struct ImpactAnalyzer {
     traceability_manager: TraceabilityManager,
@@ -156,6 +169,70 @@ impl ImpactAnalyzer {
         Ok(analysis)
     }
 
+    /// Requirements touched by the commits in `from..to` of the imported Git history
+    ///
+    /// A commit touches a requirement when its message references the
+    /// requirement's ID or it changes a source file the requirement is linked to.
+    pub fn requirements_touched_between(&self, from: &str, to: &str) -> QmsResult<Vec<TouchedRequirement>> {
+        let history = GitHistoryManager::new(&self.project_root)?.load()?;
+        let commits = history.commits_between(Some(from), to)?;
+
+        let mut linked_files: HashMap<String, Vec<String>> = HashMap::new();
+        for link in self.traceability_manager.get_trace_links()? {
+            if link.target_type == SOURCE_FILE_ENTITY && RecordKind::of(&link.source_id) == Some(RecordKind::Requirement) {
+                linked_files.entry(link.target_id).or_default().push(link.source_id);
+            }
+        }
+
+        let mut touched: BTreeMap<String, TouchedRequirement> = BTreeMap::new();
+        let mut touch = |requirement_id: &str, commit: &str, file: Option<&str>| {
+            let entry = touched.entry(requirement_id.to_string()).or_insert_with(|| TouchedRequirement {
+                requirement_id: requirement_id.to_string(),
+                title: String::new(),
+                commits: Vec::new(),
+                files: Vec::new(),
+            });
+            if !entry.commits.iter().any(|hash| hash == commit) {
+                entry.commits.push(commit.to_string());
+            }
+            if let Some(file) = file {
+                if !entry.files.iter().any(|known| known == file) {
+                    entry.files.push(file.to_string());
+                }
+            }
+        };
+        for commit in &commits {
+            for reference in &commit.references {
+                if RecordKind::of(reference) == Some(RecordKind::Requirement) {
+                    touch(reference, commit.short_hash(), None);
+                }
+            }
+            for file in &commit.files {
+                for requirement_id in linked_files.get(file).into_iter().flatten() {
+                    touch(requirement_id, commit.short_hash(), Some(file));
+                }
+            }
+        }
+
+        let requirements = RequirementManager::new(&self.project_root)?;
+        let touched: Vec<TouchedRequirement> = touched
+            .into_values()
+            .map(|mut requirement| {
+                if let Some(stored) = requirements.get_requirement_by_req_id(&requirement.requirement_id) {
+                    requirement.title = stored.title.clone();
+                }
+                requirement
+            })
+            .collect();
+
+        log_audit(&format!(
+            "IMPACT_ANALYSIS: {} requirement(s) touched by {} commit(s) between {from} and {to}",
+            touched.len(),
+            commits.len()
+        ));
+        Ok(touched)
+    }
+
     /// Analyze direct impacts from traceability links
     fn analyze_direct_impacts(&self, requirement_id: &str, links: &[TraceabilityLink]) -> QmsResult<Vec<ImpactItem>> {
         let mut impacts = Vec::new();
@@ -324,7 +401,7 @@ impl ImpactAnalyzer {
         
         let impact_level = if risk_file.exists() {
             // Try to load the risk to determine severity
-            if let Ok(content) = fs::read_to_string(&risk_file) {
+            if let Ok(content) = crate::modules::storage::encryption::read_to_string(&risk_file) {
                 if content.contains("\"severity\": 5") || content.contains("\"severity\": 4") {
                     ImpactLevel::Critical
                 } else if content.contains("\"severity\": 3") {
@@ -480,7 +557,7 @@ impl ImpactAnalyzer {
             });
         }
         
-        let content = fs::read_to_string(&test_case_file)?;
+        let content = crate::modules::storage::encryption::read_to_string(&test_case_file)?;
         
         // Simple JSON parsing - extract basic fields
         let id = test_case_id.to_string();
@@ -560,7 +637,7 @@ impl ImpactAnalyzer {
             });
         }
         
-        let content = fs::read_to_string(&req_file)?;
+        let content = crate::modules::storage::encryption::read_to_string(&req_file)?;
         
         // Simple JSON parsing - extract basic fields
         let id = requirement_id.to_string();
@@ -792,3 +869,24 @@ pub fn format_impact_analysis(analysis: &ImpactAnalysis) -> String {
     
     report
 }
+
+/// Format the requirements touched between two revisions as a text report
+pub fn format_touched_requirements(from: &str, to: &str, touched: &[TouchedRequirement]) -> String {
+    let mut report = String::new();
+
+    report.push_str(&format!("🔍 REQUIREMENTS TOUCHED BETWEEN {from} AND {to}\n"));
+    report.push_str("────────────────────────────────────────────────────────────────────────────────────────────────────\n");
+    if touched.is_empty() {
+        report.push_str("   No requirements were touched\n");
+        return report;
+    }
+    for requirement in touched {
+        report.push_str(&format!("\n• {} {}\n", requirement.requirement_id, requirement.title));
+        report.push_str(&format!("   Commits: {}\n", requirement.commits.join(", ")));
+        if !requirement.files.is_empty() {
+            report.push_str(&format!("   Linked files changed: {}\n", requirement.files.join(", ")));
+        }
+    }
+    report.push_str(&format!("\nTotal: {} requirement(s)\n", touched.len()));
+    report
+}
@@ -6,7 +6,10 @@ use std::path::Path;
 use std::collections::HashSet;
 use crate::prelude::*;
 use crate::utils::{generate_uuid, current_timestamp};
-use crate::modules::audit_logger::functions::audit_log_create;
+use crate::modules::audit_logger::functions::{audit_log_action, audit_log_create};
+use crate::modules::risk_manager::risk::RiskManager;
+use crate::modules::storage::{self, EntityStore, FileLayout, ProjectEntity, StorageEntity};
+use crate::modules::traceability::requirement::{Requirement, RequirementManager};
 
 #[derive(Debug, Clone)]
 pub struct TraceabilityLink {
@@ -21,6 +24,20 @@ pub struct TraceabilityLink {
     pub verified: bool,
     pub verified_at: Option<String>,
     pub verified_by: Option<String>,
+    /// Where the link was found, for links created by `qms trace scan`
+    pub provenance: Option<LinkProvenance>,
+}
+
+/// Source location of an annotation that produced a trace link
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkProvenance {
+    /// Path of the annotated file, '/'-separated
+    pub file: String,
+    /// 1-based line of the first matching annotation in the file
+    pub line: usize,
+    pub scanned_at: String,
+    /// Set when a later scan no longer finds the annotation
+    pub stale: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -102,7 +119,7 @@ impl std::fmt::Display for TraceLinkType {
 impl TraceabilityLink {
     pub fn to_json(&self) -> String {
         format!(
-            r#"{{"id":"{}","source_type":"{}","source_id":"{}","target_type":"{}","target_id":"{}","link_type":"{}","created_at":"{}","created_by":"{}","verified":{},"verified_at":{},"verified_by":{}}}"#,
+            r#"{{"id":"{}","source_type":"{}","source_id":"{}","target_type":"{}","target_id":"{}","link_type":"{}","created_at":"{}","created_by":"{}","verified":{},"verified_at":{},"verified_by":{}{}}}"#,
             self.id.replace('"', "\\\""),
             self.source_type.replace('"', "\\\""),
             self.source_id.replace('"', "\\\""),
@@ -119,6 +136,16 @@ impl TraceabilityLink {
             match &self.verified_by {
                 Some(v) => format!("\"{}\"", v.replace('"', "\\\"")),
                 None => "null".to_string(),
+            },
+            match &self.provenance {
+                Some(p) => format!(
+                    r#","source_file":"{}","source_line":{},"scanned_at":"{}","stale":{}"#,
+                    p.file.replace('"', "\\\""),
+                    p.line,
+                    p.scanned_at.replace('"', "\\\""),
+                    p.stale
+                ),
+                None => String::new(),
             }
         )
     }
@@ -141,6 +168,10 @@ impl TraceabilityLink {
         let mut verified = false;
         let mut verified_at = None;
         let mut verified_by = None;
+        let mut source_file = None;
+        let mut source_line = 0;
+        let mut scanned_at = String::new();
+        let mut stale = false;
 
         // Parse JSON fields
         let content = &json[1..json.len()-1]; // Remove braces
@@ -172,6 +203,10 @@ impl TraceabilityLink {
                             verified_by = Some(value.trim_matches('"').to_string());
                         }
                     },
+                    "source_file" => source_file = Some(value.trim_matches('"').to_string()),
+                    "source_line" => source_line = value.parse().unwrap_or(0),
+                    "scanned_at" => scanned_at = value.trim_matches('"').to_string(),
+                    "stale" => stale = value == "true",
                     _ => {}
                 }
             }
@@ -191,13 +226,45 @@ impl TraceabilityLink {
             verified,
             verified_at,
             verified_by,
+            provenance: source_file.map(|file| LinkProvenance {
+                file,
+                line: source_line,
+                scanned_at,
+                stale,
+            }),
         })
     }
 }
 
+impl StorageEntity for TraceabilityLink {
+    fn storage_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn to_storage_json(&self) -> String {
+        self.to_json()
+    }
+
+    fn from_storage_json(json: &str) -> QmsResult<Self> {
+        TraceabilityLink::from_json(json)
+    }
+}
+
+impl ProjectEntity for TraceabilityLink {
+    const COLLECTION: &'static str = "trace_links";
+
+    fn file_layout(project_path: &Path) -> FileLayout {
+        FileLayout::Collection {
+            path: project_path.join("trace").join("links.json"),
+            array_key: "links".to_string(),
+        }
+    }
+}
+
 pub struct TraceabilityManager {
     project_root: PathBuf,
-    links_path: PathBuf,
+    links: EntityStore<TraceabilityLink>,
+    requirements: EntityStore<Requirement>,
 }
 
 impl TraceabilityManager {
@@ -213,23 +280,24 @@ impl TraceabilityManager {
         // Initialize links file if it doesn't exist or is corrupted
         if !links_path.exists() {
             let empty_links = r#"{"version":"1.0","links":[]}"#;
-            fs::write(&links_path, empty_links)?;
+            crate::modules::storage::encryption::write(&links_path, empty_links)?;
         } else {
             // Check if existing file is valid JSON, if not, reinitialize
-            if let Ok(content) = fs::read_to_string(&links_path) {
+            if let Ok(content) = crate::modules::storage::encryption::read_to_string(&links_path) {
                 // Try to parse the JSON to detect corruption
                 if !content.trim().starts_with('{') || !content.trim().ends_with('}') ||
                    !content.contains("\"version\"") || !content.contains("\"links\"") {
                     // File appears corrupted, reinitialize
                     let empty_links = r#"{"version":"1.0","links":[]}"#;
-                    fs::write(&links_path, empty_links)?;
+                    crate::modules::storage::encryption::write(&links_path, empty_links)?;
                 }
             }
         }
         
         Ok(TraceabilityManager {
             project_root: project_root.to_path_buf(),
-            links_path,
+            links: EntityStore::open(project_root)?,
+            requirements: EntityStore::open(project_root)?,
         })
     }
 
@@ -238,6 +306,39 @@ impl TraceabilityManager {
         source_id: &str,
         target_id: &str,
         link_type: TraceLinkType,
+    ) -> QmsResult<TraceabilityLink> {
+        let link = self.add_trace_link(source_id, target_id, link_type)?;
+        
+        // Log audit entry
+        let _ = audit_log_create("TraceabilityLink", &link.id, &link.to_json());
+        
+        Ok(link)
+    }
+
+    /// Link a risk to a requirement
+    ///
+    /// The requirement's `linked_risks` entry and the trace link are written in
+    /// one storage transaction, so on the SQLite backend a failure in either
+    /// leaves neither in place.
+    pub fn link_risk_to_requirement(&self, req_id: &str, risk_id: &str) -> QmsResult<TraceabilityLink> {
+        let link = storage::with_transaction(&self.project_root, || {
+            let mut requirements = RequirementManager::new(&self.project_root)?;
+            requirements.add_linked_risk(req_id, risk_id)?;
+            self.add_trace_link(req_id, risk_id, TraceLinkType::Related)
+        })?;
+
+        let _ = audit_log_create("TraceabilityLink", &link.id, &link.to_json());
+        let _ = audit_log_action("RISK_LINKED", "Requirement", &format!("{req_id} linked to risk {risk_id}"));
+
+        Ok(link)
+    }
+
+    /// Validate and store a new link
+    fn add_trace_link(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        link_type: TraceLinkType,
     ) -> QmsResult<TraceabilityLink> {
         // Validate entities exist
         self.validate_entities_exist(source_id, target_id)?;
@@ -260,21 +361,17 @@ impl TraceabilityManager {
             verified: false,
             verified_at: None,
             verified_by: None,
+            provenance: None,
         };
         
         // Save link
         self.save_trace_link(&link)?;
         
-        // Log audit entry
-        let _ = audit_log_create("TraceabilityLink", &link.id, &link.to_json());
-        
         Ok(link)
     }
 
     pub fn get_trace_links(&self) -> QmsResult<Vec<TraceabilityLink>> {
-        let content = fs::read_to_string(&self.links_path)?;
-        let links_data = self.parse_links_json(&content)?;
-        Ok(links_data)
+        self.links.reader().read_all()
     }
 
     pub fn get_links_for_entity(&self, entity_id: &str) -> QmsResult<Vec<TraceabilityLink>> {
@@ -287,16 +384,19 @@ impl TraceabilityManager {
     }
 
     pub fn delete_trace_link(&self, link_id: &str) -> QmsResult<()> {
-        let mut links = self.get_trace_links()?;
-        let initial_count = links.len();
-        links.retain(|link| link.id != link_id);
-        
-        if links.len() == initial_count {
+        if !self.links.reader().exists(link_id)? {
             return Err(QmsError::not_found(&format!("Link with ID {link_id} not found")));
         }
         
-        self.save_all_links(&links)?;
-        Ok(())
+        self.links.writer().delete(link_id)
+    }
+
+    /// Store links produced by `qms trace scan`, replacing any with the same ID
+    pub fn save_scanned_links(&self, links: &[TraceabilityLink]) -> QmsResult<()> {
+        if links.is_empty() {
+            return Ok(());
+        }
+        self.links.writer().save_batch(links)
     }
 
     fn validate_entities_exist(&self, source_id: &str, target_id: &str) -> QmsResult<()> {
@@ -313,13 +413,11 @@ impl TraceabilityManager {
         Ok(())
     }
 
-    fn entity_exists(&self, entity_id: &str) -> QmsResult<bool> {
+    pub fn entity_exists(&self, entity_id: &str) -> QmsResult<bool> {
         // Check different entity types based on ID prefix
         if entity_id.starts_with("REQ-") {
             // Check requirements - handle both with and without spaces in JSON
-            let req_path = self.project_root.join("trace").join("requirements.json");
-            if req_path.exists() {
-                let content = fs::read_to_string(&req_path)?;
+            if let Some(content) = self.requirements_content()? {
                 return Ok(content.contains(&format!("\"req_id\": \"{entity_id}\"")) ||
                          content.contains(&format!("\"req_id\":\"{entity_id}\"")));
             }
@@ -327,7 +425,7 @@ impl TraceabilityManager {
             // Check test cases - handle both with and without spaces in JSON
             let test_path = self.project_root.join("trace").join("testcases.json");
             if test_path.exists() {
-                let content = fs::read_to_string(&test_path)?;
+                let content = crate::modules::storage::encryption::read_to_string(&test_path)?;
                 return Ok(content.contains(&format!("\"test_id\": \"{entity_id}\"")) ||
                          content.contains(&format!("\"test_id\":\"{entity_id}\"")));
             }
@@ -335,7 +433,7 @@ impl TraceabilityManager {
             // Check risks - handle both with and without spaces in JSON
             let risk_path = self.project_root.join("risks").join("risks.json");
             if risk_path.exists() {
-                let content = fs::read_to_string(&risk_path)?;
+                let content = crate::modules::storage::encryption::read_to_string(&risk_path)?;
                 return Ok(content.contains(&format!("\"id\": \"{entity_id}\"")) ||
                          content.contains(&format!("\"id\":\"{entity_id}\"")));
             }
@@ -343,24 +441,33 @@ impl TraceabilityManager {
             // Check documents - handle both with and without spaces in JSON
             let doc_path = self.project_root.join("documents").join("documents.json");
             if doc_path.exists() {
-                let content = fs::read_to_string(&doc_path)?;
+                let content = crate::modules::storage::encryption::read_to_string(&doc_path)?;
                 return Ok(content.contains(&format!("\"id\": \"{entity_id}\"")) ||
                          content.contains(&format!("\"id\":\"{entity_id}\"")));
             }
+        } else if entity_id.starts_with("CAPA-") {
+            // CAPA records are stored one file per record
+            return Ok(self.project_root.join("capa").join(format!("{entity_id}.json")).exists());
+        } else if entity_id.starts_with("HAZ-") {
+            // Risks are referenced by hazard ID
+            let risks = RiskManager::new(&self.project_root)?.list_risks(None)?;
+            return Ok(risks.iter().any(|risk| risk.hazard_id == entity_id));
         }
 
         Ok(false)
     }
 
-    fn get_entity_type(&self, entity_id: &str) -> QmsResult<String> {
+    pub fn get_entity_type(&self, entity_id: &str) -> QmsResult<String> {
         if entity_id.starts_with("REQ-") {
             Ok("Requirement".to_string())
         } else if entity_id.starts_with("TC-") {
             Ok("TestCase".to_string())
-        } else if entity_id.starts_with("RISK-") {
+        } else if entity_id.starts_with("RISK-") || entity_id.starts_with("HAZ-") {
             Ok("Risk".to_string())
         } else if entity_id.starts_with("DOC-") {
             Ok("Document".to_string())
+        } else if entity_id.starts_with("CAPA-") {
+            Ok("CAPA".to_string())
         } else {
             Err(QmsError::validation_error(&format!("Unknown entity type for ID: {entity_id}")))
         }
@@ -476,108 +583,38 @@ impl TraceabilityManager {
     }
 
     fn save_trace_link(&self, link: &TraceabilityLink) -> QmsResult<()> {
-        let mut links = self.get_trace_links()?;
-        links.push(link.clone());
-        self.save_all_links(&links)
+        self.links.writer().save(link)
     }
 
+    /// Replace the stored links with `links`
     fn save_all_links(&self, links: &[TraceabilityLink]) -> QmsResult<()> {
-        let mut json_content = String::new();
-        json_content.push_str(r#"{"version":"1.0","links":["#);
-        
-        for (i, link) in links.iter().enumerate() {
-            if i > 0 {
-                json_content.push(',');
-            }
-            json_content.push_str(&link.to_json());
-        }
-        
-        json_content.push_str("]}");
-        
-        fs::write(&self.links_path, json_content)?;
-        Ok(())
+        let removed: Vec<String> = self.get_trace_links()?
+            .into_iter()
+            .filter(|existing| !links.iter().any(|link| link.id == existing.id))
+            .map(|existing| existing.id)
+            .collect();
+        self.links.writer().delete_batch(&removed)?;
+        self.links.writer().save_batch(links)
     }
 
-    fn parse_links_json(&self, json_content: &str) -> QmsResult<Vec<TraceabilityLink>> {
-        let json = json_content.trim();
-        if !json.starts_with('{') || !json.ends_with('}') {
-            return Err(QmsError::parse_error("Invalid JSON format"));
-        }
-
-        // Find the links array
-        let links_start = json.find(r#""links":["#);
-        if links_start.is_none() {
-            return Ok(Vec::new());
-        }
-
-        let links_start = links_start.unwrap() + 8; // Skip `"links":[`
-        let links_end = json.rfind("]");
-        if links_end.is_none() {
-            return Err(QmsError::parse_error("Invalid links array format"));
-        }
-
-        let links_content = &json[links_start..links_end.unwrap()];
-        if links_content.trim().is_empty() {
-            return Ok(Vec::new());
+    /// Stored requirements as JSON text, whichever backend holds them
+    fn requirements_content(&self) -> QmsResult<Option<String>> {
+        if !self.requirements.is_database() {
+            let req_path = self.project_root.join("trace").join("requirements.json");
+            return Ok(if req_path.exists() { Some(crate::modules::storage::encryption::read_to_string(&req_path)?) } else { None });
         }
 
-
-
-        let mut links = Vec::new();
-        let mut brace_count = 0;
-        let mut current_link = String::new();
-        let mut in_string = false;
-        let mut escape_next = false;
-
-        for ch in links_content.chars() {
-            if escape_next {
-                current_link.push(ch);
-                escape_next = false;
-                continue;
-            }
-
-            match ch {
-                '\\' if in_string => {
-                    current_link.push(ch);
-                    escape_next = true;
-                },
-                '"' => {
-                    in_string = !in_string;
-                    current_link.push(ch);
-                },
-                '{' if !in_string => {
-                    brace_count += 1;
-                    current_link.push(ch);
-                },
-                '}' if !in_string => {
-                    brace_count -= 1;
-                    current_link.push(ch);
-                    if brace_count == 0 {
-                        let link = TraceabilityLink::from_json(&current_link)?;
-                        links.push(link);
-                        current_link.clear();
-                    }
-                },
-                ',' if !in_string && brace_count == 0 => {
-                    // Skip comma between links
-                },
-                '[' | ']' if !in_string && brace_count == 0 => {
-                    // Skip array brackets when not inside an object
-                },
-                ' ' | '\n' | '\t' if !in_string && brace_count == 0 => {
-                    // Skip whitespace when not inside an object
-                },
-                _ if brace_count > 0 => {
-                    // Only add characters when we're inside an object
-                    current_link.push(ch);
-                },
-                _ => {
-                    // Skip other characters when not inside an object
-                },
-            }
+        let requirements = self.requirements.reader().read_all()?;
+        if requirements.is_empty() {
+            return Ok(None);
         }
-
-        Ok(links)
+        Ok(Some(
+            requirements
+                .iter()
+                .map(|requirement| requirement.to_json())
+                .collect::<Vec<_>>()
+                .join(",\n"),
+        ))
     }
 
     /// Trace forward from a source entity to all connected targets
@@ -726,10 +763,8 @@ impl TraceabilityManager {
     // Helper functions for finding orphaned items by type
     fn find_orphaned_requirements(&self, linked_entities: &HashSet<String>) -> QmsResult<Vec<OrphanedItem>> {
         let mut orphans = Vec::new();
-        let req_path = self.project_root.join("trace").join("requirements.json");
         
-        if req_path.exists() {
-            let content = fs::read_to_string(&req_path)?;
+        if let Some(content) = self.requirements_content()? {
             if let Ok(requirements) = self.parse_requirements_json(&content) {
                 for req in requirements {
                     if !linked_entities.contains(&req) {
@@ -751,7 +786,7 @@ impl TraceabilityManager {
         let test_path = self.project_root.join("trace").join("testcases.json");
         
         if test_path.exists() {
-            let content = fs::read_to_string(&test_path)?;
+            let content = crate::modules::storage::encryption::read_to_string(&test_path)?;
             if let Ok(test_cases) = self.parse_test_cases_json(&content) {
                 for test_case in test_cases {
                     if !linked_entities.contains(&test_case) {
@@ -773,7 +808,7 @@ impl TraceabilityManager {
         let risk_path = self.project_root.join("risks").join("risks.json");
         
         if risk_path.exists() {
-            let content = fs::read_to_string(&risk_path)?;
+            let content = crate::modules::storage::encryption::read_to_string(&risk_path)?;
             if let Ok(risks) = self.parse_risks_json(&content) {
                 for risk in risks {
                     if !linked_entities.contains(&risk) {
@@ -795,7 +830,7 @@ impl TraceabilityManager {
         let doc_path = self.project_root.join("documents").join("documents.json");
         
         if doc_path.exists() {
-            let content = fs::read_to_string(&doc_path)?;
+            let content = crate::modules::storage::encryption::read_to_string(&doc_path)?;
             if let Ok(documents) = self.parse_documents_json(&content) {
                 for document in documents {
                     if !linked_entities.contains(&document) {
@@ -931,6 +966,7 @@ mod tests {
             verified: false,
             verified_at: None,
             verified_by: None,
+            provenance: None,
         };
 
         let json = link.to_json();
@@ -942,6 +978,47 @@ mod tests {
         assert!(matches!(parsed_link.link_type, TraceLinkType::Verifies));
     }
 
+    #[test]
+    fn test_link_risk_to_requirement_is_atomic_on_sqlite() {
+        use crate::modules::storage::{StorageConfig, StorageType};
+        use crate::modules::traceability::requirement::RequirementCategory;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let project = temp_dir.path();
+        StorageConfig::set_project_backend(project, &StorageType::Database).unwrap();
+
+        let mut requirements = RequirementManager::new(project).unwrap();
+        requirements.create_requirement(
+            "project".to_string(),
+            "REQ-001".to_string(),
+            "Alarm on occlusion".to_string(),
+            "The pump shall alarm when an occlusion is detected".to_string(),
+            RequirementCategory::Safety,
+            "tester".to_string(),
+        ).unwrap();
+        let mut risks = RiskManager::new(project).unwrap();
+        risks.initialize().unwrap();
+        let first = risks.create_risk("Occlusion", "Line blocked", "Under-infusion").unwrap();
+        let second = risks.create_risk("Air in line", "Bubble passes", "Embolism").unwrap();
+
+        let manager = TraceabilityManager::new(project).unwrap();
+        let link = manager.link_risk_to_requirement("REQ-001", &first.hazard_id).unwrap();
+        assert_eq!(link.target_type, "Risk");
+        let linked = |project: &Path| {
+            RequirementManager::new(project).unwrap()
+                .get_requirement_by_req_id("REQ-001").unwrap()
+                .linked_risks.clone()
+        };
+        assert_eq!(linked(project), vec![first.hazard_id.clone()]);
+
+        // The requirement is updated before the link is rejected as a duplicate;
+        // the rollback must undo that update
+        manager.create_trace_link("REQ-001", &second.hazard_id, TraceLinkType::Related).unwrap();
+        assert!(manager.link_risk_to_requirement("REQ-001", &second.hazard_id).is_err());
+        assert_eq!(linked(project), vec![first.hazard_id.clone()]);
+        assert_eq!(manager.get_trace_links().unwrap().len(), 2);
+    }
+
     #[test]
     fn test_traceability_manager_creation() {
         let temp_dir = env::temp_dir().join("qms_test_trace");
@@ -981,6 +1058,7 @@ mod tests {
                 verified: false,
                 verified_at: None,
                 verified_by: None,
+                provenance: None,
             },
         ];
         
@@ -1020,6 +1098,7 @@ mod tests {
             verified: false,
             verified_at: None,
             verified_by: None,
+            provenance: None,
         };
         
         let link2 = TraceabilityLink {
@@ -1034,6 +1113,7 @@ mod tests {
             verified: false,
             verified_at: None,
             verified_by: None,
+            provenance: None,
         };
         
         manager.save_all_links(&[link1, link2]).unwrap();
@@ -1065,6 +1145,7 @@ mod tests {
             verified: false,
             verified_at: None,
             verified_by: None,
+            provenance: None,
         };
         
         let link2 = TraceabilityLink {
@@ -1079,6 +1160,7 @@ mod tests {
             verified: false,
             verified_at: None,
             verified_by: None,
+            provenance: None,
         };
         
         manager.save_all_links(&[link1, link2]).unwrap();
@@ -1110,6 +1192,7 @@ mod tests {
             verified: false,
             verified_at: None,
             verified_by: None,
+            provenance: None,
         };
         
         let link2 = TraceabilityLink {
@@ -1124,6 +1207,7 @@ mod tests {
             verified: false,
             verified_at: None,
             verified_by: None,
+            provenance: None,
         };
         
         manager.save_all_links(&[link1, link2]).unwrap();
@@ -1161,6 +1245,7 @@ mod tests {
             verified: false,
             verified_at: None,
             verified_by: None,
+            provenance: None,
         };
         
         manager.save_all_links(&[link]).unwrap();
@@ -1191,6 +1276,7 @@ mod tests {
             verified: false,
             verified_at: None,
             verified_by: None,
+            provenance: None,
         };
         
         let link2 = TraceabilityLink {
@@ -1205,6 +1291,7 @@ mod tests {
             verified: false,
             verified_at: None,
             verified_by: None,
+            provenance: None,
         };
         
         manager.save_all_links(&[link1, link2]).unwrap();
@@ -1240,6 +1327,7 @@ mod tests {
             verified: false,
             verified_at: None,
             verified_by: None,
+            provenance: None,
         };
         
         let link2 = TraceabilityLink {
@@ -1254,6 +1342,7 @@ mod tests {
             verified: false,
             verified_at: None,
             verified_by: None,
+            provenance: None,
         };
         
         manager.save_all_links(&[link1, link2]).unwrap();
@@ -1286,6 +1375,7 @@ mod tests {
             verified: false,
             verified_at: None,
             verified_by: None,
+            provenance: None,
         };
         
         manager.save_all_links(&[link]).unwrap();
@@ -1463,10 +1553,8 @@ impl TraceabilityManager {
     fn get_entity_title(&self, entity_id: &str, entity_type: &str) -> QmsResult<String> {
         match entity_type {
             "Requirement" => {
-                // Read requirements.json and extract title
-                let req_path = self.project_root.join("trace").join("requirements.json");
-                if req_path.exists() {
-                    let content = fs::read_to_string(&req_path)?;
+                // Read requirements and extract title
+                if let Some(content) = self.requirements_content()? {
                     if let Ok(title) = self.extract_requirement_title(&content, entity_id) {
                         return Ok(title);
                     }
@@ -1491,9 +1579,7 @@ impl TraceabilityManager {
     fn get_entity_status(&self, entity_id: &str, entity_type: &str) -> QmsResult<String> {
         match entity_type {
             "Requirement" => {
-                let req_path = self.project_root.join("trace").join("requirements.json");
-                if req_path.exists() {
-                    let content = fs::read_to_string(&req_path)?;
+                if let Some(content) = self.requirements_content()? {
                     if let Ok(status) = self.extract_requirement_status(&content, entity_id) {
                         return Ok(status);
                     }
@@ -1777,6 +1863,7 @@ impl TraceabilityManager {
             verified: false,
             verified_at: None,
             verified_by: None,
+            provenance: None,
         };
         
         for line in lines {
@@ -1834,6 +1921,7 @@ impl TraceabilityManager {
                         verified: false,
                         verified_at: None,
                         verified_by: None,
+                        provenance: None,
                     };
                     in_link_object = false;
                 }
@@ -7,6 +7,9 @@ pub mod visualization;
 pub mod coverage;
 pub mod impact;
 pub mod verification;
+pub mod test_results;
+pub mod reqif;
+pub mod source_scan;
 
 #[cfg(test)]
 pub mod integration_tests;
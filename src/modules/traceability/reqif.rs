@@ -0,0 +1,1056 @@
+//! ReqIF 1.2 requirements interchange
+//! Imports spec objects as requirements and spec relations as trace links, and exports them
+//! back under their original identifiers so DOORS and Polarion exchanges round-trip.
+
+use crate::audit::log_audit;
+use crate::error::{QmsError, QmsResult};
+use crate::json_utils::JsonValue;
+use crate::modules::traceability::links::{TraceLinkType, TraceabilityManager};
+use crate::modules::traceability::requirement::{
+    Requirement, RequirementCategory, RequirementManager, RequirementPriority, RequirementStatus,
+    RequirementUpdate, VerificationMethod,
+};
+use crate::utils::{current_timestamp, generate_uuid, iso8601_from_unix};
+use crate::xml_utils::XmlElement;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+const REQIF_NAMESPACE: &str = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd";
+const XHTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+
+/// Requirement field a ReqIF attribute can be mapped to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ReqIfField {
+    ReqId,
+    Title,
+    Description,
+    Category,
+    Priority,
+    VerificationMethod,
+    Status,
+    Source,
+    Rationale,
+    AcceptanceCriteria,
+}
+
+impl ReqIfField {
+    pub const ALL: [ReqIfField; 10] = [
+        ReqIfField::ReqId,
+        ReqIfField::Title,
+        ReqIfField::Description,
+        ReqIfField::Category,
+        ReqIfField::Priority,
+        ReqIfField::VerificationMethod,
+        ReqIfField::Status,
+        ReqIfField::Source,
+        ReqIfField::Rationale,
+        ReqIfField::AcceptanceCriteria,
+    ];
+
+    pub fn parse(name: &str) -> QmsResult<Self> {
+        let normalized = name.trim().to_lowercase().replace(['-', ' '], "_");
+        Self::ALL
+            .into_iter()
+            .find(|field| field.name() == normalized)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = Self::ALL.iter().map(|f| f.name()).collect();
+                QmsError::validation_error(&format!(
+                    "Unknown requirement field '{name}'. Valid fields: {}",
+                    valid.join(", ")
+                ))
+            })
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            ReqIfField::ReqId => "req_id",
+            ReqIfField::Title => "title",
+            ReqIfField::Description => "description",
+            ReqIfField::Category => "category",
+            ReqIfField::Priority => "priority",
+            ReqIfField::VerificationMethod => "verification_method",
+            ReqIfField::Status => "status",
+            ReqIfField::Source => "source",
+            ReqIfField::Rationale => "rationale",
+            ReqIfField::AcceptanceCriteria => "acceptance_criteria",
+        }
+    }
+
+    /// Attribute long name used on export when the mapping file names none
+    pub const fn default_attribute(&self) -> &'static str {
+        match self {
+            ReqIfField::ReqId => "ReqIF.ForeignID",
+            ReqIfField::Title => "ReqIF.Name",
+            ReqIfField::Description => "ReqIF.Text",
+            ReqIfField::Category => "Category",
+            ReqIfField::Priority => "Priority",
+            ReqIfField::VerificationMethod => "Verification Method",
+            ReqIfField::Status => "Status",
+            ReqIfField::Source => "Source",
+            ReqIfField::Rationale => "Rationale",
+            ReqIfField::AcceptanceCriteria => "Acceptance Criteria",
+        }
+    }
+
+    /// Field for the standard and common tool attribute names
+    fn from_default_attribute(long_name: &str) -> Option<Self> {
+        match long_name {
+            "ReqIF.ChapterName" | "Object Heading" | "Title" => Some(ReqIfField::Title),
+            "Object Text" | "Description" => Some(ReqIfField::Description),
+            _ => Self::ALL
+                .into_iter()
+                .find(|field| field.default_attribute().eq_ignore_ascii_case(long_name)),
+        }
+    }
+
+    const fn is_enumeration(&self) -> bool {
+        matches!(
+            self,
+            ReqIfField::Category | ReqIfField::Priority | ReqIfField::VerificationMethod | ReqIfField::Status
+        )
+    }
+
+    /// Values an enumeration field can take
+    const fn standard_values(&self) -> &'static [&'static str] {
+        match self {
+            ReqIfField::Category => &[
+                "functional", "performance", "usability", "reliability", "safety",
+                "security", "regulatory", "interface", "data", "system",
+            ],
+            ReqIfField::Priority => &["critical", "high", "medium", "low"],
+            ReqIfField::VerificationMethod => &["test", "analysis", "inspection", "demonstration", "review"],
+            ReqIfField::Status => &[
+                "draft", "under_review", "approved", "implemented", "verified", "validated", "obsolete",
+            ],
+            _ => &[],
+        }
+    }
+
+    fn value_of(&self, requirement: &Requirement) -> String {
+        match self {
+            ReqIfField::ReqId => requirement.req_id.clone(),
+            ReqIfField::Title => requirement.title.clone(),
+            ReqIfField::Description => requirement.description.clone(),
+            ReqIfField::Category => requirement.category.as_str().to_string(),
+            ReqIfField::Priority => requirement.priority.as_str().to_string(),
+            ReqIfField::VerificationMethod => requirement.verification_method.as_str().to_string(),
+            ReqIfField::Status => requirement.status.as_str().to_string(),
+            ReqIfField::Source => requirement.source.clone(),
+            ReqIfField::Rationale => requirement.rationale.clone(),
+            ReqIfField::AcceptanceCriteria => requirement.acceptance_criteria.clone(),
+        }
+    }
+
+    /// Identifier fragment used for the generated attribute and datatype definitions
+    fn identifier(&self) -> String {
+        self.name().replace('_', "-")
+    }
+}
+
+/// Attribute, enumeration value and relation type mapping
+///
+/// The mapping file is JSON:
+///
+/// ```json
+/// {
+///   "attributes": { "Object Text": "description", "Safety Class": "priority" },
+///   "values": { "priority": { "Class C": "critical", "Class B": "high" } },
+///   "relations": { "satisfies": "derived_from" }
+/// }
+/// ```
+///
+/// Mapped attribute names and value labels are also used on export.
+#[derive(Debug, Clone, Default)]
+pub struct ReqIfMapping {
+    attributes: Vec<(String, ReqIfField)>,
+    values: HashMap<ReqIfField, Vec<(String, String)>>, // (ReqIF label, QMS value)
+    relations: Vec<(String, String)>,                   // (relation type long name, link type)
+}
+
+impl ReqIfMapping {
+    pub fn load(path: &Path) -> QmsResult<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn parse(content: &str) -> QmsResult<Self> {
+        let JsonValue::Object(root) = JsonValue::parse(content)? else {
+            return Err(QmsError::parse_error("ReqIF mapping must be a JSON object"));
+        };
+        let mut mapping = Self::default();
+
+        if let Some(attributes) = root.get("attributes") {
+            for (long_name, field) in string_entries(attributes, "attributes")? {
+                mapping.attributes.push((long_name, ReqIfField::parse(&field)?));
+            }
+        }
+        if let Some(JsonValue::Object(values)) = root.get("values") {
+            for (field, labels) in values {
+                let field = ReqIfField::parse(field)?;
+                mapping.values.insert(field, string_entries(labels, field.name())?);
+            }
+        }
+        if let Some(relations) = root.get("relations") {
+            for (relation, link_type) in string_entries(relations, "relations")? {
+                mapping.relations.push((relation, TraceLinkType::from_str(&link_type)?.to_string()));
+            }
+        }
+        Ok(mapping)
+    }
+
+    /// Field an attribute maps to; the mapping file wins over the standard names
+    pub fn field_for(&self, long_name: &str) -> Option<ReqIfField> {
+        self.attributes
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(long_name))
+            .map(|(_, field)| *field)
+            .or_else(|| ReqIfField::from_default_attribute(long_name))
+    }
+
+    fn attribute_name(&self, field: ReqIfField) -> String {
+        self.attributes
+            .iter()
+            .find(|(_, mapped)| *mapped == field)
+            .map_or_else(|| field.default_attribute().to_string(), |(name, _)| name.clone())
+    }
+
+    fn import_value(&self, field: ReqIfField, label: &str) -> String {
+        self.values
+            .get(&field)
+            .and_then(|labels| labels.iter().find(|(l, _)| l.eq_ignore_ascii_case(label)))
+            .map_or_else(|| label.to_string(), |(_, value)| value.clone())
+    }
+
+    fn export_value(&self, field: ReqIfField, value: &str) -> String {
+        self.values
+            .get(&field)
+            .and_then(|labels| labels.iter().find(|(_, v)| v.eq_ignore_ascii_case(value)))
+            .map_or_else(|| value.to_string(), |(label, _)| label.clone())
+    }
+
+    fn link_type_for(&self, relation_type: &str) -> TraceLinkType {
+        let name = self
+            .relations
+            .iter()
+            .find(|(relation, _)| relation.eq_ignore_ascii_case(relation_type))
+            .map_or(relation_type, |(_, link_type)| link_type.as_str());
+        TraceLinkType::from_str(name).unwrap_or(TraceLinkType::Related)
+    }
+
+    fn relation_type_name(&self, link_type: &TraceLinkType) -> String {
+        let link_type = link_type.to_string();
+        self.relations
+            .iter()
+            .find(|(_, mapped)| *mapped == link_type)
+            .map_or(link_type, |(relation, _)| relation.clone())
+    }
+}
+
+/// String-valued entries of a JSON object
+fn string_entries(value: &JsonValue, section: &str) -> QmsResult<Vec<(String, String)>> {
+    let JsonValue::Object(obj) = value else {
+        return Err(QmsError::parse_error(&format!("ReqIF mapping '{section}' must be an object")));
+    };
+    let mut entries: Vec<(String, String)> = obj
+        .iter()
+        .map(|(key, value)| {
+            value
+                .as_string()
+                .map(|v| (key.clone(), v.clone()))
+                .ok_or_else(|| QmsError::parse_error(&format!("ReqIF mapping '{section}.{key}' must be a string")))
+        })
+        .collect::<QmsResult<_>>()?;
+    // Object key order is not preserved; keep lookups deterministic
+    entries.sort();
+    Ok(entries)
+}
+
+/// ReqIF identifiers of imported spec objects and relations, kept so exports reuse them
+#[derive(Debug, Default)]
+struct ReqIfIdentifiers {
+    objects: BTreeMap<String, String>,   // SPEC-OBJECT identifier -> req_id
+    relations: BTreeMap<String, String>, // SPEC-RELATION identifier -> trace link id
+}
+
+impl ReqIfIdentifiers {
+    fn path(project_path: &Path) -> PathBuf {
+        project_path.join("trace").join("reqif_identifiers.json")
+    }
+
+    fn load(project_path: &Path) -> QmsResult<Self> {
+        let path = Self::path(project_path);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = crate::modules::storage::encryption::read_to_string(&path)?;
+        let JsonValue::Object(root) = JsonValue::parse(&content)? else {
+            return Err(QmsError::parse_error("Invalid ReqIF identifier map"));
+        };
+        let section = |key: &str| -> BTreeMap<String, String> {
+            match root.get(key) {
+                Some(JsonValue::Object(map)) => map
+                    .iter()
+                    .filter_map(|(k, v)| v.as_string().map(|v| (k.clone(), v.clone())))
+                    .collect(),
+                _ => BTreeMap::new(),
+            }
+        };
+        Ok(Self {
+            objects: section("spec_objects"),
+            relations: section("spec_relations"),
+        })
+    }
+
+    fn save(&self, project_path: &Path) -> QmsResult<()> {
+        let section = |map: &BTreeMap<String, String>| {
+            JsonValue::Object(map.iter().map(|(k, v)| (k.clone(), JsonValue::String(v.clone()))).collect())
+        };
+        let mut root = HashMap::new();
+        root.insert("version".to_string(), JsonValue::String("1.0".to_string()));
+        root.insert("spec_objects".to_string(), section(&self.objects));
+        root.insert("spec_relations".to_string(), section(&self.relations));
+        std::fs::create_dir_all(project_path.join("trace"))?;
+        crate::modules::storage::encryption::write(Self::path(project_path), JsonValue::Object(root).json_to_string())?;
+        Ok(())
+    }
+
+    fn object_identifier(&self, requirement: &Requirement) -> String {
+        self.objects
+            .iter()
+            .find(|(_, req_id)| **req_id == requirement.req_id)
+            .map_or_else(|| format!("_{}", requirement.id), |(identifier, _)| identifier.clone())
+    }
+
+    fn relation_identifier(&self, link_id: &str) -> String {
+        self.relations
+            .iter()
+            .find(|(_, id)| *id == link_id)
+            .map_or_else(|| format!("_{link_id}"), |(identifier, _)| identifier.clone())
+    }
+}
+
+/// Outcome of a ReqIF import
+#[derive(Debug, Default)]
+pub struct ReqIfImportStats {
+    pub spec_objects: usize,
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub links_created: usize,
+    pub links_existing: usize,
+    pub errors: Vec<String>,
+    pub unmapped_attributes: BTreeSet<String>,
+}
+
+/// Spec object read from the exchange file
+struct ImportedObject {
+    identifier: String,
+    fields: HashMap<ReqIfField, String>,
+}
+
+/// Import spec objects as requirements and spec relations between them as trace links
+///
+/// A spec object updates the requirement it was imported as before, or the one named by its
+/// mapped `req_id` attribute; otherwise it becomes a new requirement with the next free ID.
+pub fn import_reqif(project_path: &Path, content: &str, mapping: &ReqIfMapping) -> QmsResult<ReqIfImportStats> {
+    let root = XmlElement::parse(content)?;
+    if root.local_name() != "REQ-IF" {
+        return Err(QmsError::parse_error(&format!("Expected a REQ-IF document, found <{}>", root.name)));
+    }
+    let reqif_content = root
+        .child("CORE-CONTENT")
+        .and_then(|core| core.child("REQ-IF-CONTENT"))
+        .ok_or_else(|| QmsError::parse_error("ReqIF document has no REQ-IF-CONTENT"))?;
+
+    let mut names = HashMap::new();
+    collect_long_names(reqif_content, &mut names);
+
+    let mut stats = ReqIfImportStats::default();
+    let objects: Vec<ImportedObject> = reqif_content
+        .child("SPEC-OBJECTS")
+        .map(|objects| objects.children_named("SPEC-OBJECT").collect::<Vec<_>>())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|object| read_spec_object(object, &names, mapping, &mut stats.unmapped_attributes))
+        .collect();
+    stats.spec_objects = objects.len();
+
+    let mut manager = RequirementManager::new(project_path)?;
+    let mut identifiers = ReqIfIdentifiers::load(project_path)?;
+    let req_ids = resolve_req_ids(&objects, &manager, &identifiers, &mut stats.errors);
+
+    let project_id = crate::utils::user_context::get_current_project_id();
+    let user_id = crate::utils::user_context::get_current_user_id();
+    for (object, req_id) in objects.iter().zip(&req_ids) {
+        let Some(req_id) = req_id else {
+            continue;
+        };
+        match apply_spec_object(&mut manager, object, req_id, &project_id, &user_id) {
+            Ok(true) => stats.created.push(req_id.clone()),
+            Ok(false) => stats.updated.push(req_id.clone()),
+            Err(e) => {
+                stats.errors.push(format!("{} ({req_id}): {e}", object.identifier));
+                continue;
+            }
+        }
+        identifiers.objects.insert(object.identifier.clone(), req_id.clone());
+    }
+
+    if let Some(relations) = reqif_content.child("SPEC-RELATIONS") {
+        import_relations(project_path, relations, &names, mapping, &mut identifiers, &mut stats)?;
+    }
+
+    identifiers.save(project_path)?;
+    log_audit("reqif_imported");
+    Ok(stats)
+}
+
+/// Map every IDENTIFIER in the content to its LONG-NAME
+fn collect_long_names(element: &XmlElement, names: &mut HashMap<String, String>) {
+    if let Some(identifier) = element.attr("IDENTIFIER") {
+        let long_name = element.attr("LONG-NAME").unwrap_or(identifier);
+        names.insert(identifier.to_string(), long_name.to_string());
+    }
+    for child in element.elements() {
+        collect_long_names(child, names);
+    }
+}
+
+/// Text of the first reference element below `element`'s `container` child
+fn reference(element: &XmlElement, container: &str) -> Option<String> {
+    element
+        .child(container)
+        .and_then(|c| c.elements().next())
+        .map(|r| r.text().trim().to_string())
+}
+
+fn read_spec_object(
+    object: &XmlElement,
+    names: &HashMap<String, String>,
+    mapping: &ReqIfMapping,
+    unmapped: &mut BTreeSet<String>,
+) -> ImportedObject {
+    let mut fields = HashMap::new();
+    for value in object.child("VALUES").into_iter().flat_map(|values| values.elements()) {
+        let Some(definition) = reference(value, "DEFINITION") else {
+            continue;
+        };
+        let long_name = names.get(&definition).cloned().unwrap_or(definition);
+        let text = match value.local_name() {
+            "ATTRIBUTE-VALUE-XHTML" => value.child("THE-VALUE").map(xhtml_to_text).unwrap_or_default(),
+            "ATTRIBUTE-VALUE-ENUMERATION" => value
+                .child("VALUES")
+                .into_iter()
+                .flat_map(|refs| refs.elements())
+                .map(|r| {
+                    let identifier = r.text().trim().to_string();
+                    names.get(&identifier).cloned().unwrap_or(identifier)
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => value.attr("THE-VALUE").unwrap_or_default().to_string(),
+        };
+
+        match mapping.field_for(&long_name) {
+            Some(field) => {
+                fields.insert(field, mapping.import_value(field, text.trim()));
+            }
+            None => {
+                unmapped.insert(long_name);
+            }
+        }
+    }
+
+    ImportedObject {
+        identifier: object.attr("IDENTIFIER").unwrap_or_default().to_string(),
+        fields,
+    }
+}
+
+/// Plain text of an XHTML attribute value, one line per block element
+fn xhtml_to_text(element: &XmlElement) -> String {
+    fn walk(element: &XmlElement, out: &mut String) {
+        for node in &element.children {
+            match node {
+                crate::xml_utils::XmlNode::Text(text) => out.push_str(text),
+                crate::xml_utils::XmlNode::Element(child) => match child.local_name() {
+                    "br" => out.push('\n'),
+                    "p" | "div" | "li" | "tr" | "pre" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                        out.push('\n');
+                        walk(child, out);
+                        out.push('\n');
+                    }
+                    _ => walk(child, out),
+                },
+            }
+        }
+    }
+
+    let mut text = String::new();
+    walk(element, &mut text);
+    text.lines().map(str::trim).filter(|line| !line.is_empty()).collect::<Vec<_>>().join("\n")
+}
+
+fn req_number(req_id: &str) -> Option<u32> {
+    req_id.strip_prefix("REQ-").and_then(|n| n.parse().ok())
+}
+
+/// Requirement ID for each spec object, reusing earlier imports and explicit IDs first
+fn resolve_req_ids(
+    objects: &[ImportedObject],
+    manager: &RequirementManager,
+    identifiers: &ReqIfIdentifiers,
+    errors: &mut Vec<String>,
+) -> Vec<Option<String>> {
+    let mut claimed = BTreeSet::new();
+    let mut resolved: Vec<Option<String>> = objects
+        .iter()
+        .map(|object| {
+            let req_id = identifiers
+                .objects
+                .get(&object.identifier)
+                .filter(|req_id| manager.get_requirement_by_req_id(req_id).is_some())
+                .cloned()
+                .or_else(|| {
+                    object.fields.get(&ReqIfField::ReqId).filter(|id| id.starts_with("REQ-")).cloned()
+                })
+                .or_else(|| {
+                    // Spec objects this project exported itself carry the requirement UUID
+                    manager
+                        .list_requirements()
+                        .into_iter()
+                        .find(|r| format!("_{}", r.id) == object.identifier)
+                        .map(|r| r.req_id.clone())
+                })?;
+            if claimed.insert(req_id.clone()) {
+                Some(req_id)
+            } else {
+                errors.push(format!("{}: {req_id} is already used by another spec object", object.identifier));
+                None
+            }
+        })
+        .collect();
+
+    let mut next = manager
+        .list_requirements()
+        .iter()
+        .filter_map(|r| req_number(&r.req_id))
+        .chain(claimed.iter().filter_map(|id| req_number(id)))
+        .max()
+        .unwrap_or(0)
+        + 1;
+    for (object, req_id) in objects.iter().zip(resolved.iter_mut()) {
+        let explicit = object.fields.get(&ReqIfField::ReqId).is_some_and(|id| id.starts_with("REQ-"));
+        if req_id.is_none() && !explicit {
+            *req_id = Some(format!("REQ-{next:03}"));
+            next += 1;
+        }
+    }
+    resolved
+}
+
+/// Create or update the requirement for a spec object; returns whether it was created
+fn apply_spec_object(
+    manager: &mut RequirementManager,
+    object: &ImportedObject,
+    req_id: &str,
+    project_id: &str,
+    user_id: &str,
+) -> QmsResult<bool> {
+    let field = |field: ReqIfField| object.fields.get(&field).filter(|v| !v.is_empty());
+    let existing = manager.get_requirement_by_req_id(req_id).cloned();
+
+    // Headings often carry no text and plain objects no name; fall back to each other
+    let description = field(ReqIfField::Description)
+        .or_else(|| field(ReqIfField::Title))
+        .cloned()
+        .or_else(|| existing.as_ref().map(|r| r.description.clone()))
+        .unwrap_or_default();
+    let title = field(ReqIfField::Title)
+        .cloned()
+        .or_else(|| existing.as_ref().map(|r| r.title.clone()))
+        .unwrap_or_else(|| description.lines().next().unwrap_or_default().chars().take(200).collect());
+    let category = field(ReqIfField::Category).map(|c| RequirementCategory::from_str(c));
+    let enum_value = |f: ReqIfField| field(f).map(|v| v.to_lowercase().replace(' ', "_"));
+
+    let candidate = Requirement::new(
+        project_id.to_string(),
+        req_id.to_string(),
+        title.clone(),
+        description.clone(),
+        category.clone().unwrap_or(RequirementCategory::Functional),
+        user_id.to_string(),
+    );
+    candidate.validate()?;
+
+    let mut update = RequirementUpdate::new();
+    if let Some(priority) = enum_value(ReqIfField::Priority) {
+        update = update.priority(RequirementPriority::from_str(&priority));
+    }
+    if let Some(status) = enum_value(ReqIfField::Status) {
+        update = update.status(RequirementStatus::from_str(&status));
+    }
+    if let Some(method) = enum_value(ReqIfField::VerificationMethod) {
+        update = update.verification_method(VerificationMethod::from_str(&method));
+    }
+    if let Some(source) = field(ReqIfField::Source) {
+        update = update.source(source.clone());
+    }
+    if let Some(rationale) = field(ReqIfField::Rationale) {
+        update = update.rationale(rationale.clone());
+    }
+    if let Some(criteria) = field(ReqIfField::AcceptanceCriteria) {
+        update = update.acceptance_criteria(criteria.clone());
+    }
+
+    let created = existing.is_none();
+    if created {
+        manager.create_requirement(
+            project_id.to_string(),
+            req_id.to_string(),
+            title,
+            description,
+            category.unwrap_or(RequirementCategory::Functional),
+            user_id.to_string(),
+        )?;
+    } else {
+        update = update.title(title).description(description);
+        if let Some(category) = category {
+            update = update.category(category);
+        }
+    }
+    manager.update_requirement(req_id, update)?;
+    Ok(created)
+}
+
+fn import_relations(
+    project_path: &Path,
+    relations: &XmlElement,
+    names: &HashMap<String, String>,
+    mapping: &ReqIfMapping,
+    identifiers: &mut ReqIfIdentifiers,
+    stats: &mut ReqIfImportStats,
+) -> QmsResult<()> {
+    let trace_manager = TraceabilityManager::new(project_path)?;
+    let existing_links = trace_manager.get_trace_links()?;
+
+    for relation in relations.children_named("SPEC-RELATION") {
+        let identifier = relation.attr("IDENTIFIER").unwrap_or_default().to_string();
+        let endpoint = |container: &str| {
+            reference(relation, container).and_then(|object| identifiers.objects.get(&object).cloned())
+        };
+        let (Some(source), Some(target)) = (endpoint("SOURCE"), endpoint("TARGET")) else {
+            stats.errors.push(format!("{identifier}: relation references a spec object that was not imported"));
+            continue;
+        };
+        let relation_type = reference(relation, "TYPE").unwrap_or_default();
+        let link_type = mapping.link_type_for(names.get(&relation_type).unwrap_or(&relation_type));
+
+        let known = identifiers
+            .relations
+            .get(&identifier)
+            .filter(|link_id| existing_links.iter().any(|link| &link.id == *link_id))
+            .cloned()
+            .or_else(|| {
+                existing_links
+                    .iter()
+                    .find(|link| {
+                        link.source_id == source
+                            && link.target_id == target
+                            && link.link_type.to_string() == link_type.to_string()
+                    })
+                    .map(|link| link.id.clone())
+            });
+        match known {
+            Some(link_id) => {
+                stats.links_existing += 1;
+                identifiers.relations.insert(identifier, link_id);
+            }
+            None => match trace_manager.create_trace_link(&source, &target, link_type) {
+                Ok(link) => {
+                    stats.links_created += 1;
+                    identifiers.relations.insert(identifier, link.id);
+                }
+                Err(e) => stats.errors.push(format!("{identifier} ({source} -> {target}): {e}")),
+            },
+        }
+    }
+    Ok(())
+}
+
+/// Outcome of a ReqIF export
+#[derive(Debug)]
+pub struct ReqIfExport {
+    pub document: String,
+    pub requirements: usize,
+    pub relations: usize,
+}
+
+/// Export every requirement as a spec object and requirement-to-requirement trace links as spec relations
+pub fn export_reqif(project_path: &Path, mapping: &ReqIfMapping) -> QmsResult<ReqIfExport> {
+    let manager = RequirementManager::new(project_path)?;
+    let mut requirements: Vec<&Requirement> = manager.list_requirements();
+    requirements.sort_by(|a, b| a.req_id.cmp(&b.req_id));
+
+    let identifiers = ReqIfIdentifiers::load(project_path)?;
+    let object_ids: HashMap<&str, String> = requirements
+        .iter()
+        .map(|r| (r.req_id.as_str(), identifiers.object_identifier(r)))
+        .collect();
+
+    let mut links = TraceabilityManager::new(project_path)?.get_trace_links()?;
+    links.retain(|link| object_ids.contains_key(link.source_id.as_str()) && object_ids.contains_key(link.target_id.as_str()));
+    links.sort_by(|a, b| (&a.source_id, &a.target_id).cmp(&(&b.source_id, &b.target_id)));
+
+    let now = iso8601_from_unix(current_timestamp());
+    let identifiable = |tag: &str, identifier: &str, long_name: Option<&str>, last_change: &str| {
+        let element = XmlElement::new(tag).with_attr("IDENTIFIER", identifier).with_attr("LAST-CHANGE", last_change);
+        match long_name {
+            Some(long_name) => element.with_attr("LONG-NAME", long_name),
+            None => element,
+        }
+    };
+    let reference_to = |container: &str, tag: &str, identifier: &str| {
+        XmlElement::new(container).with_child(XmlElement::new(tag).with_text(identifier))
+    };
+
+    // Datatypes: one string, one XHTML and one enumeration per enumerated field
+    let mut enum_values: HashMap<ReqIfField, Vec<String>> = HashMap::new();
+    let mut datatypes = XmlElement::new("DATATYPES")
+        .with_child(identifiable("DATATYPE-DEFINITION-STRING", "_qms-datatype-string", Some("String"), &now)
+            .with_attr("MAX-LENGTH", "65535"))
+        .with_child(identifiable("DATATYPE-DEFINITION-XHTML", "_qms-datatype-xhtml", Some("XHTML"), &now));
+    for field in ReqIfField::ALL.into_iter().filter(|f| f.is_enumeration()) {
+        let mut labels: Vec<String> = Vec::new();
+        let values = field.standard_values().iter().map(|v| v.to_string())
+            .chain(requirements.iter().map(|r| field.value_of(r)));
+        for value in values {
+            let label = mapping.export_value(field, &value);
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+        let mut specified = XmlElement::new("SPECIFIED-VALUES");
+        for (key, label) in labels.iter().enumerate() {
+            specified = specified.with_child(
+                identifiable("ENUM-VALUE", &format!("_qms-datatype-{}-{key}", field.identifier()), Some(label), &now)
+                    .with_child(XmlElement::new("PROPERTIES").with_child(
+                        XmlElement::new("EMBEDDED-VALUE").with_attr("KEY", &key.to_string()).with_attr("OTHER-CONTENT", ""),
+                    )),
+            );
+        }
+        datatypes = datatypes.with_child(
+            identifiable("DATATYPE-DEFINITION-ENUMERATION", &format!("_qms-datatype-{}", field.identifier()),
+                Some(&mapping.attribute_name(field)), &now)
+                .with_child(specified),
+        );
+        enum_values.insert(field, labels);
+    }
+
+    // Spec types
+    let kind = |field: ReqIfField| match field {
+        ReqIfField::Description => ("XHTML", "_qms-datatype-xhtml".to_string()),
+        f if f.is_enumeration() => ("ENUMERATION", format!("_qms-datatype-{}", f.identifier())),
+        _ => ("STRING", "_qms-datatype-string".to_string()),
+    };
+    let attribute_id = |field: ReqIfField| format!("_qms-attribute-{}", field.identifier());
+    let mut spec_attributes = XmlElement::new("SPEC-ATTRIBUTES");
+    for field in ReqIfField::ALL {
+        let (kind, datatype) = kind(field);
+        let mut definition = identifiable(
+            &format!("ATTRIBUTE-DEFINITION-{kind}"), &attribute_id(field), Some(&mapping.attribute_name(field)), &now,
+        );
+        if kind == "ENUMERATION" {
+            definition = definition.with_attr("MULTI-VALUED", "false");
+        }
+        spec_attributes = spec_attributes.with_child(
+            definition.with_child(reference_to("TYPE", &format!("DATATYPE-DEFINITION-{kind}-REF"), &datatype)),
+        );
+    }
+    let mut spec_types = XmlElement::new("SPEC-TYPES").with_child(
+        identifiable("SPEC-OBJECT-TYPE", "_qms-spec-object-type", Some("Requirement"), &now).with_child(spec_attributes),
+    );
+    let relation_types: BTreeMap<String, &TraceLinkType> =
+        links.iter().map(|link| (link.link_type.to_string(), &link.link_type)).collect();
+    let relation_type_id = |link_type: &TraceLinkType| format!("_qms-relation-type-{}", link_type.to_string().to_lowercase());
+    for link_type in relation_types.values() {
+        spec_types = spec_types.with_child(identifiable(
+            "SPEC-RELATION-TYPE", &relation_type_id(link_type), Some(&mapping.relation_type_name(link_type)), &now,
+        ));
+    }
+    spec_types = spec_types.with_child(identifiable(
+        "SPECIFICATION-TYPE", "_qms-specification-type", Some("Requirements Specification"), &now,
+    ));
+
+    // Spec objects and the specification listing them
+    let mut spec_objects = XmlElement::new("SPEC-OBJECTS");
+    let mut hierarchy = XmlElement::new("CHILDREN");
+    for requirement in &requirements {
+        let last_change = requirement.updated_at.parse().map_or_else(|_| now.clone(), iso8601_from_unix);
+        let identifier = &object_ids[requirement.req_id.as_str()];
+        let mut values = XmlElement::new("VALUES");
+        for field in ReqIfField::ALL {
+            let value = field.value_of(requirement);
+            if value.is_empty() {
+                continue;
+            }
+            let (kind, _) = kind(field);
+            let definition = reference_to("DEFINITION", &format!("ATTRIBUTE-DEFINITION-{kind}-REF"), &attribute_id(field));
+            let element = XmlElement::new(&format!("ATTRIBUTE-VALUE-{kind}")).with_child(definition);
+            values = values.with_child(match kind {
+                "XHTML" => {
+                    let mut div = XmlElement::new("xhtml:div");
+                    for line in value.lines().filter(|l| !l.trim().is_empty()) {
+                        div = div.with_child(XmlElement::new("xhtml:p").with_text(line));
+                    }
+                    element.with_child(XmlElement::new("THE-VALUE").with_child(div))
+                }
+                "ENUMERATION" => {
+                    let label = mapping.export_value(field, &value);
+                    let key = enum_values[&field].iter().position(|l| *l == label).unwrap_or(0);
+                    element.with_child(reference_to(
+                        "VALUES", "ENUM-VALUE-REF", &format!("_qms-datatype-{}-{key}", field.identifier()),
+                    ))
+                }
+                _ => element.with_attr("THE-VALUE", &value),
+            });
+        }
+        spec_objects = spec_objects.with_child(
+            identifiable("SPEC-OBJECT", identifier, None, &last_change)
+                .with_child(values)
+                .with_child(reference_to("TYPE", "SPEC-OBJECT-TYPE-REF", "_qms-spec-object-type")),
+        );
+        hierarchy = hierarchy.with_child(
+            identifiable("SPEC-HIERARCHY", &format!("{identifier}-hierarchy"), None, &last_change)
+                .with_child(reference_to("OBJECT", "SPEC-OBJECT-REF", identifier)),
+        );
+    }
+
+    let mut spec_relations = XmlElement::new("SPEC-RELATIONS");
+    for link in &links {
+        let last_change = link.created_at.parse().map_or_else(|_| now.clone(), iso8601_from_unix);
+        spec_relations = spec_relations.with_child(
+            identifiable("SPEC-RELATION", &identifiers.relation_identifier(&link.id), None, &last_change)
+                .with_child(reference_to("SOURCE", "SPEC-OBJECT-REF", &object_ids[link.source_id.as_str()]))
+                .with_child(reference_to("TARGET", "SPEC-OBJECT-REF", &object_ids[link.target_id.as_str()]))
+                .with_child(reference_to("TYPE", "SPEC-RELATION-TYPE-REF", &relation_type_id(&link.link_type))),
+        );
+    }
+
+    let specifications = XmlElement::new("SPECIFICATIONS").with_child(
+        identifiable("SPECIFICATION", "_qms-specification", Some("Requirements"), &now)
+            .with_child(reference_to("TYPE", "SPECIFICATION-TYPE-REF", "_qms-specification-type"))
+            .with_child(hierarchy),
+    );
+
+    let header = XmlElement::new("REQ-IF-HEADER")
+        .with_attr("IDENTIFIER", &format!("_{}", generate_uuid()))
+        .with_child(XmlElement::new("CREATION-TIME").with_text(&now))
+        .with_child(XmlElement::new("REQ-IF-TOOL-ID").with_text("QMS"))
+        .with_child(XmlElement::new("REQ-IF-VERSION").with_text("1.0"))
+        .with_child(XmlElement::new("SOURCE-TOOL-ID").with_text("QMS"))
+        .with_child(XmlElement::new("TITLE").with_text("Requirements"));
+
+    let document = XmlElement::new("REQ-IF")
+        .with_attr("xmlns", REQIF_NAMESPACE)
+        .with_attr("xmlns:xhtml", XHTML_NAMESPACE)
+        .with_child(XmlElement::new("THE-HEADER").with_child(header))
+        .with_child(XmlElement::new("CORE-CONTENT").with_child(
+            XmlElement::new("REQ-IF-CONTENT")
+                .with_child(datatypes)
+                .with_child(spec_types)
+                .with_child(spec_objects)
+                .with_child(spec_relations)
+                .with_child(specifications),
+        ));
+
+    log_audit("reqif_exported");
+    Ok(ReqIfExport {
+        document: document.to_document(),
+        requirements: requirements.len(),
+        relations: links.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const DOORS_EXPORT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<REQ-IF xmlns="http://www.omg.org/spec/ReqIF/20110401/reqif.xsd" xmlns:xhtml="http://www.w3.org/1999/xhtml">
+  <THE-HEADER><REQ-IF-HEADER IDENTIFIER="_h"><REQ-IF-TOOL-ID>DOORS</REQ-IF-TOOL-ID></REQ-IF-HEADER></THE-HEADER>
+  <CORE-CONTENT><REQ-IF-CONTENT>
+    <DATATYPES>
+      <DATATYPE-DEFINITION-STRING IDENTIFIER="_dt-string" LAST-CHANGE="2026-01-01T00:00:00Z"/>
+      <DATATYPE-DEFINITION-XHTML IDENTIFIER="_dt-xhtml" LAST-CHANGE="2026-01-01T00:00:00Z"/>
+      <DATATYPE-DEFINITION-ENUMERATION IDENTIFIER="_dt-class" LAST-CHANGE="2026-01-01T00:00:00Z">
+        <SPECIFIED-VALUES>
+          <ENUM-VALUE IDENTIFIER="_ev-c" LONG-NAME="Class C" LAST-CHANGE="2026-01-01T00:00:00Z"/>
+          <ENUM-VALUE IDENTIFIER="_ev-b" LONG-NAME="Class B" LAST-CHANGE="2026-01-01T00:00:00Z"/>
+        </SPECIFIED-VALUES>
+      </DATATYPE-DEFINITION-ENUMERATION>
+    </DATATYPES>
+    <SPEC-TYPES>
+      <SPEC-OBJECT-TYPE IDENTIFIER="_sot" LONG-NAME="Requirement" LAST-CHANGE="2026-01-01T00:00:00Z">
+        <SPEC-ATTRIBUTES>
+          <ATTRIBUTE-DEFINITION-STRING IDENTIFIER="_ad-id" LONG-NAME="ReqIF.ForeignID" LAST-CHANGE="2026-01-01T00:00:00Z"/>
+          <ATTRIBUTE-DEFINITION-XHTML IDENTIFIER="_ad-text" LONG-NAME="Object Text" LAST-CHANGE="2026-01-01T00:00:00Z"/>
+          <ATTRIBUTE-DEFINITION-ENUMERATION IDENTIFIER="_ad-class" LONG-NAME="Safety Class" LAST-CHANGE="2026-01-01T00:00:00Z"/>
+          <ATTRIBUTE-DEFINITION-STRING IDENTIFIER="_ad-owner" LONG-NAME="Owner" LAST-CHANGE="2026-01-01T00:00:00Z"/>
+        </SPEC-ATTRIBUTES>
+      </SPEC-OBJECT-TYPE>
+      <SPEC-RELATION-TYPE IDENTIFIER="_srt" LONG-NAME="satisfies" LAST-CHANGE="2026-01-01T00:00:00Z"/>
+    </SPEC-TYPES>
+    <SPEC-OBJECTS>
+      <SPEC-OBJECT IDENTIFIER="_o1" LAST-CHANGE="2026-01-01T00:00:00Z">
+        <TYPE><SPEC-OBJECT-TYPE-REF>_sot</SPEC-OBJECT-TYPE-REF></TYPE>
+        <VALUES>
+          <ATTRIBUTE-VALUE-STRING THE-VALUE="101"><DEFINITION><ATTRIBUTE-DEFINITION-STRING-REF>_ad-id</ATTRIBUTE-DEFINITION-STRING-REF></DEFINITION></ATTRIBUTE-VALUE-STRING>
+          <ATTRIBUTE-VALUE-XHTML>
+            <DEFINITION><ATTRIBUTE-DEFINITION-XHTML-REF>_ad-text</ATTRIBUTE-DEFINITION-XHTML-REF></DEFINITION>
+            <THE-VALUE><xhtml:div><xhtml:p>The pump shall stop within 2 s.</xhtml:p><xhtml:p>An alarm shall sound.</xhtml:p></xhtml:div></THE-VALUE>
+          </ATTRIBUTE-VALUE-XHTML>
+          <ATTRIBUTE-VALUE-ENUMERATION>
+            <DEFINITION><ATTRIBUTE-DEFINITION-ENUMERATION-REF>_ad-class</ATTRIBUTE-DEFINITION-ENUMERATION-REF></DEFINITION>
+            <VALUES><ENUM-VALUE-REF>_ev-c</ENUM-VALUE-REF></VALUES>
+          </ATTRIBUTE-VALUE-ENUMERATION>
+          <ATTRIBUTE-VALUE-STRING THE-VALUE="jdoe"><DEFINITION><ATTRIBUTE-DEFINITION-STRING-REF>_ad-owner</ATTRIBUTE-DEFINITION-STRING-REF></DEFINITION></ATTRIBUTE-VALUE-STRING>
+        </VALUES>
+      </SPEC-OBJECT>
+      <SPEC-OBJECT IDENTIFIER="_o2" LAST-CHANGE="2026-01-01T00:00:00Z">
+        <TYPE><SPEC-OBJECT-TYPE-REF>_sot</SPEC-OBJECT-TYPE-REF></TYPE>
+        <VALUES>
+          <ATTRIBUTE-VALUE-XHTML>
+            <DEFINITION><ATTRIBUTE-DEFINITION-XHTML-REF>_ad-text</ATTRIBUTE-DEFINITION-XHTML-REF></DEFINITION>
+            <THE-VALUE><xhtml:div>Flow accuracy shall be &#177;5%</xhtml:div></THE-VALUE>
+          </ATTRIBUTE-VALUE-XHTML>
+          <ATTRIBUTE-VALUE-ENUMERATION>
+            <DEFINITION><ATTRIBUTE-DEFINITION-ENUMERATION-REF>_ad-class</ATTRIBUTE-DEFINITION-ENUMERATION-REF></DEFINITION>
+            <VALUES><ENUM-VALUE-REF>_ev-b</ENUM-VALUE-REF></VALUES>
+          </ATTRIBUTE-VALUE-ENUMERATION>
+        </VALUES>
+      </SPEC-OBJECT>
+    </SPEC-OBJECTS>
+    <SPEC-RELATIONS>
+      <SPEC-RELATION IDENTIFIER="_r1" LAST-CHANGE="2026-01-01T00:00:00Z">
+        <TYPE><SPEC-RELATION-TYPE-REF>_srt</SPEC-RELATION-TYPE-REF></TYPE>
+        <SOURCE><SPEC-OBJECT-REF>_o2</SPEC-OBJECT-REF></SOURCE>
+        <TARGET><SPEC-OBJECT-REF>_o1</SPEC-OBJECT-REF></TARGET>
+      </SPEC-RELATION>
+    </SPEC-RELATIONS>
+  </REQ-IF-CONTENT></CORE-CONTENT>
+</REQ-IF>"#;
+
+    const DOORS_MAPPING: &str = r#"{
+        "attributes": { "Safety Class": "priority" },
+        "values": { "priority": { "Class C": "critical", "Class B": "high" } },
+        "relations": { "satisfies": "derived_from" }
+    }"#;
+
+    #[test]
+    fn test_mapping_file() {
+        let mapping = ReqIfMapping::parse(DOORS_MAPPING).unwrap();
+        assert_eq!(mapping.field_for("safety class"), Some(ReqIfField::Priority));
+        assert_eq!(mapping.field_for("Object Text"), Some(ReqIfField::Description));
+        assert_eq!(mapping.field_for("Owner"), None);
+        assert_eq!(mapping.import_value(ReqIfField::Priority, "Class C"), "critical");
+        assert_eq!(mapping.export_value(ReqIfField::Priority, "high"), "Class B");
+        assert_eq!(mapping.attribute_name(ReqIfField::Priority), "Safety Class");
+        assert_eq!(mapping.relation_type_name(&TraceLinkType::DerivedFrom), "satisfies");
+
+        assert!(ReqIfMapping::parse(r#"{"attributes": {"Owner": "assignee"}}"#).is_err());
+        assert!(ReqIfMapping::parse(r#"{"relations": {"satisfies": "fulfils"}}"#).is_err());
+        assert_eq!(iso8601_from_unix(0), "1970-01-01T00:00:00Z");
+        assert_eq!(iso8601_from_unix(1_709_251_199), "2024-02-29T23:59:59Z");
+    }
+
+    #[test]
+    fn test_import_maps_attributes_and_keeps_identifiers_on_reimport_and_export() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_path = temp_dir.path();
+        let mapping = ReqIfMapping::parse(DOORS_MAPPING).unwrap();
+
+        let stats = import_reqif(project_path, DOORS_EXPORT, &mapping).unwrap();
+        assert_eq!(stats.spec_objects, 2);
+        assert_eq!(stats.created, vec!["REQ-001".to_string(), "REQ-002".to_string()]);
+        assert_eq!(stats.links_created, 1);
+        assert!(stats.errors.is_empty(), "{:?}", stats.errors);
+        assert!(stats.unmapped_attributes.contains("Owner"));
+
+        let manager = RequirementManager::new(project_path).unwrap();
+        let stop = manager.get_requirement_by_req_id("REQ-001").unwrap();
+        assert_eq!(stop.title, "The pump shall stop within 2 s.");
+        assert_eq!(stop.description, "The pump shall stop within 2 s.\nAn alarm shall sound.");
+        assert_eq!(stop.priority, RequirementPriority::Critical);
+        let flow = manager.get_requirement_by_req_id("REQ-002").unwrap();
+        assert_eq!(flow.description, "Flow accuracy shall be ±5%");
+        assert_eq!(flow.priority, RequirementPriority::High);
+
+        let links = TraceabilityManager::new(project_path).unwrap().get_trace_links().unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!((links[0].source_id.as_str(), links[0].target_id.as_str()), ("REQ-002", "REQ-001"));
+        assert!(matches!(links[0].link_type, TraceLinkType::DerivedFrom));
+
+        // A second delivery of the same file updates rather than duplicates
+        let stats = import_reqif(project_path, DOORS_EXPORT, &mapping).unwrap();
+        assert!(stats.created.is_empty());
+        assert_eq!(stats.updated.len(), 2);
+        assert_eq!((stats.links_created, stats.links_existing), (0, 1));
+
+        let export = export_reqif(project_path, &mapping).unwrap();
+        assert_eq!((export.requirements, export.relations), (2, 1));
+        for expected in [
+            r#"<SPEC-OBJECT IDENTIFIER="_o1""#,
+            r#"<SPEC-RELATION IDENTIFIER="_r1""#,
+            r#"LONG-NAME="Safety Class""#,
+            r#"LONG-NAME="satisfies""#,
+            "<xhtml:p>An alarm shall sound.</xhtml:p>",
+        ] {
+            assert!(export.document.contains(expected), "missing {expected}");
+        }
+    }
+
+    #[test]
+    fn test_export_round_trips_into_another_project() {
+        let source_dir = TempDir::new().unwrap();
+        let mut manager = RequirementManager::new(source_dir.path()).unwrap();
+        for (req_id, title) in [("REQ-001", "Occlusion alarm"), ("REQ-002", "Pressure sensing")] {
+            manager.create_requirement(
+                "p1".to_string(),
+                req_id.to_string(),
+                title.to_string(),
+                format!("{title} <shall> work & report"),
+                RequirementCategory::Safety,
+                "tester".to_string(),
+            ).unwrap();
+        }
+        manager.update_requirement("REQ-002", RequirementUpdate::new()
+            .status(RequirementStatus::Approved)
+            .verification_method(VerificationMethod::Analysis)
+            .rationale("Derived from hazard analysis")).unwrap();
+        TraceabilityManager::new(source_dir.path()).unwrap()
+            .create_trace_link("REQ-002", "REQ-001", TraceLinkType::Implements).unwrap();
+
+        let mapping = ReqIfMapping::default();
+        let export = export_reqif(source_dir.path(), &mapping).unwrap();
+        let original_id = format!("_{}", manager.get_requirement_by_req_id("REQ-001").unwrap().id);
+
+        let target_dir = TempDir::new().unwrap();
+        let stats = import_reqif(target_dir.path(), &export.document, &mapping).unwrap();
+        assert!(stats.errors.is_empty(), "{:?}", stats.errors);
+        assert!(stats.unmapped_attributes.is_empty());
+        assert_eq!(stats.created.len(), 2);
+        assert_eq!(stats.links_created, 1);
+
+        let imported = RequirementManager::new(target_dir.path()).unwrap();
+        let req = imported.get_requirement_by_req_id("REQ-002").unwrap();
+        assert_eq!(req.title, "Pressure sensing");
+        assert_eq!(req.description, "Pressure sensing <shall> work & report");
+        assert_eq!(req.category, RequirementCategory::Safety);
+        assert_eq!(req.status, RequirementStatus::Approved);
+        assert_eq!(req.verification_method, VerificationMethod::Analysis);
+        assert_eq!(req.rationale, "Derived from hazard analysis");
+
+        let reexport = export_reqif(target_dir.path(), &mapping).unwrap();
+        assert!(reexport.document.contains(&format!(r#"<SPEC-OBJECT IDENTIFIER="{original_id}""#)));
+    }
+}
@@ -23,6 +23,8 @@ use std::path::{Path, PathBuf};
 use crate::prelude::*;
 use crate::modules::document_control::document::RegulatoryReference;
 use crate::modules::audit_logger::functions::audit_log_create;
+use crate::modules::storage::{EntityStore, FileLayout, ProjectEntity, StorageEntity};
+use crate::modules::search::{self, SearchEntity, SearchRecord};
 use crate::utils::{generate_uuid, current_timestamp};
 
 /// Requirement category classification
@@ -442,12 +444,43 @@ impl Requirement {
     }
 }
 
+impl StorageEntity for Requirement {
+    fn storage_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn to_storage_json(&self) -> String {
+        self.to_json()
+    }
+
+    fn from_storage_json(json: &str) -> QmsResult<Self> {
+        RequirementManager::parse_requirement_json(json)
+    }
+}
+
+impl ProjectEntity for Requirement {
+    const COLLECTION: &'static str = "requirements";
+    const SEARCH_ENTITY: Option<SearchEntity> = Some(SearchEntity::Requirement);
+
+    fn file_layout(project_path: &Path) -> FileLayout {
+        FileLayout::Collection {
+            path: project_path.join("trace").join("requirements.json"),
+            array_key: "data".to_string(),
+        }
+    }
+
+    fn search_record(&self) -> Option<SearchRecord> {
+        Some(search::records::requirement_record(self))
+    }
+}
+
 /// Requirement manager for CRUD operations
 pub struct RequirementManager {
     project_path: PathBuf,
     trace_dir: PathBuf,
     requirements_file: PathBuf,
     requirements: HashMap<String, Requirement>,
+    store: EntityStore<Requirement>,
 }
 
 impl RequirementManager {
@@ -461,6 +494,7 @@ impl RequirementManager {
             trace_dir,
             requirements_file,
             requirements: HashMap::new(),
+            store: EntityStore::open(project_path)?,
         };
         
         manager.initialize()?;
@@ -476,13 +510,12 @@ impl RequirementManager {
         fs::create_dir_all(self.trace_dir.join("reports"))?;
         
         // Create empty index files if they don't exist
-        let requirements_file = self.trace_dir.join("requirements.json");
-        if !requirements_file.exists() {
+        if !self.requirements_file.exists() {
             let empty_index = r#"{
   "version": "1.0",
   "data": []
 }"#;
-            fs::write(&requirements_file, empty_index)?;
+            crate::modules::storage::encryption::write(&self.requirements_file, empty_index)?;
         }
         
         let testcases_file = self.trace_dir.join("testcases.json");
@@ -491,7 +524,7 @@ impl RequirementManager {
   "version": "1.0",
   "data": []
 }"#;
-            fs::write(&testcases_file, empty_index)?;
+            crate::modules::storage::encryption::write(&testcases_file, empty_index)?;
         }
         
         // Note: links.json is managed by TraceabilityManager, not RequirementManager
@@ -506,7 +539,7 @@ impl RequirementManager {
     "verification_coverage": 0.0
   }
 }"#;
-            fs::write(&coverage_file, empty_index)?;
+            crate::modules::storage::encryption::write(&coverage_file, empty_index)?;
         }
         
         // Load existing requirements
@@ -517,67 +550,14 @@ impl RequirementManager {
     
     /// Load requirements from storage
     fn load_requirements(&mut self) -> QmsResult<()> {
-        let requirements_file = self.project_path.join("trace/requirements.json");
-        
-        if !requirements_file.exists() {
-            return Ok(());
-        }
-        
-        let content = fs::read_to_string(&requirements_file)?;
-        if content.trim().is_empty() {
-            return Ok(());
-        }
-        
-        // Basic JSON parsing - extract requirements array
-        if let Some(data_start) = content.find("\"data\": [") {
-            let data_section = &content[data_start + 9..];
-            if let Some(data_end) = data_section.find("]") {
-                let data_content = &data_section[..data_end];
-                if !data_content.trim().is_empty() {
-                    // Parse individual requirement objects
-                    let mut current_obj = String::new();
-                    let mut brace_count = 0;
-                    let mut in_string = false;
-                    let mut escaped = false;
-                    
-                    for ch in data_content.chars() {
-                        current_obj.push(ch);
-                        
-                        if !escaped && ch == '\\' {
-                            escaped = true;
-                            continue;
-                        }
-                        
-                        if !escaped && ch == '"' {
-                            in_string = !in_string;
-                        }
-                        
-                        if !in_string {
-                            if ch == '{' {
-                                brace_count += 1;
-                            } else if ch == '}' {
-                                brace_count -= 1;
-                                if brace_count == 0 {
-                                    // Parse complete requirement object
-                                    if let Ok(req) = self.parse_requirement_json(&current_obj) {
-                                        self.requirements.insert(req.id.clone(), req);
-                                    }
-                                    current_obj.clear();
-                                }
-                            }
-                        }
-                        
-                        escaped = false;
-                    }
-                }
-            }
+        for requirement in self.store.reader().read_all()? {
+            self.requirements.insert(requirement.id.clone(), requirement);
         }
-        
         Ok(())
     }
     
     /// Parse requirement from JSON string
-    fn parse_requirement_json(&self, json: &str) -> QmsResult<Requirement> {
+    fn parse_requirement_json(json: &str) -> QmsResult<Requirement> {
         let mut id = String::new();
         let mut project_id = String::new();
         let mut req_id = String::new();
@@ -594,10 +574,10 @@ impl RequirementManager {
         let mut updated_at = String::new();
         let mut created_by = String::new();
         let mut assigned_to = None;
-        let tags = Vec::new();
-        let linked_requirements = Vec::new();
-        let linked_tests = Vec::new();
-        let linked_risks = Vec::new();
+        let tags = extract_json_string_array(json, "tags");
+        let linked_requirements = extract_json_string_array(json, "linked_requirements");
+        let linked_tests = extract_json_string_array(json, "linked_tests");
+        let linked_risks = extract_json_string_array(json, "linked_risks");
         let regulatory_mapping = Vec::new(); // TODO: Implement parsing
         
         // Extract fields from JSON
@@ -652,8 +632,6 @@ impl RequirementManager {
             }
         }
         
-        // TODO: Parse arrays (tags, linked_requirements, etc.)
-        
         Ok(Requirement {
             id,
             project_id,
@@ -701,10 +679,10 @@ impl RequirementManager {
         requirement.validate()?;
         
         let id = requirement.id.clone();
-        self.requirements.insert(id.clone(), requirement.clone());
         
         // Save to storage
-        self.save_requirements()?;
+        self.store.writer().save(&requirement)?;
+        self.requirements.insert(id.clone(), requirement.clone());
         
         // Log audit entry
         let _ = audit_log_create("Requirement", &id, &requirement.to_json());
@@ -712,38 +690,6 @@ impl RequirementManager {
         Ok(id)
     }
     
-    /// Save requirements to storage
-    fn save_requirements(&self) -> QmsResult<()> {
-        let requirements_file = self.project_path.join("trace/requirements.json");
-        
-        let mut json = String::new();
-        json.push_str("{\n");
-        json.push_str("  \"version\": \"1.0\",\n");
-        json.push_str("  \"data\": [\n");
-        
-        let mut first = true;
-        for requirement in self.requirements.values() {
-            if !first {
-                json.push_str(",\n");
-            }
-            first = false;
-            
-            // Indent requirement JSON
-            let req_json = requirement.to_json();
-            for line in req_json.lines() {
-                json.push_str("    ");
-                json.push_str(line);
-                json.push('\n');
-            }
-        }
-        
-        json.push_str("  ]\n");
-        json.push_str("}\n");
-        
-        fs::write(&requirements_file, json)?;
-        Ok(())
-    }
-    
     /// Get requirement by req_id
     pub fn get_requirement_by_req_id(&self, req_id: &str) -> Option<&Requirement> {
         self.requirements.values().find(|r| r.req_id == req_id)
@@ -774,26 +720,30 @@ impl RequirementManager {
         format!("REQ-{next_num:03}")
     }
     
-    /// Save requirements to file
+    /// Save all requirements to storage
     pub fn save(&self) -> QmsResult<()> {
-        let requirements_vec: Vec<&Requirement> = self.requirements.values().collect();
-        
-        // Manual JSON serialization
-        let mut json_content = String::from("[\n");
-        
-        for (i, requirement) in requirements_vec.iter().enumerate() {
-            if i > 0 {
-                json_content.push_str(",\n");
-            }
-            json_content.push_str(&requirement.to_json());
+        let requirements: Vec<Requirement> = self.requirements.values().cloned().collect();
+        self.store.writer().save_batch(&requirements)
+    }
+
+    /// Record a risk against a requirement
+    ///
+    /// Returns `false` when the risk was already linked. Callers creating the
+    /// matching trace link wrap both writes in one storage transaction.
+    pub fn add_linked_risk(&mut self, req_id: &str, risk_id: &str) -> QmsResult<bool> {
+        let requirement = self.requirements.values_mut()
+            .find(|r| r.req_id == req_id)
+            .ok_or_else(|| QmsError::not_found(&format!("Requirement {req_id} not found")))?;
+        if requirement.linked_risks.iter().any(|r| r == risk_id) {
+            return Ok(false);
         }
-        
-        json_content.push_str("\n]");
-        
-        fs::write(&self.requirements_file, json_content)
-            .map_err(|e| QmsError::io_error(&format!("Failed to write requirements file: {e}")))?;
-        
-        Ok(())
+
+        let mut updated = requirement.clone();
+        updated.linked_risks.push(risk_id.to_string());
+        updated.updated_at = current_timestamp().to_string();
+        self.store.writer().save(&updated)?;
+        *requirement = updated;
+        Ok(true)
     }
     
     /// Update an existing requirement
@@ -836,13 +786,11 @@ impl RequirementManager {
             // Update timestamp
             requirement.updated_at = current_timestamp().to_string();
 
-            // Return copies for audit logging
+            // Persist, then return copies for audit logging
+            self.store.writer().save(requirement)?;
             (requirement.req_id.clone(), requirement.id.clone())
         };
 
-        // Save changes
-        self.save()?;
-
         // Add audit logging for requirement update
         if let Err(e) = crate::modules::audit_logger::audit_log_action(
             "REQUIREMENT_UPDATED",
@@ -863,12 +811,11 @@ impl RequirementManager {
             .map(|r| r.id.clone())
             .ok_or_else(|| QmsError::validation_error(&format!("Requirement {req_id} not found")))?;
         
-        // Remove from collection
+        // Remove from storage, then from the collection
+        self.store.writer().delete(&requirement_id)?;
         let removed = self.requirements.remove(&requirement_id);
         
         if removed.is_some() {
-            // Save changes
-            self.save()?;
             
             // Add audit logging for requirement deletion
             if let Err(e) = crate::modules::audit_logger::audit_log_action(
@@ -884,21 +831,100 @@ impl RequirementManager {
     }
 }
 
+/// Find the `]` closing an array whose contents start at the beginning of `section`,
+/// skipping nested arrays and brackets inside strings
+fn find_array_end(section: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, ch) in section.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match ch {
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '[' if !in_string => depth += 1,
+            ']' if !in_string => {
+                if depth == 0 {
+                    return Some(i);
+                }
+                depth -= 1;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
 /// Helper function to extract JSON field value
 fn extract_json_field(json: &str, field_name: &str) -> Option<String> {
     let pattern = format!("\"{field_name}\": \"");
-    if let Some(start) = json.find(&pattern) {
-        let value_start = start + pattern.len();
-        let remaining = &json[value_start..];
-        
-        if let Some(end) = remaining.find('"') {
-            let value = &remaining[..end];
-            return Some(value.to_string());
+    let start = json.find(&pattern)?;
+    let remaining = &json[start + pattern.len()..];
+
+    // Read up to the closing quote, undoing escape_json_string
+    let mut value = String::new();
+    let mut escaped = false;
+    for ch in remaining.chars() {
+        if escaped {
+            value.push(match ch {
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                other => other,
+            });
+            escaped = false;
+        } else if ch == '\\' {
+            escaped = true;
+        } else if ch == '"' {
+            return Some(value);
+        } else {
+            value.push(ch);
         }
     }
     None
 }
 
+/// Helper function to extract an array of strings, e.g. `"tags": ["a", "b"]`
+fn extract_json_string_array(json: &str, field_name: &str) -> Vec<String> {
+    let pattern = format!("\"{field_name}\": [");
+    let Some(start) = json.find(&pattern) else {
+        return Vec::new();
+    };
+    let section = &json[start + pattern.len()..];
+    let Some(end) = find_array_end(section) else {
+        return Vec::new();
+    };
+
+    let mut values = Vec::new();
+    let mut current = String::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for ch in section[..end].chars() {
+        if escaped {
+            current.push(match ch {
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                other => other,
+            });
+            escaped = false;
+        } else if in_string && ch == '\\' {
+            escaped = true;
+        } else if ch == '"' {
+            if in_string {
+                values.push(std::mem::take(&mut current));
+            }
+            in_string = !in_string;
+        } else if in_string {
+            current.push(ch);
+        }
+    }
+    values
+}
+
 /// Helper function to escape JSON strings
 fn escape_json_string(s: &str) -> String {
     s.replace('\\', "\\\\")
@@ -992,6 +1018,25 @@ mod tests {
         assert!(json.contains("\"category\": \"functional\""));
         assert!(json.contains("\"status\": \"draft\""));
     }
+
+    #[test]
+    fn test_requirement_json_escapes_round_trip() {
+        let mut req = Requirement::new(
+            "proj-001".to_string(),
+            "REQ-001".to_string(),
+            "Flow \"max\" limit".to_string(),
+            "The pump shall stop.\nAn alarm shall sound at C:\\alarms.".to_string(),
+            RequirementCategory::Safety,
+            "test_user".to_string(),
+        );
+        req.rationale = "Tab\tseparated".to_string();
+
+        let parsed = RequirementManager::parse_requirement_json(&req.to_json()).unwrap();
+        assert_eq!(parsed.title, req.title);
+        assert_eq!(parsed.description, req.description);
+        assert_eq!(parsed.rationale, req.rationale);
+        assert_eq!(parsed.category, RequirementCategory::Safety);
+    }
     
     #[test]
     fn test_requirement_manager_create() {
@@ -1018,6 +1063,32 @@ mod tests {
         let _ = fs::remove_dir_all(&test_dir);
     }
     
+    #[test]
+    fn test_requirement_update_survives_reload() {
+        let test_dir = std::env::temp_dir().join("qms_test_req_update_reload");
+        let _ = fs::remove_dir_all(&test_dir);
+        
+        let mut manager = RequirementManager::new(&test_dir).unwrap();
+        manager.create_requirement(
+            "proj-001".to_string(),
+            "REQ-001".to_string(),
+            "Test Requirement".to_string(),
+            "Test description".to_string(),
+            RequirementCategory::Functional,
+            "test_user".to_string(),
+        ).unwrap();
+        
+        let mut updates = RequirementUpdate::new();
+        updates.description = Some("Updated description".to_string());
+        manager.update_requirement("REQ-001", updates).unwrap();
+        
+        let reloaded = RequirementManager::new(&test_dir).unwrap();
+        let requirement = reloaded.get_requirement_by_req_id("REQ-001");
+        assert_eq!(requirement.unwrap().description, "Updated description");
+        
+        let _ = fs::remove_dir_all(&test_dir);
+    }
+    
     #[test]
     fn test_next_req_id_generation() {
         let test_dir = std::env::temp_dir().join("qms_test_req_id_gen");
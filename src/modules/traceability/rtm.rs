@@ -1,10 +1,12 @@
 #![allow(dead_code)]
 
 use crate::prelude::*;
-use crate::modules::traceability::links::{TraceabilityManager, TraceLinkType};
+use crate::modules::baseline::{Baseline, BaselineManager};
+use crate::modules::traceability::links::{TraceabilityManager, TraceabilityLink, TraceLinkType};
 use crate::modules::traceability::requirement::{RequirementManager, Requirement, RequirementStatus};
 use crate::modules::traceability::test_case::{TestCaseManager};
 use crate::modules::audit_logger::functions::audit_log_create;
+use crate::modules::report_generator::pdf::{PdfDocument, PdfTable};
 use crate::utils::current_timestamp;
 use std::collections::HashMap;
 use std::fs;
@@ -20,6 +22,7 @@ pub struct RTMGenerator {
     trace_manager: TraceabilityManager,
     req_manager: RequirementManager,
     test_manager: TestCaseManager,
+    baseline: Option<Baseline>, // Render the matrix as frozen in this baseline
 }
 
 /// RTM Entry representing a single row in the traceability matrix
@@ -116,21 +119,44 @@ impl RTMGenerator {
             trace_manager,
             req_manager,
             test_manager,
+            baseline: None,
         })
     }
 
+    /// Render the matrix from the requirements and links frozen in `baseline`
+    pub fn with_baseline(mut self, baseline: Baseline) -> Self {
+        self.baseline = Some(baseline);
+        self
+    }
+
+    /// Requirements and trace links to render, from the baseline when one is set
+    fn load_design_record(&self) -> QmsResult<(Vec<Requirement>, Vec<TraceabilityLink>)> {
+        match self.baseline {
+            Some(ref baseline) => {
+                let manager = BaselineManager::new(&self.project_path)?;
+                Ok((manager.requirements(baseline)?, manager.trace_links(baseline)?))
+            }
+            None => Ok((
+                self.req_manager.list_requirements().into_iter().cloned().collect(),
+                self.trace_manager.get_trace_links()?,
+            )),
+        }
+    }
+
+    /// Heading line naming the baseline the matrix was rendered from
+    fn baseline_label(&self) -> Option<String> {
+        self.baseline.as_ref().map(|baseline| format!("{} ({})", baseline.name, baseline.created_at))
+    }
+
     /// Generate a complete RTM with all entries
     pub fn generate_rtm(&mut self, config: &RTMConfig) -> QmsResult<Vec<RTMEntry>> {
-        // Load all requirements
-        let requirements = self.req_manager.list_requirements();
-        
-        // Load all traceability links
-        let links = self.trace_manager.get_trace_links()?;
+        // Load all requirements and traceability links
+        let (requirements, links) = self.load_design_record()?;
         
         // Build RTM entries
         let mut rtm_entries = Vec::new();
         
-        for req in requirements {
+        for req in &requirements {
             // Apply filters
             if !self.matches_filters(req, config) {
                 continue;
@@ -267,9 +293,8 @@ impl RTMGenerator {
 
     /// Generate RTM statistics and metrics
     pub fn generate_stats(&mut self) -> QmsResult<RTMStats> {
-        let requirements = self.req_manager.list_requirements();
+        let (requirements, links) = self.load_design_record()?;
         let test_cases = self.test_manager.list_test_cases();
-        let links = self.trace_manager.get_trace_links()?;
         
         let mut stats = RTMStats {
             total_requirements: requirements.len(),
@@ -489,6 +514,9 @@ impl RTMGenerator {
         json_content.push_str("{\n");
         json_content.push_str("  \"version\": \"1.0\",\n");
         json_content.push_str(&format!("  \"generated_at\": \"{}\",\n", current_timestamp()));
+        if let Some(ref baseline) = self.baseline {
+            json_content.push_str(&format!("  \"baseline\": \"{}\",\n", baseline.name));
+        }
         json_content.push_str(&format!("  \"total_entries\": {},\n", entries.len()));
         json_content.push_str("  \"entries\": [\n");
         
@@ -565,6 +593,9 @@ impl RTMGenerator {
         html_content.push_str("<body>\n");
         html_content.push_str("  <h1>Requirements Traceability Matrix</h1>\n");
         html_content.push_str(&format!("  <p>Generated: {}</p>\n", current_timestamp()));
+        if let Some(label) = self.baseline_label() {
+            html_content.push_str(&format!("  <p>As of Baseline: {label}</p>\n"));
+        }
         html_content.push_str(&format!("  <p>Total Requirements: {}</p>\n", entries.len()));
         html_content.push_str("  <table>\n");
         html_content.push_str("    <tr>\n");
@@ -625,105 +656,77 @@ impl RTMGenerator {
         Ok(())
     }
 
-    /// Export RTM as PDF (text-based format for stdlib-only implementation)
+    /// Export RTM as a native PDF document
     fn export_pdf(&self, entries: &[RTMEntry], output_path: &Path, config: &RTMConfig) -> QmsResult<()> {
-        let mut content = String::new();
-        let line_width = 80;
-
-        // PDF Header
-        content.push_str(&"=".repeat(line_width));
-        content.push('\n');
-        content.push_str(&Self::center_text("REQUIREMENTS TRACEABILITY MATRIX", line_width));
-        content.push('\n');
-        content.push_str(&"=".repeat(line_width));
-        content.push('\n');
-        content.push('\n');
+        let generated = crate::utils::current_timestamp_string();
+        let project = self.project_path.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Current Project".to_string());
+        let mut pdf = PdfDocument::new("Requirements Traceability Matrix")
+            .with_document_id(&format!("RTM-{project}"))
+            .with_footer_note(&format!("Generated {generated}"));
 
         // Metadata
-        content.push_str(&format!("Generated: {}\n", crate::utils::current_timestamp_string()));
-        content.push_str(&format!("Total Entries: {}\n", entries.len()));
-        content.push_str("Project: Current Project\n"); // RTMConfig doesn't have project_id field
-        content.push('\n');
-        content.push_str(&"-".repeat(line_width));
-        content.push('\n');
-        content.push('\n');
-
-        // RTM Entries
-        for (index, entry) in entries.iter().enumerate() {
-            content.push_str(&format!("Entry {}: {}\n", index + 1, entry.requirement_id));
-            content.push_str(&"-".repeat(40));
-            content.push('\n');
-
-            content.push_str(&format!("Requirement: {}\n", entry.requirement_description));
-            content.push_str(&format!("Category: {}\n", entry.requirement_category));
-            content.push_str(&format!("Priority: {}\n", entry.requirement_priority));
-            content.push_str(&format!("Status: {}\n", entry.requirement_status));
-
-            if !entry.linked_test_cases.is_empty() {
-                content.push_str("Linked Test Cases:\n");
-                for test_case in &entry.linked_test_cases {
-                    content.push_str(&format!("  - {}\n", test_case));
-                }
-            }
+        pdf.field("Generated", &generated);
+        if let Some(label) = self.baseline_label() {
+            pdf.field("As of Baseline", &label);
+        }
+        pdf.field("Total Entries", &entries.len().to_string());
+        pdf.field("Project", &project);
 
-            if !entry.linked_design_elements.is_empty() {
-                content.push_str("Linked Design Elements:\n");
-                for design in &entry.linked_design_elements {
-                    content.push_str(&format!("  - {}\n", design));
-                }
+        // Matrix
+        pdf.heading(1, "Traceability Matrix");
+        let mut headers = vec!["Requirement", "Category", "Priority", "Status", "Test Cases", "Design Elements"];
+        if config.show_coverage_metrics {
+            headers.push("Coverage");
+        }
+        if config.show_verification_details {
+            headers.extend(["Verification Method", "Verification Status"]);
+        }
+        let mut table = PdfTable::new(&headers);
+        for entry in entries {
+            let requirement = if config.show_descriptions && !entry.requirement_description.is_empty() {
+                format!("{}\n{}", entry.requirement_id, entry.requirement_description)
+            } else {
+                entry.requirement_id.clone()
+            };
+            let mut row = vec![
+                requirement,
+                entry.requirement_category.clone(),
+                entry.requirement_priority.clone(),
+                entry.requirement_status.clone(),
+                entry.linked_test_cases.join(", "),
+                entry.linked_design_elements.join(", "),
+            ];
+            if config.show_coverage_metrics {
+                row.push(format!("{:.1}%", entry.coverage_percentage));
             }
-
-            if !entry.verification_method.is_empty() {
-                content.push_str(&format!("Verification Method: {}\n", entry.verification_method));
-                content.push_str(&format!("Verification Status: {}\n", entry.verification_status));
+            if config.show_verification_details {
+                row.push(entry.verification_method.clone());
+                row.push(entry.verification_status.clone());
             }
-
-            content.push('\n');
+            table.add_row(row);
         }
+        pdf.table(table);
 
         // Summary
-        content.push_str(&"=".repeat(line_width));
-        content.push('\n');
-        content.push_str(&Self::center_text("SUMMARY", line_width));
-        content.push('\n');
-        content.push_str(&"=".repeat(line_width));
-        content.push('\n');
-        content.push('\n');
-
         let total_requirements = entries.len();
         let tested_requirements = entries.iter().filter(|e| !e.linked_test_cases.is_empty()).count();
         let verified_requirements = entries.iter().filter(|e| !e.verification_method.is_empty()).count();
+        let percentage = |count: usize| {
+            if total_requirements > 0 { (count as f64 / total_requirements as f64) * 100.0 } else { 0.0 }
+        };
 
-        content.push_str(&format!("Total Requirements: {}\n", total_requirements));
-        content.push_str(&format!("Requirements with Tests: {} ({:.1}%)\n",
-            tested_requirements,
-            if total_requirements > 0 { (tested_requirements as f64 / total_requirements as f64) * 100.0 } else { 0.0 }
-        ));
-        content.push_str(&format!("Requirements with Verification: {} ({:.1}%)\n",
-            verified_requirements,
-            if total_requirements > 0 { (verified_requirements as f64 / total_requirements as f64) * 100.0 } else { 0.0 }
-        ));
-
-        content.push('\n');
-        content.push_str("Note: This is a text-based PDF format. For graphical PDF output,\n");
-        content.push_str("convert this file using external tools like pandoc or wkhtmltopdf.\n");
+        pdf.heading(1, "Summary");
+        pdf.field("Total Requirements", &total_requirements.to_string());
+        pdf.field("Requirements with Tests", &format!("{} ({:.1}%)", tested_requirements, percentage(tested_requirements)));
+        pdf.field("Requirements with Verification", &format!("{} ({:.1}%)", verified_requirements, percentage(verified_requirements)));
 
-        // Write to file
-        std::fs::write(output_path, content)?;
+        pdf.write_to(output_path)?;
 
         Ok(())
     }
 
-    /// Center text within a given width
-    fn center_text(text: &str, width: usize) -> String {
-        if text.len() >= width {
-            return text.to_string();
-        }
-
-        let padding = (width - text.len()) / 2;
-        format!("{}{}", " ".repeat(padding), text)
-    }
-
     /// Export RTM as Markdown
     fn export_markdown(&self, entries: &[RTMEntry], output_path: &Path, config: &RTMConfig) -> QmsResult<()> {
         let mut md_content = String::new();
@@ -731,6 +734,9 @@ impl RTMGenerator {
         // Markdown header
         md_content.push_str("# Requirements Traceability Matrix\n\n");
         md_content.push_str(&format!("**Generated:** {}\n", current_timestamp()));
+        if let Some(label) = self.baseline_label() {
+            md_content.push_str(&format!("**As of Baseline:** {label}\n"));
+        }
         md_content.push_str(&format!("**Total Requirements:** {}\n\n", entries.len()));
         
         // Markdown table header
@@ -917,6 +923,96 @@ mod tests {
         assert_eq!(stats.orphaned_test_cases, 0);
     }
 
+    #[test]
+    fn test_rtm_pdf_export() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_path = temp_dir.path();
+        fs::create_dir_all(project_path.join("trace")).unwrap();
+        fs::write(project_path.join("trace/requirements.json"), "[]").unwrap();
+        fs::write(project_path.join("trace/test_cases.json"), "[]").unwrap();
+        fs::write(project_path.join("trace/links.json"), "[]").unwrap();
+        fs::create_dir_all(project_path.join("audit")).unwrap();
+        fs::write(project_path.join("audit/audit.log"), "").unwrap();
+
+        let generator = RTMGenerator::new(project_path).unwrap();
+        let entry = RTMEntry {
+            requirement_id: "REQ-001".to_string(),
+            requirement_title: "Alarm".to_string(),
+            requirement_description: "Audible alarm on occlusion".to_string(),
+            requirement_category: "Safety".to_string(),
+            requirement_priority: "High".to_string(),
+            requirement_status: "Approved".to_string(),
+            linked_test_cases: vec!["TC-001".to_string()],
+            linked_design_elements: vec![],
+            linked_risks: vec![],
+            linked_documents: vec![],
+            verification_status: "Verified".to_string(),
+            verification_method: "Test".to_string(),
+            coverage_percentage: 100.0,
+            last_verified_at: None,
+            verification_notes: None,
+        };
+
+        let output = project_path.join("rtm.pdf");
+        generator.export_pdf(&[entry], &output, &RTMConfig::default()).unwrap();
+        let pdf = fs::read_to_string(&output).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4"));
+        assert!(pdf.contains("(REQ-001)"));
+        assert!(pdf.contains("(TC-001)"));
+        assert!(pdf.contains("(Page 1 of 1)"));
+    }
+
+    #[test]
+    fn test_rtm_as_of_baseline_uses_frozen_requirements() {
+        use crate::modules::audit_logger::{initialize_audit_system, AuditConfig};
+        use crate::modules::traceability::requirement::{RequirementCategory, RequirementUpdate};
+
+        let temp_dir = TempDir::new().unwrap();
+        let project_path = temp_dir.path();
+        let _ = initialize_audit_system(AuditConfig {
+            project_path: project_path.to_string_lossy().to_string(),
+            retention_days: 30,
+            daily_rotation: false,
+            max_file_size_mb: 10,
+            require_checksums: false,
+        });
+
+        let mut requirements = RequirementManager::new(project_path).unwrap();
+        requirements.create_requirement(
+            "project-001".to_string(),
+            "REQ-001".to_string(),
+            "Flow accuracy".to_string(),
+            "Deliver within 5% of set rate".to_string(),
+            RequirementCategory::Functional,
+            "alice".to_string(),
+        ).unwrap();
+        let baseline = BaselineManager::new(project_path).unwrap().create("R1.0", "First release", "alice").unwrap();
+
+        let mut update = RequirementUpdate::new();
+        update.title = Some("Flow accuracy (tightened)".to_string());
+        requirements.update_requirement("REQ-001", update).unwrap();
+        requirements.create_requirement(
+            "project-001".to_string(),
+            "REQ-002".to_string(),
+            "Occlusion alarm".to_string(),
+            "Alarm within 30 s of occlusion".to_string(),
+            RequirementCategory::Safety,
+            "alice".to_string(),
+        ).unwrap();
+
+        let mut generator = RTMGenerator::new(project_path).unwrap().with_baseline(baseline);
+        let entries = generator.generate_rtm(&RTMConfig::default()).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].requirement_title, "Flow accuracy");
+
+        let output = project_path.join("rtm.md");
+        generator.export_markdown(&entries, &output, &RTMConfig::default()).unwrap();
+        assert!(fs::read_to_string(&output).unwrap().contains("**As of Baseline:** R1.0"));
+
+        let mut working = RTMGenerator::new(project_path).unwrap();
+        assert_eq!(working.generate_rtm(&RTMConfig::default()).unwrap().len(), 2);
+    }
+
     #[test]
     fn test_rtm_entry_structure() {
         let entry = RTMEntry {
@@ -0,0 +1,480 @@
+//! Source-code annotation scanning
+//! Walks a source tree for annotations such as `// @implements REQ-014` or
+//! `#[doc = "verifies REQ-014"]` and keeps one trace link per entity, file and
+//! link type in step with them, recording where each annotation was found.
+
+use crate::error::{QmsError, QmsResult};
+use crate::modules::audit_logger::functions::{audit_log_action, audit_log_create};
+use crate::modules::traceability::links::{
+    LinkProvenance, TraceLinkType, TraceabilityLink, TraceabilityManager,
+};
+use crate::utils::{current_timestamp, generate_uuid};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Entity type recorded on the code side of scanned links
+pub const SOURCE_FILE_ENTITY: &str = "SourceFile";
+
+/// An annotation keyword and the link it produces
+#[derive(Debug, Clone)]
+pub struct AnnotationRule {
+    pub keyword: String,
+    pub link_type: TraceLinkType,
+}
+
+/// What `scan_tree` looks for and where
+#[derive(Debug, Clone)]
+pub struct ScanConfig {
+    pub annotations: Vec<AnnotationRule>,
+    /// File extensions scanned when walking a directory
+    pub extensions: Vec<String>,
+    /// Directory names never descended into
+    pub exclude_dirs: Vec<String>,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            annotations: vec![
+                AnnotationRule { keyword: "implements".to_string(), link_type: TraceLinkType::Implements },
+                AnnotationRule { keyword: "verifies".to_string(), link_type: TraceLinkType::Verifies },
+            ],
+            extensions: [
+                "rs", "c", "h", "cc", "cpp", "cxx", "hpp", "hh", "py", "js", "jsx", "ts", "tsx",
+                "java", "kt", "cs", "go", "swift", "m", "mm", "rb", "php", "scala", "sh",
+            ]
+            .iter()
+            .map(|ext| ext.to_string())
+            .collect(),
+            exclude_dirs: ["target", "node_modules", "build", "dist", "__pycache__"]
+                .iter()
+                .map(|dir| dir.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl ScanConfig {
+    /// Add or replace an annotation from a `<keyword>=<link type>` spec, e.g. `satisfies=implements`
+    pub fn add_annotation(&mut self, spec: &str) -> QmsResult<()> {
+        let (keyword, link_type) = spec.split_once('=').ok_or_else(|| {
+            QmsError::validation_error(&format!("Invalid annotation '{spec}'. Expected <keyword>=<link type>"))
+        })?;
+        let keyword = keyword.trim().trim_start_matches('@').to_lowercase();
+        if keyword.is_empty() || !keyword.chars().all(is_word_char) {
+            return Err(QmsError::validation_error(&format!("Invalid annotation keyword in '{spec}'")));
+        }
+        let link_type = TraceLinkType::from_str(link_type.trim())?;
+
+        self.annotations.retain(|rule| rule.keyword != keyword);
+        self.annotations.push(AnnotationRule { keyword, link_type });
+        Ok(())
+    }
+
+    fn scans_extension(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| self.extensions.iter().any(|allowed| allowed.eq_ignore_ascii_case(ext)))
+            .unwrap_or(false)
+    }
+}
+
+/// One entity reference found in a source file
+#[derive(Debug, Clone)]
+pub struct AnnotationMatch {
+    pub entity_id: String,
+    pub link_type: TraceLinkType,
+    pub file: String,
+    pub line: usize,
+}
+
+/// Outcome of reconciling a scan with the stored links
+#[derive(Debug, Default)]
+pub struct ScanReport {
+    /// Scanned path as recorded in provenance ("" for the whole base directory)
+    pub scope: String,
+    pub files_scanned: usize,
+    pub annotations: usize,
+    pub created: Vec<TraceabilityLink>,
+    /// Links whose annotation moved or reappeared after being flagged stale
+    pub updated: Vec<TraceabilityLink>,
+    pub unchanged: usize,
+    /// Links whose annotation is no longer in the scanned files
+    pub stale: Vec<TraceabilityLink>,
+    /// Annotations naming entities that do not exist in the project
+    pub unresolved: Vec<AnnotationMatch>,
+}
+
+/// Find annotations in one file's text, as (line, link type, entity ID)
+pub fn scan_text(content: &str, config: &ScanConfig) -> Vec<(usize, TraceLinkType, String)> {
+    let mut found = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let lower = line.to_ascii_lowercase();
+        let comment_start = comment_start(line);
+        for rule in &config.annotations {
+            let mut from = 0;
+            while let Some(offset) = lower[from..].find(&rule.keyword) {
+                let start = from + offset;
+                let end = start + rule.keyword.len();
+                from = end;
+
+                let tagged = start > 0 && lower.as_bytes()[start - 1] == b'@';
+                let word_start = if tagged { start - 1 } else { start };
+                let bounded = !lower[..word_start].chars().next_back().map(is_word_char).unwrap_or(false)
+                    && !lower[end..].chars().next().map(is_word_char).unwrap_or(false);
+                let in_comment = comment_start.map(|position| position <= word_start).unwrap_or(false);
+                if !bounded || !(tagged || in_comment) {
+                    continue;
+                }
+
+                for entity_id in entity_ids(&line[end..]) {
+                    found.push((index + 1, rule.link_type.clone(), entity_id));
+                }
+            }
+        }
+    }
+    found
+}
+
+/// Walk `root` and collect annotations, with file paths relative to `base`
+pub fn scan_tree(root: &Path, base: &Path, config: &ScanConfig) -> QmsResult<(Vec<AnnotationMatch>, usize)> {
+    let mut files = Vec::new();
+    if root.is_file() {
+        files.push(root.to_path_buf());
+    } else if root.is_dir() {
+        collect_files(root, config, &mut files)?;
+    } else {
+        return Err(QmsError::not_found(&format!("Source path {} not found", root.display())));
+    }
+
+    let mut matches = Vec::new();
+    for path in &files {
+        let content = String::from_utf8_lossy(&fs::read(path)?).into_owned();
+        let file = relative_path(path, base);
+        for (line, link_type, entity_id) in scan_text(&content, config) {
+            matches.push(AnnotationMatch { entity_id, link_type, file: file.clone(), line });
+        }
+    }
+    Ok((matches, files.len()))
+}
+
+/// Scan `scan_path` and create, update or flag stale the scanned links
+///
+/// Files are recorded relative to the project root when the scanned path lies
+/// inside it, otherwise relative to the scanned directory. Only scanned links
+/// under the scanned path are considered for staleness. With `dry_run` the
+/// report is computed but nothing is stored.
+pub fn scan_and_reconcile(
+    project_root: &Path,
+    scan_path: &Path,
+    config: &ScanConfig,
+    dry_run: bool,
+) -> QmsResult<ScanReport> {
+    let root = scan_path
+        .canonicalize()
+        .map_err(|_| QmsError::not_found(&format!("Source path {} not found", scan_path.display())))?;
+    let project = project_root.canonicalize()?;
+    let base = if root.starts_with(&project) {
+        project
+    } else if root.is_file() {
+        root.parent().map(Path::to_path_buf).unwrap_or_else(|| root.clone())
+    } else {
+        root.clone()
+    };
+    let scope = relative_path(&root, &base);
+
+    let manager = TraceabilityManager::new(project_root)?;
+    let (matches, files_scanned) = scan_tree(&root, &base, config)?;
+    let mut report = ScanReport {
+        scope: scope.clone(),
+        files_scanned,
+        annotations: matches.len(),
+        ..ScanReport::default()
+    };
+
+    // One link per entity, file and link type, located at its first annotation
+    let mut exists: HashMap<String, bool> = HashMap::new();
+    let mut found: BTreeMap<(String, String, String), (usize, TraceLinkType)> = BTreeMap::new();
+    for annotation in matches {
+        let known = match exists.get(&annotation.entity_id) {
+            Some(known) => *known,
+            None => {
+                let known = manager.entity_exists(&annotation.entity_id)?;
+                exists.insert(annotation.entity_id.clone(), known);
+                known
+            }
+        };
+        if !known {
+            report.unresolved.push(annotation);
+            continue;
+        }
+        let key = (annotation.entity_id, annotation.file, annotation.link_type.to_string());
+        let entry = found.entry(key).or_insert((annotation.line, annotation.link_type));
+        entry.0 = entry.0.min(annotation.line);
+    }
+
+    let now = current_timestamp().to_string();
+    let mut scanned: Vec<TraceabilityLink> = manager
+        .get_trace_links()?
+        .into_iter()
+        .filter(|link| link.provenance.is_some())
+        .collect();
+    let mut matched = vec![false; scanned.len()];
+
+    for ((entity_id, file, link_type_name), (line, link_type)) in found {
+        let existing = scanned.iter().position(|link| {
+            link.source_id == entity_id && link.target_id == file && link.link_type.to_string() == link_type_name
+        });
+        match existing {
+            Some(index) => {
+                matched[index] = true;
+                let link = &mut scanned[index];
+                let provenance = link.provenance.as_mut().expect("scanned links carry provenance");
+                if provenance.line == line && !provenance.stale {
+                    report.unchanged += 1;
+                    continue;
+                }
+                provenance.line = line;
+                provenance.stale = false;
+                provenance.scanned_at = now.clone();
+                report.updated.push(link.clone());
+            }
+            None => report.created.push(TraceabilityLink {
+                id: generate_uuid(),
+                source_type: manager.get_entity_type(&entity_id)?,
+                source_id: entity_id,
+                target_type: SOURCE_FILE_ENTITY.to_string(),
+                target_id: file.clone(),
+                link_type,
+                created_at: now.clone(),
+                created_by: crate::utils::user_context::get_current_user_id(),
+                verified: false,
+                verified_at: None,
+                verified_by: None,
+                provenance: Some(LinkProvenance { file, line, scanned_at: now.clone(), stale: false }),
+            }),
+        }
+    }
+
+    for (link, matched) in scanned.iter_mut().zip(matched) {
+        let provenance = link.provenance.as_mut().expect("scanned links carry provenance");
+        if matched || provenance.stale || !within_scope(&provenance.file, &scope) {
+            continue;
+        }
+        provenance.stale = true;
+        provenance.scanned_at = now.clone();
+        report.stale.push(link.clone());
+    }
+
+    if !dry_run {
+        let changed: Vec<TraceabilityLink> = report
+            .created
+            .iter()
+            .chain(&report.updated)
+            .chain(&report.stale)
+            .cloned()
+            .collect();
+        manager.save_scanned_links(&changed)?;
+
+        for link in &report.created {
+            let _ = audit_log_create("TraceabilityLink", &link.id, &link.to_json());
+        }
+        let _ = audit_log_action(
+            "TRACE_SCAN",
+            "TraceabilityLink",
+            &format!(
+                "Scanned {}: {} created, {} updated, {} stale",
+                if scope.is_empty() { "." } else { &scope },
+                report.created.len(),
+                report.updated.len(),
+                report.stale.len()
+            ),
+        );
+    }
+
+    Ok(report)
+}
+
+fn collect_files(dir: &Path, config: &ScanConfig, files: &mut Vec<PathBuf>) -> QmsResult<()> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+
+    for path in entries {
+        let name = path.file_name().and_then(|name| name.to_str()).unwrap_or("");
+        if path.is_dir() {
+            if !name.starts_with('.') && !config.exclude_dirs.iter().any(|excluded| excluded == name) {
+                collect_files(&path, config, files)?;
+            }
+        } else if config.scans_extension(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Byte offset where a comment or doc attribute starts on the line
+fn comment_start(line: &str) -> Option<usize> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with('*') {
+        return Some(line.len() - trimmed.len());
+    }
+    ["//", "/*", "#", "--", "\"\"\"", "'''"]
+        .iter()
+        .filter_map(|marker| line.find(marker))
+        .min()
+}
+
+/// Entity IDs listed after an annotation keyword, e.g. `: REQ-014, REQ-015 and RISK-002`
+fn entity_ids(rest: &str) -> Vec<String> {
+    let mut ids = Vec::new();
+    let rest = rest.trim_start().trim_start_matches(':');
+    for token in rest.split(|c: char| c.is_whitespace() || c == ',') {
+        let token = token.trim_matches(|c: char| "\"'`()[]{}.;".contains(c));
+        if token.is_empty() || token.eq_ignore_ascii_case("and") || token == "&" {
+            continue;
+        }
+        if !is_entity_id(token) {
+            break;
+        }
+        ids.push(token.to_string());
+    }
+    ids
+}
+
+fn is_entity_id(token: &str) -> bool {
+    match token.split_once('-') {
+        Some((prefix, rest)) => {
+            !prefix.is_empty()
+                && prefix.starts_with(|c: char| c.is_ascii_uppercase())
+                && prefix.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+                && rest.chars().any(|c| c.is_ascii_digit())
+                && rest.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        }
+        None => false,
+    }
+}
+
+const fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// `path` relative to `base`, '/'-separated
+fn relative_path(path: &Path, base: &Path) -> String {
+    path.strip_prefix(base)
+        .unwrap_or(path)
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn within_scope(file: &str, scope: &str) -> bool {
+    scope.is_empty() || file == scope || file.starts_with(&format!("{scope}/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::traceability::requirement::{RequirementCategory, RequirementManager};
+    use tempfile::TempDir;
+
+    fn ids(found: &[(usize, TraceLinkType, String)]) -> Vec<(usize, String, String)> {
+        found
+            .iter()
+            .map(|(line, link_type, id)| (*line, link_type.to_string(), id.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_scan_text_recognises_annotations() {
+        let source = r#"// @implements REQ-014
+/// Flow limit, see docs. Implements: REQ-015, REQ-016 and RISK-002.
+#[doc = "verifies REQ-014"]
+fn verifies(req: &str) -> bool { req == "implements REQ-099" }
+# this implements the alarm REQ-017
+/* @verifies tc */
+"#;
+        let found = scan_text(source, &ScanConfig::default());
+        assert_eq!(ids(&found), vec![
+            (1, "Implements".to_string(), "REQ-014".to_string()),
+            (2, "Implements".to_string(), "REQ-015".to_string()),
+            (2, "Implements".to_string(), "REQ-016".to_string()),
+            (2, "Implements".to_string(), "RISK-002".to_string()),
+            (3, "Verifies".to_string(), "REQ-014".to_string()),
+        ]);
+
+        let mut config = ScanConfig::default();
+        config.add_annotation("@satisfies=implements").unwrap();
+        assert!(config.add_annotation("satisfies").is_err());
+        assert_eq!(ids(&scan_text("// satisfies REQ-020", &config)), vec![
+            (1, "Implements".to_string(), "REQ-020".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_scan_reconciles_created_moved_and_stale_links() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_path = temp_dir.path();
+        let mut requirements = RequirementManager::new(project_path).unwrap();
+        for req_id in ["REQ-001", "REQ-002"] {
+            requirements.create_requirement(
+                project_path.to_string_lossy().to_string(),
+                req_id.to_string(),
+                format!("Requirement {req_id}"),
+                "The pump shall alarm".to_string(),
+                RequirementCategory::Functional,
+                "tester".to_string(),
+            ).unwrap();
+        }
+
+        let src = project_path.join("src");
+        fs::create_dir_all(src.join("target")).unwrap();
+        fs::write(src.join("pump.rs"), "// @implements REQ-001\nfn run() {}\n// @implements REQ-001\n").unwrap();
+        fs::write(src.join("alarm.rs"), "// @implements REQ-002 REQ-404\n").unwrap();
+        fs::write(src.join("target").join("gen.rs"), "// @implements REQ-002\n").unwrap();
+        fs::write(src.join("notes.txt"), "@implements REQ-002\n").unwrap();
+
+        let config = ScanConfig::default();
+        let preview = scan_and_reconcile(project_path, &src, &config, true).unwrap();
+        assert_eq!(preview.created.len(), 2);
+        assert!(TraceabilityManager::new(project_path).unwrap().get_trace_links().unwrap().is_empty());
+
+        let report = scan_and_reconcile(project_path, &src, &config, false).unwrap();
+        assert_eq!((report.scope.as_str(), report.files_scanned, report.annotations), ("src", 2, 4));
+        assert_eq!(report.created.len(), 2);
+        assert_eq!(report.unresolved.len(), 1);
+        assert_eq!(report.unresolved[0].entity_id, "REQ-404");
+
+        let links = TraceabilityManager::new(project_path).unwrap().get_trace_links().unwrap();
+        let pump = links.iter().find(|link| link.target_id == "src/pump.rs").unwrap();
+        assert_eq!((pump.source_id.as_str(), pump.target_type.as_str()), ("REQ-001", SOURCE_FILE_ENTITY));
+        assert!(matches!(pump.link_type, TraceLinkType::Implements));
+        assert_eq!(pump.provenance.as_ref().unwrap().line, 1);
+
+        fs::write(src.join("pump.rs"), "fn run() {}\n// @implements REQ-001\n").unwrap();
+        fs::write(src.join("alarm.rs"), "fn alarm() {}\n").unwrap();
+        let report = scan_and_reconcile(project_path, &src, &config, false).unwrap();
+        assert!(report.created.is_empty());
+        assert_eq!(report.updated.len(), 1);
+        assert_eq!(report.stale.len(), 1);
+        assert_eq!(report.stale[0].target_id, "src/alarm.rs");
+
+        let links = TraceabilityManager::new(project_path).unwrap().get_trace_links().unwrap();
+        assert_eq!(links.len(), 2);
+        let pump = links.iter().find(|link| link.target_id == "src/pump.rs").unwrap();
+        assert_eq!(pump.provenance.as_ref().unwrap().line, 2);
+        let alarm = links.iter().find(|link| link.target_id == "src/alarm.rs").unwrap();
+        assert!(alarm.provenance.as_ref().unwrap().stale);
+
+        // Scanning elsewhere leaves links under src alone
+        fs::create_dir_all(project_path.join("tests")).unwrap();
+        let report = scan_and_reconcile(project_path, &project_path.join("tests"), &config, false).unwrap();
+        assert_eq!((report.unchanged, report.updated.len(), report.stale.len()), (0, 0, 0));
+    }
+}
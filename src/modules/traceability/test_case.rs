@@ -5,6 +5,7 @@ use std::fs::{self, File};
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use crate::error::{QmsError, QmsResult};
+use crate::json_utils::JsonValue;
 use crate::modules::audit_logger::entry::{AuditLogger, AuditConfig, log_action};
 use crate::models::AuditAction;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -73,6 +74,7 @@ pub struct TestExecution {
     pub overall_status: TestExecutionStatus,
     pub duration_seconds: Option<u64>,
     pub environment: Option<String>,
+    pub build: Option<String>, // Build or revision under test, for automated runs
     pub notes: Option<String>,
     pub step_results: Vec<TestStepResult>,
 }
@@ -219,6 +221,7 @@ impl TestCaseManager {
             overall_status: TestExecutionStatus::Incomplete,
             duration_seconds: None,
             environment,
+            build: None,
             notes: None,
             step_results: Vec::new(),
         };
@@ -308,6 +311,57 @@ impl TestCaseManager {
         Ok(())
     }
     
+    /// Record a completed execution, such as an ingested automated run, and return its ID
+    pub fn record_execution(&mut self, test_id: &str, mut execution: TestExecution) -> QmsResult<String> {
+        let test_case = self.test_cases.get_mut(test_id)
+            .ok_or_else(|| QmsError::not_found(&format!("Test case {test_id} not found")))?;
+
+        // Several runs can land within the same second; keep execution IDs unique
+        let base_id = format!("EXEC-{}-{}", test_id, SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs());
+        let mut execution_id = base_id.clone();
+        let mut suffix = 1;
+        while test_case.execution_results.iter().any(|e| e.execution_id == execution_id) {
+            suffix += 1;
+            execution_id = format!("{base_id}-{suffix}");
+        }
+        execution.execution_id = execution_id.clone();
+        let executed_by = execution.executed_by.clone();
+
+        test_case.execution_results.push(execution);
+        test_case.last_modified = SystemTime::now();
+
+        self.save_test_case(test_id)?;
+
+        log_action(
+            &executed_by,
+            AuditAction::Create,
+            "test_execution",
+            &execution_id,
+        )?;
+
+        Ok(execution_id)
+    }
+
+    /// Replace the tags used to match automated results to a test case
+    pub fn set_tags(&mut self, test_id: &str, tags: Vec<String>) -> QmsResult<()> {
+        let test_case = self.test_cases.get_mut(test_id)
+            .ok_or_else(|| QmsError::not_found(&format!("Test case {test_id} not found")))?;
+
+        test_case.tags = tags;
+        test_case.last_modified = SystemTime::now();
+
+        self.save_test_case(test_id)?;
+
+        log_action(
+            "System",
+            AuditAction::Update,
+            "test_case",
+            test_id,
+        )?;
+
+        Ok(())
+    }
+
     pub fn get_test_case(&self, test_id: &str) -> Option<&TestCase> {
         self.test_cases.get(test_id)
     }
@@ -340,6 +394,9 @@ impl TestCaseManager {
             if let Some(env) = &execution.environment {
                 summary.push_str(&format!("  Environment: {env}\n"));
             }
+            if let Some(build) = &execution.build {
+                summary.push_str(&format!("  Build: {build}\n"));
+            }
             summary.push_str(&format!("  Step Results: {}\n\n", execution.step_results.len()));
         }
         
@@ -351,11 +408,13 @@ impl TestCaseManager {
             .ok_or_else(|| QmsError::not_found(&format!("Test case {test_id} not found")))?;
         
         let file_path = self.project_path.join("tests").join(format!("{test_id}.json"));
-        let mut file = File::create(&file_path)?;
-        
-        let json = format!("{{\"test_id\":\"{}\",\"title\":\"{}\",\"category\":\"{:?}\",\"priority\":\"{:?}\",\"steps\":{},\"execution_results\":{}}}", 
-            test_case.test_id, test_case.title, test_case.category, test_case.priority, test_case.steps.len(), test_case.execution_results.len());
-        file.write_all(json.as_bytes())?;
+        let json = test_case_to_json(test_case).json_to_string();
+        crate::modules::storage::encryption::write(&file_path, json)?;
+        crate::modules::search::update_index_or_warn(
+            &self.project_path,
+            &[crate::modules::search::records::test_case_record(test_case)],
+            &[],
+        );
         
         Ok(())
     }
@@ -393,7 +452,7 @@ impl TestCaseManager {
         json.push_str("\n  ]\n");
         json.push_str("}\n");
 
-        fs::write(&testcases_file, json)?;
+        crate::modules::storage::encryption::write(&testcases_file, json)?;
         Ok(())
     }
 
@@ -423,14 +482,13 @@ impl TestCaseManager {
     }
     
     fn load_test_case(&self, file_path: &Path) -> QmsResult<TestCase> {
-        let _content = fs::read_to_string(file_path)?;
-        // Simple JSON parsing - create a basic test case from filename
+        let content = crate::modules::storage::encryption::read_to_string(file_path)?;
         let test_id = file_path.file_stem()
             .and_then(|s| s.to_str())
             .unwrap_or("unknown")
             .to_string();
         
-        let test_case = TestCase {
+        let mut test_case = TestCase {
             test_id: test_id.clone(),
             title: format!("Test Case {test_id}"),
             description: "Loaded from file".to_string(),
@@ -445,11 +503,170 @@ impl TestCaseManager {
             execution_results: Vec::new(),
             tags: Vec::new(),
         };
+
+        // Older files only hold counts; keep the defaults above for anything they lack
+        if let Ok(JsonValue::Object(obj)) = JsonValue::parse(&content) {
+            let text = |field: &str| obj.get(field).and_then(|v| v.as_string()).cloned();
+            if let Some(title) = text("title") {
+                test_case.title = title;
+            }
+            if let Some(description) = text("description") {
+                test_case.description = description;
+            }
+            if let Some(created_by) = text("created_by") {
+                test_case.created_by = created_by;
+            }
+            if let Some(category) = text("category").as_deref().and_then(parse_category) {
+                test_case.category = category;
+            }
+            if let Some(priority) = text("priority").as_deref().and_then(parse_priority) {
+                test_case.priority = priority;
+            }
+            if let Some(JsonValue::Array(tags)) = obj.get("tags") {
+                test_case.tags = tags.iter().filter_map(|t| t.as_string().cloned()).collect();
+            }
+            if let Some(JsonValue::Array(executions)) = obj.get("executions") {
+                test_case.execution_results = executions.iter().filter_map(execution_from_json).collect();
+            }
+        }
         
         Ok(test_case)
     }
 }
 
+fn system_time_to_secs(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH).map(|d| d.as_secs() as f64).unwrap_or(0.0)
+}
+
+fn secs_to_system_time(secs: f64) -> SystemTime {
+    UNIX_EPOCH + std::time::Duration::from_secs(secs.max(0.0) as u64)
+}
+
+fn optional_string(value: &Option<String>) -> JsonValue {
+    value.as_ref().map_or(JsonValue::Null, |v| JsonValue::String(v.clone()))
+}
+
+/// Test case file contents; `steps` and `execution_results` stay counts for existing readers
+fn test_case_to_json(test_case: &TestCase) -> JsonValue {
+    let executions = test_case.execution_results.iter().map(|execution| {
+        let step_results = execution.step_results.iter().map(|step| {
+            let mut obj = HashMap::new();
+            obj.insert("step_number".to_string(), JsonValue::Number(step.step_number as f64));
+            obj.insert("status".to_string(), JsonValue::String(format!("{:?}", step.status)));
+            obj.insert("actual_result".to_string(), JsonValue::String(step.actual_result.clone()));
+            obj.insert("notes".to_string(), optional_string(&step.notes));
+            JsonValue::Object(obj)
+        }).collect();
+
+        let mut obj = HashMap::new();
+        obj.insert("execution_id".to_string(), JsonValue::String(execution.execution_id.clone()));
+        obj.insert("executed_by".to_string(), JsonValue::String(execution.executed_by.clone()));
+        obj.insert("execution_date".to_string(), JsonValue::Number(system_time_to_secs(execution.execution_date)));
+        obj.insert("overall_status".to_string(), JsonValue::String(format!("{:?}", execution.overall_status)));
+        obj.insert("duration_seconds".to_string(),
+            execution.duration_seconds.map_or(JsonValue::Null, |d| JsonValue::Number(d as f64)));
+        obj.insert("environment".to_string(), optional_string(&execution.environment));
+        obj.insert("build".to_string(), optional_string(&execution.build));
+        obj.insert("notes".to_string(), optional_string(&execution.notes));
+        obj.insert("step_results".to_string(), JsonValue::Array(step_results));
+        JsonValue::Object(obj)
+    }).collect();
+
+    let mut obj = HashMap::new();
+    obj.insert("test_id".to_string(), JsonValue::String(test_case.test_id.clone()));
+    obj.insert("title".to_string(), JsonValue::String(test_case.title.clone()));
+    obj.insert("description".to_string(), JsonValue::String(test_case.description.clone()));
+    obj.insert("category".to_string(), JsonValue::String(format!("{:?}", test_case.category)));
+    obj.insert("priority".to_string(), JsonValue::String(format!("{:?}", test_case.priority)));
+    obj.insert("created_by".to_string(), JsonValue::String(test_case.created_by.clone()));
+    obj.insert("tags".to_string(),
+        JsonValue::Array(test_case.tags.iter().map(|t| JsonValue::String(t.clone())).collect()));
+    obj.insert("steps".to_string(), JsonValue::Number(test_case.steps.len() as f64));
+    obj.insert("execution_results".to_string(), JsonValue::Number(test_case.execution_results.len() as f64));
+    obj.insert("executions".to_string(), JsonValue::Array(executions));
+    JsonValue::Object(obj)
+}
+
+fn execution_from_json(value: &JsonValue) -> Option<TestExecution> {
+    let JsonValue::Object(obj) = value else {
+        return None;
+    };
+    let text = |field: &str| obj.get(field).and_then(|v| v.as_string()).cloned();
+    let number = |field: &str| obj.get(field).and_then(|v| v.as_number());
+
+    let step_results = match obj.get("step_results") {
+        Some(JsonValue::Array(steps)) => steps.iter().filter_map(|step| {
+            let JsonValue::Object(step) = step else {
+                return None;
+            };
+            Some(TestStepResult {
+                step_number: step.get("step_number").and_then(|v| v.as_number())? as u32,
+                status: step.get("status").and_then(|v| v.as_string()).and_then(|s| parse_step_status(s))?,
+                actual_result: step.get("actual_result").and_then(|v| v.as_string()).cloned().unwrap_or_default(),
+                notes: step.get("notes").and_then(|v| v.as_string()).cloned(),
+            })
+        }).collect(),
+        _ => Vec::new(),
+    };
+
+    Some(TestExecution {
+        execution_id: text("execution_id")?,
+        executed_by: text("executed_by").unwrap_or_default(),
+        execution_date: secs_to_system_time(number("execution_date").unwrap_or(0.0)),
+        overall_status: parse_execution_status(&text("overall_status")?)?,
+        duration_seconds: number("duration_seconds").map(|d| d as u64),
+        environment: text("environment"),
+        build: text("build"),
+        notes: text("notes"),
+        step_results,
+    })
+}
+
+fn parse_category(name: &str) -> Option<TestCategory> {
+    match name {
+        "Functional" => Some(TestCategory::Functional),
+        "Performance" => Some(TestCategory::Performance),
+        "Security" => Some(TestCategory::Security),
+        "Usability" => Some(TestCategory::Usability),
+        "Integration" => Some(TestCategory::Integration),
+        "Regression" => Some(TestCategory::Regression),
+        "Smoke" => Some(TestCategory::Smoke),
+        "UserAcceptance" => Some(TestCategory::UserAcceptance),
+        _ => None,
+    }
+}
+
+fn parse_priority(name: &str) -> Option<TestPriority> {
+    match name {
+        "Critical" => Some(TestPriority::Critical),
+        "High" => Some(TestPriority::High),
+        "Medium" => Some(TestPriority::Medium),
+        "Low" => Some(TestPriority::Low),
+        _ => None,
+    }
+}
+
+fn parse_step_status(name: &str) -> Option<TestStepStatus> {
+    match name {
+        "NotExecuted" => Some(TestStepStatus::NotExecuted),
+        "Passed" => Some(TestStepStatus::Passed),
+        "Failed" => Some(TestStepStatus::Failed),
+        "Blocked" => Some(TestStepStatus::Blocked),
+        "Skipped" => Some(TestStepStatus::Skipped),
+        _ => None,
+    }
+}
+
+fn parse_execution_status(name: &str) -> Option<TestExecutionStatus> {
+    match name {
+        "Passed" => Some(TestExecutionStatus::Passed),
+        "Failed" => Some(TestExecutionStatus::Failed),
+        "Blocked" => Some(TestExecutionStatus::Blocked),
+        "Incomplete" => Some(TestExecutionStatus::Incomplete),
+        _ => None,
+    }
+}
+
 impl std::fmt::Display for TestCategory {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -615,4 +832,53 @@ mod tests {
         let test_case = manager.get_test_case("TC-001").unwrap();
         assert_eq!(test_case.execution_results.len(), 1);
     }
+
+    #[test]
+    fn test_tags_and_recorded_executions_survive_reload() {
+        let temp_dir = TempDir::new().unwrap();
+        init_audit_for_test(temp_dir.path());
+        let mut manager = TestCaseManager::new(temp_dir.path()).unwrap();
+
+        manager.create_test_case(
+            "TC-002".to_string(),
+            "Flow \"limit\" Test".to_string(),
+            "Occlusion alarm".to_string(),
+            TestCategory::Security,
+            TestPriority::Critical,
+            "test_user".to_string(),
+        ).unwrap();
+        manager.set_tags("TC-002", vec!["flow_limit".to_string()]).unwrap();
+
+        let execution = TestExecution {
+            execution_id: String::new(),
+            executed_by: "ci".to_string(),
+            execution_date: SystemTime::now(),
+            overall_status: TestExecutionStatus::Failed,
+            duration_seconds: Some(3),
+            environment: Some("CI".to_string()),
+            build: Some("1.4.0+abc123".to_string()),
+            notes: None,
+            step_results: vec![TestStepResult {
+                step_number: 1,
+                status: TestStepStatus::Failed,
+                actual_result: "flow_limit_alarm failed".to_string(),
+                notes: Some("assertion failed".to_string()),
+            }],
+        };
+        let first = manager.record_execution("TC-002", execution.clone()).unwrap();
+        let second = manager.record_execution("TC-002", execution).unwrap();
+        assert_ne!(first, second);
+
+        let reloaded = TestCaseManager::new(temp_dir.path()).unwrap();
+        let test_case = reloaded.get_test_case("TC-002").unwrap();
+        assert_eq!(test_case.title, "Flow \"limit\" Test");
+        assert!(matches!(test_case.category, TestCategory::Security));
+        assert_eq!(test_case.tags, vec!["flow_limit".to_string()]);
+        assert_eq!(test_case.execution_results.len(), 2);
+        let latest = &test_case.execution_results[1];
+        assert_eq!(latest.execution_id, second);
+        assert!(matches!(latest.overall_status, TestExecutionStatus::Failed));
+        assert_eq!(latest.build.as_deref(), Some("1.4.0+abc123"));
+        assert_eq!(latest.step_results[0].notes.as_deref(), Some("assertion failed"));
+    }
 }
@@ -0,0 +1,648 @@
+//! Automated test result ingestion
+//! Parses JUnit XML, TAP and libtest JSON output, records the results as test executions
+//! and refreshes the verification status of the requirements those test cases verify.
+
+use crate::error::{QmsError, QmsResult};
+use crate::json_utils::JsonValue;
+use crate::modules::traceability::links::TraceabilityManager;
+use crate::modules::traceability::test_case::{
+    TestCase, TestCaseManager, TestExecution, TestExecutionStatus, TestStepResult, TestStepStatus,
+};
+use crate::modules::traceability::verification::{
+    RequirementVerificationManager, VerificationMethod, VerificationStatus,
+};
+use crate::xml_utils::{XmlElement, XmlNode};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Supported result file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestResultFormat {
+    JUnit,
+    Tap,
+    LibtestJson,
+}
+
+impl TestResultFormat {
+    pub fn parse(name: &str) -> QmsResult<Self> {
+        match name.to_lowercase().as_str() {
+            "junit" | "xml" => Ok(Self::JUnit),
+            "tap" => Ok(Self::Tap),
+            "libtest" | "json" | "cargo" => Ok(Self::LibtestJson),
+            _ => Err(QmsError::validation_error(&format!(
+                "Unknown result format '{name}'. Valid formats: junit, tap, libtest"
+            ))),
+        }
+    }
+
+    /// Guess the format from the file contents
+    pub fn detect(content: &str) -> Option<Self> {
+        let first_line = content
+            .trim_start_matches('\u{feff}')
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())?;
+        if first_line.starts_with('<') {
+            Some(Self::JUnit)
+        } else if first_line.starts_with('{') {
+            Some(Self::LibtestJson)
+        } else if first_line.starts_with("TAP version")
+            || first_line.starts_with("1..")
+            || first_line.starts_with("ok")
+            || first_line.starts_with("not ok")
+        {
+            Some(Self::Tap)
+        } else {
+            None
+        }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::JUnit => "junit",
+            Self::Tap => "tap",
+            Self::LibtestJson => "libtest",
+        }
+    }
+}
+
+/// Outcome of a single automated test
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestOutcome {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+/// One result read from a result file
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutomatedTestResult {
+    pub name: String,
+    pub suite: Option<String>, // JUnit classname or enclosing suite
+    pub outcome: TestOutcome,
+    pub duration_seconds: Option<f64>,
+    pub message: Option<String>,
+}
+
+impl AutomatedTestResult {
+    /// Name including the suite, as shown in execution records
+    pub fn full_name(&self) -> String {
+        match &self.suite {
+            Some(suite) if !suite.is_empty() => format!("{suite}::{}", self.name),
+            _ => self.name.clone(),
+        }
+    }
+}
+
+/// Parse a result file in the given format
+pub fn parse_results(content: &str, format: TestResultFormat) -> QmsResult<Vec<AutomatedTestResult>> {
+    match format {
+        TestResultFormat::JUnit => parse_junit(content),
+        TestResultFormat::Tap => Ok(parse_tap(content)),
+        TestResultFormat::LibtestJson => Ok(parse_libtest_json(content)),
+    }
+}
+
+fn parse_junit(content: &str) -> QmsResult<Vec<AutomatedTestResult>> {
+    let root = XmlElement::parse(content)?;
+    let mut results = Vec::new();
+    collect_junit_cases(&root, None, &mut results);
+    Ok(results)
+}
+
+fn collect_junit_cases(element: &XmlElement, suite: Option<&str>, results: &mut Vec<AutomatedTestResult>) {
+    match element.local_name() {
+        "testcase" => {
+            let mut outcome = TestOutcome::Passed;
+            let mut message = None;
+            for child in element.elements() {
+                let found = match child.local_name() {
+                    "failure" | "error" => TestOutcome::Failed,
+                    "skipped" => TestOutcome::Skipped,
+                    _ => continue,
+                };
+                // A failure outranks a skip if a runner reports both
+                if outcome != TestOutcome::Failed {
+                    outcome = found;
+                    let text = child.text();
+                    message = child
+                        .attr("message")
+                        .map(str::to_string)
+                        .or_else(|| Some(text.trim().to_string()).filter(|t| !t.is_empty()));
+                }
+            }
+            results.push(AutomatedTestResult {
+                name: element.attr("name").unwrap_or_default().to_string(),
+                suite: element.attr("classname").or(suite).map(str::to_string),
+                outcome,
+                duration_seconds: element.attr("time").and_then(|t| t.trim().parse().ok()),
+                message,
+            });
+        }
+        local_name => {
+            let suite = if local_name == "testsuite" { element.attr("name").or(suite) } else { suite };
+            for child in &element.children {
+                if let XmlNode::Element(child) = child {
+                    collect_junit_cases(child, suite, results);
+                }
+            }
+        }
+    }
+}
+
+fn parse_tap(content: &str) -> Vec<AutomatedTestResult> {
+    let mut results: Vec<AutomatedTestResult> = Vec::new();
+    let mut lines = content.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        // Indented lines belong to subtests or diagnostics of the previous result
+        let (passed, rest) = if let Some(rest) = line.strip_prefix("not ok") {
+            (false, rest)
+        } else if let Some(rest) = line.strip_prefix("ok") {
+            (true, rest)
+        } else {
+            continue;
+        };
+        if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+            continue;
+        }
+
+        let (description, directive) = match rest.find(" # ") {
+            Some(pos) => (&rest[..pos], Some(rest[pos + 3..].trim())),
+            None => (rest, None),
+        };
+        let description = description.trim_start();
+        let number_end = description.find(|c: char| !c.is_ascii_digit()).unwrap_or(description.len());
+        let number = &description[..number_end];
+        let name = description[number_end..].trim().trim_start_matches('-').trim();
+        let name = if name.is_empty() { format!("test {number}") } else { name.to_string() };
+
+        let directive_upper = directive.map(str::to_uppercase).unwrap_or_default();
+        let outcome = if directive_upper.starts_with("SKIP") || directive_upper.starts_with("TODO") {
+            TestOutcome::Skipped
+        } else if passed {
+            TestOutcome::Passed
+        } else {
+            TestOutcome::Failed
+        };
+
+        // Optional YAML diagnostic block
+        let mut message = directive.filter(|_| outcome == TestOutcome::Skipped).map(str::to_string);
+        if lines.peek().is_some_and(|next| next.trim() == "---") {
+            lines.next();
+            let mut block = Vec::new();
+            for diagnostic in lines.by_ref() {
+                if diagnostic.trim() == "..." {
+                    break;
+                }
+                block.push(diagnostic.trim());
+            }
+            message = block
+                .iter()
+                .find_map(|line| line.strip_prefix("message:"))
+                .map(|m| m.trim().trim_matches(|c| c == '\'' || c == '"').to_string())
+                .or_else(|| Some(block.join("\n")).filter(|b| !b.is_empty()));
+        }
+
+        results.push(AutomatedTestResult {
+            name,
+            suite: None,
+            outcome,
+            duration_seconds: None,
+            message,
+        });
+    }
+    results
+}
+
+fn parse_libtest_json(content: &str) -> Vec<AutomatedTestResult> {
+    content
+        .lines()
+        .filter_map(|line| match JsonValue::parse(line.trim()) {
+            Ok(JsonValue::Object(obj)) => Some(obj),
+            _ => None,
+        })
+        .filter(|obj| obj.get("type").and_then(|t| t.as_string()).map(String::as_str) == Some("test"))
+        .filter_map(|obj| {
+            let outcome = match obj.get("event").and_then(|e| e.as_string())?.as_str() {
+                "ok" => TestOutcome::Passed,
+                "failed" => TestOutcome::Failed,
+                "ignored" => TestOutcome::Skipped,
+                // "started" and "timeout" are progress events; the final event follows
+                _ => return None,
+            };
+            let text = |field: &str| {
+                obj.get(field)
+                    .and_then(|v| v.as_string())
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+            };
+            Some(AutomatedTestResult {
+                name: text("name")?,
+                suite: None,
+                outcome,
+                duration_seconds: obj.get("exec_time").and_then(|t| t.as_number()),
+                message: text("message").or_else(|| text("stdout")),
+            })
+        })
+        .collect()
+}
+
+/// Normalise a name for matching: upper case with `_` and spaces read as `-`
+fn normalize(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '_' | ' ' => '-',
+            c => c.to_ascii_uppercase(),
+        })
+        .collect()
+}
+
+/// Whether `token` occurs in `haystack` bounded by non-alphanumeric characters
+fn contains_token(haystack: &str, token: &str) -> bool {
+    if token.is_empty() {
+        return false;
+    }
+    haystack.match_indices(token).any(|(start, _)| {
+        let before = haystack[..start].chars().next_back();
+        let after = haystack[start + token.len()..].chars().next();
+        !before.is_some_and(|c| c.is_ascii_alphanumeric()) && !after.is_some_and(|c| c.is_ascii_alphanumeric())
+    })
+}
+
+/// Whether an automated result belongs to a test case, by test case ID or one of its tags
+pub fn result_matches(result: &AutomatedTestResult, test_case: &TestCase) -> bool {
+    let name = normalize(&result.full_name());
+    contains_token(&name, &normalize(&test_case.test_id))
+        || test_case.tags.iter().any(|tag| contains_token(&name, &normalize(tag)))
+}
+
+/// Metadata recorded on every execution created by an ingest
+#[derive(Debug, Clone)]
+pub struct IngestOptions {
+    pub executed_by: String,
+    pub environment: Option<String>,
+    pub build: Option<String>,
+    pub source_name: String, // Result file name, quoted in execution notes
+}
+
+/// Execution recorded for one test case
+#[derive(Debug, Clone)]
+pub struct IngestedExecution {
+    pub test_id: String,
+    pub execution_id: String,
+    pub status: TestExecutionStatus,
+    pub result_count: usize,
+}
+
+/// Outcome of an ingest
+#[derive(Debug, Clone)]
+pub struct IngestSummary {
+    pub format: TestResultFormat,
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub unmatched: Vec<String>,
+    pub executions: Vec<IngestedExecution>,
+    pub requirements: Vec<(String, VerificationStatus)>,
+}
+
+/// Record automated results as one execution per matched test case and
+/// refresh the verification status of the requirements linked to those test cases
+pub fn ingest_results(
+    project_path: &Path,
+    manager: &mut TestCaseManager,
+    results: &[AutomatedTestResult],
+    format: TestResultFormat,
+    options: &IngestOptions,
+) -> QmsResult<IngestSummary> {
+    let count = |outcome: TestOutcome| results.iter().filter(|r| r.outcome == outcome).count();
+    let mut summary = IngestSummary {
+        format,
+        total: results.len(),
+        passed: count(TestOutcome::Passed),
+        failed: count(TestOutcome::Failed),
+        skipped: count(TestOutcome::Skipped),
+        unmatched: Vec::new(),
+        executions: Vec::new(),
+        requirements: Vec::new(),
+    };
+
+    let mut matched: BTreeMap<String, Vec<&AutomatedTestResult>> = BTreeMap::new();
+    for result in results {
+        let test_ids: Vec<String> = manager
+            .list_test_cases()
+            .into_iter()
+            .filter(|test_case| result_matches(result, test_case))
+            .map(|test_case| test_case.test_id.clone())
+            .collect();
+        if test_ids.is_empty() {
+            summary.unmatched.push(result.full_name());
+        }
+        for test_id in test_ids {
+            matched.entry(test_id).or_default().push(result);
+        }
+    }
+
+    for (test_id, test_results) in &matched {
+        let execution = build_execution(test_results, format, options);
+        let status = execution.overall_status.clone();
+        let execution_id = manager.record_execution(test_id, execution)?;
+        summary.executions.push(IngestedExecution {
+            test_id: test_id.clone(),
+            execution_id,
+            status,
+            result_count: test_results.len(),
+        });
+    }
+
+    if !summary.executions.is_empty() {
+        summary.requirements = refresh_verification(project_path, manager, &summary.executions)?;
+    }
+    Ok(summary)
+}
+
+fn build_execution(
+    results: &[&AutomatedTestResult],
+    format: TestResultFormat,
+    options: &IngestOptions,
+) -> TestExecution {
+    let has = |outcome: TestOutcome| results.iter().any(|r| r.outcome == outcome);
+    let overall_status = if has(TestOutcome::Failed) {
+        TestExecutionStatus::Failed
+    } else if has(TestOutcome::Passed) {
+        TestExecutionStatus::Passed
+    } else {
+        TestExecutionStatus::Incomplete
+    };
+
+    let step_results = results
+        .iter()
+        .enumerate()
+        .map(|(i, result)| TestStepResult {
+            step_number: i as u32 + 1,
+            status: match result.outcome {
+                TestOutcome::Passed => TestStepStatus::Passed,
+                TestOutcome::Failed => TestStepStatus::Failed,
+                TestOutcome::Skipped => TestStepStatus::Skipped,
+            },
+            actual_result: result.full_name(),
+            notes: result.message.clone(),
+        })
+        .collect();
+
+    let durations: Vec<f64> = results.iter().filter_map(|r| r.duration_seconds).collect();
+    let count = |outcome: TestOutcome| results.iter().filter(|r| r.outcome == outcome).count();
+
+    TestExecution {
+        execution_id: String::new(),
+        executed_by: options.executed_by.clone(),
+        execution_date: SystemTime::now(),
+        overall_status,
+        duration_seconds: (!durations.is_empty()).then(|| durations.iter().sum::<f64>().round() as u64),
+        environment: options.environment.clone(),
+        build: options.build.clone(),
+        notes: Some(format!(
+            "Ingested from {} ({}): {} passed, {} failed, {} skipped",
+            options.source_name,
+            format.name(),
+            count(TestOutcome::Passed),
+            count(TestOutcome::Failed),
+            count(TestOutcome::Skipped)
+        )),
+        step_results,
+    }
+}
+
+/// Re-derive the status of every requirement linked to a freshly executed test case
+///
+/// A requirement is fully verified when the latest execution of each of its linked
+/// test cases passed, partially verified when at least one did, and otherwise not verified.
+fn refresh_verification(
+    project_path: &Path,
+    manager: &TestCaseManager,
+    executions: &[IngestedExecution],
+) -> QmsResult<Vec<(String, VerificationStatus)>> {
+    let links = TraceabilityManager::new(project_path)?.get_trace_links()?;
+    let mut tests_by_requirement: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for link in &links {
+        let pair = match (link.source_type.as_str(), link.target_type.as_str()) {
+            ("Requirement", "TestCase") => (&link.source_id, &link.target_id),
+            ("TestCase", "Requirement") => (&link.target_id, &link.source_id),
+            _ => continue,
+        };
+        tests_by_requirement.entry(pair.0.clone()).or_default().insert(pair.1.clone());
+    }
+
+    let mut verification = RequirementVerificationManager::new(&project_path.join("verification.json").to_string_lossy());
+    verification.load()?;
+
+    let mut updated = Vec::new();
+    for (requirement_id, test_ids) in &tests_by_requirement {
+        let evidence: Vec<&IngestedExecution> =
+            executions.iter().filter(|e| test_ids.contains(&e.test_id)).collect();
+        if evidence.is_empty() {
+            continue;
+        }
+
+        let latest_passed: Vec<bool> = test_ids
+            .iter()
+            .map(|test_id| {
+                manager
+                    .get_test_case(test_id)
+                    .and_then(|test_case| test_case.execution_results.last())
+                    .is_some_and(|e| matches!(e.overall_status, TestExecutionStatus::Passed))
+            })
+            .collect();
+        let status = if latest_passed.iter().all(|passed| *passed) {
+            VerificationStatus::FullyVerified
+        } else if latest_passed.iter().any(|passed| *passed) {
+            VerificationStatus::PartiallyVerified
+        } else {
+            VerificationStatus::NotVerified
+        };
+
+        for execution in evidence {
+            verification.add_verification(requirement_id, VerificationMethod::Test, &execution.execution_id)?;
+        }
+        verification.update_verification_status(requirement_id, status.clone())?;
+        updated.push((requirement_id.clone(), status));
+    }
+
+    verification.save()?;
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::audit_logger::{initialize_audit_system, AuditConfig};
+    use crate::modules::traceability::links::TraceLinkType;
+    use crate::modules::traceability::requirement::{RequirementCategory, RequirementManager};
+    use crate::modules::traceability::test_case::{TestCategory, TestPriority};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_junit_results() {
+        let xml = r#"<?xml version="1.0"?>
+<testsuites>
+  <testsuite name="pump" tests="3">
+    <testcase name="tc_012_flow_limit" classname="pump::flow" time="0.50"/>
+    <testcase name="occlusion_alarm" time="1.25">
+      <failure message="alarm not raised">assertion failed</failure>
+    </testcase>
+    <testcase name="battery_soak"><skipped/></testcase>
+  </testsuite>
+</testsuites>"#;
+        assert_eq!(TestResultFormat::detect(xml), Some(TestResultFormat::JUnit));
+        let results = parse_results(xml, TestResultFormat::JUnit).unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].full_name(), "pump::flow::tc_012_flow_limit");
+        assert_eq!(results[0].duration_seconds, Some(0.5));
+        assert_eq!(results[1].suite.as_deref(), Some("pump"));
+        assert_eq!(results[1].outcome, TestOutcome::Failed);
+        assert_eq!(results[1].message.as_deref(), Some("alarm not raised"));
+        assert_eq!(results[2].outcome, TestOutcome::Skipped);
+    }
+
+    #[test]
+    fn test_parse_tap_and_libtest_results() {
+        let tap = "TAP version 13\n1..4\nok 1 - TC-001 login\nnot ok 2 - TC-002 logout\n  ---\n  message: 'session kept'\n  ...\nok 3 # SKIP no hardware\nnot ok 4 flaky # TODO fix\n";
+        assert_eq!(TestResultFormat::detect(tap), Some(TestResultFormat::Tap));
+        let results = parse_results(tap, TestResultFormat::Tap).unwrap();
+        let outcomes: Vec<_> = results.iter().map(|r| r.outcome).collect();
+        assert_eq!(outcomes, vec![TestOutcome::Passed, TestOutcome::Failed, TestOutcome::Skipped, TestOutcome::Skipped]);
+        assert_eq!(results[0].name, "TC-001 login");
+        assert_eq!(results[1].message.as_deref(), Some("session kept"));
+        assert_eq!(results[2].name, "test 3");
+
+        let json = concat!(
+            "{ \"type\": \"suite\", \"event\": \"started\", \"test_count\": 2 }\n",
+            "{ \"type\": \"test\", \"event\": \"started\", \"name\": \"tests::tc_003_alarm\" }\n",
+            "{ \"type\": \"test\", \"name\": \"tests::tc_003_alarm\", \"event\": \"ok\", \"exec_time\": 0.25 }\n",
+            "{ \"type\": \"test\", \"name\": \"tests::tc_004_bolus\", \"event\": \"failed\", \"stdout\": \"panicked at 'dose'\\n\" }\n",
+            "{ \"type\": \"suite\", \"event\": \"failed\", \"passed\": 1, \"failed\": 1 }\n",
+        );
+        assert_eq!(TestResultFormat::detect(json), Some(TestResultFormat::LibtestJson));
+        let results = parse_results(json, TestResultFormat::LibtestJson).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].duration_seconds, Some(0.25));
+        assert_eq!(results[1].outcome, TestOutcome::Failed);
+        assert_eq!(results[1].message.as_deref(), Some("panicked at 'dose'"));
+    }
+
+    #[test]
+    fn test_matching_by_id_and_tag_respects_token_boundaries() {
+        let mut test_case = TestCase {
+            test_id: "TC-012".to_string(),
+            title: "Flow".to_string(),
+            description: String::new(),
+            category: TestCategory::Functional,
+            priority: TestPriority::High,
+            preconditions: None,
+            postconditions: None,
+            steps: Vec::new(),
+            created_by: "tester".to_string(),
+            created_date: SystemTime::now(),
+            last_modified: SystemTime::now(),
+            execution_results: Vec::new(),
+            tags: Vec::new(),
+        };
+        let result = |name: &str| AutomatedTestResult {
+            name: name.to_string(),
+            suite: None,
+            outcome: TestOutcome::Passed,
+            duration_seconds: None,
+            message: None,
+        };
+
+        assert!(result_matches(&result("tests::tc_012_flow_limit"), &test_case));
+        assert!(result_matches(&result("TC-012"), &test_case));
+        assert!(!result_matches(&result("tc_0120_other"), &test_case));
+        assert!(!result_matches(&result("xtc_012"), &test_case));
+
+        test_case.tags.push("flow-limit".to_string());
+        assert!(result_matches(&result("pump::flow_limit_alarm"), &test_case));
+        assert!(!result_matches(&result("pump::flow_limits"), &test_case));
+    }
+
+    #[test]
+    fn test_ingest_records_executions_and_refreshes_verification() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_path = temp_dir.path();
+        let _ = initialize_audit_system(AuditConfig {
+            project_path: project_path.to_string_lossy().to_string(),
+            retention_days: 30,
+            daily_rotation: false,
+            max_file_size_mb: 10,
+            require_checksums: false,
+        });
+
+        let mut manager = TestCaseManager::new(project_path).unwrap();
+        for test_id in ["TC-001", "TC-002"] {
+            manager.create_test_case(
+                test_id.to_string(),
+                format!("Test {test_id}"),
+                "Automated".to_string(),
+                TestCategory::Functional,
+                TestPriority::High,
+                "tester".to_string(),
+            ).unwrap();
+        }
+        manager.set_tags("TC-002", vec!["occlusion".to_string()]).unwrap();
+
+        let mut requirements = RequirementManager::new(project_path).unwrap();
+        for req_id in ["REQ-001", "REQ-002"] {
+            requirements.create_requirement(
+                project_path.to_string_lossy().to_string(),
+                req_id.to_string(),
+                format!("Requirement {req_id}"),
+                "The pump shall alarm".to_string(),
+                RequirementCategory::Functional,
+                "tester".to_string(),
+            ).unwrap();
+        }
+        let trace = TraceabilityManager::new(project_path).unwrap();
+        trace.create_trace_link("REQ-001", "TC-001", TraceLinkType::Verifies).unwrap();
+        trace.create_trace_link("REQ-002", "TC-001", TraceLinkType::Verifies).unwrap();
+        trace.create_trace_link("REQ-002", "TC-002", TraceLinkType::Verifies).unwrap();
+
+        let junit = r#"<testsuite name="pump">
+  <testcase name="tc_001_flow_rate" time="1.4"/>
+  <testcase name="tc_001_flow_limit" time="0.7"/>
+  <testcase name="occlusion_alarm"><failure message="no alarm"/></testcase>
+  <testcase name="unrelated"/>
+</testsuite>"#;
+        let results = parse_results(junit, TestResultFormat::JUnit).unwrap();
+        let options = IngestOptions {
+            executed_by: "ci".to_string(),
+            environment: Some("CI".to_string()),
+            build: Some("1.4.0".to_string()),
+            source_name: "junit.xml".to_string(),
+        };
+        let summary = ingest_results(project_path, &mut manager, &results, TestResultFormat::JUnit, &options).unwrap();
+
+        assert_eq!((summary.total, summary.passed, summary.failed), (4, 3, 1));
+        assert_eq!(summary.unmatched, vec!["pump::unrelated".to_string()]);
+        assert_eq!(summary.executions.len(), 2);
+        assert_eq!(summary.executions[0].result_count, 2);
+        assert!(matches!(summary.executions[0].status, TestExecutionStatus::Passed));
+        assert!(matches!(summary.executions[1].status, TestExecutionStatus::Failed));
+
+        let execution = &manager.get_test_case("TC-001").unwrap().execution_results[0];
+        assert_eq!(execution.build.as_deref(), Some("1.4.0"));
+        assert_eq!(execution.duration_seconds, Some(2));
+        assert_eq!(execution.step_results.len(), 2);
+
+        assert_eq!(summary.requirements, vec![
+            ("REQ-001".to_string(), VerificationStatus::FullyVerified),
+            ("REQ-002".to_string(), VerificationStatus::PartiallyVerified),
+        ]);
+        let mut verification = RequirementVerificationManager::new(&project_path.join("verification.json").to_string_lossy());
+        verification.load().unwrap();
+        let req_002 = verification.get_verification("REQ-002").unwrap();
+        assert_eq!(req_002.verification_status, VerificationStatus::PartiallyVerified);
+    }
+}
@@ -182,7 +182,7 @@ impl RequirementVerificationManager {
             return Ok(());
         }
         
-        let content = fs::read_to_string(path)?;
+        let content = crate::modules::storage::encryption::read_to_string(path)?;
         if content.trim().is_empty() {
             return Ok(());
         }
@@ -198,7 +198,7 @@ impl RequirementVerificationManager {
     /// Save verification data to storage
     pub fn save(&self) -> QmsResult<()> {
         let json_content = self.serialize_verifications_to_json()?;
-        fs::write(&self.storage_path, json_content)?;
+        crate::modules::storage::encryption::write(&self.storage_path, json_content)?;
         log_audit("verification_data_saved");
         Ok(())
     }
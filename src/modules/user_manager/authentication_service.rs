@@ -3,6 +3,8 @@
 
 use crate::error::{QmsError, QmsResult};
 use crate::audit::{log_user_action, log_system_event};
+use crate::modules::user_manager::mfa::{is_second_factor_required, MfaStore};
+use crate::modules::user_manager::signing_keys::SigningKeyStore;
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH, Duration};
 use argon2::{self, Config};
@@ -86,8 +88,8 @@ pub struct User {
     pub failed_login_attempts: u32,
     pub account_locked: bool,
     pub password_expires_at: u64,
-    pub mfa_enabled: bool,
-    pub mfa_secret: Option<String>,
+    pub mfa_enabled: bool,          // Mirrors enrollment in the service's MFA store
+    pub mfa_secret: Option<String>, // Base32 TOTP secret from that enrollment
 }
 
 impl User {
@@ -132,6 +134,8 @@ pub struct AuthenticationService {
     sessions: HashMap<String, UserSession>,
     session_timeout_minutes: u64,
     max_failed_attempts: u32,
+    mfa_store: Option<MfaStore>,
+    signing_keys: Option<SigningKeyStore>,
 }
 
 impl AuthenticationService {
@@ -141,11 +145,40 @@ impl AuthenticationService {
             sessions: HashMap::new(),
             session_timeout_minutes: SESSION_TIMEOUT_MINUTES, // FDA recommended session timeout
             max_failed_attempts: MAX_FAILED_ATTEMPTS,
+            mfa_store: None,
+            signing_keys: None,
         }
     }
 
+    /// Require a TOTP code at login from users enrolled in `mfa_store`
+    pub fn with_mfa_store(mut self, mfa_store: MfaStore) -> Self {
+        for user in self.users.values_mut() {
+            Self::sync_mfa(user, Some(&mfa_store));
+        }
+        self.mfa_store = Some(mfa_store);
+        self
+    }
+
+    /// Re-wrap users' signing keys in `signing_keys` whenever their password changes
+    pub fn with_signing_key_store(mut self, signing_keys: SigningKeyStore) -> Self {
+        self.signing_keys = Some(signing_keys);
+        self
+    }
+
+    /// Refresh a user's MFA fields from the enrollment kept in `mfa_store`
+    fn sync_mfa(user: &mut User, mfa_store: Option<&MfaStore>) {
+        let enrollment = mfa_store.and_then(|store| store.load(&user.username).ok());
+        user.mfa_enabled = mfa_store.is_some_and(|store| store.is_enrolled(&user.username));
+        user.mfa_secret = enrollment.map(|enrollment| enrollment.secret);
+    }
+
     /// Authenticate user and create session
     pub fn authenticate(&mut self, credentials: UserCredentials) -> QmsResult<UserSession> {
+        self.authenticate_with_code(credentials, None)
+    }
+
+    /// Authenticate user, requiring a TOTP or recovery code when they are enrolled
+    pub fn authenticate_with_code(&mut self, credentials: UserCredentials, code: Option<&str>) -> QmsResult<UserSession> {
         log_user_action(&credentials.username, "LOGIN_ATTEMPT", "authentication_service", "INITIATED");
 
         // Check if user exists
@@ -154,6 +187,7 @@ impl AuthenticationService {
                 log_user_action(&credentials.username, "LOGIN_ATTEMPT", "authentication_service", "FAILED_USER_NOT_FOUND");
                 QmsError::authentication_error("Invalid credentials")
             })?;
+        Self::sync_mfa(user, self.mfa_store.as_ref());
 
         // Check if account is locked
         if user.account_locked {
@@ -169,17 +203,27 @@ impl AuthenticationService {
 
         // Verify password
         if !self.verify_password(&credentials.password, &user.password_hash) {
-            user.failed_login_attempts += 1;
-            
-            if user.failed_login_attempts >= self.max_failed_attempts {
-                user.account_locked = true;
-                log_user_action(&credentials.username, "ACCOUNT_LOCKED", "authentication_service", "MAX_ATTEMPTS_EXCEEDED");
-            }
-            
+            Self::record_failed_attempt(user, self.max_failed_attempts);
             log_user_action(&credentials.username, "LOGIN_ATTEMPT", "authentication_service", "FAILED_INVALID_PASSWORD");
             return Err(QmsError::authentication_error("Invalid credentials"));
         }
 
+        // Verify second factor; a code that cannot be checked counts as a wrong one
+        if let Some(ref mfa_store) = self.mfa_store {
+            match mfa_store.challenge(&credentials.username, code) {
+                Ok(_) => {}
+                Err(e) if is_second_factor_required(&e) => {
+                    log_user_action(&credentials.username, "LOGIN_ATTEMPT", "authentication_service", "MFA_CODE_REQUIRED");
+                    return Err(e);
+                }
+                Err(_) => {
+                    Self::record_failed_attempt(user, self.max_failed_attempts);
+                    log_user_action(&credentials.username, "LOGIN_ATTEMPT", "authentication_service", "FAILED_INVALID_MFA_CODE");
+                    return Err(QmsError::authentication_error("Invalid authentication code"));
+                }
+            }
+        }
+
         // Reset failed attempts on successful authentication
         user.failed_login_attempts = 0;
         let now = SystemTime::now()
@@ -197,6 +241,15 @@ impl AuthenticationService {
         Ok(session)
     }
 
+    /// Count a failed login and lock the account once the limit is reached
+    fn record_failed_attempt(user: &mut User, max_failed_attempts: u32) {
+        user.failed_login_attempts += 1;
+        if user.failed_login_attempts >= max_failed_attempts {
+            user.account_locked = true;
+            log_user_action(&user.username, "ACCOUNT_LOCKED", "authentication_service", "MAX_ATTEMPTS_EXCEEDED");
+        }
+    }
+
     /// Create a new user session
     fn create_session(&mut self, user: &User) -> QmsResult<UserSession> {
         let now = SystemTime::now()
@@ -264,6 +317,7 @@ impl AuthenticationService {
         let mut user = User::new(username.clone(), email, role);
         let password_hash = self.hash_password(&password)?;
         user.password_hash = password_hash;
+        Self::sync_mfa(&mut user, self.mfa_store.as_ref());
 
         self.users.insert(username.clone(), user.clone());
         
@@ -287,10 +341,18 @@ impl AuthenticationService {
         // Validate new password strength
         self.validate_password_strength(new_password)?;
 
-        // Update password
+        // Re-wrap the signing key before switching passwords, so a failure leaves both unchanged
         let password_hash = self.hash_password(new_password)?;
+        if let Some(ref signing_keys) = self.signing_keys {
+            if signing_keys.has_key_pair(username) {
+                signing_keys.change_password(username, old_password, new_password).map_err(|e| {
+                    log_user_action(username, "PASSWORD_CHANGE", "authentication_service", "FAILED_SIGNING_KEY_REWRAP");
+                    QmsError::domain_error(&format!("Failed to re-wrap signing key: {e}"))
+                })?;
+            }
+        }
         user.password_hash = password_hash;
-        
+
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
@@ -394,6 +456,7 @@ use uuid::Uuid;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::modules::user_manager::mfa;
 
     #[test]
     fn test_user_creation() {
@@ -515,6 +578,71 @@ mod tests {
         assert!(!session.session_id.is_empty());
     }
 
+    #[test]
+    fn test_authentication_with_mfa() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mfa_store = MfaStore::new(temp_dir.path());
+        let mut auth_service = AuthenticationService::new().with_mfa_store(mfa_store.clone());
+        auth_service.register_user(
+            "mfa_user".to_string(),
+            "mfa@example.com".to_string(),
+            "Password123!".to_string(),
+            UserRole::QualityEngineer
+        ).unwrap();
+        let setup = mfa_store.enroll("mfa_user", "QMS").unwrap();
+
+        let credentials = UserCredentials {
+            username: "mfa_user".to_string(),
+            password: "Password123!".to_string(),
+        };
+        let missing = auth_service.authenticate(credentials.clone()).unwrap_err();
+        assert!(is_second_factor_required(&missing));
+        let user = auth_service.get_user("mfa_user").unwrap();
+        assert!(user.mfa_enabled);
+        assert_eq!(user.mfa_secret.as_deref(), Some(setup.secret.as_str()));
+
+        let secret = mfa::base32_decode(&setup.secret).unwrap();
+        let code = mfa::totp_code(&secret, crate::utils::current_timestamp());
+        assert!(auth_service.authenticate_with_code(credentials.clone(), Some(&code)).is_ok());
+        // The same code cannot be replayed
+        assert!(auth_service.authenticate_with_code(credentials.clone(), Some(&code)).is_err());
+        assert!(auth_service.authenticate_with_code(credentials, Some(&setup.recovery_codes[0])).is_ok());
+    }
+
+    #[test]
+    fn test_wrong_mfa_codes_lock_the_account() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mfa_store = MfaStore::new(temp_dir.path());
+        let mut auth_service = AuthenticationService::new().with_mfa_store(mfa_store.clone());
+        auth_service.register_user(
+            "mfa_user".to_string(),
+            "mfa@example.com".to_string(),
+            "Password123!".to_string(),
+            UserRole::QualityEngineer
+        ).unwrap();
+        mfa_store.enroll("mfa_user", "QMS").unwrap();
+        let credentials = UserCredentials {
+            username: "mfa_user".to_string(),
+            password: "Password123!".to_string(),
+        };
+
+        for _ in 0..MAX_FAILED_ATTEMPTS {
+            assert!(auth_service.authenticate_with_code(credentials.clone(), Some("not-a-code")).is_err());
+        }
+        assert!(auth_service.get_user("mfa_user").unwrap().account_locked);
+
+        // An enrollment that cannot be read never lets the user through
+        let mut other = AuthenticationService::new().with_mfa_store(mfa_store);
+        other.register_user(
+            "mfa_user".to_string(),
+            "mfa@example.com".to_string(),
+            "Password123!".to_string(),
+            UserRole::QualityEngineer
+        ).unwrap();
+        std::fs::write(temp_dir.path().join("users").join("mfa").join("mfa_user.json"), "{}").unwrap();
+        assert!(other.authenticate_with_code(credentials, Some("123456")).is_err());
+    }
+
     #[test]
     fn test_failed_authentication_invalid_user() {
         let mut auth_service = AuthenticationService::new();
@@ -704,6 +832,34 @@ mod tests {
         assert!(auth_service.authenticate(new_credentials).is_ok());
     }
 
+    #[test]
+    fn test_password_change_rewraps_signing_key() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let key_store = SigningKeyStore::new(temp_dir.path());
+        let mut auth_service = AuthenticationService::new()
+            .with_signing_key_store(SigningKeyStore::new(temp_dir.path()));
+        auth_service.register_user(
+            "signer".to_string(),
+            "signer@example.com".to_string(),
+            "Password123!".to_string(),
+            UserRole::QualityEngineer
+        ).unwrap();
+        key_store.generate_key_pair("signer", "Password123!").unwrap();
+
+        auth_service.change_password("signer", "Password123!", "NewPassword456!").unwrap();
+        assert!(key_store.unlock("signer", "NewPassword456!").is_ok());
+        assert!(key_store.unlock("signer", "Password123!").is_err());
+
+        // A key that cannot be re-wrapped leaves the login password unchanged
+        key_store.change_password("signer", "NewPassword456!", "Other789!").unwrap();
+        assert!(auth_service.change_password("signer", "NewPassword456!", "Third012!").is_err());
+        let credentials = UserCredentials {
+            username: "signer".to_string(),
+            password: "NewPassword456!".to_string(),
+        };
+        assert!(auth_service.authenticate(credentials).is_ok());
+    }
+
     #[test]
     fn test_expired_session_cleanup() {
         let mut auth_service = AuthenticationService::new();
@@ -23,7 +23,7 @@ impl FileSessionStorage {
         if !sessions_path.exists() {
             let empty_sessions = Vec::<UserSession>::new();
             let json_content = Self::serialize_sessions(&empty_sessions)?;
-            fs::write(&sessions_path, json_content)?;
+            crate::modules::storage::encryption::write(&sessions_path, json_content)?;
         }
         
         Ok(FileSessionStorage {
@@ -341,14 +341,14 @@ impl FileSessionStorage {
             return Ok(Vec::new());
         }
 
-        let content = fs::read_to_string(&self.sessions_path)?;
+        let content = crate::modules::storage::encryption::read_to_string(&self.sessions_path)?;
         self.parse_sessions_json(&content)
     }
     
     /// Save all sessions to file
     fn save_sessions(&self, sessions: &[UserSession]) -> QmsResult<()> {
         let json_content = Self::serialize_sessions(sessions)?;
-        fs::write(&self.sessions_path, json_content)?;
+        crate::modules::storage::encryption::write(&self.sessions_path, json_content)?;
         Ok(())
     }
 }
@@ -72,7 +72,7 @@ impl FileUserStorage {
             admin_user.created_at
         );
 
-        fs::write(path, users_json)?;
+        crate::modules::storage::encryption::write(path, users_json)?;
         Ok(())
     }
     
@@ -475,14 +475,14 @@ impl FileUserStorage {
 
         json.push_str("\n  ]\n}");
 
-        fs::write(&self.users_path, json)?;
+        crate::modules::storage::encryption::write(&self.users_path, json)?;
         Ok(())
     }
 }
 
 impl UserStorage for FileUserStorage {
     fn save_user(&self, user: &User) -> QmsResult<()> {
-        let content = fs::read_to_string(&self.users_path)?;
+        let content = crate::modules::storage::encryption::read_to_string(&self.users_path)?;
         let mut users = self.parse_users_json(&content)?;
         
         // Update existing user or add new one
@@ -504,7 +504,7 @@ impl UserStorage for FileUserStorage {
     }
     
     fn load_user(&self, username: &str) -> QmsResult<User> {
-        let content = fs::read_to_string(&self.users_path)?;
+        let content = crate::modules::storage::encryption::read_to_string(&self.users_path)?;
         let users = self.parse_users_json(&content)?;
         
         users.into_iter()
@@ -521,12 +521,12 @@ impl UserStorage for FileUserStorage {
     }
     
     fn list_users(&self) -> QmsResult<Vec<User>> {
-        let content = fs::read_to_string(&self.users_path)?;
+        let content = crate::modules::storage::encryption::read_to_string(&self.users_path)?;
         self.parse_users_json(&content)
     }
     
     fn delete_user(&self, username: &str) -> QmsResult<()> {
-        let content = fs::read_to_string(&self.users_path)?;
+        let content = crate::modules::storage::encryption::read_to_string(&self.users_path)?;
         let mut users = self.parse_users_json(&content)?;
         
         let initial_len = users.len();
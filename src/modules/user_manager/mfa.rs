@@ -0,0 +1,505 @@
+//! Time-based one-time passwords (RFC 6238) as a second authentication factor
+//!
+//! A user enrolls once: a random 160-bit secret is generated and handed to
+//! their authenticator app through an `otpauth://` URI, together with a set
+//! of single-use recovery codes. Only the secret and the SHA-256 of each
+//! recovery code are persisted. Codes are HMAC-SHA1, 30-second steps, six
+//! digits, accepted one step either side of the current time; a step that
+//! has already been accepted is refused so an observed code cannot be
+//! replayed. Part 11 §11.200(a)(1) asks for two distinct identification
+//! components, so enrolled users must present a code at login and again
+//! each time they sign.
+//!
+//! A user has exactly one enrollment. Other stores (such as a project's, used
+//! for signing) hold only a link to the store that owns it, so a step or
+//! recovery code consumed in one place is consumed everywhere.
+
+use crate::error::{QmsError, QmsResult};
+use crate::json_utils::JsonValue;
+use crate::utils::{current_timestamp, encode_hex};
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Length of one TOTP time step in seconds
+pub const TOTP_STEP_SECS: u64 = 30;
+/// Number of digits in a TOTP code
+pub const TOTP_DIGITS: u32 = 6;
+/// Steps either side of the current one that are still accepted (clock skew)
+const TOTP_SKEW_STEPS: u64 = 1;
+/// Recovery codes issued at enrollment
+const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Error message used when the password was right but no code was supplied
+pub const SECOND_FACTOR_REQUIRED: &str = "Authentication code required";
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Which factor satisfied a verification
+#[derive(Debug, Clone, PartialEq)]
+pub enum SecondFactor {
+    Totp,
+    RecoveryCode,
+}
+
+/// Stored second-factor material for a single user
+#[derive(Debug, Clone)]
+pub struct MfaEnrollment {
+    pub username: String,
+    pub secret: String,              // Base32 TOTP secret
+    pub recovery_codes: Vec<String>, // SHA-256 of each unused recovery code (hex)
+    pub enrolled_at: u64,
+    pub last_used_step: u64, // Most recent accepted time step, for replay protection
+}
+
+/// What the user is shown once at enrollment
+#[derive(Debug, Clone)]
+pub struct MfaSetup {
+    pub secret: String,
+    pub otpauth_uri: String,
+    pub recovery_codes: Vec<String>,
+}
+
+/// File-backed store of TOTP enrollments under `<base>/users/mfa`
+#[derive(Debug, Clone)]
+pub struct MfaStore {
+    mfa_dir: PathBuf,
+}
+
+impl MfaEnrollment {
+    fn generate(username: &str) -> (Self, Vec<String>) {
+        let secret: [u8; 20] = rand::thread_rng().gen();
+        let recovery_codes: Vec<String> = (0..RECOVERY_CODE_COUNT).map(|_| generate_recovery_code()).collect();
+
+        let enrollment = Self {
+            username: username.to_string(),
+            secret: base32_encode(&secret),
+            recovery_codes: recovery_codes.iter().map(|c| hash_recovery_code(c)).collect(),
+            enrolled_at: current_timestamp(),
+            last_used_step: 0,
+        };
+        (enrollment, recovery_codes)
+    }
+
+    /// Check a TOTP or recovery code at `now`, consuming it on success
+    fn verify_at(&mut self, code: &str, now: u64) -> QmsResult<SecondFactor> {
+        let code: String = code.chars().filter(|c| !c.is_whitespace() && *c != '-').collect();
+
+        if code.len() == TOTP_DIGITS as usize && code.chars().all(|c| c.is_ascii_digit()) {
+            let secret = base32_decode(&self.secret)?;
+            let current = now / TOTP_STEP_SECS;
+            let first = current.saturating_sub(TOTP_SKEW_STEPS).max(self.last_used_step + 1);
+            for step in first..=current + TOTP_SKEW_STEPS {
+                if hotp(&secret, step) == code {
+                    self.last_used_step = step;
+                    return Ok(SecondFactor::Totp);
+                }
+            }
+        } else {
+            let hash = hash_recovery_code(&code.to_ascii_lowercase());
+            if let Some(index) = self.recovery_codes.iter().position(|h| *h == hash) {
+                self.recovery_codes.remove(index);
+                return Ok(SecondFactor::RecoveryCode);
+            }
+        }
+
+        Err(QmsError::Authentication("Invalid authentication code".to_string()))
+    }
+
+    fn to_json(&self) -> String {
+        let mut obj = HashMap::new();
+        obj.insert("version".to_string(), JsonValue::String("1.0".to_string()));
+        obj.insert("algorithm".to_string(), JsonValue::String("TOTP-SHA1".to_string()));
+        obj.insert("username".to_string(), JsonValue::String(self.username.clone()));
+        obj.insert("secret".to_string(), JsonValue::String(self.secret.clone()));
+        obj.insert(
+            "recovery_codes".to_string(),
+            JsonValue::Array(self.recovery_codes.iter().cloned().map(JsonValue::String).collect()),
+        );
+        obj.insert("enrolled_at".to_string(), JsonValue::Number(self.enrolled_at as f64));
+        obj.insert("last_used_step".to_string(), JsonValue::Number(self.last_used_step as f64));
+        JsonValue::Object(obj).json_to_string()
+    }
+
+    fn from_json(json: &str) -> QmsResult<Self> {
+        let obj = match JsonValue::parse(json)? {
+            JsonValue::Object(obj) => obj,
+            _ => return Err(QmsError::parse_error("MFA file must contain a JSON object")),
+        };
+
+        let recovery_codes = match obj.get("recovery_codes") {
+            Some(JsonValue::Array(items)) => items
+                .iter()
+                .filter_map(|item| match item {
+                    JsonValue::String(s) => Some(s.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Ok(Self {
+            username: string_field(&obj, "username")?,
+            secret: string_field(&obj, "secret")?,
+            recovery_codes,
+            enrolled_at: obj.get("enrolled_at").and_then(JsonValue::as_number).unwrap_or(0.0) as u64,
+            last_used_step: obj.get("last_used_step").and_then(JsonValue::as_number).unwrap_or(0.0) as u64,
+        })
+    }
+}
+
+impl MfaStore {
+    /// Create a store next to the user records kept under `base_path`
+    pub fn new(base_path: &Path) -> Self {
+        Self {
+            mfa_dir: base_path.join("users").join("mfa"),
+        }
+    }
+
+    /// Enroll a user, returning the secret, URI and recovery codes to show once
+    pub fn enroll(&self, username: &str, issuer: &str) -> QmsResult<MfaSetup> {
+        if self.is_enrolled(username) {
+            return Err(QmsError::already_exists(&format!(
+                "Two-factor authentication already enrolled for user: {username}"
+            )));
+        }
+
+        let (enrollment, recovery_codes) = MfaEnrollment::generate(username);
+        self.save(&enrollment)?;
+
+        Ok(MfaSetup {
+            otpauth_uri: otpauth_uri(issuer, username, &enrollment.secret),
+            secret: enrollment.secret,
+            recovery_codes,
+        })
+    }
+
+    /// Make this store use the enrollment kept in `owner` instead of a copy
+    pub fn link(&self, username: &str, owner: &MfaStore) -> QmsResult<()> {
+        if owner.mfa_dir == self.mfa_dir {
+            return Err(QmsError::validation_error("An MFA store cannot link to itself"));
+        }
+        if owner.linked_store(username)?.is_some() {
+            return Err(QmsError::validation_error(&format!(
+                "Enrollment for {username} is itself a link and cannot be linked to"
+            )));
+        }
+
+        let mut obj = HashMap::new();
+        obj.insert("version".to_string(), JsonValue::String("1.0".to_string()));
+        obj.insert("username".to_string(), JsonValue::String(username.to_string()));
+        obj.insert(
+            "linked_store".to_string(),
+            JsonValue::String(owner.mfa_dir.to_string_lossy().into_owned()),
+        );
+
+        std::fs::create_dir_all(&self.mfa_dir)?;
+        let path = self.enrollment_path(username);
+        crate::modules::storage::encryption::atomic_write(&path, &JsonValue::Object(obj).json_to_string())?;
+        crate::fs_utils::set_secure_file_permissions(&path)
+    }
+
+    /// Check whether a user has enrolled a second factor
+    pub fn is_enrolled(&self, username: &str) -> bool {
+        self.enrollment_path(username).exists()
+    }
+
+    /// Load a user's enrollment, following a link to the store that owns it
+    pub fn load(&self, username: &str) -> QmsResult<MfaEnrollment> {
+        self.owner(username)?.load_own(username)
+    }
+
+    /// Verify a TOTP or recovery code for `username`
+    pub fn verify(&self, username: &str, code: &str) -> QmsResult<SecondFactor> {
+        self.verify_at(username, code, current_timestamp())
+    }
+
+    /// Verify a code against the clock value `now` (seconds since the epoch)
+    pub fn verify_at(&self, username: &str, code: &str, now: u64) -> QmsResult<SecondFactor> {
+        let owner = self.owner(username)?;
+        let mut enrollment = owner.load_own(username)?;
+        let factor = enrollment.verify_at(code, now)?;
+        owner.save(&enrollment)?;
+        Ok(factor)
+    }
+
+    /// Check a login or signing attempt: enrolled users must supply a valid code
+    pub fn challenge(&self, username: &str, code: Option<&str>) -> QmsResult<Option<SecondFactor>> {
+        if !self.is_enrolled(username) {
+            return Ok(None);
+        }
+        match code.map(str::trim).filter(|c| !c.is_empty()) {
+            Some(code) => self.verify(username, code).map(Some),
+            None => Err(QmsError::Authentication(SECOND_FACTOR_REQUIRED.to_string())),
+        }
+    }
+
+    /// Remove a user's enrollment, or only the link when this store holds one
+    pub fn disable(&self, username: &str) -> QmsResult<()> {
+        let path = self.enrollment_path(username);
+        if !path.exists() {
+            return Err(QmsError::not_found(&format!(
+                "No second factor enrolled for user: {username}"
+            )));
+        }
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+
+    /// The store holding the user's enrollment: this one, or the one it links to
+    fn owner(&self, username: &str) -> QmsResult<MfaStore> {
+        match self.linked_store(username)? {
+            Some(owner) if owner.is_enrolled(username) => Ok(owner),
+            Some(_) => Err(QmsError::not_found(&format!(
+                "Linked second-factor enrollment for user {username} is missing"
+            ))),
+            None => Ok(self.clone()),
+        }
+    }
+
+    /// The store this one links to for `username`, if its entry is a link
+    fn linked_store(&self, username: &str) -> QmsResult<Option<MfaStore>> {
+        let path = self.enrollment_path(username);
+        if !path.exists() {
+            return Err(QmsError::not_found(&format!(
+                "No second factor enrolled for user: {username}"
+            )));
+        }
+        match JsonValue::parse(&crate::modules::storage::encryption::read_to_string(path)?)? {
+            JsonValue::Object(obj) => Ok(match obj.get("linked_store") {
+                Some(JsonValue::String(dir)) => Some(MfaStore { mfa_dir: PathBuf::from(dir) }),
+                _ => None,
+            }),
+            _ => Err(QmsError::parse_error("MFA file must contain a JSON object")),
+        }
+    }
+
+    fn load_own(&self, username: &str) -> QmsResult<MfaEnrollment> {
+        let path = self.enrollment_path(username);
+        MfaEnrollment::from_json(&crate::modules::storage::encryption::read_to_string(path)?)
+    }
+
+    fn save(&self, enrollment: &MfaEnrollment) -> QmsResult<()> {
+        std::fs::create_dir_all(&self.mfa_dir)?;
+        let path = self.enrollment_path(&enrollment.username);
+        crate::modules::storage::encryption::atomic_write(&path, &enrollment.to_json())?;
+        crate::fs_utils::set_secure_file_permissions(&path)
+    }
+
+    fn enrollment_path(&self, username: &str) -> PathBuf {
+        self.mfa_dir.join(format!("{username}.json"))
+    }
+}
+
+/// Whether an authentication error means only the second factor was missing
+pub fn is_second_factor_required(error: &QmsError) -> bool {
+    matches!(error, QmsError::Authentication(msg) if msg == SECOND_FACTOR_REQUIRED)
+}
+
+/// Compute the TOTP code for `secret` at `now` (seconds since the epoch)
+pub fn totp_code(secret: &[u8], now: u64) -> String {
+    hotp(secret, now / TOTP_STEP_SECS)
+}
+
+/// RFC 4226 HOTP value for `counter`, zero-padded to `TOTP_DIGITS`
+fn hotp(secret: &[u8], counter: u64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(&counter.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([digest[offset], digest[offset + 1], digest[offset + 2], digest[offset + 3]])
+        & 0x7fff_ffff;
+    format!("{:0width$}", binary % 10u32.pow(TOTP_DIGITS), width = TOTP_DIGITS as usize)
+}
+
+/// Build the `otpauth://` URI understood by authenticator apps
+pub fn otpauth_uri(issuer: &str, username: &str, secret: &str) -> String {
+    let issuer = percent_encode(issuer);
+    format!(
+        "otpauth://totp/{issuer}:{}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={TOTP_DIGITS}&period={TOTP_STEP_SECS}",
+        percent_encode(username)
+    )
+}
+
+/// RFC 4648 base32 without padding
+pub fn base32_encode(bytes: &[u8]) -> String {
+    let mut output = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            output.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        output.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    output
+}
+
+/// Decode RFC 4648 base32, ignoring case, spaces and padding
+pub fn base32_decode(text: &str) -> QmsResult<Vec<u8>> {
+    let mut output = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in text.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a as char == c.to_ascii_uppercase())
+            .ok_or_else(|| QmsError::parse_error(&format!("Invalid base32 character '{c}'")))?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+    Ok(output)
+}
+
+/// Ten random base32 characters shown as `xxxxx-xxxxx`
+fn generate_recovery_code() -> String {
+    let bytes: [u8; 10] = rand::thread_rng().gen();
+    let code: String = bytes
+        .iter()
+        .map(|b| BASE32_ALPHABET[(b & 0x1f) as usize].to_ascii_lowercase() as char)
+        .collect();
+    format!("{}-{}", &code[..5], &code[5..])
+}
+
+fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code.chars().filter(|c| *c != '-').collect();
+    encode_hex(&Sha256::digest(normalized.to_ascii_lowercase().as_bytes()))
+}
+
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+fn string_field(obj: &HashMap<String, JsonValue>, field: &str) -> QmsResult<String> {
+    match obj.get(field) {
+        Some(JsonValue::String(s)) => Ok(s.clone()),
+        _ => Err(QmsError::parse_error(&format!("Missing or invalid field '{field}'"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_rfc6238_sha1_vectors() {
+        // RFC 6238 appendix B, truncated to six digits
+        let secret = b"12345678901234567890";
+        assert_eq!(totp_code(secret, 59), "287082");
+        assert_eq!(totp_code(secret, 1_111_111_109), "081804");
+        assert_eq!(totp_code(secret, 1_234_567_890), "005924");
+        assert_eq!(totp_code(secret, 2_000_000_000), "279037");
+    }
+
+    #[test]
+    fn test_base32_round_trip() {
+        assert_eq!(base32_encode(b"foobar"), "MZXW6YTBOI");
+        assert_eq!(base32_decode("mzxw6ytboi======").unwrap(), b"foobar");
+        assert!(base32_decode("not*base32").is_err());
+    }
+
+    #[test]
+    fn test_enroll_and_verify_totp() {
+        let temp_dir = tempdir().unwrap();
+        let store = MfaStore::new(temp_dir.path());
+
+        let setup = store.enroll("alice", "QMS").unwrap();
+        assert!(setup.otpauth_uri.starts_with("otpauth://totp/QMS:alice?secret="));
+        assert_eq!(setup.recovery_codes.len(), RECOVERY_CODE_COUNT);
+        assert!(store.enroll("alice", "QMS").is_err());
+
+        let secret = base32_decode(&setup.secret).unwrap();
+        let now = 1_700_000_000;
+        let code = totp_code(&secret, now);
+        assert_eq!(store.verify_at("alice", &code, now).unwrap(), SecondFactor::Totp);
+
+        // The same step cannot be used twice
+        assert!(store.verify_at("alice", &code, now).is_err());
+
+        // One step of clock skew is tolerated; codes far outside the window are not
+        let next = totp_code(&secret, now + TOTP_STEP_SECS);
+        assert!(store.verify_at("alice", &next, now).is_ok());
+        assert!(store.verify_at("alice", "000000", now + 10 * TOTP_STEP_SECS).is_err());
+    }
+
+    #[test]
+    fn test_recovery_codes_are_single_use() {
+        let temp_dir = tempdir().unwrap();
+        let store = MfaStore::new(temp_dir.path());
+        let setup = store.enroll("bob", "QMS").unwrap();
+
+        let code = setup.recovery_codes[0].to_ascii_uppercase();
+        assert_eq!(store.verify("bob", &code).unwrap(), SecondFactor::RecoveryCode);
+        assert!(store.verify("bob", &code).is_err());
+        assert_eq!(store.load("bob").unwrap().recovery_codes.len(), RECOVERY_CODE_COUNT - 1);
+    }
+
+    #[test]
+    fn test_linked_store_shares_used_codes() {
+        let login_dir = tempdir().unwrap();
+        let project_dir = tempdir().unwrap();
+        let login_store = MfaStore::new(login_dir.path());
+        let project_store = MfaStore::new(project_dir.path());
+
+        let setup = login_store.enroll("erin", "QMS").unwrap();
+        project_store.link("erin", &login_store).unwrap();
+        assert!(project_store.is_enrolled("erin"));
+        assert!(project_store.link("erin", &project_store).is_err());
+        assert!(MfaStore::new(login_dir.path()).link("erin", &project_store).is_err());
+
+        // A step accepted at login cannot be replayed for a signature, and vice versa
+        let secret = base32_decode(&setup.secret).unwrap();
+        let now = 1_700_000_000;
+        let code = totp_code(&secret, now);
+        login_store.verify_at("erin", &code, now).unwrap();
+        assert!(project_store.verify_at("erin", &code, now).is_err());
+        let next = totp_code(&secret, now + TOTP_STEP_SECS);
+        project_store.verify_at("erin", &next, now).unwrap();
+        assert!(login_store.verify_at("erin", &next, now).is_err());
+
+        // A recovery code spent in either place is gone from both
+        let recovery = &setup.recovery_codes[0];
+        assert_eq!(login_store.verify("erin", recovery).unwrap(), SecondFactor::RecoveryCode);
+        assert!(project_store.verify("erin", recovery).is_err());
+        assert_eq!(project_store.load("erin").unwrap().recovery_codes.len(), RECOVERY_CODE_COUNT - 1);
+
+        // Without the owning enrollment the link fails closed
+        login_store.disable("erin").unwrap();
+        assert!(project_store.challenge("erin", Some(&totp_code(&secret, now))).is_err());
+        project_store.disable("erin").unwrap();
+        assert_eq!(project_store.challenge("erin", None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_challenge_requires_code_only_when_enrolled() {
+        let temp_dir = tempdir().unwrap();
+        let store = MfaStore::new(temp_dir.path());
+        assert_eq!(store.challenge("carol", None).unwrap(), None);
+
+        store.enroll("carol", "QMS").unwrap();
+        let missing = store.challenge("carol", Some("  ")).unwrap_err();
+        assert!(is_second_factor_required(&missing));
+
+        store.disable("carol").unwrap();
+        assert!(!store.is_enrolled("carol"));
+    }
+}
@@ -22,6 +22,12 @@ pub mod startup_auth_service;
 // FDA 21 CFR Part 11 compliant authentication service
 pub mod authentication_service;
 
+// Per-user Ed25519 keys for electronic signatures
+pub mod signing_keys;
+
+// TOTP second factor for login and signing
+pub mod mfa;
+
 // Re-export main components
 pub use authentication_service::{
     AuthenticationService, User, UserCredentials, UserSession, UserRole, Permission
@@ -47,6 +53,8 @@ pub use profile_manager::{UserProfileManager, ProfileSummary, SetupValidation};
 pub use startup_auth_service::{StartupAuthService, StartupAuthResult, AdminSetupRequest, QmsFolderSetupRequest, SystemStats};
 pub use unified_auth_service::UnifiedAuthenticationService;
 pub use unified_auth_factory::{UnifiedAuthFactory, FileBasedAuthService};
+pub use signing_keys::{SigningKeyStore, UserKeyPair, PublicKeyRecord};
+pub use mfa::{MfaStore, MfaEnrollment, MfaSetup, SecondFactor};
 pub use implementations::{FileUserStorage, FileSessionStorage};
 
 // Legacy exports for backward compatibility
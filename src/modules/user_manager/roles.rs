@@ -168,7 +168,7 @@ impl RoleManager {
     
     /// Load user from storage (simplified version)
     fn load_user(&self, username: &str) -> QmsResult<User> {
-        let content = fs::read_to_string(&self.users_path)?;
+        let content = crate::modules::storage::encryption::read_to_string(&self.users_path)?;
         
         // Find user in JSON content
         if let Some(user_start) = content.find(&format!("\"username\": \"{username}\"")) {
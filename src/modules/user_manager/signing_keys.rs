@@ -0,0 +1,372 @@
+//! Per-user Ed25519 signing keys for 21 CFR Part 11 electronic signatures
+//!
+//! Each user owns one active key pair. The public key is stored in clear so
+//! any reviewer can verify signatures; the 32-byte private seed is wrapped
+//! with a key derived from the user's password (Argon2, random salt), so a
+//! signature can only be produced by someone who knows that password.
+//! Retired public keys are kept so signatures made before a key rotation
+//! remain verifiable.
+
+use crate::error::{QmsError, QmsResult};
+use crate::json_utils::JsonValue;
+use crate::utils::{current_timestamp, decode_hex, encode_hex};
+use argon2::Config;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Public half of a user's signing key
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublicKeyRecord {
+    pub fingerprint: String, // SHA-256 of the public key bytes (hex, first 16 bytes)
+    pub public_key: String,  // Ed25519 public key (hex)
+    pub created_at: u64,
+    pub retired_at: Option<u64>,
+}
+
+/// Stored key material for a single user
+#[derive(Debug, Clone)]
+pub struct UserKeyPair {
+    pub username: String,
+    pub active: PublicKeyRecord,
+    pub wrapped_seed: String, // Private seed XOR Argon2(password, salt) (hex)
+    pub salt: String,         // Argon2 salt (hex)
+    pub retired: Vec<PublicKeyRecord>,
+}
+
+/// File-backed store of user signing keys under `<project>/users/keys`
+pub struct SigningKeyStore {
+    keys_dir: PathBuf,
+}
+
+impl PublicKeyRecord {
+    fn from_verifying_key(key: &VerifyingKey) -> Self {
+        Self {
+            fingerprint: key_fingerprint(key),
+            public_key: encode_hex(key.as_bytes()),
+            created_at: current_timestamp(),
+            retired_at: None,
+        }
+    }
+
+    /// Decode the stored public key
+    pub fn verifying_key(&self) -> QmsResult<VerifyingKey> {
+        let bytes = decode_hex(&self.public_key)?;
+        let bytes: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| QmsError::validation_error("Public key must be 32 bytes"))?;
+        VerifyingKey::from_bytes(&bytes)
+            .map_err(|e| QmsError::validation_error(&format!("Invalid public key: {e}")))
+    }
+
+    fn to_json_value(&self) -> JsonValue {
+        let mut obj = HashMap::new();
+        obj.insert("fingerprint".to_string(), JsonValue::String(self.fingerprint.clone()));
+        obj.insert("public_key".to_string(), JsonValue::String(self.public_key.clone()));
+        obj.insert("created_at".to_string(), JsonValue::Number(self.created_at as f64));
+        obj.insert(
+            "retired_at".to_string(),
+            self.retired_at.map_or(JsonValue::Null, |t| JsonValue::Number(t as f64)),
+        );
+        JsonValue::Object(obj)
+    }
+
+    fn from_json_value(value: &JsonValue) -> QmsResult<Self> {
+        let obj = match value {
+            JsonValue::Object(obj) => obj,
+            _ => return Err(QmsError::parse_error("Public key record must be an object")),
+        };
+        Ok(Self {
+            fingerprint: string_field(obj, "fingerprint")?,
+            public_key: string_field(obj, "public_key")?,
+            created_at: obj.get("created_at").and_then(JsonValue::as_number).unwrap_or(0.0) as u64,
+            retired_at: obj.get("retired_at").and_then(JsonValue::as_number).map(|n| n as u64),
+        })
+    }
+}
+
+impl UserKeyPair {
+    /// Generate a fresh key pair whose private seed is protected by `password`
+    fn generate(username: &str, password: &str) -> QmsResult<(Self, SigningKey)> {
+        let seed: [u8; 32] = rand::thread_rng().gen();
+        let signing_key = SigningKey::from_bytes(&seed);
+        let key_pair = Self::wrap(username, &signing_key, password, Vec::new())?;
+        Ok((key_pair, signing_key))
+    }
+
+    /// Wrap an existing signing key under `password` with a new random salt
+    fn wrap(
+        username: &str,
+        signing_key: &SigningKey,
+        password: &str,
+        retired: Vec<PublicKeyRecord>,
+    ) -> QmsResult<Self> {
+        let salt: [u8; 32] = rand::thread_rng().gen();
+        let kek = derive_wrapping_key(password, &salt)?;
+        let wrapped: Vec<u8> = signing_key
+            .to_bytes()
+            .iter()
+            .zip(kek.iter())
+            .map(|(s, k)| s ^ k)
+            .collect();
+
+        Ok(Self {
+            username: username.to_string(),
+            active: PublicKeyRecord::from_verifying_key(&signing_key.verifying_key()),
+            wrapped_seed: encode_hex(&wrapped),
+            salt: encode_hex(&salt),
+            retired,
+        })
+    }
+
+    /// Recover the private key using the user's password.
+    /// A wrong password yields a seed whose public key does not match the stored one.
+    pub fn unlock(&self, password: &str) -> QmsResult<SigningKey> {
+        let salt = decode_hex(&self.salt)?;
+        let wrapped = decode_hex(&self.wrapped_seed)?;
+        if wrapped.len() != 32 {
+            return Err(QmsError::validation_error("Wrapped signing key is corrupt"));
+        }
+
+        let kek = derive_wrapping_key(password, &salt)?;
+        let mut seed = [0u8; 32];
+        for (i, byte) in seed.iter_mut().enumerate() {
+            *byte = wrapped[i] ^ kek[i];
+        }
+
+        let signing_key = SigningKey::from_bytes(&seed);
+        if encode_hex(signing_key.verifying_key().as_bytes()) != self.active.public_key {
+            return Err(QmsError::Authentication(
+                "Invalid password for signing key".to_string(),
+            ));
+        }
+        Ok(signing_key)
+    }
+
+    /// Find the public key (active or retired) with the given fingerprint
+    pub fn find_public_key(&self, fingerprint: &str) -> Option<&PublicKeyRecord> {
+        std::iter::once(&self.active)
+            .chain(self.retired.iter())
+            .find(|record| record.fingerprint == fingerprint)
+    }
+
+    fn to_json(&self) -> String {
+        let mut obj = HashMap::new();
+        obj.insert("version".to_string(), JsonValue::String("1.0".to_string()));
+        obj.insert("algorithm".to_string(), JsonValue::String("Ed25519".to_string()));
+        obj.insert("username".to_string(), JsonValue::String(self.username.clone()));
+        obj.insert("active".to_string(), self.active.to_json_value());
+        obj.insert("wrapped_seed".to_string(), JsonValue::String(self.wrapped_seed.clone()));
+        obj.insert("salt".to_string(), JsonValue::String(self.salt.clone()));
+        obj.insert(
+            "retired".to_string(),
+            JsonValue::Array(self.retired.iter().map(PublicKeyRecord::to_json_value).collect()),
+        );
+        JsonValue::Object(obj).json_to_string()
+    }
+
+    fn from_json(json: &str) -> QmsResult<Self> {
+        let obj = match JsonValue::parse(json)? {
+            JsonValue::Object(obj) => obj,
+            _ => return Err(QmsError::parse_error("Key file must contain a JSON object")),
+        };
+
+        let active = obj
+            .get("active")
+            .ok_or_else(|| QmsError::parse_error("Key file missing 'active' key"))
+            .and_then(PublicKeyRecord::from_json_value)?;
+
+        let retired = match obj.get("retired") {
+            Some(JsonValue::Array(items)) => items
+                .iter()
+                .map(PublicKeyRecord::from_json_value)
+                .collect::<QmsResult<Vec<_>>>()?,
+            _ => Vec::new(),
+        };
+
+        Ok(Self {
+            username: string_field(&obj, "username")?,
+            active,
+            wrapped_seed: string_field(&obj, "wrapped_seed")?,
+            salt: string_field(&obj, "salt")?,
+            retired,
+        })
+    }
+}
+
+impl SigningKeyStore {
+    /// Create a key store for a project
+    pub fn new(project_path: &Path) -> Self {
+        Self {
+            keys_dir: project_path.join("users").join("keys"),
+        }
+    }
+
+    /// Generate and persist a key pair for a new user
+    pub fn generate_key_pair(&self, username: &str, password: &str) -> QmsResult<PublicKeyRecord> {
+        if self.has_key_pair(username) {
+            return Err(QmsError::already_exists(&format!(
+                "Signing key already exists for user: {username}"
+            )));
+        }
+
+        let (key_pair, _) = UserKeyPair::generate(username, password)?;
+        self.save(&key_pair)?;
+        Ok(key_pair.active)
+    }
+
+    /// Replace the active key with a new one, retiring the old public key
+    pub fn rotate_key_pair(&self, username: &str, password: &str) -> QmsResult<PublicKeyRecord> {
+        let current = self.load(username)?;
+        current.unlock(password)?;
+
+        let mut retired = current.retired.clone();
+        let mut old = current.active.clone();
+        old.retired_at = Some(current_timestamp());
+        retired.push(old);
+
+        let (mut key_pair, _) = UserKeyPair::generate(username, password)?;
+        key_pair.retired = retired;
+        self.save(&key_pair)?;
+        Ok(key_pair.active)
+    }
+
+    /// Re-wrap the private key after a password change
+    pub fn change_password(&self, username: &str, old_password: &str, new_password: &str) -> QmsResult<()> {
+        let current = self.load(username)?;
+        let signing_key = current.unlock(old_password)?;
+        let key_pair = UserKeyPair::wrap(username, &signing_key, new_password, current.retired)?;
+        self.save(&key_pair)
+    }
+
+    /// Check whether a user has enrolled a signing key
+    pub fn has_key_pair(&self, username: &str) -> bool {
+        self.key_path(username).is_ok_and(|path| path.exists())
+    }
+
+    /// Unlock a user's private key with their password
+    pub fn unlock(&self, username: &str, password: &str) -> QmsResult<SigningKey> {
+        self.load(username)?.unlock(password)
+    }
+
+    /// Load a user's key pair
+    pub fn load(&self, username: &str) -> QmsResult<UserKeyPair> {
+        let path = self.key_path(username)?;
+        if !path.exists() {
+            return Err(QmsError::not_found(&format!(
+                "No signing key enrolled for user: {username}"
+            )));
+        }
+        UserKeyPair::from_json(&crate::modules::storage::encryption::read_to_string(path)?)
+    }
+
+    /// Verify a signature produced by `username` with the key `fingerprint`
+    pub fn verify(&self, username: &str, fingerprint: &str, message: &[u8], signature_hex: &str) -> QmsResult<bool> {
+        let key_pair = self.load(username)?;
+        let record = key_pair.find_public_key(fingerprint).ok_or_else(|| {
+            QmsError::not_found(&format!("Public key {fingerprint} not registered for user {username}"))
+        })?;
+
+        let signature_bytes: [u8; 64] = decode_hex(signature_hex)?
+            .try_into()
+            .map_err(|_| QmsError::validation_error("Signature must be 64 bytes"))?;
+        let signature = Signature::from_bytes(&signature_bytes);
+
+        Ok(record.verifying_key()?.verify(message, &signature).is_ok())
+    }
+
+    fn save(&self, key_pair: &UserKeyPair) -> QmsResult<()> {
+        std::fs::create_dir_all(&self.keys_dir)?;
+        crate::modules::storage::encryption::atomic_write(&self.key_path(&key_pair.username)?, &key_pair.to_json())?;
+        Ok(())
+    }
+
+    fn key_path(&self, username: &str) -> QmsResult<PathBuf> {
+        // The username becomes a file name, so it must not reach outside users/keys
+        if username.is_empty() || username.contains(['/', '\\']) || username.contains("..") {
+            return Err(QmsError::validation_error(&format!("Invalid username for a signing key: {username}")));
+        }
+        Ok(self.keys_dir.join(format!("{username}.json")))
+    }
+}
+
+/// Sign a message with an unlocked key, returning the hex-encoded signature
+pub fn sign_message(signing_key: &SigningKey, message: &[u8]) -> String {
+    encode_hex(&signing_key.sign(message).to_bytes())
+}
+
+/// Short, stable identifier for a public key
+pub fn key_fingerprint(key: &VerifyingKey) -> String {
+    encode_hex(&Sha256::digest(key.as_bytes())[..16])
+}
+
+/// Derive the 32-byte key-encryption key from a password
+fn derive_wrapping_key(password: &str, salt: &[u8]) -> QmsResult<Vec<u8>> {
+    let config = Config::default();
+    argon2::hash_raw(password.as_bytes(), salt, &config)
+        .map_err(|e| QmsError::domain_error(&format!("Key derivation failed: {e}")))
+}
+
+fn string_field(obj: &HashMap<String, JsonValue>, field: &str) -> QmsResult<String> {
+    match obj.get(field) {
+        Some(JsonValue::String(s)) => Ok(s.clone()),
+        _ => Err(QmsError::parse_error(&format!("Missing or invalid field '{field}'"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_generate_and_unlock_key_pair() {
+        let temp_dir = tempdir().unwrap();
+        let store = SigningKeyStore::new(temp_dir.path());
+
+        let record = store.generate_key_pair("alice", "correct horse").unwrap();
+        assert!(store.has_key_pair("alice"));
+
+        let signing_key = store.unlock("alice", "correct horse").unwrap();
+        assert_eq!(key_fingerprint(&signing_key.verifying_key()), record.fingerprint);
+
+        assert!(store.unlock("alice", "wrong password").is_err());
+
+        // Usernames cannot place key files outside users/keys
+        for username in ["../escape", "a/b", "a\\b", ".."] {
+            assert!(store.generate_key_pair(username, "correct horse").is_err());
+        }
+        assert!(!temp_dir.path().join("users").join("escape.json").exists());
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let temp_dir = tempdir().unwrap();
+        let store = SigningKeyStore::new(temp_dir.path());
+        let record = store.generate_key_pair("bob", "password123").unwrap();
+
+        let signing_key = store.unlock("bob", "password123").unwrap();
+        let signature = sign_message(&signing_key, b"record content");
+
+        assert!(store.verify("bob", &record.fingerprint, b"record content", &signature).unwrap());
+        assert!(!store.verify("bob", &record.fingerprint, b"tampered content", &signature).unwrap());
+    }
+
+    #[test]
+    fn test_rotation_keeps_old_keys_verifiable() {
+        let temp_dir = tempdir().unwrap();
+        let store = SigningKeyStore::new(temp_dir.path());
+        let old = store.generate_key_pair("carol", "password123").unwrap();
+        let old_signature = sign_message(&store.unlock("carol", "password123").unwrap(), b"v1");
+
+        let new = store.rotate_key_pair("carol", "password123").unwrap();
+        assert_ne!(old.fingerprint, new.fingerprint);
+        assert!(store.verify("carol", &old.fingerprint, b"v1", &old_signature).unwrap());
+
+        store.change_password("carol", "password123", "new-password").unwrap();
+        assert!(store.unlock("carol", "password123").is_err());
+        assert!(store.unlock("carol", "new-password").is_ok());
+    }
+}
@@ -4,7 +4,7 @@
 
 use crate::prelude::*;
 use crate::modules::user_manager::{
-    UnifiedAuthenticationService, FileUserStorage, FileSessionStorage, SessionType
+    UnifiedAuthenticationService, FileUserStorage, FileSessionStorage, SessionType, MfaStore
 };
 use std::path::Path;
 
@@ -17,7 +17,8 @@ impl UnifiedAuthFactory {
         let user_storage = FileUserStorage::new(project_path)?;
         let session_storage = FileSessionStorage::new(project_path)?;
 
-        Ok(UnifiedAuthenticationService::new(user_storage, session_storage))
+        Ok(UnifiedAuthenticationService::new(user_storage, session_storage)
+            .with_mfa_store(MfaStore::new(project_path)))
     }
 
     /// Create a global authentication service (for CLI use)
@@ -32,7 +33,8 @@ impl UnifiedAuthFactory {
         let user_storage = FileUserStorage::new(&qms_dir)?;
         let session_storage = FileSessionStorage::new(&qms_dir)?;
 
-        Ok(UnifiedAuthenticationService::new(user_storage, session_storage))
+        Ok(UnifiedAuthenticationService::new(user_storage, session_storage)
+            .with_mfa_store(MfaStore::new(&qms_dir)))
     }
 
     /// Create a file-based unified authentication service with custom timeout
@@ -43,7 +45,8 @@ impl UnifiedAuthFactory {
         let user_storage = FileUserStorage::new(project_path)?;
         let session_storage = FileSessionStorage::new(project_path)?;
 
-        Ok(UnifiedAuthenticationService::with_timeout(user_storage, session_storage, timeout_hours))
+        Ok(UnifiedAuthenticationService::with_timeout(user_storage, session_storage, timeout_hours)
+            .with_mfa_store(MfaStore::new(project_path)))
     }
 }
 
@@ -66,6 +69,11 @@ impl FileBasedAuthService {
     pub fn cli_login(&self, username: &str, password: &str) -> QmsResult<crate::modules::user_manager::UserSession> {
         self.login(username, password, SessionType::CLI, None, None)
     }
+
+    /// Login for CLI usage with a second-factor code
+    pub fn cli_login_with_code(&self, username: &str, password: &str, code: Option<&str>) -> QmsResult<crate::modules::user_manager::UserSession> {
+        self.login_with_code(username, password, code, SessionType::CLI, None, None)
+    }
     
     /// Login for web usage
     pub fn web_login(
@@ -77,6 +85,18 @@ impl FileBasedAuthService {
     ) -> QmsResult<crate::modules::user_manager::UserSession> {
         self.login(username, password, SessionType::Web, ip_address, user_agent)
     }
+
+    /// Login for web usage with a second-factor code
+    pub fn web_login_with_code(
+        &self,
+        username: &str,
+        password: &str,
+        code: Option<&str>,
+        ip_address: Option<String>,
+        user_agent: Option<String>
+    ) -> QmsResult<crate::modules::user_manager::UserSession> {
+        self.login_with_code(username, password, code, SessionType::Web, ip_address, user_agent)
+    }
 }
 
 #[cfg(test)]
@@ -166,6 +186,29 @@ mod tests {
         assert_eq!(session.user_agent, Some("Mozilla/5.0".to_string()));
     }
     
+    #[test]
+    fn test_login_requires_second_factor_when_enrolled() {
+        use crate::modules::user_manager::mfa::{base32_decode, is_second_factor_required, totp_code};
+
+        let temp_dir = tempdir().unwrap();
+        init_audit_for_test(temp_dir.path());
+
+        let auth_service = FileBasedAuthService::from_project_path(temp_dir.path()).unwrap();
+        auth_service.create_user("testuser", "password123", vec![]).unwrap();
+        let setup = MfaStore::new(temp_dir.path()).enroll("testuser", "QMS").unwrap();
+
+        let missing = auth_service.cli_login("testuser", "password123").unwrap_err();
+        assert!(is_second_factor_required(&missing));
+        assert!(auth_service.cli_login_with_code("testuser", "password123", Some("000000")).is_err());
+
+        let secret = base32_decode(&setup.secret).unwrap();
+        let code = totp_code(&secret, crate::utils::current_timestamp());
+        let session = auth_service
+            .web_login_with_code("testuser", "password123", Some(&code), None, None)
+            .unwrap();
+        assert_eq!(session.session_type, SessionType::Web);
+    }
+
     #[test]
     fn test_session_validation() {
         let temp_dir = tempdir().unwrap();
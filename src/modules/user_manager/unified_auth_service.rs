@@ -6,6 +6,7 @@ use crate::modules::user_manager::interfaces::{UserSession, User, UserRole, Sess
 use crate::audit::{log_user_action, log_system_event};
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
+use crate::modules::user_manager::mfa::{is_second_factor_required, MfaStore};
 use argon2::{self, Config};
 use rand::Rng;
 use tracing::error;
@@ -15,6 +16,7 @@ pub struct UnifiedAuthenticationService<U: UserStorage, S: SessionStorage> {
     user_storage: U,
     session_storage: Arc<Mutex<S>>,
     session_timeout_hours: u64,
+    mfa_store: Option<MfaStore>,
 }
 
 impl<U: UserStorage, S: SessionStorage> UnifiedAuthenticationService<U, S> {
@@ -24,6 +26,7 @@ impl<U: UserStorage, S: SessionStorage> UnifiedAuthenticationService<U, S> {
             user_storage,
             session_storage: Arc::new(Mutex::new(session_storage)),
             session_timeout_hours: 24, // Default 24 hours
+            mfa_store: None,
         }
     }
     
@@ -33,8 +36,15 @@ impl<U: UserStorage, S: SessionStorage> UnifiedAuthenticationService<U, S> {
             user_storage,
             session_storage: Arc::new(Mutex::new(session_storage)),
             session_timeout_hours: timeout_hours,
+            mfa_store: None,
         }
     }
+
+    /// Require a TOTP code at login from users enrolled in `mfa_store`
+    pub fn with_mfa_store(mut self, mfa_store: MfaStore) -> Self {
+        self.mfa_store = Some(mfa_store);
+        self
+    }
     
     /// Authenticate user and create session
     pub fn login(
@@ -44,6 +54,19 @@ impl<U: UserStorage, S: SessionStorage> UnifiedAuthenticationService<U, S> {
         session_type: SessionType,
         ip_address: Option<String>,
         user_agent: Option<String>
+    ) -> QmsResult<UserSession> {
+        self.login_with_code(username, password, None, session_type, ip_address, user_agent)
+    }
+
+    /// Authenticate user with password and, if enrolled, a TOTP or recovery code
+    pub fn login_with_code(
+        &self,
+        username: &str,
+        password: &str,
+        code: Option<&str>,
+        session_type: SessionType,
+        ip_address: Option<String>,
+        user_agent: Option<String>
     ) -> QmsResult<UserSession> {
         // Load user from storage
         let user = self.user_storage.load_user(username)?;
@@ -53,6 +76,22 @@ impl<U: UserStorage, S: SessionStorage> UnifiedAuthenticationService<U, S> {
             let _ = audit_log_action("LOGIN_FAILED", "User", username);
             return Err(QmsError::Authentication("Invalid credentials".to_string()));
         }
+
+        // Second factor, checked only once the password is known to be right
+        if let Some(ref mfa_store) = self.mfa_store {
+            match mfa_store.challenge(username, code) {
+                Ok(Some(factor)) => {
+                    let _ = audit_log_action("LOGIN_MFA_VERIFIED", "User", &format!("{username}|{factor:?}"));
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    if !is_second_factor_required(&e) {
+                        let _ = audit_log_action("LOGIN_MFA_FAILED", "User", username);
+                    }
+                    return Err(e);
+                }
+            }
+        }
         
         // Create session
         let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
@@ -331,6 +370,7 @@ impl<U: UserStorage + Clone, S: SessionStorage + Clone> Clone for UnifiedAuthent
             user_storage: self.user_storage.clone(),
             session_storage: Arc::new(Mutex::new(session_storage)),
             session_timeout_hours: self.session_timeout_hours,
+            mfa_store: self.mfa_store.clone(),
         }
     }
 }
@@ -18,6 +18,28 @@ use std::collections::HashMap;
 // Type alias for JSON values to avoid serde_json dependency
 pub type JsonValue = String;
 
+/// Raw JSON documents are stored under their top-level `id` field
+impl crate::modules::storage::StorageEntity for JsonValue {
+    fn storage_id(&self) -> String {
+        match crate::json_utils::JsonValue::parse(self) {
+            Ok(crate::json_utils::JsonValue::Object(obj)) => obj
+                .get("id")
+                .and_then(|id| id.as_string())
+                .cloned()
+                .unwrap_or_else(crate::utils::generate_uuid),
+            _ => crate::utils::generate_uuid(),
+        }
+    }
+
+    fn to_storage_json(&self) -> String {
+        self.clone()
+    }
+
+    fn from_storage_json(json: &str) -> QmsResult<Self> {
+        Ok(json.to_string())
+    }
+}
+
 /// Data Access Service Interface
 ///
 /// Provides unified data access capabilities that can be used by all interfaces,
@@ -287,7 +309,7 @@ impl UnifiedDataAccessService {
                     }
                     
                     // Check if file is readable
-                    if let Err(e) = std::fs::read_to_string(&path) {
+                    if let Err(e) = crate::modules::storage::encryption::read_to_string(&path) {
                         errors.push(format!("Cannot read file content: {} - {}", path.display(), e));
                     }
                 }
@@ -354,6 +354,7 @@ impl RiskServiceInterface for UnifiedRiskService {
             detectability: RiskDetectability::Moderate,
             risk_priority_number: 27, // 3 * 3 * 3
             initial_risk_level: RiskLevel::ALARP,
+            policy_version: String::new(),
             mitigation_measures: Vec::new(),
             residual_severity: RiskSeverity::Major,
             residual_occurrence: RiskOccurrence::Occasional,
@@ -7,6 +7,7 @@
 use crate::prelude::*;
 use crate::modules::user_manager::auth::{AuthManager, UserSession};
 use crate::modules::user_manager::implementations::FileUserStorage;
+use crate::modules::user_manager::mfa::MfaStore;
 use crate::modules::audit_logger::audit_log_action;
 use crate::models::{Permission, Role};
 use std::path::PathBuf;
@@ -46,6 +47,11 @@ impl TuiAuthManager {
 
     /// Authenticate user with username and password
     pub fn login(&mut self, username: &str, password: &str) -> QmsResult<TuiAuthResult> {
+        self.login_with_code(username, password, None)
+    }
+
+    /// Authenticate user with password and, if they enrolled MFA, a TOTP or recovery code
+    pub fn login_with_code(&mut self, username: &str, password: &str, code: Option<&str>) -> QmsResult<TuiAuthResult> {
         // Validate input
         if username.trim().is_empty() {
             return Ok(TuiAuthResult {
@@ -63,6 +69,30 @@ impl TuiAuthManager {
             });
         }
 
+        // Second factor, checked only once the password is known to be right
+        let mfa_store = MfaStore::new(&self.project_path);
+        if mfa_store.is_enrolled(username) {
+            let password_ok = {
+                let auth_manager = self.auth_manager.lock().unwrap();
+                auth_manager
+                    .load_user(username)
+                    .map(|user| AuthManager::<FileUserStorage>::verify_password(password, &user.password_hash))
+                    .unwrap_or(false)
+            };
+            if password_ok {
+                if let Err(e) = mfa_store.challenge(username, code) {
+                    if let Err(audit_err) = audit_log_action("TUI_LOGIN_FAILED", "Session", username) {
+                        eprintln!("Warning: Failed to log TUI login failure: {}", audit_err);
+                    }
+                    return Ok(TuiAuthResult {
+                        success: false,
+                        message: format!("Login failed: {}", e),
+                        session: None,
+                    });
+                }
+            }
+        }
+
         // Attempt authentication through unified auth system
         let login_result = {
             let mut auth_manager = self.auth_manager.lock().unwrap();
@@ -6,6 +6,7 @@
 use crate::prelude::*;
 use crate::tui::{Terminal, Theme};
 use crate::tui::auth::{TuiAuthManager, TuiAuthResult};
+use crate::modules::user_manager::mfa::SECOND_FACTOR_REQUIRED;
 use std::io::{self, Write};
 use std::path::PathBuf;
 
@@ -14,6 +15,7 @@ pub struct TuiLoginScreen {
     auth_manager: TuiAuthManager,
     username: String,
     password: String,
+    code: String,
     current_field: LoginField,
     message: Option<String>,
     is_error: bool,
@@ -26,6 +28,7 @@ pub struct TuiLoginScreen {
 enum LoginField {
     Username,
     Password,
+    Code,
     Submit,
 }
 
@@ -47,6 +50,7 @@ impl TuiLoginScreen {
             auth_manager,
             username: String::new(),
             password: String::new(),
+            code: String::new(),
             current_field: LoginField::Username,
             message: None,
             is_error: false,
@@ -126,8 +130,28 @@ impl TuiLoginScreen {
         };
         terminal.write_text(&format!("│ {:<30} │", password_display))?;
 
-        // Submit button
+        // Authentication code field (only needed when MFA is enrolled)
         terminal.move_cursor(20, 10)?;
+        terminal.set_color(theme.colors.text_primary, theme.colors.background)?;
+        terminal.write_text("Authentication code:")?;
+
+        terminal.move_cursor(21, 12)?;
+        let code_style = if self.current_field == LoginField::Code {
+            theme.styles.form_input_focused
+        } else {
+            theme.styles.form_input
+        };
+        terminal.set_color(code_style.0, code_style.1)?;
+
+        let code_display = if self.code.is_empty() {
+            "[If enrolled in MFA]".to_string()
+        } else {
+            "*".repeat(self.code.len())
+        };
+        terminal.write_text(&format!("│ {:<30} │", code_display))?;
+
+        // Submit button
+        terminal.move_cursor(23, 10)?;
         let submit_style = if self.current_field == LoginField::Submit {
             theme.styles.button_focused
         } else {
@@ -137,20 +161,20 @@ impl TuiLoginScreen {
         terminal.write_text("[ Login ]")?;
 
         // Instructions
-        terminal.move_cursor(23, 10)?;
+        terminal.move_cursor(25, 10)?;
         terminal.set_color(theme.colors.text_secondary, theme.colors.background)?;
         terminal.write_text("Instructions:")?;
         
-        terminal.move_cursor(24, 12)?;
+        terminal.move_cursor(26, 12)?;
         terminal.write_text("• Use Tab/Shift+Tab to navigate fields")?;
         
-        terminal.move_cursor(25, 12)?;
-        terminal.write_text("• Type to enter username/password")?;
+        terminal.move_cursor(27, 12)?;
+        terminal.write_text("• Type to enter username/password and authentication code")?;
         
-        terminal.move_cursor(26, 12)?;
+        terminal.move_cursor(28, 12)?;
         terminal.write_text("• Press Enter to login")?;
         
-        terminal.move_cursor(27, 12)?;
+        terminal.move_cursor(29, 12)?;
         terminal.write_text("• Press 'q' to quit")?;
 
         // Status message
@@ -223,6 +247,10 @@ impl TuiLoginScreen {
                             self.password = input.to_string();
                             self.message = None;
                         }
+                        LoginField::Code => {
+                            self.code = input.to_string();
+                            self.message = None;
+                        }
                         LoginField::Submit => {
                             return self.attempt_login();
                         }
@@ -259,7 +287,8 @@ impl TuiLoginScreen {
         }
 
         // Attempt authentication
-        match self.auth_manager.login(&self.username, &self.password) {
+        let code = Some(self.code.as_str()).filter(|c| !c.trim().is_empty());
+        match self.auth_manager.login_with_code(&self.username, &self.password, code) {
             Ok(auth_result) => {
                 if auth_result.success {
                     self.message = Some(auth_result.message.clone());
@@ -267,15 +296,24 @@ impl TuiLoginScreen {
                     
                     // Clear sensitive data
                     self.password.clear();
+                    self.code.clear();
                     
                     Ok(AuthScreenResult::LoginSuccess(self.username.clone()))
+                } else if auth_result.message.ends_with(SECOND_FACTOR_REQUIRED) {
+                    // Password accepted; ask for the code without counting a failed attempt
+                    self.message = Some(auth_result.message);
+                    self.is_error = true;
+                    self.current_field = LoginField::Code;
+
+                    Ok(AuthScreenResult::Continue)
                 } else {
                     self.login_attempts += 1;
                     self.message = Some(auth_result.message);
                     self.is_error = true;
                     
-                    // Clear password on failed attempt
+                    // Clear password and code on failed attempt
                     self.password.clear();
+                    self.code.clear();
                     self.current_field = LoginField::Password;
                     
                     Ok(AuthScreenResult::Continue)
@@ -286,8 +324,9 @@ impl TuiLoginScreen {
                 self.message = Some(format!("Authentication error: {}", e));
                 self.is_error = true;
                 
-                // Clear password on error
+                // Clear password and code on error
                 self.password.clear();
+                self.code.clear();
                 self.current_field = LoginField::Password;
                 
                 Ok(AuthScreenResult::Continue)
@@ -299,7 +338,8 @@ impl TuiLoginScreen {
     fn next_field(&mut self) {
         self.current_field = match self.current_field {
             LoginField::Username => LoginField::Password,
-            LoginField::Password => LoginField::Submit,
+            LoginField::Password => LoginField::Code,
+            LoginField::Code => LoginField::Submit,
             LoginField::Submit => LoginField::Username,
         };
     }
@@ -309,7 +349,8 @@ impl TuiLoginScreen {
         self.current_field = match self.current_field {
             LoginField::Username => LoginField::Submit,
             LoginField::Password => LoginField::Username,
-            LoginField::Submit => LoginField::Password,
+            LoginField::Code => LoginField::Password,
+            LoginField::Submit => LoginField::Code,
         };
     }
 
@@ -22,6 +22,7 @@ pub mod widgets;
 pub mod layout;
 pub mod events;
 pub mod screens;
+pub mod record_screens;
 pub mod theme;
 pub mod app;
 pub mod simple_app;
@@ -0,0 +1,1614 @@
+//! Record Screens for QMS TUI
+//!
+//! List, detail and edit screens for projects, documents, risks, requirements
+//! and the audit trail. Each list supports filtering ('/') and paging, and
+//! approvals collect an electronic signature (user ID and password) before
+//! the record is changed.
+
+use crate::prelude::*;
+use crate::json_utils::JsonValue;
+use crate::tui::{Terminal, Theme, Event};
+use crate::tui::layout::Region;
+use crate::tui::screens::{Screen, ScreenAction};
+use crate::tui::widgets::{Widget, MenuWidget, FormWidget, MenuItem, FormField, FormFieldType};
+use crate::modules::audit_logger::signatures::{ElectronicSignature, ElectronicSignatureManager};
+use crate::modules::audit_logger::search::{AuditSearchCriteria, AuditSearchEngine};
+use crate::modules::document_control::document::DocumentStatus;
+use crate::modules::document_control::service::DocumentService;
+use crate::modules::repository::project::Repository;
+use crate::modules::risk_manager::risk::{RiskDetectability, RiskManager, RiskOccurrence, RiskSeverity};
+use crate::modules::traceability::requirement::{
+    Requirement, RequirementManager, RequirementPriority, RequirementStatus, RequirementUpdate,
+};
+
+const DOCUMENT_APPROVE_ACTION: &str = "document_approve";
+const RISK_ACCEPT_ACTION: &str = "risk_accept";
+const REQUIREMENT_APPROVE_ACTION: &str = "requirement_approve";
+
+/// Number of audit entries fetched per page from the search engine
+const AUDIT_PAGE_SIZE: usize = 50;
+
+/// Resolve the project a screen works on: an explicit path, or the current project
+fn resolve_project(explicit: &Option<PathBuf>) -> QmsResult<PathBuf> {
+    match explicit {
+        Some(path) => Ok(path.clone()),
+        None => crate::utils::get_current_project_path(),
+    }
+}
+
+/// Truncate or pad a cell to an exact column width
+fn fit(text: &str, width: usize) -> String {
+    let count = text.chars().count();
+    if count > width {
+        let mut cell: String = text.chars().take(width.saturating_sub(1)).collect();
+        cell.push('…');
+        cell
+    } else {
+        format!("{text}{}", " ".repeat(width - count))
+    }
+}
+
+/// Filterable, paged table of records shared by the record screens.
+/// The first cell of each row is the record key handed back to the screen.
+struct RecordList {
+    columns: Vec<(&'static str, usize)>,
+    rows: Vec<Vec<String>>,
+    filter: String,
+    editing_filter: bool,
+    selected: usize,
+    page_size: usize,
+    /// Page index and total match count when the screen pages on the server side
+    remote_page: Option<(usize, usize)>,
+}
+
+impl RecordList {
+    fn new(columns: &[(&'static str, usize)]) -> Self {
+        Self {
+            columns: columns.to_vec(),
+            rows: Vec::new(),
+            filter: String::new(),
+            editing_filter: false,
+            selected: 0,
+            page_size: 10,
+            remote_page: None,
+        }
+    }
+
+    fn set_rows(&mut self, rows: Vec<Vec<String>>) {
+        self.rows = rows;
+        self.selected = self.selected.min(self.matching().len().saturating_sub(1));
+    }
+
+    /// Rows whose cells contain the filter text (case-insensitive)
+    fn matching(&self) -> Vec<&Vec<String>> {
+        if self.remote_page.is_some() || self.filter.is_empty() {
+            return self.rows.iter().collect();
+        }
+        let needle = self.filter.to_lowercase();
+        self.rows.iter()
+            .filter(|row| row.iter().any(|cell| cell.to_lowercase().contains(&needle)))
+            .collect()
+    }
+
+    fn selected_key(&self) -> Option<String> {
+        self.matching().get(self.selected).and_then(|row| row.first().cloned())
+    }
+
+    fn page(&self) -> usize {
+        self.selected / self.page_size.max(1)
+    }
+
+    fn page_count(&self) -> usize {
+        let size = self.page_size.max(1);
+        ((self.matching().len() + size - 1) / size).max(1)
+    }
+
+    /// Handle list navigation and filter input; returns true when the key was consumed
+    fn handle_key(&mut self, key: &str) -> bool {
+        let count = self.matching().len();
+        if self.editing_filter {
+            match key {
+                "Enter" => self.editing_filter = false,
+                "Escape" => {
+                    self.filter.clear();
+                    self.editing_filter = false;
+                }
+                "Backspace" => { self.filter.pop(); }
+                "Space" => self.filter.push(' '),
+                text if text.chars().count() == 1 => self.filter.push_str(text),
+                _ => return false,
+            }
+            self.selected = 0;
+            return true;
+        }
+
+        let local_paging = self.remote_page.is_none();
+        match key {
+            "/" => self.editing_filter = true,
+            "Up" => self.selected = self.selected.saturating_sub(1),
+            "Down" if self.selected + 1 < count => self.selected += 1,
+            "PageUp" if local_paging => self.selected = self.selected.saturating_sub(self.page_size),
+            "PageDown" if local_paging && count > 0 => {
+                self.selected = (self.selected + self.page_size).min(count - 1);
+            }
+            "Home" => self.selected = 0,
+            "End" => self.selected = count.saturating_sub(1),
+            _ => return false,
+        }
+        true
+    }
+
+    fn render(&mut self, terminal: &Terminal, region: &Region, theme: &Theme) -> QmsResult<()> {
+        self.page_size = (region.height.saturating_sub(9) as usize).max(1);
+        let mut row = region.y + 4;
+
+        terminal.move_cursor(row, region.x + 2)?;
+        terminal.set_color(theme.colors.text_secondary, theme.colors.background)?;
+        let header: Vec<String> = self.columns.iter().map(|(title, width)| fit(title, *width)).collect();
+        terminal.write_text(&header.join(" "))?;
+        row += 1;
+
+        let matching = self.matching();
+        let first = self.page() * self.page_size;
+        for (index, record) in matching.iter().enumerate().skip(first).take(self.page_size) {
+            terminal.move_cursor(row, region.x + 2)?;
+            if index == self.selected {
+                terminal.set_color(theme.colors.text_inverse, theme.colors.selection)?;
+            } else {
+                terminal.set_color(theme.colors.text_primary, theme.colors.background)?;
+            }
+            // The key column is not displayed; remaining cells line up with the headers
+            let cells: Vec<String> = record.iter().skip(1).zip(&self.columns)
+                .map(|(cell, (_, width))| fit(cell, *width))
+                .collect();
+            terminal.write_text(&cells.join(" "))?;
+            row += 1;
+        }
+
+        let (page, pages, total) = match self.remote_page {
+            Some((page, total)) => (page, ((total + AUDIT_PAGE_SIZE - 1) / AUDIT_PAGE_SIZE).max(1), total),
+            None => (self.page(), self.page_count(), matching.len()),
+        };
+        terminal.move_cursor(region.y + region.height.saturating_sub(3), region.x + 2)?;
+        terminal.set_color(theme.colors.text_muted, theme.colors.background)?;
+        terminal.write_text(&format!("Page {} of {pages} - {total} records", page + 1))?;
+        if self.editing_filter || !self.filter.is_empty() {
+            terminal.set_color(theme.colors.accent, theme.colors.background)?;
+            let cursor = if self.editing_filter { "_" } else { "" };
+            terminal.write_text(&format!("   Filter: {}{cursor}", self.filter))?;
+        }
+        Ok(())
+    }
+}
+
+/// Detail view: the record's fields above a menu of the actions available for it
+struct DetailView {
+    key: String,
+    lines: Vec<String>,
+    actions: MenuWidget,
+}
+
+impl DetailView {
+    fn new(key: String, lines: Vec<String>, actions: Vec<MenuItem>) -> Self {
+        let mut menu = MenuWidget::new("Actions".to_string());
+        for action in actions {
+            menu.add_item(action);
+        }
+        menu.add_item(MenuItem::new("back".to_string(), "Back to list".to_string()).with_shortcut('b'));
+        menu.set_focus(true);
+        Self { key, lines, actions: menu }
+    }
+
+    /// Id of the selected action, if it is enabled
+    fn selected_action(&self) -> Option<String> {
+        self.actions.selected_item().filter(|item| item.enabled).map(|item| item.id.clone())
+    }
+
+    fn render(&self, terminal: &Terminal, region: &Region, theme: &Theme) -> QmsResult<()> {
+        let menu_height = self.actions.min_size().1;
+        let text_rows = region.height.saturating_sub(menu_height + 7) as usize;
+
+        terminal.set_color(theme.colors.text_primary, theme.colors.background)?;
+        for (offset, line) in self.lines.iter().take(text_rows).enumerate() {
+            terminal.move_cursor(region.y + 4 + offset as u16, region.x + 2)?;
+            terminal.write_text(&fit(line, region.width.saturating_sub(4) as usize))?;
+        }
+
+        let menu_region = Region {
+            x: region.x + 2,
+            y: region.y + region.height.saturating_sub(menu_height + 2),
+            width: region.width.saturating_sub(4).min(60),
+            height: menu_height,
+        };
+        self.actions.render(terminal, &menu_region, theme)
+    }
+}
+
+/// Outcome of a key press in the signature prompt
+enum PromptOutcome {
+    Pending,
+    Cancelled,
+    Submitted(Signer),
+}
+
+/// Credentials entered in the signature prompt
+struct Signer {
+    user_id: String,
+    password: String,
+    code: Option<String>,
+    reason: Option<String>,
+}
+
+impl Signer {
+    /// Apply the signature for `action` over `content`
+    fn sign(self, path: PathBuf, action: &str, entity_type: &str, entity_id: &str, content: &[u8]) -> QmsResult<ElectronicSignature> {
+        ElectronicSignatureManager::new(path).create_signature_with_code(
+            self.user_id,
+            &self.password,
+            self.code.as_deref(),
+            action,
+            entity_type.to_string(),
+            entity_id.to_string(),
+            content,
+            self.reason,
+        )
+    }
+}
+
+/// Electronic signature prompt shown before an approval is applied.
+/// The password unlocks the signer's key, so every approval re-authenticates;
+/// signers enrolled in MFA also enter a fresh authentication code.
+struct SignaturePrompt {
+    form: FormWidget,
+    caption: String,
+    action: &'static str,
+    key: String,
+}
+
+impl SignaturePrompt {
+    fn new(caption: String, action: &'static str, key: String, reason_required: bool) -> Self {
+        let mut form = FormWidget::new("Electronic Signature".to_string());
+        let fields = [
+            ("user_id", "User ID", FormFieldType::Text, true),
+            ("password", "Password", FormFieldType::Password, true),
+            ("code", "Authentication code (if enrolled)", FormFieldType::Password, false),
+            ("reason", "Reason", FormFieldType::Text, reason_required),
+        ];
+        for (id, label, field_type, required) in fields {
+            form.add_field(FormField {
+                id: id.to_string(),
+                label: label.to_string(),
+                value: String::new(),
+                field_type,
+                required,
+                error: None,
+            });
+        }
+        form.set_focus(true);
+        Self { form, caption, action, key }
+    }
+
+    fn handle_event(&mut self, event: &Event) -> QmsResult<PromptOutcome> {
+        if let Event::Key(key_event) = event {
+            match key_event.key.as_str() {
+                "Escape" => return Ok(PromptOutcome::Cancelled),
+                "Enter" if self.form.validate() => {
+                    let values = self.form.get_values();
+                    let value = |id: &str| values.get(id).map(|v| v.trim().to_string()).unwrap_or_default();
+                    let optional = |id: &str| Some(value(id)).filter(|v| !v.is_empty());
+                    return Ok(PromptOutcome::Submitted(Signer {
+                        user_id: value("user_id"),
+                        password: values.get("password").cloned().unwrap_or_default(),
+                        code: optional("code"),
+                        reason: optional("reason"),
+                    }));
+                }
+                "Enter" => {}
+                _ => { self.form.handle_event(event)?; }
+            }
+        }
+        Ok(PromptOutcome::Pending)
+    }
+
+    fn render(&self, terminal: &Terminal, region: &Region, theme: &Theme) -> QmsResult<()> {
+        terminal.move_cursor(region.y + 4, region.x + 2)?;
+        terminal.set_color(theme.colors.warning, theme.colors.background)?;
+        terminal.write_text(&format!("✍️  {} ({})", self.caption, self.action))?;
+        let form_region = Region {
+            x: region.x + 2,
+            y: region.y + 6,
+            width: region.width.saturating_sub(4).min(60),
+            height: self.form.min_size().1 + 3,
+        };
+        self.form.render(terminal, &form_region, theme)
+    }
+}
+
+/// Build an edit form from (id, label, current value) triples
+fn edit_form(title: &str, fields: &[(&str, &str, String)]) -> FormWidget {
+    let mut form = FormWidget::new(title.to_string());
+    for (id, label, value) in fields {
+        form.add_field(FormField {
+            id: id.to_string(),
+            label: label.to_string(),
+            value: value.clone(),
+            field_type: FormFieldType::Text,
+            required: false,
+            error: None,
+        });
+    }
+    form.set_focus(true);
+    form
+}
+
+/// What a record screen is currently showing
+enum View {
+    List,
+    Detail(DetailView),
+    Edit(String, FormWidget),
+    Sign(SignaturePrompt),
+}
+
+/// Screen chrome shared by the record screens: title, status message and key hints
+fn render_frame(terminal: &Terminal, region: &Region, theme: &Theme, title: &str,
+                message: &Option<(String, bool)>, hints: &str) -> QmsResult<()> {
+    terminal.move_cursor(region.y + 2, region.x + 2)?;
+    terminal.set_color(theme.colors.primary, theme.colors.background)?;
+    terminal.write_text(title)?;
+
+    if let Some((text, is_error)) = message {
+        terminal.move_cursor(region.y + region.height.saturating_sub(2), region.x + 2)?;
+        let color = if *is_error { theme.colors.error } else { theme.colors.success };
+        terminal.set_color(color, theme.colors.background)?;
+        terminal.write_text(text)?;
+    }
+
+    terminal.move_cursor(region.y + region.height.saturating_sub(1), region.x + 2)?;
+    terminal.set_color(theme.colors.text_muted, theme.colors.background)?;
+    terminal.write_text(hints)?;
+    terminal.reset_colors()?;
+    Ok(())
+}
+
+/// Key hints for each view
+fn view_hints(view: &View, list_hint: &str) -> String {
+    match view {
+        View::List => list_hint.to_string(),
+        View::Detail(_) => "Up/Down choose action, Enter run, Escape back".to_string(),
+        View::Edit(..) => "Tab next field, Enter save, Escape cancel".to_string(),
+        View::Sign(_) => "Tab next field, Enter sign, Escape cancel".to_string(),
+    }
+}
+
+/// Document control screen backed by `DocumentService`
+pub struct DocumentsScreen {
+    project_path: Option<PathBuf>,
+    list: RecordList,
+    view: View,
+    message: Option<(String, bool)>,
+}
+
+impl DocumentsScreen {
+    pub fn new() -> Self {
+        Self {
+            project_path: None,
+            list: RecordList::new(&[("Title", 34), ("Type", 18), ("Version", 8), ("Status", 10), ("Author", 12)]),
+            view: View::List,
+            message: None,
+        }
+    }
+
+    /// Create the screen for an explicit project directory
+    pub fn for_project(project_path: &Path) -> Self {
+        Self { project_path: Some(project_path.to_path_buf()), ..Self::new() }
+    }
+
+    fn service(&self) -> QmsResult<DocumentService> {
+        Ok(DocumentService::new(resolve_project(&self.project_path)?))
+    }
+
+    fn reload(&mut self) {
+        match self.service().and_then(|service| service.list_documents()) {
+            Ok(mut entries) => {
+                entries.sort_by(|a, b| a.title.cmp(&b.title));
+                self.list.set_rows(entries.into_iter()
+                    .map(|e| vec![e.id, e.title, e.doc_type, e.version, e.status, e.author])
+                    .collect());
+            }
+            Err(e) => {
+                self.list.set_rows(Vec::new());
+                self.message = Some((format!("Unable to load documents: {e}"), true));
+            }
+        }
+    }
+
+    fn open_detail(&mut self, id: &str) -> QmsResult<()> {
+        let path = resolve_project(&self.project_path)?;
+        let document = DocumentService::new(path.clone()).read_document(id)?;
+        let mut lines = vec![
+            format!("{} - {}", document.title, document.id),
+            format!("Type: {}   Version: {}   Status: {}", document.doc_type.to_string(), document.version, document.status.to_string()),
+            format!("Author: {}   Approved by: {}", document.created_by, document.approved_by.as_deref().unwrap_or("-")),
+            format!("Updated: {}   Checksum: {}", document.updated_at, document.checksum),
+        ];
+        if let Some(ref holder) = document.locked_by {
+            lines.push(format!("Checked out by {holder}"));
+        }
+        for signature in ElectronicSignatureManager::new(path).list_signatures_for_entity("Document", id)? {
+            lines.push(format!("Signed: {} by {} at {}", signature.meaning, signature.user_id, signature.timestamp));
+        }
+        lines.push(String::new());
+        lines.extend(document.content.lines().map(str::to_string));
+
+        let draft = document.status == DocumentStatus::Draft;
+        let actions = vec![
+            MenuItem::new("edit".to_string(), "Edit title".to_string()).with_shortcut('e').with_enabled(draft),
+            MenuItem::new("submit".to_string(), "Submit for review".to_string()).with_shortcut('s').with_enabled(draft),
+            MenuItem::new("approve".to_string(), "Approve (signature required)".to_string())
+                .with_shortcut('a').with_enabled(document.status == DocumentStatus::InReview),
+        ];
+        self.view = View::Detail(DetailView::new(document.id, lines, actions));
+        Ok(())
+    }
+
+    fn run_action(&mut self, action: &str, id: String) -> QmsResult<()> {
+        match action {
+            "edit" => {
+                let document = self.service()?.read_document(&id)?;
+                let form = edit_form("Edit Document", &[
+                    ("title", "Title", document.title),
+                    ("change", "Change description", String::new()),
+                ]);
+                self.view = View::Edit(id, form);
+            }
+            "submit" => {
+                let document = self.service()?.submit_for_review(&id, &crate::utils::user_context::get_current_user_id())?;
+                self.message = Some((format!("{} submitted for review", document.title), false));
+                self.reload();
+                self.open_detail(&id)?;
+            }
+            "approve" => {
+                let caption = format!("Approve document {id}");
+                self.view = View::Sign(SignaturePrompt::new(caption, DOCUMENT_APPROVE_ACTION, id, false));
+            }
+            _ => self.view = View::List,
+        }
+        Ok(())
+    }
+
+    fn save_edit(&mut self, id: &str, values: &HashMap<String, String>) -> QmsResult<()> {
+        let title = values.get("title").map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+        let change = values.get("change").map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+        let document = self.service()?.update_document(
+            id, title, None, None, change, crate::utils::user_context::get_current_user_id(),
+        )?;
+        self.message = Some((format!("Saved {} as version {}", document.title, document.version), false));
+        self.reload();
+        self.open_detail(id)
+    }
+
+    /// Sign the document content, then move it from InReview to Approved
+    fn approve(&mut self, id: &str, signer: Signer) -> QmsResult<()> {
+        let path = resolve_project(&self.project_path)?;
+        let service = DocumentService::new(path.clone());
+        let document = service.read_document(id)?;
+        if document.status != DocumentStatus::InReview {
+            return Err(QmsError::validation_error("Only documents in review can be approved"));
+        }
+        let user_id = signer.user_id.clone();
+        let signature = signer.sign(path, DOCUMENT_APPROVE_ACTION, "Document", id, document.content.as_bytes())?;
+        service.approve_document(id, &user_id, Some(&signature.id))?;
+        self.message = Some((format!("{} approved - signature {}", document.title, signature.id), false));
+        self.reload();
+        self.open_detail(id)
+    }
+}
+
+impl Default for DocumentsScreen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Screen for DocumentsScreen {
+    fn name(&self) -> &str {
+        "documents"
+    }
+
+    fn render(&mut self, terminal: &Terminal, region: &Region, theme: &Theme) -> QmsResult<()> {
+        let title = format!("📄 Document Control ({} documents)", self.list.rows.len());
+        match &self.view {
+            View::List => self.list.render(terminal, region, theme)?,
+            View::Detail(detail) => detail.render(terminal, region, theme)?,
+            View::Edit(_, form) => form.render(terminal, &Region { x: region.x + 2, y: region.y + 4, width: region.width.saturating_sub(4).min(70), height: form.min_size().1 + 2 }, theme)?,
+            View::Sign(prompt) => prompt.render(terminal, region, theme)?,
+        }
+        let hints = view_hints(&self.view, "Up/Down/PgUp/PgDn select, / filter, Enter open, r refresh, Escape back");
+        render_frame(terminal, region, theme, &title, &self.message, &hints)
+    }
+
+    fn handle_event(&mut self, event: &Event) -> QmsResult<ScreenAction> {
+        let Event::Key(key_event) = event else { return Ok(ScreenAction::None) };
+        let key = key_event.key.as_str();
+        let result = match std::mem::replace(&mut self.view, View::List) {
+            View::List => {
+                if self.list.handle_key(key) {
+                    Ok(())
+                } else {
+                    match key {
+                        "Enter" => match self.list.selected_key() {
+                            Some(id) => self.open_detail(&id),
+                            None => Ok(()),
+                        },
+                        "r" => { self.reload(); Ok(()) }
+                        "Escape" => return Ok(ScreenAction::NavigateBack),
+                        _ => Ok(()),
+                    }
+                }
+            }
+            View::Detail(mut detail) => match key {
+                "Escape" => Ok(()),
+                "Enter" => match detail.selected_action() {
+                    Some(action) => self.run_action(&action, detail.key.clone()),
+                    None => { self.view = View::Detail(detail); Ok(()) }
+                },
+                _ => {
+                    detail.actions.handle_event(event)?;
+                    self.view = View::Detail(detail);
+                    Ok(())
+                }
+            },
+            View::Edit(id, mut form) => match key {
+                "Escape" => self.open_detail(&id),
+                "Enter" => {
+                    let outcome = self.save_edit(&id, &form.get_values());
+                    if outcome.is_err() {
+                        self.view = View::Edit(id, form);
+                    }
+                    outcome
+                }
+                _ => {
+                    form.handle_event(event)?;
+                    self.view = View::Edit(id, form);
+                    Ok(())
+                }
+            },
+            View::Sign(mut prompt) => match prompt.handle_event(event)? {
+                PromptOutcome::Pending => { self.view = View::Sign(prompt); Ok(()) }
+                PromptOutcome::Cancelled => self.open_detail(&prompt.key),
+                PromptOutcome::Submitted(signer) => {
+                    let outcome = self.approve(&prompt.key, signer);
+                    if outcome.is_err() {
+                        self.view = View::Sign(prompt);
+                    }
+                    outcome
+                }
+            },
+        };
+        if let Err(e) = result {
+            self.message = Some((e.to_string(), true));
+        }
+        Ok(ScreenAction::None)
+    }
+
+    fn on_enter(&mut self) -> QmsResult<()> {
+        self.view = View::List;
+        self.message = None;
+        self.reload();
+        Ok(())
+    }
+}
+
+/// Parse a 1-5 rating entered in a risk edit form
+fn parse_rating(values: &HashMap<String, String>, field: &str) -> QmsResult<u8> {
+    let raw = values.get(field).map(|v| v.trim()).unwrap_or_default();
+    match raw.parse::<u8>() {
+        Ok(value @ 1..=5) => Ok(value),
+        _ => Err(QmsError::validation_error(&format!("{field} must be a number from 1 to 5"))),
+    }
+}
+
+/// Risk management screen backed by `RiskManager`
+pub struct RisksScreen {
+    project_path: Option<PathBuf>,
+    list: RecordList,
+    view: View,
+    message: Option<(String, bool)>,
+}
+
+impl RisksScreen {
+    pub fn new() -> Self {
+        Self {
+            project_path: None,
+            list: RecordList::new(&[("Hazard", 9), ("Severity", 12), ("RPN", 5), ("Level", 12), ("Status", 10), ("Description", 36)]),
+            view: View::List,
+            message: None,
+        }
+    }
+
+    /// Create the screen for an explicit project directory
+    pub fn for_project(project_path: &Path) -> Self {
+        Self { project_path: Some(project_path.to_path_buf()), ..Self::new() }
+    }
+
+    fn manager(&self) -> QmsResult<RiskManager> {
+        RiskManager::new(&resolve_project(&self.project_path)?)
+    }
+
+    fn reload(&mut self) {
+        match self.manager().and_then(|manager| manager.list_all_risks()) {
+            Ok(mut risks) => {
+                risks.sort_by_key(|r| std::cmp::Reverse(r.risk_priority_number));
+                self.list.set_rows(risks.into_iter()
+                    .map(|r| vec![
+                        r.id.clone(),
+                        r.hazard_id.clone(),
+                        format!("{:?}", r.severity),
+                        r.risk_priority_number.to_string(),
+                        r.initial_risk_level.to_string().to_string(),
+                        r.risk_status.to_string().to_string(),
+                        r.hazard_description,
+                    ])
+                    .collect());
+            }
+            Err(e) => {
+                self.list.set_rows(Vec::new());
+                self.message = Some((format!("Unable to load risks: {e}"), true));
+            }
+        }
+    }
+
+    fn open_detail(&mut self, id: &str) -> QmsResult<()> {
+        let risk = self.manager()?.load_risk(id)?;
+        let mut lines = vec![
+            format!("{} - {}", risk.hazard_id, risk.hazard_description),
+            format!("Situation: {}", risk.hazardous_situation),
+            format!("Harm: {}", risk.harm),
+            format!("Severity: {:?}   Occurrence: {:?}   Detectability: {:?}", risk.severity, risk.occurrence, risk.detectability),
+            format!("RPN: {}   Level: {}   Policy: {}", risk.risk_priority_number,
+                risk.initial_risk_level.to_string(), if risk.policy_version.is_empty() { "-" } else { &risk.policy_version }),
+            format!("Residual RPN: {}   Residual level: {}", risk.residual_rpn, risk.residual_risk_level.to_string()),
+            format!("Justification: {}", risk.residual_risk_justification.as_deref().unwrap_or("-")),
+        ];
+        if risk.residual_risk_approved {
+            lines.push(format!("Residual risk accepted by {} on {}",
+                risk.residual_risk_approved_by.as_deref().unwrap_or("?"),
+                risk.residual_risk_approval_date.as_deref().unwrap_or("?")));
+        }
+        for measure in &risk.mitigation_measures {
+            lines.push(format!("  Mitigation: {} ({:.0}% effective, {:?})",
+                measure.description, measure.effectiveness * 100.0, measure.verification_status));
+        }
+
+        let can_accept = risk.residual_risk_justification.is_some() && !risk.residual_risk_approved;
+        let actions = vec![
+            MenuItem::new("edit".to_string(), "Assess / justify".to_string()).with_shortcut('e'),
+            MenuItem::new("accept".to_string(), "Accept residual risk (signature required)".to_string())
+                .with_shortcut('a').with_enabled(can_accept),
+        ];
+        self.view = View::Detail(DetailView::new(risk.id, lines, actions));
+        Ok(())
+    }
+
+    fn run_action(&mut self, action: &str, id: String) -> QmsResult<()> {
+        match action {
+            "edit" => {
+                let risk = self.manager()?.load_risk(&id)?;
+                let form = edit_form("Assess Risk", &[
+                    ("severity", "Severity (1-5)", (risk.severity as u8).to_string()),
+                    ("occurrence", "Occurrence (1-5)", (risk.occurrence as u8).to_string()),
+                    ("detectability", "Detectability (1-5)", (risk.detectability as u8).to_string()),
+                    ("justification", "Residual risk justification", risk.residual_risk_justification.unwrap_or_default()),
+                ]);
+                self.view = View::Edit(id, form);
+            }
+            "accept" => {
+                let caption = format!("Accept residual risk {id}");
+                self.view = View::Sign(SignaturePrompt::new(caption, RISK_ACCEPT_ACTION, id, true));
+            }
+            _ => self.view = View::List,
+        }
+        Ok(())
+    }
+
+    fn save_edit(&mut self, id: &str, values: &HashMap<String, String>) -> QmsResult<()> {
+        let severity = match parse_rating(values, "severity")? {
+            5 => RiskSeverity::Catastrophic,
+            4 => RiskSeverity::Critical,
+            3 => RiskSeverity::Major,
+            2 => RiskSeverity::Minor,
+            _ => RiskSeverity::Negligible,
+        };
+        let occurrence = match parse_rating(values, "occurrence")? {
+            5 => RiskOccurrence::Frequent,
+            4 => RiskOccurrence::Probable,
+            3 => RiskOccurrence::Occasional,
+            2 => RiskOccurrence::Remote,
+            _ => RiskOccurrence::Improbable,
+        };
+        let detectability = match parse_rating(values, "detectability")? {
+            5 => RiskDetectability::VeryLow,
+            4 => RiskDetectability::Low,
+            3 => RiskDetectability::Moderate,
+            2 => RiskDetectability::High,
+            _ => RiskDetectability::VeryHigh,
+        };
+
+        let mut manager = self.manager()?;
+        let mut risk = manager.assess_risk(id, Some(severity), Some(occurrence), Some(detectability))?;
+        let justification = values.get("justification").map(|j| j.trim()).unwrap_or_default();
+        if !justification.is_empty() && risk.residual_risk_justification.as_deref() != Some(justification) {
+            risk = manager.justify_residual_risk(id, justification)?;
+        }
+        self.message = Some((format!("{} assessed: RPN {}", risk.hazard_id, risk.risk_priority_number), false));
+        self.reload();
+        self.open_detail(id)
+    }
+
+    /// Sign the residual risk and its justification, then record the acceptance
+    fn accept(&mut self, id: &str, signer: Signer) -> QmsResult<()> {
+        let path = resolve_project(&self.project_path)?;
+        let mut manager = RiskManager::new(&path)?;
+        let risk = manager.load_risk(id)?;
+        let Some(ref justification) = risk.residual_risk_justification else {
+            return Err(QmsError::validation_error("Residual risk must be justified before acceptance"));
+        };
+        let content = format!("{}|{}|{}|{}", risk.id, risk.residual_rpn, risk.residual_risk_level.to_string(), justification);
+        let user_id = signer.user_id.clone();
+        let signature = signer.sign(path, RISK_ACCEPT_ACTION, "Risk", id, content.as_bytes())?;
+        let risk = manager.approve_residual_risk(id, &user_id)?;
+        self.message = Some((format!("Residual risk {} accepted - signature {}", risk.hazard_id, signature.id), false));
+        self.reload();
+        self.open_detail(id)
+    }
+}
+
+impl Default for RisksScreen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Screen for RisksScreen {
+    fn name(&self) -> &str {
+        "risks"
+    }
+
+    fn render(&mut self, terminal: &Terminal, region: &Region, theme: &Theme) -> QmsResult<()> {
+        let title = format!("⚠️  Risk Management ({} risks)", self.list.rows.len());
+        match &self.view {
+            View::List => self.list.render(terminal, region, theme)?,
+            View::Detail(detail) => detail.render(terminal, region, theme)?,
+            View::Edit(_, form) => form.render(terminal, &Region { x: region.x + 2, y: region.y + 4, width: region.width.saturating_sub(4).min(70), height: form.min_size().1 + 2 }, theme)?,
+            View::Sign(prompt) => prompt.render(terminal, region, theme)?,
+        }
+        let hints = view_hints(&self.view, "Up/Down/PgUp/PgDn select, / filter, Enter open, r refresh, Escape back");
+        render_frame(terminal, region, theme, &title, &self.message, &hints)
+    }
+
+    fn handle_event(&mut self, event: &Event) -> QmsResult<ScreenAction> {
+        let Event::Key(key_event) = event else { return Ok(ScreenAction::None) };
+        let key = key_event.key.as_str();
+        let result = match std::mem::replace(&mut self.view, View::List) {
+            View::List => {
+                if self.list.handle_key(key) {
+                    Ok(())
+                } else {
+                    match key {
+                        "Enter" => match self.list.selected_key() {
+                            Some(id) => self.open_detail(&id),
+                            None => Ok(()),
+                        },
+                        "r" => { self.reload(); Ok(()) }
+                        "Escape" => return Ok(ScreenAction::NavigateBack),
+                        _ => Ok(()),
+                    }
+                }
+            }
+            View::Detail(mut detail) => match key {
+                "Escape" => Ok(()),
+                "Enter" => match detail.selected_action() {
+                    Some(action) => self.run_action(&action, detail.key.clone()),
+                    None => { self.view = View::Detail(detail); Ok(()) }
+                },
+                _ => {
+                    detail.actions.handle_event(event)?;
+                    self.view = View::Detail(detail);
+                    Ok(())
+                }
+            },
+            View::Edit(id, mut form) => match key {
+                "Escape" => self.open_detail(&id),
+                "Enter" => {
+                    let outcome = self.save_edit(&id, &form.get_values());
+                    if outcome.is_err() {
+                        self.view = View::Edit(id, form);
+                    }
+                    outcome
+                }
+                _ => {
+                    form.handle_event(event)?;
+                    self.view = View::Edit(id, form);
+                    Ok(())
+                }
+            },
+            View::Sign(mut prompt) => match prompt.handle_event(event)? {
+                PromptOutcome::Pending => { self.view = View::Sign(prompt); Ok(()) }
+                PromptOutcome::Cancelled => self.open_detail(&prompt.key),
+                PromptOutcome::Submitted(signer) => {
+                    let outcome = self.accept(&prompt.key, signer);
+                    if outcome.is_err() {
+                        self.view = View::Sign(prompt);
+                    }
+                    outcome
+                }
+            },
+        };
+        if let Err(e) = result {
+            self.message = Some((e.to_string(), true));
+        }
+        Ok(ScreenAction::None)
+    }
+
+    fn on_enter(&mut self) -> QmsResult<()> {
+        self.view = View::List;
+        self.message = None;
+        self.reload();
+        Ok(())
+    }
+}
+
+/// Requirements management screen backed by `RequirementManager`
+pub struct RequirementsScreen {
+    project_path: Option<PathBuf>,
+    list: RecordList,
+    view: View,
+    message: Option<(String, bool)>,
+}
+
+impl RequirementsScreen {
+    pub fn new() -> Self {
+        Self {
+            project_path: None,
+            list: RecordList::new(&[("ID", 9), ("Status", 13), ("Priority", 9), ("Category", 12), ("Title", 40)]),
+            view: View::List,
+            message: None,
+        }
+    }
+
+    /// Create the screen for an explicit project directory
+    pub fn for_project(project_path: &Path) -> Self {
+        Self { project_path: Some(project_path.to_path_buf()), ..Self::new() }
+    }
+
+    fn manager(&self) -> QmsResult<RequirementManager> {
+        RequirementManager::new(&resolve_project(&self.project_path)?)
+    }
+
+    fn load(&self, req_id: &str) -> QmsResult<Requirement> {
+        self.manager()?.get_requirement_by_req_id(req_id).cloned()
+            .ok_or_else(|| QmsError::not_found(&format!("Requirement {req_id} not found")))
+    }
+
+    fn reload(&mut self) {
+        match self.manager() {
+            Ok(manager) => {
+                let mut requirements = manager.list_requirements();
+                requirements.sort_by(|a, b| a.req_id.cmp(&b.req_id));
+                self.list.set_rows(requirements.into_iter()
+                    .map(|r| vec![
+                        r.req_id.clone(),
+                        r.req_id.clone(),
+                        r.status.as_str().to_string(),
+                        r.priority.as_str().to_string(),
+                        r.category.as_str().to_string(),
+                        r.title.clone(),
+                    ])
+                    .collect());
+            }
+            Err(e) => {
+                self.list.set_rows(Vec::new());
+                self.message = Some((format!("Unable to load requirements: {e}"), true));
+            }
+        }
+    }
+
+    fn open_detail(&mut self, req_id: &str) -> QmsResult<()> {
+        let requirement = self.load(req_id)?;
+        let path = resolve_project(&self.project_path)?;
+        let mut lines = vec![
+            format!("{} - {}", requirement.req_id, requirement.title),
+            format!("Status: {}   Priority: {}   Category: {}", requirement.status.as_str(),
+                requirement.priority.as_str(), requirement.category.as_str()),
+            format!("Verification: {}   Source: {}", requirement.verification_method.as_str(), requirement.source),
+            format!("Linked tests: {}   Linked risks: {}", requirement.linked_tests.join(", "), requirement.linked_risks.join(", ")),
+            String::new(),
+            requirement.description.clone(),
+        ];
+        if !requirement.rationale.is_empty() {
+            lines.push(format!("Rationale: {}", requirement.rationale));
+        }
+        if !requirement.acceptance_criteria.is_empty() {
+            lines.push(format!("Acceptance criteria: {}", requirement.acceptance_criteria));
+        }
+        for signature in ElectronicSignatureManager::new(path).list_signatures_for_entity("Requirement", &requirement.id)? {
+            lines.push(format!("Signed: {} by {} at {}", signature.meaning, signature.user_id, signature.timestamp));
+        }
+
+        let approvable = matches!(requirement.status, RequirementStatus::Draft | RequirementStatus::UnderReview);
+        let actions = vec![
+            MenuItem::new("edit".to_string(), "Edit".to_string()).with_shortcut('e'),
+            MenuItem::new("approve".to_string(), "Approve (signature required)".to_string())
+                .with_shortcut('a').with_enabled(approvable),
+        ];
+        self.view = View::Detail(DetailView::new(requirement.req_id, lines, actions));
+        Ok(())
+    }
+
+    fn run_action(&mut self, action: &str, req_id: String) -> QmsResult<()> {
+        match action {
+            "edit" => {
+                let requirement = self.load(&req_id)?;
+                let form = edit_form("Edit Requirement", &[
+                    ("title", "Title", requirement.title),
+                    ("description", "Description", requirement.description),
+                    ("priority", "Priority (critical/high/medium/low)", requirement.priority.as_str().to_string()),
+                    ("acceptance", "Acceptance criteria", requirement.acceptance_criteria),
+                ]);
+                self.view = View::Edit(req_id, form);
+            }
+            "approve" => {
+                let caption = format!("Approve requirement {req_id}");
+                self.view = View::Sign(SignaturePrompt::new(caption, REQUIREMENT_APPROVE_ACTION, req_id, false));
+            }
+            _ => self.view = View::List,
+        }
+        Ok(())
+    }
+
+    fn save_edit(&mut self, req_id: &str, values: &HashMap<String, String>) -> QmsResult<()> {
+        let value = |id: &str| values.get(id).map(|v| v.trim().to_string()).unwrap_or_default();
+        let priority = value("priority").to_lowercase();
+        if !matches!(priority.as_str(), "critical" | "high" | "medium" | "low") {
+            return Err(QmsError::validation_error("Priority must be critical, high, medium or low"));
+        }
+        if value("title").is_empty() {
+            return Err(QmsError::validation_error("Title cannot be empty"));
+        }
+
+        let mut updates = RequirementUpdate::new();
+        updates.title = Some(value("title"));
+        updates.description = Some(value("description"));
+        updates.priority = Some(RequirementPriority::from_str(&priority));
+        updates.acceptance_criteria = Some(value("acceptance"));
+        self.manager()?.update_requirement(req_id, updates)?;
+
+        self.message = Some((format!("Saved {req_id}"), false));
+        self.reload();
+        self.open_detail(req_id)
+    }
+
+    /// Sign the requirement text, then mark it approved
+    fn approve(&mut self, req_id: &str, signer: Signer) -> QmsResult<()> {
+        let path = resolve_project(&self.project_path)?;
+        let mut manager = RequirementManager::new(&path)?;
+        let requirement = manager.get_requirement_by_req_id(req_id).cloned()
+            .ok_or_else(|| QmsError::not_found(&format!("Requirement {req_id} not found")))?;
+        if !matches!(requirement.status, RequirementStatus::Draft | RequirementStatus::UnderReview) {
+            return Err(QmsError::validation_error(&format!(
+                "Requirement {req_id} is already {}", requirement.status.as_str()
+            )));
+        }
+        let content = format!("{}|{}|{}|{}", requirement.id, requirement.req_id, requirement.title, requirement.description);
+        let user_id = signer.user_id.clone();
+        let signature = signer.sign(path, REQUIREMENT_APPROVE_ACTION, "Requirement", &requirement.id, content.as_bytes())?;
+
+        let mut updates = RequirementUpdate::new();
+        updates.status = Some(RequirementStatus::Approved);
+        manager.update_requirement(req_id, updates)?;
+        self.message = Some((format!("{req_id} approved - signature {}", signature.id), false));
+        self.reload();
+        self.open_detail(req_id)
+    }
+}
+
+impl Default for RequirementsScreen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Screen for RequirementsScreen {
+    fn name(&self) -> &str {
+        "requirements"
+    }
+
+    fn render(&mut self, terminal: &Terminal, region: &Region, theme: &Theme) -> QmsResult<()> {
+        let title = format!("📋 Requirements Management ({} requirements)", self.list.rows.len());
+        match &self.view {
+            View::List => self.list.render(terminal, region, theme)?,
+            View::Detail(detail) => detail.render(terminal, region, theme)?,
+            View::Edit(_, form) => form.render(terminal, &Region { x: region.x + 2, y: region.y + 4, width: region.width.saturating_sub(4).min(70), height: form.min_size().1 + 2 }, theme)?,
+            View::Sign(prompt) => prompt.render(terminal, region, theme)?,
+        }
+        let hints = view_hints(&self.view, "Up/Down/PgUp/PgDn select, / filter, Enter open, r refresh, Escape back");
+        render_frame(terminal, region, theme, &title, &self.message, &hints)
+    }
+
+    fn handle_event(&mut self, event: &Event) -> QmsResult<ScreenAction> {
+        let Event::Key(key_event) = event else { return Ok(ScreenAction::None) };
+        let key = key_event.key.as_str();
+        let result = match std::mem::replace(&mut self.view, View::List) {
+            View::List => {
+                if self.list.handle_key(key) {
+                    Ok(())
+                } else {
+                    match key {
+                        "Enter" => match self.list.selected_key() {
+                            Some(id) => self.open_detail(&id),
+                            None => Ok(()),
+                        },
+                        "r" => { self.reload(); Ok(()) }
+                        "Escape" => return Ok(ScreenAction::NavigateBack),
+                        _ => Ok(()),
+                    }
+                }
+            }
+            View::Detail(mut detail) => match key {
+                "Escape" => Ok(()),
+                "Enter" => match detail.selected_action() {
+                    Some(action) => self.run_action(&action, detail.key.clone()),
+                    None => { self.view = View::Detail(detail); Ok(()) }
+                },
+                _ => {
+                    detail.actions.handle_event(event)?;
+                    self.view = View::Detail(detail);
+                    Ok(())
+                }
+            },
+            View::Edit(id, mut form) => match key {
+                "Escape" => self.open_detail(&id),
+                "Enter" => {
+                    let outcome = self.save_edit(&id, &form.get_values());
+                    if outcome.is_err() {
+                        self.view = View::Edit(id, form);
+                    }
+                    outcome
+                }
+                _ => {
+                    form.handle_event(event)?;
+                    self.view = View::Edit(id, form);
+                    Ok(())
+                }
+            },
+            View::Sign(mut prompt) => match prompt.handle_event(event)? {
+                PromptOutcome::Pending => { self.view = View::Sign(prompt); Ok(()) }
+                PromptOutcome::Cancelled => self.open_detail(&prompt.key),
+                PromptOutcome::Submitted(signer) => {
+                    let outcome = self.approve(&prompt.key, signer);
+                    if outcome.is_err() {
+                        self.view = View::Sign(prompt);
+                    }
+                    outcome
+                }
+            },
+        };
+        if let Err(e) = result {
+            self.message = Some((e.to_string(), true));
+        }
+        Ok(ScreenAction::None)
+    }
+
+    fn on_enter(&mut self) -> QmsResult<()> {
+        self.view = View::List;
+        self.message = None;
+        self.reload();
+        Ok(())
+    }
+}
+
+/// Build search criteria from the audit filter text.
+/// `user:`, `action:`, `entity:` and `id:` prefixes select fields; other words match details.
+fn audit_criteria(filter: &str, page: usize) -> AuditSearchCriteria {
+    let mut criteria = AuditSearchCriteria::new()
+        .with_limit(AUDIT_PAGE_SIZE)
+        .with_offset(page * AUDIT_PAGE_SIZE);
+    let mut keywords = Vec::new();
+    for token in filter.split_whitespace() {
+        match token.split_once(':') {
+            Some(("user", value)) => criteria = criteria.with_user(value),
+            Some(("action", value)) => criteria = criteria.with_action(value),
+            Some(("entity", value)) => criteria = criteria.with_entity_type(value),
+            Some(("id", value)) => criteria = criteria.with_entity_id(value),
+            _ => keywords.push(token),
+        }
+    }
+    if !keywords.is_empty() {
+        criteria = criteria.with_details_keyword(&keywords.join(" "));
+    }
+    criteria
+}
+
+fn audit_action_name(action: &AuditAction) -> String {
+    match action {
+        AuditAction::Other(name) => name.clone(),
+        other => format!("{other:?}"),
+    }
+}
+
+/// Audit trail screen backed by `AuditSearchEngine`; pages are fetched from the engine
+pub struct AuditScreen {
+    project_path: Option<PathBuf>,
+    list: RecordList,
+    entries: Vec<AuditEntry>,
+    page: usize,
+    total: usize,
+    view: View,
+    message: Option<(String, bool)>,
+}
+
+impl AuditScreen {
+    pub fn new() -> Self {
+        let mut list = RecordList::new(&[("Timestamp", 20), ("User", 12), ("Action", 16), ("Entity", 12), ("Entity ID", 14), ("Details", 30)]);
+        list.remote_page = Some((0, 0));
+        Self {
+            project_path: None,
+            list,
+            entries: Vec::new(),
+            page: 0,
+            total: 0,
+            view: View::List,
+            message: None,
+        }
+    }
+
+    /// Create the screen for an explicit project directory
+    pub fn for_project(project_path: &Path) -> Self {
+        Self { project_path: Some(project_path.to_path_buf()), ..Self::new() }
+    }
+
+    fn reload(&mut self) {
+        let criteria = audit_criteria(&self.list.filter, self.page);
+        let results = resolve_project(&self.project_path)
+            .and_then(|path| AuditSearchEngine::new(path).search(&criteria));
+        match results {
+            Ok(results) => {
+                self.total = results.total_matches;
+                self.entries = results.entries;
+            }
+            Err(e) => {
+                self.total = 0;
+                self.entries.clear();
+                self.message = Some((format!("Unable to search audit trail: {e}"), true));
+            }
+        }
+        self.list.remote_page = Some((self.page, self.total));
+        self.list.set_rows(self.entries.iter().enumerate()
+            .map(|(index, entry)| vec![
+                index.to_string(),
+                entry.timestamp.clone(),
+                entry.user_id.clone(),
+                audit_action_name(&entry.action),
+                entry.entity_type.clone(),
+                entry.entity_id.clone(),
+                entry.details.clone().unwrap_or_default(),
+            ])
+            .collect());
+    }
+
+    fn open_detail(&mut self, index: usize) {
+        let Some(entry) = self.entries.get(index) else { return };
+        let mut lines = vec![
+            format!("Entry {}", entry.id),
+            format!("Timestamp: {}", entry.timestamp),
+            format!("User: {}   Session: {}", entry.user_id, entry.session_id.as_deref().unwrap_or("-")),
+            format!("Action: {}", audit_action_name(&entry.action)),
+            format!("Entity: {} {}", entry.entity_type, entry.entity_id),
+            format!("Details: {}", entry.details.as_deref().unwrap_or("-")),
+            format!("Old value: {}", entry.old_value.as_deref().unwrap_or("-")),
+            format!("New value: {}", entry.new_value.as_deref().unwrap_or("-")),
+            format!("Checksum: {}", entry.checksum),
+            format!("Previous hash: {}", entry.previous_hash.as_deref().unwrap_or("-")),
+        ];
+        if let Some(ref signature) = entry.signature {
+            lines.push(format!("Signed: {} by {} at {}", signature.meaning, signature.user_id, signature.timestamp));
+        }
+        let actions = vec![
+            MenuItem::new("entity".to_string(), "Show entries for this entity".to_string()).with_shortcut('e'),
+            MenuItem::new("user".to_string(), "Show entries by this user".to_string()).with_shortcut('u'),
+        ];
+        self.view = View::Detail(DetailView::new(index.to_string(), lines, actions));
+    }
+
+    fn run_action(&mut self, action: &str, key: &str) {
+        let Some(entry) = key.parse::<usize>().ok().and_then(|index| self.entries.get(index)) else { return };
+        self.list.filter = match action {
+            "entity" => format!("entity:{} id:{}", entry.entity_type, entry.entity_id),
+            "user" => format!("user:{}", entry.user_id),
+            _ => return,
+        };
+        self.page = 0;
+        self.list.selected = 0;
+        self.reload();
+    }
+}
+
+impl Default for AuditScreen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Screen for AuditScreen {
+    fn name(&self) -> &str {
+        "audit"
+    }
+
+    fn render(&mut self, terminal: &Terminal, region: &Region, theme: &Theme) -> QmsResult<()> {
+        let title = format!("🔍 Audit Trail ({} matching entries)", self.total);
+        match &self.view {
+            View::Detail(detail) => detail.render(terminal, region, theme)?,
+            _ => self.list.render(terminal, region, theme)?,
+        }
+        let hints = view_hints(&self.view,
+            "Up/Down select, PgUp/PgDn page, / filter (user: action: entity: id:), Enter open, Escape back");
+        render_frame(terminal, region, theme, &title, &self.message, &hints)
+    }
+
+    fn handle_event(&mut self, event: &Event) -> QmsResult<ScreenAction> {
+        let Event::Key(key_event) = event else { return Ok(ScreenAction::None) };
+        let key = key_event.key.as_str();
+        match std::mem::replace(&mut self.view, View::List) {
+            View::Detail(mut detail) => match key {
+                "Escape" => {}
+                "Enter" => match detail.selected_action() {
+                    Some(action) if action != "back" => self.run_action(&action, &detail.key),
+                    Some(_) => {}
+                    None => self.view = View::Detail(detail),
+                },
+                _ => {
+                    detail.actions.handle_event(event)?;
+                    self.view = View::Detail(detail);
+                }
+            },
+            _ => {
+                let was_filtering = self.list.editing_filter;
+                if self.list.handle_key(key) {
+                    // The engine applies the filter, so re-query once editing finishes
+                    if was_filtering && !self.list.editing_filter {
+                        self.page = 0;
+                        self.reload();
+                    }
+                    return Ok(ScreenAction::None);
+                }
+                match key {
+                    "PageDown" if (self.page + 1) * AUDIT_PAGE_SIZE < self.total => {
+                        self.page += 1;
+                        self.list.selected = 0;
+                        self.reload();
+                    }
+                    "PageUp" if self.page > 0 => {
+                        self.page -= 1;
+                        self.list.selected = 0;
+                        self.reload();
+                    }
+                    "Enter" => {
+                        if let Some(index) = self.list.selected_key().and_then(|k| k.parse().ok()) {
+                            self.open_detail(index);
+                        }
+                    }
+                    "r" => self.reload(),
+                    "Escape" => return Ok(ScreenAction::NavigateBack),
+                    _ => {}
+                }
+            }
+        }
+        Ok(ScreenAction::None)
+    }
+
+    fn on_enter(&mut self) -> QmsResult<()> {
+        self.view = View::List;
+        self.message = None;
+        self.page = 0;
+        self.reload();
+        Ok(())
+    }
+}
+
+/// Project screen: summary of the current project and a picker to switch projects
+pub struct ProjectScreen {
+    project_path: Option<PathBuf>,
+    summary: Vec<String>,
+    projects: Vec<Project>,
+    menu: MenuWidget,
+    message: Option<(String, bool)>,
+}
+
+impl ProjectScreen {
+    pub fn new() -> Self {
+        Self {
+            project_path: None,
+            summary: Vec::new(),
+            projects: Vec::new(),
+            menu: MenuWidget::new("Projects".to_string()),
+            message: None,
+        }
+    }
+
+    /// Create the screen for an explicit project directory
+    pub fn for_project(project_path: &Path) -> Self {
+        Self { project_path: Some(project_path.to_path_buf()), ..Self::new() }
+    }
+
+    fn summarize(path: &Path) -> Vec<String> {
+        let count = |result: QmsResult<usize>| result.map_or_else(|_| "-".to_string(), |n| n.to_string());
+        let documents = count(DocumentService::new(path.to_path_buf()).list_documents().map(|d| d.len()));
+        let risks = count(RiskManager::new(path).and_then(|m| m.list_all_risks()).map(|r| r.len()));
+        let requirements = count(RequirementManager::new(path).map(|m| m.list_requirements().len()));
+
+        let mut lines = vec![format!("Path: {}", path.display())];
+        let metadata = fs::read_to_string(path.join("project.json")).ok()
+            .and_then(|content| JsonValue::parse(&content).ok());
+        if let Some(JsonValue::Object(root)) = metadata {
+            let data = match root.get("data") {
+                Some(JsonValue::Object(data)) => data.clone(),
+                _ => root,
+            };
+            for (field, label) in [("name", "Name"), ("id", "ID"), ("version", "Version")] {
+                if let Some(JsonValue::String(value)) = data.get(field) {
+                    lines.push(format!("{label}: {value}"));
+                }
+            }
+        }
+        lines.push(format!("Documents: {documents}   Risks: {risks}   Requirements: {requirements}"));
+        lines
+    }
+
+    fn reload(&mut self) {
+        self.summary = match resolve_project(&self.project_path) {
+            Ok(path) => Self::summarize(&path),
+            Err(e) => vec![e.to_string()],
+        };
+        self.projects = Repository::list_projects().unwrap_or_default();
+        let mut menu = MenuWidget::new("Projects".to_string());
+        for project in &self.projects {
+            menu.add_item(MenuItem::new(project.id.clone(), project.name.clone())
+                .with_description(project.path.display().to_string()));
+        }
+        menu.set_focus(true);
+        self.menu = menu;
+    }
+
+    /// Make the selected project current for this session
+    fn switch_to(&mut self, project_id: &str) -> QmsResult<()> {
+        let project = self.projects.iter().find(|p| p.id == project_id)
+            .ok_or_else(|| QmsError::not_found(&format!("Project {project_id} not found")))?;
+        if !project.path.join("project.json").exists() {
+            return Err(QmsError::not_found(&format!("Project directory {} is missing", project.path.display())));
+        }
+        std::env::set_var("QMS_PROJECT_PATH", &project.path);
+        if self.project_path.is_some() {
+            self.project_path = Some(project.path.clone());
+        }
+        log_project_event("PROJECT_SWITCHED", &project.id);
+        self.message = Some((format!("Switched to {}", project.name), false));
+        self.reload();
+        Ok(())
+    }
+}
+
+impl Default for ProjectScreen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Screen for ProjectScreen {
+    fn name(&self) -> &str {
+        "projects"
+    }
+
+    fn render(&mut self, terminal: &Terminal, region: &Region, theme: &Theme) -> QmsResult<()> {
+        terminal.set_color(theme.colors.text_primary, theme.colors.background)?;
+        for (offset, line) in self.summary.iter().enumerate() {
+            terminal.move_cursor(region.y + 4 + offset as u16, region.x + 2)?;
+            terminal.write_text(line)?;
+        }
+
+        let top = region.y + 5 + self.summary.len() as u16;
+        if self.projects.is_empty() {
+            terminal.move_cursor(top, region.x + 2)?;
+            terminal.set_color(theme.colors.text_secondary, theme.colors.background)?;
+            terminal.write_text("No other projects found. Create one with 'qms init'.")?;
+        } else {
+            let menu_region = Region {
+                x: region.x + 2,
+                y: top,
+                width: region.width.saturating_sub(4),
+                height: self.menu.min_size().1.min(region.height.saturating_sub(top - region.y + 3)),
+            };
+            self.menu.render(terminal, &menu_region, theme)?;
+        }
+        render_frame(terminal, region, theme, "🏥 Project Management", &self.message,
+            "Up/Down select, Enter switch project, r refresh, Escape back")
+    }
+
+    fn handle_event(&mut self, event: &Event) -> QmsResult<ScreenAction> {
+        if let Event::Key(key_event) = event {
+            match key_event.key.as_str() {
+                "Enter" => {
+                    if let Some(id) = self.menu.selected_item().map(|item| item.id.clone()) {
+                        if let Err(e) = self.switch_to(&id) {
+                            self.message = Some((e.to_string(), true));
+                        }
+                    }
+                }
+                "r" => self.reload(),
+                "Escape" => return Ok(ScreenAction::NavigateBack),
+                _ => { self.menu.handle_event(event)?; }
+            }
+        }
+        Ok(ScreenAction::None)
+    }
+
+    fn on_enter(&mut self) -> QmsResult<()> {
+        self.message = None;
+        self.reload();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_utils::JsonSerializable;
+    use crate::modules::audit_logger::{initialize_audit_system, AuditConfig};
+    use crate::modules::document_control::document::DocumentType;
+    use crate::modules::traceability::requirement::RequirementCategory;
+    use crate::modules::user_manager::signing_keys::SigningKeyStore;
+    use crate::tui::KeyEvent;
+
+    fn setup() -> tempfile::TempDir {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let _ = initialize_audit_system(AuditConfig {
+            project_path: temp_dir.path().to_string_lossy().to_string(),
+            retention_days: 30,
+            daily_rotation: false,
+            max_file_size_mb: 10,
+            require_checksums: false,
+        });
+        temp_dir
+    }
+
+    fn press(screen: &mut dyn Screen, keys: &[&str]) {
+        for key in keys {
+            screen.handle_event(&Event::Key(KeyEvent::new(key.to_string()))).unwrap();
+        }
+    }
+
+    fn type_text(screen: &mut dyn Screen, text: &str) {
+        for ch in text.chars() {
+            let key = if ch == ' ' { "Space".to_string() } else { ch.to_string() };
+            screen.handle_event(&Event::Key(KeyEvent::new(key))).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_documents_screen_filters_and_approves_with_signature() {
+        let dir = setup();
+        SigningKeyStore::new(dir.path()).generate_key_pair("quality", "password123").unwrap();
+        let service = DocumentService::new(dir.path().to_path_buf());
+        service.create_document("Software Requirements".to_string(), "# SRS".to_string(), DocumentType::SoftwareRequirementsSpecification, "author".to_string()).unwrap();
+        let plan = service.create_document("Risk Management Plan".to_string(), "# RMP".to_string(), DocumentType::RiskManagementFile, "author".to_string()).unwrap();
+        service.submit_for_review(&plan.id, "author").unwrap();
+
+        let mut screen = DocumentsScreen::for_project(dir.path());
+        screen.on_enter().unwrap();
+        assert_eq!(screen.list.matching().len(), 2);
+
+        press(&mut screen, &["/"]);
+        type_text(&mut screen, "risk management");
+        press(&mut screen, &["Enter"]);
+        assert_eq!(screen.list.matching().len(), 1);
+        assert_eq!(screen.list.selected_key(), Some(plan.id.clone()));
+
+        // Open the record, pick the approve action and sign with a wrong password first
+        press(&mut screen, &["Enter", "a", "Enter"]);
+        assert!(matches!(screen.view, View::Sign(_)));
+        type_text(&mut screen, "quality");
+        press(&mut screen, &["Tab"]);
+        type_text(&mut screen, "wrong");
+        press(&mut screen, &["Enter"]);
+        assert!(matches!(screen.view, View::Sign(_)));
+        assert!(screen.message.as_ref().is_some_and(|(_, is_error)| *is_error));
+        assert_eq!(service.read_document(&plan.id).unwrap().status, DocumentStatus::InReview);
+
+        press(&mut screen, &["Backspace", "Backspace", "Backspace", "Backspace", "Backspace"]);
+        type_text(&mut screen, "password123");
+        press(&mut screen, &["Enter"]);
+        assert!(matches!(screen.view, View::Detail(_)));
+        assert_eq!(service.read_document(&plan.id).unwrap().status, DocumentStatus::Approved);
+        let signatures = ElectronicSignatureManager::new(dir.path().to_path_buf())
+            .list_signatures_for_entity("Document", &plan.id).unwrap();
+        assert_eq!(signatures.len(), 1);
+        assert_eq!(signatures[0].user_id, "quality");
+    }
+
+    #[test]
+    fn test_requirements_screen_edits_and_approves() {
+        let dir = setup();
+        SigningKeyStore::new(dir.path()).generate_key_pair("lead", "password123").unwrap();
+        let mut manager = RequirementManager::new(dir.path()).unwrap();
+        manager.create_requirement("P1".to_string(), "REQ-001".to_string(), "Alarm volume".to_string(),
+            "Alarm shall be audible at 3 m".to_string(), RequirementCategory::Safety, "author".to_string()).unwrap();
+
+        let mut screen = RequirementsScreen::for_project(dir.path());
+        screen.on_enter().unwrap();
+        assert_eq!(screen.list.selected_key(), Some("REQ-001".to_string()));
+
+        // Invalid priority keeps the form open with an error
+        press(&mut screen, &["Enter", "e", "Enter", "Tab", "Tab"]);
+        type_text(&mut screen, "x");
+        press(&mut screen, &["Enter"]);
+        assert!(matches!(screen.view, View::Edit(..)));
+        press(&mut screen, &["Backspace", "Up", "Up"]);
+        type_text(&mut screen, " level");
+        press(&mut screen, &["Enter"]);
+        assert!(matches!(screen.view, View::Detail(_)));
+
+        press(&mut screen, &["a", "Enter"]);
+        type_text(&mut screen, "lead");
+        press(&mut screen, &["Tab"]);
+        type_text(&mut screen, "password123");
+        press(&mut screen, &["Enter"]);
+
+        let requirement = RequirementManager::new(dir.path()).unwrap()
+            .get_requirement_by_req_id("REQ-001").cloned().unwrap();
+        assert_eq!(requirement.title, "Alarm volume level");
+        assert_eq!(requirement.status, RequirementStatus::Approved);
+    }
+
+    #[test]
+    fn test_audit_screen_pages_and_filters_through_search_engine() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("audit")).unwrap();
+        let log: Vec<String> = (0..60).map(|n| AuditEntry {
+            id: format!("entry-{n}"),
+            timestamp: format!("2024-01-01T00:{:02}:00Z", n),
+            user_id: if n % 3 == 0 { "alice".to_string() } else { "bob".to_string() },
+            session_id: None,
+            action: AuditAction::Update,
+            entity_type: "Document".to_string(),
+            entity_id: format!("DOC-{n}"),
+            old_value: None,
+            new_value: None,
+            details: Some(format!("edit {n}")),
+            ip_address: None,
+            signature: None,
+            checksum: String::new(),
+            previous_hash: None,
+        }.to_json()).collect();
+        fs::write(dir.path().join("audit").join("audit.log"), log.join("\n")).unwrap();
+
+        let mut screen = AuditScreen::for_project(dir.path());
+        screen.on_enter().unwrap();
+        assert_eq!(screen.total, 60);
+        assert_eq!(screen.entries.len(), AUDIT_PAGE_SIZE);
+
+        press(&mut screen, &["PageDown"]);
+        assert_eq!(screen.page, 1);
+        assert_eq!(screen.entries.len(), 10);
+
+        press(&mut screen, &["/"]);
+        type_text(&mut screen, "user:alice");
+        press(&mut screen, &["Enter"]);
+        assert_eq!(screen.page, 0);
+        assert_eq!(screen.total, 20);
+        assert!(screen.entries.iter().all(|entry| entry.user_id == "alice"));
+
+        // Drill into one entity from the detail view
+        press(&mut screen, &["Enter", "e", "Enter"]);
+        assert_eq!(screen.total, 1);
+        assert!(screen.list.filter.starts_with("entity:Document id:DOC-"));
+    }
+}
@@ -9,6 +9,8 @@ use crate::tui::layout::{Region, Layout, QmsLayouts};
 use crate::tui::widgets::{Widget, MenuWidget, FormWidget, MenuItem, FormField, FormFieldType};
 use std::collections::HashMap;
 
+pub use crate::tui::record_screens::{ProjectScreen, DocumentsScreen, RisksScreen, RequirementsScreen, AuditScreen};
+
 // Placeholder for CommandResult when interfaces module is not available
 #[derive(Debug, Clone)]
 pub struct CommandResult {
@@ -86,6 +88,7 @@ impl ScreenManager {
         self.add_screen(Box::new(RisksScreen::new()));
         self.add_screen(Box::new(RequirementsScreen::new()));
         self.add_screen(Box::new(AuditScreen::new()));
+        self.add_screen(Box::new(CapaScreen::new()));
         self.add_screen(Box::new(HelpScreen::new()));
     }
 
@@ -307,6 +310,10 @@ impl MainMenuScreen {
             .with_description("Audit logs and compliance reporting".to_string())
             .with_shortcut('5'));
         
+        menu.add_item(MenuItem::new("capa".to_string(), "CAPA".to_string())
+            .with_description("Corrective and preventive actions".to_string())
+            .with_shortcut('6'));
+        
         menu.add_item(MenuItem::new("help".to_string(), "Help & Documentation".to_string())
             .with_description("User guide and system information".to_string())
             .with_shortcut('h'));
@@ -386,6 +393,161 @@ impl Screen for MainMenuScreen {
     }
 }
 
+/// CAPA screen - lists CAPA records for the current project with a detail view
+#[derive(Default)]
+pub struct CapaScreen {
+    records: Vec<crate::modules::capa::CapaRecord>,
+    selected: usize,
+    show_detail: bool,
+    message: Option<String>,
+}
+
+impl CapaScreen {
+    pub const fn new() -> Self {
+        Self {
+            records: Vec::new(),
+            selected: 0,
+            show_detail: false,
+            message: None,
+        }
+    }
+
+    fn reload(&mut self) {
+        let loaded = crate::utils::get_current_project_path()
+            .and_then(|path| crate::modules::capa::CapaManager::new(&path))
+            .and_then(|manager| manager.list_capas(None));
+        match loaded {
+            Ok(records) => {
+                self.records = records;
+                self.message = None;
+            }
+            Err(e) => {
+                self.records.clear();
+                self.message = Some(format!("Unable to load CAPA records: {e}"));
+            }
+        }
+        self.selected = self.selected.min(self.records.len().saturating_sub(1));
+    }
+
+    fn render_list(&self, terminal: &Terminal, region: &Region, theme: &Theme) -> QmsResult<()> {
+        let mut row = region.y + 4;
+        terminal.move_cursor(row, region.x + 2)?;
+        terminal.set_color(theme.colors.text_secondary, theme.colors.background)?;
+        terminal.write_text(&format!("{:<10} {:<20} {:<15} {:<9} Title", "ID", "Status", "Source", "Priority"))?;
+        row += 1;
+
+        let visible = region.height.saturating_sub(8) as usize;
+        let first = self.selected.saturating_sub(visible.saturating_sub(1));
+        for (index, record) in self.records.iter().enumerate().skip(first).take(visible) {
+            terminal.move_cursor(row, region.x + 2)?;
+            if index == self.selected {
+                terminal.set_color(theme.colors.text_inverse, theme.colors.selection)?;
+            } else {
+                terminal.set_color(theme.colors.text_primary, theme.colors.background)?;
+            }
+            terminal.write_text(&format!(
+                "{:<10} {:<20} {:<15} {:<9} {}",
+                record.id,
+                record.status.display_name(),
+                record.source.display_name(),
+                record.priority.display_name(),
+                record.title
+            ))?;
+            row += 1;
+        }
+        Ok(())
+    }
+
+    fn render_detail(&self, record: &crate::modules::capa::CapaRecord, terminal: &Terminal, region: &Region, theme: &Theme) -> QmsResult<()> {
+        let mut lines = vec![
+            format!("{} - {}", record.id, record.title),
+            format!("Status: {}   Type: {}   Priority: {}", record.status.display_name(),
+                record.capa_type.display_name(), record.priority.display_name()),
+            format!("Source: {}{}", record.source.display_name(),
+                record.source_reference.as_ref().map(|r| format!(" ({r})")).unwrap_or_default()),
+            format!("Owner: {}", record.owner),
+            String::new(),
+            record.description.clone(),
+        ];
+        if record.root_cause.is_complete() {
+            lines.push(String::new());
+            lines.push(format!("Root cause: {}", record.root_cause.root_cause_statement));
+            for (n, why) in record.root_cause.five_whys.iter().enumerate() {
+                lines.push(format!("  Why {}: {why}", n + 1));
+            }
+        }
+        for action in &record.actions {
+            lines.push(format!("  {} [{}] {}", action.id, action.status.display_name(), action.description));
+        }
+        if let Some(ref check) = record.effectiveness {
+            lines.push(format!("Effectiveness: {} ({})", if check.effective { "effective" } else { "not effective" }, check.method));
+        }
+        if let Some(ref signature) = record.closure_signature_id {
+            lines.push(format!("Closed by {} - signature {signature}", record.closed_by.as_deref().unwrap_or("?")));
+        }
+
+        terminal.set_color(theme.colors.text_primary, theme.colors.background)?;
+        for (offset, line) in lines.iter().take(region.height.saturating_sub(6) as usize).enumerate() {
+            terminal.move_cursor(region.y + 4 + offset as u16, region.x + 2)?;
+            terminal.write_text(line)?;
+        }
+        Ok(())
+    }
+}
+
+impl Screen for CapaScreen {
+    fn name(&self) -> &str {
+        "capa"
+    }
+
+    fn render(&mut self, terminal: &Terminal, region: &Region, theme: &Theme) -> QmsResult<()> {
+        terminal.move_cursor(region.y + 2, region.x + 2)?;
+        terminal.set_color(theme.colors.primary, theme.colors.background)?;
+        terminal.write_text(&format!("🛠️  CAPA ({} records)", self.records.len()))?;
+
+        if let Some(ref message) = self.message {
+            terminal.move_cursor(region.y + 4, region.x + 2)?;
+            terminal.set_color(theme.colors.error, theme.colors.background)?;
+            terminal.write_text(message)?;
+        } else if self.records.is_empty() {
+            terminal.move_cursor(region.y + 4, region.x + 2)?;
+            terminal.set_color(theme.colors.text_secondary, theme.colors.background)?;
+            terminal.write_text("No CAPA records. Create one with 'qms capa create'.")?;
+        } else if self.show_detail {
+            self.render_detail(&self.records[self.selected], terminal, region, theme)?;
+        } else {
+            self.render_list(terminal, region, theme)?;
+        }
+
+        terminal.move_cursor(region.y + region.height.saturating_sub(1), region.x + 2)?;
+        terminal.set_color(theme.colors.text_muted, theme.colors.background)?;
+        terminal.write_text("Up/Down select, Enter details, r refresh, Escape back")?;
+        terminal.reset_colors()?;
+        Ok(())
+    }
+
+    fn handle_event(&mut self, event: &Event) -> QmsResult<ScreenAction> {
+        if let Event::Key(key_event) = event {
+            match key_event.key.as_str() {
+                "Up" if !self.show_detail => self.selected = self.selected.saturating_sub(1),
+                "Down" if !self.show_detail && self.selected + 1 < self.records.len() => self.selected += 1,
+                "Enter" if !self.records.is_empty() => self.show_detail = !self.show_detail,
+                "r" => self.reload(),
+                "Escape" if self.show_detail => self.show_detail = false,
+                "Escape" => return Ok(ScreenAction::NavigateBack),
+                _ => {}
+            }
+        }
+        Ok(ScreenAction::None)
+    }
+
+    fn on_enter(&mut self) -> QmsResult<()> {
+        self.show_detail = false;
+        self.reload();
+        Ok(())
+    }
+}
+
 // Placeholder screen implementations
 macro_rules! impl_placeholder_screen {
     ($name:ident, $screen_name:expr, $title:expr) => {
@@ -441,9 +603,4 @@ macro_rules! impl_placeholder_screen {
     };
 }
 
-impl_placeholder_screen!(ProjectScreen, "projects", "Project Management");
-impl_placeholder_screen!(DocumentsScreen, "documents", "Document Control");
-impl_placeholder_screen!(RisksScreen, "risks", "Risk Management");
-impl_placeholder_screen!(RequirementsScreen, "requirements", "Requirements Management");
-impl_placeholder_screen!(AuditScreen, "audit", "Audit & Compliance");
 impl_placeholder_screen!(HelpScreen, "help", "Help & Documentation");
@@ -568,6 +568,13 @@ impl Widget for FormWidget {
                         }
                         Ok(true)
                     }
+                    "Space" => {
+                        if let Some(field) = self.fields.get_mut(self.current_field) {
+                            field.value.push(' ');
+                            field.error = None; // Clear error on edit
+                        }
+                        Ok(true)
+                    }
                     key if key.len() == 1 && key_event.is_printable() => {
                         if let Some(field) = self.fields.get_mut(self.current_field) {
                             field.value.push(key.chars().next().unwrap());
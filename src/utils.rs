@@ -80,6 +80,22 @@ pub fn current_iso8601_timestamp() -> String {
             second)
 }
 
+/// UTC ISO 8601 date-time for a Unix timestamp
+pub fn iso8601_from_unix(secs: u64) -> String {
+    // Civil-from-days conversion (proleptic Gregorian calendar)
+    let z = (secs / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    let rem = secs % 86_400;
+    format!("{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z", rem / 3_600, rem % 3_600 / 60, rem % 60)
+}
+
 /// Get current date string in YYYY-MM-DD format
 pub fn current_date_string() -> String {
     let timestamp = current_timestamp();
@@ -246,6 +262,26 @@ pub fn calculate_sha256(content: &str) -> String {
     format!("{:x}", Sha256::digest(content.as_bytes()))
 }
 
+/// Encode bytes as lowercase hexadecimal
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Decode a hexadecimal string into bytes
+pub fn decode_hex(hex: &str) -> Result<Vec<u8>, crate::error::QmsError> {
+    if !hex.is_ascii() || hex.len() % 2 != 0 {
+        return Err(crate::error::QmsError::parse_error("Hex string must be ASCII with even length"));
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16)
+                .map_err(|_| crate::error::QmsError::parse_error(&format!("Invalid hex digit at position {i}")))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -6,7 +6,8 @@
 //! Medical Device Compliance: ISO 14971 Risk Management for Medical Devices
 //! Ensures consistent risk calculation methodology across the entire QMS system
 
-use crate::modules::risk_manager::{RiskSeverity, RiskOccurrence, RiskDetectability};
+use crate::modules::risk_manager::{RiskSeverity, RiskOccurrence, RiskDetectability, RiskPolicy};
+use crate::modules::risk_manager::RiskLevel as PolicyRiskLevel;
 
 /// Risk levels based on RPN values for medical device compliance
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -49,7 +50,8 @@ impl RiskCalculator {
 
     /// Determine risk level based on RPN value
     /// 
-    /// Risk level thresholds based on medical device industry standards:
+    /// Thresholds come from the current project's risk acceptability policy.
+    /// The built-in policy uses the medical device industry bands:
     /// - Unacceptable (100-125): Immediate action required
     /// - ALARP (25-99): Risk reduction measures needed
     /// - Acceptable (1-24): Monitor and maintain controls
@@ -60,12 +62,7 @@ impl RiskCalculator {
     /// # Returns
     /// * `RiskLevel` - Categorized risk level
     pub fn calculate_risk_level(rpn: u32) -> RiskLevel {
-        match rpn {
-            100..=125 => RiskLevel::Unacceptable,
-            25..=99 => RiskLevel::ALARP,
-            1..=24 => RiskLevel::Acceptable,
-            _ => RiskLevel::Acceptable, // Default to acceptable for edge cases
-        }
+        Self::from_policy_level(RiskPolicy::active().rpn_level(rpn))
     }
 
     /// Calculate RPN and determine risk level in one operation
@@ -83,10 +80,18 @@ impl RiskCalculator {
         detectability: &RiskDetectability,
     ) -> (u32, RiskLevel) {
         let rpn = Self::calculate_rpn(severity, occurrence, detectability);
-        let level = Self::calculate_risk_level(rpn);
+        let level = Self::from_policy_level(RiskPolicy::active().classify(severity, occurrence, detectability));
         (rpn, level)
     }
 
+    const fn from_policy_level(level: PolicyRiskLevel) -> RiskLevel {
+        match level {
+            PolicyRiskLevel::Unacceptable => RiskLevel::Unacceptable,
+            PolicyRiskLevel::ALARP => RiskLevel::ALARP,
+            PolicyRiskLevel::Acceptable => RiskLevel::Acceptable,
+        }
+    }
+
     /// Validate RPN calculation inputs
     /// 
     /// Ensures all risk factors are within valid ranges (1-5)
@@ -0,0 +1,158 @@
+// Attachments API Handler - Medical Device Quality Management System
+// REST endpoints for evidence files attached to QMS records
+//
+//   GET  /api/attachments                       List attachments (?type=<type>&record_id=<id>)
+//   POST /api/attachments                       Upload (multipart: type, record_id, file, name?, comment?)
+//   GET  /api/attachments/{id}                  Attachment with its versions
+//   GET  /api/attachments/{id}/content          File contents (?version=<n>, default latest)
+
+use crate::error::{QmsError, QmsResult};
+use crate::json_utils::{JsonSerializable, JsonValue};
+use crate::modules::attachments::{Attachment, AttachmentManager, AttachmentTarget, NewAttachment};
+use crate::web::response::HttpStatus;
+use crate::web::search_api::decode_query_value;
+use crate::web::unified_auth_context::UnifiedAuthContext;
+use crate::web::{HttpRequest, HttpResponse};
+use std::collections::HashMap;
+
+/// Attachments API Handler
+pub struct AttachmentsApiHandler;
+
+impl AttachmentsApiHandler {
+    /// Handle GET /api/attachments - List attachments
+    pub fn handle_list_attachments(request: &HttpRequest) -> QmsResult<HttpResponse> {
+        let auth = UnifiedAuthContext::from_web_request(request)?;
+        let manager = AttachmentManager::new(&auth.project_path)?;
+
+        let target = match request.get_query_param("type") {
+            Some(value) => match AttachmentTarget::parse(&decode_query_value(value)) {
+                Ok(target) => Some(target),
+                Err(e) => return Ok(Self::error_response(&e)),
+            },
+            None => None,
+        };
+        let record_id = request.get_query_param("record_id").map(|value| decode_query_value(value));
+
+        let attachments = manager.list(target, record_id.as_deref())?;
+        let mut data = HashMap::new();
+        data.insert(
+            "attachments".to_string(),
+            JsonValue::Array(attachments.iter().map(Self::attachment_summary).collect()),
+        );
+        data.insert("total_count".to_string(), JsonValue::Number(attachments.len() as f64));
+        Ok(HttpResponse::json(&JsonValue::Object(data).json_to_string()))
+    }
+
+    /// Handle POST /api/attachments - Upload a file or a new version of one
+    pub fn handle_upload_attachment(request: &HttpRequest) -> QmsResult<HttpResponse> {
+        let auth = UnifiedAuthContext::from_web_request(request)?;
+        let manager = AttachmentManager::new(&auth.project_path)?;
+
+        let result = request.multipart_parts().and_then(|parts| {
+            let field = |name: &str| {
+                parts
+                    .iter()
+                    .find(|part| part.name == name)
+                    .map(|part| String::from_utf8_lossy(&part.data).trim().to_string())
+                    .filter(|value| !value.is_empty())
+            };
+            let file = parts
+                .iter()
+                .find(|part| part.name == "file")
+                .ok_or_else(|| QmsError::validation_error("Missing file part 'file'"))?;
+            let upload = NewAttachment {
+                target: AttachmentTarget::parse(
+                    &field("type").ok_or_else(|| QmsError::validation_error("Missing field 'type'"))?,
+                )?,
+                record_id: field("record_id").ok_or_else(|| QmsError::validation_error("Missing field 'record_id'"))?,
+                file_name: file
+                    .file_name
+                    .clone()
+                    .ok_or_else(|| QmsError::validation_error("File part 'file' has no filename"))?,
+                name: field("name"),
+                comment: field("comment"),
+            };
+            manager.add(upload, &file.data, &auth.session.username)
+        });
+
+        match result {
+            Ok(attachment) => Ok(HttpResponse::new_with_body(HttpStatus::Created, attachment.to_json())),
+            Err(e) => Ok(Self::error_response(&e)),
+        }
+    }
+
+    /// Handle GET /api/attachments/{id}[/content] - Attachment details or contents
+    pub fn handle_get_attachment(request: &HttpRequest) -> QmsResult<HttpResponse> {
+        let auth = UnifiedAuthContext::from_web_request(request)?;
+        let manager = AttachmentManager::new(&auth.project_path)?;
+
+        match Self::path_segments(request.path()).as_slice() {
+            [id] => match manager.get(id) {
+                Ok(attachment) => Ok(HttpResponse::json(&attachment.to_json())),
+                Err(e) => Ok(Self::error_response(&e)),
+            },
+            [id, "content"] => {
+                let version = match request.get_query_param("version") {
+                    Some(value) => match value.trim_start_matches('v').parse::<u32>() {
+                        Ok(version) => Some(version),
+                        Err(_) => {
+                            return Ok(Self::error_response(&QmsError::validation_error(&format!(
+                                "Invalid version '{value}'"
+                            ))))
+                        }
+                    },
+                    None => None,
+                };
+                match manager.read_content(id, version) {
+                    Ok((_, selected, data)) => Ok(HttpResponse::ok_with_content(data, &selected.mime_type)
+                        .with_header(
+                            "Content-Disposition",
+                            &format!("attachment; filename=\"{}\"", selected.original_filename.replace('"', "")),
+                        )
+                        .with_header("X-Content-SHA256", &selected.sha256)),
+                    Err(e) => Ok(Self::error_response(&e)),
+                }
+            }
+            _ => Ok(HttpResponse::not_found("Unknown attachments endpoint")),
+        }
+    }
+
+    /// Split the path after /api/attachments into segments
+    fn path_segments(path: &str) -> Vec<&str> {
+        path.trim_start_matches("/api/attachments")
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect()
+    }
+
+    /// Summary object used in list responses
+    fn attachment_summary(attachment: &Attachment) -> JsonValue {
+        let mut obj = HashMap::new();
+        obj.insert("id".to_string(), JsonValue::String(attachment.id.clone()));
+        obj.insert("target".to_string(), JsonValue::String(attachment.target.name().to_string()));
+        obj.insert("record_id".to_string(), JsonValue::String(attachment.record_id.clone()));
+        obj.insert("name".to_string(), JsonValue::String(attachment.name.clone()));
+        if let Some(latest) = attachment.latest() {
+            obj.insert("version".to_string(), JsonValue::Number(f64::from(latest.version)));
+            obj.insert("sha256".to_string(), JsonValue::String(latest.sha256.clone()));
+            obj.insert("size".to_string(), JsonValue::Number(latest.size as f64));
+            obj.insert("mime_type".to_string(), JsonValue::String(latest.mime_type.clone()));
+            obj.insert("uploaded_at".to_string(), JsonValue::String(latest.uploaded_at.clone()));
+        }
+        JsonValue::Object(obj)
+    }
+
+    /// Map a QMS error to a JSON error response
+    fn error_response(error: &QmsError) -> HttpResponse {
+        let status = match error {
+            QmsError::NotFound(_) => HttpStatus::NotFound,
+            QmsError::Validation(_) | QmsError::Parse(_) | QmsError::InvalidOperation(_) => HttpStatus::BadRequest,
+            QmsError::Authentication(_) => HttpStatus::Unauthorized,
+            QmsError::Permission(_) => HttpStatus::Forbidden,
+            _ => HttpStatus::InternalServerError,
+        };
+        let mut data = HashMap::new();
+        data.insert("error".to_string(), JsonValue::String(error.to_string()));
+        HttpResponse::new_with_body(status, JsonValue::Object(data).json_to_string())
+    }
+}
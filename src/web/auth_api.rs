@@ -14,6 +14,7 @@ use crate::prelude::*;
 use crate::web::{HttpRequest, HttpResponse};
 use crate::web::response::HttpStatus;
 use crate::modules::user_manager::{StartupAuthService, AdminSetupRequest, QmsFolderSetupRequest, FileBasedAuthService, UserSession, SessionType};
+use crate::modules::user_manager::mfa::is_second_factor_required;
 use crate::modules::audit_logger::audit_log_action;
 use std::sync::Arc;
 use std::path::PathBuf;
@@ -153,6 +154,8 @@ impl AuthApiHandler {
     
     /// Handle user login
     /// POST /api/auth/login
+    /// Users enrolled in TOTP must also send `code`; without it the response
+    /// is 401 with `"mfa_required": true`.
     pub fn handle_login(&self, request: &HttpRequest) -> HttpResponse {
         let body = match request.get_body() {
             Some(body) => body,
@@ -169,8 +172,11 @@ impl AuthApiHandler {
             Err(_) => return HttpResponse::bad_request("Password required"),
         };
         
+        // Second factor for users enrolled in TOTP (or a recovery code)
+        let code = Self::extract_json_string(&body, "code").ok();
+
         // Use unified authentication service for web login
-        match self.auth_service.web_login(&username, &password,
+        match self.auth_service.web_login_with_code(&username, &password, code.as_deref(),
                                          request.get_header("x-forwarded-for").map(|s| s.to_string()),
                                          request.get_header("user-agent").map(|s| s.to_string())) {
             Ok(session) => {
@@ -199,6 +205,10 @@ impl AuthApiHandler {
                 response.add_header("Set-Cookie", &format!("session_id={}; HttpOnly; Path=/", session.session_id));
                 response
             }
+            Err(e) if is_second_factor_required(&e) => {
+                let json = r#"{"success": false, "mfa_required": true, "message": "Authentication code required"}"#;
+                HttpResponse::new_with_body(HttpStatus::Unauthorized, json.to_string())
+            }
             Err(_) => {
                 HttpResponse::unauthorized("Invalid credentials")
             }
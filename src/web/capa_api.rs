@@ -0,0 +1,281 @@
+// CAPA API Handler - Medical Device Quality Management System
+// REST endpoints for Corrective and Preventive Actions (FDA 21 CFR 820.100)
+//
+//   GET  /api/capa                              List CAPAs (?status=<status>)
+//   POST /api/capa                              Create a CAPA
+//   GET  /api/capa/{id}                         CAPA details
+//   POST /api/capa/{id}/root-cause              Record root-cause analysis
+//   POST /api/capa/{id}/actions                 Add an action
+//   POST /api/capa/{id}/actions/{action}/status Update an action's status
+//   POST /api/capa/{id}/effectiveness           Record the effectiveness check
+//   POST /api/capa/{id}/risks                   Link a risk
+//   POST /api/capa/{id}/links                   Create a traceability link
+//   POST /api/capa/{id}/close                   Close with electronic signature
+//   POST /api/capa/{id}/cancel                  Cancel with justification
+//
+// Every request acts as the session user. CapaManager requires that user to have
+// write access to create a CAPA and a CAPA approver role to record the
+// effectiveness check or close, the same as the CLI; refusals return 403.
+
+use crate::error::{QmsError, QmsResult};
+use crate::json_utils::{JsonSerializable, JsonValue};
+use crate::modules::capa::{
+    CapaActionStatus, CapaManager, CapaPriority, CapaRecord, CapaSource, CapaStatus, CapaType,
+    FishboneCategory, FishboneCause, NewCapa,
+};
+use crate::modules::traceability::links::TraceLinkType;
+use crate::web::response::HttpStatus;
+use crate::web::unified_auth_context::UnifiedAuthContext;
+use crate::web::{HttpRequest, HttpResponse};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// CAPA API Handler
+pub struct CapaApiHandler;
+
+impl CapaApiHandler {
+    /// Handle GET /api/capa - List CAPA records
+    pub fn handle_list_capas(request: &HttpRequest) -> QmsResult<HttpResponse> {
+        let auth = UnifiedAuthContext::from_web_request(request)?;
+        let manager = CapaManager::new(&auth.project_path)?;
+
+        let status = match request.get_query_param("status") {
+            Some(status) => match CapaStatus::from_str(status) {
+                Ok(status) => Some(status),
+                Err(e) => return Ok(Self::error_response(&e)),
+            },
+            None => None,
+        };
+
+        let records = manager.list_capas(status.as_ref())?;
+        let mut data = HashMap::new();
+        data.insert(
+            "capas".to_string(),
+            JsonValue::Array(records.iter().map(Self::capa_summary).collect()),
+        );
+        data.insert("total_count".to_string(), JsonValue::Number(records.len() as f64));
+        Ok(HttpResponse::json(&JsonValue::Object(data).json_to_string()))
+    }
+
+    /// Handle POST /api/capa - Create a CAPA record
+    pub fn handle_create_capa(request: &HttpRequest) -> QmsResult<HttpResponse> {
+        let auth = UnifiedAuthContext::from_web_request(request)?;
+        let manager = CapaManager::new(&auth.project_path)?;
+        let username = auth.session.username.clone();
+
+        let result = Self::parse_json_request(request).and_then(|body| {
+            let new_capa = NewCapa {
+                title: Self::get_string_field(&body, "title")?,
+                description: Self::get_string_field(&body, "description")?,
+                source: CapaSource::from_str(&Self::get_string_field(&body, "source")?)?,
+                source_reference: Self::get_optional_string_field(&body, "source_reference"),
+                capa_type: match Self::get_optional_string_field(&body, "capa_type") {
+                    Some(value) => CapaType::from_str(&value)?,
+                    None => CapaType::Corrective,
+                },
+                priority: match Self::get_optional_string_field(&body, "priority") {
+                    Some(value) => CapaPriority::from_str(&value)?,
+                    None => CapaPriority::Medium,
+                },
+                owner: Self::get_optional_string_field(&body, "owner").unwrap_or_else(|| username.clone()),
+                due_date: Self::get_optional_string_field(&body, "due_date"),
+            };
+            manager.create_capa(new_capa, &username)
+        });
+
+        match result {
+            Ok(record) => Ok(HttpResponse::new_with_body(HttpStatus::Created, record.to_json())),
+            Err(e) => Ok(Self::error_response(&e)),
+        }
+    }
+
+    /// Handle GET /api/capa/{id} - CAPA details with traceability links
+    pub fn handle_get_capa(request: &HttpRequest) -> QmsResult<HttpResponse> {
+        let auth = UnifiedAuthContext::from_web_request(request)?;
+        let manager = CapaManager::new(&auth.project_path)?;
+        let segments = Self::path_segments(request.path());
+        if segments.len() != 1 {
+            return Ok(HttpResponse::not_found("Unknown CAPA endpoint"));
+        }
+
+        match manager.load_capa(segments[0]) {
+            Ok(record) => Ok(HttpResponse::json(&Self::capa_detail(&manager, &record))),
+            Err(e) => Ok(Self::error_response(&e)),
+        }
+    }
+
+    /// Handle POST /api/capa/{id}/... - CAPA workflow actions
+    pub fn handle_capa_action(request: &HttpRequest) -> QmsResult<HttpResponse> {
+        let auth = UnifiedAuthContext::from_web_request(request)?;
+        let manager = CapaManager::new(&auth.project_path)?;
+        let username = auth.session.username.clone();
+        let segments = Self::path_segments(request.path());
+
+        let body = match Self::parse_json_request(request) {
+            Ok(body) => body,
+            Err(e) => return Ok(Self::error_response(&e)),
+        };
+
+        let result = match segments.as_slice() {
+            [id, "root-cause"] => {
+                let five_whys = Self::get_string_array_field(&body, "five_whys");
+                let fishbone = match body.get("fishbone") {
+                    Some(JsonValue::Array(items)) => items.iter().map(|item| match item {
+                        JsonValue::Object(cause) => Ok(FishboneCause {
+                            category: FishboneCategory::from_str(&Self::get_string_field(cause, "category")?)?,
+                            cause: Self::get_string_field(cause, "cause")?,
+                        }),
+                        _ => Err(QmsError::validation_error("Fishbone entries must be objects")),
+                    }).collect::<QmsResult<Vec<_>>>(),
+                    _ => Ok(Vec::new()),
+                };
+                fishbone.and_then(|fishbone| {
+                    let statement = Self::get_string_field(&body, "root_cause_statement")?;
+                    manager.record_root_cause(id, five_whys, fishbone, &statement, &username)
+                })
+            }
+            [id, "actions"] => Self::get_string_field(&body, "description").and_then(|description| {
+                let owner = Self::get_optional_string_field(&body, "owner").unwrap_or_else(|| username.clone());
+                let due_date = Self::get_optional_string_field(&body, "due_date");
+                manager.add_action(id, &description, &owner, due_date)?;
+                manager.load_capa(id)
+            }),
+            [id, "actions", action_id, "status"] => Self::get_string_field(&body, "status")
+                .and_then(|status| CapaActionStatus::from_str(&status))
+                .and_then(|status| manager.update_action_status(id, action_id, status)),
+            [id, "effectiveness"] => (|| {
+                let effective = match body.get("effective") {
+                    Some(JsonValue::Bool(effective)) => *effective,
+                    _ => return Err(QmsError::validation_error("Field 'effective' must be a boolean")),
+                };
+                manager.record_effectiveness(
+                    id,
+                    &Self::get_string_field(&body, "method")?,
+                    &Self::get_string_field(&body, "criteria")?,
+                    effective,
+                    &Self::get_optional_string_field(&body, "evidence").unwrap_or_default(),
+                    &username,
+                )
+            })(),
+            [id, "risks"] => Self::get_string_field(&body, "risk_id")
+                .and_then(|risk_id| manager.link_risk(id, &risk_id)),
+            [id, "links"] => (|| {
+                let target_id = Self::get_string_field(&body, "target_id")?;
+                let link_type = match Self::get_optional_string_field(&body, "link_type") {
+                    Some(value) => TraceLinkType::from_str(&value)?,
+                    None => TraceLinkType::Related,
+                };
+                manager.link_trace(id, &target_id, link_type)?;
+                manager.load_capa(id)
+            })(),
+            [id, "close"] => Self::get_string_field(&body, "password").and_then(|password| {
+                let code = Self::get_optional_string_field(&body, "code");
+                manager.close_capa(id, &username, &password, code.as_deref())
+            }),
+            [id, "cancel"] => Self::get_string_field(&body, "reason")
+                .and_then(|reason| manager.cancel_capa(id, &reason)),
+            _ => return Ok(HttpResponse::not_found("Unknown CAPA endpoint")),
+        };
+
+        match result {
+            Ok(record) => Ok(HttpResponse::json(&Self::capa_detail(&manager, &record))),
+            Err(e) => Ok(Self::error_response(&e)),
+        }
+    }
+
+    /// Path segments after /api/capa/
+    fn path_segments(path: &str) -> Vec<&str> {
+        path.trim_start_matches("/api/capa")
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect()
+    }
+
+    /// Summary object used in list responses
+    fn capa_summary(record: &CapaRecord) -> JsonValue {
+        let mut obj = HashMap::new();
+        obj.insert("id".to_string(), JsonValue::String(record.id.clone()));
+        obj.insert("title".to_string(), JsonValue::String(record.title.clone()));
+        obj.insert("status".to_string(), JsonValue::String(record.status.display_name().to_string()));
+        obj.insert("source".to_string(), JsonValue::String(record.source.display_name().to_string()));
+        obj.insert("capa_type".to_string(), JsonValue::String(record.capa_type.display_name().to_string()));
+        obj.insert("priority".to_string(), JsonValue::String(record.priority.display_name().to_string()));
+        obj.insert("owner".to_string(), JsonValue::String(record.owner.clone()));
+        obj.insert("updated_at".to_string(), JsonValue::String(record.updated_at.clone()));
+        JsonValue::Object(obj)
+    }
+
+    /// Full record plus traceability links and closure signature status
+    fn capa_detail(manager: &CapaManager, record: &CapaRecord) -> String {
+        let mut data = match JsonValue::parse(&record.to_json()) {
+            Ok(JsonValue::Object(obj)) => obj,
+            _ => HashMap::new(),
+        };
+
+        let links = manager.trace_links(&record.id).unwrap_or_default();
+        data.insert("trace_links".to_string(), JsonValue::Array(links.iter().map(|link| {
+            let mut obj = HashMap::new();
+            obj.insert("id".to_string(), JsonValue::String(link.id.clone()));
+            obj.insert("source_id".to_string(), JsonValue::String(link.source_id.clone()));
+            obj.insert("target_id".to_string(), JsonValue::String(link.target_id.clone()));
+            obj.insert("link_type".to_string(), JsonValue::String(link.link_type.to_string()));
+            JsonValue::Object(obj)
+        }).collect()));
+
+        if record.closure_signature_id.is_some() {
+            let valid = manager.verify_closure(&record.id).map(|v| v.is_valid).unwrap_or(false);
+            data.insert("closure_signature_valid".to_string(), JsonValue::Bool(valid));
+        }
+
+        JsonValue::Object(data).json_to_string()
+    }
+
+    /// Map workflow errors to client-facing HTTP status codes
+    fn error_response(error: &QmsError) -> HttpResponse {
+        let status = match error {
+            QmsError::NotFound(_) => HttpStatus::NotFound,
+            QmsError::Validation(_) | QmsError::Parse(_) | QmsError::InvalidOperation(_) => HttpStatus::BadRequest,
+            QmsError::Authentication(_) => HttpStatus::Unauthorized,
+            QmsError::Permission(_) => HttpStatus::Forbidden,
+            _ => HttpStatus::InternalServerError,
+        };
+        let mut data = HashMap::new();
+        data.insert("error".to_string(), JsonValue::String(error.to_string()));
+        HttpResponse::new_with_body(status, JsonValue::Object(data).json_to_string())
+    }
+
+    /// Parse JSON request body
+    fn parse_json_request(request: &HttpRequest) -> QmsResult<HashMap<String, JsonValue>> {
+        let body = request.get_body_as_string()
+            .map_err(|e| QmsError::Parse(format!("Invalid request body: {e}")))?;
+        if body.trim().is_empty() {
+            return Ok(HashMap::new());
+        }
+        match JsonValue::parse_from_str(&body).map_err(|e| QmsError::Parse(format!("Invalid JSON: {e}")))? {
+            JsonValue::Object(obj) => Ok(obj),
+            _ => Err(QmsError::Parse("Expected JSON object".to_string())),
+        }
+    }
+
+    /// Get required string field from JSON object
+    fn get_string_field(data: &HashMap<String, JsonValue>, field: &str) -> QmsResult<String> {
+        match data.get(field) {
+            Some(JsonValue::String(s)) => Ok(s.clone()),
+            Some(_) => Err(QmsError::validation_error(&format!("Field '{field}' must be a string"))),
+            None => Err(QmsError::validation_error(&format!("Field '{field}' is required"))),
+        }
+    }
+
+    /// Get optional string field from JSON object
+    fn get_optional_string_field(data: &HashMap<String, JsonValue>, field: &str) -> Option<String> {
+        data.get(field).and_then(|v| v.as_string().cloned())
+    }
+
+    /// Get array of strings from JSON object (missing field yields an empty list)
+    fn get_string_array_field(data: &HashMap<String, JsonValue>, field: &str) -> Vec<String> {
+        match data.get(field) {
+            Some(JsonValue::Array(items)) => items.iter().filter_map(|v| v.as_string().cloned()).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
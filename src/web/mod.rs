@@ -38,6 +38,8 @@ pub mod unified_audit_api;
 #[allow(dead_code)]
 pub mod http_parser;
 #[allow(dead_code)]
+pub mod attachments_api;
+#[allow(dead_code)]
 pub mod audit_api;
 #[allow(dead_code)]
 pub mod reports_api;
@@ -46,6 +48,10 @@ pub mod project_api;
 #[allow(dead_code)]
 pub mod auth_api;
 #[allow(dead_code)]
+pub mod capa_api;
+#[allow(dead_code)]
+pub mod search_api;
+#[allow(dead_code)]
 pub mod unified_session_adapter;
 
 pub use request::HttpRequest;
@@ -142,6 +148,18 @@ pub use reports_api::{ReportsApiHandler, ReportProvider, MedicalDeviceReportProv
 #[allow(unused_imports)]
 pub use project_api::{ProjectApiHandler, ProjectProvider, MedicalDeviceProjectProvider};
 
+// CAPA API types - REST endpoints for corrective and preventive actions
+#[allow(unused_imports)]
+pub use capa_api::CapaApiHandler;
+
+// Search API types - REST endpoint for full-text search across record types
+#[allow(unused_imports)]
+pub use search_api::SearchApiHandler;
+
+// Attachments API types - REST endpoints for evidence file upload and download
+#[allow(unused_imports)]
+pub use attachments_api::AttachmentsApiHandler;
+
 // Authentication API types - REST endpoints for user-first authentication flow
 #[allow(unused_imports)]
 pub use auth_api::AuthApiHandler;
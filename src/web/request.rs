@@ -12,6 +12,15 @@ pub struct HttpRequest {
     pub timestamp: u64,
 }
 
+/// One field of a `multipart/form-data` body
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultipartPart {
+    pub name: String,
+    pub file_name: Option<String>,
+    pub content_type: Option<String>,
+    pub data: Vec<u8>,
+}
+
 /// HTTP methods
 #[derive(Debug, Clone, PartialEq)]
 pub enum HttpMethod {
@@ -153,6 +162,26 @@ impl HttpRequest {
         })
     }
 
+    /// Parse a request as received, keeping the body bytes unchanged
+    ///
+    /// `parse` works on text and re-joins body lines, which would corrupt
+    /// binary uploads.
+    pub fn parse_bytes(request_data: &[u8]) -> Result<Self, crate::error::QmsError> {
+        let Some(body_start) = Self::body_offset(request_data) else {
+            return Self::parse(&String::from_utf8_lossy(request_data));
+        };
+        let mut request = Self::parse(&String::from_utf8_lossy(&request_data[..body_start]))?;
+        request.body = request_data[body_start..].to_vec();
+        Ok(request)
+    }
+
+    /// Offset of the first body byte, once the blank line ending the head is present
+    pub fn body_offset(data: &[u8]) -> Option<usize> {
+        find_bytes(data, b"\r\n\r\n", 0)
+            .map(|pos| pos + 4)
+            .or_else(|| find_bytes(data, b"\n\n", 0).map(|pos| pos + 2))
+    }
+
     pub fn path(&self) -> &str {
         &self.uri
     }
@@ -229,6 +258,79 @@ impl HttpRequest {
             .map(|ct| ct.contains("multipart/form-data"))
             .unwrap_or(false)
     }
+
+    /// Fields of a `multipart/form-data` body, in the order sent
+    pub fn multipart_parts(&self) -> Result<Vec<MultipartPart>, crate::error::QmsError> {
+        use crate::error::QmsError;
+
+        let boundary = self
+            .get_content_type()
+            .filter(|_| self.is_multipart())
+            .and_then(|content_type| {
+                content_type
+                    .split(';')
+                    .find_map(|param| param.trim().strip_prefix("boundary="))
+                    .map(|boundary| boundary.trim_matches('"').to_string())
+            })
+            .filter(|boundary| !boundary.is_empty())
+            .ok_or_else(|| QmsError::validation_error("Expected multipart/form-data with a boundary"))?;
+        let delimiter = format!("--{boundary}").into_bytes();
+
+        let mut parts = Vec::new();
+        let mut pos = find_bytes(&self.body, &delimiter, 0)
+            .ok_or_else(|| QmsError::validation_error("Multipart body has no boundary"))?
+            + delimiter.len();
+        // The closing delimiter is followed by "--"
+        while !self.body[pos..].starts_with(b"--") {
+            let next = find_bytes(&self.body, &delimiter, pos)
+                .ok_or_else(|| QmsError::validation_error("Unterminated multipart body"))?;
+            let part = &self.body[pos..next];
+            let part = part.strip_prefix(b"\r\n").or_else(|| part.strip_prefix(b"\n")).unwrap_or(part);
+            let part = part.strip_suffix(b"\r\n").or_else(|| part.strip_suffix(b"\n")).unwrap_or(part);
+
+            let data_start = Self::body_offset(part)
+                .ok_or_else(|| QmsError::validation_error("Multipart part has no header end"))?;
+            let mut name = None;
+            let mut file_name = None;
+            let mut content_type = None;
+            for line in String::from_utf8_lossy(&part[..data_start]).lines() {
+                let Some((header, value)) = line.split_once(':') else { continue };
+                match header.trim().to_lowercase().as_str() {
+                    "content-disposition" => {
+                        for param in value.split(';').skip(1) {
+                            let Some((key, param_value)) = param.split_once('=') else { continue };
+                            let param_value = param_value.trim().trim_matches('"').to_string();
+                            match key.trim().to_lowercase().as_str() {
+                                "name" => name = Some(param_value),
+                                "filename" => file_name = Some(param_value),
+                                _ => {}
+                            }
+                        }
+                    }
+                    "content-type" => content_type = Some(value.trim().to_string()),
+                    _ => {}
+                }
+            }
+
+            parts.push(MultipartPart {
+                name: name.ok_or_else(|| QmsError::validation_error("Multipart part has no field name"))?,
+                file_name,
+                content_type,
+                data: part[data_start..].to_vec(),
+            });
+            pos = next + delimiter.len();
+        }
+        Ok(parts)
+    }
+}
+
+/// Position of `needle` in `haystack` at or after `from`
+fn find_bytes(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|pos| pos + from)
 }
 
 #[cfg(test)]
@@ -290,6 +392,35 @@ mod tests {
         assert!(request.is_multipart());
     }
 
+    #[test]
+    fn test_binary_body_and_multipart_parts() {
+        let mut raw = b"POST /api/attachments HTTP/1.1\r\n\
+Content-Type: multipart/form-data; boundary=XyZ\r\n\r\n\
+--XyZ\r\nContent-Disposition: form-data; name=\"record_id\"\r\n\r\nCAPA-001\r\n\
+--XyZ\r\nContent-Disposition: form-data; name=\"file\"; filename=\"scope.png\"\r\n\
+Content-Type: image/png\r\n\r\n"
+            .to_vec();
+        let image = b"\x89PNG\r\n\x1a\n\x00\xff\r\n\r\nend".to_vec();
+        raw.extend_from_slice(&image);
+        raw.extend_from_slice(b"\r\n--XyZ--\r\n");
+
+        let request = HttpRequest::parse_bytes(&raw).unwrap();
+        assert_eq!(request.uri, "/api/attachments");
+        assert!(request.body.starts_with(b"--XyZ"));
+
+        let parts = request.multipart_parts().unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].name, "record_id");
+        assert_eq!(parts[0].data, b"CAPA-001");
+        assert_eq!(parts[1].file_name.as_deref(), Some("scope.png"));
+        assert_eq!(parts[1].content_type.as_deref(), Some("image/png"));
+        // Binary content, including bytes that look like line breaks, is kept exactly
+        assert_eq!(parts[1].data, image);
+
+        let plain = HttpRequest::new("POST".to_string(), "/".to_string());
+        assert!(plain.multipart_parts().is_err());
+    }
+
     #[test]
     fn test_session_cookie_extraction() {
         let mut request = HttpRequest::new("GET".to_string(), "/".to_string());
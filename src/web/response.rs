@@ -23,6 +23,7 @@ pub enum HttpStatus {
     NotFound = 404,
     MethodNotAllowed = 405,
     Conflict = 409,
+    PayloadTooLarge = 413,
     InternalServerError = 500,
     NotImplemented = 501,
     ServiceUnavailable = 503,
@@ -46,6 +47,7 @@ impl HttpStatus {
             HttpStatus::NotFound => "Not Found",
             HttpStatus::MethodNotAllowed => "Method Not Allowed",
             HttpStatus::Conflict => "Conflict",
+            HttpStatus::PayloadTooLarge => "Payload Too Large",
             HttpStatus::InternalServerError => "Internal Server Error",
             HttpStatus::NotImplemented => "Not Implemented",
             HttpStatus::ServiceUnavailable => "Service Unavailable",
@@ -0,0 +1,117 @@
+// Search API Handler - Medical Device Quality Management System
+// Full-text search across documents, requirements, risks and test cases
+//
+//   GET /api/search?q=<query>[&type=<types>][&limit=<n>]   Ranked hits
+
+use crate::error::{QmsError, QmsResult};
+use crate::json_utils::JsonValue;
+use crate::modules::search::{search_project, SearchEntity, SearchQuery};
+use crate::web::response::HttpStatus;
+use crate::web::unified_auth_context::UnifiedAuthContext;
+use crate::web::{HttpRequest, HttpResponse};
+use std::collections::HashMap;
+
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 200;
+
+/// Search API Handler
+pub struct SearchApiHandler;
+
+impl SearchApiHandler {
+    /// Handle GET /api/search - Search every record type
+    pub fn handle_search(request: &HttpRequest) -> QmsResult<HttpResponse> {
+        let auth = UnifiedAuthContext::from_web_request(request)?;
+
+        let text = request.get_query_param("q").map(|q| decode_query_value(q)).unwrap_or_default();
+        let mut query = match SearchQuery::parse(&text) {
+            Ok(query) => query,
+            Err(e) => return Ok(Self::error_response(&e)),
+        };
+
+        let mut entities = Vec::new();
+        if let Some(types) = request.get_query_param("type") {
+            for name in decode_query_value(types).split(',') {
+                match SearchEntity::parse(name) {
+                    Some(entity) => entities.push(entity),
+                    None => {
+                        return Ok(Self::error_response(&QmsError::validation_error(&format!(
+                            "Unknown record type '{name}'"
+                        ))))
+                    }
+                }
+            }
+        }
+        if query.is_empty() && entities.is_empty() {
+            return Ok(Self::error_response(&QmsError::validation_error("Query parameter 'q' is required")));
+        }
+        query.restrict_to(&entities);
+
+        let limit = match request.get_query_param("limit").map(|limit| limit.parse::<usize>()) {
+            None => DEFAULT_LIMIT,
+            Some(Ok(limit)) => limit.min(MAX_LIMIT),
+            Some(Err(_)) => {
+                return Ok(Self::error_response(&QmsError::validation_error("Invalid 'limit' parameter")))
+            }
+        };
+
+        let results = search_project(&auth.project_path, &query, limit)?;
+        let mut data = match results.to_json() {
+            JsonValue::Object(data) => data,
+            _ => HashMap::new(),
+        };
+        data.insert("query".to_string(), JsonValue::String(text));
+        Ok(HttpResponse::json(&JsonValue::Object(data).json_to_string()))
+    }
+
+    fn error_response(error: &QmsError) -> HttpResponse {
+        let status = match error {
+            QmsError::Validation(_) | QmsError::Parse(_) => HttpStatus::BadRequest,
+            QmsError::Authentication(_) => HttpStatus::Unauthorized,
+            QmsError::Permission(_) => HttpStatus::Forbidden,
+            _ => HttpStatus::InternalServerError,
+        };
+        let mut data = HashMap::new();
+        data.insert("error".to_string(), JsonValue::String(error.to_string()));
+        HttpResponse::new_with_body(status, JsonValue::Object(data).json_to_string())
+    }
+}
+
+/// Decode a `application/x-www-form-urlencoded` query value
+pub(crate) fn decode_query_value(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_query_value() {
+        assert_eq!(
+            decode_query_value("type%3ASRS+%22alarm%20latency%22"),
+            "type:SRS \"alarm latency\""
+        );
+        assert_eq!(decode_query_value("100%"), "100%");
+        assert_eq!(decode_query_value("%zz"), "%zz");
+    }
+}
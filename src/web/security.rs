@@ -6,7 +6,9 @@ use crate::prelude::*;
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
+use rustls::pki_types::CertificateDer;
 
 /// Security configuration for the QMS web server
 #[derive(Debug, Clone)]
@@ -65,12 +67,14 @@ pub struct TlsCertificate {
     pub valid_until: u64,
     pub subject: String,
     pub issuer: String,
+    pub chain_length: usize,
 }
 
 /// Security manager for the QMS web server
 pub struct SecurityManager {
     config: SecurityConfig,
     certificate: Option<TlsCertificate>,
+    tls_config: Option<Arc<rustls::ServerConfig>>,
 }
 
 impl SecurityManager {
@@ -79,6 +83,7 @@ impl SecurityManager {
         Self {
             config: SecurityConfig::default(),
             certificate: None,
+            tls_config: None,
         }
     }
 
@@ -87,10 +92,12 @@ impl SecurityManager {
         Self {
             config,
             certificate: None,
+            tls_config: None,
         }
     }
 
-    /// Load TLS certificate and key from files
+    /// Load a PEM certificate chain and private key and build the TLS configuration.
+    /// The leaf certificate must come first; intermediates follow it in the same file.
     pub fn load_certificate(&mut self, cert_path: &str, key_path: &str) -> QmsResult<()> {
         if !Path::new(cert_path).exists() {
             return Err(QmsError::io_error("Certificate file not found"));
@@ -106,32 +113,67 @@ impl SecurityManager {
         let key_data = fs::read(key_path)
             .map_err(|_| QmsError::io_error("Failed to read private key"))?;
 
-        // Basic certificate validation (simplified for stdlib-only implementation)
-        if cert_data.is_empty() || key_data.is_empty() {
-            return Err(QmsError::validation_error("Certificate or key file is empty"));
+        let chain: Vec<CertificateDer<'static>> = rustls_pemfile::certs(&mut cert_data.as_slice())
+            .collect::<Result<_, _>>()
+            .map_err(|e| QmsError::validation_error(&format!("Invalid PEM certificate: {e}")))?;
+        if chain.is_empty() {
+            return Err(QmsError::validation_error("Certificate file contains no PEM certificates"));
         }
 
-        // Create certificate info (simplified - in production, parse actual certificate)
+        let private_key = rustls_pemfile::private_key(&mut key_data.as_slice())
+            .map_err(|e| QmsError::validation_error(&format!("Invalid PEM private key: {e}")))?
+            .ok_or_else(|| QmsError::validation_error("Key file contains no private key"))?;
+
+        let (_, leaf) = x509_parser::parse_x509_certificate(chain[0].as_ref())
+            .map_err(|e| QmsError::validation_error(&format!("Failed to parse certificate: {e}")))?;
+        let validity = leaf.validity();
+        let expires = validity.not_after.to_string();
+
         let certificate = TlsCertificate {
+            valid_from: validity.not_before.timestamp().max(0) as u64,
+            valid_until: validity.not_after.timestamp().max(0) as u64,
+            subject: leaf.subject().to_string(),
+            issuer: leaf.issuer().to_string(),
+            chain_length: chain.len(),
             cert_data,
             key_data,
-            valid_from: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
-            valid_until: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + (365 * 24 * 3600), // 1 year
-            subject: "QMS Medical Device Server".to_string(),
-            issuer: "QMS Certificate Authority".to_string(),
         };
 
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        if now < certificate.valid_from || now > certificate.valid_until {
+            return Err(QmsError::validation_error(&format!(
+                "Certificate for '{}' is not valid at the current time",
+                certificate.subject
+            )));
+        }
+
+        // TLS 1.2 and 1.3 only; rustls also checks the key matches the leaf certificate
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let tls_config = rustls::ServerConfig::builder_with_provider(provider)
+            .with_protocol_versions(&[&rustls::version::TLS13, &rustls::version::TLS12])
+            .map_err(|e| QmsError::validation_error(&format!("Unsupported TLS configuration: {e}")))?
+            .with_no_client_auth()
+            .with_single_cert(chain, private_key)
+            .map_err(|e| QmsError::validation_error(&format!("Certificate and key do not match: {e}")))?;
+
+        println!("🔒 TLS Certificate loaded: {} (expires {expires})", certificate.subject);
+
         self.certificate = Some(certificate);
+        self.tls_config = Some(Arc::new(tls_config));
         self.config.cert_file = Some(cert_path.to_string());
         self.config.key_file = Some(key_path.to_string());
 
-        println!("🔒 TLS Certificate loaded successfully");
         Ok(())
     }
 
+    /// TLS server configuration built from the loaded certificate
+    pub fn tls_server_config(&self) -> Option<Arc<rustls::ServerConfig>> {
+        self.tls_config.clone()
+    }
+
     /// Check if HTTPS is properly configured
     pub const fn is_https_configured(&self) -> bool {
-        self.certificate.is_some() && self.config.cert_file.is_some() && self.config.key_file.is_some()
+        self.tls_config.is_some() && self.config.cert_file.is_some() && self.config.key_file.is_some()
     }
 
     /// Get security configuration
@@ -308,6 +350,49 @@ mod tests {
         assert!(response.contains("Strict-Transport-Security"));
     }
 
+    fn write_self_signed(dir: &Path) -> (String, String) {
+        let certified = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let cert_path = dir.join("server.crt");
+        let key_path = dir.join("server.key");
+        fs::write(&cert_path, certified.cert.pem()).unwrap();
+        fs::write(&key_path, certified.key_pair.serialize_pem()).unwrap();
+        (cert_path.to_string_lossy().to_string(), key_path.to_string_lossy().to_string())
+    }
+
+    #[test]
+    fn test_load_certificate_parses_pem() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (cert_path, key_path) = write_self_signed(temp_dir.path());
+
+        let mut manager = SecurityManager::new();
+        manager.load_certificate(&cert_path, &key_path).unwrap();
+
+        let info = manager.get_certificate_info().unwrap();
+        assert_eq!(info.chain_length, 1);
+        assert!(info.valid_until > info.valid_from);
+        assert!(manager.is_https_configured());
+        assert!(manager.is_certificate_valid());
+        assert!(manager.tls_server_config().is_some());
+    }
+
+    #[test]
+    fn test_load_certificate_rejects_invalid_material() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (cert_path, _) = write_self_signed(temp_dir.path());
+
+        // Key from a different certificate must not be accepted
+        let other_dir = tempfile::tempdir().unwrap();
+        let (_, other_key) = write_self_signed(other_dir.path());
+        let mut manager = SecurityManager::new();
+        assert!(manager.load_certificate(&cert_path, &other_key).is_err());
+
+        // Non-PEM content is rejected rather than trusted
+        let bogus = temp_dir.path().join("bogus.crt");
+        fs::write(&bogus, "not a certificate").unwrap();
+        assert!(manager.load_certificate(&bogus.to_string_lossy(), &other_key).is_err());
+        assert!(!manager.is_https_configured());
+    }
+
     #[test]
     fn test_request_size_validation() {
         let manager = SecurityManager::new();
@@ -14,16 +14,82 @@ use std::thread;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use crate::prelude::{QmsResult, QmsError};
 
+/// Largest accepted request line plus headers
+const MAX_HEAD_SIZE: usize = 64 * 1024;
+
+/// Room for multipart boundaries and form fields around an uploaded file
+const UPLOAD_OVERHEAD: usize = 64 * 1024;
+
 /// HTTP method handler function type
 type ApiHandler = fn(&HttpRequest, &mut TcpStream) -> QmsResult<()>;
 
+/// Accepted client connection, either plain TCP or TLS-terminated
+pub enum ConnectionStream {
+    Plain(TcpStream),
+    Tls(Box<rustls::StreamOwned<rustls::ServerConnection, TcpStream>>),
+}
+
+impl ConnectionStream {
+    /// Wrap a TCP stream, terminating TLS when a server configuration is given.
+    /// The handshake itself runs lazily on the first read, inside the worker thread.
+    pub fn accept(stream: TcpStream, tls_config: Option<Arc<rustls::ServerConfig>>) -> QmsResult<Self> {
+        match tls_config {
+            Some(config) => {
+                let connection = rustls::ServerConnection::new(config)
+                    .map_err(|e| QmsError::io_error(&format!("TLS session setup failed: {e}")))?;
+                Ok(ConnectionStream::Tls(Box::new(rustls::StreamOwned::new(connection, stream))))
+            }
+            None => Ok(ConnectionStream::Plain(stream)),
+        }
+    }
+
+    fn tcp(&self) -> &TcpStream {
+        match self {
+            ConnectionStream::Plain(stream) => stream,
+            ConnectionStream::Tls(tls) => &tls.sock,
+        }
+    }
+}
+
+impl Read for ConnectionStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            ConnectionStream::Plain(stream) => stream.read(buf),
+            ConnectionStream::Tls(tls) => tls.read(buf),
+        }
+    }
+}
+
+impl Write for ConnectionStream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            ConnectionStream::Plain(stream) => stream.write(buf),
+            ConnectionStream::Tls(tls) => tls.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            ConnectionStream::Plain(stream) => stream.flush(),
+            ConnectionStream::Tls(tls) => tls.flush(),
+        }
+    }
+}
+
 /// Connection job for thread pool
 pub struct ConnectionJob {
-    stream: TcpStream,
+    stream: ConnectionStream,
     security_manager: Arc<Mutex<SecurityManager>>,
     asset_manager: AssetManager,
 }
 
+/// Plain HTTP connection to answer with a redirect to the HTTPS listener
+pub struct RedirectJob {
+    stream: TcpStream,
+    security_manager: Arc<Mutex<SecurityManager>>,
+    default_host: String,
+}
+
 /// Thread pool for handling HTTP connections
 pub struct ThreadPool {
     workers: Vec<Worker>,
@@ -34,6 +100,7 @@ pub struct ThreadPool {
 /// Message types for thread pool communication
 enum Message {
     NewJob(ConnectionJob),
+    Redirect(RedirectJob),
     Terminate,
 }
 
@@ -68,6 +135,11 @@ impl ThreadPool {
         self.sender.send(Message::NewJob(job)).map_err(|_| "Failed to send job to thread pool")
     }
 
+    /// Sender for queueing work from other listener threads
+    fn job_sender(&self) -> mpsc::Sender<Message> {
+        self.sender.clone()
+    }
+
     /// Get thread pool statistics
     pub fn get_stats(&self) -> ThreadPoolStats {
         ThreadPoolStats {
@@ -92,6 +164,7 @@ impl Worker {
                         eprintln!("Worker {id} connection error: {e}");
                     }
                 }
+                Ok(Message::Redirect(redirect_job)) => QMSWebServer::handle_redirect_connection(redirect_job),
                 Ok(Message::Terminate) => {
                     // Terminate signal received
                     break;
@@ -238,9 +311,15 @@ impl QMSWebServer {
         }
     }
 
-    /// Enable HTTPS enforcement
+    /// Enable HTTPS enforcement. Requires a TLS certificate to be configured first;
+    /// plain HTTP requests are then redirected to the HTTPS port.
     pub fn enable_https_enforcement(&mut self) -> QmsResult<()> {
         if let Ok(mut security_manager) = self.security_manager.lock() {
+            if !security_manager.is_https_configured() {
+                return Err(QmsError::validation_error(
+                    "HTTPS enforcement requires a TLS certificate (--tls-cert and --tls-key)",
+                ));
+            }
             let mut config = security_manager.get_config().clone();
             config.enforce_https = true;
             security_manager.update_config(config);
@@ -251,26 +330,55 @@ impl QMSWebServer {
         }
     }
 
+    /// Set the plain HTTP port that redirects to HTTPS when enforcement is enabled
+    pub fn set_redirect_port(&mut self, port: u16) -> QmsResult<()> {
+        if let Ok(mut security_manager) = self.security_manager.lock() {
+            let mut config = security_manager.get_config().clone();
+            config.http_port = port;
+            security_manager.update_config(config);
+            Ok(())
+        } else {
+            Err(QmsError::io_error("Failed to lock security manager"))
+        }
+    }
+
     /// Start the web server and begin handling requests
     pub fn start(&mut self) -> QmsResult<()> {
         let listener = TcpListener::bind(format!("{}:{}", self.bind_address, self.port))?;
         self.running.store(true, Ordering::SeqCst);
 
+        // TLS is served on the main port; the redirect listener must point clients there
+        let (tls_config, redirect_port) = match self.security_manager.lock() {
+            Ok(mut security_manager) => {
+                let mut config = security_manager.get_config().clone();
+                config.https_port = self.port;
+                let redirect_port = (config.enforce_https && config.http_port != self.port).then_some(config.http_port);
+                security_manager.update_config(config);
+                (security_manager.tls_server_config(), redirect_port)
+            }
+            Err(_) => return Err(QmsError::io_error("Failed to lock security manager")),
+        };
+        let scheme = if tls_config.is_some() { "https" } else { "http" };
+
         // Initialize thread pool
         self.thread_pool = Some(ThreadPool::new(self.pool_size));
 
-        println!("🚀 QMS Web Server started on http://{}:{}", self.bind_address, self.port);
+        println!("🚀 QMS Web Server started on {scheme}://{}:{}", self.bind_address, self.port);
         println!("📋 Medical Device Quality Management System");
         println!("🔒 FDA 21 CFR Part 820, ISO 13485, ISO 14971 Compliant");
-        println!("📊 Dashboard: http://{}:{}/", self.bind_address, self.port);
+        println!("📊 Dashboard: {scheme}://{}:{}/", self.bind_address, self.port);
         println!("🧵 Thread Pool: {} worker threads", self.pool_size);
         println!("🔗 Max Connections: {}", self.max_connections);
 
+        if let (Some(_), Some(http_port)) = (&tls_config, redirect_port) {
+            self.start_https_redirect_listener(http_port);
+        }
+
         // Audit log server start
         if let Err(e) = crate::modules::audit_logger::audit_log_action(
             "WEB_SERVER_START",
             "WebServer",
-            &format!("{}:{} pool_size:{}", self.bind_address, self.port, self.pool_size)
+            &format!("{}:{} {} pool_size:{}", self.bind_address, self.port, scheme, self.pool_size)
         ) {
             eprintln!("⚠️  Warning: Failed to log server start: {e}");
         }
@@ -293,6 +401,14 @@ impl QMSWebServer {
                         continue;
                     }
 
+                    let stream = match ConnectionStream::accept(stream, tls_config.clone()) {
+                        Ok(stream) => stream,
+                        Err(e) => {
+                            eprintln!("❌ {e}");
+                            continue;
+                        }
+                    };
+
                     let job = ConnectionJob {
                         stream,
                         security_manager: Arc::clone(&security_manager),
@@ -323,29 +439,129 @@ impl QMSWebServer {
         Ok(())
     }
 
-    /// Handle individual HTTP connection
-    pub fn handle_connection(
-        mut stream: TcpStream,
+    /// Answer plain HTTP on `http_port` with permanent redirects to the HTTPS listener
+    fn start_https_redirect_listener(&self, http_port: u16) {
+        let listener = match TcpListener::bind(format!("{}:{}", self.bind_address, http_port))
+            .and_then(|listener| listener.set_nonblocking(true).map(|()| listener))
+        {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("⚠️  Warning: HTTP redirect listener could not bind port {http_port}: {e}");
+                return;
+            }
+        };
+        let Some(ref pool) = self.thread_pool else {
+            return;
+        };
+
+        println!("↪️  Redirecting http://{}:{http_port} to HTTPS", self.bind_address);
+
+        Self::spawn_redirect_listener(
+            listener,
+            pool.job_sender(),
+            Arc::clone(&self.security_manager),
+            Arc::clone(&self.running),
+            self.bind_address.clone(),
+        );
+    }
+
+    /// Accept redirect connections until `running` is cleared, handing each to the worker pool
+    ///
+    /// The listener must be non-blocking so the loop notices a stop without
+    /// waiting for another connection.
+    fn spawn_redirect_listener(
+        listener: TcpListener,
+        pool: mpsc::Sender<Message>,
         security_manager: Arc<Mutex<SecurityManager>>,
-        asset_manager: AssetManager,
-    ) -> QmsResult<()> {
-        // Set connection timeouts for better resource management
-        stream.set_read_timeout(Some(Duration::from_secs(30)))?;
-        stream.set_write_timeout(Some(Duration::from_secs(30)))?;
+        running: Arc<AtomicBool>,
+        default_host: String,
+    ) -> thread::JoinHandle<()> {
+        thread::spawn(move || {
+            while running.load(Ordering::SeqCst) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        if stream.set_nonblocking(false).is_err() {
+                            continue;
+                        }
+                        let job = RedirectJob {
+                            stream,
+                            security_manager: Arc::clone(&security_manager),
+                            default_host: default_host.clone(),
+                        };
+                        if pool.send(Message::Redirect(job)).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                        thread::sleep(Duration::from_millis(50));
+                    }
+                    Err(e) => {
+                        eprintln!("❌ Redirect listener accept error: {e}");
+                        thread::sleep(Duration::from_millis(100));
+                    }
+                }
+            }
+        })
+    }
 
+    /// Answer one plain HTTP request with a permanent redirect to HTTPS
+    fn handle_redirect_connection(job: RedirectJob) {
+        let RedirectJob { mut stream, security_manager, default_host } = job;
+        let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+        let _ = stream.set_write_timeout(Some(Duration::from_secs(5)));
         let mut buffer = [0; 8192];
-        let bytes_read = stream.read(&mut buffer)?;
-
+        let bytes_read = stream.read(&mut buffer).unwrap_or(0);
         if bytes_read == 0 {
-            return Ok(());
+            return;
         }
 
         let request_data = String::from_utf8_lossy(&buffer[..bytes_read]);
-        let request = HttpRequest::parse(&request_data)?;
+        let (host, path) = match HttpRequest::parse(&request_data) {
+            Ok(request) => (
+                request.headers.get("host").map_or(default_host.as_str(), |h| redirect_host(h)).to_string(),
+                request.uri.clone(),
+            ),
+            Err(_) => (default_host.clone(), "/".to_string()),
+        };
+
+        let response = match security_manager.lock() {
+            Ok(manager) => manager.create_https_redirect_response(&host, &path),
+            Err(_) => return,
+        };
+        let _ = stream.write_all(response.as_bytes());
+        let _ = stream.flush();
+    }
+
+    /// Handle individual HTTP connection
+    pub fn handle_connection(
+        mut stream: ConnectionStream,
+        security_manager: Arc<Mutex<SecurityManager>>,
+        asset_manager: AssetManager,
+    ) -> QmsResult<()> {
+        // Set connection timeouts for better resource management
+        stream.tcp().set_read_timeout(Some(Duration::from_secs(30)))?;
+        stream.tcp().set_write_timeout(Some(Duration::from_secs(30)))?;
+
+        let max_request_size = match security_manager.lock() {
+            Ok(manager) => manager.get_config().max_request_size,
+            Err(_) => SecurityConfig::default().max_request_size,
+        };
+        let (request, checked_size) = match Self::read_request(&mut stream, max_request_size) {
+            Ok(Some(read)) => read,
+            Ok(None) => return Ok(()),
+            Err(QmsError::Validation(message)) => {
+                eprintln!("🚨 Request rejected: {message}");
+                let response_data = HttpResponse::new(HttpStatus::PayloadTooLarge).to_string();
+                stream.write_all(response_data.as_bytes())?;
+                stream.flush()?;
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
 
         // Security validation
         if let Ok(security_manager) = security_manager.lock() {
-            if let Err(e) = security_manager.validate_request_security(&request.headers, bytes_read) {
+            if let Err(e) = security_manager.validate_request_security(&request.headers, checked_size) {
                 eprintln!("🚨 Security validation failed: {e}");
                 let error_response = HttpResponse::new(crate::web::response::HttpStatus::BadRequest);
                 let response_data = error_response.to_string();
@@ -391,6 +607,69 @@ impl QMSWebServer {
         Ok(())
     }
 
+    /// Read a whole request: the head, then the body announced by Content-Length
+    ///
+    /// Returns the request and the size to check against the server's request
+    /// limit. Attachment uploads may be as large as the project's attachment
+    /// limit allows, so their body is bounded here and only the head is counted.
+    fn read_request(stream: &mut ConnectionStream, max_request_size: usize) -> QmsResult<Option<(HttpRequest, usize)>> {
+        let mut data = Vec::new();
+        let mut buffer = [0; 8192];
+        let body_start = loop {
+            let bytes_read = stream.read(&mut buffer)?;
+            if bytes_read == 0 {
+                if data.is_empty() {
+                    return Ok(None);
+                }
+                break HttpRequest::body_offset(&data).unwrap_or(data.len());
+            }
+            data.extend_from_slice(&buffer[..bytes_read]);
+            if let Some(offset) = HttpRequest::body_offset(&data) {
+                break offset;
+            }
+            if data.len() > MAX_HEAD_SIZE {
+                return Err(QmsError::validation_error("Request headers are too large"));
+            }
+        };
+
+        let head = HttpRequest::parse_bytes(&data[..body_start])?;
+        let limit = Self::body_limit(&head, max_request_size);
+        if head.get_header("content-length").is_some() {
+            let content_length = head.get_content_length();
+            if content_length > limit {
+                return Err(QmsError::validation_error(&format!(
+                    "Request body of {content_length} bytes exceeds the limit of {limit} bytes"
+                )));
+            }
+            while data.len() < body_start + content_length {
+                let bytes_read = stream.read(&mut buffer)?;
+                if bytes_read == 0 {
+                    break;
+                }
+                data.extend_from_slice(&buffer[..bytes_read]);
+            }
+            data.truncate(body_start + content_length);
+        }
+
+        let checked_size = if limit > max_request_size { body_start } else { data.len() };
+        Ok(Some((HttpRequest::parse_bytes(&data)?, checked_size)))
+    }
+
+    /// Largest body accepted for a request, judged from its head
+    fn body_limit(head: &HttpRequest, max_request_size: usize) -> usize {
+        use crate::modules::attachments::AttachmentManager;
+        use crate::web::unified_auth_context::UnifiedAuthContext;
+
+        if head.get_method() == Some(crate::web::request::HttpMethod::POST) && head.path() == "/api/attachments" {
+            let attachment_limit = UnifiedAuthContext::from_web_request(head)
+                .and_then(|auth| AttachmentManager::new(&auth.project_path)?.max_size());
+            if let Ok(attachment_limit) = attachment_limit {
+                return max_request_size.max(attachment_limit as usize + UPLOAD_OVERHEAD);
+            }
+        }
+        max_request_size
+    }
+
     /// Route HTTP requests to appropriate handlers
     fn route_request(
         request: &HttpRequest,
@@ -1295,6 +1574,36 @@ impl QMSWebServer {
                 crate::web::UnifiedRequirementsApiHandler::static_handle_delete_requirement(request)
             }
 
+            // Full-text search API
+            (Some(crate::web::request::HttpMethod::GET), "/api/search") => {
+                crate::web::SearchApiHandler::handle_search(request)
+            }
+
+            // CAPA APIs (FDA 21 CFR 820.100)
+            (Some(crate::web::request::HttpMethod::GET), "/api/capa") => {
+                crate::web::CapaApiHandler::handle_list_capas(request)
+            }
+            (Some(crate::web::request::HttpMethod::POST), "/api/capa") => {
+                crate::web::CapaApiHandler::handle_create_capa(request)
+            }
+            (Some(crate::web::request::HttpMethod::GET), path) if path.starts_with("/api/capa/") => {
+                crate::web::CapaApiHandler::handle_get_capa(request)
+            }
+            (Some(crate::web::request::HttpMethod::POST), path) if path.starts_with("/api/capa/") => {
+                crate::web::CapaApiHandler::handle_capa_action(request)
+            }
+
+            // Attachment APIs - evidence files on QMS records
+            (Some(crate::web::request::HttpMethod::GET), "/api/attachments") => {
+                crate::web::AttachmentsApiHandler::handle_list_attachments(request)
+            }
+            (Some(crate::web::request::HttpMethod::POST), "/api/attachments") => {
+                crate::web::AttachmentsApiHandler::handle_upload_attachment(request)
+            }
+            (Some(crate::web::request::HttpMethod::GET), path) if path.starts_with("/api/attachments/") => {
+                crate::web::AttachmentsApiHandler::handle_get_attachment(request)
+            }
+
             // Audit Trail APIs - Unified CLI Bridge
             (Some(crate::web::request::HttpMethod::GET), "/api/audit") => {
                 crate::web::UnifiedAuditApiHandler::static_handle_list_audit_logs(request)
@@ -1652,7 +1961,7 @@ impl QMSWebServer {
 
         if let Ok(project_path) = get_current_project_path() {
             let audit_file = project_path.join("audit").join("audit.log");
-            if let Ok(content) = std::fs::read_to_string(&audit_file) {
+            if let Ok(content) = crate::modules::storage::encryption::read_to_string(&audit_file) {
                 let lines: Vec<&str> = content.lines().take(50).collect(); // Get recent entries
                 let search_data = format!(r#"{{"message": "Found {} audit entries", "results": {}}}"#,
                     lines.len(), lines.len());
@@ -1791,6 +2100,16 @@ impl QMSWebServer {
     }
 }
 
+/// Host name from a `Host` header without its port, for building HTTPS redirects
+fn redirect_host(host_header: &str) -> &str {
+    let host = host_header.trim();
+    if host.starts_with('[') {
+        // Bracketed IPv6 literal, e.g. [::1]:8080
+        return host.find(']').map_or(host, |end| &host[..=end]);
+    }
+    host.split(':').next().unwrap_or(host)
+}
+
 impl Drop for QMSWebServer {
     fn drop(&mut self) {
         self.stop();
@@ -1805,6 +2124,40 @@ impl Drop for QMSWebServer {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_redirect_listener_uses_pool_and_stops() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let address = listener.local_addr().unwrap();
+        let pool = ThreadPool::new(2);
+        let running = Arc::new(AtomicBool::new(true));
+        let handle = QMSWebServer::spawn_redirect_listener(
+            listener,
+            pool.job_sender(),
+            Arc::new(Mutex::new(SecurityManager::new())),
+            Arc::clone(&running),
+            "127.0.0.1".to_string(),
+        );
+
+        // A client that never sends must not hold up the next one
+        let idle = TcpStream::connect(address).unwrap();
+        let mut client = TcpStream::connect(address).unwrap();
+        client.set_read_timeout(Some(Duration::from_secs(3))).unwrap();
+        client.write_all(b"GET /docs HTTP/1.1\r\nHost: example.com:8080\r\n\r\n").unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        assert!(response.contains("301 Moved Permanently"), "{response}");
+        assert!(response.contains("https://example.com"), "{response}");
+        drop(idle);
+
+        running.store(false, Ordering::SeqCst);
+        let started = SystemTime::now();
+        while !handle.is_finished() {
+            assert!(started.elapsed().unwrap() < Duration::from_secs(2), "redirect listener did not stop");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
     #[test]
     fn test_thread_pool_creation() {
         let pool = ThreadPool::new(4);
@@ -1903,6 +2256,67 @@ mod tests {
         assert!(info.contains("FDA 21 CFR Part 820"));
     }
 
+    #[test]
+    fn test_https_enforcement_requires_certificate() {
+        let mut server = QMSWebServer::new("127.0.0.1", 8080).unwrap();
+        assert!(server.enable_https_enforcement().is_err());
+        assert!(!server.get_security_status().https_enforced);
+    }
+
+    #[test]
+    fn test_tls_connection_stream_handshake() {
+        let certified = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cert_path = temp_dir.path().join("server.crt");
+        let key_path = temp_dir.path().join("server.key");
+        std::fs::write(&cert_path, certified.cert.pem()).unwrap();
+        std::fs::write(&key_path, certified.key_pair.serialize_pem()).unwrap();
+
+        let mut security_manager = SecurityManager::new();
+        security_manager
+            .load_certificate(&cert_path.to_string_lossy(), &key_path.to_string_lossy())
+            .unwrap();
+        let server_config = security_manager.tls_server_config().unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (tcp, _) = listener.accept().unwrap();
+            let mut stream = ConnectionStream::accept(tcp, Some(server_config)).unwrap();
+            let mut buffer = [0u8; 4];
+            stream.read_exact(&mut buffer).unwrap();
+            stream.write_all(&buffer).unwrap();
+            stream.flush().unwrap();
+        });
+
+        // Client pinned to TLS 1.2 to confirm the older supported version still negotiates
+        let mut roots = rustls::RootCertStore::empty();
+        roots.add(certified.cert.der().clone()).unwrap();
+        let client_config = rustls::ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_protocol_versions(&[&rustls::version::TLS12])
+            .unwrap()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        let server_name = rustls::pki_types::ServerName::try_from("localhost").unwrap();
+        let connection = rustls::ClientConnection::new(Arc::new(client_config), server_name).unwrap();
+        let mut client = rustls::StreamOwned::new(connection, TcpStream::connect(addr).unwrap());
+
+        client.write_all(b"ping").unwrap();
+        let mut reply = [0u8; 4];
+        client.read_exact(&mut reply).unwrap();
+        assert_eq!(&reply, b"ping");
+        assert_eq!(client.conn.protocol_version(), Some(rustls::ProtocolVersion::TLSv1_2));
+
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_redirect_host_strips_port() {
+        assert_eq!(redirect_host("qms.lab.local:8080"), "qms.lab.local");
+        assert_eq!(redirect_host("qms.lab.local"), "qms.lab.local");
+        assert_eq!(redirect_host("[::1]:8080"), "[::1]");
+    }
+
     #[test]
     fn test_asset_manager_integration() {
         let server = QMSWebServer::new("127.0.0.1", 8081).unwrap();
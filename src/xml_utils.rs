@@ -0,0 +1,460 @@
+//! XML utilities for importing and exporting exchange formats
+//! Minimal, dependency-free XML reader and writer used for JUnit results, ReqIF and similar interchange files
+
+use std::fmt::Write as _;
+
+/// Deepest element nesting the parser accepts, so hostile files cannot exhaust the stack
+const MAX_DEPTH: usize = 256;
+
+/// Error raised while reading XML
+#[derive(Debug)]
+pub enum XmlError {
+    InvalidFormat(String),
+    UnexpectedEnd,
+}
+
+impl std::fmt::Display for XmlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            XmlError::InvalidFormat(msg) => write!(f, "Invalid XML format: {msg}"),
+            XmlError::UnexpectedEnd => write!(f, "Invalid XML format: unexpected end of document"),
+        }
+    }
+}
+
+impl std::error::Error for XmlError {}
+
+/// Node inside an element
+#[derive(Debug, Clone, PartialEq)]
+pub enum XmlNode {
+    Element(XmlElement),
+    Text(String),
+}
+
+/// XML element with its attributes and children
+#[derive(Debug, Clone, PartialEq)]
+pub struct XmlElement {
+    pub name: String, // Qualified name as written, e.g. xhtml:div
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<XmlNode>,
+}
+
+impl XmlElement {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            attributes: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Parse a document and return its root element
+    pub fn parse(xml: &str) -> Result<Self, XmlError> {
+        let mut parser = Parser { src: xml, pos: 0, depth: 1 };
+        parser.skip_prolog()?;
+        let root = parser.parse_element()?;
+        parser.skip_misc()?;
+        if parser.pos < parser.src.len() {
+            return Err(XmlError::InvalidFormat("content after the root element".to_string()));
+        }
+        Ok(root)
+    }
+
+    /// Builder: add an attribute
+    pub fn with_attr(mut self, name: &str, value: &str) -> Self {
+        self.attributes.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Builder: add a child element
+    pub fn with_child(mut self, child: XmlElement) -> Self {
+        self.children.push(XmlNode::Element(child));
+        self
+    }
+
+    /// Builder: add a text node
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.children.push(XmlNode::Text(text.to_string()));
+        self
+    }
+
+    /// Serialize as a standalone UTF-8 document with an XML declaration
+    pub fn to_document(&self) -> String {
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        self.write(&mut out, 0);
+        out.push('\n');
+        out
+    }
+
+    /// Indent element-only content; write mixed content inline so text is preserved exactly
+    fn write(&self, out: &mut String, depth: usize) {
+        out.push('<');
+        out.push_str(&self.name);
+        for (key, value) in &self.attributes {
+            let _ = write!(out, " {key}=\"{}\"", escape_xml(value));
+        }
+        if self.children.is_empty() {
+            out.push_str("/>");
+            return;
+        }
+        out.push('>');
+
+        let mixed = self.children.iter().any(|node| matches!(node, XmlNode::Text(_)));
+        for node in &self.children {
+            if !mixed {
+                out.push('\n');
+                out.push_str(&"  ".repeat(depth + 1));
+            }
+            match node {
+                XmlNode::Text(text) => out.push_str(&escape_xml(text)),
+                XmlNode::Element(element) if mixed => element.write(out, 0),
+                XmlNode::Element(element) => element.write(out, depth + 1),
+            }
+        }
+        if !mixed {
+            out.push('\n');
+            out.push_str(&"  ".repeat(depth));
+        }
+        let _ = write!(out, "</{}>", self.name);
+    }
+
+    /// Name without its namespace prefix
+    pub fn local_name(&self) -> &str {
+        self.name.rsplit(':').next().unwrap_or(&self.name)
+    }
+
+    /// Attribute value by name (qualified or local)
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name || key.rsplit(':').next() == Some(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Child elements
+    pub fn elements(&self) -> impl Iterator<Item = &XmlElement> {
+        self.children.iter().filter_map(|node| match node {
+            XmlNode::Element(element) => Some(element),
+            XmlNode::Text(_) => None,
+        })
+    }
+
+    /// Child elements with the given local name
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.elements().filter(move |element| element.local_name() == name)
+    }
+
+    /// First child element with the given local name
+    pub fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.elements().find(|element| element.local_name() == name)
+    }
+
+    /// Every element below this one with the given local name, in document order
+    pub fn descendants(&self, name: &str) -> Vec<&XmlElement> {
+        let mut found = Vec::new();
+        for element in self.elements() {
+            if element.local_name() == name {
+                found.push(element);
+            }
+            found.extend(element.descendants(name));
+        }
+        found
+    }
+
+    /// Concatenated text content of this element and its descendants
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for node in &self.children {
+            match node {
+                XmlNode::Text(t) => text.push_str(t),
+                XmlNode::Element(element) => text.push_str(&element.text()),
+            }
+        }
+        text
+    }
+}
+
+/// Escape text for use in element content or a double-quoted attribute
+pub fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            '\n' | '\r' | '\t' => {
+                let _ = write!(out, "&#{};", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Resolve the predefined and numeric character references in `text`
+fn unescape_xml(text: &str) -> Result<String, XmlError> {
+    if !text.contains('&') {
+        return Ok(text.to_string());
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find(';')
+            .ok_or_else(|| XmlError::InvalidFormat("unterminated character reference".to_string()))?;
+        let entity = &rest[start + 1..start + end];
+        let decoded = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if let Some(hex) = entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                    u32::from_str_radix(hex, 16).ok()
+                } else if let Some(dec) = entity.strip_prefix('#') {
+                    dec.parse().ok()
+                } else {
+                    None
+                };
+                code.and_then(char::from_u32)
+                    .ok_or_else(|| XmlError::InvalidFormat(format!("unknown entity &{entity};")))?
+            }
+        };
+        out.push(decoded);
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+    depth: usize, // Nesting level of the element being parsed, root is 1
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let trimmed = self.rest().trim_start();
+        self.pos = self.src.len() - trimmed.len();
+    }
+
+    /// Advance past the next occurrence of `marker`
+    fn skip_past(&mut self, marker: &str) -> Result<&'a str, XmlError> {
+        let rest = self.rest();
+        let end = rest.find(marker).ok_or(XmlError::UnexpectedEnd)?;
+        self.pos += end + marker.len();
+        Ok(&rest[..end])
+    }
+
+    /// Skip comments, processing instructions and whitespace
+    fn skip_misc(&mut self) -> Result<(), XmlError> {
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Skip the byte order mark, XML declaration, comments and doctype
+    fn skip_prolog(&mut self) -> Result<(), XmlError> {
+        if self.rest().starts_with('\u{feff}') {
+            self.pos += '\u{feff}'.len_utf8();
+        }
+        loop {
+            self.skip_misc()?;
+            if !self.rest().starts_with("<!DOCTYPE") {
+                return Ok(());
+            }
+            // The internal subset may itself contain '>' characters
+            let rest = self.rest();
+            let end = match (rest.find('['), rest.find('>')) {
+                (Some(open), Some(close)) if open < close => rest.find("]>").map(|i| i + 2),
+                (_, Some(close)) => Some(close + 1),
+                _ => None,
+            };
+            self.pos += end.ok_or(XmlError::UnexpectedEnd)?;
+        }
+    }
+
+    fn parse_name(&mut self) -> Result<&'a str, XmlError> {
+        let rest = self.rest();
+        let end = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '='))
+            .unwrap_or(rest.len());
+        if end == 0 {
+            return Err(XmlError::InvalidFormat(format!("expected a name at offset {}", self.pos)));
+        }
+        self.pos += end;
+        Ok(&rest[..end])
+    }
+
+    fn parse_element(&mut self) -> Result<XmlElement, XmlError> {
+        if !self.rest().starts_with('<') {
+            return Err(XmlError::InvalidFormat(format!("expected an element at offset {}", self.pos)));
+        }
+        self.pos += 1;
+        let mut element = XmlElement::new(self.parse_name()?);
+
+        // Attributes
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                return Ok(element);
+            }
+            if rest.starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            if rest.is_empty() {
+                return Err(XmlError::UnexpectedEnd);
+            }
+            let key = self.parse_name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return Err(XmlError::InvalidFormat(format!("attribute {key} has no value")));
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let quote = self.rest().chars().next().ok_or(XmlError::UnexpectedEnd)?;
+            if quote != '"' && quote != '\'' {
+                return Err(XmlError::InvalidFormat(format!("attribute {key} is not quoted")));
+            }
+            self.pos += 1;
+            let value = self.skip_past(if quote == '"' { "\"" } else { "'" })?;
+            element.attributes.push((key.to_string(), unescape_xml(value)?));
+        }
+
+        // Content
+        loop {
+            let rest = self.rest();
+            if rest.is_empty() {
+                return Err(XmlError::UnexpectedEnd);
+            }
+            if rest.starts_with("</") {
+                self.pos += 2;
+                let name = self.parse_name()?;
+                if name != element.name {
+                    return Err(XmlError::InvalidFormat(format!(
+                        "closing tag </{name}> does not match <{}>", element.name
+                    )));
+                }
+                self.skip_whitespace();
+                if !self.rest().starts_with('>') {
+                    return Err(XmlError::InvalidFormat(format!("malformed closing tag </{name}>")));
+                }
+                self.pos += 1;
+                return Ok(element);
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with("<![CDATA[") {
+                self.pos += "<![CDATA[".len();
+                let text = self.skip_past("]]>")?;
+                element.children.push(XmlNode::Text(text.to_string()));
+            } else if rest.starts_with('<') {
+                if self.depth >= MAX_DEPTH {
+                    return Err(XmlError::InvalidFormat(format!(
+                        "elements nested deeper than {MAX_DEPTH} levels at offset {}", self.pos
+                    )));
+                }
+                self.depth += 1;
+                let child = self.parse_element()?;
+                self.depth -= 1;
+                element.children.push(XmlNode::Element(child));
+            } else {
+                let end = rest.find('<').unwrap_or(rest.len());
+                self.pos += end;
+                let raw = &rest[..end];
+                if !raw.trim().is_empty() {
+                    element.children.push(XmlNode::Text(unescape_xml(raw)?));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_elements_attributes_and_text() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- results -->
+<testsuites name="all">
+  <testsuite name="pump" tests="2">
+    <testcase name="tc_001 &amp; flow" classname='pump::flow' time="0.25"/>
+    <testcase name="tc_002">
+      <failure message="expected 5%"><![CDATA[left < right]]></failure>
+    </testcase>
+  </testsuite>
+</testsuites>"#;
+        let root = XmlElement::parse(xml).unwrap();
+        assert_eq!(root.name, "testsuites");
+        let cases = root.descendants("testcase");
+        assert_eq!(cases.len(), 2);
+        assert_eq!(cases[0].attr("name"), Some("tc_001 & flow"));
+        assert_eq!(cases[0].attr("classname"), Some("pump::flow"));
+        let failure = cases[1].child("failure").unwrap();
+        assert_eq!(failure.attr("message"), Some("expected 5%"));
+        assert_eq!(failure.text(), "left < right");
+    }
+
+    #[test]
+    fn test_namespaced_names_and_character_references() {
+        let xml = "\u{feff}<REQ-IF xmlns:xhtml=\"http://www.w3.org/1999/xhtml\"><xhtml:p>Flow &lt; 5% &#x26; &#34;stable&#34;</xhtml:p></REQ-IF>";
+        let root = XmlElement::parse(xml).unwrap();
+        let paragraph = root.child("p").unwrap();
+        assert_eq!(paragraph.name, "xhtml:p");
+        assert_eq!(paragraph.local_name(), "p");
+        assert_eq!(paragraph.text(), "Flow < 5% & \"stable\"");
+        assert_eq!(root.attr("xhtml"), Some("http://www.w3.org/1999/xhtml"));
+    }
+
+    #[test]
+    fn test_written_documents_parse_back() {
+        let root = XmlElement::new("REQ-IF")
+            .with_attr("xmlns:xhtml", "http://www.w3.org/1999/xhtml")
+            .with_child(XmlElement::new("SPEC-OBJECT").with_attr("LONG-NAME", "Flow \"max\" < 5 & stable\nline 2"))
+            .with_child(XmlElement::new("xhtml:p").with_text("Alarm ").with_child(XmlElement::new("xhtml:b").with_text("within")).with_text(" 2 s"));
+        let xml = root.to_document();
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<REQ-IF"));
+        assert!(xml.contains("<xhtml:p>Alarm <xhtml:b>within</xhtml:b> 2 s</xhtml:p>"));
+
+        let parsed = XmlElement::parse(&xml).unwrap();
+        assert_eq!(parsed, root);
+    }
+
+    #[test]
+    fn test_malformed_documents_are_rejected() {
+        assert!(XmlElement::parse("<a><b></a>").is_err());
+        assert!(XmlElement::parse("<a attr=unquoted/>").is_err());
+        assert!(XmlElement::parse("<a>").is_err());
+        assert!(XmlElement::parse("<a/><b/>").is_err());
+        assert!(XmlElement::parse("<a>&bogus;</a>").is_err());
+    }
+
+    #[test]
+    fn test_nesting_depth_is_limited() {
+        let nested = |depth: usize| format!("{}{}", "<a>".repeat(depth), "</a>".repeat(depth));
+        assert!(XmlElement::parse(&nested(MAX_DEPTH)).is_ok());
+        assert!(matches!(XmlElement::parse(&nested(MAX_DEPTH + 1)), Err(XmlError::InvalidFormat(_))));
+        assert!(XmlElement::parse(&nested(100_000)).is_err());
+    }
+}